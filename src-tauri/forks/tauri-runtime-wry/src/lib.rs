@@ -4670,6 +4670,7 @@ You may have it installed on another user account, but it is not available for t
   if webview_attributes.drag_drop_handler_enabled {
     let proxy = context.proxy.clone();
     let window_id_ = window_id.clone();
+    let block_default = webview_attributes.drag_drop_block_default.clone();
     webview_builder = webview_builder.with_drag_drop_handler(move |event| {
       let event = match event {
         WryDragDropEvent::Enter {
@@ -4700,7 +4701,9 @@ You may have it installed on another user account, but it is not available for t
       };
 
       let _ = proxy.send_event(Message::Webview(*window_id_.lock().unwrap(), id, message));
-      true
+      block_default
+        .as_ref()
+        .map_or(true, |b| b.load(std::sync::atomic::Ordering::Relaxed))
     });
   }
 
@@ -339,6 +339,9 @@ pub struct WebviewAttributes {
   pub initialization_scripts: Vec<InitializationScript>,
   pub data_directory: Option<PathBuf>,
   pub drag_drop_handler_enabled: bool,
+  /// Read on every drop: while set (or when None) the OS default is blocked and the
+  /// page never sees the drop, otherwise it gets it as well as the drag-drop events.
+  pub drag_drop_block_default: Option<Arc<std::sync::atomic::AtomicBool>>,
   pub clipboard: bool,
   pub accept_first_mouse: bool,
   pub additional_browser_args: Option<String>,
@@ -487,6 +490,7 @@ impl WebviewAttributes {
       initialization_scripts: Vec::new(),
       data_directory: None,
       drag_drop_handler_enabled: true,
+      drag_drop_block_default: None,
       clipboard: false,
       accept_first_mouse: false,
       additional_browser_args: None,
@@ -980,6 +980,15 @@ fn main() {
     self
   }
 
+  /// Decide per drop whether the OS default is blocked: while `block` is set the page
+  /// never receives dropped files, otherwise it gets them as well as the drag-drop
+  /// events. Without this every drop is blocked.
+  #[must_use]
+  pub fn drag_drop_block_default(mut self, block: std::sync::Arc<std::sync::atomic::AtomicBool>) -> Self {
+    self.webview_attributes.drag_drop_block_default = Some(block);
+    self
+  }
+
   /// Enables clipboard access for the page rendered on **Linux** and **Windows**.
   ///
   /// **macOS** doesn't provide such method and is always enabled by default,
@@ -25,6 +25,14 @@ const RESOURCES_JSON: &str = include_str!("../adblock/scriptlet-resources.json")
 const ENGINE_FILE: &str = "adblock/engine.dat";
 const LISTS_DIR: &str = "adblock/lists";
 const METADATA_FILE: &str = "adblock/metadata.json";
+// user rules, one ABP-syntax filter per line. Outside adblock/ so reset_to_bundled keeps them.
+const CUSTOM_FILTERS_FILE: &str = "custom_filters.txt";
+const MAX_CUSTOM_FILTERS: usize = 1000;
+const MAX_CUSTOM_FILTER_LEN: usize = 2048;
+
+// serializes custom-filter edits with their rebuild, so a slow compile can't swap in an
+// engine built from an older file after a newer one
+static CUSTOM_FILTERS_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
 
 // ── filter list definitions ─────────────────────────────────────────────────
 struct FilterListDef {
@@ -159,6 +167,13 @@ fn compile_engine(data_dir: &PathBuf) -> Engine {
         }
     }
 
+    // user rules last, with no permissions: ordinary `##+js(...)` scriptlets still run,
+    // only the permission-gated trusted-* ones are kept to the trusted lists
+    let custom = read_custom_filters(data_dir);
+    if !custom.is_empty() {
+        filter_set.add_filters(&custom, ParseOptions::default());
+    }
+
     let mut engine = Engine::from_filter_set(filter_set, true);
 
     // tune regex memory: discard unused regexes after 5 min, check every 2 min
@@ -275,6 +290,112 @@ pub async fn update_filter_lists(
     Ok(())
 }
 
+// ── drop downloaded lists and go back to the bundled ones ───────────────────
+/// Moves downloaded lists, resources and metadata aside as `.bak`, then recompiles
+/// from the bundled lists and swaps the engine. Returns the backup paths.
+pub async fn reset_to_bundled(
+    data_dir: PathBuf,
+    engine: Arc<RwLock<Engine>>,
+) -> Result<Vec<PathBuf>, String> {
+    let mut backups = Vec::new();
+    for rel in [LISTS_DIR, "adblock/resources.json", METADATA_FILE] {
+        let path = data_dir.join(rel);
+        if !path.exists() { continue; }
+        let bak = PathBuf::from(format!("{}.bak", path.display()));
+        if bak.is_dir() {
+            let _ = std::fs::remove_dir_all(&bak);
+        }
+        std::fs::rename(&path, &bak).map_err(|e| format!("backup {}: {}", rel, e))?;
+        backups.push(bak);
+    }
+    // the cached engine is derived data — no backup needed
+    let _ = std::fs::remove_file(data_dir.join(ENGINE_FILE));
+
+    let data_dir_clone = data_dir.clone();
+    let new_engine = tokio::task::spawn_blocking(move || compile_engine(&data_dir_clone))
+        .await
+        .map_err(|e| e.to_string())?;
+    cache_engine(&new_engine, &data_dir);
+    *engine.write() = new_engine;
+    Ok(backups)
+}
+
+// ── user custom filters ─────────────────────────────────────────────────────
+fn read_custom_filters(data_dir: &PathBuf) -> Vec<String> {
+    std::fs::read_to_string(data_dir.join(CUSTOM_FILTERS_FILE))
+        .map(|s| s.lines().map(str::trim).filter(|l| !l.is_empty()).map(String::from).collect())
+        .unwrap_or_default()
+}
+
+fn write_custom_filters(data_dir: &PathBuf, rules: &[String]) -> Result<(), String> {
+    let mut text = rules.join("\n");
+    text.push('\n');
+    std::fs::write(data_dir.join(CUSTOM_FILTERS_FILE), text).map_err(|e| e.to_string())
+}
+
+pub fn list_custom_filters(data_dir: &PathBuf) -> Vec<String> {
+    read_custom_filters(data_dir)
+}
+
+/// Recompile with the current custom filters and swap the engine in.
+///
+/// Swap strategy: every request handler holds a clone of the same `Arc<RwLock<Engine>>`,
+/// never the engine itself, so the new engine is moved *into* the shared lock rather than
+/// replacing the Arc. Compilation happens off-lock on a blocking thread; the write lock is
+/// only held for the move, so in-flight checks finish on the old engine and the next
+/// request sees the new one. Open tabs pick up the change without being recreated.
+async fn rebuild_with_custom(data_dir: PathBuf, engine: Arc<RwLock<Engine>>) -> Result<(), String> {
+    let data_dir_clone = data_dir.clone();
+    let new_engine = tokio::task::spawn_blocking(move || compile_engine(&data_dir_clone))
+        .await
+        .map_err(|e| e.to_string())?;
+    // the cache must include the custom rules, or the next startup would drop them
+    cache_engine(&new_engine, &data_dir);
+    *engine.write() = new_engine;
+    Ok(())
+}
+
+/// Validate and append a user filter, then rebuild. Returns the updated list.
+pub async fn add_custom_filter(data_dir: PathBuf, engine: Arc<RwLock<Engine>>, rule: &str) -> Result<Vec<String>, String> {
+    let rule = rule.trim();
+    if rule.is_empty() || rule.starts_with('!') {
+        return Err("empty filter".into());
+    }
+    if rule.len() > MAX_CUSTOM_FILTER_LEN || rule.contains(['\n', '\r']) {
+        return Err("filter must be a single line under 2048 characters".into());
+    }
+    adblock::lists::parse_filter(rule, false, ParseOptions::default())
+        .map_err(|e| format!("invalid filter: {:?}", e))?;
+
+    let _guard = CUSTOM_FILTERS_LOCK.lock().await;
+    let mut rules = read_custom_filters(&data_dir);
+    if rules.iter().any(|r| r == rule) {
+        return Ok(rules);
+    }
+    if rules.len() >= MAX_CUSTOM_FILTERS {
+        return Err(format!("at most {} custom filters", MAX_CUSTOM_FILTERS));
+    }
+    rules.push(rule.to_string());
+    write_custom_filters(&data_dir, &rules)?;
+    rebuild_with_custom(data_dir, engine).await?;
+    Ok(rules)
+}
+
+/// Remove a user filter (exact match) and rebuild. Returns the updated list.
+pub async fn remove_custom_filter(data_dir: PathBuf, engine: Arc<RwLock<Engine>>, rule: &str) -> Result<Vec<String>, String> {
+    let rule = rule.trim();
+    let _guard = CUSTOM_FILTERS_LOCK.lock().await;
+    let mut rules = read_custom_filters(&data_dir);
+    let before = rules.len();
+    rules.retain(|r| r != rule);
+    if rules.len() == before {
+        return Err("no such custom filter".into());
+    }
+    write_custom_filters(&data_dir, &rules)?;
+    rebuild_with_custom(data_dir, engine).await?;
+    Ok(rules)
+}
+
 // ── load metadata to check last update time ─────────────────────────────────
 pub fn load_metadata(data_dir: &PathBuf) -> FilterListMetadata {
     let meta_path = data_dir.join(METADATA_FILE);
@@ -333,18 +454,11 @@ pub fn resource_type_str(ctx: u32) -> &'static str {
 // ── tracking parameter stripping ────────────────────────────────────────────
 // FIX #4: Expanded tracking param stripping (research/18 B.2)
 // Brave strips 60+ params. Our old list had 31 — now 62.
-const TRACKING_PARAMS: &[&str] = &[
+// The one list for both sub-resource requests and redirect.rs's navigation rewrite.
+// Matched case-insensitively; a trailing `*` matches a prefix.
+pub const TRACKING_PARAMS: &[&str] = &[
     // Google Analytics / Ads
-    "utm_source",
-    "utm_medium",
-    "utm_campaign",
-    "utm_term",
-    "utm_content",
-    "utm_name",
-    "utm_cid",
-    "utm_reader",
-    "utm_viz_id",
-    "utm_pubreferrer",
+    "utm_*",
     "gclid",
     "gclsrc",
     "dclid",
@@ -379,6 +493,7 @@ const TRACKING_PARAMS: &[&str] = &[
     "__hsfp",
     "__hssc",
     "__hstc",
+    "_hsmktg",
     "hsa_cam",
     "hsa_grp",
     "hsa_mt",
@@ -423,40 +538,30 @@ const TRACKING_PARAMS: &[&str] = &[
     "piCId",
 ];
 
-/// Strip tracking parameters from a URL, return new URL if modified
-pub fn strip_tracking_params(url_str: &str) -> Option<String> {
-    let mut parsed = url::Url::parse(url_str).ok()?;
-    if parsed.query().is_none() {
-        return None;
-    }
+fn is_tracking_param(key: &str) -> bool {
+    TRACKING_PARAMS.iter().any(|p| match p.strip_suffix('*') {
+        Some(prefix) => key.len() >= prefix.len() && key[..prefix.len()].eq_ignore_ascii_case(prefix),
+        None => key.eq_ignore_ascii_case(p),
+    })
+}
 
-    let original_count = parsed.query_pairs().count();
-    let filtered: Vec<(String, String)> = parsed
-        .query_pairs()
-        .filter(|(k, _)| !TRACKING_PARAMS.contains(&k.as_ref()))
-        .map(|(k, v)| (k.to_string(), v.to_string()))
+/// Drop tracking params, leaving the rest of the query byte-for-byte as it was.
+/// Returns whether anything was removed.
+pub fn strip_tracking(url: &mut url::Url) -> bool {
+    let Some(query) = url.query() else { return false };
+    let kept: Vec<&str> = query.split('&')
+        .filter(|pair| !is_tracking_param(pair.split('=').next().unwrap_or("")))
         .collect();
-
-    if filtered.len() == original_count {
-        return None; // nothing changed
-    }
-
-    if filtered.is_empty() {
-        parsed.set_query(None);
-    } else {
-        let new_query: String = filtered
-            .iter()
-            .map(|(k, v)| {
-                if v.is_empty() {
-                    urlencoding::encode(k).to_string()
-                } else {
-                    format!("{}={}", urlencoding::encode(k), urlencoding::encode(v))
-                }
-            })
-            .collect::<Vec<_>>()
-            .join("&");
-        parsed.set_query(Some(&new_query));
+    if kept.len() == query.split('&').count() {
+        return false;
     }
+    let kept = kept.join("&");
+    url.set_query(if kept.is_empty() { None } else { Some(&kept) });
+    true
+}
 
-    Some(parsed.to_string())
+/// Strip tracking parameters from a URL, return new URL if modified
+pub fn strip_tracking_params(url_str: &str) -> Option<String> {
+    let mut parsed = url::Url::parse(url_str).ok()?;
+    strip_tracking(&mut parsed).then(|| parsed.to_string())
 }
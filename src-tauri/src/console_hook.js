@@ -0,0 +1,51 @@
+(function() {
+  if (window.__bushidoConsole) { window.__bushidoConsoleOff = false; return; }
+  Object.defineProperty(window, '__bushidoConsole', { value: true, writable: false, configurable: false });
+  window.__bushidoConsoleOff = false;
+
+  var MAX_LEN = 2000;
+  var PER_SECOND = 20;
+  var windowStart = 0, sent = 0, dropped = 0;
+
+  function str(v) {
+    if (v instanceof Error) return v.stack || (v.name + ': ' + v.message);
+    if (typeof v === 'string') return v;
+    try { return JSON.stringify(v); } catch (e) { return String(v); }
+  }
+
+  function post(level, message, source, line) {
+    if (window.__bushidoConsoleOff || !(window.chrome && window.chrome.webview)) return;
+    var now = Date.now();
+    if (now - windowStart >= 1000) {
+      if (dropped > 0) {
+        window.chrome.webview.postMessage(JSON.stringify({
+          __bushido: 'console', level: 'warn', message: '[bushido] ' + dropped + ' messages dropped (rate limit)'
+        }));
+      }
+      windowStart = now; sent = 0; dropped = 0;
+    }
+    if (++sent > PER_SECOND) { dropped++; return; }
+    window.chrome.webview.postMessage(JSON.stringify({
+      __bushido: 'console',
+      level: level,
+      message: String(message).slice(0, MAX_LEN),
+      source: source ? String(source).slice(0, 500) : '',
+      line: line || 0
+    }));
+  }
+
+  ['error', 'warn'].forEach(function(level) {
+    var orig = console[level];
+    console[level] = function() {
+      try { post(level, Array.prototype.map.call(arguments, str).join(' ')); } catch (e) {}
+      return orig.apply(this, arguments);
+    };
+  });
+
+  window.addEventListener('error', function(e) {
+    if (e.error || e.message) post('uncaught', e.error ? str(e.error) : e.message, e.filename, e.lineno);
+  });
+  window.addEventListener('unhandledrejection', function(e) {
+    post('rejection', 'Unhandled rejection: ' + str(e.reason));
+  });
+})();
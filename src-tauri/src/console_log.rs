@@ -0,0 +1,148 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use parking_lot::Mutex;
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+/// Hooks console.error/warn, uncaught errors and unhandled rejections and posts them
+/// as `{__bushido: "console"}` messages. Only injected into tabs that opted in.
+pub const CONSOLE_HOOK_SCRIPT: &str = include_str!("console_hook.js");
+
+const MAX_ENTRIES: usize = 500;
+const MAX_MESSAGE_LEN: usize = 2000;
+const MAX_SOURCE_LEN: usize = 500;
+// the page can post these messages itself, so the hook's own limit isn't trusted
+const MAX_PER_SECOND: u32 = 50;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsoleEntry {
+    pub level: String,
+    pub message: String,
+    pub source: String,
+    pub line: u32,
+    pub timestamp_ms: u64,
+}
+
+pub struct ConsoleLogState {
+    enabled: Mutex<HashSet<String>>,
+    entries: Mutex<HashMap<String, VecDeque<ConsoleEntry>>>,
+    // tab id → (window start, messages in window)
+    rate: Mutex<HashMap<String, (Instant, u32)>>,
+}
+
+impl ConsoleLogState {
+    pub fn new() -> Self {
+        Self {
+            enabled: Mutex::new(HashSet::new()),
+            entries: Mutex::new(HashMap::new()),
+            rate: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn is_enabled(&self, tab_id: &str) -> bool {
+        self.enabled.lock().contains(tab_id)
+    }
+
+    pub fn remove_tab(&self, tab_id: &str) {
+        self.enabled.lock().remove(tab_id);
+        self.entries.lock().remove(tab_id);
+        self.rate.lock().remove(tab_id);
+    }
+
+    fn allow(&self, tab_id: &str) -> bool {
+        let mut rate = self.rate.lock();
+        let slot = rate.entry(tab_id.to_string()).or_insert((Instant::now(), 0));
+        if slot.0.elapsed().as_secs() >= 1 {
+            *slot = (Instant::now(), 0);
+        }
+        slot.1 += 1;
+        slot.1 <= MAX_PER_SECOND
+    }
+}
+
+fn truncate(s: &str, max: usize) -> String {
+    if s.len() <= max { return s.to_string(); }
+    let mut end = max;
+    while !s.is_char_boundary(end) { end -= 1; }
+    format!("{}…", &s[..end])
+}
+
+fn push_capped(buf: &mut VecDeque<ConsoleEntry>, entry: ConsoleEntry) {
+    if buf.len() >= MAX_ENTRIES {
+        buf.pop_front();
+    }
+    buf.push_back(entry);
+}
+
+/// Build an entry from a `console` IPC message; None for unknown levels
+fn parse_entry(level: &str, message: &str, source: &str, line: u64) -> Option<ConsoleEntry> {
+    if !matches!(level, "error" | "warn" | "uncaught" | "rejection") { return None; }
+    Some(ConsoleEntry {
+        level: level.to_string(),
+        message: truncate(message, MAX_MESSAGE_LEN),
+        source: truncate(source, MAX_SOURCE_LEN),
+        line: line.min(u32::MAX as u64) as u32,
+        timestamp_ms: SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64,
+    })
+}
+
+/// Called from the WebMessageReceived handler. Dropped unless the tab opted in.
+pub fn record(app: &AppHandle, tab_id: &str, level: &str, message: &str, source: &str, line: u64) {
+    let cs = app.state::<ConsoleLogState>();
+    if !cs.is_enabled(tab_id) || !cs.allow(tab_id) { return; }
+    let Some(entry) = parse_entry(level, message, source, line) else { return };
+    push_capped(cs.entries.lock().entry(tab_id.to_string()).or_default(), entry);
+}
+
+/// Opt a tab in or out of console capture. Turning it off keeps what was already captured.
+#[tauri::command]
+pub async fn set_console_capture(app: AppHandle, id: String, enabled: bool) -> Result<(), String> {
+    let cs = app.state::<ConsoleLogState>();
+    if enabled {
+        cs.enabled.lock().insert(id.clone());
+    } else {
+        cs.enabled.lock().remove(&id);
+    }
+    if let Some(wv) = app.get_webview(&id) {
+        let js = if enabled { CONSOLE_HOOK_SCRIPT } else { "window.__bushidoConsoleOff=true;" };
+        wv.eval(js).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_console_log(app: AppHandle, id: String) -> Result<Vec<ConsoleEntry>, String> {
+    let cs = app.state::<ConsoleLogState>();
+    let entries = cs.entries.lock();
+    Ok(entries.get(&id).map(|d| d.iter().cloned().collect()).unwrap_or_default())
+}
+
+#[tauri::command]
+pub async fn clear_console_log(app: AppHandle, id: String) -> Result<(), String> {
+    app.state::<ConsoleLogState>().entries.lock().remove(&id);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncates_and_caps() {
+        let long = "é".repeat(MAX_MESSAGE_LEN);
+        let e = parse_entry("error", &long, "", 12).unwrap();
+        assert!(e.message.len() <= MAX_MESSAGE_LEN + '…'.len_utf8());
+        assert_eq!(e.line, 12);
+        assert!(parse_entry("log", "x", "", 0).is_none());
+
+        let mut buf = VecDeque::new();
+        for i in 0..MAX_ENTRIES + 10 {
+            let mut entry = e.clone();
+            entry.line = i as u32;
+            push_capped(&mut buf, entry);
+        }
+        assert_eq!(buf.len(), MAX_ENTRIES);
+        assert_eq!(buf.front().unwrap().line, 10);
+    }
+}
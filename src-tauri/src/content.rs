@@ -0,0 +1,518 @@
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::error::BushidoError;
+
+/// Canonical "find the main content" extractor. Defines `window.__bushidoExtract()`;
+/// reader mode (with its reading time and table of contents) and page_text all go
+/// through it. The reader's headings get the same `bushido-h-N` ids as `headings`,
+/// which is what the reader's table of contents links to.
+pub const EXTRACT_SCRIPT: &str = include_str!("content_extract.js");
+
+const EXTRACT_TIMEOUT_SECS: u64 = 5;
+// bytes of page_text kept for indexing
+const MAX_INDEX_TEXT: usize = 100_000;
+/// Shared by reading_minutes and the reader overlay's estimate
+pub const WORDS_PER_MINUTE: usize = 230;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContentLink {
+    pub href: String,
+    #[serde(default)]
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContentHeading {
+    pub level: u8,
+    pub text: String,
+    #[serde(default)]
+    pub id: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractedContent {
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub byline: Option<String>,
+    #[serde(default)]
+    pub html: String,
+    #[serde(default)]
+    pub text: String,
+    #[serde(default)]
+    pub links: Vec<ContentLink>,
+    #[serde(default)]
+    pub headings: Vec<ContentHeading>,
+}
+
+fn collapse_ws(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+impl ExtractedContent {
+    /// Parse the extractor's JSON and normalize it — the page controls this payload,
+    /// so whitespace is collapsed, unsafe links dropped and heading levels clamped.
+    pub fn parse(raw: &str) -> Result<Self, String> {
+        let mut c: ExtractedContent = serde_json::from_str(raw).map_err(|e| format!("parse extract: {}", e))?;
+
+        c.title = collapse_ws(&c.title);
+        c.byline = c.byline.map(|b| collapse_ws(&b)).filter(|b| !b.is_empty());
+        c.text = collapse_ws(&c.text);
+
+        let mut seen = std::collections::HashSet::new();
+        c.links.retain_mut(|l| {
+            l.text = collapse_ws(&l.text);
+            let safe = l.href.starts_with("https://") || l.href.starts_with("http://");
+            safe && !crate::is_blocked_scheme(&l.href) && seen.insert(l.href.clone())
+        });
+
+        c.headings.retain_mut(|h| {
+            h.text = collapse_ws(&h.text);
+            h.level = h.level.clamp(1, 6);
+            !h.text.is_empty()
+        });
+        Ok(c)
+    }
+
+    pub fn word_count(&self) -> usize {
+        self.text.split_whitespace().count()
+    }
+
+    pub fn reading_minutes(&self) -> u32 {
+        let words = self.word_count();
+        if words == 0 { return 0; }
+        words.div_ceil(WORDS_PER_MINUTE) as u32
+    }
+}
+
+/// Run the extractor in a tab and return the parsed result
+pub async fn extract(app: &AppHandle, id: &str) -> Result<ExtractedContent, BushidoError> {
+    let expr = format!("(function(){{{}\nreturn JSON.stringify(window.__bushidoExtract())}})()", EXTRACT_SCRIPT);
+    let raw = crate::eval_with_result(app, id, &expr, std::time::Duration::from_secs(EXTRACT_TIMEOUT_SECS))
+        .await
+        .map_err(BushidoError::WebView)?;
+    if raw.is_empty() {
+        return Err(BushidoError::WebView("extraction failed".into()));
+    }
+    Ok(ExtractedContent::parse(&raw)?)
+}
+
+#[tauri::command]
+pub async fn extract_content(app: AppHandle, id: String) -> Result<ExtractedContent, BushidoError> {
+    extract(&app, &id).await
+}
+
+fn index_text(text: &str) -> &str {
+    if text.len() <= MAX_INDEX_TEXT { return text; }
+    let mut end = MAX_INDEX_TEXT;
+    while !text.is_char_boundary(end) { end -= 1; }
+    &text[..end]
+}
+
+/// The tab's main text for indexing: the extractor's text with page chrome left out,
+/// whitespace collapsed, capped at MAX_INDEX_TEXT bytes
+#[tauri::command]
+pub async fn page_text(app: AppHandle, id: String) -> Result<String, BushidoError> {
+    let c = extract(&app, &id).await?;
+    Ok(index_text(&c.text).to_string())
+}
+
+const READER_TIMEOUT_SECS: u64 = 3;
+const MAX_FILE_STEM: usize = 100;
+
+// the reader overlay toggle_reader builds; empty string when it isn't showing
+const READER_GRAB_SCRIPT: &str = "(function(){var c=document.querySelector('#__bushido_reader .bushido-reader-content');if(!c)return '';return JSON.stringify({html:c.innerHTML,title:document.title,url:location.href})})()";
+
+#[derive(Deserialize)]
+struct ReaderSnapshot {
+    html: String,
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    url: String,
+}
+
+fn decode_entities(s: &str) -> String {
+    if !s.contains('&') {
+        return s.to_string();
+    }
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(i) = rest.find('&') {
+        out.push_str(&rest[..i]);
+        rest = &rest[i..];
+        let end = rest.find(';').filter(|&e| e <= 10);
+        let decoded = end.and_then(|e| {
+            let name = &rest[1..e];
+            let c = match name {
+                "amp" => '&', "lt" => '<', "gt" => '>', "quot" => '"', "apos" | "#39" => '\'',
+                "nbsp" => ' ', "mdash" => '—', "ndash" => '–', "hellip" => '…',
+                "lsquo" => '‘', "rsquo" => '’', "ldquo" => '“', "rdquo" => '”',
+                _ => {
+                    let n = name.strip_prefix("#x").or_else(|| name.strip_prefix("#X"))
+                        .and_then(|h| u32::from_str_radix(h, 16).ok())
+                        .or_else(|| name.strip_prefix('#').and_then(|d| d.parse().ok()))?;
+                    char::from_u32(n)?
+                }
+            };
+            Some((c, e))
+        });
+        match decoded {
+            Some((c, e)) => { out.push(c); rest = &rest[e + 1..]; }
+            None => { out.push('&'); rest = &rest[1..]; }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Value of `name` in a start tag's attribute text, entity-decoded.
+fn attr(attrs: &str, name: &str) -> Option<String> {
+    let lower = attrs.to_ascii_lowercase();
+    let mut from = 0;
+    while let Some(i) = lower[from..].find(name) {
+        let at = from + i;
+        from = at + name.len();
+        // whole attribute names only (not data-src for src)
+        if at > 0 && !lower.as_bytes()[at - 1].is_ascii_whitespace() { continue; }
+        let after = attrs[from..].trim_start();
+        let Some(v) = after.strip_prefix('=') else { continue };
+        let v = v.trim_start();
+        let value = match v.chars().next()? {
+            q @ ('"' | '\'') => v[1..].split(q).next().unwrap_or(""),
+            _ => v.split(|c: char| c.is_whitespace() || c == '>').next().unwrap_or(""),
+        };
+        return Some(decode_entities(value));
+    }
+    None
+}
+
+/// Absolute http(s) form of a link or image src, or None for anything else
+/// (javascript:, data:, fragments).
+fn resolve_url(base: Option<&url::Url>, href: &str) -> Option<String> {
+    let href = href.trim();
+    if href.is_empty() || href.starts_with('#') { return None; }
+    let u = match base {
+        Some(b) => b.join(href).ok()?,
+        None => url::Url::parse(href).ok()?,
+    };
+    matches!(u.scheme(), "http" | "https").then(|| u.to_string())
+}
+
+/// Backslash-escape the characters Markdown would read as formatting
+fn escape_md(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '*' | '_' | '[' | ']' | '#' | '`') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+// a `(` `)` or space would end or split the `(...)` part of a link
+fn link_target(url: &str) -> String {
+    url.replace(' ', "%20").replace('(', "%28").replace(')', "%29")
+}
+
+#[derive(Default)]
+struct MdWriter {
+    out: String,
+    quote_depth: usize,
+    // one entry per open ul/ol: None = bullets, Some(n) = next number
+    lists: Vec<Option<u32>>,
+    pre: bool,
+    // inside inline <code>, where markdown escapes would show up literally
+    code: bool,
+    // per open <a>: whether it became a markdown link, and its target
+    links: Vec<Option<String>>,
+}
+
+impl MdWriter {
+    fn prefix(&self) -> String {
+        "> ".repeat(self.quote_depth)
+    }
+
+    fn at_line_start(&self) -> bool {
+        self.out.is_empty() || self.out.ends_with('\n') || self.out.ends_with("> ")
+    }
+
+    fn newline(&mut self) {
+        let p = self.prefix();
+        self.out.push('\n');
+        self.out.push_str(&p);
+    }
+
+    /// End the current block with an empty line, unless one is already there.
+    fn block(&mut self) {
+        let p = self.prefix();
+        let trimmed = self.out.trim_end_matches([' ', '>']).len();
+        if trimmed == 0 {
+            self.out.clear();
+            self.out.push_str(&p);
+            return;
+        }
+        self.out.truncate(trimmed);
+        let nl = self.out.len() - self.out.trim_end_matches('\n').len();
+        for _ in nl..2 { self.out.push('\n'); }
+        self.out.push_str(&p);
+    }
+
+    fn text(&mut self, raw: &str) {
+        let t = decode_entities(raw);
+        if self.pre {
+            let p = self.prefix();
+            self.out.push_str(&t.replace('\n', &format!("\n{}", p)));
+            return;
+        }
+        let collapsed = collapse_ws(&t);
+        if collapsed.is_empty() {
+            if t.chars().any(char::is_whitespace) && !self.at_line_start() && !self.out.ends_with(' ') {
+                self.out.push(' ');
+            }
+            return;
+        }
+        if t.starts_with(char::is_whitespace) && !self.at_line_start() && !self.out.ends_with(' ') {
+            self.out.push(' ');
+        }
+        if self.code {
+            self.out.push_str(&collapsed);
+        } else {
+            self.out.push_str(&escape_md(&collapsed));
+        }
+        if t.ends_with(char::is_whitespace) {
+            self.out.push(' ');
+        }
+    }
+
+    fn open(&mut self, name: &str, attrs: &str, base: Option<&url::Url>) {
+        match name {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                self.block();
+                let level = name[1..].parse::<usize>().unwrap_or(1);
+                self.out.push_str(&"#".repeat(level));
+                self.out.push(' ');
+            }
+            "p" | "div" | "section" | "article" | "header" | "footer" | "figure" | "figcaption" | "table" | "dl" => self.block(),
+            "tr" | "dt" | "dd" => self.newline(),
+            "blockquote" => { self.block(); self.quote_depth += 1; self.block(); }
+            "ul" => { self.block(); self.lists.push(None); }
+            "ol" => { self.block(); self.lists.push(Some(1)); }
+            "li" => {
+                let depth = self.lists.len().max(1) - 1;
+                let marker = match self.lists.last_mut() {
+                    Some(Some(n)) => { *n += 1; format!("{}. ", *n - 1) }
+                    _ => "- ".to_string(),
+                };
+                if !self.at_line_start() { self.newline(); }
+                self.out.push_str(&"  ".repeat(depth));
+                self.out.push_str(&marker);
+            }
+            "pre" => { self.block(); self.out.push_str("```"); self.newline(); self.pre = true; }
+            "br" => self.newline(),
+            "hr" => { self.block(); self.out.push_str("---"); self.block(); }
+            "strong" | "b" => self.out.push_str("**"),
+            "em" | "i" => self.out.push('*'),
+            "code" if !self.pre => { self.out.push('`'); self.code = true; }
+            "a" => {
+                let href = attr(attrs, "href").and_then(|h| resolve_url(base, &h));
+                if href.is_some() { self.out.push('['); }
+                self.links.push(href);
+            }
+            "img" => {
+                if let Some(src) = attr(attrs, "src").and_then(|s| resolve_url(base, &s)) {
+                    let alt = collapse_ws(&attr(attrs, "alt").unwrap_or_default()).replace(['[', ']'], "");
+                    self.out.push_str(&format!("![{}]({})", alt, link_target(&src)));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn close(&mut self, name: &str) {
+        match name {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "p" | "div" | "section" | "article" | "header"
+            | "footer" | "figure" | "figcaption" | "table" | "dl" => self.block(),
+            "blockquote" => { self.quote_depth = self.quote_depth.saturating_sub(1); self.block(); }
+            "ul" | "ol" => { self.lists.pop(); self.block(); }
+            "pre" => {
+                self.pre = false;
+                if !self.at_line_start() { self.newline(); }
+                self.out.push_str("```");
+                self.block();
+            }
+            "strong" | "b" => self.out.push_str("**"),
+            "em" | "i" => self.out.push('*'),
+            "code" if !self.pre => { self.out.push('`'); self.code = false; }
+            "a" => {
+                if let Some(Some(href)) = self.links.pop() {
+                    self.out.push_str(&format!("]({})", link_target(&href)));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Small HTML → Markdown pass for reader output: headings, paragraphs, links,
+/// images (as URLs), lists, quotes, code and emphasis. Unknown tags keep their text;
+/// script/style contents are dropped. `base` resolves relative links.
+pub fn html_to_markdown(html: &str, base: Option<&str>) -> String {
+    let base = base.and_then(|b| url::Url::parse(b).ok());
+    let mut w = MdWriter::default();
+    let mut rest = html;
+    while !rest.is_empty() {
+        let Some(lt) = rest.find('<') else { w.text(rest); break };
+        if lt > 0 { w.text(&rest[..lt]); }
+        rest = &rest[lt..];
+        if let Some(after) = rest.strip_prefix("<!--") {
+            rest = after.find("-->").map_or("", |e| &after[e + 3..]);
+            continue;
+        }
+        let Some(gt) = rest.find('>') else { w.text(rest); break };
+        let tag = &rest[1..gt];
+        rest = &rest[gt + 1..];
+        let closing = tag.starts_with('/');
+        let tag = tag.trim_start_matches('/').trim_end_matches('/');
+        let (name, attrs) = tag.split_once(|c: char| c.is_whitespace()).unwrap_or((tag, ""));
+        let name = name.to_ascii_lowercase();
+        if closing {
+            w.close(&name);
+        } else if matches!(name.as_str(), "script" | "style" | "noscript" | "template" | "svg") {
+            let end = format!("</{}", name);
+            rest = rest.to_ascii_lowercase().find(&end)
+                .and_then(|i| rest[i..].find('>').map(|g| &rest[i + g + 1..]))
+                .unwrap_or("");
+        } else {
+            w.open(&name, attrs, base.as_ref());
+        }
+    }
+
+    let mut md = String::new();
+    let mut blank = 0;
+    for line in w.out.lines().map(str::trim_end) {
+        let empty = line.trim_start_matches(['>', ' ']).is_empty();
+        if empty { blank += 1; if blank > 1 { continue; } } else { blank = 0; }
+        md.push_str(line);
+        md.push('\n');
+    }
+    md.trim().to_string() + "\n"
+}
+
+fn file_stem_for(title: &str) -> String {
+    let stem: String = title.chars()
+        .map(|c| if c.is_control() || "<>:\"/\\|?*".contains(c) { ' ' } else { c })
+        .collect();
+    let stem = collapse_ws(&stem).trim_matches('.').chars().take(MAX_FILE_STEM).collect::<String>();
+    if stem.trim().is_empty() { "Article".into() } else { stem.trim().to_string() }
+}
+
+/// Save the open reader view of a tab as Markdown in the Downloads folder.
+/// Returns the written path.
+#[tauri::command]
+pub async fn export_reader_markdown(app: AppHandle, id: String) -> Result<String, BushidoError> {
+    let raw = crate::eval_with_result(&app, &id, READER_GRAB_SCRIPT, std::time::Duration::from_secs(READER_TIMEOUT_SECS))
+        .await
+        .map_err(BushidoError::WebView)?;
+    if raw.is_empty() {
+        return Err(BushidoError::Other("Reader mode isn't on for this tab — turn it on first".into()));
+    }
+    let snap: ReaderSnapshot = serde_json::from_str(&raw).map_err(|e| format!("parse reader: {}", e))?;
+    let mut md = html_to_markdown(&snap.html, Some(&snap.url));
+    if resolve_url(None, &snap.url).is_some() {
+        md.push_str(&format!("\n---\n\nSource: <{}>\n", snap.url));
+    }
+
+    let dir = dirs::download_dir().unwrap_or_else(|| std::path::PathBuf::from("."));
+    let stem = file_stem_for(&snap.title);
+    let mut path = dir.join(format!("{}.md", stem));
+    let mut i = 1;
+    while path.exists() {
+        path = dir.join(format!("{} ({}).md", stem, i));
+        i += 1;
+    }
+    std::fs::write(&path, md)?;
+    Ok(path.to_string_lossy().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // extractor output captured from a news article and a blog post
+    const ARTICLE: &str = include_str!("../tests/fixtures/extract_article.json");
+    const BLOG: &str = include_str!("../tests/fixtures/extract_blog.json");
+
+    #[test]
+    fn parses_news_article() {
+        let c = ExtractedContent::parse(ARTICLE).unwrap();
+        assert_eq!(c.title, "City council approves new bike lanes");
+        assert_eq!(c.byline.as_deref(), Some("Jane Doe"));
+        assert!(!c.text.contains("  "));
+        assert_eq!(c.headings.len(), 3);
+        assert_eq!(c.headings[1].id, "bushido-h-1");
+        // the extractor already strips the javascript: href; the duplicate is dropped here
+        let hrefs: Vec<&str> = c.links.iter().map(|l| l.href.as_str()).collect();
+        assert_eq!(hrefs, vec!["https://example.com/council/minutes", "https://example.com/maps/bike-lanes"]);
+        assert!(c.reading_minutes() >= 1);
+    }
+
+    #[test]
+    fn normalizes_blog_post() {
+        let c = ExtractedContent::parse(BLOG).unwrap();
+        assert_eq!(c.title, "Notes on writing a tiny allocator");
+        assert_eq!(c.byline, None); // blank byline becomes None
+        assert_eq!(c.headings.iter().map(|h| h.level).collect::<Vec<_>>(), vec![1, 2, 6]);
+        // file:// link is dropped
+        assert_eq!(c.links.len(), 1);
+        assert!(!c.text.contains('\n'));
+        assert_eq!(c.word_count(), c.text.split(' ').count());
+    }
+
+    #[test]
+    fn index_text_is_capped_on_a_char_boundary() {
+        assert_eq!(index_text("short"), "short");
+        let long = "é".repeat(MAX_INDEX_TEXT);
+        let cut = index_text(&long);
+        assert!(cut.len() <= MAX_INDEX_TEXT && cut.len() > MAX_INDEX_TEXT - 2);
+    }
+
+    #[test]
+    fn rejects_garbage() {
+        assert!(ExtractedContent::parse("").is_err());
+        let c = ExtractedContent::parse(r#"{"headings":[{"level":9,"text":"x"}]}"#).unwrap();
+        assert_eq!(c.headings[0].level, 6);
+        assert_eq!(c.reading_minutes(), 0);
+    }
+
+    #[test]
+    fn markdown_keeps_structure() {
+        let html = r#"<h1>Title</h1><div><p>First <strong>bold</strong> and <a href="/docs?a=1&amp;b=2">a link</a>.</p>
+            <h2>Part   two</h2><p>See <img src="pic.png" alt="a [pic]"> here</p><script>alert(1)</script>
+            <ul><li>one</li><li>two<ol><li>inner</li></ol></li></ul><p><a href="javascript:x()">bad</a></p></div>"#;
+        let md = html_to_markdown(html, Some("https://example.com/post/1"));
+        assert_eq!(md, "# Title\n\nFirst **bold** and [a link](https://example.com/docs?a=1&b=2).\n\n## Part two\n\nSee ![a pic](https://example.com/post/pic.png) here\n\n- one\n- two\n\n  1. inner\n\nbad\n");
+    }
+
+    #[test]
+    fn markdown_quotes_and_code() {
+        let md = html_to_markdown("<blockquote><p>quoted</p></blockquote><pre><code>let x = 1 &lt; 2;\nfoo()</code></pre>", None);
+        assert_eq!(md, "> quoted\n\n```\nlet x = 1 < 2;\nfoo()\n```\n");
+    }
+
+    #[test]
+    fn markdown_escapes_page_text() {
+        let html = r#"<p>2 * 3 = snake_case [x] #1 `y` a\b <code>f(*p)</code></p><a href="https://en.wikipedia.org/wiki/Rust_(lang)?q=a b">Rust</a>"#;
+        let md = html_to_markdown(html, None);
+        assert_eq!(md, "2 \\* 3 = snake\\_case \\[x\\] \\#1 \\`y\\` a\\\\b `f(*p)`\n\n[Rust](https://en.wikipedia.org/wiki/Rust_%28lang%29?q=a%20b)\n");
+    }
+
+    #[test]
+    fn file_stem_is_safe() {
+        assert_eq!(file_stem_for("A/B: c?"), "A B c");
+        assert_eq!(file_stem_for("  "), "Article");
+    }
+}
@@ -0,0 +1,96 @@
+(function() {
+  if (window.__bushidoExtract) return;
+
+  var NOISE = 'script,style,noscript,iframe,object,embed,nav,footer,aside,form,button,input,select,textarea,' +
+    '[role="navigation"],[role="banner"],[role="complementary"],[aria-hidden="true"],' +
+    '.ad,.ads,.advert,.advertisement,.share,.social,.comments,.related,.newsletter';
+
+  function clean(s) {
+    return (s || '').replace(/\s+/g, ' ').trim();
+  }
+
+  function textLen(el) {
+    return clean(el.textContent).length;
+  }
+
+  // score a block by the paragraph text it directly owns, penalising link-heavy blocks (menus, footers)
+  function score(el) {
+    var pText = 0;
+    for (var i = 0; i < el.children.length; i++) {
+      var c = el.children[i];
+      if (c.tagName === 'P' || c.tagName === 'PRE' || c.tagName === 'BLOCKQUOTE') pText += textLen(c);
+    }
+    if (pText === 0) return 0;
+    var linkText = 0;
+    el.querySelectorAll('a').forEach(function(a) { linkText += textLen(a); });
+    var density = linkText / Math.max(textLen(el), 1);
+    return pText * (1 - density);
+  }
+
+  function findRoot() {
+    var el = document.querySelector('article') || document.querySelector('[role="main"]') || document.querySelector('main');
+    if (el && textLen(el) > 200) return el;
+    var best = null, bestScore = 0;
+    document.querySelectorAll('div,section').forEach(function(c) {
+      var s = score(c);
+      if (s > bestScore) { bestScore = s; best = c; }
+    });
+    return best || el || document.body;
+  }
+
+  function meta(sel) {
+    var m = document.querySelector(sel);
+    return m ? clean(m.getAttribute('content')) : '';
+  }
+
+  function findByline(root) {
+    var b = meta('meta[name="author"]') || meta('meta[property="article:author"]');
+    if (b && !/^https?:/.test(b)) return b;
+    var el = root.querySelector('[rel="author"],[itemprop="author"],.byline,.author') ||
+      document.querySelector('[rel="author"],[itemprop="author"],.byline');
+    return el ? clean(el.textContent) : '';
+  }
+
+  window.__bushidoExtract = function() {
+    var root = findRoot();
+    var clone = root.cloneNode(true);
+    clone.querySelectorAll(NOISE).forEach(function(n) { n.remove(); });
+
+    // the html is re-inserted into the page (reader mode), so strip handlers and script urls
+    clone.querySelectorAll('*').forEach(function(n) {
+      for (var i = n.attributes.length - 1; i >= 0; i--) {
+        var a = n.attributes[i];
+        if (/^on/i.test(a.name) || /^\s*javascript:/i.test(a.value)) n.removeAttribute(a.name);
+      }
+    });
+
+    var headings = [];
+    clone.querySelectorAll('h1,h2,h3,h4,h5,h6').forEach(function(h, i) {
+      var text = clean(h.textContent);
+      if (!text) return;
+      if (!h.id) h.id = 'bushido-h-' + i;
+      headings.push({ level: parseInt(h.tagName.charAt(1), 10), text: text, id: h.id });
+    });
+
+    var links = [];
+    clone.querySelectorAll('a[href]').forEach(function(a) {
+      if (links.length >= 500) return;
+      links.push({ href: a.href, text: clean(a.textContent) });
+    });
+
+    var title = meta('meta[property="og:title"]');
+    if (!title) {
+      var h1 = clone.querySelector('h1');
+      title = h1 ? clean(h1.textContent) : clean(document.title);
+    }
+
+    return {
+      title: title,
+      byline: findByline(root),
+      html: clone.innerHTML,
+      text: clean(clone.textContent),
+      links: links,
+      headings: headings
+    };
+  };
+})();
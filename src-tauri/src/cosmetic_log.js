@@ -0,0 +1,90 @@
+// Cosmetic-hiding log: which hiding selectors actually matched something on this
+// page, so a missing element can be traced back to its filter. Only injected into
+// tabs that turned the log on; scanning every hiding rule isn't free.
+(function() {
+    'use strict';
+    if (window.__bushidoCosmeticLog) {
+        window.__bushidoCosmeticLogOff = false;
+        if (window.__bushidoCosmeticLogReset) window.__bushidoCosmeticLogReset();
+        return;
+    }
+    Object.defineProperty(window, '__bushidoCosmeticLog', { value: true, writable: false, configurable: false });
+    window.__bushidoCosmeticLogOff = false;
+
+    var STYLE_IDS = ['bushido-cosmetic', 'bushido-cosmetic-early', 'bushido-generic-cosmetic'];
+    var MAX_LOGGED = 200;
+    var BATCH = 50;
+    var logged = new Set();
+    var scanTimer = null;
+    var lastScan = 0;
+
+    // split a selector list on top-level commas only (":is(a, b)" stays whole)
+    var splitList = function(text) {
+        var out = [], depth = 0, quote = null, start = 0;
+        for (var i = 0; i < text.length; i++) {
+            var c = text[i];
+            if (quote) { if (c === '\\') i++; else if (c === quote) quote = null; continue; }
+            if (c === '"' || c === "'") quote = c;
+            else if (c === '(' || c === '[') depth++;
+            else if (c === ')' || c === ']') depth--;
+            else if (c === ',' && depth === 0) { out.push(text.slice(start, i).trim()); start = i + 1; }
+        }
+        out.push(text.slice(start).trim());
+        return out.filter(Boolean);
+    };
+
+    var selectorsOf = function(style) {
+        var out = [];
+        try {
+            var rules = style.sheet ? style.sheet.cssRules : [];
+            for (var i = 0; i < rules.length; i++) {
+                if (rules[i].selectorText) out = out.concat(splitList(rules[i].selectorText));
+            }
+        } catch(e) {}
+        return out;
+    };
+
+    var scan = function() {
+        scanTimer = null;
+        if (window.__bushidoCosmeticLogOff) return;
+        lastScan = Date.now();
+        var hits = [];
+        STYLE_IDS.forEach(function(id) {
+            var style = document.getElementById(id);
+            if (!style) return;
+            var sels = selectorsOf(style).filter(function(s) { return !logged.has(s); });
+            for (var i = 0; i < sels.length && logged.size < MAX_LOGGED; i += BATCH) {
+                var chunk = sels.slice(i, i + BATCH);
+                // one query per batch; only a batch with a match is checked selector by selector
+                try { if (!document.querySelector(chunk.join(','))) continue; } catch(e) {}
+                chunk.forEach(function(s) {
+                    if (logged.size >= MAX_LOGGED || logged.has(s)) return;
+                    try {
+                        var n = document.querySelectorAll(s).length;
+                        if (n) { logged.add(s); hits.push({ selector: s, count: n }); }
+                    } catch(e) {}
+                });
+            }
+        });
+        for (var k = 0; k < hits.length; k += BATCH) {
+            window.chrome.webview.postMessage(JSON.stringify({
+                __bushido: 'cosmetic-log', url: location.href, hits: hits.slice(k, k + BATCH)
+            }));
+        }
+    };
+
+    var scheduleScan = function() {
+        if (window.__bushidoCosmeticLogOff || scanTimer || logged.size >= MAX_LOGGED) return;
+        scanTimer = setTimeout(scan, Math.max(500, 2000 - (Date.now() - lastScan)));
+    };
+
+    window.__bushidoCosmeticLogReset = function() { logged.clear(); scheduleScan(); };
+
+    if (window.chrome && window.chrome.webview) {
+        new MutationObserver(scheduleScan).observe(document, { childList: true, subtree: true });
+        document.addEventListener('DOMContentLoaded', scheduleScan);
+        window.addEventListener('load', scheduleScan);
+        // turned on after the page loaded
+        scheduleScan();
+    }
+})();
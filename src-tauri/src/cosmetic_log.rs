@@ -0,0 +1,128 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::{SystemTime, UNIX_EPOCH};
+use parking_lot::Mutex;
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::error::BushidoError;
+use crate::ipc::CosmeticHit;
+
+/// Scans the page's hiding rules for selectors that matched and posts them as
+/// `{__bushido: "cosmetic-log"}` messages. Only injected into tabs that opted in.
+pub const COSMETIC_LOG_SCRIPT: &str = include_str!("cosmetic_log.js");
+
+const MAX_ENTRIES: usize = 300;
+const MAX_URL_LEN: usize = 2048;
+
+/// A hiding selector that matched on a page, for tracing hidden content back to a filter
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CosmeticLogEntry {
+    pub selector: String,
+    pub url: String,
+    /// elements the selector matched when first seen, or the most since
+    pub count: u64,
+    pub timestamp_ms: u64,
+}
+
+/// Per-tab log of cosmetic filters that hid something, oldest first
+pub struct CosmeticLogState {
+    enabled: Mutex<HashSet<String>>,
+    entries: Mutex<HashMap<String, VecDeque<CosmeticLogEntry>>>,
+}
+
+fn add_hits(buf: &mut VecDeque<CosmeticLogEntry>, url: &str, hits: Vec<CosmeticHit>, now_ms: u64) {
+    for hit in hits {
+        if let Some(e) = buf.iter_mut().find(|e| e.selector == hit.selector && e.url == url) {
+            e.count = e.count.max(hit.count);
+            continue;
+        }
+        if buf.len() >= MAX_ENTRIES {
+            buf.pop_front();
+        }
+        buf.push_back(CosmeticLogEntry { selector: hit.selector, url: url.to_string(), count: hit.count, timestamp_ms: now_ms });
+    }
+}
+
+impl CosmeticLogState {
+    pub fn new() -> Self {
+        Self { enabled: Mutex::new(HashSet::new()), entries: Mutex::new(HashMap::new()) }
+    }
+
+    pub fn is_enabled(&self, tab_id: &str) -> bool {
+        self.enabled.lock().contains(tab_id)
+    }
+
+    pub fn forget_tab(&self, tab_id: &str) {
+        self.enabled.lock().remove(tab_id);
+        self.entries.lock().remove(tab_id);
+    }
+}
+
+/// Called from the WebMessageReceived handler with an already validated `cosmetic-log`.
+/// Dropped unless the tab opted in.
+pub fn record(app: &AppHandle, tab_id: &str, url: &str, hits: Vec<CosmeticHit>) {
+    let cs = app.state::<CosmeticLogState>();
+    if !cs.is_enabled(tab_id) { return; }
+    let url: String = url.chars().take(MAX_URL_LEN).collect();
+    let now_ms = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64;
+    add_hits(cs.entries.lock().entry(tab_id.to_string()).or_default(), &url, hits, now_ms);
+}
+
+/// Opt a tab in or out of the cosmetic log. Turning it off keeps what was already logged.
+#[tauri::command]
+pub async fn set_cosmetic_logging(app: AppHandle, id: String, enabled: bool) -> Result<(), BushidoError> {
+    let cs = app.state::<CosmeticLogState>();
+    if enabled {
+        cs.enabled.lock().insert(id.clone());
+    } else {
+        cs.enabled.lock().remove(&id);
+    }
+    if let Some(wv) = app.get_webview(&id) {
+        wv.eval(if enabled { COSMETIC_LOG_SCRIPT } else { "window.__bushidoCosmeticLogOff=true;" })?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_cosmetic_log(app: AppHandle, id: String) -> Result<Vec<CosmeticLogEntry>, BushidoError> {
+    let entries = app.state::<CosmeticLogState>().entries.lock();
+    Ok(entries.get(&id).map(|d| d.iter().cloned().collect()).unwrap_or_default())
+}
+
+/// Empties the tab's log; the page reports whatever is still hidden again
+#[tauri::command]
+pub async fn clear_cosmetic_log(app: AppHandle, id: String) -> Result<(), BushidoError> {
+    let cs = app.state::<CosmeticLogState>();
+    cs.entries.lock().remove(&id);
+    if !cs.is_enabled(&id) { return Ok(()); }
+    if let Some(wv) = app.get_webview(&id) {
+        wv.eval("window.__bushidoCosmeticLogReset && window.__bushidoCosmeticLogReset();")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hit(selector: &str, count: u64) -> CosmeticHit {
+        CosmeticHit { selector: selector.into(), count }
+    }
+
+    #[test]
+    fn merges_repeats_and_caps() {
+        let mut buf = VecDeque::new();
+        add_hits(&mut buf, "https://a.example/", vec![hit(".ad", 2), hit("#banner", 1)], 1);
+        add_hits(&mut buf, "https://a.example/", vec![hit(".ad", 5)], 2);
+        add_hits(&mut buf, "https://b.example/", vec![hit(".ad", 1)], 3);
+        assert_eq!(buf.len(), 3);
+        assert_eq!(buf[0].count, 5);
+        assert_eq!(buf[0].timestamp_ms, 1);
+
+        let many = (0..MAX_ENTRIES).map(|i| hit(&format!(".x{}", i), 1)).collect();
+        add_hits(&mut buf, "https://c.example/", many, 4);
+        assert_eq!(buf.len(), MAX_ENTRIES);
+        assert_eq!(buf.front().unwrap().selector, ".x0");
+    }
+}
@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::time::{Duration, Instant};
+use parking_lot::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::crash_log;
+use crate::error::BushidoError;
+
+pub const DEFAULT_MAX_RETRIES: u32 = 3;
+const MAX_RETRIES_LIMIT: u32 = 10;
+// crashes older than this no longer count against the tab
+const CRASH_WINDOW: Duration = Duration::from_secs(60);
+const BASE_DELAY: Duration = Duration::from_secs(1);
+const MAX_DELAY: Duration = Duration::from_secs(30);
+
+/// Auto-reload policy for tabs whose renderer crashed, and the recent crashes per tab
+pub struct CrashRecoveryState {
+    enabled: AtomicBool,
+    max_retries: AtomicU32,
+    /// tab id → when its renderer crashed, within the last CRASH_WINDOW
+    crashes: Mutex<HashMap<String, Vec<Instant>>>,
+}
+
+/// Wait before the reload after the `n`th recent crash: 1s, 2s, 4s… capped at MAX_DELAY
+fn backoff(n: u32) -> Duration {
+    BASE_DELAY.saturating_mul(1u32 << n.saturating_sub(1).min(16)).min(MAX_DELAY)
+}
+
+impl CrashRecoveryState {
+    pub fn new() -> Self {
+        Self {
+            enabled: AtomicBool::new(false),
+            max_retries: AtomicU32::new(DEFAULT_MAX_RETRIES),
+            crashes: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Count a crash; how long to wait before reloading, or None to leave the tab crashed
+    fn on_crash(&self, tab_id: &str, now: Instant) -> Option<Duration> {
+        let mut crashes = self.crashes.lock();
+        let recent = crashes.entry(tab_id.to_string()).or_default();
+        recent.retain(|t| now.duration_since(*t) < CRASH_WINDOW);
+        recent.push(now);
+        let n = recent.len() as u32;
+        (self.enabled.load(Ordering::Relaxed) && n <= self.max_retries.load(Ordering::Relaxed)).then(|| backoff(n))
+    }
+
+    pub fn forget_tab(&self, tab_id: &str) {
+        self.crashes.lock().remove(tab_id);
+    }
+}
+
+/// The renderer behind `tab_id` died. Tells the UI, then reloads the tab's last URL
+/// after the backoff if the policy allows another attempt. `recoverable` is false
+/// when the whole browser process is gone and only recreating the webview helps.
+pub fn handle_crash(app: &AppHandle, tab_id: &str, recoverable: bool) {
+    let delay = if recoverable {
+        app.state::<CrashRecoveryState>().on_crash(tab_id, Instant::now())
+    } else {
+        None
+    };
+    let _ = app.emit_to("main", "tab-crashed", serde_json::json!({
+        "id": tab_id,
+        "recovering": delay.is_some(),
+    }));
+    let Some(delay) = delay else { return };
+
+    let app = app.clone();
+    let tab_id = tab_id.to_string();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(delay).await;
+        // closed (or recreated by the user) while we waited
+        if !app.state::<crate::WebviewState>().tabs.lock().contains_key(&tab_id) {
+            return;
+        }
+        let Some(url) = app.state::<crate::UrlState>().tabs.lock().get(&tab_id).cloned() else { return };
+        let (Some(wv), Ok(parsed)) = (app.get_webview(&tab_id), url.parse::<url::Url>()) else { return };
+        match wv.navigate(parsed) {
+            Ok(()) => {
+                crash_log::log_info("crash_recovery", &format!("reloaded tab={} after {:?}", tab_id, delay));
+                let _ = app.emit_to("main", "tab-recovered", serde_json::json!({ "id": tab_id, "url": url }));
+            }
+            Err(e) => crash_log::log_warn("crash_recovery", &format!("reload of tab={} failed: {}", tab_id, e)),
+        }
+    });
+}
+
+/// Turn automatic reloading of crashed tabs on/off. A tab that crashes more than
+/// `max_retries` times within a minute is left crashed. Returns the limit actually used.
+#[tauri::command]
+pub async fn set_crash_recovery(app: AppHandle, enabled: bool, max_retries: Option<u32>) -> Result<u32, BushidoError> {
+    let cs = app.state::<CrashRecoveryState>();
+    let max = max_retries.unwrap_or(DEFAULT_MAX_RETRIES).clamp(1, MAX_RETRIES_LIMIT);
+    cs.max_retries.store(max, Ordering::Relaxed);
+    cs.enabled.store(enabled, Ordering::Relaxed);
+    Ok(max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_up_to_the_cap() {
+        assert_eq!(backoff(1), Duration::from_secs(1));
+        assert_eq!(backoff(2), Duration::from_secs(2));
+        assert_eq!(backoff(3), Duration::from_secs(4));
+        assert_eq!(backoff(10), MAX_DELAY);
+        assert_eq!(backoff(u32::MAX), MAX_DELAY);
+    }
+
+    #[test]
+    fn gives_up_after_max_retries_within_the_window() {
+        let cs = CrashRecoveryState::new();
+        let t0 = Instant::now();
+        assert_eq!(cs.on_crash("t", t0), None, "disabled by default");
+
+        cs.enabled.store(true, Ordering::Relaxed);
+        cs.max_retries.store(2, Ordering::Relaxed);
+        cs.forget_tab("t");
+        assert_eq!(cs.on_crash("t", t0), Some(Duration::from_secs(1)));
+        assert_eq!(cs.on_crash("t", t0 + Duration::from_secs(5)), Some(Duration::from_secs(2)));
+        assert_eq!(cs.on_crash("t", t0 + Duration::from_secs(10)), None);
+        // other tabs have their own budget
+        assert!(cs.on_crash("u", t0).is_some());
+        // once the old crashes age out the tab gets another attempt
+        assert!(cs.on_crash("t", t0 + Duration::from_secs(66)).is_some());
+    }
+}
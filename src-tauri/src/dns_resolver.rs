@@ -260,3 +260,51 @@ pub async fn start() -> Result<u16, String> {
 pub fn flags(port: u16) -> String {
     format!("--enable-features=DnsOverHttps --dns-over-https-mode=secure --dns-over-https-templates=http://localhost:{}/dns-query", port)
 }
+
+const MAX_TEMPLATE_LEN: usize = 512;
+const DNS_VARIABLE: &str = "{?dns}";
+
+/// Normalized DoH URI template (RFC 8484): an https URL, optionally ending in `{?dns}`.
+/// It lands on the browser command line, so only the URL's serialized form is used.
+pub fn validate_template(template: &str) -> Result<String, String> {
+    let template = template.trim();
+    if template.len() > MAX_TEMPLATE_LEN {
+        return Err(format!("resolver URL is limited to {} characters", MAX_TEMPLATE_LEN));
+    }
+    let (base, variable) = match template.strip_suffix(DNS_VARIABLE) {
+        Some(base) => (base, DNS_VARIABLE),
+        None => (template, ""),
+    };
+    let url = url::Url::parse(base).map_err(|e| format!("invalid resolver URL: {}", e))?;
+    if url.scheme() != "https" || url.host().is_none() {
+        return Err("resolver URL must be https://".into());
+    }
+    if !url.username().is_empty() || url.password().is_some() || url.fragment().is_some() {
+        return Err("resolver URL can't carry credentials or a fragment".into());
+    }
+    if !variable.is_empty() && url.query().is_some() {
+        return Err("resolver URL can't combine a query with {?dns}".into());
+    }
+    Ok(format!("{}{}", url, variable))
+}
+
+/// Browser flags for a resolver of the user's choosing in place of the local one
+pub fn custom_flags(template: &str) -> String {
+    format!("--enable-features=DnsOverHttps --dns-over-https-mode=secure --dns-over-https-templates={}", template)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn template_must_be_plain_https() {
+        assert_eq!(validate_template(" https://dns.quad9.net/dns-query ").unwrap(), "https://dns.quad9.net/dns-query");
+        assert_eq!(validate_template("https://doh.example/q{?dns}").unwrap(), "https://doh.example/q{?dns}");
+        assert_eq!(validate_template("https://doh.example/a b\"--x").unwrap(), "https://doh.example/a%20b%22--x");
+        assert!(validate_template("http://dns.example/dns-query").is_err());
+        assert!(validate_template("https://user:pw@dns.example/").is_err());
+        assert!(validate_template("https://dns.example/?a=1{?dns}").is_err());
+        assert!(validate_template("dns.example").is_err());
+    }
+}
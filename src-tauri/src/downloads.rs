@@ -1,22 +1,28 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use parking_lot::Mutex;
-use std::sync::atomic::{AtomicU64, Ordering};
-use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use serde::{Serialize, Deserialize};
 use tauri::{AppHandle, Manager, Emitter};
 use futures_util::StreamExt;
 
+use crate::error::BushidoError;
+
 const PROGRESS_INTERVAL_MS: u128 = 500;
 const MANIFEST_INTERVAL_SECS: u64 = 5;
-const SPEED_WINDOW_SECS: f64 = 2.0;
+const SPEED_WINDOW: Duration = Duration::from_secs(1);
+const SPEED_SAMPLE_GAP: Duration = Duration::from_millis(100);
 const MIN_CHUNK_SIZE: u64 = 512 * 1024; // 512KB min per segment
 const DEFAULT_SEGMENTS: u32 = 6;
+pub const DEFAULT_MAX_CONCURRENT: usize = 3;
+pub const MAX_CONCURRENT_RANGE: std::ops::RangeInclusive<usize> = 1..=10;
 
 #[derive(Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum DlState {
+    Queued,
     Downloading,
     Paused,
     Completed,
@@ -34,12 +40,18 @@ pub struct DlItem {
     pub total_bytes: Option<u64>,
     pub received_bytes: u64,
     pub state: DlState,
-    pub speed: u64,
+    pub speed_bps: u64,
+    pub eta_secs: Option<u64>, // None while the total size or speed is unknown
     pub error: Option<String>,
     pub created_at: u64,
     pub supports_range: bool,
     pub segments: u32, // active connection count (0 = single-stream)
     pub priority: u32,
+    // validators captured at start, checked before appending on resume
+    #[serde(skip)]
+    pub etag: Option<String>,
+    #[serde(skip)]
+    pub last_modified: Option<String>,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -62,6 +74,8 @@ struct Manifest {
     received_bytes: u64,
     supports_range: bool,
     etag: Option<String>,
+    #[serde(default)]
+    last_modified: Option<String>,
     created_at: u64,
     #[serde(default)]
     segments: Vec<Segment>,
@@ -74,6 +88,11 @@ struct Manifest {
 pub struct DownloadManager {
     pub downloads: Mutex<HashMap<String, DlItem>>,
     pub cancel_tx: Mutex<HashMap<String, tokio::sync::watch::Sender<bool>>>,
+    // directories we've written downloads into — remove(delete_file) never touches anything else
+    dirs: Mutex<HashSet<PathBuf>>,
+    pub max_concurrent: AtomicUsize,
+    running: AtomicUsize,
+    queue_changed: tokio::sync::Notify,
 }
 
 impl DownloadManager {
@@ -81,10 +100,89 @@ impl DownloadManager {
         Self {
             downloads: Mutex::new(HashMap::new()),
             cancel_tx: Mutex::new(HashMap::new()),
+            dirs: Mutex::new(HashSet::new()),
+            max_concurrent: AtomicUsize::new(DEFAULT_MAX_CONCURRENT),
+            running: AtomicUsize::new(0),
+            queue_changed: tokio::sync::Notify::new(),
+        }
+    }
+
+    /// Wake queued downloads so they re-check whether it's their turn.
+    pub fn kick_queue(&self) {
+        self.queue_changed.notify_waiters();
+    }
+
+    fn remember_dir(&self, dir: &Path) {
+        if let Ok(canon) = dir.canonicalize() {
+            self.dirs.lock().insert(canon);
+        }
+    }
+}
+
+/// A running download's hold on one of the `max_concurrent` slots; released on drop,
+/// however the transfer task ends.
+struct Slot(AppHandle);
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        let dm = self.0.state::<DownloadManager>();
+        dm.running.fetch_sub(1, Ordering::AcqRel);
+        dm.kick_queue();
+    }
+}
+
+/// Wait until `id` is at the head of the queue and a slot is free. Not a tokio Semaphore:
+/// its waiters are strictly FIFO, and the queue has to follow download priority.
+/// Returns None if the download was paused or cancelled while waiting.
+async fn wait_for_slot(app: &AppHandle, id: &str, mut cancel_rx: tokio::sync::watch::Receiver<bool>) -> Option<Slot> {
+    let dm = app.state::<DownloadManager>();
+    loop {
+        // register before checking so a kick between the check and the await isn't lost
+        let notified = dm.queue_changed.notified();
+        tokio::pin!(notified);
+        notified.as_mut().enable();
+
+        {
+            let mut downloads = dm.downloads.lock();
+            if downloads.get(id).map_or(true, |d| d.state != DlState::Queued) {
+                return None;
+            }
+            // lowest priority value first, then oldest
+            let head = downloads.values()
+                .filter(|d| d.state == DlState::Queued)
+                .min_by_key(|d| (d.priority, d.created_at))
+                .map(|d| d.id.clone());
+            let max = dm.max_concurrent.load(Ordering::Relaxed);
+            if head.as_deref() == Some(id) && dm.running.load(Ordering::Acquire) < max {
+                dm.running.fetch_add(1, Ordering::AcqRel);
+                if let Some(item) = downloads.get_mut(id) {
+                    item.state = DlState::Downloading;
+                    let _ = app.emit_to("main", "download-progress", item.clone());
+                }
+                drop(downloads);
+                // the next in line may fit in a slot too
+                dm.kick_queue();
+                return Some(Slot(app.clone()));
+            }
+        }
+
+        tokio::select! {
+            _ = &mut notified => {}
+            _ = cancel_rx.changed() => return None,
         }
     }
 }
 
+/// Change the concurrency cap, clamped to `MAX_CONCURRENT_RANGE`. Downloads already running
+/// past a lowered cap finish; the queue just waits for them.
+pub fn set_max_concurrent(app: &AppHandle, n: usize) -> usize {
+    let n = n.clamp(*MAX_CONCURRENT_RANGE.start(), *MAX_CONCURRENT_RANGE.end());
+    let dm = app.state::<DownloadManager>();
+    dm.max_concurrent.store(n, Ordering::Relaxed);
+    dm.kick_queue();
+    n
+}
+
 pub struct RateLimiter {
     pub limit_bps: AtomicU64, // 0 = unlimited
     tokens: AtomicU64,
@@ -126,6 +224,18 @@ impl RateLimiter {
             tokio::time::sleep(std::time::Duration::from_millis(25)).await;
         }
     }
+
+    /// `acquire` for payloads that can exceed a whole second's budget — takes it in slices,
+    /// since the bucket never holds more than `limit_bps`.
+    pub async fn acquire_large(&self, mut bytes: u64) {
+        while bytes > 0 {
+            let limit = self.limit_bps.load(Ordering::Relaxed);
+            if limit == 0 { return; }
+            let step = bytes.min(limit);
+            self.acquire(step).await;
+            bytes -= step;
+        }
+    }
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -135,12 +245,44 @@ pub struct MimeRoute {
     pub folder: String,
 }
 
+/// Moving-average throughput over the last `SPEED_WINDOW`, fed with the running byte count.
+struct SpeedSampler {
+    samples: VecDeque<(Instant, u64)>,
+}
+
+impl SpeedSampler {
+    fn new(received: u64) -> Self {
+        Self { samples: VecDeque::from([(Instant::now(), received)]) }
+    }
+
+    fn sample(&mut self, received: u64) -> u64 {
+        let now = Instant::now();
+        if self.samples.back().map_or(true, |(t, _)| now.duration_since(*t) >= SPEED_SAMPLE_GAP) {
+            self.samples.push_back((now, received));
+        }
+        // keep one sample at or beyond the window edge so the average spans the full window
+        while self.samples.len() > 2 && now.duration_since(self.samples[1].0) >= SPEED_WINDOW {
+            self.samples.pop_front();
+        }
+        let Some(&(t0, b0)) = self.samples.front() else { return 0 };
+        let secs = now.duration_since(t0).as_secs_f64();
+        if secs < 0.1 { return 0; }
+        (received.saturating_sub(b0) as f64 / secs) as u64
+    }
+}
+
+fn eta_secs(total: Option<u64>, received: u64, speed_bps: u64) -> Option<u64> {
+    let total = total?;
+    if speed_bps == 0 { return None; }
+    Some(total.saturating_sub(received).div_ceil(speed_bps))
+}
+
 fn now_epoch() -> u64 {
     SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
 }
 
 fn manifests_dir(app: &AppHandle) -> PathBuf {
-    let dir = app.path().app_data_dir().unwrap_or_else(|_| PathBuf::from(".")).join("downloads");
+    let dir = crate::data_dir(app).join("downloads");
     let _ = std::fs::create_dir_all(&dir);
     dir
 }
@@ -168,6 +310,46 @@ fn delete_manifest(app: &AppHandle, id: &str) {
     let _ = std::fs::remove_file(manifest_path(app, id));
 }
 
+fn partial_len(file_path: &str) -> u64 {
+    std::fs::metadata(file_path).map(|m| m.len()).unwrap_or(0)
+}
+
+fn header_string(headers: &reqwest::header::HeaderMap, name: &str) -> Option<String> {
+    headers.get(name).and_then(|v| v.to_str().ok()).map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+/// (ETag, Last-Modified) from a response.
+fn validators(headers: &reqwest::header::HeaderMap) -> (Option<String>, Option<String>) {
+    (header_string(headers, "etag"), header_string(headers, "last-modified"))
+}
+
+/// Value for `If-Range`. Weak ETags aren't allowed there, so fall back to Last-Modified.
+fn if_range_value(etag: Option<&str>, last_modified: Option<&str>) -> Option<String> {
+    etag.filter(|e| !e.starts_with("W/"))
+        .or(last_modified)
+        .map(String::from)
+}
+
+/// Whether a ranged response is still the resource we started downloading.
+/// A validator the server doesn't send back can't be compared, so it doesn't count against.
+fn same_resource(etag: Option<&str>, last_modified: Option<&str>, headers: &reqwest::header::HeaderMap) -> bool {
+    let (new_etag, new_lm) = validators(headers);
+    if let (Some(old), Some(new)) = (etag, new_etag.as_deref()) {
+        return old == new;
+    }
+    if let (Some(old), Some(new)) = (last_modified, new_lm.as_deref()) {
+        return old == new;
+    }
+    true
+}
+
+/// First byte of a `Content-Range: bytes <start>-<end>/<total>` header.
+fn content_range_start(headers: &reqwest::header::HeaderMap) -> Option<u64> {
+    let v = header_string(headers, "content-range")?;
+    let range = v.strip_prefix("bytes ")?;
+    range.split('-').next()?.trim().parse().ok()
+}
+
 pub fn load_pending(app: &AppHandle) -> Vec<DlItem> {
     let dir = manifests_dir(app);
     let mut items = Vec::new();
@@ -178,7 +360,17 @@ pub fn load_pending(app: &AppHandle) -> Vec<DlItem> {
                 if let Some(m) = load_manifest(&path) {
                     // only restore if partial file exists
                     if Path::new(&m.file_path).exists() {
+                        if let Some(parent) = Path::new(&m.file_path).parent() {
+                            app.state::<DownloadManager>().remember_dir(parent);
+                        }
                         let seg_count = m.segments.iter().filter(|s| !s.done).count() as u32;
+                        // single-stream manifests are only saved every few seconds; the
+                        // partial file itself is the real resume point
+                        let received_bytes = if m.segments.is_empty() {
+                            partial_len(&m.file_path)
+                        } else {
+                            m.received_bytes
+                        };
                         items.push(DlItem {
                             id: m.id,
                             url: m.url,
@@ -186,14 +378,17 @@ pub fn load_pending(app: &AppHandle) -> Vec<DlItem> {
                             file_name: m.file_name,
                             mime_type: String::new(),
                             total_bytes: m.total_bytes,
-                            received_bytes: m.received_bytes,
+                            received_bytes,
                             state: DlState::Paused,
-                            speed: 0,
+                            speed_bps: 0,
+                            eta_secs: None,
                             error: None,
                             created_at: m.created_at,
                             supports_range: m.supports_range,
                             segments: seg_count,
                             priority: m.priority,
+                            etag: m.etag,
+                            last_modified: m.last_modified,
                         });
                     }
                 }
@@ -203,6 +398,33 @@ pub fn load_pending(app: &AppHandle) -> Vec<DlItem> {
     items
 }
 
+/// Native "Save as" dialog pre-filled with `dir` / `file_name`. Returns (dir, file name),
+/// or None if the user cancelled or the dialog failed.
+pub async fn prompt_save_location(app: &AppHandle, dir: &str, file_name: &str) -> Option<(String, String)> {
+    use tauri_plugin_dialog::DialogExt;
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    app.dialog()
+        .file()
+        .set_directory(dir)
+        .set_file_name(file_name)
+        .save_file(move |path| { let _ = tx.send(path); });
+    let path = rx.await.ok()??.into_path().ok()?;
+    let parent = path.parent()?.to_string_lossy().to_string();
+    let name = path.file_name()?.to_string_lossy().to_string();
+    Some((parent, name))
+}
+
+// shell-handled types that are only ever used to deliver exploits — refused at every entry point
+const BLOCKED_EXTENSIONS: &[&str] = &[
+    "scf", "lnk", "url", "hta", "library-ms", "searchconnector-ms",
+    "settingcontent-ms", "search-ms", "appref-ms", "diagcab", "cpl", "inf",
+];
+
+fn is_blocked_extension(name: &str) -> bool {
+    let lower = name.trim_end_matches(['.', ' ']).to_lowercase();
+    BLOCKED_EXTENSIONS.iter().any(|ext| lower.ends_with(&format!(".{}", ext)))
+}
+
 // deduplicate filename: report.pdf -> report (1).pdf
 fn dedup_filename(dir: &str, name: &str) -> String {
     let base = Path::new(dir).join(name);
@@ -259,13 +481,20 @@ pub fn parse_filename(url: &str, disposition: &str) -> String {
     "download".to_string()
 }
 
-pub async fn start(app: AppHandle, url: String, file_name: String, download_dir: String, cookies: Option<String>, mime_routing: Vec<MimeRoute>, rate_limiter: Arc<RateLimiter>) -> Result<String, String> {
+pub async fn start(app: AppHandle, url: String, file_name: String, download_dir: String, cookies: Option<String>, mime_routing: Vec<MimeRoute>, rate_limiter: Arc<RateLimiter>) -> Result<String, BushidoError> {
+    if crate::is_offline(&app) {
+        return Err(BushidoError::Permission("Working offline".into()));
+    }
     let id = uuid::Uuid::new_v4().to_string();
     // strip path traversal — keep only the basename
     let safe_name = Path::new(&file_name).file_name()
         .and_then(|n| n.to_str())
         .unwrap_or("download")
         .to_string();
+    if is_blocked_extension(&safe_name) {
+        crate::crash_log::log_warn("downloads", &format!("blocked dangerous file type: {}", safe_name));
+        return Err(BushidoError::Permission(format!("Blocked dangerous file type: {}", safe_name)));
+    }
 
     // HEAD request first to get Content-Type for MIME routing
     let client = reqwest::Client::new();
@@ -276,7 +505,9 @@ pub async fn start(app: AppHandle, url: String, file_name: String, download_dir:
     let mut mime_type = String::new();
     let mut head_total: Option<u64> = None;
     let mut head_supports_range = false;
+    let mut head_validators = (None, None);
     if let Ok(head) = head_req.send().await {
+        head_validators = validators(head.headers());
         if let Some(ct) = head.headers().get("content-type") {
             mime_type = ct.to_str().unwrap_or("").split(';').next().unwrap_or("").trim().to_lowercase();
         }
@@ -334,17 +565,21 @@ pub async fn start(app: AppHandle, url: String, file_name: String, download_dir:
         mime_type: mime_type.clone(),
         total_bytes: head_total,
         received_bytes: 0,
-        state: DlState::Downloading,
-        speed: 0,
+        state: DlState::Queued,
+        speed_bps: 0,
+        eta_secs: None,
         error: None,
         created_at: now_epoch(),
         supports_range: head_supports_range,
         segments: 0,
         priority: 0,
+        etag: head_validators.0,
+        last_modified: head_validators.1,
     };
 
     {
         let dm = app.state::<DownloadManager>();
+        dm.remember_dir(Path::new(&target_dir));
         dm.downloads.lock().insert(id.clone(), item.clone());
     }
 
@@ -361,6 +596,8 @@ pub async fn start(app: AppHandle, url: String, file_name: String, download_dir:
     let cookies2 = cookies.clone();
     let rl = rate_limiter;
     tokio::spawn(async move {
+        let Some(_slot) = wait_for_slot(&app2, &id2, rx.clone()).await else { return; };
+
         // HEAD already done above — use cached values
         let total_bytes = head_total;
         let supports_range = head_supports_range;
@@ -380,7 +617,10 @@ pub async fn start(app: AppHandle, url: String, file_name: String, download_dir:
     Ok(id)
 }
 
-pub async fn resume(app: AppHandle, id: String, rate_limiter: Arc<RateLimiter>) -> Result<(), String> {
+pub async fn resume(app: AppHandle, id: String, rate_limiter: Arc<RateLimiter>) -> Result<(), BushidoError> {
+    if crate::is_offline(&app) {
+        return Err(BushidoError::Permission("Working offline".into()));
+    }
     let manifest_data = {
         let manifest_p = manifest_path(&app, &id);
         load_manifest(&manifest_p)
@@ -389,14 +629,19 @@ pub async fn resume(app: AppHandle, id: String, rate_limiter: Arc<RateLimiter>)
     let (url, file_path, _file_name, _download_dir, offset, has_segments) = {
         let dm = app.state::<DownloadManager>();
         let mut downloads = dm.downloads.lock();
-        let item = downloads.get_mut(&id).ok_or("not found")?;
+        let item = downloads.get_mut(&id).ok_or_else(|| BushidoError::NotFound("not found".into()))?;
         if item.state != DlState::Paused { return Err("not paused".into()); }
-        if !item.supports_range { return Err("server doesn't support resume".into()); }
-        item.state = DlState::Downloading;
-        item.speed = 0;
+        // a download paused while still queued has nothing on disk and can start over anywhere
+        if !item.supports_range && partial_len(&item.file_path) > 0 { return Err("server doesn't support resume".into()); }
+        item.state = DlState::Queued;
+        item.speed_bps = 0;
+        item.eta_secs = None;
         let dir = Path::new(&item.file_path).parent().map(|p| p.to_string_lossy().to_string()).unwrap_or_default();
         let has_segs = manifest_data.as_ref().map_or(false, |m| !m.segments.is_empty());
-        (item.url.clone(), item.file_path.clone(), item.file_name.clone(), dir, item.received_bytes, has_segs)
+        // single-stream picks up from whatever actually made it to disk
+        let offset = if has_segs { item.received_bytes } else { partial_len(&item.file_path) };
+        item.received_bytes = offset;
+        (item.url.clone(), item.file_path.clone(), item.file_name.clone(), dir, offset, has_segs)
     };
 
     let (tx, rx) = tokio::sync::watch::channel(false);
@@ -411,18 +656,45 @@ pub async fn resume(app: AppHandle, id: String, rate_limiter: Arc<RateLimiter>)
     if has_segments {
         // chunked resume
         let m = manifest_data.ok_or("missing manifest data for chunked resume")?;
-        let total = m.total_bytes.unwrap_or(0);
+        let mut total = m.total_bytes.unwrap_or(0);
         let cookies = m.cookies.clone();
-        let segments = m.segments.clone();
+        let mut segments = Some(m.segments.clone());
+        // segments write at fixed offsets, so check the resource up front
+        if m.etag.is_some() || m.last_modified.is_some() {
+            let mut head_req = reqwest::Client::new().head(&url);
+            if let Some(ref c) = cookies {
+                head_req = head_req.header("Cookie", c.as_str());
+            }
+            if let Ok(head) = head_req.send().await {
+                let head_total = header_string(head.headers(), "content-length").and_then(|l| l.parse::<u64>().ok());
+                let changed = !same_resource(m.etag.as_deref(), m.last_modified.as_deref(), head.headers())
+                    || head_total.is_some_and(|t| t != total);
+                if changed {
+                    crate::crash_log::log_warn("downloads", &format!("{} changed on the server, restarting", id));
+                    segments = None;
+                    total = head_total.unwrap_or(total);
+                    let dm = app.state::<DownloadManager>();
+                    if let Some(item) = dm.downloads.lock().get_mut(&id) {
+                        item.received_bytes = 0;
+                        item.total_bytes = Some(total);
+                        let (etag, last_modified) = validators(head.headers());
+                        item.etag = etag;
+                        item.last_modified = last_modified;
+                    }
+                }
+            }
+        }
         let rl = rate_limiter;
         tokio::spawn(async move {
-            dl_task_chunked(app2, id2, url, file_path, total, cookies, Some(segments), rx, rl).await;
+            let Some(_slot) = wait_for_slot(&app2, &id2, rx.clone()).await else { return; };
+            dl_task_chunked(app2, id2, url, file_path, total, cookies, segments, rx, rl).await;
         });
     } else {
         // single-stream resume
         let cookies = manifest_data.and_then(|m| m.cookies.clone());
         let rl = rate_limiter;
         tokio::spawn(async move {
+            let Some(_slot) = wait_for_slot(&app2, &id2, rx.clone()).await else { return; };
             dl_task(app2, id2, url, file_path, cookies, offset, rx, rl).await;
         });
     }
@@ -430,7 +702,7 @@ pub async fn resume(app: AppHandle, id: String, rate_limiter: Arc<RateLimiter>)
     Ok(())
 }
 
-pub fn pause(app: &AppHandle, id: &str) -> Result<(), String> {
+pub fn pause(app: &AppHandle, id: &str) -> Result<(), BushidoError> {
     let dm = app.state::<DownloadManager>();
 
     // signal cancel to stop the stream
@@ -439,9 +711,10 @@ pub fn pause(app: &AppHandle, id: &str) -> Result<(), String> {
     }
 
     let mut downloads = dm.downloads.lock();
-    let item = downloads.get_mut(id).ok_or("not found")?;
+    let item = downloads.get_mut(id).ok_or_else(|| BushidoError::NotFound("not found".into()))?;
     item.state = DlState::Paused;
-    item.speed = 0;
+    item.speed_bps = 0;
+    item.eta_secs = None;
 
     // save manifest for resume (segments saved by chunked task itself on cancel)
     // but we always write a basic manifest here as fallback
@@ -453,7 +726,8 @@ pub fn pause(app: &AppHandle, id: &str) -> Result<(), String> {
         total_bytes: item.total_bytes,
         received_bytes: item.received_bytes,
         supports_range: item.supports_range,
-        etag: None,
+        etag: item.etag.clone(),
+        last_modified: item.last_modified.clone(),
         created_at: item.created_at,
         segments: Vec::new(),
         cookies: None,
@@ -466,10 +740,12 @@ pub fn pause(app: &AppHandle, id: &str) -> Result<(), String> {
     }
 
     let _ = app.emit_to("main", "download-progress", item.clone());
+    drop(downloads);
+    dm.kick_queue();
     Ok(())
 }
 
-pub fn cancel(app: &AppHandle, id: &str) -> Result<(), String> {
+pub fn cancel(app: &AppHandle, id: &str) -> Result<(), BushidoError> {
     let dm = app.state::<DownloadManager>();
 
     // signal cancel
@@ -479,10 +755,12 @@ pub fn cancel(app: &AppHandle, id: &str) -> Result<(), String> {
 
     let file_path = {
         let mut downloads = dm.downloads.lock();
-        let item = downloads.remove(id).ok_or("not found")?;
+        let item = downloads.remove(id).ok_or_else(|| BushidoError::NotFound("not found".into()))?;
         item.file_path
     };
 
+    dm.kick_queue();
+
     // clean up partial file + manifest
     let _ = std::fs::remove_file(&file_path);
     delete_manifest(app, id);
@@ -491,6 +769,112 @@ pub fn cancel(app: &AppHandle, id: &str) -> Result<(), String> {
     Ok(())
 }
 
+fn emit_list(app: &AppHandle) {
+    let list: Vec<DlItem> = app.state::<DownloadManager>().downloads.lock().values().cloned().collect();
+    let _ = app.emit_to("main", "downloads-updated", list);
+}
+
+/// Drop every completed or failed item from the list. Files on disk are left alone.
+pub fn clear_completed(app: &AppHandle) -> usize {
+    let dm = app.state::<DownloadManager>();
+    let removed: Vec<String> = {
+        let mut downloads = dm.downloads.lock();
+        let ids: Vec<String> = downloads.values()
+            .filter(|d| matches!(d.state, DlState::Completed | DlState::Failed))
+            .map(|d| d.id.clone())
+            .collect();
+        for id in &ids { downloads.remove(id); }
+        ids
+    };
+    for id in &removed {
+        delete_manifest(app, id);
+    }
+    emit_list(app);
+    removed.len()
+}
+
+/// Remove one item. Active or paused downloads are cancelled (partial file removed);
+/// finished ones only lose their file when `delete_file` is set.
+pub fn remove(app: &AppHandle, id: &str, delete_file: bool) -> Result<(), BushidoError> {
+    let dm = app.state::<DownloadManager>();
+    let in_flight = {
+        let downloads = dm.downloads.lock();
+        let item = downloads.get(id).ok_or_else(|| BushidoError::NotFound("not found".into()))?;
+        matches!(item.state, DlState::Queued | DlState::Downloading | DlState::Paused)
+    };
+    if in_flight {
+        cancel(app, id)?;
+        emit_list(app);
+        return Ok(());
+    }
+
+    let item = dm.downloads.lock().remove(id).ok_or_else(|| BushidoError::NotFound("not found".into()))?;
+    delete_manifest(app, id);
+    emit_list(app);
+
+    if delete_file {
+        delete_download_file(&dm, &item)?;
+    }
+    Ok(())
+}
+
+fn delete_download_file(dm: &DownloadManager, item: &DlItem) -> Result<(), BushidoError> {
+    let path = Path::new(&item.file_path);
+    let meta = match std::fs::symlink_metadata(path) {
+        Ok(m) => m,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()), // already gone
+        Err(e) => return Err(e.into()),
+    };
+    if !meta.is_file() {
+        return Err(BushidoError::Permission("not a regular file".into()));
+    }
+    let canon = path.canonicalize()?;
+    let in_known_dir = canon.parent().is_some_and(|p| dm.dirs.lock().contains(p));
+    let name_matches = canon.file_name().and_then(|n| n.to_str()) == Some(item.file_name.as_str());
+    if !in_known_dir || !name_matches {
+        crate::crash_log::log_warn("downloads", &format!("refusing to delete {}", item.file_path));
+        return Err(BushidoError::Permission("file is outside the download folders".into()));
+    }
+    match std::fs::remove_file(&canon) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+fn csv_field(s: &str) -> String {
+    // neutralise spreadsheet formulas, then quote
+    let s = if s.starts_with(['=', '+', '-', '@']) { format!("'{}", s) } else { s.to_string() };
+    format!("\"{}\"", s.replace('"', "\"\""))
+}
+
+/// Write the download list to `path` — CSV if it ends in .csv, JSON otherwise.
+pub fn export(app: &AppHandle, path: &Path) -> Result<usize, BushidoError> {
+    let mut list: Vec<DlItem> = app.state::<DownloadManager>().downloads.lock().values().cloned().collect();
+    list.sort_by_key(|d| d.created_at);
+
+    let is_csv = path.extension().and_then(|e| e.to_str()).is_some_and(|e| e.eq_ignore_ascii_case("csv"));
+    let body = if is_csv {
+        let mut out = String::from("id,fileName,url,filePath,mimeType,state,totalBytes,receivedBytes,createdAt,error\n");
+        for d in &list {
+            let state = serde_json::to_value(&d.state).ok()
+                .and_then(|v| v.as_str().map(String::from))
+                .unwrap_or_default();
+            let cols = [
+                d.id.clone(), d.file_name.clone(), d.url.clone(), d.file_path.clone(), d.mime_type.clone(), state,
+                d.total_bytes.map(|t| t.to_string()).unwrap_or_default(),
+                d.received_bytes.to_string(), d.created_at.to_string(), d.error.clone().unwrap_or_default(),
+            ];
+            out.push_str(&cols.iter().map(|c| csv_field(c)).collect::<Vec<_>>().join(","));
+            out.push('\n');
+        }
+        out
+    } else {
+        serde_json::to_string_pretty(&list).map_err(|e| BushidoError::Other(e.to_string()))?
+    };
+    std::fs::write(path, body)?;
+    Ok(list.len())
+}
+
 // single-stream download (v1 path, also fallback for non-range servers)
 async fn dl_task(
     app: AppHandle,
@@ -507,25 +891,36 @@ async fn dl_task(
     // HEAD to check range support + total size (skip if start() already did it)
     let mut total_bytes: Option<u64> = None;
     let mut supports_range = false;
+    let mut etag: Option<String> = None;
+    let mut last_modified: Option<String> = None;
     {
         let dm = app.state::<DownloadManager>();
         let downloads = dm.downloads.lock();
         if let Some(item) = downloads.get(&id) {
             total_bytes = item.total_bytes;
             supports_range = item.supports_range;
+            etag = item.etag.clone();
+            last_modified = item.last_modified.clone();
         }
     }
 
-    // build GET request
-    let mut req = client.get(&url);
-    if let Some(ref c) = cookies {
-        req = req.header("Cookie", c.as_str());
-    }
-    if resume_offset > 0 && supports_range {
-        req = req.header("Range", format!("bytes={}-", resume_offset));
-    }
+    let get = |offset: u64| {
+        let mut req = client.get(&url);
+        if let Some(ref c) = cookies {
+            req = req.header("Cookie", c.as_str());
+        }
+        if offset > 0 {
+            req = req.header("Range", format!("bytes={}-", offset));
+            // server sends the whole body (200) instead of a range if the resource changed
+            if let Some(v) = if_range_value(etag.as_deref(), last_modified.as_deref()) {
+                req = req.header("If-Range", v);
+            }
+        }
+        req.send()
+    };
 
-    let resp = match req.send().await {
+    let requested_offset = if supports_range { resume_offset } else { 0 };
+    let mut resp = match get(requested_offset).await {
         Ok(r) => r,
         Err(e) => {
             fail(&app, &id, &e.to_string());
@@ -533,23 +928,60 @@ async fn dl_task(
         }
     };
 
-    // if resuming and server doesn't honor range, restart from 0
-    let actual_offset = if resume_offset > 0 && resp.status() == 200 {
-        0u64
-    } else {
-        resume_offset
-    };
+    // only append on a 206 for exactly our offset from the same resource;
+    // anything else means starting over with a truncated file
+    let mut actual_offset = requested_offset;
+    if requested_offset > 0 {
+        let appendable = resp.status() == reqwest::StatusCode::PARTIAL_CONTENT
+            && content_range_start(resp.headers()).map_or(true, |s| s == requested_offset)
+            && same_resource(etag.as_deref(), last_modified.as_deref(), resp.headers());
+        if !appendable {
+            crate::crash_log::log_warn("downloads", &format!("can't resume {} at byte {} (HTTP {}), restarting", id, requested_offset, resp.status().as_u16()));
+            actual_offset = 0;
+            if resp.status() != reqwest::StatusCode::OK {
+                resp = match get(0).await {
+                    Ok(r) => r,
+                    Err(e) => {
+                        fail(&app, &id, &e.to_string());
+                        return;
+                    }
+                };
+            }
+        }
+    }
+    if !resp.status().is_success() {
+        fail(&app, &id, &format!("HTTP {}", resp.status().as_u16()));
+        return;
+    }
 
-    // if we got 200 on fresh request, check content-length from response
-    if total_bytes.is_none() {
-        if let Some(cl) = resp.headers().get("content-length") {
-            if let Ok(len) = cl.to_str().unwrap_or("0").parse::<u64>() {
-                total_bytes = Some(len + actual_offset);
-                let dm = app.state::<DownloadManager>();
-                let mut downloads = dm.downloads.lock();
-                if let Some(item) = downloads.get_mut(&id) {
-                    item.total_bytes = total_bytes;
-                }
+    // fresh body: size and validators come from this response
+    if actual_offset == 0 {
+        let (new_etag, new_lm) = validators(resp.headers());
+        let len = resp.headers().get("content-length")
+            .and_then(|cl| cl.to_str().ok())
+            .and_then(|cl| cl.parse::<u64>().ok());
+        if requested_offset > 0 || total_bytes.is_none() {
+            total_bytes = len;
+        }
+        let dm = app.state::<DownloadManager>();
+        let mut downloads = dm.downloads.lock();
+        if let Some(item) = downloads.get_mut(&id) {
+            item.total_bytes = total_bytes;
+            item.received_bytes = 0;
+            if new_etag.is_some() || new_lm.is_some() {
+                item.etag = new_etag;
+                item.last_modified = new_lm;
+            }
+        }
+    } else if total_bytes.is_none() {
+        let len = resp.headers().get("content-length")
+            .and_then(|cl| cl.to_str().ok())
+            .and_then(|cl| cl.parse::<u64>().ok());
+        if let Some(len) = len {
+            let dm = app.state::<DownloadManager>();
+            let mut downloads = dm.downloads.lock();
+            if let Some(item) = downloads.get_mut(&id) {
+                item.total_bytes = Some(len + actual_offset);
             }
         }
     }
@@ -557,10 +989,10 @@ async fn dl_task(
     // open file for writing
     use std::io::{Seek, SeekFrom, Write};
     let file = if actual_offset > 0 {
-        // resume: open existing, seek to offset
+        // resume: append after the bytes already on disk
         match std::fs::OpenOptions::new().write(true).open(&file_path) {
             Ok(mut f) => {
-                if f.seek(SeekFrom::Start(actual_offset)).is_err() {
+                if f.set_len(actual_offset).is_err() || f.seek(SeekFrom::End(0)).is_err() {
                     fail(&app, &id, "failed to seek in file");
                     return;
                 }
@@ -585,8 +1017,7 @@ async fn dl_task(
     let mut received = actual_offset;
     let mut last_emit = Instant::now();
     let mut last_manifest = Instant::now();
-    let mut speed_bytes: u64 = 0;
-    let mut speed_start = Instant::now();
+    let mut sampler = SpeedSampler::new(received);
     let mut stream = resp.bytes_stream();
 
     loop {
@@ -600,21 +1031,8 @@ async fn dl_task(
                         }
                         let chunk_len = bytes.len() as u64;
                         received += chunk_len;
-                        speed_bytes += chunk_len;
                         rate_limiter.acquire(chunk_len).await;
-
-                        // calc speed
-                        let elapsed = speed_start.elapsed().as_secs_f64();
-                        let speed = if elapsed >= SPEED_WINDOW_SECS {
-                            let s = (speed_bytes as f64 / elapsed) as u64;
-                            speed_bytes = 0;
-                            speed_start = Instant::now();
-                            s
-                        } else if elapsed > 0.1 {
-                            (speed_bytes as f64 / elapsed) as u64
-                        } else {
-                            0
-                        };
+                        let speed = sampler.sample(received);
 
                         // throttled progress emit
                         if last_emit.elapsed().as_millis() >= PROGRESS_INTERVAL_MS {
@@ -623,7 +1041,8 @@ async fn dl_task(
                             let mut downloads = dm.downloads.lock();
                             if let Some(item) = downloads.get_mut(&id) {
                                 item.received_bytes = received;
-                                item.speed = speed;
+                                item.speed_bps = speed;
+                                item.eta_secs = eta_secs(item.total_bytes, received, speed);
                                 let _ = app.emit_to("main", "download-progress", item.clone());
                             }
                         }
@@ -642,7 +1061,8 @@ async fn dl_task(
                                     total_bytes: item.total_bytes,
                                     received_bytes: received,
                                     supports_range: item.supports_range,
-                                    etag: None,
+                                    etag: item.etag.clone(),
+                                    last_modified: item.last_modified.clone(),
                                     created_at: item.created_at,
                                     segments: Vec::new(),
                                     cookies: cookies.clone(),
@@ -676,7 +1096,8 @@ async fn dl_task(
         if let Some(item) = downloads.get_mut(&id) {
             item.received_bytes = received;
             item.state = DlState::Completed;
-            item.speed = 0;
+            item.speed_bps = 0;
+            item.eta_secs = None;
             item.segments = 0;
             let _ = app.emit_to("main", "download-complete", item.clone());
         }
@@ -774,12 +1195,10 @@ async fn dl_task_chunked(
 
     let mut tick = tokio::time::interval(std::time::Duration::from_millis(500));
     let mut last_manifest_save = Instant::now();
-    let mut speed_bytes: u64 = 0;
-    let mut speed_start = Instant::now();
-    let mut last_total: u64 = {
+    let mut sampler = SpeedSampler::new({
         let segs = seg_state.lock();
         segs.iter().map(|s| s.downloaded).sum()
-    };
+    });
 
     loop {
         tokio::select! {
@@ -822,21 +1241,7 @@ async fn dl_task_chunked(
                     (r, a)
                 };
 
-                // speed calc
-                let delta = received.saturating_sub(last_total);
-                speed_bytes += delta;
-                last_total = received;
-                let elapsed = speed_start.elapsed().as_secs_f64();
-                let speed = if elapsed >= SPEED_WINDOW_SECS {
-                    let s = (speed_bytes as f64 / elapsed) as u64;
-                    speed_bytes = 0;
-                    speed_start = Instant::now();
-                    s
-                } else if elapsed > 0.1 {
-                    (speed_bytes as f64 / elapsed) as u64
-                } else {
-                    0
-                };
+                let speed = sampler.sample(received);
 
                 // emit progress
                 {
@@ -844,7 +1249,8 @@ async fn dl_task_chunked(
                     let mut downloads = dm.downloads.lock();
                     if let Some(item) = downloads.get_mut(&id) {
                         item.received_bytes = received;
-                        item.speed = speed;
+                        item.speed_bps = speed;
+                        item.eta_secs = eta_secs(item.total_bytes, received, speed);
                         item.segments = active_count;
                         let _ = app.emit_to("main", "download-progress", item.clone());
                     }
@@ -865,7 +1271,8 @@ async fn dl_task_chunked(
                             total_bytes: item.total_bytes,
                             received_bytes: received,
                             supports_range: item.supports_range,
-                            etag: None,
+                            etag: item.etag.clone(),
+                            last_modified: item.last_modified.clone(),
                             created_at: item.created_at,
                             segments: segs,
                             cookies: cookies.clone(),
@@ -890,7 +1297,8 @@ async fn dl_task_chunked(
                         total_bytes: item.total_bytes,
                         received_bytes: received,
                         supports_range: item.supports_range,
-                        etag: None,
+                        etag: item.etag.clone(),
+                        last_modified: item.last_modified.clone(),
                         created_at: item.created_at,
                         segments: segs,
                         cookies: cookies.clone(),
@@ -916,7 +1324,8 @@ async fn dl_task_chunked(
         if let Some(item) = downloads.get_mut(&id) {
             item.received_bytes = received;
             item.state = DlState::Completed;
-            item.speed = 0;
+            item.speed_bps = 0;
+            item.eta_secs = None;
             item.segments = 0;
             let _ = app.emit_to("main", "download-complete", item.clone());
         }
@@ -1086,12 +1495,15 @@ fn try_split(seg_state: &Arc<Mutex<Vec<Segment>>>) -> Option<(u32, u64, u64)> {
     Some((new_idx, midpoint + 1, new_end))
 }
 
-pub fn set_priority(app: &AppHandle, id: &str, priority: u32) -> Result<(), String> {
+pub fn set_priority(app: &AppHandle, id: &str, priority: u32) -> Result<(), BushidoError> {
     let dm = app.state::<DownloadManager>();
     let mut downloads = dm.downloads.lock();
-    let item = downloads.get_mut(id).ok_or("not found")?;
+    let item = downloads.get_mut(id).ok_or_else(|| BushidoError::NotFound("not found".into()))?;
     item.priority = priority;
     let _ = app.emit_to("main", "download-progress", item.clone());
+    drop(downloads);
+    // reordering can change which queued download goes next
+    dm.kick_queue();
     Ok(())
 }
 
@@ -1101,7 +1513,8 @@ fn fail(app: &AppHandle, id: &str, error: &str) {
     if let Some(item) = downloads.get_mut(id) {
         item.state = DlState::Failed;
         item.error = Some(error.to_string());
-        item.speed = 0;
+        item.speed_bps = 0;
+        item.eta_secs = None;
         let _ = app.emit_to("main", "download-failed", item.clone());
     }
 }
@@ -0,0 +1,58 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tauri::{AppHandle, DragDropEvent, Emitter, Manager, WebviewEvent};
+
+use crate::error::BushidoError;
+
+/// Whether files dropped on a tab are kept from the page. Shared with every tab's
+/// drop handler, so a change applies to the next drop without recreating webviews.
+pub struct DropState {
+    pub block_default: Arc<AtomicBool>,
+}
+
+impl DropState {
+    pub fn new() -> Self {
+        // the page has never seen drops; keep that unless the UI opts in
+        Self { block_default: Arc::new(AtomicBool::new(true)) }
+    }
+}
+
+/// Forward the tab's drag-and-drop sequence (enter, over, drop, leave) to the UI as
+/// `drag-drop`. Positions are logical and relative to the main window so an overlay
+/// can be drawn over the tab; paths are only sent with the drop itself.
+pub fn attach(app: &AppHandle, tab_id: &str, webview: &tauri::Webview) {
+    let app = app.clone();
+    let tab_id = tab_id.to_string();
+    let wv = webview.clone();
+    webview.on_webview_event(move |event| {
+        let WebviewEvent::DragDrop(event) = event else { return };
+        let (kind, paths, position) = match event {
+            DragDropEvent::Enter { position, .. } => ("enter", None, Some(*position)),
+            DragDropEvent::Over { position } => ("over", None, Some(*position)),
+            DragDropEvent::Drop { paths, position } => ("drop", Some(paths), Some(*position)),
+            DragDropEvent::Leave => ("leave", None, None),
+            _ => return,
+        };
+        let (x, y) = position.map(|p| {
+            let origin = wv.position().unwrap_or_default();
+            let scale = wv.window().scale_factor().unwrap_or(1.0);
+            ((p.x + origin.x as f64) / scale, (p.y + origin.y as f64) / scale)
+        }).unwrap_or_default();
+        let _ = app.emit_to("main", "drag-drop", serde_json::json!({
+            "tabId": tab_id,
+            "type": kind,
+            "paths": paths,
+            "x": x,
+            "y": y,
+            "blocked": app.state::<DropState>().block_default.load(Ordering::Relaxed),
+        }));
+    });
+}
+
+/// Keep dropped files from pages (`block`) so only the UI's drag-drop events see them,
+/// or let pages' own drop targets such as upload areas receive them too
+#[tauri::command]
+pub async fn set_drop_interception(app: AppHandle, block: bool) -> Result<(), BushidoError> {
+    app.state::<DropState>().block_default.store(block, Ordering::Relaxed);
+    Ok(())
+}
@@ -0,0 +1,194 @@
+(function() {
+  if (window.__bushidoPicker) return;
+
+  var STYLE_IDS = ['bushido-cosmetic', 'bushido-cosmetic-early', 'bushido-generic-cosmetic'];
+  var MARK = '__bushido-picker-hidden';
+  var MAX_MARKED = 500;
+  var MAX_ATTR_LEN = 80;
+
+  function post(selector) {
+    if (!(window.chrome && window.chrome.webview)) return;
+    window.chrome.webview.postMessage(JSON.stringify({ __bushido: 'element-picked', selector: selector }));
+  }
+
+  // toggles the blocker's hiding sheets; returns the ones it touched
+  function setFilters(enabled) {
+    var out = [];
+    STYLE_IDS.forEach(function(id) {
+      var s = document.getElementById(id);
+      if (s && s.sheet) { s.sheet.disabled = !enabled; out.push(s); }
+    });
+    return out;
+  }
+
+  function esc(s) {
+    return window.CSS && CSS.escape ? CSS.escape(s) : s.replace(/[^\w-]/g, '\\$&');
+  }
+
+  function uniqueFor(sel, el) {
+    try {
+      var m = document.querySelectorAll(sel);
+      return m.length === 1 && m[0] === el;
+    } catch (e) { return false; }
+  }
+
+  // most stable first: id, then data-* attributes, then tag and classes
+  function candidates(el) {
+    var tag = el.tagName.toLowerCase();
+    var out = [];
+    if (el.id && el.id.length <= MAX_ATTR_LEN) out.push('#' + esc(el.id));
+    for (var i = 0; i < el.attributes.length; i++) {
+      var a = el.attributes[i];
+      if (a.name.indexOf('data-') !== 0 || !a.value || a.value.length > MAX_ATTR_LEN) continue;
+      out.push(tag + '[' + a.name + '="' + a.value.replace(/["\\]/g, '\\$&') + '"]');
+    }
+    var classes = Array.prototype.filter.call(el.classList, function(c) {
+      return c !== MARK && c.length <= MAX_ATTR_LEN;
+    }).slice(0, 3);
+    out.push(tag + classes.map(function(c) { return '.' + esc(c); }).join(''));
+    return out;
+  }
+
+  function nthOfType(el) {
+    var n = 1, same = false;
+    for (var s = el.parentElement && el.parentElement.firstElementChild; s; s = s.nextElementSibling) {
+      if (s === el) continue;
+      if (s.tagName === el.tagName) {
+        same = true;
+        if (s.compareDocumentPosition(el) & Node.DOCUMENT_POSITION_FOLLOWING) n++;
+      }
+    }
+    return same ? ':nth-of-type(' + n + ')' : '';
+  }
+
+  // shortest ancestor chain that matches only `el`; positions only where nothing better does
+  function selectorFor(el) {
+    var rest = '';
+    for (var node = el; node && node.nodeType === 1 && node !== document.documentElement; node = node.parentElement) {
+      var cands = candidates(node);
+      for (var i = 0; i < cands.length; i++) {
+        if (uniqueFor(cands[i] + rest, el)) return cands[i] + rest;
+      }
+      rest = ' > ' + cands[cands.length - 1] + nthOfType(node) + rest;
+      if (uniqueFor(rest.slice(3), el)) return rest.slice(3);
+    }
+    return rest ? rest.slice(3) : null;
+  }
+
+  var active = null;
+
+  function finish(selector) {
+    var a = active;
+    if (!a) return;
+    active = null;
+    document.removeEventListener('mouseover', a.onOver, true);
+    document.removeEventListener('click', a.onClick, true);
+    document.removeEventListener('keydown', a.onKey, true);
+    a.box.remove();
+    a.style.remove();
+    a.marked.forEach(function(el) { el.classList.remove(MARK); });
+    setFilters(true);
+    post(selector);
+  }
+
+  // Shows what the filters hid (outlined) and lets the user click one element.
+  // Reports its selector, or null on Escape; a second start cancels.
+  function start() {
+    if (active) { finish(null); return; }
+    var marked = [];
+    STYLE_IDS.forEach(function(id) {
+      var s = document.getElementById(id);
+      if (!s || !s.sheet) return;
+      Array.prototype.forEach.call(s.sheet.cssRules, function(rule) {
+        if (marked.length >= MAX_MARKED || !rule.selectorText) return;
+        try {
+          Array.prototype.forEach.call(document.querySelectorAll(rule.selectorText), function(el) {
+            if (marked.length < MAX_MARKED && !el.classList.contains(MARK)) { el.classList.add(MARK); marked.push(el); }
+          });
+        } catch (e) {}
+      });
+    });
+    setFilters(false);
+
+    var style = document.createElement('style');
+    style.textContent = '.' + MARK + '{outline:2px dashed rgba(244,63,94,.8)!important;outline-offset:-2px}';
+    (document.head || document.documentElement).appendChild(style);
+    var box = document.createElement('div');
+    box.style.cssText = 'position:fixed;pointer-events:none;z-index:2147483647;background:rgba(99,102,241,.25);' +
+      'outline:2px solid rgb(99,102,241);border-radius:2px;display:none';
+    document.documentElement.appendChild(box);
+
+    var current = null;
+    active = {
+      box: box,
+      style: style,
+      marked: marked,
+      onOver: function(e) {
+        current = e.target;
+        if (!current || current === box || current === document.documentElement) { box.style.display = 'none'; return; }
+        var r = current.getBoundingClientRect();
+        box.style.left = r.left + 'px';
+        box.style.top = r.top + 'px';
+        box.style.width = r.width + 'px';
+        box.style.height = r.height + 'px';
+        box.style.display = 'block';
+      },
+      onClick: function(e) {
+        e.preventDefault();
+        e.stopPropagation();
+        finish(current ? selectorFor(current) : null);
+      },
+      onKey: function(e) {
+        if (e.key !== 'Escape') return;
+        e.preventDefault();
+        e.stopPropagation();
+        finish(null);
+      }
+    };
+    document.addEventListener('mouseover', active.onOver, true);
+    document.addEventListener('click', active.onClick, true);
+    document.addEventListener('keydown', active.onKey, true);
+  }
+
+  // Un-hide rules: the element gets back the display it has without the hiding sheets,
+  // inline and !important so no filter selector can out-rank it. Elements the page
+  // hides by itself stay hidden.
+  var unhideSelectors = [];
+  var pending = false, observer = null;
+
+  function applyUnhide() {
+    pending = false;
+    var found = [];
+    unhideSelectors.forEach(function(sel) {
+      try {
+        Array.prototype.forEach.call(document.querySelectorAll(sel), function(el) {
+          if (!el.__bushidoUnhidden) found.push(el);
+        });
+      } catch (e) {}
+    });
+    if (!found.length) return;
+    // both toggles in one task, so nothing gets painted without the filters
+    var sheets = setFilters(false);
+    var displays = found.map(function(el) { return getComputedStyle(el).display; });
+    sheets.forEach(function(s) { s.sheet.disabled = false; });
+    found.forEach(function(el, i) {
+      el.__bushidoUnhidden = true;
+      if (displays[i] !== 'none') el.style.setProperty('display', displays[i], 'important');
+    });
+  }
+
+  function unhide(selectors) {
+    unhideSelectors = selectors || [];
+    if (!unhideSelectors.length) return;
+    applyUnhide();
+    if (observer) return;
+    observer = new MutationObserver(function() {
+      if (pending) return;
+      pending = true;
+      setTimeout(applyUnhide, 200);
+    });
+    observer.observe(document, { childList: true, subtree: true });
+  }
+
+  window.__bushidoPicker = { start: start, unhide: unhide };
+})();
@@ -0,0 +1,247 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+use parking_lot::Mutex;
+use tauri::{AppHandle, Manager};
+
+use crate::error::BushidoError;
+
+/// Defines `window.__bushidoPicker`; idempotent, so it rides along with every call.
+const PICKER_SCRIPT: &str = include_str!("element_picker.js");
+
+const MAX_SITES: usize = 500;
+const MAX_PER_SITE: usize = 100;
+const MAX_SELECTOR_LEN: usize = 1024;
+
+/// Elements the user brought back from cosmetic filtering, as selectors per domain
+pub struct CosmeticExceptionState {
+    /// domain → selectors in the order they were added, persisted in cosmetic_exceptions.json
+    sites: Mutex<HashMap<String, Vec<String>>>,
+    /// tabs with a picker open; an `element-picked` from any other tab is the page's own
+    picking: Mutex<HashSet<String>>,
+}
+
+fn sites_path(app: &AppHandle) -> PathBuf {
+    crate::data_dir(app).join("cosmetic_exceptions.json")
+}
+
+fn save_sites(app: &AppHandle, sites: &HashMap<String, Vec<String>>) {
+    if let Ok(json) = serde_json::to_string(sites) {
+        let _ = fs::write(sites_path(app), json);
+    }
+}
+
+fn normalize_domain(domain: &str) -> Result<String, BushidoError> {
+    let domain = domain.trim().trim_start_matches("www.").to_lowercase();
+    if domain.is_empty() { return Err(BushidoError::Other("empty domain".into())); }
+    Ok(domain)
+}
+
+/// Selectors only ever reach querySelectorAll as JSON strings; a bad one just
+/// matches nothing, so this is about keeping the file sane.
+fn valid_selector(selector: &str) -> Result<String, BushidoError> {
+    let selector = selector.trim();
+    if selector.is_empty() {
+        return Err(BushidoError::Other("empty selector".into()));
+    }
+    if selector.len() > MAX_SELECTOR_LEN {
+        return Err(BushidoError::Limit(format!("selectors are limited to {} characters", MAX_SELECTOR_LEN)));
+    }
+    if selector.chars().any(|c| c.is_control() || c == '{' || c == '}') {
+        return Err(BushidoError::Other(format!("invalid selector: {}", selector)));
+    }
+    Ok(selector.to_string())
+}
+
+fn unhide_script(selectors: &[String]) -> String {
+    format!(
+        "{}\nwindow.__bushidoPicker.unhide({});",
+        PICKER_SCRIPT,
+        serde_json::to_string(selectors).unwrap_or_else(|_| "[]".into()),
+    )
+}
+
+impl CosmeticExceptionState {
+    pub fn load(app: &AppHandle) -> Self {
+        let sites: HashMap<String, Vec<String>> = fs::read_to_string(sites_path(app)).ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default();
+        Self { sites: Mutex::new(sites), picking: Mutex::new(HashSet::new()) }
+    }
+
+    /// The tab's picker reported back (a pick or a cancel). False when no picker was
+    /// open, so the message didn't come from one we started.
+    pub fn finish_pick(&self, tab_id: &str) -> bool {
+        self.picking.lock().remove(tab_id)
+    }
+
+    /// Navigation or closing the tab takes the picker with it
+    pub fn forget_tab(&self, tab_id: &str) {
+        self.picking.lock().remove(tab_id);
+    }
+
+    /// Every selector from domains matching the URL's host
+    fn selectors_for_url(&self, url: &str) -> Vec<String> {
+        let Some(host) = url::Url::parse(url).ok().and_then(|u| u.host_str().map(str::to_lowercase)) else {
+            return Vec::new();
+        };
+        let sites = self.sites.lock();
+        let mut out: Vec<String> = Vec::new();
+        for (_, selectors) in sites.iter().filter(|(d, _)| crate::domain_matches(&host, d)) {
+            for s in selectors {
+                if !out.contains(s) {
+                    out.push(s.clone());
+                }
+            }
+        }
+        out
+    }
+
+    /// Script for on_page_load, next to the cosmetic filters; None when nothing is un-hidden here
+    pub fn script_for_url(&self, url: &str) -> Option<String> {
+        let selectors = self.selectors_for_url(url);
+        (!selectors.is_empty()).then(|| unhide_script(&selectors))
+    }
+
+    /// Whether the selector is new for the domain
+    fn add(&self, domain: &str, selector: String) -> Result<bool, BushidoError> {
+        let mut sites = self.sites.lock();
+        if !sites.contains_key(domain) && sites.len() >= MAX_SITES {
+            return Err(BushidoError::Limit(format!("at most {} sites with un-hidden elements", MAX_SITES)));
+        }
+        let list = sites.entry(domain.to_string()).or_default();
+        if list.contains(&selector) {
+            return Ok(false);
+        }
+        if list.len() >= MAX_PER_SITE {
+            return Err(BushidoError::Limit(format!("at most {} un-hidden elements per site", MAX_PER_SITE)));
+        }
+        list.push(selector);
+        Ok(true)
+    }
+
+    /// Whether the domain had the selector
+    fn remove(&self, domain: &str, selector: &str) -> bool {
+        let mut sites = self.sites.lock();
+        let Some(list) = sites.get_mut(domain) else { return false };
+        let before = list.len();
+        list.retain(|s| s != selector);
+        let removed = list.len() != before;
+        if list.is_empty() {
+            sites.remove(domain);
+        }
+        removed
+    }
+}
+
+/// Let the user click an element on the page, showing filter-hidden ones while
+/// picking. The page answers with an `element-picked` message (selector or null
+/// when cancelled); calling again while picking cancels.
+#[tauri::command]
+pub async fn start_element_picker(app: AppHandle, id: String) -> Result<(), BushidoError> {
+    let wv = app.get_webview(&id).ok_or_else(|| BushidoError::NotFound(format!("tab {}", id)))?;
+    wv.eval(&format!("{}\nwindow.__bushidoPicker.start();", PICKER_SCRIPT))?;
+    // a second call cancels, which the page reports like any other answer
+    app.state::<CosmeticExceptionState>().picking.lock().insert(id);
+    // Escape has to reach the page, not the shell UI that invoked us
+    let _ = wv.set_focus();
+    Ok(())
+}
+
+/// Keep `selector` visible on `domain` from now on, applied to open tabs right away
+#[tauri::command]
+pub async fn add_cosmetic_exception(app: AppHandle, domain: String, selector: String) -> Result<(), BushidoError> {
+    let domain = normalize_domain(&domain)?;
+    let selector = valid_selector(&selector)?;
+    let ces = app.state::<CosmeticExceptionState>();
+    if !ces.add(&domain, selector)? {
+        return Ok(());
+    }
+    let snapshot = ces.sites.lock().clone();
+    save_sites(&app, &snapshot);
+
+    let tabs: Vec<String> = app.state::<crate::WebviewState>().tabs.lock().keys().cloned().collect();
+    for id in tabs {
+        let Some(wv) = app.get_webview(&id) else { continue };
+        let Ok(url) = wv.url() else { continue };
+        if let Some(js) = ces.script_for_url(url.as_str()) {
+            let _ = wv.eval(&js);
+        }
+    }
+    Ok(())
+}
+
+/// Selectors stored for exactly this domain
+#[tauri::command]
+pub async fn get_cosmetic_exceptions(app: AppHandle, domain: String) -> Result<Vec<String>, BushidoError> {
+    let domain = normalize_domain(&domain)?;
+    Ok(app.state::<CosmeticExceptionState>().sites.lock().get(&domain).cloned().unwrap_or_default())
+}
+
+/// Hidden again from the next page load on
+#[tauri::command]
+pub async fn remove_cosmetic_exception(app: AppHandle, domain: String, selector: String) -> Result<(), BushidoError> {
+    let domain = normalize_domain(&domain)?;
+    let ces = app.state::<CosmeticExceptionState>();
+    if ces.remove(&domain, selector.trim()) {
+        let snapshot = ces.sites.lock().clone();
+        save_sites(&app, &snapshot);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state() -> CosmeticExceptionState {
+        CosmeticExceptionState { sites: Mutex::new(HashMap::new()), picking: Mutex::new(HashSet::new()) }
+    }
+
+    #[test]
+    fn picks_only_count_inside_a_session() {
+        let s = state();
+        assert!(!s.finish_pick("t"));
+        s.picking.lock().insert("t".into());
+        assert!(!s.finish_pick("other"));
+        assert!(s.finish_pick("t"));
+        // one answer per session
+        assert!(!s.finish_pick("t"));
+        s.picking.lock().insert("t".into());
+        s.forget_tab("t");
+        assert!(!s.finish_pick("t"));
+    }
+
+    #[test]
+    fn matches_subdomains_without_duplicates() {
+        let s = state();
+        assert!(s.add("example.com", "#promo".into()).unwrap());
+        assert!(!s.add("example.com", "#promo".into()).unwrap());
+        s.add("news.example.com", "#promo".into()).unwrap();
+        s.add("news.example.com", "div[data-slot=\"top\"]".into()).unwrap();
+        s.add("other.example", ".x".into()).unwrap();
+
+        let mut sel = s.selectors_for_url("https://news.example.com/a");
+        sel.sort();
+        assert_eq!(sel, vec!["#promo".to_string(), "div[data-slot=\"top\"]".to_string()]);
+        assert_eq!(s.selectors_for_url("https://example.com/"), vec!["#promo".to_string()]);
+        assert!(s.script_for_url("https://unrelated.example/").is_none());
+        assert!(s.script_for_url("not a url").is_none());
+
+        assert!(s.remove("example.com", "#promo"));
+        assert!(!s.remove("example.com", "#promo"));
+        assert!(!s.sites.lock().contains_key("example.com"));
+    }
+
+    #[test]
+    fn selectors_are_checked_and_embedded_as_json() {
+        assert!(valid_selector("  ").is_err());
+        assert!(valid_selector("a{}").is_err());
+        assert!(valid_selector("a\nb").is_err());
+        assert!(valid_selector(&"a".repeat(MAX_SELECTOR_LEN + 1)).is_err());
+        assert_eq!(valid_selector(" #ad ").unwrap(), "#ad");
+
+        let js = unhide_script(&["a[title=\"x\"]');alert(1);('".into()]);
+        assert!(js.ends_with(r#"window.__bushidoPicker.unhide(["a[title=\"x\"]');alert(1);('"]);"#));
+    }
+}
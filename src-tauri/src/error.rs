@@ -0,0 +1,87 @@
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+/// Typed error for Tauri commands.
+/// Serializes as `{ kind, message }` — the UI can branch on `kind` (e.g. show the unlock
+/// prompt on "Locked"), while existing `e?.message || e` handlers keep working unchanged.
+#[derive(Debug, thiserror::Error)]
+pub enum BushidoError {
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+    #[error("{0}")]
+    NotFound(String),
+    #[error("vault locked")]
+    Locked,
+    #[error("{0}")]
+    Sync(String),
+    #[error("{0}")]
+    Permission(String),
+    #[error("{0}")]
+    WebView(String),
+    #[error("{0}")]
+    Limit(String),
+    #[error("{0}")]
+    Other(String),
+}
+
+impl BushidoError {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            BushidoError::Io(_) => "Io",
+            BushidoError::NotFound(_) => "NotFound",
+            BushidoError::Locked => "Locked",
+            BushidoError::Sync(_) => "Sync",
+            BushidoError::Permission(_) => "Permission",
+            BushidoError::WebView(_) => "WebView",
+            BushidoError::Limit(_) => "Limit",
+            BushidoError::Other(_) => "Other",
+        }
+    }
+}
+
+impl Serialize for BushidoError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut s = serializer.serialize_struct("BushidoError", 2)?;
+        s.serialize_field("kind", self.kind())?;
+        s.serialize_field("message", &self.to_string())?;
+        s.end()
+    }
+}
+
+// legacy helpers still return Result<_, String> — `?` lifts them into Other
+impl From<String> for BushidoError {
+    fn from(s: String) -> Self { BushidoError::Other(s) }
+}
+
+impl From<&str> for BushidoError {
+    fn from(s: &str) -> Self { BushidoError::Other(s.to_string()) }
+}
+
+impl From<tauri::Error> for BushidoError {
+    fn from(e: tauri::Error) -> Self { BushidoError::WebView(e.to_string()) }
+}
+
+impl From<url::ParseError> for BushidoError {
+    fn from(e: url::ParseError) -> Self { BushidoError::Other(e.to_string()) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serializes_kind_and_message() {
+        let v = serde_json::to_value(BushidoError::Locked).unwrap();
+        assert_eq!(v, serde_json::json!({ "kind": "Locked", "message": "vault locked" }));
+
+        let v = serde_json::to_value(BushidoError::NotFound("not found".into())).unwrap();
+        assert_eq!(v["kind"], "NotFound");
+        assert_eq!(v["message"], "not found");
+    }
+
+    #[test]
+    fn legacy_string_errors_become_other() {
+        let e: BushidoError = String::from("db open: busy").into();
+        assert_eq!(e.kind(), "Other");
+        assert_eq!(e.to_string(), "db open: busy");
+    }
+}
@@ -0,0 +1,196 @@
+(function() {
+  if (window.__bushidoFind) return;
+
+  var MARK = '__bushido-find';
+  var CURRENT = '__bushido-find-current';
+  // past this the page is mostly spans; the count stays exact up to here
+  var MAX_MATCHES = 5000;
+  var SKIP = { SCRIPT: 1, STYLE: 1, NOSCRIPT: 1, TEMPLATE: 1, TEXTAREA: 1, INPUT: 1, SELECT: 1, OPTION: 1 };
+
+  var query = '';
+  // marks[i] = the spans making up match i; overlapping matches share spans
+  var marks = [];
+  var current = -1;
+
+  function post(total, cur) {
+    if (window.chrome && window.chrome.webview) {
+      window.chrome.webview.postMessage(JSON.stringify({ __bushido: 'match-count', count: total, current: cur }));
+    }
+  }
+
+  function ensureStyle() {
+    if (document.getElementById(MARK + '-style')) return;
+    var s = document.createElement('style');
+    s.id = MARK + '-style';
+    s.textContent = '.' + MARK + '{background:#ffe066!important;color:#000!important;border-radius:2px}' +
+      '.' + CURRENT + '{background:#ff9632!important}';
+    (document.head || document.documentElement).appendChild(s);
+  }
+
+  // one lowercase code unit per input code unit, so offsets in the folded
+  // string are offsets in the page text ('İ' lowercases to two units; kept as-is)
+  function fold(s) {
+    var out = '';
+    for (var i = 0; i < s.length; i++) {
+      var c = s[i], l = c.toLowerCase();
+      out += l.length === 1 ? l : c;
+    }
+    return out;
+  }
+
+  function visible(el, cache) {
+    var v = cache.get(el);
+    if (v !== undefined) return v;
+    if (el.checkVisibility) v = el.checkVisibility({ checkVisibilityCSS: true });
+    else {
+      var st = getComputedStyle(el);
+      v = st.display !== 'none' && st.visibility !== 'hidden' && el.getClientRects().length > 0;
+    }
+    cache.set(el, v);
+    return v;
+  }
+
+  function textNodes() {
+    var cache = new Map();
+    var walker = document.createTreeWalker(document.body || document.documentElement, NodeFilter.SHOW_TEXT, {
+      acceptNode: function(n) {
+        var p = n.parentElement;
+        if (!p || !n.nodeValue || SKIP[p.tagName]) return NodeFilter.FILTER_REJECT;
+        return visible(p, cache) ? NodeFilter.FILTER_ACCEPT : NodeFilter.FILTER_REJECT;
+      }
+    });
+    var out = [];
+    for (var n = walker.nextNode(); n; n = walker.nextNode()) out.push(n);
+    return out;
+  }
+
+  function clear() {
+    var spans = document.querySelectorAll('span.' + MARK);
+    var parents = new Set();
+    for (var i = 0; i < spans.length; i++) {
+      var s = spans[i], p = s.parentNode;
+      if (!p) continue;
+      while (s.firstChild) p.insertBefore(s.firstChild, s);
+      p.removeChild(s);
+      parents.add(p);
+    }
+    parents.forEach(function(p) { p.normalize(); });
+    marks = [];
+    current = -1;
+  }
+
+  function highlight(q) {
+    var nodes = textNodes();
+    var starts = [], text = '';
+    for (var i = 0; i < nodes.length; i++) {
+      starts.push(text.length);
+      text += nodes[i].nodeValue;
+    }
+    var hay = fold(text), needle = fold(q), len = q.length;
+
+    // every start position, stepping by one so "aa" in "aaa" is two matches
+    var found = [];
+    for (var at = hay.indexOf(needle); at !== -1 && found.length < MAX_MATCHES; at = hay.indexOf(needle, at + 1)) {
+      found.push(at);
+    }
+    if (!found.length) return;
+
+    // cut the text at every match edge; each piece between cuts is covered by a
+    // contiguous run of matches, so one span per piece serves all of them
+    var cuts = [];
+    found.forEach(function(s) { cuts.push(s, s + len); });
+    cuts.sort(function(a, b) { return a - b; });
+    var pieces = [], first = 0;
+    for (var c = 0; c + 1 < cuts.length; c++) {
+      var a = cuts[c], b = cuts[c + 1];
+      if (a === b) continue;
+      while (first < found.length && found[first] + len <= a) first++;
+      if (first >= found.length || found[first] > a) continue;
+      var last = first;
+      while (last + 1 < found.length && found[last + 1] <= a) last++;
+      pieces.push({ start: a, end: b, from: first, to: last });
+    }
+
+    marks = found.map(function() { return []; });
+    // a piece may cross text nodes; split per node, last first, so earlier
+    // offsets in the same node stay valid
+    var byNode = new Map(), k0 = 0;
+    pieces.forEach(function(p) {
+      while (k0 < nodes.length && starts[k0] + nodes[k0].nodeValue.length <= p.start) k0++;
+      for (var k = k0; k < nodes.length; k++) {
+        var ns = starts[k], ne = ns + nodes[k].nodeValue.length;
+        if (ne <= p.start) continue;
+        if (ns >= p.end) break;
+        var list = byNode.get(k) || [];
+        list.push({ a: Math.max(p.start, ns) - ns, b: Math.min(p.end, ne) - ns, piece: p });
+        byNode.set(k, list);
+      }
+    });
+    var spansByPiece = new Map();
+    byNode.forEach(function(list, k) {
+      var node = nodes[k];
+      for (var j = list.length - 1; j >= 0; j--) {
+        var seg = list[j];
+        var mid = seg.a > 0 ? node.splitText(seg.a) : node;
+        if (seg.b - seg.a < mid.nodeValue.length) mid.splitText(seg.b - seg.a);
+        var span = document.createElement('span');
+        span.className = MARK;
+        mid.parentNode.insertBefore(span, mid);
+        span.appendChild(mid);
+        var arr = spansByPiece.get(seg.piece) || [];
+        arr.push(span);
+        spansByPiece.set(seg.piece, arr);
+      }
+    });
+    // nodes are visited in document order, so each piece's spans already are
+    pieces.forEach(function(p) {
+      var spans = spansByPiece.get(p) || [];
+      for (var m = p.from; m <= p.to; m++) marks[m] = marks[m].concat(spans);
+    });
+  }
+
+  function select(i) {
+    if (current >= 0 && marks[current]) {
+      marks[current].forEach(function(s) { s.classList.remove(CURRENT); });
+    }
+    current = i;
+    var spans = marks[i];
+    spans.forEach(function(s) { s.classList.add(CURRENT); });
+    if (spans[0]) spans[0].scrollIntoView({ block: 'center', inline: 'nearest' });
+  }
+
+  // like the browser's own find: start from the first match in or below the viewport
+  function firstInView() {
+    for (var i = 0; i < marks.length; i++) {
+      var s = marks[i][0];
+      if (s && s.getBoundingClientRect().bottom >= 0) return i;
+    }
+    return 0;
+  }
+
+  function stale() {
+    return marks.length > 0 && !marks.every(function(m) { return m.length && m[0].isConnected; });
+  }
+
+  window.__bushidoFind = {
+    run: function(q, forward) {
+      if (q !== query || stale()) {
+        clear();
+        query = q;
+        if (!q) { post(0, 0); return; }
+        ensureStyle();
+        highlight(q);
+        if (!marks.length) { post(0, 0); return; }
+        select(firstInView());
+      } else if (marks.length) {
+        var n = marks.length;
+        select(((forward ? current + 1 : current - 1) % n + n) % n);
+      }
+      post(marks.length, marks.length ? current + 1 : 0);
+    },
+    clear: function() {
+      clear();
+      query = '';
+    }
+  };
+})();
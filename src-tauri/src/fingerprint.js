@@ -141,12 +141,31 @@
     } catch(e) {}
 
     // ── spoofed values ──────────────────────────────────────────────────────
-    var spoofedUA = 'Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/131.0.0.0 Safari/537.36';
+    // window.__bushidoFp (FingerprintConfig) switches categories off; create_tab builds
+    // Accept-Language from the same languages so header and navigator agree
+    var fp = window.__bushidoFp || {};
+    function fpOn(key) { return fp[key] !== false; }
+    // the UA the webview was built with (site override, desktop or mobile). A per-site
+    // switch after that changes the native navigator.userAgent too, so that wins unless
+    // it's WebView2's own Edge string
+    var nativeUA = navigator.userAgent || '';
+    var spoofedUA = (nativeUA && nativeUA.indexOf('Edg/') < 0) ? nativeUA
+        : (fp.userAgent || 'Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/131.0.0.0 Safari/537.36');
+    var uaMobile = /Mobile|Android|iPhone|iPad/.test(spoofedUA);
+    var uaOS = /Windows/.test(spoofedUA) ? 'Windows'
+        : /Android/.test(spoofedUA) ? 'Android'
+        : /iPhone|iPad/.test(spoofedUA) ? 'iOS'
+        : /Mac OS X|Macintosh/.test(spoofedUA) ? 'macOS'
+        : /Linux|X11|CrOS/.test(spoofedUA) ? 'Linux' : 'Windows';
+    var spoofedPlatform = { Windows: 'Win32', Android: 'Linux armv8l', iOS: /iPad/.test(spoofedUA) ? 'iPad' : 'iPhone', macOS: 'MacIntel', Linux: 'Linux x86_64' }[uaOS];
+    var spoofedAppVersion = spoofedUA.replace(/^Mozilla\//, '');
+    // Chromium version for userAgentData; other engines don't have that API at all
+    var uaChrome = /Firefox\//.test(spoofedUA) ? null : (spoofedUA.match(/(?:Chrome|CriOS)\/(\d+)([\d.]*)/) || null);
     var spoofedHWC = 8;
     var spoofedDM = 8;
-    var spoofedMTP = 0;
-    var spoofedPlatform = 'Win32';
-    var spoofedLangs = ['en-US', 'en'];
+    var spoofedMTP = uaMobile ? 5 : 0;
+    var spoofedLangs = (fp.languages && fp.languages.length) ? fp.languages : ['en-US', 'en'];
+    var spoofedLocale = spoofedLangs[0];
 
     // ── navigator props ─────────────────────────────────────────────────────
     var _spoofedProps = [];
@@ -157,8 +176,19 @@
         } catch(e) {}
     }
 
-    spoofProp(navigator, 'doNotTrack', '1');
-    spoofProp(navigator, 'globalPrivacyControl', true);
+    // DNT / GPC follow the same global switch + per-site exemptions as the request headers
+    var signalsOn = (function() {
+        var cfg = window.__bushidoSignals;
+        if (!cfg) return true;
+        if (!cfg.enabled) return false;
+        var host = domain.toLowerCase();
+        return !(cfg.exempt || []).some(function(d) { return host === d || host.endsWith('.' + d); });
+    })();
+    Object.defineProperty(window, '__bushidoSignalsOn', { value: signalsOn, writable: false, configurable: false });
+    if (signalsOn) {
+        spoofProp(navigator, 'doNotTrack', '1');
+        spoofProp(navigator, 'globalPrivacyControl', true);
+    }
     try {
         var fakePlugin = function(name, desc, fname) {
             return { name: name, description: desc, filename: fname, length: 1, item: function() { return null; }, namedItem: function() { return null; } };
@@ -180,7 +210,7 @@
         Object.defineProperty(navigator, 'mimeTypes', { get: function() { return fakeMimes; }, configurable: false });
     } catch(e) {}
     try { if (navigator.getBattery) navigator.getBattery = undefined; } catch(e) {}
-    spoofProp(navigator, 'language', 'en-US');
+    spoofProp(navigator, 'language', spoofedLocale);
     spoofProp(navigator, 'languages', spoofedLangs);
     spoofProp(navigator, 'platform', spoofedPlatform);
     spoofProp(navigator, 'connection', undefined);
@@ -191,23 +221,25 @@
     spoofProp(navigator, 'maxTouchPoints', spoofedMTP);
     spoofProp(navigator, 'pdfViewerEnabled', true);
     spoofProp(navigator, 'cookieEnabled', true);
-    spoofProp(navigator, 'hardwareConcurrency', spoofedHWC);
+    if (fpOn('hardwareConcurrency')) spoofProp(navigator, 'hardwareConcurrency', spoofedHWC);
     spoofProp(navigator, 'vendor', 'Google Inc.');
-    spoofProp(navigator, 'appVersion', '5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/131.0.0.0 Safari/537.36');
+    spoofProp(navigator, 'appVersion', spoofedAppVersion);
     spoofProp(navigator, 'userAgent', spoofedUA);
     spoofProp(navigator, 'productSub', '20030107');
     spoofProp(navigator, 'vendorSub', '');
 
     // screen normalization
-    spoofProp(screen, 'width', 1920);
-    spoofProp(screen, 'height', 1080);
-    spoofProp(screen, 'availWidth', 1920);
-    spoofProp(screen, 'availHeight', 1040);
-    spoofProp(screen, 'availLeft', 0);
-    spoofProp(screen, 'availTop', 0);
-    spoofProp(screen, 'colorDepth', 24);
-    spoofProp(screen, 'pixelDepth', 24);
-    spoofProp(window, 'devicePixelRatio', 1);
+    if (fpOn('screen')) {
+        spoofProp(screen, 'width', 1920);
+        spoofProp(screen, 'height', 1080);
+        spoofProp(screen, 'availWidth', 1920);
+        spoofProp(screen, 'availHeight', 1040);
+        spoofProp(screen, 'availLeft', 0);
+        spoofProp(screen, 'availTop', 0);
+        spoofProp(screen, 'colorDepth', 24);
+        spoofProp(screen, 'pixelDepth', 24);
+        spoofProp(window, 'devicePixelRatio', 1);
+    }
 
     // ── Object.getOwnPropertyDescriptor hardening ───────────────────────────
     try {
@@ -231,197 +263,203 @@
     } catch(e) {}
 
     // ── canvas fingerprint noise ────────────────────────────────────────────
-    try {
-        var origToDataURL = HTMLCanvasElement.prototype.toDataURL;
-        var origToBlob = HTMLCanvasElement.prototype.toBlob;
-        var origGetCtx = HTMLCanvasElement.prototype.getContext;
-        var addNoise = function(canvas) {
-            try {
-                var ctx = origGetCtx.call(canvas, '2d');
-                if (!ctx) return;
-                var w = canvas.width, h = canvas.height;
-                if (w === 0 || h === 0 || w > 4096 || h > 4096) return;
-                var img = ctx.getImageData(0, 0, w, h);
-                var d = img.data;
-                for (var i = 0; i < d.length; i += 4) {
-                    var r = rng();
-                    d[i] = d[i] ^ (r > 0.5 ? 1 : 0);
-                    d[i+1] = d[i+1] ^ (r > 0.75 ? 1 : 0);
-                }
-                ctx.putImageData(img, 0, 0);
-            } catch(e) {}
-        };
-        HTMLCanvasElement.prototype.toDataURL = function() {
-            addNoise(this);
-            return origToDataURL.apply(this, arguments);
-        };
-        HTMLCanvasElement.prototype.toBlob = function() {
-            addNoise(this);
-            return origToBlob.apply(this, arguments);
-        };
-        harden(HTMLCanvasElement.prototype, 'toDataURL');
-        harden(HTMLCanvasElement.prototype, 'toBlob');
-    } catch(e) {}
+    if (fpOn('canvasNoise')) {
+        try {
+            var origToDataURL = HTMLCanvasElement.prototype.toDataURL;
+            var origToBlob = HTMLCanvasElement.prototype.toBlob;
+            var origGetCtx = HTMLCanvasElement.prototype.getContext;
+            var addNoise = function(canvas) {
+                try {
+                    var ctx = origGetCtx.call(canvas, '2d');
+                    if (!ctx) return;
+                    var w = canvas.width, h = canvas.height;
+                    if (w === 0 || h === 0 || w > 4096 || h > 4096) return;
+                    var img = ctx.getImageData(0, 0, w, h);
+                    var d = img.data;
+                    for (var i = 0; i < d.length; i += 4) {
+                        var r = rng();
+                        d[i] = d[i] ^ (r > 0.5 ? 1 : 0);
+                        d[i+1] = d[i+1] ^ (r > 0.75 ? 1 : 0);
+                    }
+                    ctx.putImageData(img, 0, 0);
+                } catch(e) {}
+            };
+            HTMLCanvasElement.prototype.toDataURL = function() {
+                addNoise(this);
+                return origToDataURL.apply(this, arguments);
+            };
+            HTMLCanvasElement.prototype.toBlob = function() {
+                addNoise(this);
+                return origToBlob.apply(this, arguments);
+            };
+            harden(HTMLCanvasElement.prototype, 'toDataURL');
+            harden(HTMLCanvasElement.prototype, 'toBlob');
+        } catch(e) {}
 
-    // ── FIX #6: OffscreenCanvas noise on main thread (research/07 FP-Scanner) ──
-    // FP-Scanner checks Canvas vs OffscreenCanvas hash consistency.
-    // Worker OffscreenCanvas is patched in the worker preamble; main thread was missing.
-    try {
-        if (typeof OffscreenCanvas !== 'undefined') {
-            var origOCConvertToBlob = OffscreenCanvas.prototype.convertToBlob;
-            if (origOCConvertToBlob) {
-                OffscreenCanvas.prototype.convertToBlob = function() {
-                    try {
-                        var ctx = this.getContext('2d');
-                        if (ctx) {
-                            var w = this.width, h = this.height;
-                            if (w > 0 && h > 0 && w <= 4096 && h <= 4096) {
-                                var img = ctx.getImageData(0, 0, w, h);
-                                var d = img.data;
-                                for (var i = 0; i < d.length; i += 4) {
-                                    d[i] ^= (rng() > 0.5 ? 1 : 0);
-                                    d[i+1] ^= (rng() > 0.75 ? 1 : 0);
+        // ── FIX #6: OffscreenCanvas noise on main thread (research/07 FP-Scanner) ──
+        // FP-Scanner checks Canvas vs OffscreenCanvas hash consistency.
+        // Worker OffscreenCanvas is patched in the worker preamble; main thread was missing.
+        try {
+            if (typeof OffscreenCanvas !== 'undefined') {
+                var origOCConvertToBlob = OffscreenCanvas.prototype.convertToBlob;
+                if (origOCConvertToBlob) {
+                    OffscreenCanvas.prototype.convertToBlob = function() {
+                        try {
+                            var ctx = this.getContext('2d');
+                            if (ctx) {
+                                var w = this.width, h = this.height;
+                                if (w > 0 && h > 0 && w <= 4096 && h <= 4096) {
+                                    var img = ctx.getImageData(0, 0, w, h);
+                                    var d = img.data;
+                                    for (var i = 0; i < d.length; i += 4) {
+                                        d[i] ^= (rng() > 0.5 ? 1 : 0);
+                                        d[i+1] ^= (rng() > 0.75 ? 1 : 0);
+                                    }
+                                    ctx.putImageData(img, 0, 0);
                                 }
-                                ctx.putImageData(img, 0, 0);
                             }
-                        }
-                    } catch(e2) {}
-                    return origOCConvertToBlob.apply(this, arguments);
-                };
-                harden(OffscreenCanvas.prototype, 'convertToBlob');
+                        } catch(e2) {}
+                        return origOCConvertToBlob.apply(this, arguments);
+                    };
+                    harden(OffscreenCanvas.prototype, 'convertToBlob');
+                }
             }
-        }
-    } catch(e) {}
+        } catch(e) {}
+    }
 
     // ── FIX #7: Expanded GPU pool (research/05 entropy, research/07 FP-Scanner) ──
     // 4 GPUs = 2 bits entropy. 16 GPUs = 4 bits. Harder to cluster users.
     // Added AMD Radeon, Intel Iris, and more UHD variants with matching params.
-    try {
-        var gpuPool = [
-            // Intel UHD series (most common on laptops)
-            ['Google Inc. (Intel)', 'ANGLE (Intel, Intel(R) UHD Graphics 620 Direct3D11 vs_5_0 ps_5_0, D3D11)', 'intel'],
-            ['Google Inc. (Intel)', 'ANGLE (Intel, Intel(R) UHD Graphics 630 Direct3D11 vs_5_0 ps_5_0, D3D11)', 'intel'],
-            ['Google Inc. (Intel)', 'ANGLE (Intel, Intel(R) UHD Graphics Direct3D11 vs_5_0 ps_5_0, D3D11)', 'intel'],
-            ['Google Inc. (Intel)', 'ANGLE (Intel, Intel(R) UHD Graphics 770 Direct3D11 vs_5_0 ps_5_0, D3D11)', 'intel'],
-            // Intel HD series (older but still common)
-            ['Google Inc. (Intel)', 'ANGLE (Intel, Intel(R) HD Graphics 620 Direct3D11 vs_5_0 ps_5_0, D3D11)', 'intel'],
-            ['Google Inc. (Intel)', 'ANGLE (Intel, Intel(R) HD Graphics 630 Direct3D11 vs_5_0 ps_5_0, D3D11)', 'intel'],
-            ['Google Inc. (Intel)', 'ANGLE (Intel, Intel(R) HD Graphics 530 Direct3D11 vs_5_0 ps_5_0, D3D11)', 'intel'],
-            // Intel Iris (higher-end laptops)
-            ['Google Inc. (Intel)', 'ANGLE (Intel, Intel(R) Iris(R) Xe Graphics Direct3D11 vs_5_0 ps_5_0, D3D11)', 'intel'],
-            ['Google Inc. (Intel)', 'ANGLE (Intel, Intel(R) Iris(R) Plus Graphics Direct3D11 vs_5_0 ps_5_0, D3D11)', 'intel'],
-            ['Google Inc. (Intel)', 'ANGLE (Intel, Intel(R) Iris(R) Graphics 6100 Direct3D11 vs_5_0 ps_5_0, D3D11)', 'intel'],
-            // AMD Radeon integrated (common on AMD laptops/desktops)
-            ['Google Inc. (AMD)', 'ANGLE (AMD, AMD Radeon(TM) Graphics Direct3D11 vs_5_0 ps_5_0, D3D11)', 'amd'],
-            ['Google Inc. (AMD)', 'ANGLE (AMD, AMD Radeon RX 580 Direct3D11 vs_5_0 ps_5_0, D3D11)', 'amd'],
-            ['Google Inc. (AMD)', 'ANGLE (AMD, AMD Radeon RX Vega 8 Direct3D11 vs_5_0 ps_5_0, D3D11)', 'amd'],
-            ['Google Inc. (AMD)', 'ANGLE (AMD, AMD Radeon(TM) Vega 8 Graphics Direct3D11 vs_5_0 ps_5_0, D3D11)', 'amd'],
-            // NVIDIA (common discrete GPUs)
-            ['Google Inc. (NVIDIA)', 'ANGLE (NVIDIA, NVIDIA GeForce GTX 1650 Direct3D11 vs_5_0 ps_5_0, D3D11)', 'nvidia'],
-            ['Google Inc. (NVIDIA)', 'ANGLE (NVIDIA, NVIDIA GeForce RTX 3060 Direct3D11 vs_5_0 ps_5_0, D3D11)', 'nvidia'],
-        ];
+    if (fpOn('webglVendor')) {
+        try {
+            var gpuPool = [
+                // Intel UHD series (most common on laptops)
+                ['Google Inc. (Intel)', 'ANGLE (Intel, Intel(R) UHD Graphics 620 Direct3D11 vs_5_0 ps_5_0, D3D11)', 'intel'],
+                ['Google Inc. (Intel)', 'ANGLE (Intel, Intel(R) UHD Graphics 630 Direct3D11 vs_5_0 ps_5_0, D3D11)', 'intel'],
+                ['Google Inc. (Intel)', 'ANGLE (Intel, Intel(R) UHD Graphics Direct3D11 vs_5_0 ps_5_0, D3D11)', 'intel'],
+                ['Google Inc. (Intel)', 'ANGLE (Intel, Intel(R) UHD Graphics 770 Direct3D11 vs_5_0 ps_5_0, D3D11)', 'intel'],
+                // Intel HD series (older but still common)
+                ['Google Inc. (Intel)', 'ANGLE (Intel, Intel(R) HD Graphics 620 Direct3D11 vs_5_0 ps_5_0, D3D11)', 'intel'],
+                ['Google Inc. (Intel)', 'ANGLE (Intel, Intel(R) HD Graphics 630 Direct3D11 vs_5_0 ps_5_0, D3D11)', 'intel'],
+                ['Google Inc. (Intel)', 'ANGLE (Intel, Intel(R) HD Graphics 530 Direct3D11 vs_5_0 ps_5_0, D3D11)', 'intel'],
+                // Intel Iris (higher-end laptops)
+                ['Google Inc. (Intel)', 'ANGLE (Intel, Intel(R) Iris(R) Xe Graphics Direct3D11 vs_5_0 ps_5_0, D3D11)', 'intel'],
+                ['Google Inc. (Intel)', 'ANGLE (Intel, Intel(R) Iris(R) Plus Graphics Direct3D11 vs_5_0 ps_5_0, D3D11)', 'intel'],
+                ['Google Inc. (Intel)', 'ANGLE (Intel, Intel(R) Iris(R) Graphics 6100 Direct3D11 vs_5_0 ps_5_0, D3D11)', 'intel'],
+                // AMD Radeon integrated (common on AMD laptops/desktops)
+                ['Google Inc. (AMD)', 'ANGLE (AMD, AMD Radeon(TM) Graphics Direct3D11 vs_5_0 ps_5_0, D3D11)', 'amd'],
+                ['Google Inc. (AMD)', 'ANGLE (AMD, AMD Radeon RX 580 Direct3D11 vs_5_0 ps_5_0, D3D11)', 'amd'],
+                ['Google Inc. (AMD)', 'ANGLE (AMD, AMD Radeon RX Vega 8 Direct3D11 vs_5_0 ps_5_0, D3D11)', 'amd'],
+                ['Google Inc. (AMD)', 'ANGLE (AMD, AMD Radeon(TM) Vega 8 Graphics Direct3D11 vs_5_0 ps_5_0, D3D11)', 'amd'],
+                // NVIDIA (common discrete GPUs)
+                ['Google Inc. (NVIDIA)', 'ANGLE (NVIDIA, NVIDIA GeForce GTX 1650 Direct3D11 vs_5_0 ps_5_0, D3D11)', 'nvidia'],
+                ['Google Inc. (NVIDIA)', 'ANGLE (NVIDIA, NVIDIA GeForce RTX 3060 Direct3D11 vs_5_0 ps_5_0, D3D11)', 'nvidia'],
+            ];
 
-        var gpuParams = {
-            intel: {
-                0x0D33: 16384, 0x0D36: new Int32Array([16384, 16384]), 0x8D42: 16384,
-                0x8869: 16, 0x8DFB: 16, 0x8872: 16,
-                0x8B4C: 1024, 0x8B49: 256, 0x8B4B: 15
-            },
-            amd: {
-                0x0D33: 16384, 0x0D36: new Int32Array([16384, 16384]), 0x8D42: 16384,
-                0x8869: 16, 0x8DFB: 16, 0x8872: 16,
-                0x8B4C: 4096, 0x8B49: 1024, 0x8B4B: 32
-            },
-            nvidia: {
-                0x0D33: 32768, 0x0D36: new Int32Array([32768, 32768]), 0x8D42: 32768,
-                0x8869: 16, 0x8DFB: 32, 0x8872: 32,
-                0x8B4C: 4096, 0x8B49: 1024, 0x8B4B: 32
-            }
-        };
+            var gpuParams = {
+                intel: {
+                    0x0D33: 16384, 0x0D36: new Int32Array([16384, 16384]), 0x8D42: 16384,
+                    0x8869: 16, 0x8DFB: 16, 0x8872: 16,
+                    0x8B4C: 1024, 0x8B49: 256, 0x8B4B: 15
+                },
+                amd: {
+                    0x0D33: 16384, 0x0D36: new Int32Array([16384, 16384]), 0x8D42: 16384,
+                    0x8869: 16, 0x8DFB: 16, 0x8872: 16,
+                    0x8B4C: 4096, 0x8B49: 1024, 0x8B4B: 32
+                },
+                nvidia: {
+                    0x0D33: 32768, 0x0D36: new Int32Array([32768, 32768]), 0x8D42: 32768,
+                    0x8869: 16, 0x8DFB: 32, 0x8872: 32,
+                    0x8B4C: 4096, 0x8B49: 1024, 0x8B4B: 32
+                }
+            };
 
-        var gi = Math.floor(rng() * gpuPool.length);
-        var sv = gpuPool[gi][0], sr = gpuPool[gi][1];
-        var selectedParams = gpuParams[gpuPool[gi][2]];
+            var gi = Math.floor(rng() * gpuPool.length);
+            var sv = gpuPool[gi][0], sr = gpuPool[gi][1];
+            var selectedParams = gpuParams[gpuPool[gi][2]];
 
-        function patchGetParameter(proto, origFn) {
-            proto.getParameter = function(p) {
-                if (p === 37445) return sv;   // UNMASKED_VENDOR_WEBGL
-                if (p === 37446) return sr;   // UNMASKED_RENDERER_WEBGL
-                if (selectedParams[p] !== undefined) return selectedParams[p];
-                return origFn.call(this, p);
-            };
-            harden(proto, 'getParameter');
-        }
+            function patchGetParameter(proto, origFn) {
+                proto.getParameter = function(p) {
+                    if (p === 37445) return sv;   // UNMASKED_VENDOR_WEBGL
+                    if (p === 37446) return sr;   // UNMASKED_RENDERER_WEBGL
+                    if (selectedParams[p] !== undefined) return selectedParams[p];
+                    return origFn.call(this, p);
+                };
+                harden(proto, 'getParameter');
+            }
 
-        var origGP = WebGLRenderingContext.prototype.getParameter;
-        patchGetParameter(WebGLRenderingContext.prototype, origGP);
+            var origGP = WebGLRenderingContext.prototype.getParameter;
+            patchGetParameter(WebGLRenderingContext.prototype, origGP);
 
-        if (typeof WebGL2RenderingContext !== 'undefined') {
-            var origGP2 = WebGL2RenderingContext.prototype.getParameter;
-            patchGetParameter(WebGL2RenderingContext.prototype, origGP2);
-        }
+            if (typeof WebGL2RenderingContext !== 'undefined') {
+                var origGP2 = WebGL2RenderingContext.prototype.getParameter;
+                patchGetParameter(WebGL2RenderingContext.prototype, origGP2);
+            }
 
-        var origPrec = WebGLRenderingContext.prototype.getShaderPrecisionFormat;
-        WebGLRenderingContext.prototype.getShaderPrecisionFormat = function(st, pt) {
-            var r = origPrec.call(this, st, pt);
-            if (!r) return r;
-            return { rangeMin: r.rangeMin, rangeMax: r.rangeMax, precision: r.precision };
-        };
-        harden(WebGLRenderingContext.prototype, 'getShaderPrecisionFormat');
-    } catch(e) {}
+            var origPrec = WebGLRenderingContext.prototype.getShaderPrecisionFormat;
+            WebGLRenderingContext.prototype.getShaderPrecisionFormat = function(st, pt) {
+                var r = origPrec.call(this, st, pt);
+                if (!r) return r;
+                return { rangeMin: r.rangeMin, rangeMax: r.rangeMax, precision: r.precision };
+            };
+            harden(WebGLRenderingContext.prototype, 'getShaderPrecisionFormat');
+        } catch(e) {}
+    }
 
     // ── audio fingerprint noise ─────────────────────────────────────────────
     // Per-call noise from PRNG (not a cached constant — research/07 consistency)
-    function audioNoise() { return (rng() - 0.5) * 0.01; }
-    try {
-        var origGF = AnalyserNode.prototype.getFloatFrequencyData;
-        AnalyserNode.prototype.getFloatFrequencyData = function(arr) {
-            origGF.call(this, arr);
-            var n = audioNoise();
-            for (var i = 0; i < arr.length; i++) arr[i] += n;
-        };
-        harden(AnalyserNode.prototype, 'getFloatFrequencyData');
-    } catch(e) {}
-    try {
-        var origGFT = AnalyserNode.prototype.getFloatTimeDomainData;
-        AnalyserNode.prototype.getFloatTimeDomainData = function(arr) {
-            origGFT.call(this, arr);
-            var n = audioNoise();
-            for (var i = 0; i < arr.length; i++) arr[i] += n;
-        };
-        harden(AnalyserNode.prototype, 'getFloatTimeDomainData');
-    } catch(e) {}
-    try {
-        var origSR = OfflineAudioContext.prototype.startRendering;
-        OfflineAudioContext.prototype.startRendering = function() {
-            return origSR.call(this).then(function(buf) {
-                try {
-                    var origGCD = buf.getChannelData.bind(buf);
-                    buf.getChannelData = function(ch) {
-                        var d = origGCD(ch);
-                        var n = audioNoise();
-                        for (var i = 0; i < d.length; i++) d[i] += n;
-                        return d;
-                    };
-                } catch(e) {}
-                return buf;
-            });
-        };
-        harden(OfflineAudioContext.prototype, 'startRendering');
-    } catch(e) {}
-    try {
-        var origABGCD = AudioBuffer.prototype.getChannelData;
-        AudioBuffer.prototype.getChannelData = function(ch) {
-            var d = origABGCD.call(this, ch);
-            if (!this.__bushidoNoised) {
-                this.__bushidoNoised = true;
+    if (fpOn('audioNoise')) {
+        function audioNoise() { return (rng() - 0.5) * 0.01; }
+        try {
+            var origGF = AnalyserNode.prototype.getFloatFrequencyData;
+            AnalyserNode.prototype.getFloatFrequencyData = function(arr) {
+                origGF.call(this, arr);
                 var n = audioNoise();
-                for (var i = 0; i < d.length; i++) d[i] += n;
-            }
-            return d;
-        };
-        harden(AudioBuffer.prototype, 'getChannelData');
-    } catch(e) {}
+                for (var i = 0; i < arr.length; i++) arr[i] += n;
+            };
+            harden(AnalyserNode.prototype, 'getFloatFrequencyData');
+        } catch(e) {}
+        try {
+            var origGFT = AnalyserNode.prototype.getFloatTimeDomainData;
+            AnalyserNode.prototype.getFloatTimeDomainData = function(arr) {
+                origGFT.call(this, arr);
+                var n = audioNoise();
+                for (var i = 0; i < arr.length; i++) arr[i] += n;
+            };
+            harden(AnalyserNode.prototype, 'getFloatTimeDomainData');
+        } catch(e) {}
+        try {
+            var origSR = OfflineAudioContext.prototype.startRendering;
+            OfflineAudioContext.prototype.startRendering = function() {
+                return origSR.call(this).then(function(buf) {
+                    try {
+                        var origGCD = buf.getChannelData.bind(buf);
+                        buf.getChannelData = function(ch) {
+                            var d = origGCD(ch);
+                            var n = audioNoise();
+                            for (var i = 0; i < d.length; i++) d[i] += n;
+                            return d;
+                        };
+                    } catch(e) {}
+                    return buf;
+                });
+            };
+            harden(OfflineAudioContext.prototype, 'startRendering');
+        } catch(e) {}
+        try {
+            var origABGCD = AudioBuffer.prototype.getChannelData;
+            AudioBuffer.prototype.getChannelData = function(ch) {
+                var d = origABGCD.call(this, ch);
+                if (!this.__bushidoNoised) {
+                    this.__bushidoNoised = true;
+                    var n = audioNoise();
+                    for (var i = 0; i < d.length; i++) d[i] += n;
+                }
+                return d;
+            };
+            harden(AudioBuffer.prototype, 'getChannelData');
+        } catch(e) {}
+    }
 
     // ── timing clamping ─────────────────────────────────────────────────────
     try {
@@ -525,102 +563,104 @@
     // FIX #8: Ported Shadey's improved timezone handling — injects TZ into
     // constructor args (not just resolvedOptions), patches Date.toString/
     // toTimeString/toDateString, fixes getTimezoneOffset sign (was -300, must be 300)
-    try {
-        var _origDTF = Intl.DateTimeFormat;
-        var _spoofedTZ = 'America/New_York';
-        var _spoofedLocale = 'en-US';
-
-        Intl.DateTimeFormat = function(locales, options) {
-            var opts = Object.assign({}, options || {});
-            if (!opts.timeZone) {
-                opts.timeZone = _spoofedTZ;
-            }
-            if (!locales) locales = _spoofedLocale;
-            var instance = new _origDTF(locales, opts);
-            var _origResolved = instance.resolvedOptions.bind(instance);
-            instance.resolvedOptions = function() {
-                var r = _origResolved();
-                r.timeZone = _spoofedTZ;
-                r.locale = _spoofedLocale;
-                return r;
+    if (fpOn('timezone')) {
+        try {
+            var _origDTF = Intl.DateTimeFormat;
+            var _spoofedTZ = 'America/New_York';
+            var _spoofedLocale = spoofedLocale;
+
+            Intl.DateTimeFormat = function(locales, options) {
+                var opts = Object.assign({}, options || {});
+                if (!opts.timeZone) {
+                    opts.timeZone = _spoofedTZ;
+                }
+                if (!locales) locales = _spoofedLocale;
+                var instance = new _origDTF(locales, opts);
+                var _origResolved = instance.resolvedOptions.bind(instance);
+                instance.resolvedOptions = function() {
+                    var r = _origResolved();
+                    r.timeZone = _spoofedTZ;
+                    r.locale = _spoofedLocale;
+                    return r;
+                };
+                return instance;
             };
-            return instance;
-        };
-        Intl.DateTimeFormat.prototype = _origDTF.prototype;
-        Intl.DateTimeFormat.supportedLocalesOf = _origDTF.supportedLocalesOf;
-        Object.defineProperty(Intl.DateTimeFormat, Symbol.hasInstance, {
-            value: function(inst) { return inst instanceof _origDTF; }
-        });
-        harden(Intl, 'DateTimeFormat');
-    } catch(e) {}
-    try {
-        // FIX #8b: getTimezoneOffset — EST=300, EDT=240 (DST-aware)
-        // Was hardcoded -300 (wrong sign AND no DST). research/11 documents this.
-        Date.prototype.getTimezoneOffset = function() {
-            // Check if this date falls in DST for America/New_York
-            try {
-                var formatter = new _origDTF('en-US', { timeZone: 'America/New_York', timeZoneName: 'short' });
-                var parts = formatter.formatToParts(this);
-                for (var i = 0; i < parts.length; i++) {
-                    if (parts[i].type === 'timeZoneName') {
-                        return parts[i].value === 'EDT' ? 240 : 300;
+            Intl.DateTimeFormat.prototype = _origDTF.prototype;
+            Intl.DateTimeFormat.supportedLocalesOf = _origDTF.supportedLocalesOf;
+            Object.defineProperty(Intl.DateTimeFormat, Symbol.hasInstance, {
+                value: function(inst) { return inst instanceof _origDTF; }
+            });
+            harden(Intl, 'DateTimeFormat');
+        } catch(e) {}
+        try {
+            // FIX #8b: getTimezoneOffset — EST=300, EDT=240 (DST-aware)
+            // Was hardcoded -300 (wrong sign AND no DST). research/11 documents this.
+            Date.prototype.getTimezoneOffset = function() {
+                // Check if this date falls in DST for America/New_York
+                try {
+                    var formatter = new _origDTF('en-US', { timeZone: 'America/New_York', timeZoneName: 'short' });
+                    var parts = formatter.formatToParts(this);
+                    for (var i = 0; i < parts.length; i++) {
+                        if (parts[i].type === 'timeZoneName') {
+                            return parts[i].value === 'EDT' ? 240 : 300;
+                        }
                     }
-                }
-            } catch(e2) {}
-            return 300; // fallback to EST
-        };
-        harden(Date.prototype, 'getTimezoneOffset');
+                } catch(e2) {}
+                return 300; // fallback to EST
+            };
+            harden(Date.prototype, 'getTimezoneOffset');
 
-        // Date.toString: reconstruct with spoofed timezone
-        var _origDateToString = Date.prototype.toString;
-        Date.prototype.toString = function() {
-            try {
-                var d = this;
-                var formatter = new Intl.DateTimeFormat('en-US', {
-                    timeZone: 'America/New_York',
-                    weekday: 'short', year: 'numeric', month: 'short', day: '2-digit',
-                    hour: '2-digit', minute: '2-digit', second: '2-digit',
-                    hour12: false, timeZoneName: 'long'
-                });
-                var parts = formatter.formatToParts(d);
-                var get = function(type) {
-                    for (var i = 0; i < parts.length; i++) if (parts[i].type === type) return parts[i].value;
-                    return '';
-                };
-                // Dynamic offset: EST=-0500, EDT=-0400 (Intl handles DST)
-                var tzName = get('timeZoneName') || 'Eastern Standard Time';
-                var isDST = tzName.indexOf('Daylight') !== -1 || tzName.indexOf('Summer') !== -1;
-                var offset = isDST ? '-0400' : '-0500';
-                return get('weekday') + ' ' + get('month') + ' ' + get('day') + ' ' + get('year') + ' ' +
-                       get('hour') + ':' + get('minute') + ':' + get('second') + ' GMT' + offset + ' (' + tzName + ')';
-            } catch(e2) {
-                return _origDateToString.call(this);
-            }
-        };
-        harden(Date.prototype, 'toString');
+            // Date.toString: reconstruct with spoofed timezone
+            var _origDateToString = Date.prototype.toString;
+            Date.prototype.toString = function() {
+                try {
+                    var d = this;
+                    var formatter = new Intl.DateTimeFormat('en-US', {
+                        timeZone: 'America/New_York',
+                        weekday: 'short', year: 'numeric', month: 'short', day: '2-digit',
+                        hour: '2-digit', minute: '2-digit', second: '2-digit',
+                        hour12: false, timeZoneName: 'long'
+                    });
+                    var parts = formatter.formatToParts(d);
+                    var get = function(type) {
+                        for (var i = 0; i < parts.length; i++) if (parts[i].type === type) return parts[i].value;
+                        return '';
+                    };
+                    // Dynamic offset: EST=-0500, EDT=-0400 (Intl handles DST)
+                    var tzName = get('timeZoneName') || 'Eastern Standard Time';
+                    var isDST = tzName.indexOf('Daylight') !== -1 || tzName.indexOf('Summer') !== -1;
+                    var offset = isDST ? '-0400' : '-0500';
+                    return get('weekday') + ' ' + get('month') + ' ' + get('day') + ' ' + get('year') + ' ' +
+                           get('hour') + ':' + get('minute') + ':' + get('second') + ' GMT' + offset + ' (' + tzName + ')';
+                } catch(e2) {
+                    return _origDateToString.call(this);
+                }
+            };
+            harden(Date.prototype, 'toString');
 
-        var _origTimeString = Date.prototype.toTimeString;
-        Date.prototype.toTimeString = function() {
-            try {
-                var s = Date.prototype.toString.call(this);
-                return s.split(' ').slice(4).join(' ');
-            } catch(e2) {
-                return _origTimeString.call(this);
-            }
-        };
-        harden(Date.prototype, 'toTimeString');
+            var _origTimeString = Date.prototype.toTimeString;
+            Date.prototype.toTimeString = function() {
+                try {
+                    var s = Date.prototype.toString.call(this);
+                    return s.split(' ').slice(4).join(' ');
+                } catch(e2) {
+                    return _origTimeString.call(this);
+                }
+            };
+            harden(Date.prototype, 'toTimeString');
 
-        var _origDateString = Date.prototype.toDateString;
-        Date.prototype.toDateString = function() {
-            try {
-                var s = Date.prototype.toString.call(this);
-                return s.split(' ').slice(0, 4).join(' ');
-            } catch(e2) {
-                return _origDateString.call(this);
-            }
-        };
-        harden(Date.prototype, 'toDateString');
-    } catch(e) {}
+            var _origDateString = Date.prototype.toDateString;
+            Date.prototype.toDateString = function() {
+                try {
+                    var s = Date.prototype.toString.call(this);
+                    return s.split(' ').slice(0, 4).join(' ');
+                } catch(e2) {
+                    return _origDateString.call(this);
+                }
+            };
+            harden(Date.prototype, 'toDateString');
+        } catch(e) {}
+    }
 
     // ── CSS media query protection ──────────────────────────────────────────
     // Uses Object.defineProperty on the real result (not Object.create which broke YouTube)
@@ -737,23 +777,27 @@
                 var a = ws0, b = ws1; ws0 = b; a ^= (a << 23) | 0; a ^= a >>> 17; a ^= b; a ^= b >>> 26; ws1 = a;
                 return ((a + b) >>> 0) / 0xFFFFFFFF;
             }
-            try { Object.defineProperty(navigator, 'hardwareConcurrency', { get: function() { return __HWC__; }, configurable: false }); } catch(e) {}
+            var wfp = __FP__;
+            if (wfp.hardwareConcurrency !== false) {
+                try { Object.defineProperty(navigator, 'hardwareConcurrency', { get: function() { return __HWC__; }, configurable: false }); } catch(e) {}
+            }
             try { Object.defineProperty(navigator, 'deviceMemory', { get: function() { return __DM__; }, configurable: false }); } catch(e) {}
             try { Object.defineProperty(navigator, 'platform', { get: function() { return '__PLAT__'; }, configurable: false }); } catch(e) {}
-            try { Object.defineProperty(navigator, 'language', { get: function() { return 'en-US'; }, configurable: false }); } catch(e) {}
-            try { Object.defineProperty(navigator, 'languages', { get: function() { return ['en-US','en']; }, configurable: false }); } catch(e) {}
-            try {
+            var wlangs = __LANGS__;
+            try { Object.defineProperty(navigator, 'language', { get: function() { return wlangs[0]; }, configurable: false }); } catch(e) {}
+            try { Object.defineProperty(navigator, 'languages', { get: function() { return wlangs.slice(); }, configurable: false }); } catch(e) {}
+            if (wfp.timezone !== false) try {
                 var _origDTF = Intl.DateTimeFormat;
                 Intl.DateTimeFormat = function(locales, options) {
                     var opts = Object.assign({}, options || {});
                     if (!opts.timeZone) opts.timeZone = 'America/New_York';
-                    if (!locales) locales = 'en-US';
+                    if (!locales) locales = wlangs[0];
                     var instance = new _origDTF(locales, opts);
                     var _origResolved = instance.resolvedOptions.bind(instance);
                     instance.resolvedOptions = function() {
                         var r = _origResolved();
                         r.timeZone = 'America/New_York';
-                        r.locale = 'en-US';
+                        r.locale = wlangs[0];
                         return r;
                     };
                     return instance;
@@ -761,7 +805,7 @@
                 Intl.DateTimeFormat.prototype = _origDTF.prototype;
                 Intl.DateTimeFormat.supportedLocalesOf = _origDTF.supportedLocalesOf;
             } catch(e) {}
-            try {
+            if (wfp.canvasNoise !== false) try {
                 if (typeof OffscreenCanvas !== 'undefined') {
                     var origCTB = OffscreenCanvas.prototype.convertToBlob;
                     if (origCTB) {
@@ -791,6 +835,8 @@
             .replace('__HWC__', String(spoofedHWC))
             .replace('__DM__', String(spoofedDM))
             .replace('__PLAT__', spoofedPlatform)
+            .replace('__FP__', JSON.stringify({ hardwareConcurrency: fpOn('hardwareConcurrency'), timezone: fpOn('timezone'), canvasNoise: fpOn('canvasNoise') }))
+            .replace('__LANGS__', JSON.stringify(spoofedLangs))
         + ')();\n';
 
         window.Worker = function(scriptURL, options) {
@@ -824,47 +870,46 @@
         }
     } catch(e) {}
 
-    // ── navigator.userAgentData (hide Edge, spoof Chrome) ───────────────────
+    // ── navigator.userAgentData (hide Edge, match the spoofed UA) ───────────
     try {
-        var uadObj = {
-            brands: [
-                { brand: 'Google Chrome', version: '131' },
-                { brand: 'Chromium', version: '131' },
+        var uadObj;
+        if (uaChrome) {
+            var uaMajor = uaChrome[1];
+            var uaFull = uaMajor + (uaChrome[2] && uaChrome[2].length > 1 ? uaChrome[2] : '.0.0.0');
+            var uaBrands = [
+                { brand: 'Google Chrome', version: uaMajor },
+                { brand: 'Chromium', version: uaMajor },
                 { brand: 'Not_A Brand', version: '24' },
-            ],
-            mobile: false,
-            platform: 'Windows',
-            getHighEntropyValues: function() {
-                return Promise.resolve({
-                    architecture: 'x86',
-                    bitness: '64',
-                    brands: [{ brand: 'Google Chrome', version: '131.0.0.0' }],
-                    fullVersionList: [
-                        { brand: 'Google Chrome', version: '131.0.0.0' },
-                        { brand: 'Chromium', version: '131.0.0.0' },
-                        { brand: 'Not_A Brand', version: '24.0.0.0' },
-                    ],
-                    mobile: false,
-                    model: '',
-                    platform: 'Windows',
-                    platformVersion: '15.0.0',
-                    uaFullVersion: '131.0.0.0',
-                });
-            },
-            toJSON: function() {
-                return {
-                    brands: [
-                        { brand: 'Google Chrome', version: '131' },
-                        { brand: 'Chromium', version: '131' },
-                        { brand: 'Not_A Brand', version: '24' },
-                    ],
-                    mobile: false,
-                    platform: 'Windows',
-                };
-            },
-        };
-        harden(uadObj, 'getHighEntropyValues');
-        harden(uadObj, 'toJSON');
+            ];
+            var uaPlatformVersion = { Windows: '15.0.0', Android: '10.0.0', macOS: '14.0.0' }[uaOS] || '';
+            uadObj = {
+                brands: uaBrands,
+                mobile: uaMobile,
+                platform: uaOS,
+                getHighEntropyValues: function() {
+                    return Promise.resolve({
+                        architecture: uaMobile ? '' : 'x86',
+                        bitness: uaMobile ? '' : '64',
+                        brands: uaBrands,
+                        fullVersionList: [
+                            { brand: 'Google Chrome', version: uaFull },
+                            { brand: 'Chromium', version: uaFull },
+                            { brand: 'Not_A Brand', version: '24.0.0.0' },
+                        ],
+                        mobile: uaMobile,
+                        model: uaOS === 'Android' ? 'K' : '',
+                        platform: uaOS,
+                        platformVersion: uaPlatformVersion,
+                        uaFullVersion: uaFull,
+                    });
+                },
+                toJSON: function() {
+                    return { brands: uaBrands, mobile: uaMobile, platform: uaOS };
+                },
+            };
+            harden(uadObj, 'getHighEntropyValues');
+            harden(uadObj, 'toJSON');
+        }
         Object.defineProperty(navigator, 'userAgentData', {
             get: function() { return uadObj; },
             configurable: false
@@ -917,7 +962,7 @@
     } catch(e) {}
 
     // ── screen.orientation ──────────────────────────────────────────────────
-    try {
+    if (fpOn('screen')) try {
         if (!screen.orientation || !screen.orientation.type) {
             Object.defineProperty(screen, 'orientation', {
                 get: function() { return { type: 'landscape-primary', angle: 0 }; },
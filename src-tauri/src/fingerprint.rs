@@ -0,0 +1,152 @@
+use std::fs;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::error::BushidoError;
+
+const DEFAULT_LANGUAGE: &str = "en-US";
+
+/// Which fingerprint.js protections run in new tabs, plus the one locale both
+/// `navigator.language(s)` and the Accept-Language header are built from.
+/// Persisted as `fingerprint` in settings.json; missing fields keep protection on.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FingerprintConfig {
+    pub canvas_noise: bool,
+    pub webgl_vendor: bool,
+    pub audio_noise: bool,
+    pub timezone: bool,
+    pub screen: bool,
+    pub hardware_concurrency: bool,
+    pub language: String,
+}
+
+impl Default for FingerprintConfig {
+    fn default() -> Self {
+        Self {
+            canvas_noise: true,
+            webgl_vendor: true,
+            audio_noise: true,
+            timezone: true,
+            screen: true,
+            hardware_concurrency: true,
+            language: DEFAULT_LANGUAGE.into(),
+        }
+    }
+}
+
+/// BCP 47-ish: a 2–3 letter language plus optional alphanumeric subtags. The value
+/// ends up in a request header and a script, so anything else is refused.
+fn valid_language(tag: &str) -> bool {
+    let mut parts = tag.split('-');
+    let lang = parts.next().unwrap_or("");
+    (2..=3).contains(&lang.len())
+        && lang.chars().all(|c| c.is_ascii_alphabetic())
+        && parts.all(|p| (2..=8).contains(&p.len()) && p.chars().all(|c| c.is_ascii_alphanumeric()))
+}
+
+impl FingerprintConfig {
+    /// `navigator.languages`: the tag, then its bare language if it has a region.
+    pub fn languages(&self) -> Vec<String> {
+        let tag = if valid_language(&self.language) { self.language.as_str() } else { DEFAULT_LANGUAGE };
+        let mut out = vec![tag.to_string()];
+        if let Some((base, _)) = tag.split_once('-') {
+            out.push(base.to_string());
+        }
+        out
+    }
+
+    /// Accept-Language matching `languages()` in order, e.g. "en-US,en;q=0.9".
+    pub fn accept_language(&self) -> String {
+        self.languages()
+            .iter()
+            .enumerate()
+            .map(|(i, l)| if i == 0 { l.clone() } else { format!("{};q=0.{}", l, 10 - i.min(9)) })
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    /// Init script defining `window.__bushidoFp`; must run before fingerprint.js, which
+    /// derives navigator.platform, appVersion and userAgentData from `user_agent`.
+    pub fn script(&self, user_agent: &str) -> String {
+        let mut v = serde_json::to_value(self).unwrap_or_default();
+        v["languages"] = serde_json::json!(self.languages());
+        v["userAgent"] = serde_json::json!(user_agent);
+        format!("window.__bushidoFp={};", v)
+    }
+}
+
+pub struct FingerprintState {
+    pub config: Mutex<FingerprintConfig>,
+}
+
+impl FingerprintState {
+    pub fn load() -> Self {
+        let config = fs::read_to_string(crate::base_data_dir().join("settings.json")).ok()
+            .and_then(|s| serde_json::from_str::<serde_json::Value>(&s).ok())
+            .and_then(|v| serde_json::from_value(v.get("fingerprint")?.clone()).ok())
+            .unwrap_or_default();
+        Self { config: Mutex::new(config) }
+    }
+}
+
+/// Applies to tabs created from now on; open tabs keep what they were built with.
+#[tauri::command]
+pub async fn set_fingerprint_config(app: AppHandle, config: FingerprintConfig) -> Result<(), BushidoError> {
+    if !valid_language(&config.language) {
+        return Err(BushidoError::Other(format!("'{}' is not a language tag like en-US", config.language)));
+    }
+    *app.state::<FingerprintState>().config.lock() = config;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_language(language: &str) -> FingerprintConfig {
+        FingerprintConfig { language: language.into(), ..Default::default() }
+    }
+
+    #[test]
+    fn default_matches_previous_hardcoded_header() {
+        assert_eq!(FingerprintConfig::default().accept_language(), "en-US,en;q=0.9");
+    }
+
+    #[test]
+    fn header_and_navigator_agree() {
+        let c = with_language("de-DE");
+        assert_eq!(c.languages(), vec!["de-DE", "de"]);
+        assert_eq!(c.accept_language(), "de-DE,de;q=0.9");
+        let c = with_language("fr");
+        assert_eq!(c.languages(), vec!["fr"]);
+        assert_eq!(c.accept_language(), "fr");
+    }
+
+    #[test]
+    fn invalid_language_falls_back() {
+        for bad in ["", "e", "english", "en_US", "en-US\r\nX-Evil: 1", "en-'US"] {
+            assert!(!valid_language(bad), "{:?}", bad);
+            assert_eq!(with_language(bad).accept_language(), "en-US,en;q=0.9");
+        }
+        assert!(valid_language("zh-Hant-TW"));
+    }
+
+    #[test]
+    fn missing_fields_keep_protection_on() {
+        let c: FingerprintConfig = serde_json::from_str(r#"{"canvasNoise":false}"#).unwrap();
+        assert!(!c.canvas_noise);
+        assert!(c.webgl_vendor && c.audio_noise && c.timezone && c.screen && c.hardware_concurrency);
+        assert_eq!(c.language, "en-US");
+    }
+
+    #[test]
+    fn script_carries_languages_and_user_agent() {
+        let s = with_language("pt-BR").script("Foo/1.0 \"quoted\"");
+        assert!(s.starts_with("window.__bushidoFp={"));
+        assert!(s.contains(r#""languages":["pt-BR","pt"]"#));
+        assert!(s.contains(r#""userAgent":"Foo/1.0 \"quoted\"""#));
+        assert!(s.contains(r#""canvasNoise":true"#));
+    }
+}
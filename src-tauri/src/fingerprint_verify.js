@@ -61,13 +61,15 @@
         navigator.productSub === '20030107',
         navigator.productSub);
 
-    assert('nav.doNotTrack is 1 (matches DNT header)',
-        navigator.doNotTrack === '1',
-        String(navigator.doNotTrack));
-
-    assert('nav.globalPrivacyControl is true (matches Sec-GPC header)',
-        navigator.globalPrivacyControl === true,
-        String(navigator.globalPrivacyControl));
+    if (window.__bushidoSignalsOn !== false) {
+        assert('nav.doNotTrack is 1 (matches DNT header)',
+            navigator.doNotTrack === '1',
+            String(navigator.doNotTrack));
+
+        assert('nav.globalPrivacyControl is true (matches Sec-GPC header)',
+            navigator.globalPrivacyControl === true,
+            String(navigator.globalPrivacyControl));
+    }
 
     assert('nav.connection is undefined',
         navigator.connection === undefined,
@@ -0,0 +1,159 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use parking_lot::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::crash_log;
+use crate::error::BushidoError;
+
+pub const DEFAULT_THRESHOLD_MB: u64 = 4096;
+// below this the browser can't do much more than show one page
+const MIN_THRESHOLD_MB: u64 = 512;
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+// a tab looked at this recently is never picked, however old the others are
+const MIN_IDLE: Duration = Duration::from_secs(5 * 60);
+
+/// Memory-pressure tab hibernation. Per-tab focus times live in `WebviewState`;
+/// this only holds the policy and what the policy itself suspended.
+pub struct HibernateState {
+    enabled: AtomicBool,
+    threshold_mb: AtomicU64,
+    /// tabs shown in a pane at the last layout — never hibernated
+    visible: Mutex<HashSet<String>>,
+    hibernated: Mutex<HashSet<String>>,
+}
+
+impl HibernateState {
+    pub fn new() -> Self {
+        Self {
+            enabled: AtomicBool::new(false),
+            threshold_mb: AtomicU64::new(DEFAULT_THRESHOLD_MB),
+            visible: Mutex::new(HashSet::new()),
+            hibernated: Mutex::new(HashSet::new()),
+        }
+    }
+
+    pub fn set_visible(&self, ids: HashSet<String>) {
+        *self.visible.lock() = ids;
+    }
+
+    /// Tab was resumed or closed — the policy may pick it again later.
+    pub fn forget(&self, id: &str) {
+        self.hibernated.lock().remove(id);
+    }
+}
+
+/// Least recently focused tab that has been idle for `min_idle`. Tabs never
+/// focused count as the oldest; ties break on id so the pick is stable.
+fn pick_candidate(
+    tabs: &[String],
+    last_focus: &HashMap<String, Instant>,
+    excluded: &dyn Fn(&str) -> bool,
+    now: Instant,
+    min_idle: Duration,
+) -> Option<String> {
+    tabs.iter()
+        .filter(|id| !excluded(id))
+        .filter(|id| last_focus.get(*id).map_or(true, |t| now.duration_since(*t) >= min_idle))
+        .min_by(|a, b| last_focus.get(*a).cmp(&last_focus.get(*b)).then_with(|| a.cmp(b)))
+        .cloned()
+}
+
+/// Suspend one more tab if Bushido plus its WebView2 processes are over the threshold.
+/// One per tick: the freed memory takes a moment to show up in the counters.
+async fn check(app: &AppHandle) {
+    let hs = app.state::<HibernateState>();
+    if !hs.enabled.load(Ordering::Relaxed) {
+        return;
+    }
+    let threshold = hs.threshold_mb.load(Ordering::Relaxed);
+    let Some(used) = crate::process_manager::total_memory_bytes(app).await else { return };
+    let used_mb = used / (1024 * 1024);
+    if used_mb <= threshold {
+        return;
+    }
+
+    let ws = app.state::<crate::WebviewState>();
+    let tabs: Vec<String> = ws.tabs.lock().keys().cloned().collect();
+    let last_focus = ws.last_focus.lock().clone();
+    let pinned = app.state::<crate::PinnedState>().tabs.lock().clone();
+    let panels = app.state::<crate::PanelState>().ids.lock().clone();
+    let visible = hs.visible.lock().clone();
+    let hibernated = hs.hibernated.lock().clone();
+    let excluded = |id: &str| {
+        pinned.contains(id) || panels.contains(id) || visible.contains(id) || hibernated.contains(id)
+            || crate::media_session::is_playing(app, id)
+            || app.state::<crate::throttling::ThrottleState>().keeps_running(id)
+            || app.state::<crate::tab_window::TabWindowState>().is_detached(id)
+    };
+    let Some(id) = pick_candidate(&tabs, &last_focus, &excluded, Instant::now(), MIN_IDLE) else { return };
+
+    if let Err(e) = crate::suspend_tab(app.clone(), id.clone()).await {
+        crash_log::log_warn("hibernate", &format!("suspend {} failed: {}", id, e));
+        return;
+    }
+    hs.hibernated.lock().insert(id.clone());
+    crash_log::log_info("hibernate", &format!("hibernated {} at {} MB (limit {} MB)", id, used_mb, threshold));
+    let _ = app.emit_to("main", "tab-hibernated", serde_json::json!({ "id": id, "memoryMb": used_mb }));
+}
+
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            check(&app).await;
+        }
+    });
+}
+
+/// Turn memory-pressure hibernation on/off. Returns the threshold actually used.
+#[tauri::command]
+pub async fn set_auto_hibernate(app: AppHandle, enabled: bool, threshold_mb: u64) -> Result<u64, BushidoError> {
+    let hs = app.state::<HibernateState>();
+    let threshold = threshold_mb.max(MIN_THRESHOLD_MB);
+    hs.threshold_mb.store(threshold, Ordering::Relaxed);
+    hs.enabled.store(enabled, Ordering::Relaxed);
+    Ok(threshold)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(v: &[&str]) -> Vec<String> {
+        v.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn picks_least_recently_focused() {
+        let now = Instant::now() + Duration::from_secs(3600);
+        let focus = HashMap::from([
+            ("a".to_string(), now - Duration::from_secs(600)),
+            ("b".to_string(), now - Duration::from_secs(1800)),
+            ("c".to_string(), now - Duration::from_secs(900)),
+        ]);
+        let pick = pick_candidate(&ids(&["a", "b", "c"]), &focus, &|_: &str| false, now, MIN_IDLE);
+        assert_eq!(pick.as_deref(), Some("b"));
+    }
+
+    #[test]
+    fn never_focused_goes_first() {
+        let now = Instant::now() + Duration::from_secs(3600);
+        let focus = HashMap::from([("a".to_string(), now - Duration::from_secs(1800))]);
+        let pick = pick_candidate(&ids(&["a", "z"]), &focus, &|_: &str| false, now, MIN_IDLE);
+        assert_eq!(pick.as_deref(), Some("z"));
+    }
+
+    #[test]
+    fn skips_excluded_and_recently_focused() {
+        let now = Instant::now() + Duration::from_secs(3600);
+        let focus = HashMap::from([
+            ("pinned".to_string(), now - Duration::from_secs(1800)),
+            ("fresh".to_string(), now - Duration::from_secs(60)),
+        ]);
+        let excluded = |id: &str| id == "pinned";
+        assert_eq!(pick_candidate(&ids(&["pinned", "fresh"]), &focus, &excluded, now, MIN_IDLE), None);
+    }
+}
@@ -0,0 +1,171 @@
+use std::fs;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::crash_log;
+use crate::error::BushidoError;
+use crate::history_search::{HistoryEntry, HistoryIndex};
+
+const DAY_MS: f64 = 86_400_000.0;
+const MAX_RETENTION_DAYS: u32 = 3650;
+const PRUNE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// How long history is kept; set by the frontend from `historyRetentionDays`. Only
+/// local history is pruned: dropping synced visits would delete them on every peer.
+pub struct HistoryRetentionState {
+    /// 0 = forever
+    days: AtomicU32,
+}
+
+impl HistoryRetentionState {
+    pub fn new() -> Self {
+        Self { days: AtomicU32::new(0) }
+    }
+}
+
+fn now_ms() -> f64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as f64
+}
+
+/// Oldest last-visit time still kept, or None when history is kept forever
+pub fn retention_cutoff(days: u32, now_ms: f64) -> Option<f64> {
+    (days > 0).then(|| now_ms - days as f64 * DAY_MS)
+}
+
+/// Drops entries last visited before `cutoff_ms`; one visited exactly at the cutoff
+/// stays. Returns how many went.
+pub fn prune_before(entries: &mut Vec<HistoryEntry>, cutoff_ms: f64) -> usize {
+    let before = entries.len();
+    entries.retain(|e| e.last_visit_at >= cutoff_ms);
+    before - entries.len()
+}
+
+/// Drops entries last visited within `from_ms..=to_ms`. Returns how many went.
+pub fn remove_range(entries: &mut Vec<HistoryEntry>, from_ms: f64, to_ms: f64) -> usize {
+    let before = entries.len();
+    entries.retain(|e| e.last_visit_at < from_ms || e.last_visit_at > to_ms);
+    before - entries.len()
+}
+
+/// Apply `f` to history.json and write it back when it removed anything. The UI
+/// holds its own copy and saves it wholesale, so it's told to reload with
+/// `history-changed`.
+fn rewrite(app: &AppHandle, f: impl FnOnce(&mut Vec<HistoryEntry>) -> usize) -> Result<usize, BushidoError> {
+    let path = crate::history_path(app);
+    let Ok(json) = fs::read_to_string(&path) else { return Ok(0) };
+    let mut entries: Vec<HistoryEntry> = serde_json::from_str(&json)
+        .map_err(|e| BushidoError::Other(format!("history.json: {}", e)))?;
+    let removed = f(&mut entries);
+    if removed > 0 {
+        let json = serde_json::to_string(&entries).map_err(|e| BushidoError::Other(e.to_string()))?;
+        fs::write(&path, json)?;
+        app.state::<HistoryIndex>().invalidate();
+        let _ = app.emit_to("main", "history-changed", ());
+    }
+    Ok(removed)
+}
+
+/// Clearing a span of history also clears it from the sync doc; otherwise the next
+/// sync would bring the visits back from peers.
+async fn clear_synced_range(app: &AppHandle, from_ms: f64, to_ms: f64) -> Result<usize, BushidoError> {
+    let Some(state) = app.try_state::<crate::sync::SyncState>() else { return Ok(0) };
+    if !state.syncs(crate::sync::DataType::History) {
+        return Ok(0);
+    }
+    let removed = {
+        let mut g = state.sync_doc.lock().await;
+        let Some(doc) = g.as_mut() else { return Ok(0) };
+        let removed = doc.remove_history_range(from_ms.ceil() as i64, to_ms.floor() as i64)?;
+        if removed > 0 {
+            doc.save()?;
+        }
+        removed
+    };
+    if removed > 0 {
+        crate::sync::notify_sync_change(&state);
+    }
+    Ok(removed)
+}
+
+async fn prune(app: &AppHandle) -> Result<usize, BushidoError> {
+    let days = app.state::<HistoryRetentionState>().days.load(Ordering::Relaxed);
+    let Some(cutoff) = retention_cutoff(days, now_ms()) else { return Ok(0) };
+    let removed = rewrite(app, |entries| prune_before(entries, cutoff))?;
+    if removed > 0 {
+        crash_log::log_info("history_retention", &format!("pruned {} history entries older than {} days", removed, days));
+    }
+    Ok(removed)
+}
+
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(PRUNE_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = prune(&app).await {
+                crash_log::log_warn("history_retention", &format!("prune failed: {}", e));
+            }
+        }
+    });
+}
+
+/// Keep history for `days` (0 = forever) and prune right away. Returns how many
+/// entries were removed.
+#[tauri::command]
+pub async fn set_history_retention(app: AppHandle, days: u32) -> Result<usize, BushidoError> {
+    if days > MAX_RETENTION_DAYS {
+        return Err(BushidoError::Limit(format!("history retention is limited to {} days", MAX_RETENTION_DAYS)));
+    }
+    app.state::<HistoryRetentionState>().days.store(days, Ordering::Relaxed);
+    prune(&app).await
+}
+
+/// Remove entries last visited between the two unix-ms timestamps, both included,
+/// here and (with history sync on) on paired devices. Returns how many were removed
+/// locally.
+#[tauri::command]
+pub async fn clear_history_range(app: AppHandle, from_ts: f64, to_ts: f64) -> Result<usize, BushidoError> {
+    if !from_ts.is_finite() || !to_ts.is_finite() || from_ts > to_ts {
+        return Err(BushidoError::Other("invalid history range".into()));
+    }
+    let removed = rewrite(&app, |entries| remove_range(entries, from_ts, to_ts))?;
+    clear_synced_range(&app, from_ts, to_ts).await?;
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(url: &str, last_visit_at: f64) -> HistoryEntry {
+        HistoryEntry { url: url.into(), title: String::new(), favicon: None, visit_count: 1, last_visit_at }
+    }
+
+    fn urls(entries: &[HistoryEntry]) -> Vec<&str> {
+        entries.iter().map(|e| e.url.as_str()).collect()
+    }
+
+    #[test]
+    fn zero_days_keeps_forever() {
+        assert_eq!(retention_cutoff(0, 10.0 * DAY_MS), None);
+        assert_eq!(retention_cutoff(7, 10.0 * DAY_MS), Some(3.0 * DAY_MS));
+    }
+
+    #[test]
+    fn prune_keeps_the_cutoff_itself() {
+        let cutoff = 1_000.0;
+        let mut entries = vec![at("old", cutoff - 1.0), at("edge", cutoff), at("new", cutoff + 1.0)];
+        assert_eq!(prune_before(&mut entries, cutoff), 1);
+        assert_eq!(urls(&entries), vec!["edge", "new"]);
+        assert_eq!(prune_before(&mut entries, cutoff), 0);
+    }
+
+    #[test]
+    fn range_includes_both_ends() {
+        let mut entries = vec![at("before", 99.0), at("from", 100.0), at("mid", 150.0), at("to", 200.0), at("after", 201.0)];
+        assert_eq!(remove_range(&mut entries, 100.0, 200.0), 3);
+        assert_eq!(urls(&entries), vec!["before", "after"]);
+        assert_eq!(remove_range(&mut entries, 150.0, 150.0), 0);
+    }
+}
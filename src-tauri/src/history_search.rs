@@ -0,0 +1,184 @@
+use std::fs;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::error::BushidoError;
+
+const DEFAULT_LIMIT: usize = 8;
+const MAX_LIMIT: usize = 100;
+const MAX_QUERY_LEN: usize = 256;
+const HOUR_MS: f64 = 3_600_000.0;
+
+/// One row of history.json, as the frontend writes it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryEntry {
+    pub url: String,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub favicon: Option<String>,
+    #[serde(default)]
+    pub visit_count: u64,
+    /// unix ms
+    #[serde(default)]
+    pub last_visit_at: f64,
+}
+
+/// history.json parsed on the first search and kept until save_history replaces it,
+/// so typing in the URL bar doesn't re-read the file per keystroke
+pub struct HistoryIndex {
+    entries: Mutex<Option<Arc<Vec<HistoryEntry>>>>,
+}
+
+/// Same buckets as the URL bar's frecencyScore: recent visits count for more
+pub fn frecency(visit_count: u64, last_visit_ms: f64, now_ms: f64) -> f64 {
+    let age_hours = (now_ms - last_visit_ms) / HOUR_MS;
+    let weight = if age_hours < 4.0 {
+        100.0
+    } else if age_hours < 24.0 {
+        70.0
+    } else if age_hours < 72.0 {
+        50.0
+    } else if age_hours < 336.0 {
+        30.0
+    } else {
+        10.0
+    };
+    visit_count.max(1) as f64 * weight
+}
+
+/// Frecency scaled by how well the entry matches, or None when some term is in
+/// neither the URL nor the title. A host or title word starting with a term counts
+/// for more than a match somewhere in the middle.
+fn score(entry: &HistoryEntry, terms: &[String], now_ms: f64) -> Option<f64> {
+    let url = entry.url.to_lowercase();
+    let title = entry.title.to_lowercase();
+    let host = url::Url::parse(&entry.url).ok()
+        .and_then(|u| u.host_str().map(|h| h.trim_start_matches("www.").to_lowercase()))
+        .unwrap_or_default();
+    let mut boost = 1.0;
+    for term in terms {
+        if !url.contains(term.as_str()) && !title.contains(term.as_str()) {
+            return None;
+        }
+        if host.starts_with(term.as_str()) {
+            boost += 1.0;
+        } else if title.split(|c: char| !c.is_alphanumeric()).any(|w| w.starts_with(term.as_str())) {
+            boost += 0.5;
+        }
+    }
+    Some(frecency(entry.visit_count, entry.last_visit_at, now_ms) * boost)
+}
+
+/// The `limit` best matches for `query`, best first; an empty query ranks everything
+pub fn rank(entries: &[HistoryEntry], query: &str, limit: usize, now_ms: f64) -> Vec<HistoryEntry> {
+    let terms: Vec<String> = query.to_lowercase().split_whitespace().map(String::from).collect();
+    let mut scored: Vec<(f64, &HistoryEntry)> = entries.iter()
+        .filter_map(|e| score(e, &terms, now_ms).map(|s| (s, e)))
+        .collect();
+    scored.sort_by(|a, b| {
+        b.0.total_cmp(&a.0).then_with(|| b.1.last_visit_at.total_cmp(&a.1.last_visit_at))
+    });
+    scored.into_iter().take(limit).map(|(_, e)| e.clone()).collect()
+}
+
+impl HistoryIndex {
+    pub fn new() -> Self {
+        Self { entries: Mutex::new(None) }
+    }
+
+    /// history.json changed; parse it again on the next search
+    pub fn invalidate(&self) {
+        *self.entries.lock() = None;
+    }
+
+    fn entries(&self, app: &AppHandle) -> Arc<Vec<HistoryEntry>> {
+        if let Some(entries) = self.entries.lock().as_ref() {
+            return entries.clone();
+        }
+        let entries: Vec<HistoryEntry> = fs::read_to_string(crate::history_path(app)).ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default();
+        let entries = Arc::new(entries);
+        *self.entries.lock() = Some(entries.clone());
+        entries
+    }
+}
+
+/// Best history matches for what's typed in the URL bar, ranked by frecency and
+/// match quality
+#[tauri::command]
+pub async fn search_history(app: AppHandle, query: String, limit: Option<usize>) -> Result<Vec<HistoryEntry>, BushidoError> {
+    if query.len() > MAX_QUERY_LEN {
+        return Err(BushidoError::Limit(format!("history searches are limited to {} characters", MAX_QUERY_LEN)));
+    }
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT);
+    let entries = app.state::<HistoryIndex>().entries(&app);
+    let now_ms = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as f64;
+    Ok(rank(&entries, &query, limit, now_ms))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: f64 = 1_000.0 * HOUR_MS;
+
+    fn entry(url: &str, title: &str, visit_count: u64, hours_ago: f64) -> HistoryEntry {
+        HistoryEntry { url: url.into(), title: title.into(), favicon: None, visit_count, last_visit_at: NOW - hours_ago * HOUR_MS }
+    }
+
+    fn urls(v: Vec<HistoryEntry>) -> Vec<String> {
+        v.into_iter().map(|e| e.url).collect()
+    }
+
+    #[test]
+    fn recent_visits_outweigh_old_ones() {
+        assert_eq!(frecency(3, NOW - HOUR_MS, NOW), 300.0);
+        assert_eq!(frecency(3, NOW - 48.0 * HOUR_MS, NOW), 150.0);
+        assert_eq!(frecency(3, NOW - 1000.0 * HOUR_MS, NOW), 30.0);
+        assert_eq!(frecency(0, NOW, NOW), 100.0);
+    }
+
+    #[test]
+    fn every_term_has_to_match() {
+        let entries = vec![
+            entry("https://docs.rs/serde", "serde - Rust", 2, 1.0),
+            entry("https://example.com/rust-book", "The Book", 1, 1.0),
+            entry("https://news.example/", "Daily news", 9, 1.0),
+        ];
+        assert_eq!(urls(rank(&entries, "rust serde", 10, NOW)), vec!["https://docs.rs/serde"]);
+        assert_eq!(urls(rank(&entries, "RUST", 10, NOW)), vec!["https://docs.rs/serde", "https://example.com/rust-book"]);
+        assert!(rank(&entries, "python", 10, NOW).is_empty());
+        assert_eq!(rank(&entries, "  ", 2, NOW).len(), 2);
+    }
+
+    #[test]
+    fn host_prefix_and_frecency_order_results() {
+        let entries = vec![
+            entry("https://blog.example/github-tips", "Tips", 1, 1.0),
+            entry("https://www.github.com/", "GitHub", 1, 1.0),
+            entry("https://old.example/git", "git internals", 20, 2000.0),
+            entry("https://new.example/git", "git internals", 20, 1.0),
+        ];
+        let ranked = urls(rank(&entries, "git", 10, NOW));
+        assert_eq!(ranked[0], "https://new.example/git");
+        assert_eq!(ranked[1], "https://old.example/git");
+        assert_eq!(ranked[2], "https://www.github.com/");
+        assert_eq!(ranked[3], "https://blog.example/github-tips");
+        assert_eq!(rank(&entries, "git", 1, NOW).len(), 1);
+    }
+
+    #[test]
+    fn reads_the_frontend_format() {
+        let json = r#"[{"url":"https://a.example/","title":"A","visitCount":3,"lastVisitAt":1700000000000},{"url":"https://b.example/"}]"#;
+        let entries: Vec<HistoryEntry> = serde_json::from_str(json).unwrap();
+        assert_eq!(entries[0].visit_count, 3);
+        assert_eq!(entries[1].title, "");
+        assert!(!serde_json::to_string(&entries[1]).unwrap().contains("favicon"));
+    }
+}
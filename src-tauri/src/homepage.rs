@@ -0,0 +1,85 @@
+use parking_lot::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::error::BushidoError;
+
+/// React's new tab page
+pub const NEW_TAB_URL: &str = "bushido://newtab";
+const MAX_URL_LEN: usize = 2048;
+
+/// Where new tabs and the home action go, from the `newTabUrl` setting
+pub struct HomepageState {
+    url: Mutex<String>,
+}
+
+/// A usable http(s) or internal URL, or the new tab page when `raw` is empty or
+/// isn't one. Bare hosts ("example.com", "localhost:8080") get https.
+pub fn normalize(raw: &str) -> String {
+    let raw = raw.trim();
+    if raw.is_empty() || raw.len() > MAX_URL_LEN {
+        return NEW_TAB_URL.into();
+    }
+    if let Some(page) = raw.strip_prefix("bushido://") {
+        let ok = !page.is_empty() && page.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+        return if ok { raw.to_lowercase() } else { NEW_TAB_URL.into() };
+    }
+    let parsed = if raw.contains("://") {
+        url::Url::parse(raw)
+    } else {
+        url::Url::parse(&format!("https://{}", raw))
+    };
+    match parsed {
+        Ok(u) if matches!(u.scheme(), "http" | "https") && u.host_str().is_some() => u.to_string(),
+        _ => NEW_TAB_URL.into(),
+    }
+}
+
+impl HomepageState {
+    pub fn new(raw: &str) -> Self {
+        Self { url: Mutex::new(normalize(raw)) }
+    }
+
+    pub fn url(&self) -> String {
+        self.url.lock().clone()
+    }
+}
+
+/// Check and apply the new-tab URL before the frontend saves it as `newTabUrl`.
+/// Returns what will actually open, the new tab page when `url` didn't make sense.
+#[tauri::command]
+pub async fn set_new_tab_url(app: AppHandle, url: String) -> Result<String, BushidoError> {
+    let url = normalize(&url);
+    *app.state::<HomepageState>().url.lock() = url.clone();
+    Ok(url)
+}
+
+/// Open the home page in a new tab. The UI owns the tab list, so it gets an
+/// `open-home-tab` event and creates the tab itself.
+#[tauri::command]
+pub async fn open_home_tab(app: AppHandle) -> Result<(), BushidoError> {
+    let url = app.state::<HomepageState>().url();
+    app.emit_to("main", "open-home-tab", serde_json::json!({ "url": url }))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_web_and_internal_urls() {
+        assert_eq!(normalize(" https://example.com/start "), "https://example.com/start");
+        assert_eq!(normalize("example.com"), "https://example.com/");
+        assert_eq!(normalize("localhost:8080"), "https://localhost:8080/");
+        assert_eq!(normalize("http://intranet/"), "http://intranet/");
+        assert_eq!(normalize("bushido://Settings"), "bushido://settings");
+    }
+
+    #[test]
+    fn falls_back_to_the_new_tab_page() {
+        for bad in ["", "   ", "javascript:alert(1)", "file:///C:/x", "ftp://example.com/", "https://", "bushido://", "bushido://a/b", "has space.com"] {
+            assert_eq!(normalize(bad), NEW_TAB_URL, "{:?}", bad);
+        }
+        assert_eq!(normalize(&format!("https://example.com/{}", "a".repeat(MAX_URL_LEN))), NEW_TAB_URL);
+    }
+}
@@ -0,0 +1,374 @@
+use serde::{Deserialize, Serialize};
+
+// anything a page script legitimately posts fits easily; the fingerprint-verify
+// report is the largest at a few tens of KiB
+const MAX_MESSAGE_LEN: usize = 256 * 1024;
+// scroll_reporter.js stops at 8 selector parts; anything far longer isn't ours
+const MAX_ANCHOR_LEN: usize = 512;
+// content_blocker.js reports at most this many matched selectors per message
+const MAX_COSMETIC_HITS: usize = 50;
+const MAX_SELECTOR_LEN: usize = 1024;
+
+/// Shortcuts the in-page bridge may forward. next-tab/prev-tab only arrive this way:
+/// RegisterHotKey can't claim Ctrl+Tab on Windows, and a global hook would steal it
+/// from every other app.
+const SHORTCUT_ACTIONS: &[&str] = &[
+    "toggle-compact", "new-tab", "close-tab", "focus-url",
+    "find", "toggle-sidebar", "bookmark", "history",
+    "command-palette", "reader-mode", "next-tab", "prev-tab",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MediaState {
+    Playing,
+    Paused,
+    Ended,
+}
+
+impl MediaState {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            MediaState::Playing => "playing",
+            MediaState::Paused => "paused",
+            MediaState::Ended => "ended",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HintState {
+    Active,
+    Empty,
+    Cancelled,
+    Activated,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TtsState {
+    Speaking,
+    Ended,
+    Stopped,
+    Error,
+    Unavailable,
+    NoReader,
+}
+
+impl TtsState {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TtsState::Speaking => "speaking",
+            TtsState::Ended => "ended",
+            TtsState::Stopped => "stopped",
+            TtsState::Error => "error",
+            TtsState::Unavailable => "unavailable",
+            TtsState::NoReader => "no-reader",
+        }
+    }
+}
+
+/// A cosmetic-hiding selector that matched `count` elements on the page
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CosmeticHit {
+    pub selector: String,
+    #[serde(default)]
+    pub count: u64,
+}
+
+/// Everything our injected scripts send through `chrome.webview.postMessage`, keyed by
+/// the `__bushido` field. Pages can post the same channel, so every message is
+/// untrusted: a wrong type anywhere drops the whole message.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "__bushido", rename_all = "kebab-case")]
+pub enum BushidoMessage {
+    Shortcut { action: String },
+    #[serde(rename_all = "camelCase")]
+    Media {
+        state: MediaState,
+        #[serde(default)]
+        title: String,
+        artist: Option<String>,
+        meta_title: Option<String>,
+        // NaN/Infinity arrive as null
+        current_time: Option<f64>,
+        duration: Option<f64>,
+        playback_rate: Option<f64>,
+    },
+    #[serde(rename_all = "camelCase")]
+    Video {
+        #[serde(default)]
+        has_video: bool,
+    },
+    AutoplayBlocked {
+        #[serde(default)]
+        domain: String,
+    },
+    MatchCount {
+        #[serde(default)]
+        count: u64,
+        #[serde(default)]
+        current: u64,
+    },
+    Console {
+        level: String,
+        message: Option<String>,
+        source: Option<String>,
+        line: Option<u64>,
+    },
+    #[serde(rename_all = "camelCase")]
+    ScriptResult {
+        request_id: String,
+        #[serde(default)]
+        value: String,
+    },
+    VaultCheck {
+        #[serde(default)]
+        domain: String,
+    },
+    VaultSavePrompt {
+        #[serde(default)]
+        domain: String,
+        #[serde(default)]
+        username: String,
+        #[serde(default)]
+        password: String,
+    },
+    CookieRejected,
+    Glance { url: String },
+    ReaderProgress {
+        percent: f64,
+        minutes: Option<u64>,
+    },
+    // reader_tts.js: index/total count paragraphs
+    Tts {
+        state: TtsState,
+        #[serde(default)]
+        index: u64,
+        #[serde(default)]
+        total: u64,
+        error: Option<String>,
+    },
+    Hint {
+        state: HintState,
+        #[serde(default)]
+        count: u64,
+    },
+    CosmeticProbe {
+        #[serde(default)]
+        classes: Vec<String>,
+        #[serde(default)]
+        ids: Vec<String>,
+    },
+    // hiding selectors that matched something, from content_blocker.js
+    CosmeticLog {
+        #[serde(default)]
+        url: String,
+        hits: Vec<CosmeticHit>,
+    },
+    // element_picker.js: the clicked element, None when the user cancelled
+    ElementPicked { selector: Option<String> },
+    Preload { url: String },
+    PreloadCancel,
+    FingerprintVerify { report: serde_json::Value },
+    #[serde(rename_all = "camelCase")]
+    Scroll {
+        x: f64,
+        y: f64,
+        url: String,
+        anchor: Option<String>,
+        anchor_offset: Option<f64>,
+    },
+}
+
+impl BushidoMessage {
+    /// Field checks serde can't express; also where values get cleaned before use.
+    fn validate(self) -> Option<Self> {
+        match self {
+            BushidoMessage::Shortcut { action } => {
+                SHORTCUT_ACTIONS.contains(&action.as_str()).then_some(BushidoMessage::Shortcut { action })
+            }
+            BushidoMessage::Media { state, title, artist, meta_title, current_time, duration, playback_rate } => {
+                Some(BushidoMessage::Media {
+                    state,
+                    title: title.replace(['<', '>'], ""),
+                    artist,
+                    meta_title,
+                    current_time,
+                    duration,
+                    playback_rate,
+                })
+            }
+            BushidoMessage::ReaderProgress { percent, minutes } => {
+                if !percent.is_finite() { return None; }
+                Some(BushidoMessage::ReaderProgress { percent: percent.clamp(0.0, 100.0).round(), minutes })
+            }
+            BushidoMessage::CosmeticProbe { classes, ids } => {
+                (!classes.is_empty() || !ids.is_empty()).then_some(BushidoMessage::CosmeticProbe { classes, ids })
+            }
+            BushidoMessage::CosmeticLog { url, mut hits } => {
+                hits.retain(|h| !h.selector.is_empty() && h.selector.len() <= MAX_SELECTOR_LEN && h.count > 0);
+                hits.truncate(MAX_COSMETIC_HITS);
+                (!hits.is_empty()).then_some(BushidoMessage::CosmeticLog { url, hits })
+            }
+            BushidoMessage::ElementPicked { selector } => {
+                let selector = selector.map(|s| s.trim().to_string());
+                Some(BushidoMessage::ElementPicked { selector: selector.filter(|s| !s.is_empty() && s.len() <= MAX_SELECTOR_LEN) })
+            }
+            BushidoMessage::Tts { state, index, total, error } => {
+                if index > total { return None; }
+                let error = error.map(|e| e.chars().filter(|c| c.is_ascii_alphanumeric() || *c == '-').take(64).collect());
+                Some(BushidoMessage::Tts { state, index, total, error })
+            }
+            BushidoMessage::Scroll { x, y, url, anchor, anchor_offset } => {
+                if !x.is_finite() || !y.is_finite() { return None; }
+                let anchor = anchor.filter(|a| !a.is_empty() && a.len() <= MAX_ANCHOR_LEN);
+                let anchor_offset = anchor.as_ref().and(anchor_offset.filter(|o| o.is_finite()));
+                Some(BushidoMessage::Scroll { x: x.max(0.0), y: y.max(0.0), url, anchor, anchor_offset })
+            }
+            other => Some(other),
+        }
+    }
+}
+
+/// Decode the argument of WebMessageReceived (`WebMessageAsJson`, i.e. the posted
+/// string JSON-encoded once more). None for oversized, malformed, unknown or
+/// invalid messages — including non-string posts, which aren't ours.
+pub fn parse_web_message(json: &str) -> Option<BushidoMessage> {
+    if json.len() > MAX_MESSAGE_LEN {
+        return None;
+    }
+    let raw: String = serde_json::from_str(json).ok()?;
+    serde_json::from_str::<BushidoMessage>(&raw).ok()?.validate()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(msg: serde_json::Value) -> Option<BushidoMessage> {
+        parse_web_message(&serde_json::to_string(&msg.to_string()).unwrap())
+    }
+
+    #[test]
+    fn decodes_known_messages() {
+        assert_eq!(
+            parse(serde_json::json!({"__bushido": "match-count", "count": 3, "current": 1})),
+            Some(BushidoMessage::MatchCount { count: 3, current: 1 })
+        );
+        assert_eq!(
+            parse(serde_json::json!({"__bushido": "preload-cancel", "url": "https://a.example/"})),
+            Some(BushidoMessage::PreloadCancel)
+        );
+        assert_eq!(
+            parse(serde_json::json!({"__bushido": "hint", "state": "active", "count": 7})),
+            Some(BushidoMessage::Hint { state: HintState::Active, count: 7 })
+        );
+    }
+
+    #[test]
+    fn cosmetic_log_drops_bad_hits() {
+        let long = "a".repeat(MAX_SELECTOR_LEN + 1);
+        let msg = parse(serde_json::json!({
+            "__bushido": "cosmetic-log", "url": "https://a.example/",
+            "hits": [{"selector": ".ad", "count": 2}, {"selector": "", "count": 1}, {"selector": long, "count": 1}, {"selector": "#x", "count": 0}]
+        }));
+        assert_eq!(
+            msg,
+            Some(BushidoMessage::CosmeticLog {
+                url: "https://a.example/".into(),
+                hits: vec![CosmeticHit { selector: ".ad".into(), count: 2 }],
+            })
+        );
+        assert_eq!(parse(serde_json::json!({"__bushido": "cosmetic-log", "hits": []})), None);
+    }
+
+    #[test]
+    fn element_picked_oversized_selector_reads_as_cancelled() {
+        assert_eq!(
+            parse(serde_json::json!({"__bushido": "element-picked", "selector": " #promo "})),
+            Some(BushidoMessage::ElementPicked { selector: Some("#promo".into()) })
+        );
+        let long = "a".repeat(MAX_SELECTOR_LEN + 1);
+        assert_eq!(
+            parse(serde_json::json!({"__bushido": "element-picked", "selector": long})),
+            Some(BushidoMessage::ElementPicked { selector: None })
+        );
+        assert_eq!(
+            parse(serde_json::json!({"__bushido": "element-picked", "selector": null})),
+            Some(BushidoMessage::ElementPicked { selector: None })
+        );
+    }
+
+    #[test]
+    fn media_keeps_optional_fields_and_cleans_title() {
+        let msg = parse(serde_json::json!({
+            "__bushido": "media", "state": "playing", "title": "<b>Song</b>",
+            "currentTime": 12.5, "duration": null, "artist": "X", "album": "ignored"
+        }));
+        let Some(BushidoMessage::Media { state, title, artist, current_time, duration, .. }) = msg else {
+            panic!("{:?}", msg);
+        };
+        assert_eq!(state, MediaState::Playing);
+        assert_eq!(title, "bSong/b");
+        assert_eq!(artist.as_deref(), Some("X"));
+        assert_eq!(current_time, Some(12.5));
+        assert_eq!(duration, None);
+    }
+
+    #[test]
+    fn rejects_unknown_malformed_and_invalid() {
+        assert_eq!(parse(serde_json::json!({"__bushido": "nope"})), None);
+        assert_eq!(parse(serde_json::json!({"hello": "page"})), None);
+        assert_eq!(parse(serde_json::json!({"__bushido": "match-count", "count": "many"})), None);
+        assert_eq!(parse(serde_json::json!({"__bushido": "media", "state": "buffering"})), None);
+        assert_eq!(parse(serde_json::json!({"__bushido": "shortcut", "action": "format-disk"})), None);
+        assert_eq!(parse(serde_json::json!({"__bushido": "cosmetic-probe", "classes": [], "ids": []})), None);
+        // an object posted directly rather than a JSON string
+        assert_eq!(parse_web_message(r#"{"__bushido":"activity"}"#), None);
+        assert_eq!(parse_web_message("not json"), None);
+    }
+
+    #[test]
+    fn rejects_oversized() {
+        let big = "x".repeat(MAX_MESSAGE_LEN);
+        assert_eq!(parse(serde_json::json!({"__bushido": "vault-check", "domain": big})), None);
+    }
+
+    #[test]
+    fn clamps_reader_progress() {
+        assert_eq!(
+            parse(serde_json::json!({"__bushido": "reader-progress", "percent": 140.2})),
+            Some(BushidoMessage::ReaderProgress { percent: 100.0, minutes: None })
+        );
+    }
+
+    #[test]
+    fn tts_progress_stays_in_range() {
+        assert_eq!(
+            parse(serde_json::json!({"__bushido": "tts", "state": "speaking", "index": 2, "total": 9})),
+            Some(BushidoMessage::Tts { state: TtsState::Speaking, index: 2, total: 9, error: None })
+        );
+        assert_eq!(
+            parse(serde_json::json!({"__bushido": "tts", "state": "error", "error": "<b>synthesis-failed</b>"})),
+            Some(BushidoMessage::Tts { state: TtsState::Error, index: 0, total: 0, error: Some("bsynthesis-failedb".into()) })
+        );
+        assert_eq!(parse(serde_json::json!({"__bushido": "tts", "state": "speaking", "index": 10, "total": 9})), None);
+        assert_eq!(parse(serde_json::json!({"__bushido": "tts", "state": "paused"})), None);
+    }
+
+    #[test]
+    fn scroll_drops_oversized_anchor() {
+        let url = "https://example.com/";
+        assert_eq!(
+            parse(serde_json::json!({"__bushido": "scroll", "x": 0, "y": 420, "url": url, "anchor": "#main", "anchorOffset": -8})),
+            Some(BushidoMessage::Scroll { x: 0.0, y: 420.0, url: url.into(), anchor: Some("#main".into()), anchor_offset: Some(-8.0) })
+        );
+        assert_eq!(
+            parse(serde_json::json!({"__bushido": "scroll", "x": 0, "y": 420, "url": url, "anchor": "a".repeat(MAX_ANCHOR_LEN + 1), "anchorOffset": -8})),
+            Some(BushidoMessage::Scroll { x: 0.0, y: 420.0, url: url.into(), anchor: None, anchor_offset: None })
+        );
+        assert_eq!(parse(serde_json::json!({"__bushido": "scroll", "y": 420, "url": url})), None);
+    }
+}
@@ -0,0 +1,237 @@
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::error::BushidoError;
+
+// must match GAP in splitLayout.ts
+const GAP: f64 = 4.0;
+// a snapshot is user data restored verbatim — keep it from growing a silly tree
+const MAX_PANES: usize = 16;
+const MAX_DEPTH: usize = 8;
+
+// mirrors PaneSplit / PaneLeaf in types.ts
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SplitDir {
+    Row,
+    Col,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum PaneNode {
+    #[serde(rename_all = "camelCase")]
+    Leaf { tab_id: String },
+    Split { dir: SplitDir, children: Vec<PaneChild> },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PaneChild {
+    pub pane: PaneNode,
+    pub ratio: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaneSnapshot {
+    pub tab_id: String,
+    #[serde(default)]
+    pub url: String,
+    pub x: f64,
+    pub y: f64,
+    pub w: f64,
+    pub h: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LayoutSnapshot {
+    /// split tree with ratios; None for a single tab
+    #[serde(default)]
+    pub tree: Option<PaneNode>,
+    /// rects as last laid out, plus each pane's url so missing tabs can be recreated
+    pub panes: Vec<PaneSnapshot>,
+    pub focused_tab_id: String,
+    pub sidebar_w: f64,
+    pub top_offset: f64,
+}
+
+pub struct LayoutState {
+    /// what layout_webviews last applied
+    current: Mutex<Option<LayoutSnapshot>>,
+    /// the one save_layout stored; persisted in session.json
+    pub saved: Mutex<Option<LayoutSnapshot>>,
+}
+
+impl LayoutState {
+    pub fn new() -> Self {
+        Self { current: Mutex::new(None), saved: Mutex::new(None) }
+    }
+}
+
+impl PaneNode {
+    pub fn leaf_ids(&self) -> Vec<String> {
+        match self {
+            PaneNode::Leaf { tab_id } => vec![tab_id.clone()],
+            PaneNode::Split { children, .. } => children.iter().flat_map(|c| c.pane.leaf_ids()).collect(),
+        }
+    }
+
+    fn depth(&self) -> usize {
+        match self {
+            PaneNode::Leaf { .. } => 1,
+            PaneNode::Split { children, .. } => 1 + children.iter().map(|c| c.pane.depth()).max().unwrap_or(0),
+        }
+    }
+
+    /// Port of computeRects in splitLayout.ts
+    pub fn rects(&self, x: f64, y: f64, w: f64, h: f64) -> Vec<(String, f64, f64, f64, f64)> {
+        match self {
+            PaneNode::Leaf { tab_id } => vec![(tab_id.clone(), x, y, w, h)],
+            PaneNode::Split { dir, children } => {
+                let total_gap = children.len().saturating_sub(1) as f64 * GAP;
+                let row = *dir == SplitDir::Row;
+                let available = (if row { w } else { h }) - total_gap;
+                let mut offset = if row { x } else { y };
+                let mut out = Vec::new();
+                for child in children {
+                    let size = available * child.ratio;
+                    if row {
+                        out.extend(child.pane.rects(offset, y, size, h));
+                    } else {
+                        out.extend(child.pane.rects(x, offset, w, size));
+                    }
+                    offset += size + GAP;
+                }
+                out
+            }
+        }
+    }
+}
+
+impl LayoutSnapshot {
+    fn validate(&self) -> Result<(), BushidoError> {
+        if self.panes.is_empty() || self.panes.len() > MAX_PANES {
+            return Err(BushidoError::Other(format!("layout must have 1–{} panes", MAX_PANES)));
+        }
+        if let Some(tree) = &self.tree {
+            if tree.depth() > MAX_DEPTH {
+                return Err(BushidoError::Other("layout is nested too deeply".into()));
+            }
+            let mut leaves = tree.leaf_ids();
+            let mut panes: Vec<String> = self.panes.iter().map(|p| p.tab_id.clone()).collect();
+            leaves.sort();
+            panes.sort();
+            if leaves != panes {
+                return Err(BushidoError::Other("layout tree and panes disagree".into()));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Called by layout_webviews. Empty pane lists (internal pages, overlays) aren't a layout.
+pub fn record(app: &AppHandle, tree: Option<PaneNode>, panes: Vec<PaneSnapshot>, focused_tab_id: &str, sidebar_w: f64, top_offset: f64) {
+    if panes.is_empty() { return; }
+    *app.state::<LayoutState>().current.lock() = Some(LayoutSnapshot {
+        tree,
+        panes,
+        focused_tab_id: focused_tab_id.to_string(),
+        sidebar_w,
+        top_offset,
+    });
+}
+
+/// Capture the current pane arrangement and keep it as the saved layout
+#[tauri::command]
+pub async fn save_layout(app: AppHandle) -> Result<LayoutSnapshot, BushidoError> {
+    let ls = app.state::<LayoutState>();
+    let mut snapshot = ls.current.lock().clone()
+        .ok_or_else(|| BushidoError::NotFound("nothing is laid out yet".into()))?;
+    for pane in &mut snapshot.panes {
+        if let Some(url) = app.get_webview(&pane.tab_id).and_then(|wv| wv.url().ok()) {
+            pane.url = url.to_string();
+        }
+    }
+    *ls.saved.lock() = Some(snapshot.clone());
+    Ok(snapshot)
+}
+
+#[tauri::command]
+pub async fn get_saved_layout(app: AppHandle) -> Result<Option<LayoutSnapshot>, BushidoError> {
+    Ok(app.state::<LayoutState>().saved.lock().clone())
+}
+
+/// Lay a snapshot out again. Geometry is recomputed from the tree at the current window
+/// size; tabs the UI hasn't recreated yet are returned so it can create them and re-sync.
+#[tauri::command]
+pub async fn restore_layout(app: AppHandle, snapshot: LayoutSnapshot) -> Result<Vec<String>, BushidoError> {
+    snapshot.validate()?;
+    let window = app.get_window("main").ok_or("no main window")?;
+    let size = window.inner_size()?;
+    let scale = window.scale_factor()?;
+    let content_w = (size.width as f64 / scale) - snapshot.sidebar_w;
+    let content_h = (size.height as f64 / scale) - snapshot.top_offset;
+
+    let rects: Vec<(String, f64, f64, f64, f64)> = match &snapshot.tree {
+        Some(tree) => tree.rects(0.0, 0.0, content_w, content_h),
+        None => snapshot.panes.iter().take(1).map(|p| (p.tab_id.clone(), 0.0, 0.0, content_w, content_h)).collect(),
+    };
+    let missing: Vec<String> = rects.iter()
+        .filter(|(id, ..)| app.get_webview(id).is_none())
+        .map(|(id, ..)| id.clone())
+        .collect();
+
+    let panes = rects.into_iter()
+        .map(|(tab_id, x, y, w, h)| crate::PaneRectArg { tab_id, x, y, w, h })
+        .collect();
+    crate::layout_webviews(app.clone(), panes, snapshot.focused_tab_id.clone(), snapshot.sidebar_w, snapshot.top_offset, snapshot.tree.clone()).await?;
+    *app.state::<LayoutState>().saved.lock() = Some(snapshot);
+    Ok(missing)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a PaneSplit as the UI serializes it
+    const TREE: &str = r#"{"type":"split","dir":"row","children":[
+        {"pane":{"type":"leaf","tabId":"a"},"ratio":0.5},
+        {"pane":{"type":"split","dir":"col","children":[
+            {"pane":{"type":"leaf","tabId":"b"},"ratio":0.25},
+            {"pane":{"type":"leaf","tabId":"c"},"ratio":0.75}]},"ratio":0.5}]}"#;
+
+    #[test]
+    fn computes_rects_like_the_ui() {
+        let tree: PaneNode = serde_json::from_str(TREE).unwrap();
+        assert_eq!(tree.leaf_ids(), vec!["a", "b", "c"]);
+        let rects = tree.rects(0.0, 0.0, 1004.0, 804.0);
+        assert_eq!(rects[0], ("a".to_string(), 0.0, 0.0, 500.0, 804.0));
+        assert_eq!(rects[1], ("b".to_string(), 504.0, 0.0, 500.0, 200.0));
+        assert_eq!(rects[2], ("c".to_string(), 504.0, 204.0, 500.0, 600.0));
+        // round-trips in the UI's shape
+        let back = serde_json::to_value(&tree).unwrap();
+        assert_eq!(back, serde_json::from_str::<serde_json::Value>(TREE).unwrap());
+    }
+
+    #[test]
+    fn rejects_mismatched_snapshot() {
+        let tree: PaneNode = serde_json::from_str(TREE).unwrap();
+        let pane = |id: &str| PaneSnapshot { tab_id: id.into(), url: String::new(), x: 0.0, y: 0.0, w: 1.0, h: 1.0 };
+        let mut snap = LayoutSnapshot {
+            tree: Some(tree),
+            panes: vec![pane("a"), pane("b"), pane("c")],
+            focused_tab_id: "a".into(),
+            sidebar_w: 300.0,
+            top_offset: 40.0,
+        };
+        assert!(snap.validate().is_ok());
+        snap.panes.pop();
+        assert!(snap.validate().is_err());
+        snap.tree = None;
+        assert!(snap.validate().is_ok());
+        snap.panes.clear();
+        assert!(snap.validate().is_err());
+    }
+}
@@ -1,10 +1,47 @@
 mod blocker;
+mod console_log;
+mod content;
+mod cosmetic_log;
 mod crash_log;
+mod crash_recovery;
 pub mod dns_resolver;
 mod downloads;
+mod drag_drop;
+mod element_picker;
+mod error;
+mod fingerprint;
+mod hibernate;
+mod history_retention;
+mod history_search;
+mod homepage;
 mod import;
+mod ipc;
+mod layout;
+mod media_session;
+mod net_capture;
+mod page_archive;
+mod page_monitor;
+mod permissions;
+mod print_pdf;
+mod process_manager;
+mod profiles;
+mod proxy;
+mod reader_tts;
+mod reading_list;
+mod redirect;
+mod reset;
 mod screenshot;
+mod scroll_restore;
+mod security_info;
+mod site_css;
+mod startup;
 mod sync;
+mod tab_groups;
+mod tab_window;
+mod theme;
+mod throttling;
+mod totp;
+mod user_agent;
 mod vault;
 
 #[global_allocator]
@@ -20,10 +57,114 @@ use std::fs;
 use std::path::PathBuf;
 use adblock::request::Request;
 use parking_lot::RwLock;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use error::BushidoError;
+use ipc::BushidoMessage;
 
 struct WebviewState {
     tabs: Mutex<HashMap<String, bool>>,
+    // tabs created without Bushido's injected page scripts (debugging site breakage);
+    // kept across close_tab so the recreated webview picks it up
+    injection_bypass: Mutex<HashSet<String>>,
+    // private tabs: non-persistent storage, left out of the session, recently closed and
+    // history. Like injection_bypass it outlives close_tab so discard/reload stay private.
+    private: Mutex<HashSet<String>>,
+    // when each tab was last shown (or created) — auto-hibernation picks the oldest
+    last_focus: Mutex<HashMap<String, std::time::Instant>>,
+}
+
+// tab id → zoom factor set by the user (zoom_tab / set_zoom_for_all_tabs); wins over the default
+struct ZoomState {
+    tabs: Mutex<HashMap<String, f64>>,
+}
+
+// pinned tab ids — survives close_tab (discard/reload recreate the webview), persisted via the session
+struct PinnedState {
+    tabs: Mutex<HashSet<String>>,
+}
+
+// tab id → current top-level URL: the URL the tab was created with until the first
+// navigation, then on_navigation and HistoryChanged (which also covers pushState)
+struct UrlState {
+    tabs: Mutex<HashMap<String, String>>,
+}
+
+// muted tab ids — like PinnedState, survives discard/reload so the page stays silent
+struct MuteState {
+    tabs: Mutex<HashSet<String>>,
+}
+
+// tab id → "no images" flag, shared with the tab's WebResourceRequested handler so a
+// toggle applies from the next fetch; like MuteState it survives discard/reload
+struct ImageBlockState {
+    tabs: Mutex<HashMap<String, Arc<AtomicBool>>>,
+}
+
+impl ImageBlockState {
+    fn flag(&self, id: &str) -> Arc<AtomicBool> {
+        self.tabs.lock().entry(id.to_string()).or_default().clone()
+    }
+}
+
+/// Set `window.__bushidoMuted` and (re)start the enforcing observer in the page
+fn apply_tab_mute(wv: &tauri::Webview, muted: bool) {
+    let _ = wv.eval(&format!("window.__bushidoMuted = {};{}", muted, media_session::MUTE_ENFORCE_JS));
+}
+
+const DEFAULT_MAX_TABS: usize = 50;
+const MAX_TABS_RANGE: std::ops::RangeInclusive<usize> = 10..=500;
+
+// open-tab cap enforced by create_tab; set from the "maxTabs" setting
+struct TabLimitState {
+    max: AtomicUsize,
+}
+
+const MAX_RECENTLY_CLOSED: usize = 25;
+
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ClosedTab {
+    url: String,
+    title: String,
+    workspace_id: String,
+    pinned: bool,
+    zoom: Option<f64>,
+    closed_at: u64,
+}
+
+// user-closed tabs, newest last; saved into the session as "recentlyClosed"
+struct RecentlyClosedState {
+    tabs: Mutex<VecDeque<ClosedTab>>,
+}
+
+// global "work offline" switch; downloads paused by it are resumed when it's turned off
+struct OfflineState {
+    enabled: AtomicBool,
+    paused_downloads: Mutex<Vec<String>>,
+}
+
+fn is_offline(app: &tauri::AppHandle) -> bool {
+    app.state::<OfflineState>().enabled.load(Ordering::Relaxed)
+}
+
+/// False while the ad blocker is globally paused (set_global_blocking)
+fn blocking_active(app: &tauri::AppHandle) -> bool {
+    app.state::<BlockerState>().enabled.load(Ordering::Relaxed)
+}
+
+// zoom factors the zoom commands accept; anything stored or applied is kept inside this
+const ZOOM_MIN: f64 = 0.25;
+const ZOOM_MAX: f64 = 5.0;
+
+fn clamp_zoom(factor: f64) -> f64 {
+    if factor.is_finite() { factor.clamp(ZOOM_MIN, ZOOM_MAX) } else { 1.0 }
+}
+
+fn apply_zoom(wv: &tauri::Webview, factor: f64) {
+    let _ = wv.with_webview(move |wv| {
+        #[cfg(windows)]
+        unsafe { let _ = wv.controller().SetZoomFactor(factor); }
+    });
 }
 
 struct PanelState {
@@ -34,6 +175,8 @@ struct PanelState {
 struct BlockerState {
     engine: Arc<RwLock<adblock::engine::Engine>>,
     data_dir: PathBuf,
+    // global pause — read per request, so open tabs follow it without being recreated
+    enabled: AtomicBool,
     scriptlet_preamble: String,
     cosmetic_script: String,
     cosmetic_observer_script: String,
@@ -51,25 +194,54 @@ struct WhitelistState {
     sites: Mutex<HashSet<String>>,
 }
 
+// sites that get no DNT / Sec-GPC signals (the global switch is the privacy_signals create_tab arg)
+struct PrivacySignalState {
+    exempt: Mutex<HashSet<String>>,
+}
+
+fn domain_matches(host: &str, domain: &str) -> bool {
+    host == domain || host.ends_with(&format!(".{}", domain))
+}
+
+struct AutoplayState {
+    sites: Mutex<HashMap<String, bool>>,  // domain → autoplay allowed
+    block_by_default: bool,
+    // autoplayPolicy "allow"; the other policies only let muted media start on its own
+    allow_by_default: bool,
+}
+
+impl AutoplayState {
+    /// Whether `host` may autoplay with sound: the most specific site rule, else the policy
+    fn allows(&self, host: &str) -> bool {
+        self.sites.lock().iter()
+            .filter(|(d, _)| domain_matches(host, d))
+            .max_by_key(|(d, _)| d.len())
+            .map(|(_, &allowed)| allowed)
+            .unwrap_or(self.allow_by_default)
+    }
+}
+
 struct PreloadState {
     url: Mutex<Option<String>>,
     webview_id: Mutex<Option<String>>,
     source_tab: Mutex<Option<String>>,
 }
 
-#[derive(Clone, serde::Serialize)]
-struct NetworkLogEntry {
-    url: String,
-    resource_type: String,
-    blocked: bool,
-    filter_rule: Option<String>,
-    timestamp_ms: u64,
+// exact blocked-request counts per tab for the current page (tab-blocked-count events
+// are sampled); reset when the tab navigates
+struct BlockerStatsState {
+    tabs: Mutex<HashMap<String, BlockedStats>>,
 }
 
-struct NetworkLog {
-    entries: Mutex<HashMap<String, VecDeque<NetworkLogEntry>>>,
-    enabled: AtomicBool,
-    pending: Mutex<HashMap<String, Vec<NetworkLogEntry>>>,
+#[derive(Clone, Default, serde::Serialize)]
+struct BlockedStats {
+    total: u32,
+    by_type: HashMap<String, u32>,
+}
+
+// pending eval_with_result calls: request id → result channel (filled by "script-result" IPC)
+struct ScriptResultState {
+    pending: Mutex<HashMap<String, tokio::sync::oneshot::Sender<String>>>,
 }
 
 struct KeybindingState {
@@ -77,7 +249,8 @@ struct KeybindingState {
 }
 
 // Default keybindings: (action, combo in React format "Ctrl+T")
-// next-tab/prev-tab excluded — Ctrl+Tab not capturable as global shortcut on Windows
+// next-tab/prev-tab excluded — Ctrl+Tab not capturable as global shortcut on Windows;
+// they're forwarded by shortcut_bridge.js instead
 const DEFAULT_KEYBINDINGS: &[(&str, &str)] = &[
     ("new-tab", "Ctrl+T"),
     ("close-tab", "Ctrl+W"),
@@ -147,6 +320,12 @@ fn normalize_combo(combo: &str) -> String {
         "`" => "backquote".into(),
         "[" => "bracketleft".into(),
         "]" => "bracketright".into(),
+        // media keys (KeyboardEvent.key names match the global_hotkey codes)
+        "MEDIAPLAYPAUSE" => "mediaplaypause".into(),
+        "MEDIASTOP" => "mediastop".into(),
+        "MEDIATRACKNEXT" => "mediatracknext".into(),
+        "MEDIATRACKPREVIOUS" => "mediatrackprevious".into(),
+        "AUDIOVOLUMEMUTE" => "audiovolumemute".into(),
         other => other.to_lowercase(),
     };
 
@@ -159,21 +338,360 @@ fn normalize_combo(combo: &str) -> String {
     result
 }
 
+// unanswered basic-auth prompts are cancelled after this long
+#[cfg(windows)]
+const BASIC_AUTH_TIMEOUT_SECS: u64 = 120;
+
 #[cfg(windows)]
-struct PendingPermission {
+struct PendingBasicAuth {
     deferral: webview2_com::Microsoft::Web::WebView2::Win32::ICoreWebView2Deferral,
-    args: webview2_com::Microsoft::Web::WebView2::Win32::ICoreWebView2PermissionRequestedEventArgs,
-    _tab_id: String,
+    args: webview2_com::Microsoft::Web::WebView2::Win32::ICoreWebView2BasicAuthenticationRequestedEventArgs,
+    host: String,
 }
 #[cfg(windows)]
-unsafe impl Send for PendingPermission {}
+unsafe impl Send for PendingBasicAuth {}
 #[cfg(windows)]
-unsafe impl Sync for PendingPermission {}
+unsafe impl Sync for PendingBasicAuth {}
 
-struct PermissionState {
-    saved: Mutex<HashMap<String, bool>>,
+struct BasicAuthState {
+    #[cfg(windows)]
+    pending: Arc<Mutex<HashMap<String, PendingBasicAuth>>>,
+}
+
+// unanswered certificate-error prompts are cancelled after this long
+#[cfg(windows)]
+const CERT_ERROR_TIMEOUT_SECS: u64 = 120;
+
+#[cfg(windows)]
+struct PendingCertError {
+    deferral: webview2_com::Microsoft::Web::WebView2::Win32::ICoreWebView2Deferral,
+    args: webview2_com::Microsoft::Web::WebView2::Win32::ICoreWebView2ServerCertificateErrorDetectedEventArgs,
+    tab_id: String,
+    host: String,
+    thumbprint: String,
+}
+#[cfg(windows)]
+unsafe impl Send for PendingCertError {}
+#[cfg(windows)]
+unsafe impl Sync for PendingCertError {}
+
+struct CertErrorState {
+    // "host|sha256-thumbprint" the user chose to trust
+    saved: Mutex<HashSet<String>>,
+    #[cfg(windows)]
+    pending: Arc<Mutex<HashMap<String, PendingCertError>>>,
+    /// tabs that proceeded past a cert error without `remember`; WebView2 caches the
+    /// allow for the whole session, so the profile's allows are cleared again once
+    /// their page has loaded
     #[cfg(windows)]
-    pending: Arc<Mutex<HashMap<String, PendingPermission>>>,
+    allow_once: Mutex<HashSet<String>>,
+}
+
+/// SHA-256 of the certificate's DER bytes, hex encoded
+#[cfg(windows)]
+fn cert_thumbprint(pem: &str) -> String {
+    use base64::Engine as _;
+    use sha2::{Digest, Sha256};
+    let body: String = pem.lines().filter(|l| !l.starts_with("-----")).collect();
+    let der = base64::engine::general_purpose::STANDARD.decode(body.trim())
+        .unwrap_or_else(|_| pem.as_bytes().to_vec());
+    Sha256::digest(&der).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Pull the realm out of a `WWW-Authenticate: Basic realm="..."` challenge
+#[cfg(windows)]
+fn basic_auth_realm(challenge: &str) -> String {
+    let lower = challenge.to_ascii_lowercase();
+    let Some(pos) = lower.find("realm=") else { return String::new() };
+    let rest = &challenge[pos + 6..];
+    match rest.strip_prefix('"') {
+        Some(quoted) => quoted.split('"').next().unwrap_or("").to_string(),
+        None => rest.split(',').next().unwrap_or("").trim().to_string(),
+    }
+}
+
+/// Complete a pending basic-auth deferral. `None` cancels. Returns false if it was already answered.
+#[cfg(windows)]
+fn finish_basic_auth(app: &tauri::AppHandle, request_id: &str, creds: Option<(&str, &str)>) -> bool {
+    let pending = app.state::<BasicAuthState>().pending.lock().remove(request_id);
+    let Some(pending) = pending else { return false };
+    unsafe {
+        match creds {
+            Some((user, pass)) => {
+                if let Ok(resp) = pending.args.Response() {
+                    let user_w: Vec<u16> = user.encode_utf16().chain(std::iter::once(0)).collect();
+                    let pass_w: Vec<u16> = pass.encode_utf16().chain(std::iter::once(0)).collect();
+                    let _ = resp.SetUserName(windows::core::PCWSTR::from_raw(user_w.as_ptr()));
+                    let _ = resp.SetPassword(windows::core::PCWSTR::from_raw(pass_w.as_ptr()));
+                }
+            }
+            None => { let _ = pending.args.SetCancel(true); }
+        }
+        let _ = pending.deferral.Complete();
+    }
+    true
+}
+
+/// Complete a pending certificate-error deferral, proceeding or cancelling.
+/// Returns None if it was already answered.
+#[cfg(windows)]
+fn finish_cert_error(app: &tauri::AppHandle, request_id: &str, proceed: bool) -> Option<PendingCertError> {
+    use webview2_com::Microsoft::Web::WebView2::Win32::COREWEBVIEW2_SERVER_CERTIFICATE_ERROR_ACTION;
+    let pending = app.state::<CertErrorState>().pending.lock().remove(request_id)?;
+    // 0 = always allow (this session), 1 = cancel
+    let action = COREWEBVIEW2_SERVER_CERTIFICATE_ERROR_ACTION(if proceed { 0 } else { 1 });
+    unsafe {
+        let _ = pending.args.SetAction(action);
+        let _ = pending.deferral.Complete();
+    }
+    Some(pending)
+}
+
+/// Forget the allows WebView2 keeps for certificate errors, so a one-time proceed
+/// prompts again next time. WebView2 only clears these per profile, not per tab: every
+/// tab sharing `wv`'s profile loses its one-time proceeds too and prompts again on its
+/// next error. Remembered certs are re-allowed from CertErrorState.saved when their
+/// error comes up.
+#[cfg(windows)]
+fn clear_profile_cert_allowances(wv: &tauri::Webview) {
+    let _ = wv.with_webview(move |wv| {
+        use webview2_com::Microsoft::Web::WebView2::Win32::*;
+        use windows::core::Interface;
+        unsafe {
+            let Ok(core) = wv.controller().CoreWebView2() else { return };
+            if let Ok(core14) = core.cast::<ICoreWebView2_14>() {
+                let handler = webview2_com::ClearServerCertificateErrorActionsCompletedHandler::create(
+                    Box::new(|_| Ok(()))
+                );
+                let _ = core14.ClearServerCertificateErrorActions(&handler);
+            }
+        }
+    });
+}
+
+/// Join a WebView2 cookie list into a `Cookie:` header value
+#[cfg(windows)]
+fn cookie_header(list: &webview2_com::Microsoft::Web::WebView2::Win32::ICoreWebView2CookieList) -> String {
+    let mut cookies_str = String::new();
+    let mut count = 0u32;
+    unsafe {
+        if list.Count(&mut count).is_err() { return cookies_str; }
+        for i in 0..count {
+            if let Ok(cookie) = list.GetValueAtIndex(i) {
+                let mut name_pw = windows::core::PWSTR::null();
+                let mut val_pw = windows::core::PWSTR::null();
+                if cookie.Name(&mut name_pw).is_ok() && cookie.Value(&mut val_pw).is_ok() {
+                    let name = if !name_pw.is_null() { name_pw.to_string().unwrap_or_default() } else { String::new() };
+                    let val = if !val_pw.is_null() { val_pw.to_string().unwrap_or_default() } else { String::new() };
+                    if !name.is_empty() {
+                        if !cookies_str.is_empty() { cookies_str.push_str("; "); }
+                        cookies_str.push_str(&name);
+                        cookies_str.push('=');
+                        cookies_str.push_str(&val);
+                    }
+                }
+            }
+        }
+    }
+    cookies_str
+}
+
+#[derive(Clone, serde::Serialize)]
+struct CookieInfo {
+    name: String,
+    value: String,
+    domain: String,
+    path: String,
+    secure: bool,
+    http_only: bool,
+    /// seconds since the epoch; None for session cookies
+    expires: Option<f64>,
+}
+
+#[cfg(windows)]
+fn cookie_infos(list: &webview2_com::Microsoft::Web::WebView2::Win32::ICoreWebView2CookieList) -> Vec<CookieInfo> {
+    let mut out = Vec::new();
+    let mut count = 0u32;
+    unsafe {
+        if list.Count(&mut count).is_err() { return out; }
+        let read = |f: &dyn Fn(&mut windows::core::PWSTR) -> windows::core::Result<()>| {
+            let mut pw = windows::core::PWSTR::null();
+            if f(&mut pw).is_ok() && !pw.is_null() { pw.to_string().unwrap_or_default() } else { String::new() }
+        };
+        for i in 0..count {
+            let Ok(cookie) = list.GetValueAtIndex(i) else { continue };
+            let name = read(&|pw| cookie.Name(pw));
+            if name.is_empty() { continue; }
+            let mut secure = windows_core::BOOL(0);
+            let mut http_only = windows_core::BOOL(0);
+            let mut session = windows_core::BOOL(0);
+            let mut expires = 0f64;
+            let _ = cookie.IsSecure(&mut secure);
+            let _ = cookie.IsHttpOnly(&mut http_only);
+            let _ = cookie.IsSession(&mut session);
+            let _ = cookie.Expires(&mut expires);
+            out.push(CookieInfo {
+                name,
+                value: read(&|pw| cookie.Value(pw)),
+                domain: read(&|pw| cookie.Domain(pw)),
+                path: read(&|pw| cookie.Path(pw)),
+                secure: secure.as_bool(),
+                http_only: http_only.as_bool(),
+                expires: if session.as_bool() { None } else { Some(expires) },
+            });
+        }
+    }
+    out
+}
+
+/// Run `GetCookies(url)` on a tab's cookie manager and map the list on the UI thread.
+/// The completion callback is bridged to a oneshot so callers can await it.
+#[cfg(windows)]
+async fn fetch_tab_cookies<T: Send + 'static>(
+    app: &tauri::AppHandle,
+    tab_id: &str,
+    url: &str,
+    map: fn(&webview2_com::Microsoft::Web::WebView2::Win32::ICoreWebView2CookieList) -> T,
+) -> Option<T> {
+    let wv = app.get_webview(tab_id)?;
+    let (tx, rx) = tokio::sync::oneshot::channel::<Option<T>>();
+    let url = url.to_string();
+    let _ = wv.with_webview(move |pw| {
+        use webview2_com::Microsoft::Web::WebView2::Win32::*;
+        use windows::core::Interface;
+        let _ = catch_unwind(AssertUnwindSafe(move || unsafe {
+            let mgr = match pw.controller().CoreWebView2()
+                .and_then(|c| c.cast::<ICoreWebView2_2>())
+                .and_then(|c2| c2.CookieManager())
+            {
+                Ok(m) => m,
+                Err(_) => return,
+            };
+            let url_wide: Vec<u16> = url.encode_utf16().chain(std::iter::once(0)).collect();
+            let handler = webview2_com::GetCookiesCompletedHandler::create(Box::new(
+                move |hr, cookie_list| {
+                    let list_ref = AssertUnwindSafe(&cookie_list);
+                    let mapped = catch_unwind(move || match (hr.is_ok(), list_ref.as_ref()) {
+                        (true, Some(list)) => Some(map(list)),
+                        _ => None,
+                    }).unwrap_or(None);
+                    let _ = tx.send(mapped);
+                    Ok(())
+                },
+            ));
+            let _ = mgr.GetCookies(windows::core::PCWSTR::from_raw(url_wide.as_ptr()), &handler);
+        }));
+    });
+    match tokio::time::timeout(std::time::Duration::from_secs(3), rx).await {
+        Ok(Ok(mapped)) => mapped,
+        _ => None,
+    }
+}
+
+/// Cookies a tab would send for `url`, via its WebView2 cookie manager
+async fn tab_cookies(app: &tauri::AppHandle, tab_id: &str, url: &str) -> Option<String> {
+    #[cfg(windows)]
+    { fetch_tab_cookies(app, tab_id, url, cookie_header).await.filter(|c| !c.is_empty()) }
+    #[cfg(not(windows))]
+    { let _ = (app, tab_id, url); None }
+}
+
+/// Every cookie the tab's current page would send — for the cookie inspector
+#[tauri::command]
+async fn get_cookies_for_tab(app: tauri::AppHandle, id: String) -> Result<Vec<CookieInfo>, BushidoError> {
+    let wv = app.get_webview(&id).ok_or_else(|| BushidoError::NotFound(format!("tab {}", id)))?;
+    let url = wv.url()?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Ok(Vec::new());
+    }
+    #[cfg(windows)]
+    { Ok(fetch_tab_cookies(&app, &id, url.as_str(), cookie_infos).await.unwrap_or_default()) }
+    #[cfg(not(windows))]
+    { Ok(Vec::new()) }
+}
+
+/// Clear cookies, storage and Cache API data for one site. Only `domain` itself unless
+/// `include_subdomains`; returns the number of cookies removed.
+#[tauri::command]
+async fn clear_site_data(app: tauri::AppHandle, id: String, domain: String, include_subdomains: Option<bool>) -> Result<u32, BushidoError> {
+    let domain = domain.trim().trim_start_matches('.').to_lowercase();
+    if domain.is_empty() || domain.contains(['/', ':', ' ']) {
+        return Err(BushidoError::Other(format!("invalid domain '{}'", domain)));
+    }
+    let subdomains = include_subdomains.unwrap_or(false);
+    let wv = app.get_webview(&id).ok_or_else(|| BushidoError::NotFound(format!("tab {}", id)))?;
+    let matches = |cookie_domain: &str| {
+        let d = cookie_domain.trim_start_matches('.').to_lowercase();
+        d == domain || (subdomains && domain_matches(&d, &domain))
+    };
+
+    #[cfg(windows)]
+    let removed = {
+        // empty uri = every cookie in the profile, so subdomain cookies can be found too
+        let cookies: Vec<CookieInfo> = fetch_tab_cookies(&app, &id, "", cookie_infos).await
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|c| matches(&c.domain))
+            .collect();
+        // origins to wipe storage for: the domain plus any subdomain we saw cookies for
+        let mut hosts: Vec<String> = cookies.iter().map(|c| c.domain.trim_start_matches('.').to_lowercase()).collect();
+        hosts.push(domain.clone());
+        hosts.sort();
+        hosts.dedup();
+
+        let (tx, rx) = tokio::sync::oneshot::channel::<u32>();
+        let _ = wv.with_webview(move |pw| {
+            use webview2_com::Microsoft::Web::WebView2::Win32::*;
+            use windows::core::Interface;
+            let _ = catch_unwind(AssertUnwindSafe(move || unsafe {
+                let mut removed = 0u32;
+                if let Ok(mgr) = pw.controller().CoreWebView2()
+                    .and_then(|c| c.cast::<ICoreWebView2_2>())
+                    .and_then(|c2| c2.CookieManager())
+                {
+                    for c in &cookies {
+                        let wide = |s: &str| -> Vec<u16> { s.encode_utf16().chain(std::iter::once(0)).collect() };
+                        let (name, dom, path) = (wide(&c.name), wide(&c.domain), wide(&c.path));
+                        if mgr.DeleteCookiesWithDomainAndPath(
+                            windows::core::PCWSTR::from_raw(name.as_ptr()),
+                            windows::core::PCWSTR::from_raw(dom.as_ptr()),
+                            windows::core::PCWSTR::from_raw(path.as_ptr()),
+                        ).is_ok() {
+                            removed += 1;
+                        }
+                    }
+                }
+                // localStorage, IndexedDB, Cache API etc. per origin; the HTTP cache
+                // isn't partitioned by site, so it's left alone
+                for host in &hosts {
+                    for scheme in ["https", "http"] {
+                        let params = serde_json::json!({
+                            "origin": format!("{}://{}", scheme, host),
+                            "storageTypes": "local_storage,indexeddb,cache_storage,service_workers,websql,file_systems",
+                        }).to_string();
+                        let (cdp_tx, _) = std::sync::mpsc::channel();
+                        screenshot::cdp_call(&pw, "Storage.clearDataForOrigin", &params, cdp_tx);
+                    }
+                }
+                let _ = tx.send(removed);
+            }));
+        });
+        tokio::time::timeout(std::time::Duration::from_secs(3), rx).await
+            .ok().and_then(|r| r.ok()).unwrap_or(0)
+    };
+    #[cfg(not(windows))]
+    let removed = 0u32;
+
+    // the tab's own origin also loses sessionStorage, which is per-tab and not reachable above
+    let on_site = wv.url().ok()
+        .and_then(|u| u.host_str().map(|h| h.to_lowercase()))
+        .is_some_and(|h| matches(&h));
+    if on_site {
+        let _ = wv.eval("(function(){try{localStorage.clear();sessionStorage.clear()}catch(e){}\
+            try{indexedDB.databases().then(function(l){l.forEach(function(d){indexedDB.deleteDatabase(d.name)})})}catch(e){}})()");
+    }
+    crash_log::log_info("site_data", &format!("cleared {} ({} cookies, subdomains={})", domain, removed, subdomains));
+    Ok(removed)
 }
 
 /// Trim working set of the current process — moves pages to the standby list,
@@ -240,6 +758,35 @@ async fn set_power_mode(low: bool) -> Result<(), String> {
     Ok(())
 }
 
+/// Add a user ABP-syntax filter (persisted in custom_filters.txt) and rebuild the engine
+#[tauri::command]
+async fn add_custom_filter(app: tauri::AppHandle, rule: String) -> Result<Vec<String>, String> {
+    let bs = app.state::<BlockerState>();
+    blocker::add_custom_filter(bs.data_dir.clone(), bs.engine.clone(), &rule).await
+}
+
+#[tauri::command]
+async fn remove_custom_filter(app: tauri::AppHandle, rule: String) -> Result<Vec<String>, String> {
+    let bs = app.state::<BlockerState>();
+    blocker::remove_custom_filter(bs.data_dir.clone(), bs.engine.clone(), &rule).await
+}
+
+#[tauri::command]
+async fn list_custom_filters(app: tauri::AppHandle) -> Result<Vec<String>, String> {
+    Ok(blocker::list_custom_filters(&app.state::<BlockerState>().data_dir))
+}
+
+/// Pause or resume the ad blocker everywhere. Open tabs pick it up on their next request;
+/// header stripping and privacy signals are unaffected.
+#[tauri::command]
+async fn set_global_blocking(app: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    if app.state::<BlockerState>().enabled.swap(enabled, Ordering::Relaxed) != enabled {
+        crash_log::log_info("blocker", if enabled { "blocking resumed" } else { "blocking paused" });
+        let _ = app.emit_to("main", "blocking-toggled", serde_json::json!({ "enabled": enabled }));
+    }
+    Ok(())
+}
+
 #[tauri::command]
 async fn update_filter_lists(app: tauri::AppHandle) -> Result<String, String> {
     let bs = app.state::<BlockerState>();
@@ -261,8 +808,50 @@ fn is_blocked_scheme(url: &str) -> bool {
         || lower.starts_with("ms-cxh:") || lower.starts_with("ms-cxh-full:")
 }
 
+// private tabs are never saved to the session or recorded in history
+fn is_private_tab(app: &tauri::AppHandle, id: &str) -> bool {
+    app.state::<WebviewState>().private.lock().contains(id)
+}
+
+// recorded only once create_tab succeeds, so a failed create leaves no stale mark
+fn remember_tab_mode(app: &tauri::AppHandle, id: &str, private: bool, throttling: throttling::ThrottlePolicy) {
+    if private {
+        app.state::<WebviewState>().private.lock().insert(id.to_string());
+    } else {
+        app.state::<WebviewState>().private.lock().remove(id);
+    }
+    app.state::<throttling::ThrottleState>().set(id, throttling);
+}
+
+// full-page error/notice, written over the current document (nav failures, offline mode)
+fn error_page_script(icon: &str, title: &str, desc: &str, extra: &str, code: &str) -> String {
+    format!(
+        r#"document.documentElement.innerHTML = '<head><style>*{{margin:0;padding:0;box-sizing:border-box}}body{{background:#0d0d14;color:#e0e0e8;font-family:-apple-system,BlinkMacSystemFont,system-ui,sans-serif;display:flex;align-items:center;justify-content:center;height:100vh;text-align:center;-webkit-font-smoothing:antialiased}}.wrap{{max-width:420px;padding:40px}}.icon{{font-size:48px;margin-bottom:20px;display:block;filter:grayscale(0.3);opacity:0.9}}h1{{font-size:22px;font-weight:600;margin-bottom:10px;letter-spacing:-0.3px;line-height:1.3}}p{{color:rgba(224,224,232,0.5);font-size:13.5px;line-height:1.6;margin-bottom:24px}}p strong{{color:rgba(224,224,232,0.7);font-weight:500}}button{{background:rgba(255,255,255,0.06);border:1px solid rgba(255,255,255,0.08);color:#e0e0e8;padding:9px 24px;border-radius:10px;cursor:pointer;font-size:13px;font-weight:500;font-family:inherit;transition:background 0.2s,border-color 0.2s}}button:hover{{background:rgba(255,255,255,0.1);border-color:rgba(255,255,255,0.14)}}.link{{color:rgba(99,102,241,0.8);text-decoration:none;font-size:13px;font-weight:500;transition:color 0.15s}}.link:hover{{color:rgba(99,102,241,1)}}.extra{{margin-top:8px}}.code{{color:rgba(224,224,232,0.2);font-size:10px;margin-top:20px;font-family:monospace;letter-spacing:0.5px}}</style></head><body><div class="wrap"><span class="icon">{icon}</span><h1>{title}</h1><p>{desc}</p>{extra}<div class="code">{code}</div></div></body>';"#,
+        icon = icon, title = title, desc = desc, extra = extra, code = code
+    )
+}
+
+// still reachable in offline mode: custom/internal schemes and loopback hosts (tauri serves on *.localhost)
+fn is_local_url(url: &str) -> bool {
+    let Ok(parsed) = url::Url::parse(url) else { return true };
+    if !matches!(parsed.scheme(), "http" | "https" | "ws" | "wss") {
+        return true;
+    }
+    match parsed.host() {
+        Some(url::Host::Domain(d)) => d == "localhost" || d.ends_with(".localhost"),
+        Some(url::Host::Ipv4(ip)) => ip.is_loopback(),
+        Some(url::Host::Ipv6(ip)) => ip.is_loopback(),
+        None => true,
+    }
+}
+
+/// `profile` puts the tab in a named WebView2 profile (cookies, storage, cache and
+/// permissions shared with no other profile) and takes precedence over the
+/// workspace's `profile_name`. The profile is fixed when the webview is created:
+/// moving a tab to another profile means closing it and creating it again.
+/// `proxy` routes the tab through an HTTP or SOCKS5 proxy, with the same caveat.
 #[tauri::command]
-async fn create_tab(app: tauri::AppHandle, id: String, url: String, sidebar_w: f64, top_offset: f64, https_only: bool, ad_blocker: bool, cookie_auto_reject: bool, is_panel: bool, profile_name: Option<String>, disable_dev_tools: Option<bool>, disable_status_bar: Option<bool>, disable_autofill: Option<bool>, disable_password_save: Option<bool>, block_service_workers: Option<bool>, block_font_enum: Option<bool>, spoof_hw_concurrency: Option<bool>, block_popups: Option<bool>, default_zoom: Option<f64>) -> Result<(), String> {
+async fn create_tab(app: tauri::AppHandle, id: String, url: String, sidebar_w: f64, top_offset: f64, https_only: bool, ad_blocker: bool, cookie_auto_reject: bool, is_panel: bool, profile_name: Option<String>, profile: Option<String>, proxy: Option<proxy::ProxySettings>, disable_dev_tools: Option<bool>, disable_status_bar: Option<bool>, disable_autofill: Option<bool>, disable_password_save: Option<bool>, block_service_workers: Option<bool>, block_font_enum: Option<bool>, block_popups: Option<bool>, default_zoom: Option<f64>, privacy_signals: Option<bool>, private: Option<bool>, throttling: Option<throttling::ThrottlePolicy>) -> Result<(), BushidoError> {
     crash_log::log_info("create_tab", &format!("id={} url={}", id, url));
     let disable_dev_tools = disable_dev_tools.unwrap_or(false);
     let disable_status_bar = disable_status_bar.unwrap_or(false);
@@ -270,23 +859,39 @@ async fn create_tab(app: tauri::AppHandle, id: String, url: String, sidebar_w: f
     let disable_password_save = disable_password_save.unwrap_or(false);
     let block_service_workers = block_service_workers.unwrap_or(false);
     let block_font_enum = block_font_enum.unwrap_or(false);
-    let _spoof_hw_concurrency = spoof_hw_concurrency.unwrap_or(false);
     let block_popups = block_popups.unwrap_or(false);
-    let default_zoom = default_zoom.unwrap_or(100.0);
+    let default_zoom = clamp_zoom(default_zoom.unwrap_or(100.0) / 100.0) * 100.0;
+    let privacy_signals = privacy_signals.unwrap_or(true);
+    // recreating a tab (discard, reload) without saying keeps what it was
+    let private = private.unwrap_or_else(|| is_private_tab(&app, &id));
+    // like private, an omitted policy keeps the one set before a discard/reload
+    let throttling = throttling.unwrap_or_else(|| app.state::<throttling::ThrottleState>().policy(&id));
+    let profile_name = profile.or(profile_name).map(|p| profiles::validate_profile_name(&p)).transpose()?;
+    let proxy = match proxy {
+        Some(settings) => Some(proxy::prepare(&app, &id, settings)?),
+        None => { app.state::<proxy::ProxyState>().forget_tab(&id); None }
+    };
 
-    // cap at 50 tabs to prevent resource exhaustion
+    // cap open tabs to prevent resource exhaustion
     {
+        let max = app.state::<TabLimitState>().max.load(Ordering::Relaxed);
         let ws = app.state::<WebviewState>();
         let tabs = ws.tabs.lock();
-        if tabs.len() >= 50 { return Err("Max 50 tabs".into()); }
+        if tabs.len() >= max { return Err(BushidoError::Limit(format!("Tab limit reached ({} tabs)", max))); }
     }
 
-    let window = app.get_window("main").ok_or("no main window")?;
-    let size = window.inner_size().map_err(|e| e.to_string())?;
-    let scale = window.scale_factor().map_err(|e| e.to_string())?;
+    // a tab moved to its own window is recreated there, filling it
+    let own_window = app.state::<tab_window::TabWindowState>().window_for(&id);
+    let (sidebar_w, top_offset) = if own_window.is_some() { (0.0, 0.0) } else { (sidebar_w, top_offset) };
+    let window = app.get_window(own_window.as_deref().unwrap_or("main")).ok_or("no main window")?;
+    let size = window.inner_size()?;
+    let scale = window.scale_factor()?;
     let content_w = (size.width as f64 / scale) - sidebar_w;
     let content_h = (size.height as f64 / scale) - top_offset;
 
+    // no URL means wherever the user's new tabs go
+    let url = if url.trim().is_empty() { app.state::<homepage::HomepageState>().url() } else { url };
+
     // block dangerous URL schemes
     if is_blocked_scheme(&url) {
         return Err("Blocked URL scheme".into());
@@ -294,6 +899,7 @@ async fn create_tab(app: tauri::AppHandle, id: String, url: String, sidebar_w: f
 
     // internal pages — handled by React, no webview needed
     if url.starts_with("bushido://") {
+        remember_tab_mode(&app, &id, private, throttling);
         return Ok(());
     }
 
@@ -331,7 +937,38 @@ async fn create_tab(app: tauri::AppHandle, id: String, url: String, sidebar_w: f
         .unwrap_or_default();
     let site_whitelisted = whitelist_sites.contains(&site_domain);
 
+    // per-site autoplay rules — media_listener.js reads this and decides by hostname
+    let autoplay_js = {
+        let aps = app.state::<AutoplayState>();
+        let sites = aps.sites.lock().clone();
+        format!(
+            "window.__bushidoAutoplay={};",
+            serde_json::json!({ "blockByDefault": aps.block_by_default, "sites": sites })
+        )
+    };
+    let inject_autoplay = autoplay_js.clone();
+
+    // DNT / GPC — fingerprint.js reads this to keep navigator.* in line with the headers
+    let signal_exempt: Vec<String> = app.state::<PrivacySignalState>().exempt.lock().iter().cloned().collect();
+    let signals_js = format!(
+        "window.__bushidoSignals={};",
+        serde_json::json!({ "enabled": privacy_signals, "exempt": signal_exempt })
+    );
+    let inject_signals = signals_js.clone();
+
+    // per-category fingerprint switches; the same config drives the Accept-Language header.
+    // navigator.userAgent & co. follow the UA the webview is built with
+    let tab_ua = app.state::<user_agent::UserAgentState>().for_new_tab(&id, &final_url, is_panel);
+    let fp_config = app.state::<fingerprint::FingerprintState>().config.lock().clone();
+    let fp_config_js = fp_config.script(&tab_ua);
+    let inject_fp_config = fp_config_js.clone();
+    let accept_language = fp_config.accept_language();
+
+    // vanilla mode: only the browser's own shortcut hook is injected
+    let bypass = app.state::<WebviewState>().injection_bypass.lock().contains(&id);
+
     let tab_id_nav = id.clone();
+    let private_nav = private;
     let tab_id_title = id.clone();
     let tab_id_load = id.clone();
     let tab_id_track = id.clone();
@@ -388,15 +1025,14 @@ async fn create_tab(app: tauri::AppHandle, id: String, url: String, sidebar_w: f
     let inject_security = security_js.clone();
 
     // per-tab cosmetic state: computed in on_navigation, injected in on_page_load
+    #[derive(Default)]
     struct PendingCosmetic {
         css: String,
         script: String,
         exceptions: HashSet<String>,
         generichide: bool,
     }
-    let pending_cosmetic: Arc<Mutex<PendingCosmetic>> = Arc::new(Mutex::new(PendingCosmetic {
-        css: String::new(), script: String::new(), exceptions: HashSet::new(), generichide: false,
-    }));
+    let pending_cosmetic: Arc<Mutex<PendingCosmetic>> = Arc::new(Mutex::new(PendingCosmetic::default()));
     let pending_for_nav = pending_cosmetic.clone();
     let pending_for_load = pending_cosmetic.clone();
     let pending_for_msg = pending_cosmetic.clone();
@@ -415,14 +1051,17 @@ async fn create_tab(app: tauri::AppHandle, id: String, url: String, sidebar_w: f
 
     let mut builder = WebviewBuilder::new(&id, webview_url)
         .auto_resize()
-        .with_profile_name(profile_name);
+        .with_profile_name(profile_name)
+        .incognito(private)
+        .background_throttling(throttling.to_config());
 
-    if is_panel {
-        builder = builder.user_agent("Mozilla/5.0 (Linux; Android 10; K) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/131.0.0.0 Mobile Safari/537.36");
-    } else {
-        builder = builder.user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/131.0.0.0 Safari/537.36");
+    match proxy {
+        Some((args, data_dir)) => builder = builder.additional_browser_args(&args).data_directory(data_dir),
+        None => builder = builder.additional_browser_args(app.state::<proxy::BrowserArgs>().base()),
     }
 
+    builder = builder.user_agent(&tab_ua);
+
     builder = builder.on_navigation(move |url| {
             let url_str = url.to_string();
 
@@ -431,13 +1070,48 @@ async fn create_tab(app: tauri::AppHandle, id: String, url: String, sidebar_w: f
                 return false;
             }
 
+            // work offline: cancel and show a notice in place of the page
+            if is_offline(&app_nav) && !is_local_url(&url_str) {
+                let host = url.host_str().unwrap_or_default().to_string();
+                let js = error_page_script(
+                    "&#x1F4E1;",
+                    "Working offline",
+                    &format!("Turn off work offline mode to load <strong>{}</strong>.", host),
+                    "",
+                    "OFFLINE",
+                );
+                let app_off = app_nav.clone();
+                let tab_off = tab_id_nav.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Some(wv) = app_off.get_webview(&tab_off) {
+                        let _ = wv.eval(&js);
+                    }
+                });
+                return false;
+            }
+
+            // user redirect rules + tracking-param stripping: cancel and go to the rewritten URL
+            if let Some(target) = redirect::rewrite_for_tab(&app_nav, &tab_id_nav, &url_str) {
+                let app_rw = app_nav.clone();
+                let tab_rw = tab_id_nav.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let (Some(wv), Ok(u)) = (app_rw.get_webview(&tab_rw), target.parse::<url::Url>()) {
+                        let _ = wv.navigate(u);
+                    }
+                });
+                return false;
+            }
+
             // block http (only when https-only mode is enabled)
             if nav_https_only && url_str.starts_with("http://") {
                 return false;
             }
 
             // adblock-rust engine check for document-level navigations
-            if nav_ad_blocker && !whitelisted_for_nav {
+            if nav_ad_blocker && !whitelisted_for_nav && !blocking_active(&app_nav) {
+                // paused: drop the previous page's cosmetics so ContentLoading injects nothing
+                *pending_for_nav.lock() = PendingCosmetic::default();
+            } else if nav_ad_blocker && !whitelisted_for_nav {
                 let guard = engine_for_nav.read();
                 if let Ok(req) = Request::new(&url_str, &url_str, "document") {
                     let result = guard.check_network_request(&req);
@@ -483,10 +1157,18 @@ async fn create_tab(app: tauri::AppHandle, id: String, url: String, sidebar_w: f
 
             // update source URL for third-party request classification
             *source_for_nav.lock() = url_str.clone();
+            app_nav.state::<net_capture::NetCaptureState>().clear_tab(&tab_id_nav);
+            app_nav.state::<element_picker::CosmeticExceptionState>().forget_tab(&tab_id_nav);
+            // blocked counts are per page, like the shield badge the UI zeroes on navigation
+            app_nav.state::<BlockerStatsState>().tabs.lock().remove(&tab_id_nav);
+            theme::apply_for_url(&app_nav, &tab_id_nav, &url_str);
+            app_nav.state::<UrlState>().tabs.lock().insert(tab_id_nav.clone(), url_str.clone());
 
             let _ = app_nav.emit_to("main", "tab-url-changed", serde_json::json!({
                 "id": tab_id_nav,
-                "url": url_str
+                "url": url_str,
+                // not is_private_tab: the tab is only marked once add_child returns
+                "private": private_nav
             }));
             true
         })
@@ -507,15 +1189,37 @@ async fn create_tab(app: tauri::AppHandle, id: String, url: String, sidebar_w: f
             // re-inject on every page load
             if matches!(payload.event(), tauri::webview::PageLoadEvent::Started) {
                 let _ = wv.eval(&inject_shortcut);
+                if app_load.state::<PinnedState>().tabs.lock().contains(&tab_id_load) {
+                    let _ = wv.eval("window.__bushidoPinned = true;");
+                }
+                if app_load.state::<MuteState>().tabs.lock().contains(&tab_id_load) {
+                    apply_tab_mute(&wv, true);
+                }
+                if app_load.state::<console_log::ConsoleLogState>().is_enabled(&tab_id_load) {
+                    let _ = wv.eval(console_log::CONSOLE_HOOK_SCRIPT);
+                }
+                if app_load.state::<cosmetic_log::CosmeticLogState>().is_enabled(&tab_id_load) {
+                    let _ = wv.eval(cosmetic_log::COSMETIC_LOG_SCRIPT);
+                }
+                // the user's own styles apply even with blocking or injection bypassed
+                if let Some(js) = app_load.state::<site_css::SiteCssState>().script_for_url(payload.url().as_str()) {
+                    let _ = wv.eval(&js);
+                }
+            }
+            if matches!(payload.event(), tauri::webview::PageLoadEvent::Started) && !bypass {
+                let _ = wv.eval(&inject_autoplay);
                 let _ = wv.eval(&inject_media);
+                let _ = wv.eval(&inject_signals);
+                let _ = wv.eval(&inject_fp_config);
                 let _ = wv.eval(&inject_fingerprint);
                 #[cfg(debug_assertions)]
                 { let _ = wv.eval(&inject_fp_verify); }
                 let _ = wv.eval(&inject_vault);
                 let _ = wv.eval(&inject_glance);
                 let _ = wv.eval(&inject_preload);
+                let _ = wv.eval(scroll_restore::SCROLL_REPORTER_SCRIPT);
                 // blockers only if enabled and not whitelisted
-                if load_ad_blocker && !whitelisted_for_load {
+                if load_ad_blocker && !whitelisted_for_load && blocking_active(&app_load) {
                     let _ = wv.eval(&inject_cosmetic);
 
                     // inject dynamic cosmetic resources (computed in on_navigation)
@@ -541,6 +1245,10 @@ async fn create_tab(app: tauri::AppHandle, id: String, url: String, sidebar_w: f
                     if !generichide {
                         let _ = wv.eval(&inject_cosmetic_observer);
                     }
+                    // elements the user un-hid with the picker
+                    if let Some(js) = app_load.state::<element_picker::CosmeticExceptionState>().script_for_url(payload.url().as_str()) {
+                        let _ = wv.eval(&js);
+                    }
                 }
                 if load_cookie_reject && !whitelisted_for_load {
                     let _ = wv.eval(&inject_cookie);
@@ -549,39 +1257,113 @@ async fn create_tab(app: tauri::AppHandle, id: String, url: String, sidebar_w: f
                     let _ = wv.eval(&inject_security);
                 }
             }
+            // re-apply zoom once loaded so cross-origin navigations don't reset it
+            if matches!(payload.event(), tauri::webview::PageLoadEvent::Finished) {
+                let factor = app_load.state::<ZoomState>().tabs.lock()
+                    .get(&tab_id_load).copied()
+                    .unwrap_or(default_zoom / 100.0);
+                if (factor - 1.0).abs() > 0.005 {
+                    apply_zoom(&wv, factor);
+                }
+                // Started can run before documentElement exists; the script is idempotent
+                if app_load.state::<MuteState>().tabs.lock().contains(&tab_id_load) {
+                    apply_tab_mute(&wv, true);
+                }
+                // first load of a tab from the restored session: put its scroll back
+                let restore = app_load.state::<scroll_restore::ScrollState>()
+                    .take_restore_script(&tab_id_load, payload.url().as_str());
+                if let Some(js) = restore.filter(|_| !bypass) {
+                    let _ = wv.eval(&js);
+                }
+                #[cfg(windows)]
+                if app_load.state::<CertErrorState>().allow_once.lock().remove(&tab_id_load) {
+                    clear_profile_cert_allowances(&wv);
+                }
+            }
         });
 
-    builder = builder.initialization_script(&scriptlet_preamble);
     builder = builder.initialization_script(&shortcut_script);
-    builder = builder.initialization_script(&media_script);
-    builder = builder.initialization_script(&fingerprint_script);
-    // fingerprint_verify.js is injected ONLY via on_page_load (not initialization_script)
-    // because window.chrome.webview may not be available at initialization_script time
-    builder = builder.initialization_script(&vault_script);
-    builder = builder.initialization_script(&glance_script);
-    builder = builder.initialization_script(&bs.preload_script);
-
-    // only inject cosmetic scripts if enabled and not whitelisted
-    if ad_blocker && !site_whitelisted {
-        builder = builder.initialization_script(&cosmetic_script);
-        builder = builder.initialization_script(&cosmetic_observer_script);
-    }
-    if cookie_auto_reject && !site_whitelisted {
-        builder = builder.initialization_script(&cookie_script);
-    }
-    if !security_js.is_empty() {
-        builder = builder.initialization_script(&security_js);
+    // WebView2 answers nav state over COM; elsewhere get_nav_state needs the shadow index
+    #[cfg(not(windows))]
+    { builder = builder.initialization_script(NAV_SHADOW_JS); }
+    if !bypass {
+        builder = builder.initialization_script(&scriptlet_preamble);
+        builder = builder.initialization_script(&autoplay_js);
+        builder = builder.initialization_script(&media_script);
+        builder = builder.initialization_script(&signals_js);
+        builder = builder.initialization_script(&fp_config_js);
+        builder = builder.initialization_script(&fingerprint_script);
+        // fingerprint_verify.js is injected ONLY via on_page_load (not initialization_script)
+        // because window.chrome.webview may not be available at initialization_script time
+        builder = builder.initialization_script(&vault_script);
+        builder = builder.initialization_script(&glance_script);
+        builder = builder.initialization_script(&bs.preload_script);
+
+        // only inject cosmetic scripts if enabled and not whitelisted
+        if ad_blocker && !site_whitelisted {
+            builder = builder.initialization_script(&cosmetic_script);
+            builder = builder.initialization_script(&cosmetic_observer_script);
+        }
+        if cookie_auto_reject && !site_whitelisted {
+            builder = builder.initialization_script(&cookie_script);
+        }
+        if !security_js.is_empty() {
+            builder = builder.initialization_script(&security_js);
+        }
     }
 
+    builder = builder.drag_drop_block_default(app.state::<drag_drop::DropState>().block_default.clone());
+
     let webview = window.add_child(
         builder,
         tauri::LogicalPosition::new(sidebar_w, top_offset),
         tauri::LogicalSize::new(content_w, content_h),
-    ).map_err(|e| e.to_string())?;
+    )?;
+    remember_tab_mode(&app, &id, private, throttling);
+    drag_drop::attach(&app, &tab_id_track, &webview);
 
     let state = app.state::<WebviewState>();
+    state.last_focus.lock().insert(tab_id_track.clone(), std::time::Instant::now());
+    // on_navigation may already have run for the initial load
+    app.state::<UrlState>().tabs.lock().entry(tab_id_track.clone()).or_insert_with(|| final_url.clone());
     state.tabs.lock().insert(tab_id_track, true);
 
+    // InPrivate needs a runtime with ICoreWebView2ControllerOptions; older ones get a
+    // normal controller from wry. The tab still stays out of the session and history,
+    // but its cookies would be kept, so say so.
+    #[cfg(windows)]
+    if private {
+        let app_priv = app.clone();
+        let tab_priv = id.clone();
+        let _ = webview.with_webview(move |wv| {
+            use webview2_com::Microsoft::Web::WebView2::Win32::*;
+            use windows::core::Interface;
+            let in_private = unsafe {
+                wv.controller().CoreWebView2().ok()
+                    .and_then(|core| core.cast::<ICoreWebView2_13>().ok())
+                    .and_then(|core13| core13.Profile().ok())
+                    .map(|profile| {
+                        let mut on = windows_core::BOOL::default();
+                        let _ = profile.IsInPrivateModeEnabled(&mut on);
+                        on.as_bool()
+                    })
+                    .unwrap_or(false)
+            };
+            if !in_private {
+                crash_log::log_warn("create_tab", &format!("WebView2 runtime has no InPrivate mode; tab {} keeps site data", tab_priv));
+                let _ = app_priv.emit_to("main", "private-tab-unsupported", serde_json::json!({ "id": tab_priv }));
+            }
+        });
+    }
+
+    // WebKitGTK permission prompts, through the same flow as WebView2's PermissionRequested below
+    #[cfg(target_os = "linux")]
+    {
+        let app_perm = app.clone();
+        let tab_perm = id.clone();
+        let _ = webview.with_webview(move |wv| permissions::attach(&app_perm, &tab_perm, &wv.inner()));
+    }
+
     // intercept downloads + ad blocking via WebView2 COM API
     #[cfg(windows)]
     {
@@ -591,6 +1373,7 @@ async fn create_tab(app: tauri::AppHandle, id: String, url: String, sidebar_w: f
         let tab_id_block = id.clone();
         let block_enabled = ad_blocker && !site_whitelisted;
         let source_url = current_source_url.clone();
+        let images_off = app.state::<ImageBlockState>().flag(&id);
 
         let wv_tab_id = id.clone();
         let with_result = webview.with_webview(move |wv| {
@@ -676,30 +1459,10 @@ async fn create_tab(app: tauri::AppHandle, id: String, url: String, sidebar_w: f
                                             let url_c = AssertUnwindSafe(&url_clone);
                                             let fname_c = AssertUnwindSafe(&filename_clone);
                                             let _ = catch_unwind(move || {
-                                                let mut cookies_str = String::new();
-                                                if hr_ref.is_ok() {
-                                                    if let Some(ref list) = *list_ref {
-                                                        let mut count = 0u32;
-                                                        if list.Count(&mut count).is_ok() {
-                                                            for i in 0..count {
-                                                                if let Ok(cookie) = list.GetValueAtIndex(i) {
-                                                                    let mut name_pw = windows::core::PWSTR::null();
-                                                                    let mut val_pw = windows::core::PWSTR::null();
-                                                                    if cookie.Name(&mut name_pw).is_ok() && cookie.Value(&mut val_pw).is_ok() {
-                                                                        let name = if !name_pw.is_null() { name_pw.to_string().unwrap_or_default() } else { String::new() };
-                                                                        let val = if !val_pw.is_null() { val_pw.to_string().unwrap_or_default() } else { String::new() };
-                                                                        if !name.is_empty() {
-                                                                            if !cookies_str.is_empty() { cookies_str.push_str("; "); }
-                                                                            cookies_str.push_str(&name);
-                                                                            cookies_str.push('=');
-                                                                            cookies_str.push_str(&val);
-                                                                        }
-                                                                    }
-                                                                }
-                                                            }
-                                                        }
-                                                    }
-                                                }
+                                                let cookies_str = match (hr_ref.is_ok(), list_ref.as_ref()) {
+                                                    (true, Some(list)) => cookie_header(list),
+                                                    _ => String::new(),
+                                                };
                                                 let cookies_opt = if cookies_str.is_empty() { serde_json::Value::Null } else { serde_json::Value::String(cookies_str) };
                                                 let _ = app_c.emit_to("main", "download-intercepted", serde_json::json!({
                                                     "url": *url_c,
@@ -737,7 +1500,9 @@ async fn create_tab(app: tauri::AppHandle, id: String, url: String, sidebar_w: f
                     let app_block = app_for_block.clone();
                     let tab_block = tab_id_block.clone();
                     let source = source_url.clone();
-                    let blocked_count = std::sync::Arc::new(std::sync::atomic::AtomicU32::new(0));
+                    let signals_enabled = privacy_signals;
+                    let accept_language_block = accept_language.clone();
+                    let images_off_block = images_off.clone();
 
                     let block_handler = webview2_com::WebResourceRequestedEventHandler::create(Box::new(
                         move |_sender, args| {
@@ -746,11 +1511,63 @@ async fn create_tab(app: tauri::AppHandle, id: String, url: String, sidebar_w: f
                             let app_ref = AssertUnwindSafe(&app_block);
                             let tab_ref = AssertUnwindSafe(&tab_block);
                             let source_ref = AssertUnwindSafe(&source);
-                            let count_ref = AssertUnwindSafe(&blocked_count);
+                            let accept_language_ref = &accept_language_block;
+                            let images_off_ref = &images_off_block;
                             let _ = catch_unwind(move || {
                                 if let Some(args) = args_ref.as_ref() {
                                     let request = match args.Request() { Ok(r) => r, Err(_) => return };
 
+                                    // request log (HAR capture, network panel)
+                                    if app_ref.state::<net_capture::NetCaptureState>().records(&tab_ref) {
+                                        let mut uri = windows::core::PWSTR::null();
+                                        let mut method = windows::core::PWSTR::null();
+                                        let url = if request.Uri(&mut uri).is_ok() && !uri.is_null() { uri.to_string().unwrap_or_default() } else { String::new() };
+                                        let method = if request.Method(&mut method).is_ok() && !method.is_null() { method.to_string().unwrap_or_default() } else { "GET".to_string() };
+                                        let mut ctx = COREWEBVIEW2_WEB_RESOURCE_CONTEXT_ALL;
+                                        let _ = args.ResourceContext(&mut ctx);
+                                        // as the page sent them, before the header stripping below
+                                        let mut headers = Vec::new();
+                                        if let Ok(it) = request.Headers().and_then(|h| h.GetIterator()) {
+                                            let mut has = windows_core::BOOL::default();
+                                            let _ = it.HasCurrentHeader(&mut has);
+                                            while has == true {
+                                                let mut name = windows::core::PWSTR::null();
+                                                let mut value = windows::core::PWSTR::null();
+                                                if it.GetCurrentHeader(&mut name, &mut value).is_ok() && !name.is_null() && !value.is_null() {
+                                                    headers.push((name.to_string().unwrap_or_default(), value.to_string().unwrap_or_default()));
+                                                }
+                                                if it.MoveNext(&mut has).is_err() { break; }
+                                            }
+                                        }
+                                        net_capture::record_request(&app_ref, &tab_ref, &method, &url, blocker::resource_type_str(ctx.0 as u32), headers);
+                                    }
+
+                                    // work offline: only local/custom schemes get through
+                                    if is_offline(&app_ref) {
+                                        let mut uri = windows::core::PWSTR::null();
+                                        let url = if request.Uri(&mut uri).is_ok() && !uri.is_null() { uri.to_string().unwrap_or_default() } else { String::new() };
+                                        if !is_local_url(&url) {
+                                            let blank: Vec<u16> = "about:blank\0".encode_utf16().collect();
+                                            let _ = request.SetUri(windows::core::PCWSTR::from_raw(blank.as_ptr()));
+                                            net_capture::record_blocked(&app_ref, &tab_ref, &url, Some("offline"));
+                                            return;
+                                        }
+                                    }
+
+                                    // per-tab "no images" mode for metered connections
+                                    if images_off_ref.load(Ordering::Relaxed) {
+                                        let mut ctx = COREWEBVIEW2_WEB_RESOURCE_CONTEXT_ALL;
+                                        let _ = args.ResourceContext(&mut ctx);
+                                        if blocker::resource_type_str(ctx.0 as u32) == "image" {
+                                            let mut uri = windows::core::PWSTR::null();
+                                            let url = if request.Uri(&mut uri).is_ok() && !uri.is_null() { uri.to_string().unwrap_or_default() } else { String::new() };
+                                            let blank: Vec<u16> = "about:blank\0".encode_utf16().collect();
+                                            let _ = request.SetUri(windows::core::PCWSTR::from_raw(blank.as_ptr()));
+                                            net_capture::record_blocked(&app_ref, &tab_ref, &url, Some("images off"));
+                                            return;
+                                        }
+                                    }
+
                                     // always-on: strip tracking headers
                                     if let Ok(headers) = request.Headers() {
                                         for h in ["Sec-CH-UA", "Sec-CH-UA-Arch", "Sec-CH-UA-Bitness",
@@ -769,14 +1586,23 @@ async fn create_tab(app: tauri::AppHandle, id: String, url: String, sidebar_w: f
                                         // normalize Accept-Language to match JS navigator.language spoof
                                         {
                                             let al_name: Vec<u16> = "Accept-Language\0".encode_utf16().collect();
-                                            let al_val: Vec<u16> = "en-US,en;q=0.9\0".encode_utf16().collect();
+                                            let al_val: Vec<u16> = accept_language_ref.encode_utf16().chain(std::iter::once(0)).collect();
                                             let _ = headers.SetHeader(
                                                 windows::core::PCWSTR::from_raw(al_name.as_ptr()),
                                                 windows::core::PCWSTR::from_raw(al_val.as_ptr()),
                                             );
                                         }
-                                        // DNT + GPC headers (research/18 — must match JS navigator.doNotTrack)
-                                        {
+                                        // DNT + GPC headers (research/18 — must match JS navigator.doNotTrack).
+                                        // Decided by the tab's top-level site so every request of a page agrees.
+                                        let send_signals = signals_enabled && {
+                                            let top = source_ref.lock().clone();
+                                            let host = url::Url::parse(&top).ok()
+                                                .and_then(|u| u.host_str().map(|h| h.to_lowercase()))
+                                                .unwrap_or_default();
+                                            !app_ref.state::<PrivacySignalState>().exempt.lock()
+                                                .iter().any(|d| domain_matches(&host, d))
+                                        };
+                                        if send_signals {
                                             let dnt_name: Vec<u16> = "DNT\0".encode_utf16().collect();
                                             let dnt_val: Vec<u16> = "1\0".encode_utf16().collect();
                                             let _ = headers.SetHeader(
@@ -816,7 +1642,7 @@ async fn create_tab(app: tauri::AppHandle, id: String, url: String, sidebar_w: f
                                     }
 
                                     // conditional: adblock engine check
-                                    if block_enabled {
+                                    if block_enabled && blocking_active(&app_ref) {
                                         let mut uri = windows::core::PWSTR::null();
                                         if request.Uri(&mut uri).is_err() { return; }
                                         let url = if !uri.is_null() { uri.to_string().unwrap_or_default() } else { return; };
@@ -828,7 +1654,8 @@ async fn create_tab(app: tauri::AppHandle, id: String, url: String, sidebar_w: f
                                         // strip tracking params from ALL requests (research/18 B.2)
                                         // not just documents — sub-resources (images, scripts, XHR) also carry
                                         // tracking params like ?fbclid=, ?_ga=, etc.
-                                        if let Some(clean) = blocker::strip_tracking_params(&url) {
+                                        let strip = app_ref.state::<redirect::RedirectState>().strips_tracking();
+                                        if let Some(clean) = blocker::strip_tracking_params(&url).filter(|_| strip) {
                                             let new_url: Vec<u16> = format!("{}\0", clean).encode_utf16().collect();
                                             let _ = request.SetUri(windows::core::PCWSTR::from_raw(new_url.as_ptr()));
                                         }
@@ -837,25 +1664,8 @@ async fn create_tab(app: tauri::AppHandle, id: String, url: String, sidebar_w: f
                                         if let Ok(req) = Request::new(&url, &current_source, rtype) {
                                             let result = engine_ref.read().check_network_request(&req);
 
-                                            // network log — only when enabled
-                                            {
-                                                let nl = app_ref.state::<NetworkLog>();
-                                                if nl.enabled.load(Ordering::Relaxed) {
-                                                    let entry = NetworkLogEntry {
-                                                        url: url.clone(),
-                                                        resource_type: rtype.to_string(),
-                                                        blocked: result.matched,
-                                                        filter_rule: if result.matched { result.filter.clone() } else { None },
-                                                        timestamp_ms: std::time::SystemTime::now()
-                                                            .duration_since(std::time::UNIX_EPOCH)
-                                                            .unwrap_or_default()
-                                                            .as_millis() as u64,
-                                                    };
-                                                    nl.pending.lock()
-                                                        .entry(tab_ref.clone())
-                                                        .or_insert_with(Vec::new)
-                                                        .push(entry);
-                                                }
+                                            if result.matched {
+                                                net_capture::record_blocked(&app_ref, &tab_ref, &url, result.filter.as_deref());
                                             }
 
                                             if !result.matched {
@@ -874,7 +1684,14 @@ async fn create_tab(app: tauri::AppHandle, id: String, url: String, sidebar_w: f
                                                     let _ = request.SetUri(windows::core::PCWSTR::from_raw(blank.as_ptr()));
                                                 }
 
-                                                let count = count_ref.fetch_add(1, std::sync::atomic::Ordering::Relaxed) + 1;
+                                                let count = {
+                                                    let bs = app_ref.state::<BlockerStatsState>();
+                                                    let mut tabs = bs.tabs.lock();
+                                                    let stats = tabs.entry(tab_ref.to_string()).or_default();
+                                                    stats.total += 1;
+                                                    *stats.by_type.entry(rtype.to_string()).or_insert(0) += 1;
+                                                    stats.total
+                                                };
                                                 if count <= 3 || count % 5 == 0 {
                                                     let _ = app_ref.emit_to("main", "tab-blocked-count", serde_json::json!({
                                                         "id": *tab_ref,
@@ -894,6 +1711,35 @@ async fn create_tab(app: tauri::AppHandle, id: String, url: String, sidebar_w: f
                     let _ = core.add_WebResourceRequested(&block_handler, &mut block_token);
                 }
 
+                // response side of network capture: status + timing
+                if let Ok(core2) = core.cast::<ICoreWebView2_2>() {
+                    let app_resp = app_for_block.clone();
+                    let tab_resp = tab_id_block.clone();
+                    let resp_handler = webview2_com::WebResourceResponseReceivedEventHandler::create(Box::new(
+                        move |_sender, args| {
+                            let args_ref = AssertUnwindSafe(&args);
+                            let app_ref = AssertUnwindSafe(&app_resp);
+                            let tab_ref = AssertUnwindSafe(&tab_resp);
+                            let _ = catch_unwind(move || {
+                                if !app_ref.state::<net_capture::NetCaptureState>().records(&tab_ref) { return; }
+                                let Some(args) = args_ref.as_ref() else { return };
+                                let (Ok(request), Ok(response)) = (args.Request(), args.Response()) else { return };
+                                let mut uri = windows::core::PWSTR::null();
+                                let mut method = windows::core::PWSTR::null();
+                                if request.Uri(&mut uri).is_err() || uri.is_null() { return; }
+                                let url = uri.to_string().unwrap_or_default();
+                                let method = if request.Method(&mut method).is_ok() && !method.is_null() { method.to_string().unwrap_or_default() } else { "GET".to_string() };
+                                let mut status = 0i32;
+                                let _ = response.StatusCode(&mut status);
+                                net_capture::record_response(&app_ref, &tab_ref, &method, &url, status.clamp(0, u16::MAX as i32) as u16);
+                            });
+                            Ok(())
+                        },
+                    ));
+                    let mut resp_token: i64 = 0;
+                    let _ = core2.add_WebResourceResponseReceived(&resp_handler, &mut resp_token);
+                }
+
                 // postMessage IPC handler — replaces title encoding
                 let app_msg = app_for_block.clone();
                 let tab_id_msg = tab_id_block.clone();
@@ -915,60 +1761,87 @@ async fn create_tab(app: tauri::AppHandle, id: String, url: String, sidebar_w: f
                                 let json_str = if !msg_pwstr.is_null() {
                                     msg_pwstr.to_string().unwrap_or_default()
                                 } else { return; };
-                                let raw: String = serde_json::from_str(&json_str).unwrap_or_default();
-                                let msg: serde_json::Value = serde_json::from_str(&raw).unwrap_or_default();
-
-                                match msg.get("__bushido").and_then(|v| v.as_str()) {
-                                    Some("shortcut") => {
-                                        let valid = ["toggle-compact","new-tab","close-tab","focus-url",
-                                                     "find","toggle-sidebar","bookmark","history",
-                                                     "command-palette","reader-mode"];
-                                        if let Some(action) = msg.get("action").and_then(|v| v.as_str()) {
-                                            if valid.contains(&action) {
-                                                let _ = app_ref.emit_to("main", "global-shortcut", action);
-                                            }
-                                        }
+                                let Some(msg) = ipc::parse_web_message(&json_str) else { return; };
+
+                                match msg {
+                                    BushidoMessage::Shortcut { action } => {
+                                        // in-page capture only fires while a tab has focus
+                                        let _ = app_ref.emit_to("main", "global-shortcut", action);
                                     }
-                                    Some("media") => {
-                                        if let Some(state) = msg.get("state").and_then(|v| v.as_str()) {
-                                            if !matches!(state, "playing" | "paused" | "ended") { return; }
-                                            let title = msg.get("title").and_then(|v| v.as_str()).unwrap_or("");
-                                            let clean = title.replace(|c: char| c == '<' || c == '>', "");
-                                            let mut payload = serde_json::json!({
-                                                "id": *tab_ref, "state": state, "title": clean
-                                            });
-                                            if let Some(artist) = msg.get("artist").and_then(|v| v.as_str()) {
-                                                payload["artist"] = serde_json::json!(artist);
-                                            }
-                                            if let Some(meta_title) = msg.get("metaTitle").and_then(|v| v.as_str()) {
-                                                payload["metaTitle"] = serde_json::json!(meta_title);
-                                            }
-                                            if let Some(ct) = msg.get("currentTime").and_then(|v| v.as_f64()) {
-                                                payload["currentTime"] = serde_json::json!(ct);
-                                            }
-                                            if let Some(dur) = msg.get("duration").and_then(|v| v.as_f64()) {
-                                                payload["duration"] = serde_json::json!(dur);
-                                            }
-                                            if let Some(rate) = msg.get("playbackRate").and_then(|v| v.as_f64()) {
-                                                payload["playbackRate"] = serde_json::json!(rate);
-                                            }
-                                            let _ = app_ref.emit_to("main", "tab-media-state", payload);
+                                    BushidoMessage::Media { state, title, artist, meta_title, current_time, duration, playback_rate } => {
+                                        let mut payload = serde_json::json!({
+                                            "id": *tab_ref, "state": state.as_str(), "title": title
+                                        });
+                                        if let Some(artist) = artist {
+                                            payload["artist"] = serde_json::json!(artist);
+                                        }
+                                        if let Some(meta_title) = meta_title {
+                                            payload["metaTitle"] = serde_json::json!(meta_title);
+                                        }
+                                        if let Some(ct) = current_time {
+                                            payload["currentTime"] = serde_json::json!(ct);
+                                        }
+                                        if let Some(dur) = duration {
+                                            payload["duration"] = serde_json::json!(dur);
                                         }
+                                        if let Some(rate) = playback_rate {
+                                            payload["playbackRate"] = serde_json::json!(rate);
+                                        }
+                                        media_session::on_media_state(&app_ref, &tab_ref, state.as_str(), &title);
+                                        let _ = app_ref.emit_to("main", "tab-media-state", payload);
                                     }
-                                    Some("video") => {
-                                        let has = msg.get("hasVideo").and_then(|v| v.as_bool()).unwrap_or(false);
+                                    BushidoMessage::Video { has_video } => {
                                         let _ = app_ref.emit_to("main", "tab-has-video", serde_json::json!({
-                                            "id": *tab_ref, "hasVideo": has
+                                            "id": *tab_ref, "hasVideo": has_video
+                                        }));
+                                    }
+                                    BushidoMessage::AutoplayBlocked { domain } => {
+                                        let _ = app_ref.emit_to("main", "media-blocked", serde_json::json!({
+                                            "id": *tab_ref, "domain": domain
                                         }));
                                     }
-                                    Some("match-count") => {
-                                        let count = msg.get("count").and_then(|v| v.as_u64()).unwrap_or(0);
+                                    BushidoMessage::MatchCount { count, current } => {
                                         let _ = app_ref.emit_to("main", "match-count", serde_json::json!({
-                                            "id": *tab_ref, "count": count
+                                            "id": *tab_ref, "count": count, "current": current
                                         }));
                                     }
-                                    Some("vault-check") => {
-                                        let domain = msg.get("domain").and_then(|v| v.as_str()).unwrap_or("").to_string();
+                                    BushidoMessage::Console { level, message, source, line } => {
+                                        console_log::record(
+                                            &app_ref,
+                                            tab_ref.as_str(),
+                                            &level,
+                                            message.as_deref().unwrap_or(""),
+                                            source.as_deref().unwrap_or(""),
+                                            line.unwrap_or(0),
+                                        );
+                                    }
+                                    BushidoMessage::CosmeticLog { url, hits } => {
+                                        cosmetic_log::record(&app_ref, tab_ref.as_str(), &url, hits);
+                                    }
+                                    BushidoMessage::ElementPicked { selector } => {
+                                        if !app_ref.state::<element_picker::CosmeticExceptionState>().finish_pick(tab_ref.as_str()) { return; }
+                                        // the frame's own idea of its URL, not what the page claims
+                                        let mut source = windows::core::PWSTR::null();
+                                        let page_url = match *sender_ref {
+                                            Some(ref s) => unsafe { s.Source(&mut source).ok().and_then(|_| source.to_string().ok()) },
+                                            None => None,
+                                        }.unwrap_or_default();
+                                        let domain = url::Url::parse(&page_url).ok()
+                                            .and_then(|u| u.host_str().map(|h| h.trim_start_matches("www.").to_lowercase()));
+                                        let _ = app_ref.emit_to("main", "element-picked", serde_json::json!({
+                                            "id": *tab_ref,
+                                            "selector": selector,
+                                            "domain": domain
+                                        }));
+                                    }
+                                    BushidoMessage::ScriptResult { request_id, value } => {
+                                        let srs = app_ref.state::<ScriptResultState>();
+                                        let tx = srs.pending.lock().remove(&request_id);
+                                        if let Some(tx) = tx {
+                                            let _ = tx.send(value);
+                                        }
+                                    }
+                                    BushidoMessage::VaultCheck { domain } => {
                                         let tab_id = tab_ref.to_string();
                                         let app_clone = (*app_ref).clone();
                                         std::thread::spawn(move || {
@@ -997,37 +1870,55 @@ async fn create_tab(app: tauri::AppHandle, id: String, url: String, sidebar_w: f
                                             }
                                         });
                                     }
-                                    Some("vault-save-prompt") => {
-                                        let domain = msg.get("domain").and_then(|v| v.as_str()).unwrap_or("").to_string();
-                                        let username = msg.get("username").and_then(|v| v.as_str()).unwrap_or("").to_string();
-                                        let password = msg.get("password").and_then(|v| v.as_str()).unwrap_or("").to_string();
+                                    BushidoMessage::VaultSavePrompt { domain, username, password } => {
                                         let _ = app_ref.emit_to("main", "vault-save-prompt", serde_json::json!({
                                             "domain": domain, "username": username, "password": password
                                         }));
                                     }
-                                    Some("cookie-rejected") => {
+                                    BushidoMessage::CookieRejected => {
                                         let _ = app_ref.emit_to("main", "cookie-rejected", serde_json::json!({
                                             "id": *tab_ref
                                         }));
                                     }
-                                    Some("glance") => {
-                                        if let Some(url) = msg.get("url").and_then(|v| v.as_str()) {
-                                            let _ = app_ref.emit_to("main", "glance-request", serde_json::json!({
-                                                "url": url,
-                                                "sourceTabId": *tab_ref
-                                            }));
+                                    BushidoMessage::Scroll { x, y, url, anchor, anchor_offset } => {
+                                        app_ref.state::<scroll_restore::ScrollState>().record(
+                                            &tab_ref,
+                                            scroll_restore::ScrollPos { url, x, y, anchor, anchor_offset },
+                                        );
+                                    }
+                                    BushidoMessage::Glance { url } => {
+                                        let _ = app_ref.emit_to("main", "glance-request", serde_json::json!({
+                                            "url": url,
+                                            "sourceTabId": *tab_ref
+                                        }));
+                                    }
+                                    BushidoMessage::ReaderProgress { percent, minutes } => {
+                                        let mut payload = serde_json::json!({
+                                            "id": *tab_ref,
+                                            "percent": percent as u32
+                                        });
+                                        if let Some(minutes) = minutes {
+                                            payload["minutes"] = minutes.into();
                                         }
+                                        let _ = app_ref.emit_to("main", "reader-progress", payload);
                                     }
-                                    Some("cosmetic-probe") => {
-                                        let classes: Vec<String> = msg.get("classes")
-                                            .and_then(|v| v.as_array())
-                                            .map(|arr| arr.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect())
-                                            .unwrap_or_default();
-                                        let ids: Vec<String> = msg.get("ids")
-                                            .and_then(|v| v.as_array())
-                                            .map(|arr| arr.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect())
-                                            .unwrap_or_default();
-                                        if classes.is_empty() && ids.is_empty() { return; }
+                                    BushidoMessage::Tts { state, index, total, error } => {
+                                        let _ = app_ref.emit_to("main", "reader-tts", serde_json::json!({
+                                            "id": *tab_ref,
+                                            "state": state.as_str(),
+                                            "index": index,
+                                            "total": total,
+                                            "error": error
+                                        }));
+                                    }
+                                    BushidoMessage::Hint { state, count } => {
+                                        let _ = app_ref.emit_to("main", "link-hints", serde_json::json!({
+                                            "id": *tab_ref,
+                                            "state": state,
+                                            "count": count
+                                        }));
+                                    }
+                                    BushidoMessage::CosmeticProbe { classes, ids } => {
                                         let exceptions = {
                                             pending_ref.lock().exceptions.clone()
                                         };
@@ -1049,61 +1940,60 @@ async fn create_tab(app: tauri::AppHandle, id: String, url: String, sidebar_w: f
                                             }
                                         }
                                     }
-                                    Some("preload") => {
-                                        if let Some(url) = msg.get("url").and_then(|v| v.as_str()) {
-                                            let url = url.to_string();
-                                            let tab_id = tab_ref.to_string();
-                                            let app_clone = (*app_ref).clone();
-                                            std::thread::spawn(move || {
-                                                let old_id = {
-                                                    let ps = app_clone.state::<PreloadState>();
-                                                    let val = ps.webview_id.lock().take();
-                                                    val
-                                                };
-                                                if let Some(old_id) = old_id {
-                                                    let panel_s = app_clone.state::<PanelState>();
-                                                    panel_s.ids.lock().remove(&old_id);
-                                                    if let Some(wv) = app_clone.get_webview(&old_id) {
-                                                        let _ = wv.close();
-                                                    }
+                                    BushidoMessage::Preload { url } => {
+                                        let tab_id = tab_ref.to_string();
+                                        let app_clone = (*app_ref).clone();
+                                        std::thread::spawn(move || {
+                                            let old_id = {
+                                                let ps = app_clone.state::<PreloadState>();
+                                                let val = ps.webview_id.lock().take();
+                                                val
+                                            };
+                                            if let Some(old_id) = old_id {
+                                                let panel_s = app_clone.state::<PanelState>();
+                                                panel_s.ids.lock().remove(&old_id);
+                                                if let Some(wv) = app_clone.get_webview(&old_id) {
+                                                    let _ = wv.close();
                                                 }
-                                                let preload_id = format!("preload-{}", std::time::SystemTime::now()
-                                                    .duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_millis());
-                                                let window = match app_clone.get_window("main") {
-                                                    Some(w) => w,
-                                                    None => return,
-                                                };
-                                                let webview_url = match url.parse::<url::Url>() {
-                                                    Ok(u) => tauri::WebviewUrl::External(u),
-                                                    Err(_) => return,
-                                                };
-                                                {
-                                                    let bs = app_clone.state::<BlockerState>();
-                                                    let mut builder = tauri::WebviewBuilder::new(&preload_id, webview_url);
-                                                    builder = builder.initialization_script(&bs.shortcut_script);
-                                                    builder = builder.initialization_script(&bs.fingerprint_script);
-                                                    match window.add_child(
-                                                        builder,
-                                                        tauri::LogicalPosition::new(-9999.0, -9999.0),
-                                                        tauri::LogicalSize::new(800.0, 600.0),
-                                                    ) {
-                                                        Ok(_) => {
-                                                            let panel_s = app_clone.state::<PanelState>();
-                                                            panel_s.ids.lock().insert(preload_id.clone());
-                                                            let ps = app_clone.state::<PreloadState>();
-                                                            *ps.url.lock() = Some(url);
-                                                            *ps.webview_id.lock() = Some(preload_id);
-                                                            *ps.source_tab.lock() = Some(tab_id);
-                                                        }
-                                                        Err(e) => {
-                                                            crash_log::log_error("preload", &format!("failed to create preload webview: {}", e));
-                                                        }
+                                            }
+                                            let preload_id = format!("preload-{}", std::time::SystemTime::now()
+                                                .duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_millis());
+                                            let window = match app_clone.get_window("main") {
+                                                Some(w) => w,
+                                                None => return,
+                                            };
+                                            let webview_url = match url.parse::<url::Url>() {
+                                                Ok(u) => tauri::WebviewUrl::External(u),
+                                                Err(_) => return,
+                                            };
+                                            {
+                                                let bs = app_clone.state::<BlockerState>();
+                                                let mut builder = tauri::WebviewBuilder::new(&preload_id, webview_url)
+                                                    .additional_browser_args(app_clone.state::<proxy::BrowserArgs>().base());
+                                                builder = builder.initialization_script(&bs.shortcut_script);
+                                                builder = builder.initialization_script(&app_clone.state::<fingerprint::FingerprintState>().config.lock().script(user_agent::DESKTOP_UA));
+                                                builder = builder.initialization_script(&bs.fingerprint_script);
+                                                match window.add_child(
+                                                    builder,
+                                                    tauri::LogicalPosition::new(-9999.0, -9999.0),
+                                                    tauri::LogicalSize::new(800.0, 600.0),
+                                                ) {
+                                                    Ok(_) => {
+                                                        let panel_s = app_clone.state::<PanelState>();
+                                                        panel_s.ids.lock().insert(preload_id.clone());
+                                                        let ps = app_clone.state::<PreloadState>();
+                                                        *ps.url.lock() = Some(url);
+                                                        *ps.webview_id.lock() = Some(preload_id);
+                                                        *ps.source_tab.lock() = Some(tab_id);
+                                                    }
+                                                    Err(e) => {
+                                                        crash_log::log_error("preload", &format!("failed to create preload webview: {}", e));
                                                     }
                                                 }
-                                            });
-                                        }
+                                            }
+                                        });
                                     }
-                                    Some("preload-cancel") => {
+                                    BushidoMessage::PreloadCancel => {
                                         let app_clone = (*app_ref).clone();
                                         std::thread::spawn(move || {
                                             std::thread::sleep(std::time::Duration::from_secs(2));
@@ -1125,63 +2015,115 @@ async fn create_tab(app: tauri::AppHandle, id: String, url: String, sidebar_w: f
                                             }
                                         });
                                     }
-                                    Some("fingerprint-verify") => {
-                                        if let Some(report) = msg.get("report") {
-                                            let passed = report.get("passed").and_then(|v| v.as_u64()).unwrap_or(0);
-                                            let failed = report.get("failed").and_then(|v| v.as_u64()).unwrap_or(0);
-                                            let total = report.get("total").and_then(|v| v.as_u64()).unwrap_or(0);
-                                            let pct = report.get("percentage").and_then(|v| v.as_u64()).unwrap_or(0);
-                                            let url = report.get("url").and_then(|v| v.as_str()).unwrap_or("?");
-                                            if failed == 0 {
-                                                println!("\x1b[32m[FP-VERIFY] {}/{} passed ({}%) on {} — ALL CLEAR\x1b[0m", passed, total, pct, url);
-                                            } else {
-                                                eprintln!("\x1b[31m[FP-VERIFY] {}/{} passed, {} FAILED ({}%) on {}\x1b[0m", passed, total, failed, pct, url);
-                                                if let Some(results) = report.get("results").and_then(|v| v.as_array()) {
-                                                    for r in results {
-                                                        if r.get("pass").and_then(|v| v.as_bool()) == Some(false) {
-                                                            let name = r.get("name").and_then(|v| v.as_str()).unwrap_or("?");
-                                                            let detail = r.get("detail").and_then(|v| v.as_str()).unwrap_or("");
-                                                            eprintln!("\x1b[31m  FAIL: {} — {}\x1b[0m", name, detail);
-                                                        }
+                                    BushidoMessage::FingerprintVerify { report } => {
+                                        let passed = report.get("passed").and_then(|v| v.as_u64()).unwrap_or(0);
+                                        let failed = report.get("failed").and_then(|v| v.as_u64()).unwrap_or(0);
+                                        let total = report.get("total").and_then(|v| v.as_u64()).unwrap_or(0);
+                                        let pct = report.get("percentage").and_then(|v| v.as_u64()).unwrap_or(0);
+                                        let url = report.get("url").and_then(|v| v.as_str()).unwrap_or("?");
+                                        if failed == 0 {
+                                            println!("\x1b[32m[FP-VERIFY] {}/{} passed ({}%) on {} — ALL CLEAR\x1b[0m", passed, total, pct, url);
+                                        } else {
+                                            eprintln!("\x1b[31m[FP-VERIFY] {}/{} passed, {} FAILED ({}%) on {}\x1b[0m", passed, total, failed, pct, url);
+                                            if let Some(results) = report.get("results").and_then(|v| v.as_array()) {
+                                                for r in results {
+                                                    if r.get("pass").and_then(|v| v.as_bool()) == Some(false) {
+                                                        let name = r.get("name").and_then(|v| v.as_str()).unwrap_or("?");
+                                                        let detail = r.get("detail").and_then(|v| v.as_str()).unwrap_or("");
+                                                        eprintln!("\x1b[31m  FAIL: {} — {}\x1b[0m", name, detail);
                                                     }
                                                 }
                                             }
-                                            // Write report to disk so automated tests can read it
-                                            let report_dir = app_ref.path().app_data_dir().unwrap_or_default();
-                                            let report_path = report_dir.join("fp-verify-report.json");
-                                            let _ = std::fs::write(&report_path, serde_json::to_string_pretty(&report).unwrap_or_default());
-
-                                            let _ = app_ref.emit_to("main", "fingerprint-verify", serde_json::json!({
-                                                "id": *tab_ref,
-                                                "report": report
-                                            }));
                                         }
+                                        // Write report to disk so automated tests can read it
+                                        let report_dir = base_data_dir();
+                                        let report_path = report_dir.join("fp-verify-report.json");
+                                        let _ = std::fs::write(&report_path, serde_json::to_string_pretty(&report).unwrap_or_default());
+
+                                        let _ = app_ref.emit_to("main", "fingerprint-verify", serde_json::json!({
+                                            "id": *tab_ref,
+                                            "report": report
+                                        }));
                                     }
-                                    _ => {}
                                 }
                             }
                         });
                         Ok(())
                     },
                 ));
-
-                let mut msg_token: i64 = 0;
-                let _ = core.add_WebMessageReceived(&msg_handler, &mut msg_token);
+
+                let mut msg_token: i64 = 0;
+                let _ = core.add_WebMessageReceived(&msg_handler, &mut msg_token);
+
+                // vault auto-lock: only input WebView2 itself vouches for counts as activity
+                // (the page can't fake focus or a user-initiated navigation)
+                let app_focus = app_for_block.clone();
+                let focus_handler = webview2_com::FocusChangedEventHandler::create(Box::new(move |_sender, _args| {
+                    crate::vault::note_activity(&app_focus);
+                    Ok(())
+                }));
+                let mut focus_token: i64 = 0;
+                let _ = controller.add_GotFocus(&focus_handler, &mut focus_token);
+
+                let app_user_nav = app_for_block.clone();
+                let user_nav_handler = webview2_com::NavigationStartingEventHandler::create(Box::new(
+                    move |_sender, args: Option<ICoreWebView2NavigationStartingEventArgs>| {
+                        let Some(args) = args else { return Ok(()) };
+                        let mut user_initiated = windows_core::BOOL::default();
+                        let _ = args.IsUserInitiated(&mut user_initiated);
+                        if user_initiated == true {
+                            crate::vault::note_activity(&app_user_nav);
+                        }
+                        Ok(())
+                    },
+                ));
+                let mut user_nav_token: i64 = 0;
+                let _ = core.add_NavigationStarting(&user_nav_handler, &mut user_nav_token);
+
+                // per-site user agent: on_navigation would only get it set after the
+                // first request went out, so switch it here while the navigation waits
+                let app_ua = app_for_block.clone();
+                let tab_id_ua = tab_id_block.clone();
+                let ua_handler = webview2_com::NavigationStartingEventHandler::create(Box::new(
+                    move |sender: Option<ICoreWebView2>, args: Option<ICoreWebView2NavigationStartingEventArgs>| {
+                        let sender_ref = AssertUnwindSafe(&sender);
+                        let args_ref = AssertUnwindSafe(&args);
+                        let app_ref = AssertUnwindSafe(&app_ua);
+                        let tab_ref = AssertUnwindSafe(&tab_id_ua);
+                        let _ = catch_unwind(move || {
+                            let (Some(core), Some(args)) = (sender_ref.as_ref(), args_ref.as_ref()) else { return };
+                            let mut uri = windows::core::PWSTR::null();
+                            if args.Uri(&mut uri).is_err() || uri.is_null() { return; }
+                            let url = uri.to_string().unwrap_or_default();
+                            user_agent::apply_on_navigation(&app_ref, &tab_ref, &url, core);
+                        });
+                        Ok(())
+                    },
+                ));
+                let mut ua_token: i64 = 0;
+                let _ = core.add_NavigationStarting(&ua_handler, &mut ua_token);
 
                 // crash handler — detect renderer process failures
                 let app_crash = app_for_block.clone();
                 let tab_id_crash = tab_id_block.clone();
                 let crash_handler = webview2_com::ProcessFailedEventHandler::create(Box::new(
-                    move |_sender, _args| {
+                    move |_sender, args: Option<ICoreWebView2ProcessFailedEventArgs>| {
                         let app_ref = AssertUnwindSafe(&app_crash);
                         let tab_ref = AssertUnwindSafe(&tab_id_crash);
+                        let args_ref = AssertUnwindSafe(&args);
                         let _ = catch_unwind(move || {
+                            let mut kind = COREWEBVIEW2_PROCESS_FAILED_KIND(0);
+                            if let Some(a) = args_ref.as_ref() {
+                                let _ = a.ProcessFailedKind(&mut kind);
+                            }
                             crate::crash_log::log_error("ProcessFailed", &format!(
-                                "WebView2 renderer crashed for tab={}", *tab_ref
+                                "WebView2 renderer crashed for tab={} kind={}", *tab_ref, kind.0
                             ));
-                            let _ = app_ref.emit_to("main", "tab-crashed", serde_json::json!({
-                                "id": *tab_ref
-                            }));
+                            // a dead or hung renderer comes back on navigation; a dead
+                            // browser process takes the whole webview with it
+                            let recoverable = kind == COREWEBVIEW2_PROCESS_FAILED_KIND_RENDER_PROCESS_EXITED
+                                || kind == COREWEBVIEW2_PROCESS_FAILED_KIND_RENDER_PROCESS_UNRESPONSIVE;
+                            crash_recovery::handle_crash(&app_ref, &tab_ref, recoverable);
                         });
                         Ok(())
                     },
@@ -1267,10 +2209,7 @@ async fn create_tab(app: tauri::AppHandle, id: String, url: String, sidebar_w: f
                                     ),
                                 };
 
-                                let js = format!(
-                                    r#"document.documentElement.innerHTML = '<head><style>*{{margin:0;padding:0;box-sizing:border-box}}body{{background:#0d0d14;color:#e0e0e8;font-family:-apple-system,BlinkMacSystemFont,system-ui,sans-serif;display:flex;align-items:center;justify-content:center;height:100vh;text-align:center;-webkit-font-smoothing:antialiased}}.wrap{{max-width:420px;padding:40px}}.icon{{font-size:48px;margin-bottom:20px;display:block;filter:grayscale(0.3);opacity:0.9}}h1{{font-size:22px;font-weight:600;margin-bottom:10px;letter-spacing:-0.3px;line-height:1.3}}p{{color:rgba(224,224,232,0.5);font-size:13.5px;line-height:1.6;margin-bottom:24px}}p strong{{color:rgba(224,224,232,0.7);font-weight:500}}button{{background:rgba(255,255,255,0.06);border:1px solid rgba(255,255,255,0.08);color:#e0e0e8;padding:9px 24px;border-radius:10px;cursor:pointer;font-size:13px;font-weight:500;font-family:inherit;transition:background 0.2s,border-color 0.2s}}button:hover{{background:rgba(255,255,255,0.1);border-color:rgba(255,255,255,0.14)}}.link{{color:rgba(99,102,241,0.8);text-decoration:none;font-size:13px;font-weight:500;transition:color 0.15s}}.link:hover{{color:rgba(99,102,241,1)}}.extra{{margin-top:8px}}.code{{color:rgba(224,224,232,0.2);font-size:10px;margin-top:20px;font-family:monospace;letter-spacing:0.5px}}</style></head><body><div class="wrap"><span class="icon">{icon}</span><h1>{title}</h1><p>{desc}</p>{extra}<div class="code">ERR_{code}</div></div></body>';"#,
-                                    icon = icon, title = title, desc = desc, extra = extra, code = status
-                                );
+                                let js = error_page_script(icon, title, &desc, extra, &format!("ERR_{}", status));
                                 let _ = unsafe { wv.ExecuteScript(
                                     &windows_core::HSTRING::from(&js),
                                     None,
@@ -1283,8 +2222,35 @@ async fn create_tab(app: tauri::AppHandle, id: String, url: String, sidebar_w: f
                 let mut nav_token: i64 = 0;
                 let _ = core.add_NavigationCompleted(&nav_handler, &mut nav_token);
 
+                // certificate of each loaded page, for the padlock panel
+                security_info::attach(&app_for_block, &tab_id_block, &core);
+
+                // back/forward availability. HistoryChanged rather than on_navigation: it fires
+                // after the entry is committed (on_navigation is too early) and for pushState too
+                let app_hist = app_for_block.clone();
+                let tab_id_hist = tab_id_block.clone();
+                let hist_handler = webview2_com::HistoryChangedEventHandler::create(Box::new(
+                    move |sender: Option<ICoreWebView2>, _args| {
+                        if let Some(wv) = sender {
+                            let state = read_nav_state(&wv);
+                            if !state.current_url.is_empty() {
+                                app_hist.state::<UrlState>().tabs.lock().insert(tab_id_hist.clone(), state.current_url.clone());
+                            }
+                            let _ = app_hist.emit_to("main", "nav-state-changed", serde_json::json!({
+                                "id": tab_id_hist,
+                                "can_go_back": state.can_go_back,
+                                "can_go_forward": state.can_go_forward,
+                                "current_url": state.current_url,
+                            }));
+                        }
+                        Ok(())
+                    },
+                ));
+                let mut hist_token: i64 = 0;
+                let _ = core.add_HistoryChanged(&hist_handler, &mut hist_token);
+
                 // early scriptlet injection via ContentLoading (fires before DOM parsing)
-                if block_enabled {
+                if block_enabled && !bypass {
                     let pending_for_content = pending_for_msg.clone();
                     let content_handler = webview2_com::ContentLoadingEventHandler::create(Box::new(
                         move |sender: Option<ICoreWebView2>, _args: Option<ICoreWebView2ContentLoadingEventArgs>| {
@@ -1495,28 +2461,18 @@ async fn create_tab(app: tauri::AppHandle, id: String, url: String, sidebar_w: f
                 {
                     let app_perm = app_for_block.clone();
                     let tab_id_perm = tab_id_block.clone();
-                    let perm_state = app_perm.state::<PermissionState>();
-                    let perm_pending = perm_state.pending.clone();
-                    let perm_saved = perm_state.saved.lock().clone();
 
                     let perm_handler = webview2_com::PermissionRequestedEventHandler::create(Box::new(
                         move |_sender, args| {
                             let args_ref = AssertUnwindSafe(&args);
                             let app_ref = AssertUnwindSafe(&app_perm);
                             let tab_ref = AssertUnwindSafe(&tab_id_perm);
-                            let pending_ref = AssertUnwindSafe(&perm_pending);
-                            let saved_ref = AssertUnwindSafe(&perm_saved);
                             let _ = catch_unwind(move || {
                                 if let Some(args) = args_ref.as_ref() {
                                     let mut uri_pw = windows::core::PWSTR::null();
                                     let _ = args.Uri(&mut uri_pw);
                                     let uri = if !uri_pw.is_null() { uri_pw.to_string().unwrap_or_default() } else { return; };
 
-                                    let domain = url::Url::parse(&uri)
-                                        .ok()
-                                        .and_then(|u| u.host_str().map(|h| h.to_lowercase()))
-                                        .unwrap_or_default();
-
                                     let mut kind_val = COREWEBVIEW2_PERMISSION_KIND(0);
                                     let _ = args.PermissionKind(&mut kind_val);
                                     let kind_str = match kind_val.0 {
@@ -1527,47 +2483,224 @@ async fn create_tab(app: tauri::AppHandle, id: String, url: String, sidebar_w: f
                                         _ => "unknown",
                                     };
 
-                                    let kind_key = format!("{}:{}", domain, kind_str);
-
-                                    if let Some(&allowed) = saved_ref.get(&kind_key) {
+                                    // autoplay with sound (WebView2 only asks when the page has no user
+                                    // activation) follows the autoplay rules, never the prompt
+                                    if kind_val.0 == 9 {
+                                        let domain = permissions::domain_of(&uri);
+                                        let allowed = app_ref.state::<AutoplayState>().allows(&domain);
                                         let _ = args.SetState(COREWEBVIEW2_PERMISSION_STATE(if allowed { 1 } else { 2 }));
+                                        if !allowed {
+                                            let _ = app_ref.emit_to("main", "media-blocked", serde_json::json!({
+                                                "id": *tab_ref, "domain": domain
+                                            }));
+                                        }
                                         return;
                                     }
 
-                                    // auto-deny local fonts (fingerprint risk), auto-allow multiple downloads
-                                    if kind_val.0 == 10 { let _ = args.SetState(COREWEBVIEW2_PERMISSION_STATE(2)); return; }
-                                    if kind_val.0 == 7 { let _ = args.SetState(COREWEBVIEW2_PERMISSION_STATE(1)); return; }
+                                    let decided = app_ref.state::<permissions::PermissionState>()
+                                        .decide(&permissions::domain_of(&uri), kind_str);
+                                    if let Some(allowed) = decided {
+                                        let _ = args.SetState(COREWEBVIEW2_PERMISSION_STATE(if allowed { 1 } else { 2 }));
+                                        return;
+                                    }
 
                                     let deferral = match args.GetDeferral() {
                                         Ok(d) => d,
                                         Err(_) => return,
                                     };
 
-                                    let request_id = uuid::Uuid::new_v4().to_string();
-
                                     let mut user_initiated = windows_core::BOOL::default();
                                     let _ = args.IsUserInitiated(&mut user_initiated);
 
-                                    pending_ref.lock()
-                                        .insert(request_id.clone(), PendingPermission {
-                                            deferral, args: args.clone(), _tab_id: tab_ref.clone(),
-                                        });
+                                    permissions::ask(&app_ref, &tab_ref, &uri, kind_str, user_initiated == true,
+                                        permissions::PlatformRequest { deferral, args: args.clone() });
+                                }
+                            });
+                            Ok(())
+                        },
+                    ));
+                    let mut perm_token: i64 = 0;
+                    let _ = core.add_PermissionRequested(&perm_handler, &mut perm_token);
+                }
+
+                // certificate errors — never auto-proceed; ask unless host+cert was explicitly trusted
+                if let Ok(core14) = core.cast::<ICoreWebView2_14>() {
+                    let app_cert = app_for_block.clone();
+                    let tab_id_cert = tab_id_block.clone();
+
+                    let cert_handler = webview2_com::ServerCertificateErrorDetectedEventHandler::create(Box::new(
+                        move |_sender, args| {
+                            let args_ref = AssertUnwindSafe(&args);
+                            let app_ref = AssertUnwindSafe(&app_cert);
+                            let tab_ref = AssertUnwindSafe(&tab_id_cert);
+                            let _ = catch_unwind(move || {
+                                if let Some(args) = args_ref.as_ref() {
+                                    let read_pw = |pw: windows::core::PWSTR| -> String {
+                                        if !pw.is_null() { pw.to_string().unwrap_or_default() } else { String::new() }
+                                    };
+                                    let mut uri_pw = windows::core::PWSTR::null();
+                                    let _ = args.RequestUri(&mut uri_pw);
+                                    let uri = read_pw(uri_pw);
+                                    let host = url::Url::parse(&uri)
+                                        .ok()
+                                        .and_then(|u| u.host_str().map(|h| h.to_lowercase()))
+                                        .unwrap_or_default();
+
+                                    let mut status = COREWEBVIEW2_WEB_ERROR_STATUS(0);
+                                    let _ = args.ErrorStatus(&mut status);
+                                    let reason = match status.0 {
+                                        1 => "The certificate name does not match the site",
+                                        2 => "The certificate has expired",
+                                        3 => "The client certificate contains errors",
+                                        4 => "The certificate has been revoked",
+                                        5 => "The certificate is not trusted",
+                                        _ => "The certificate is invalid",
+                                    };
+
+                                    let (mut subject, mut issuer, mut thumbprint) = (String::new(), String::new(), String::new());
+                                    if let Ok(cert) = args.ServerCertificate() {
+                                        let mut pw = windows::core::PWSTR::null();
+                                        let _ = cert.DisplayName(&mut pw);
+                                        subject = read_pw(pw);
+                                        let mut pw = windows::core::PWSTR::null();
+                                        let _ = cert.Issuer(&mut pw);
+                                        issuer = read_pw(pw);
+                                        let mut pw = windows::core::PWSTR::null();
+                                        let _ = cert.ToPemEncoding(&mut pw);
+                                        thumbprint = cert_thumbprint(&read_pw(pw));
+                                    }
+
+                                    let ces = app_ref.state::<CertErrorState>();
+                                    if !thumbprint.is_empty() && ces.saved.lock().contains(&format!("{}|{}", host, thumbprint)) {
+                                        let _ = args.SetAction(COREWEBVIEW2_SERVER_CERTIFICATE_ERROR_ACTION(0)); // always allow
+                                        return;
+                                    }
 
-                                    let _ = app_ref.emit_to("main", "permission-requested", serde_json::json!({
+                                    let deferral = match args.GetDeferral() {
+                                        Ok(d) => d,
+                                        Err(_) => return,
+                                    };
+                                    let request_id = uuid::Uuid::new_v4().to_string();
+                                    ces.pending.lock().insert(request_id.clone(), PendingCertError {
+                                        deferral, args: args.clone(), tab_id: tab_ref.to_string(),
+                                        host: host.clone(), thumbprint: thumbprint.clone(),
+                                    });
+
+                                    crate::crash_log::log_warn("cert-error", &format!("{} status={} tab={}", host, status.0, *tab_ref));
+                                    let _ = app_ref.emit_to("main", "cert-error", serde_json::json!({
                                         "requestId": request_id,
                                         "tabId": *tab_ref,
                                         "uri": uri,
-                                        "domain": domain,
-                                        "permission": kind_str,
-                                        "isUserInitiated": user_initiated == true,
+                                        "host": host,
+                                        "reason": reason,
+                                        "subject": subject,
+                                        "issuer": issuer,
+                                        "thumbprint": thumbprint,
                                     }));
+
+                                    let app_timeout = (*app_ref).clone();
+                                    tauri::async_runtime::spawn(async move {
+                                        tokio::time::sleep(std::time::Duration::from_secs(CERT_ERROR_TIMEOUT_SECS)).await;
+                                        if finish_cert_error(&app_timeout, &request_id, false).is_some() {
+                                            let _ = app_timeout.emit_to("main", "cert-error-expired", serde_json::json!({
+                                                "requestId": request_id,
+                                            }));
+                                        }
+                                    });
                                 }
                             });
                             Ok(())
                         },
                     ));
-                    let mut perm_token: i64 = 0;
-                    let _ = core.add_PermissionRequested(&perm_handler, &mut perm_token);
+                    let mut cert_token: i64 = 0;
+                    let _ = core14.add_ServerCertificateErrorDetected(&cert_handler, &mut cert_token);
+                }
+
+                // basic auth (site or proxy 401) — deferred until respond_basic_auth or timeout
+                if let Ok(core10) = core.cast::<ICoreWebView2_10>() {
+                    let app_auth = app_for_block.clone();
+                    let tab_id_auth = tab_id_block.clone();
+                    let auth_pending = app_auth.state::<BasicAuthState>().pending.clone();
+
+                    let auth_handler = webview2_com::BasicAuthenticationRequestedEventHandler::create(Box::new(
+                        move |_sender, args| {
+                            let args_ref = AssertUnwindSafe(&args);
+                            let app_ref = AssertUnwindSafe(&app_auth);
+                            let tab_ref = AssertUnwindSafe(&tab_id_auth);
+                            let pending_ref = AssertUnwindSafe(&auth_pending);
+                            let _ = catch_unwind(move || {
+                                if let Some(args) = args_ref.as_ref() {
+                                    let read_pw = |pw: windows::core::PWSTR| -> String {
+                                        if !pw.is_null() { pw.to_string().unwrap_or_default() } else { String::new() }
+                                    };
+                                    let mut uri_pw = windows::core::PWSTR::null();
+                                    let _ = args.Uri(&mut uri_pw);
+                                    let uri = read_pw(uri_pw);
+                                    let mut challenge_pw = windows::core::PWSTR::null();
+                                    let _ = args.Challenge(&mut challenge_pw);
+                                    let realm = basic_auth_realm(&read_pw(challenge_pw));
+                                    let host = url::Url::parse(&uri)
+                                        .ok()
+                                        .and_then(|u| u.host_str().map(|h| h.to_lowercase()))
+                                        .unwrap_or_default();
+
+                                    // the tab's own proxy asking for its login: answer without a prompt
+                                    if let Some((user, pass)) = app_ref.state::<proxy::ProxyState>().credentials_for(&tab_ref, &uri) {
+                                        if let Ok(resp) = args.Response() {
+                                            let user_w: Vec<u16> = user.encode_utf16().chain(std::iter::once(0)).collect();
+                                            let pass_w: Vec<u16> = pass.encode_utf16().chain(std::iter::once(0)).collect();
+                                            let _ = resp.SetUserName(windows::core::PCWSTR::from_raw(user_w.as_ptr()));
+                                            let _ = resp.SetPassword(windows::core::PCWSTR::from_raw(pass_w.as_ptr()));
+                                            return;
+                                        }
+                                    }
+
+                                    let deferral = match args.GetDeferral() {
+                                        Ok(d) => d,
+                                        Err(_) => return,
+                                    };
+                                    let request_id = uuid::Uuid::new_v4().to_string();
+                                    pending_ref.lock().insert(request_id.clone(), PendingBasicAuth {
+                                        deferral, args: args.clone(), host: host.clone(),
+                                    });
+
+                                    // vault lookup off the UI thread; only usernames go to React
+                                    let app_clone = (*app_ref).clone();
+                                    let tab_id = tab_ref.to_string();
+                                    let rid = request_id.clone();
+                                    std::thread::spawn(move || {
+                                        let vs = app_clone.state::<crate::vault::VaultState>();
+                                        let vault_entries: Vec<serde_json::Value> = crate::vault::get_entries_for_domain(&vs, &host)
+                                            .unwrap_or_default()
+                                            .into_iter()
+                                            .map(|e| serde_json::json!({ "id": e.id, "username": e.username }))
+                                            .collect();
+                                        let _ = app_clone.emit_to("main", "basic-auth-requested", serde_json::json!({
+                                            "requestId": rid,
+                                            "tabId": tab_id,
+                                            "uri": uri,
+                                            "host": host,
+                                            "realm": realm,
+                                            "vaultEntries": vault_entries,
+                                        }));
+                                    });
+
+                                    let app_timeout = (*app_ref).clone();
+                                    tauri::async_runtime::spawn(async move {
+                                        tokio::time::sleep(std::time::Duration::from_secs(BASIC_AUTH_TIMEOUT_SECS)).await;
+                                        if finish_basic_auth(&app_timeout, &request_id, None) {
+                                            let _ = app_timeout.emit_to("main", "basic-auth-expired", serde_json::json!({
+                                                "requestId": request_id,
+                                            }));
+                                        }
+                                    });
+                                }
+                            });
+                            Ok(())
+                        },
+                    ));
+                    let mut auth_token: i64 = 0;
+                    let _ = core10.add_BasicAuthenticationRequested(&auth_handler, &mut auth_token);
                 }
             }
         });
@@ -1576,12 +2709,19 @@ async fn create_tab(app: tauri::AppHandle, id: String, url: String, sidebar_w: f
         }
     }
 
+    theme::apply_for_url(&app, &id, &url);
     crash_log::log_info("create_tab", &format!("tab {} created successfully", id));
     Ok(())
 }
 
 #[tauri::command]
-async fn suspend_tab(app: tauri::AppHandle, id: String) -> Result<(), String> {
+async fn suspend_tab(app: tauri::AppHandle, id: String) -> Result<(), BushidoError> {
+    if app.state::<PinnedState>().tabs.lock().contains(&id) {
+        return Err(BushidoError::Permission("pinned tabs are never suspended".into()));
+    }
+    if app.state::<throttling::ThrottleState>().keeps_running(&id) {
+        return Err(BushidoError::Permission("this tab is set to keep running in the background".into()));
+    }
     if let Some(wv) = app.get_webview(&id) {
         // pause media before suspending to prevent AUDIO_RENDERER_ERROR on resume
         let _ = wv.eval("document.querySelectorAll('video,audio').forEach(m=>m.pause())");
@@ -1631,7 +2771,8 @@ async fn suspend_tab(app: tauri::AppHandle, id: String) -> Result<(), String> {
 }
 
 #[tauri::command]
-async fn resume_tab(app: tauri::AppHandle, id: String) -> Result<(), String> {
+async fn resume_tab(app: tauri::AppHandle, id: String) -> Result<(), BushidoError> {
+    app.state::<hibernate::HibernateState>().forget(&id);
     if let Some(wv) = app.get_webview(&id) {
         #[cfg(windows)]
         {
@@ -1662,16 +2803,81 @@ async fn resume_tab(app: tauri::AppHandle, id: String) -> Result<(), String> {
                 if(s)s.remove();\
             }catch(e){}})()"
         );
+        if app.state::<MuteState>().tabs.lock().contains(&id) {
+            apply_tab_mute(&wv, true);
+        }
     }
     Ok(())
 }
 
+/// `remember` puts the tab on the recently-closed stack, for closes the user asked for.
+/// `recreate` is set when the webview is only being replaced under the same id
+/// (discard, reload-all, crash recovery); the tab's mute, throttle, private and
+/// similar state is then kept for the new one, and dropped otherwise.
 #[tauri::command]
-async fn close_tab(app: tauri::AppHandle, id: String) -> Result<(), String> {
+async fn close_tab(app: tauri::AppHandle, id: String, remember: Option<bool>, url: Option<String>, title: Option<String>, workspace_id: Option<String>, recreate: Option<bool>) -> Result<(), BushidoError> {
     crash_log::log_info("close_tab", &format!("id={}", id));
     // remove from state FIRST so layout_webviews won't try to position a dying webview
     let state = app.state::<WebviewState>();
     state.tabs.lock().remove(&id);
+    let zoom = app.state::<ZoomState>().tabs.lock().remove(&id);
+    app.state::<console_log::ConsoleLogState>().remove_tab(&id);
+    app.state::<cosmetic_log::CosmeticLogState>().forget_tab(&id);
+    app.state::<element_picker::CosmeticExceptionState>().forget_tab(&id);
+    app.state::<net_capture::NetCaptureState>().remove_tab(&id);
+    app.state::<BlockerStatsState>().tabs.lock().remove(&id);
+    media_session::forget_tab(&app, &id);
+    state.last_focus.lock().remove(&id);
+    app.state::<hibernate::HibernateState>().forget(&id);
+    app.state::<theme::ThemeState>().forget_tab(&id);
+    app.state::<user_agent::UserAgentState>().forget_tab(&id);
+    app.state::<proxy::ProxyState>().forget_tab(&id);
+    let cached_url = app.state::<UrlState>().tabs.lock().remove(&id);
+    app.state::<redirect::RedirectState>().forget_tab(&id);
+    app.state::<security_info::SecurityInfoState>().forget_tab(&id);
+    app.state::<crash_recovery::CrashRecoveryState>().forget_tab(&id);
+
+    let recreate = recreate.unwrap_or(false);
+    let pinned = !recreate && app.state::<PinnedState>().tabs.lock().remove(&id);
+    if !recreate {
+        state.injection_bypass.lock().remove(&id);
+        app.state::<MuteState>().tabs.lock().remove(&id);
+        app.state::<throttling::ThrottleState>().forget_tab(&id);
+        app.state::<ImageBlockState>().tabs.lock().remove(&id);
+        tab_groups::forget_tab(&app, &id);
+        tab_window::forget_tab(&app, &id);
+    }
+    if recreate {
+        app.state::<scroll_restore::ScrollState>().carry_over(&id);
+    } else {
+        app.state::<scroll_restore::ScrollState>().forget_tab(&id);
+    }
+
+    let private = if recreate {
+        state.private.lock().contains(&id)
+    } else {
+        state.private.lock().remove(&id)
+    };
+
+    let is_panel = app.state::<PanelState>().ids.lock().contains(&id);
+    if remember.unwrap_or(false) && !is_panel && !private {
+        // the UI's url covers discarded tabs that no longer have a webview
+        let url = cached_url.or(url);
+        if let Some(url) = url.filter(|u| u.starts_with("http://") || u.starts_with("https://") || u.starts_with("file://")).filter(|u| !is_blocked_scheme(u)) {
+            let entry = ClosedTab {
+                url,
+                title: title.unwrap_or_default().replace(['<', '>'], ""),
+                workspace_id: workspace_id.unwrap_or_default(),
+                pinned,
+                zoom,
+                closed_at: std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_millis() as u64,
+            };
+            let mut stack = app.state::<RecentlyClosedState>().tabs.lock();
+            stack.push_back(entry);
+            while stack.len() > MAX_RECENTLY_CLOSED { stack.pop_front(); }
+        }
+    }
+
     if let Some(wv) = app.get_webview(&id) {
         if let Err(e) = wv.close() {
             crash_log::log_error("close_tab", &format!("wv.close() failed for {}: {}", id, e));
@@ -1682,26 +2888,76 @@ async fn close_tab(app: tauri::AppHandle, id: String) -> Result<(), String> {
     Ok(())
 }
 
+/// Drop an idle tab's webview to free memory; the UI recreates it when the tab is
+/// selected again. Pinned and keep-running tabs are never discarded.
+#[tauri::command]
+async fn discard_tab(app: tauri::AppHandle, id: String) -> Result<(), BushidoError> {
+    if app.state::<PinnedState>().tabs.lock().contains(&id) {
+        return Err(BushidoError::Permission("pinned tabs are never discarded".into()));
+    }
+    if app.state::<throttling::ThrottleState>().keeps_running(&id) {
+        return Err(BushidoError::Permission("this tab is set to keep running in the background".into()));
+    }
+    close_tab(app, id, None, None, None, None, Some(true)).await
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ClosingTab {
+    id: String,
+    url: Option<String>,
+    title: Option<String>,
+    workspace_id: Option<String>,
+}
+
+/// "Close other tabs" / "close tabs below": closes (and remembers) each tab except
+/// pinned ones. Returns the ids actually closed, which are the only ones the UI drops.
 #[tauri::command]
-async fn get_network_log(app: tauri::AppHandle, tab_id: String) -> Result<Vec<NetworkLogEntry>, String> {
-    let nl = app.state::<NetworkLog>();
-    let entries = nl.entries.lock();
-    Ok(entries.get(&tab_id).map(|d| d.iter().cloned().collect()).unwrap_or_default())
+async fn close_tabs(app: tauri::AppHandle, tabs: Vec<ClosingTab>) -> Result<Vec<String>, BushidoError> {
+    let mut closed = Vec::new();
+    for tab in tabs {
+        if app.state::<PinnedState>().tabs.lock().contains(&tab.id) {
+            continue;
+        }
+        close_tab(app.clone(), tab.id.clone(), Some(true), tab.url, tab.title, tab.workspace_id, None).await?;
+        closed.push(tab.id);
+    }
+    Ok(closed)
 }
 
+/// Newest first, for the "recently closed" menu
 #[tauri::command]
-async fn set_network_log_enabled(app: tauri::AppHandle, enabled: bool) -> Result<(), String> {
-    let nl = app.state::<NetworkLog>();
-    nl.enabled.store(enabled, Ordering::Relaxed);
-    Ok(())
+async fn get_recently_closed(app: tauri::AppHandle) -> Result<Vec<ClosedTab>, String> {
+    Ok(app.state::<RecentlyClosedState>().tabs.lock().iter().rev().cloned().collect())
 }
 
+/// Pop the most recently closed tab (or the one at `index` in get_recently_closed order)
+/// and stage its pinned/zoom state under `id`. The UI then opens it with create_tab as usual.
 #[tauri::command]
-async fn clear_network_log(app: tauri::AppHandle, tab_id: String) -> Result<(), String> {
-    let nl = app.state::<NetworkLog>();
-    nl.entries.lock().remove(&tab_id);
-    nl.pending.lock().remove(&tab_id);
-    Ok(())
+async fn reopen_closed_tab(app: tauri::AppHandle, id: String, index: Option<usize>) -> Result<Option<ClosedTab>, String> {
+    let entry = {
+        let mut stack = app.state::<RecentlyClosedState>().tabs.lock();
+        let len = stack.len();
+        match index {
+            Some(i) if i < len => stack.remove(len - 1 - i),
+            Some(_) => None,
+            None => stack.pop_back(),
+        }
+    };
+    let Some(entry) = entry else { return Ok(None) };
+    if entry.pinned {
+        app.state::<PinnedState>().tabs.lock().insert(id.clone());
+    }
+    if let Some(z) = entry.zoom {
+        app.state::<ZoomState>().tabs.lock().insert(id, clamp_zoom(z));
+    }
+    Ok(Some(entry))
+}
+
+/// Exact blocked total for a tab plus a breakdown by resource type
+#[tauri::command]
+async fn get_blocked_stats(app: tauri::AppHandle, id: String) -> Result<BlockedStats, String> {
+    Ok(app.state::<BlockerStatsState>().tabs.lock().get(&id).cloned().unwrap_or_default())
 }
 
 #[tauri::command]
@@ -1734,14 +2990,17 @@ async fn open_glance(app: tauri::AppHandle, url: String, glance_id: String, side
     let bs = app.state::<BlockerState>();
     let shortcut_s = bs.shortcut_script.clone();
     let media_s = bs.media_script.clone();
+    let fp_config_s = app.state::<fingerprint::FingerprintState>().config.lock().script(user_agent::DESKTOP_UA);
     let fingerprint_s = bs.fingerprint_script.clone();
     let vault_s = bs.vault_script.clone();
     let glance_s = bs.glance_script.clone();
 
     let mut builder = tauri::WebviewBuilder::new(&glance_id, webview_url)
-        .with_profile_name(profile_name);
+        .with_profile_name(profile_name)
+        .additional_browser_args(app.state::<proxy::BrowserArgs>().base());
     builder = builder.initialization_script(&shortcut_s);
     builder = builder.initialization_script(&media_s);
+    builder = builder.initialization_script(&fp_config_s);
     builder = builder.initialization_script(&fingerprint_s);
     builder = builder.initialization_script(&vault_s);
     builder = builder.initialization_script(&glance_s);
@@ -1840,14 +3099,34 @@ async fn vault_retry_autofill(app: tauri::AppHandle) -> Result<(), String> {
 }
 
 #[tauri::command]
-async fn layout_webviews(app: tauri::AppHandle, panes: Vec<PaneRectArg>, focused_tab_id: String, sidebar_w: f64, top_offset: f64) -> Result<(), String> {
+async fn layout_webviews(app: tauri::AppHandle, panes: Vec<PaneRectArg>, focused_tab_id: String, sidebar_w: f64, top_offset: f64, pane_layout: Option<layout::PaneNode>) -> Result<(), BushidoError> {
+    let snapshot = panes.iter().map(|p| layout::PaneSnapshot {
+        tab_id: p.tab_id.clone(), url: String::new(), x: p.x, y: p.y, w: p.w, h: p.h,
+    }).collect();
+    layout::record(&app, pane_layout, snapshot, &focused_tab_id, sidebar_w, top_offset);
+
     let state = app.state::<WebviewState>();
     let panel_state = app.state::<PanelState>();
     let panel_ids = panel_state.ids.lock().clone();
     let tabs = state.tabs.lock().clone();
 
+    {
+        let now = std::time::Instant::now();
+        let mut last_focus = state.last_focus.lock();
+        for p in &panes {
+            last_focus.insert(p.tab_id.clone(), now);
+        }
+    }
+    app.state::<hibernate::HibernateState>().set_visible(panes.iter().map(|p| p.tab_id.clone()).collect());
+
+    // tabs in their own window keep it to themselves; picking one brings that window up
+    let tab_windows = app.state::<tab_window::TabWindowState>();
+    if let Some(window) = tab_windows.window_for(&focused_tab_id).and_then(|l| app.get_window(&l)) {
+        let _ = window.set_focus();
+    }
+
     for (tab_id, _) in &tabs {
-        if panel_ids.contains(tab_id) { continue; }
+        if panel_ids.contains(tab_id) || tab_windows.is_detached(tab_id) { continue; }
         if let Some(wv) = app.get_webview(tab_id) {
             if let Some(pane) = panes.iter().find(|p| p.tab_id == *tab_id) {
                 let _ = wv.set_position(tauri::LogicalPosition::new(sidebar_w + pane.x, top_offset + pane.y));
@@ -1865,10 +3144,10 @@ async fn layout_webviews(app: tauri::AppHandle, panes: Vec<PaneRectArg>, focused
 
 // legacy wrapper — kept for incremental migration
 #[tauri::command]
-async fn switch_tab(app: tauri::AppHandle, id: String, split_id: String, sidebar_w: f64, top_offset: f64) -> Result<(), String> {
+async fn switch_tab(app: tauri::AppHandle, id: String, split_id: String, sidebar_w: f64, top_offset: f64) -> Result<(), BushidoError> {
     let window = app.get_window("main").ok_or("no main window")?;
-    let size = window.inner_size().map_err(|e| e.to_string())?;
-    let scale = window.scale_factor().map_err(|e| e.to_string())?;
+    let size = window.inner_size()?;
+    let scale = window.scale_factor()?;
     let content_w = (size.width as f64 / scale) - sidebar_w;
     let content_h = (size.height as f64 / scale) - top_offset;
 
@@ -1879,14 +3158,15 @@ async fn switch_tab(app: tauri::AppHandle, id: String, split_id: String, sidebar
         panes.push(PaneRectArg { tab_id: split_id, x: half_w, y: 0.0, w: half_w, h: content_h });
     }
 
-    layout_webviews(app, panes, id, sidebar_w, top_offset).await
+    layout_webviews(app, panes, id, sidebar_w, top_offset, None).await
 }
 
 #[tauri::command]
-async fn navigate_tab(app: tauri::AppHandle, id: String, url: String) -> Result<(), String> {
+async fn navigate_tab(app: tauri::AppHandle, id: String, url: String) -> Result<(), BushidoError> {
     if is_blocked_scheme(&url) {
         return Err("Blocked URL scheme".into());
     }
+    vault::note_activity(&app);
     if let Some(wv) = app.get_webview(&id) {
         let parsed_url = if url.starts_with("https://") {
             url.parse().map_err(|e: url::ParseError| e.to_string())?
@@ -1896,38 +3176,243 @@ async fn navigate_tab(app: tauri::AppHandle, id: String, url: String) -> Result<
         } else if url.contains('.') {
             format!("https://{}", url).parse().map_err(|e: url::ParseError| e.to_string())?
         } else {
-            format!("https://www.google.com/search?q={}", urlencoding::encode(&url))
+            let template = app.state::<SearchEngineState>().template.lock().clone();
+            fill_search_template(&template, &url)
                 .parse().map_err(|e: url::ParseError| e.to_string())?
         };
-        wv.navigate(parsed_url).map_err(|e| e.to_string())?;
+        wv.navigate(parsed_url)?;
+    }
+    Ok(())
+}
+
+/// Evaluate a JS expression in a tab and wait for its string value, returned over postMessage IPC.
+async fn eval_with_result(app: &tauri::AppHandle, id: &str, expr: &str, timeout: std::time::Duration) -> Result<String, String> {
+    let wv = app.get_webview(id).ok_or("webview not found")?;
+    let request_id = uuid::Uuid::new_v4().to_string();
+    let (tx, rx) = tokio::sync::oneshot::channel::<String>();
+    let srs = app.state::<ScriptResultState>();
+    srs.pending.lock().insert(request_id.clone(), tx);
+
+    let js = format!(
+        "(function(){{var v='';try{{v=String(({})||'')}}catch(e){{}}if(window.chrome&&window.chrome.webview){{window.chrome.webview.postMessage(JSON.stringify({{__bushido:'script-result',requestId:{},value:v}}))}}}})()",
+        expr,
+        serde_json::to_string(&request_id).unwrap_or_default()
+    );
+    if let Err(e) = wv.eval(&js) {
+        srs.pending.lock().remove(&request_id);
+        return Err(e.to_string());
+    }
+    match tokio::time::timeout(timeout, rx).await {
+        Ok(Ok(value)) => Ok(value),
+        _ => {
+            srs.pending.lock().remove(&request_id);
+            Err("Script result timed out".into())
+        }
+    }
+}
+
+const DEFAULT_SEARCH_TEMPLATE: &str = "https://www.google.com/search?q=%s";
+
+// address-bar search template (`%s` = encoded query), kept in sync with the searchEngine setting
+struct SearchEngineState {
+    template: Mutex<String>,
+}
+
+fn engine_template(engine: &str) -> &'static str {
+    match engine {
+        "duckduckgo" => "https://duckduckgo.com/?q=%s",
+        "bing" => "https://www.bing.com/search?q=%s",
+        "brave" => "https://search.brave.com/search?q=%s",
+        "startpage" => "https://www.startpage.com/do/search?q=%s",
+        _ => DEFAULT_SEARCH_TEMPLATE,
+    }
+}
+
+/// Template must contain `%s` and yield an http(s) URL once a query is substituted
+fn validate_search_template(template: &str) -> Result<(), String> {
+    if !template.contains("%s") {
+        return Err("search URL must contain %s for the query".into());
+    }
+    let url = url::Url::parse(&template.replace("%s", "test"))
+        .map_err(|e| format!("invalid search URL: {}", e))?;
+    if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none() {
+        return Err("search URL must be an http(s) address".into());
     }
     Ok(())
 }
 
+fn fill_search_template(template: &str, query: &str) -> String {
+    let template = if validate_search_template(template).is_ok() { template } else { DEFAULT_SEARCH_TEMPLATE };
+    template.replace("%s", &urlencoding::encode(query))
+}
+
+/// settings.json → template: searchEngine, or customSearchUrl when it's "custom"
+fn load_search_template(settings_path: &std::path::Path) -> String {
+    let settings = fs::read_to_string(settings_path).ok()
+        .and_then(|s| serde_json::from_str::<serde_json::Value>(&s).ok())
+        .unwrap_or_default();
+    match settings.get("searchEngine").and_then(|v| v.as_str()) {
+        Some("custom") => settings.get("customSearchUrl").and_then(|v| v.as_str())
+            .filter(|t| validate_search_template(t).is_ok())
+            .unwrap_or(DEFAULT_SEARCH_TEMPLATE)
+            .to_string(),
+        Some(engine) => engine_template(engine).to_string(),
+        None => DEFAULT_SEARCH_TEMPLATE.to_string(),
+    }
+}
+
+fn search_url(engine: &str, query: &str) -> String {
+    fill_search_template(engine_template(engine), query)
+}
+
+#[tauri::command]
+async fn set_search_engine(app: tauri::AppHandle, template: String) -> Result<(), String> {
+    let template = template.trim().to_string();
+    validate_search_template(&template)?;
+    *app.state::<SearchEngineState>().template.lock() = template;
+    Ok(())
+}
+
+#[tauri::command]
+async fn get_selection(app: tauri::AppHandle, id: String) -> Result<String, String> {
+    let text = eval_with_result(&app, &id, "window.getSelection().toString()", std::time::Duration::from_secs(2)).await?;
+    Ok(text.trim().to_string())
+}
+
+/// Open a search tab for the current selection. Returns false if nothing is selected.
+#[tauri::command]
+async fn search_selection(app: tauri::AppHandle, id: String, engine: String) -> Result<bool, String> {
+    let text = get_selection(app.clone(), id.clone()).await?;
+    if text.is_empty() { return Ok(false); }
+    let query: String = text.chars().take(1000).collect();
+    let _ = app.emit_to("main", "new-window-requested", serde_json::json!({
+        "sourceTabId": id,
+        "url": search_url(&engine, &query)
+    }));
+    Ok(true)
+}
+
 #[tauri::command]
-async fn go_back(app: tauri::AppHandle, id: String) -> Result<(), String> {
+async fn go_back(app: tauri::AppHandle, id: String) -> Result<(), BushidoError> {
+    vault::note_activity(&app);
     if let Some(wv) = app.get_webview(&id) {
-        wv.eval("window.history.back()").map_err(|e| e.to_string())?;
+        wv.eval("window.history.back()")?;
     }
     Ok(())
 }
 
 #[tauri::command]
-async fn go_forward(app: tauri::AppHandle, id: String) -> Result<(), String> {
+async fn go_forward(app: tauri::AppHandle, id: String) -> Result<(), BushidoError> {
+    vault::note_activity(&app);
     if let Some(wv) = app.get_webview(&id) {
-        wv.eval("window.history.forward()").map_err(|e| e.to_string())?;
+        wv.eval("window.history.forward()")?;
     }
     Ok(())
 }
 
+#[derive(Debug, Clone, Default, Serialize)]
+struct NavState {
+    can_go_back: bool,
+    can_go_forward: bool,
+    current_url: String,
+}
+
+#[cfg(windows)]
+fn read_nav_state(core: &webview2_com::Microsoft::Web::WebView2::Win32::ICoreWebView2) -> NavState {
+    let mut back = windows_core::BOOL(0);
+    let mut forward = windows_core::BOOL(0);
+    let mut source = windows::core::PWSTR::null();
+    unsafe {
+        let _ = core.CanGoBack(&mut back);
+        let _ = core.CanGoForward(&mut forward);
+        let current_url = core.Source(&mut source).ok().and_then(|_| source.to_string().ok()).unwrap_or_default();
+        NavState { can_go_back: back.as_bool(), can_go_forward: forward.as_bool(), current_url }
+    }
+}
+
+// Shadow history for NAV_STATE_FALLBACK_JS: the URLs of this document's same-document
+// entries and which one is current, moved by pushState/replaceState and popstate. A
+// popstate to a URL that isn't a neighbour or earlier entry counts as a new one (hash links).
+#[cfg(not(windows))]
+const NAV_SHADOW_JS: &str = "(function(){if(window.__bushidoNav)return;\
+var s=[location.href],n={i:0,forward:function(){return n.i<s.length-1}};\
+Object.defineProperty(window,'__bushidoNav',{value:n});\
+var add=function(){s.length=n.i+1;s.push(location.href);n.i++};\
+var push=history.pushState,rep=history.replaceState;\
+history.pushState=function(){var r=push.apply(this,arguments);add();return r};\
+history.replaceState=function(){var r=rep.apply(this,arguments);s[n.i]=location.href;return r};\
+addEventListener('popstate',function(){var u=location.href;\
+if(s[n.i+1]===u)n.i++;else if(s[n.i-1]===u)n.i--;\
+else{var j=s.lastIndexOf(u);if(j>=0)n.i=j;else add()}});})();";
+
+// without the COM view: history.length only says whether there's *some* other entry,
+// so back is a guess; forward comes from NAV_SHADOW_JS and only knows same-document entries
+const NAV_STATE_FALLBACK_JS: &str = "JSON.stringify({can_go_back:history.length>1,can_go_forward:!!(window.__bushidoNav&&window.__bushidoNav.forward()),current_url:location.href})";
+
+/// Whether the tab can go back/forward, for enabling the toolbar buttons.
+/// Kept current afterwards by `nav-state-changed`.
+#[tauri::command]
+async fn get_nav_state(app: tauri::AppHandle, id: String) -> Result<NavState, BushidoError> {
+    let wv = app.get_webview(&id).ok_or_else(|| BushidoError::NotFound(format!("tab {}", id)))?;
+    #[cfg(windows)]
+    {
+        let (tx, rx) = tokio::sync::oneshot::channel::<NavState>();
+        let queued = wv.with_webview(move |pw| {
+            if let Ok(core) = unsafe { pw.controller().CoreWebView2() } {
+                let _ = tx.send(read_nav_state(&core));
+            }
+        });
+        if queued.is_ok() {
+            if let Ok(Ok(state)) = tokio::time::timeout(std::time::Duration::from_secs(2), rx).await {
+                return Ok(state);
+            }
+        }
+    }
+    let raw = eval_with_result(&app, &id, NAV_STATE_FALLBACK_JS, std::time::Duration::from_secs(2)).await
+        .map_err(BushidoError::WebView)?;
+    let v: serde_json::Value = serde_json::from_str(&raw).map_err(|e| format!("nav state: {}", e))?;
+    Ok(NavState {
+        can_go_back: v["can_go_back"].as_bool().unwrap_or(false),
+        can_go_forward: v["can_go_forward"].as_bool().unwrap_or(false),
+        current_url: v["current_url"].as_str().unwrap_or_default().to_string(),
+    })
+}
+
+/// The tab's current URL from `UrlState`. Asks the page for `location.href` only when
+/// nothing is cached yet.
+#[tauri::command]
+async fn get_tab_url(app: tauri::AppHandle, id: String) -> Result<String, BushidoError> {
+    if let Some(url) = app.state::<UrlState>().tabs.lock().get(&id).cloned() {
+        return Ok(url);
+    }
+    if app.get_webview(&id).is_none() {
+        return Err(BushidoError::NotFound(format!("tab {}", id)));
+    }
+    let url = eval_with_result(&app, &id, "location.href", std::time::Duration::from_secs(2)).await
+        .map_err(BushidoError::WebView)?;
+    app.state::<UrlState>().tabs.lock().insert(id, url.clone());
+    Ok(url)
+}
+
 #[tauri::command]
-async fn reload_tab(app: tauri::AppHandle, id: String) -> Result<(), String> {
+async fn reload_tab(app: tauri::AppHandle, id: String) -> Result<(), BushidoError> {
     if let Some(wv) = app.get_webview(&id) {
-        wv.eval("window.location.reload()").map_err(|e| e.to_string())?;
+        wv.eval("window.location.reload()")?;
     }
     Ok(())
 }
 
+/// Overlays keyboard labels on the tab's visible clickable elements, or cancels
+/// hint mode if it's already showing. The page reports back via `__bushido:'hint'`.
+#[tauri::command]
+async fn toggle_link_hints(app: tauri::AppHandle, id: String) -> Result<(), BushidoError> {
+    let wv = app.get_webview(&id).ok_or_else(|| BushidoError::NotFound(format!("tab {}", id)))?;
+    wv.eval(include_str!("link_hints.js"))?;
+    // keystrokes have to reach the page, not the shell UI that invoked us
+    let _ = wv.set_focus();
+    Ok(())
+}
+
 #[tauri::command]
 async fn minimize_window(app: tauri::AppHandle) -> Result<(), String> {
     app.get_window("main").ok_or("no window")?.minimize().map_err(|e| e.to_string())
@@ -1957,11 +3442,25 @@ async fn toggle_fullscreen(app: tauri::AppHandle) -> Result<(), String> {
 
 #[tauri::command]
 async fn zoom_tab(app: tauri::AppHandle, id: String, factor: f64) -> Result<(), String> {
+    let factor = clamp_zoom(factor);
     if let Some(wv) = app.get_webview(&id) {
-        let _ = wv.with_webview(move |wv| {
-            #[cfg(windows)]
-            unsafe { let _ = wv.controller().SetZoomFactor(factor); }
-        });
+        app.state::<ZoomState>().tabs.lock().insert(id, factor);
+        apply_zoom(&wv, factor);
+    }
+    Ok(())
+}
+
+/// Apply a zoom factor to every open tab now; it sticks across their navigations
+#[tauri::command]
+async fn set_zoom_for_all_tabs(app: tauri::AppHandle, factor: f64) -> Result<(), String> {
+    let factor = clamp_zoom(factor);
+    let tabs: Vec<String> = app.state::<WebviewState>().tabs.lock().keys().cloned().collect();
+    let zs = app.state::<ZoomState>();
+    for id in tabs {
+        if let Some(wv) = app.get_webview(&id) {
+            zs.tabs.lock().insert(id, factor);
+            apply_zoom(&wv, factor);
+        }
     }
     Ok(())
 }
@@ -2001,32 +3500,82 @@ async fn copy_text_to_clipboard(text: String) -> Result<(), String> {
 
 #[tauri::command]
 async fn set_tab_pinned(app: tauri::AppHandle, id: String, pinned: bool) -> Result<(), String> {
+    {
+        let mut tabs = app.state::<PinnedState>().tabs.lock();
+        if pinned { tabs.insert(id.clone()); } else { tabs.remove(&id); }
+    }
+    // page scripts still read the flag; on_page_load re-sets it after navigations
     if let Some(wv) = app.get_webview(&id) {
         let js = format!("window.__bushidoPinned = {};", pinned);
         let _ = wv.eval(&js);
     }
+    let _ = app.emit_to("main", "tab-pinned-changed", serde_json::json!({ "id": id, "pinned": pinned }));
     Ok(())
 }
 
+/// Mark a tab to be created without any injected page scripts (fingerprint, cosmetic,
+/// cookie, media, security…). Init scripts are fixed at creation, so the UI recreates
+/// the tab when it gets `tab-injection-bypass-changed`.
+#[tauri::command]
+async fn set_tab_injection_bypass(app: tauri::AppHandle, id: String, bypass: bool) -> Result<(), String> {
+    let changed = {
+        let mut set = app.state::<WebviewState>().injection_bypass.lock();
+        if bypass { set.insert(id.clone()) } else { set.remove(&id) }
+    };
+    if changed {
+        crash_log::log_info("injection_bypass", &format!("id={} bypass={}", id, bypass));
+        let _ = app.emit_to("main", "tab-injection-bypass-changed", serde_json::json!({ "id": id, "bypass": bypass }));
+    }
+    Ok(())
+}
+
+/// Comparable form of a tab URL: no fragment, no trailing slash on the path
+pub(crate) fn normalize_tab_url(url: &str) -> Option<String> {
+    let mut u = url::Url::parse(url).ok()?;
+    u.set_fragment(None);
+    let path = u.path().to_string();
+    if path.len() > 1 && path.ends_with('/') {
+        u.set_path(path.trim_end_matches('/'));
+    }
+    Some(u.to_string())
+}
+
+/// Id of an open tab already showing `url`. Reads each webview's live source,
+/// so pushState navigations in SPAs are matched too.
+#[tauri::command]
+async fn find_tab_by_url(app: tauri::AppHandle, url: String) -> Result<Option<String>, String> {
+    let Some(target) = normalize_tab_url(&url) else { return Ok(None) };
+    let panels = app.state::<PanelState>().ids.lock().clone();
+    let mut ids: Vec<String> = app.state::<WebviewState>().tabs.lock().keys()
+        .filter(|id| !panels.contains(*id))
+        .cloned()
+        .collect();
+    ids.sort();
+    Ok(ids.into_iter().find(|id| {
+        app.get_webview(id)
+            .and_then(|wv| wv.url().ok())
+            .and_then(|u| normalize_tab_url(u.as_str()))
+            .is_some_and(|u| u == target)
+    }))
+}
+
+#[tauri::command]
+async fn get_pinned_tabs(app: tauri::AppHandle) -> Result<Vec<String>, String> {
+    Ok(app.state::<PinnedState>().tabs.lock().iter().cloned().collect())
+}
+
+/// Highlights every match of `query` (case-insensitive, overlaps included) and moves
+/// to the next/previous one; an empty query removes the highlights. The page reports
+/// `{ current, total }` back through `__bushido:'match-count'`.
 #[tauri::command]
 async fn find_in_page(app: tauri::AppHandle, id: String, query: String, forward: bool) -> Result<(), String> {
     if let Some(wv) = app.get_webview(&id) {
-        if query.is_empty() {
-            wv.eval("window.getSelection().removeAllRanges()").map_err(|e| e.to_string())?;
+        let call = if query.is_empty() {
+            "window.__bushidoFind.clear()".to_string()
         } else {
-            let dir = if forward { "false" } else { "true" };
-            let escaped = query.replace('\\', "\\\\").replace('\'', "\\'").replace('\n', "\\n").replace('\r', "\\r");
-            let js = format!(
-                "window.find('{}', false, {}, true, false, false, false)",
-                escaped, dir
-            );
-            wv.eval(&js).map_err(|e| e.to_string())?;
-            let count_js = format!(
-                "(function(){{var q='{}';var t=(document.body.innerText||'').toLowerCase();var c=t.split(q.toLowerCase()).length-1;window.chrome.webview.postMessage(JSON.stringify({{__bushido:'match-count',count:c}}))}})()",
-                escaped
-            );
-            let _ = wv.eval(&count_js);
-        }
+            format!("window.__bushidoFind.run({}, {})", serde_json::to_string(&query).map_err(|e| e.to_string())?, forward)
+        };
+        wv.eval(&format!("{}\n{};", include_str!("find_in_page.js"), call)).map_err(|e| e.to_string())?;
     }
     Ok(())
 }
@@ -2040,25 +3589,59 @@ async fn detect_video(app: tauri::AppHandle, id: String) -> Result<(), String> {
     Ok(())
 }
 
+/// Fonts the reader accepts, as (key, CSS stack). The stack is interpolated into a
+/// `<style>` block, so callers only ever pick from this list, never pass raw CSS.
+const READER_FONTS: &[(&str, &str)] = &[
+    ("serif", "Georgia,'Times New Roman',serif"),
+    ("sans", "-apple-system,system-ui,sans-serif"),
+    ("mono", "'Cascadia Mono',Consolas,monospace"),
+    ("verdana", "Verdana,Tahoma,sans-serif"),
+    ("atkinson", "'Atkinson Hyperlegible',Verdana,sans-serif"),
+    ("lexend", "Lexend,'Segoe UI',sans-serif"),
+    ("opendyslexic", "OpenDyslexic,'Comic Sans MS',sans-serif"),
+    ("comic", "'Comic Sans MS','Comic Neue',sans-serif"),
+];
+
+/// Resolve a reader font by key or by its primary family name, case-insensitively.
+fn reader_font_stack(font: &str) -> Option<&'static str> {
+    let want = font.trim().to_ascii_lowercase();
+    READER_FONTS.iter().find_map(|(key, stack)| {
+        let family = stack.split(',').next().unwrap_or("").trim_matches('\'').to_ascii_lowercase();
+        (*key == want || family == want).then_some(*stack)
+    })
+}
+
+/// Show or hide the reader overlay. While shown it posts `reader-progress` messages
+/// (scroll percent, plus estimated minutes on the first one). Spacing is in em.
 #[tauri::command]
-async fn toggle_reader(app: tauri::AppHandle, id: String, font_size: u32, font: String, theme: String, line_width: u32) -> Result<(), String> {
+async fn toggle_reader(
+    app: tauri::AppHandle,
+    id: String,
+    font_size: u32,
+    font: String,
+    theme: String,
+    line_width: u32,
+    letter_spacing: Option<f64>,
+    paragraph_spacing: Option<f64>,
+) -> Result<(), String> {
     let fs = font_size.clamp(12, 28);
     let lw = line_width.clamp(600, 900);
-    let font_family = if font == "serif" {
-        "Georgia,'Times New Roman',serif"
-    } else {
-        "-apple-system,system-ui,sans-serif"
-    };
+    let font_family = reader_font_stack(&font).ok_or_else(|| format!("Unknown reader font '{}'", font))?;
+    // numbers only reach the CSS after this, so no NaN/inf or unit tricks
+    let ls = letter_spacing.filter(|v| v.is_finite()).unwrap_or(0.0).clamp(0.0, 0.3);
+    let ps = paragraph_spacing.filter(|v| v.is_finite()).unwrap_or(1.0).clamp(0.5, 3.0);
     let (bg, text, link) = match theme.as_str() {
         "light" => ("#fafafa", "#1a1a1a", "#4f46e5"),
         "sepia" => ("#f4ecd8", "#433422", "#8b5e3c"),
         _ => ("#09090b", "#d4d4d8", "#818cf8"),
     };
     let css = format!(
-        "#__bushido_reader{{position:fixed;inset:0;z-index:999999;background:{bg};color:{text};overflow-y:auto;padding:48px 24px;font-family:{font_family};font-size:{fs}px;line-height:1.7}}.bushido-reader-content{{max-width:{lw}px;margin:0 auto}}.bushido-reader-content h1{{font-size:2em;margin-bottom:.5em;line-height:1.2}}.bushido-reader-content img{{max-width:100%;height:auto;border-radius:8px;margin:16px 0}}.bushido-reader-content a{{color:{link}}}.bushido-reader-content p{{margin-bottom:1em}}"
+        "#__bushido_reader{{position:fixed;inset:0;z-index:999999;background:{bg};color:{text};overflow-y:auto;padding:48px 24px;font-family:{font_family};font-size:{fs}px;line-height:1.7;letter-spacing:{ls:.2}em}}.bushido-reader-content{{max-width:{lw}px;margin:0 auto}}.bushido-reader-content h1{{font-size:2em;margin-bottom:.5em;line-height:1.2}}.bushido-reader-content img{{max-width:100%;height:auto;border-radius:8px;margin:16px 0}}.bushido-reader-content a{{color:{link}}}.bushido-reader-content p{{margin-bottom:{ps:.2}em}}.bushido-reader-toc{{max-width:{lw}px;margin:0 auto 2em;padding:8px 16px;border-left:2px solid {link};display:flex;flex-direction:column;gap:4px;font-size:.9em}}.bushido-reader-toc a{{color:{link};text-decoration:none}}"
     );
+    let extract = content::EXTRACT_SCRIPT;
+    let wpm = content::WORDS_PER_MINUTE;
     let js = format!(
-        r#"(function(){{if(document.getElementById('__bushido_reader')){{document.getElementById('__bushido_reader').remove();var s=document.getElementById('__bushido_reader_style');if(s)s.remove();document.querySelectorAll('[data-bushido-hidden]').forEach(function(el){{el.style.display=el.dataset.bushidoOrigDisplay||'';delete el.dataset.bushidoHidden;delete el.dataset.bushidoOrigDisplay}});return}}var article=document.querySelector('article')||document.querySelector('[role="main"]')||document.querySelector('main');if(!article){{var candidates=document.querySelectorAll('div,section');var best=null,bestLen=0;candidates.forEach(function(el){{var text=el.innerText||'';if(text.length>bestLen){{bestLen=text.length;best=el}}}});article=best}}if(!article)return;var title=document.title;var content=article.innerHTML;Array.from(document.body.children).forEach(function(el){{if(el.id==='__bushido_reader')return;el.dataset.bushidoOrigDisplay=el.style.display;el.dataset.bushidoHidden='true';el.style.display='none'}});var reader=document.createElement('div');reader.id='__bushido_reader';reader.innerHTML='<div class="bushido-reader-content"><h1>'+title+'</h1>'+content+'</div>';document.body.appendChild(reader);var style=document.createElement('style');style.id='__bushido_reader_style';style.textContent=`{css}`;document.head.appendChild(style)}})()"#
+        r#"(function(){{if(document.getElementById('__bushido_reader')){{if(window.__bushidoReaderOff)window.__bushidoReaderOff();if(window.__bushidoTts)window.__bushidoTts.stop();document.getElementById('__bushido_reader').remove();var s=document.getElementById('__bushido_reader_style');if(s)s.remove();document.querySelectorAll('[data-bushido-hidden]').forEach(function(el){{el.style.display=el.dataset.bushidoOrigDisplay||'';delete el.dataset.bushidoHidden;delete el.dataset.bushidoOrigDisplay}});return}}{extract}var c=window.__bushidoExtract();if(!c.html)return;Array.from(document.body.children).forEach(function(el){{if(el.id==='__bushido_reader')return;el.dataset.bushidoOrigDisplay=el.style.display;el.dataset.bushidoHidden='true';el.style.display='none'}});var reader=document.createElement('div');reader.id='__bushido_reader';var wrap=document.createElement('div');wrap.className='bushido-reader-content';var h=document.createElement('h1');h.textContent=c.title;wrap.appendChild(h);var body=document.createElement('div');body.innerHTML=c.html;var dup=body.querySelector('h1');if(dup&&dup.textContent.trim()===c.title)dup.remove();wrap.appendChild(body);var toc=c.headings.filter(function(x){{return x.level>1&&body.querySelector('#'+CSS.escape(x.id))}});if(toc.length>=2){{var nav=document.createElement('nav');nav.className='bushido-reader-toc';toc.forEach(function(x){{var a=document.createElement('a');a.href='#'+x.id;a.textContent=x.text;a.style.paddingLeft=(x.level-2)+'em';a.onclick=function(e){{e.preventDefault();body.querySelector('#'+CSS.escape(x.id)).scrollIntoView({{behavior:'smooth'}})}};nav.appendChild(a)}});reader.appendChild(nav)}}reader.appendChild(wrap);document.body.appendChild(reader);var style=document.createElement('style');style.id='__bushido_reader_style';style.textContent=`{css}`;document.head.appendChild(style);var words=c.text.split(/\s+/).filter(Boolean).length;var minutes=Math.max(1,Math.ceil(words/{wpm}));var first=true,pending=false;function report(){{pending=false;var max=reader.scrollHeight-reader.clientHeight;var m={{__bushido:'reader-progress',percent:max>0?Math.min(100,Math.max(0,Math.round(reader.scrollTop/max*100))):100}};if(first){{first=false;m.minutes=minutes}}if(window.chrome&&window.chrome.webview)window.chrome.webview.postMessage(JSON.stringify(m))}}function onScroll(){{if(!pending){{pending=true;requestAnimationFrame(report)}}}}reader.addEventListener('scroll',onScroll,{{passive:true}});window.__bushidoReaderOff=function(){{reader.removeEventListener('scroll',onScroll);window.__bushidoReaderOff=null}};report()}})()"#
     );
     if let Some(wv) = app.get_webview(&id) {
         wv.eval(&js).map_err(|e| e.to_string())?;
@@ -2069,26 +3652,52 @@ async fn toggle_reader(app: tauri::AppHandle, id: String, font_size: u32, font:
 #[tauri::command]
 async fn toggle_pip(app: tauri::AppHandle, id: String) -> Result<(), String> {
     if let Some(wv) = app.get_webview(&id) {
-        let js = r#"(function(){if(document.pictureInPictureElement){document.exitPictureInPicture();return}var existing=document.getElementById('__bushido_pip_host');if(existing){existing.remove();return}var v=document.querySelector('video');if(!v)return;v.removeAttribute('disablePictureInPicture');v.removeAttribute('disablepictureinpicture');var host=document.createElement('div');host.id='__bushido_pip_host';host.style.cssText='position:fixed;top:0;left:0;width:100%;height:100%;z-index:2147483647;pointer-events:none';var shadow=host.attachShadow({mode:'closed'});var btn=document.createElement('div');btn.innerHTML='<svg width="18" height="18" viewBox="0 0 16 16" fill="none" style="vertical-align:middle;margin-right:6px"><rect x="1" y="2.5" width="14" height="11" rx="1.5" stroke="white" stroke-width="1.3"/><rect x="8" y="7" width="6" height="5" rx="1" fill="white" opacity="0.4" stroke="white" stroke-width="1"/></svg>Picture in Picture';btn.style.cssText='position:fixed;top:50%;left:50%;transform:translate(-50%,-50%);background:rgba(0,0,0,0.9);color:#fff;padding:14px 24px;border-radius:10px;cursor:pointer;font:600 15px system-ui;box-shadow:0 8px 32px rgba(0,0,0,0.6);pointer-events:auto;display:flex;align-items:center;border:1px solid rgba(255,255,255,0.15);backdrop-filter:blur(12px);transition:background 0.15s';btn.onmouseenter=function(){btn.style.background='rgba(99,102,241,0.9)'};btn.onmouseleave=function(){btn.style.background='rgba(0,0,0,0.9)'};btn.onclick=function(e){e.stopPropagation();v.requestPictureInPicture().then(function(){host.remove()}).catch(function(){btn.innerHTML='PiP not available';btn.style.background='rgba(239,68,68,0.9)';setTimeout(function(){host.remove()},2000)})};shadow.appendChild(btn);document.documentElement.appendChild(host)})()"#;
-        wv.eval(js).map_err(|e| e.to_string())?;
+        let js = r#"(function(){if(document.pictureInPictureElement){document.exitPictureInPicture();return}var existing=document.getElementById('__bushido_pip_host');if(existing){existing.remove();return}var v=document.querySelector('video');if(!v)return;v.removeAttribute('disablePictureInPicture');v.removeAttribute('disablepictureinpicture');var host=document.createElement('div');host.id='__bushido_pip_host';host.style.cssText='position:fixed;top:0;left:0;width:100%;height:100%;z-index:2147483647;pointer-events:none';var shadow=host.attachShadow({mode:'closed'});var btn=document.createElement('div');btn.innerHTML='<svg width="18" height="18" viewBox="0 0 16 16" fill="none" style="vertical-align:middle;margin-right:6px"><rect x="1" y="2.5" width="14" height="11" rx="1.5" stroke="white" stroke-width="1.3"/><rect x="8" y="7" width="6" height="5" rx="1" fill="white" opacity="0.4" stroke="white" stroke-width="1"/></svg>Picture in Picture';btn.style.cssText='position:fixed;top:50%;left:50%;transform:translate(-50%,-50%);background:rgba(0,0,0,0.9);color:#fff;padding:14px 24px;border-radius:10px;cursor:pointer;font:600 15px system-ui;box-shadow:0 8px 32px rgba(0,0,0,0.6);pointer-events:auto;display:flex;align-items:center;border:1px solid rgba(255,255,255,0.15);backdrop-filter:blur(12px);transition:background 0.15s';btn.onmouseenter=function(){btn.style.background='rgba(99,102,241,0.9)'};btn.onmouseleave=function(){btn.style.background='rgba(0,0,0,0.9)'};btn.onclick=function(e){e.stopPropagation();v.requestPictureInPicture().then(function(){host.remove()}).catch(function(){btn.innerHTML='PiP not available';btn.style.background='rgba(239,68,68,0.9)';setTimeout(function(){host.remove()},2000)})};shadow.appendChild(btn);document.documentElement.appendChild(host)})()"#;
+        wv.eval(js).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+async fn media_play_pause(app: tauri::AppHandle, id: String) -> Result<(), String> {
+    if let Some(wv) = app.get_webview(&id) {
+        wv.eval(media_session::PLAY_PAUSE_JS).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Persistent per-tab mute: kept across reloads, SPA navigations and suspend/resume
+#[tauri::command]
+async fn set_tab_muted(app: tauri::AppHandle, id: String, muted: bool) -> Result<(), String> {
+    {
+        let mut tabs = app.state::<MuteState>().tabs.lock();
+        if muted { tabs.insert(id.clone()); } else { tabs.remove(&id); }
+    }
+    if let Some(wv) = app.get_webview(&id) {
+        apply_tab_mute(&wv, muted);
     }
+    let _ = app.emit_to("main", "tab-muted-changed", serde_json::json!({ "id": id, "muted": muted }));
     Ok(())
 }
 
+/// Per-tab "block all images" for low-bandwidth browsing. Takes effect from the next
+/// request; images already on the page stay until the tab reloads, which `reload` does now.
 #[tauri::command]
-async fn media_play_pause(app: tauri::AppHandle, id: String) -> Result<(), String> {
-    if let Some(wv) = app.get_webview(&id) {
-        wv.eval(r#"(function(){var v=document.querySelector('video,audio');if(v){v.paused?v.play():v.pause()}})()"#)
-          .map_err(|e| e.to_string())?;
+async fn set_tab_images_enabled(app: tauri::AppHandle, id: String, enabled: bool, reload: Option<bool>) -> Result<(), BushidoError> {
+    app.state::<ImageBlockState>().flag(&id).store(!enabled, Ordering::Relaxed);
+    if reload.unwrap_or(false) {
+        if let Some(wv) = app.get_webview(&id) {
+            wv.eval("window.location.reload()")?;
+        }
     }
+    let _ = app.emit_to("main", "tab-images-changed", serde_json::json!({ "id": id, "enabled": enabled }));
     Ok(())
 }
 
 #[tauri::command]
 async fn media_mute(app: tauri::AppHandle, id: String) -> Result<(), String> {
     if let Some(wv) = app.get_webview(&id) {
-        wv.eval(r#"(function(){var v=document.querySelector('video,audio');if(v){v.muted=!v.muted}})()"#)
-          .map_err(|e| e.to_string())?;
+        wv.eval(media_session::MUTE_JS).map_err(|e| e.to_string())?;
     }
     Ok(())
 }
@@ -2107,6 +3716,74 @@ async fn unregister_panel(app: tauri::AppHandle, id: String) -> Result<(), Strin
     Ok(())
 }
 
+#[derive(serde::Serialize)]
+struct WebviewInfo {
+    id: String,
+    // "tab", "panel", "glance", "preload", or "unknown" for a webview no registry owns
+    role: &'static str,
+    visible: bool,
+    url: String,
+}
+
+/// Every live webview except the UI itself, for spotting leaked ones
+#[tauri::command]
+async fn list_webviews(app: tauri::AppHandle) -> Result<Vec<WebviewInfo>, BushidoError> {
+    let tabs = app.state::<WebviewState>().tabs.lock().clone();
+    let panels = app.state::<PanelState>().ids.lock().clone();
+    let urls = app.state::<UrlState>().tabs.lock().clone();
+    let mut list: Vec<WebviewInfo> = app.webviews().into_iter()
+        .filter(|(id, _)| id != "main")
+        .map(|(id, wv)| {
+            // panels are registered on top of their create_tab entry, so check them first
+            let role = if panels.contains(&id) {
+                if id.starts_with("glance-") { "glance" } else if id.starts_with("preload-") { "preload" } else { "panel" }
+            } else if tabs.contains_key(&id) {
+                "tab"
+            } else {
+                "unknown"
+            };
+            // layout_webviews parks hidden webviews at -9999
+            let visible = wv.position().map(|p| p.x > -9000 && p.y > -9000).unwrap_or(false);
+            let url = urls.get(&id).cloned()
+                .or_else(|| wv.url().ok().map(|u| u.to_string()))
+                .unwrap_or_default();
+            WebviewInfo { id, role, visible, url }
+        })
+        .collect();
+    list.sort_by(|a, b| a.role.cmp(b.role).then_with(|| a.id.cmp(&b.id)));
+    Ok(list)
+}
+
+/// Close every panel, glance and preload webview — they outlive a crashed UI since
+/// only React closes them. Returns how many were closed.
+#[tauri::command]
+async fn close_all_panels(app: tauri::AppHandle) -> Result<usize, BushidoError> {
+    let ids: Vec<String> = app.state::<PanelState>().ids.lock().drain().collect();
+    {
+        let ps = app.state::<PreloadState>();
+        if ps.webview_id.lock().as_ref().is_some_and(|id| ids.contains(id)) {
+            *ps.webview_id.lock() = None;
+            *ps.url.lock() = None;
+            *ps.source_tab.lock() = None;
+        }
+    }
+    let mut closed = 0;
+    for id in ids {
+        // panels opened through create_tab also need its per-tab state dropped
+        let is_tab = app.state::<WebviewState>().tabs.lock().contains_key(&id);
+        if is_tab {
+            if close_tab(app.clone(), id, None, None, None, None, None).await.is_ok() {
+                closed += 1;
+            }
+        } else if let Some(wv) = app.get_webview(&id) {
+            if wv.close().is_ok() {
+                closed += 1;
+            }
+        }
+    }
+    Ok(closed)
+}
+
 #[tauri::command]
 async fn position_panel(app: tauri::AppHandle, id: String, x: f64, y: f64, w: f64, h: f64) -> Result<(), String> {
     if let Some(wv) = app.get_webview(&id) {
@@ -2118,17 +3795,77 @@ async fn position_panel(app: tauri::AppHandle, id: String, x: f64, y: f64, w: f6
 
 // legacy wrapper
 #[tauri::command]
-async fn resize_webviews(app: tauri::AppHandle, active_id: String, split_id: String, sidebar_w: f64, top_offset: f64) -> Result<(), String> {
+async fn resize_webviews(app: tauri::AppHandle, active_id: String, split_id: String, sidebar_w: f64, top_offset: f64) -> Result<(), BushidoError> {
     switch_tab(app, active_id, split_id, sidebar_w, top_offset).await
 }
 
-fn data_dir(app: &tauri::AppHandle) -> PathBuf {
-    let dir = app.path().app_data_dir().unwrap_or_else(|_| PathBuf::from("."));
+// Portable mode: an empty `portable.txt` next to the executable moves everything — settings,
+// session, vault, sync, download manifests, logs and the WebView2 profile — into a `data`
+// folder beside it. Every path in the app is derived from base_data_dir(); nothing should
+// call app.path().app_data_dir() directly.
+const PORTABLE_MARKER: &str = "portable.txt";
+// tauri.conf.json `identifier`; app_data_dir() is the OS data dir joined with it, and the
+// non-portable base stays there so existing installs keep their data
+const APP_IDENTIFIER: &str = "app.bushido-browser.desktop";
+// where older builds kept everything (sync identity and doc, paired devices, crash
+// logs, adblock cache, ...)
+const LEGACY_DATA_DIR: &str = "com.bushido.browser";
+static BASE_DATA_DIR: std::sync::OnceLock<(PathBuf, bool)> = std::sync::OnceLock::new();
+
+fn resolve_base_dir() -> &'static (PathBuf, bool) {
+    BASE_DATA_DIR.get_or_init(|| {
+        let exe_dir = std::env::current_exe().ok().and_then(|p| p.parent().map(|d| d.to_path_buf()));
+        if let Some(dir) = exe_dir {
+            if dir.join(PORTABLE_MARKER).is_file() {
+                return (dir.join("data"), true);
+            }
+        }
+        let os_dir = dirs::data_dir().unwrap_or_else(|| PathBuf::from("."));
+        (adopt_legacy_dir(os_dir.join(APP_IDENTIFIER), os_dir.join(LEGACY_DATA_DIR)), false)
+    })
+}
+
+/// First run after the identifier change: move the old data dir into place. If it
+/// can't be moved (files held open, another user's permissions) keep using it.
+fn adopt_legacy_dir(dir: PathBuf, legacy: PathBuf) -> PathBuf {
+    if dir.exists() || !legacy.is_dir() {
+        return dir;
+    }
+    match fs::rename(&legacy, &dir) {
+        Ok(()) => dir,
+        Err(e) => {
+            eprintln!("[bushido] could not move {} to {}: {}", legacy.display(), dir.display(), e);
+            legacy
+        }
+    }
+}
+
+fn base_data_dir() -> PathBuf {
+    let dir = resolve_base_dir().0.clone();
     let _ = fs::create_dir_all(&dir);
     dir
 }
 
-fn session_path(app: &tauri::AppHandle) -> PathBuf {
+fn is_portable() -> bool {
+    resolve_base_dir().1
+}
+
+fn data_dir(_app: &tauri::AppHandle) -> PathBuf {
+    base_data_dir()
+}
+
+#[tauri::command]
+async fn get_data_location() -> Result<serde_json::Value, String> {
+    Ok(serde_json::json!({
+        "path": base_data_dir().to_string_lossy(),
+        "portable": is_portable(),
+    }))
+}
+
+/// save_session and the scroll flush both rewrite session.json
+pub(crate) static SESSION_FILE_LOCK: Mutex<()> = Mutex::new(());
+
+pub(crate) fn session_path(app: &tauri::AppHandle) -> PathBuf {
     data_dir(app).join("session.json")
 }
 
@@ -2156,12 +3893,36 @@ fn save_whitelist(app: &tauri::AppHandle, sites: &HashSet<String>) {
     }
 }
 
-fn permissions_path(app: &tauri::AppHandle) -> PathBuf {
-    data_dir(app).join("permissions.json")
+fn cert_overrides_path(app: &tauri::AppHandle) -> PathBuf {
+    data_dir(app).join("cert_overrides.json")
+}
+
+fn load_cert_overrides(app: &tauri::AppHandle) -> HashSet<String> {
+    let path = cert_overrides_path(app);
+    if path.exists() {
+        if let Ok(data) = fs::read_to_string(&path) {
+            if let Ok(set) = serde_json::from_str::<HashSet<String>>(&data) {
+                return set;
+            }
+        }
+    }
+    HashSet::new()
+}
+
+#[cfg(windows)]
+fn save_cert_overrides(app: &tauri::AppHandle, overrides: &HashSet<String>) {
+    let path = cert_overrides_path(app);
+    if let Ok(json) = serde_json::to_string(overrides) {
+        let _ = fs::write(&path, json);
+    }
+}
+
+fn autoplay_path(app: &tauri::AppHandle) -> PathBuf {
+    data_dir(app).join("autoplay.json")
 }
 
-fn load_permissions(app: &tauri::AppHandle) -> HashMap<String, bool> {
-    let path = permissions_path(app);
+fn load_autoplay_sites(app: &tauri::AppHandle) -> HashMap<String, bool> {
+    let path = autoplay_path(app);
     if path.exists() {
         if let Ok(data) = fs::read_to_string(&path) {
             if let Ok(map) = serde_json::from_str::<HashMap<String, bool>>(&data) {
@@ -2172,13 +3933,78 @@ fn load_permissions(app: &tauri::AppHandle) -> HashMap<String, bool> {
     HashMap::new()
 }
 
-fn save_permissions(app: &tauri::AppHandle, perms: &HashMap<String, bool>) {
-    let path = permissions_path(app);
-    if let Ok(json) = serde_json::to_string(perms) {
+fn save_autoplay_sites(app: &tauri::AppHandle, sites: &HashMap<String, bool>) {
+    let path = autoplay_path(app);
+    if let Ok(json) = serde_json::to_string(sites) {
         let _ = fs::write(&path, json);
     }
 }
 
+fn privacy_signal_path(app: &tauri::AppHandle) -> PathBuf {
+    data_dir(app).join("privacy_signal_exempt.json")
+}
+
+fn load_privacy_signal_exempt(app: &tauri::AppHandle) -> HashSet<String> {
+    fs::read_to_string(privacy_signal_path(app)).ok()
+        .and_then(|data| serde_json::from_str::<Vec<String>>(&data).ok())
+        .map(|v| v.into_iter().collect())
+        .unwrap_or_default()
+}
+
+fn save_privacy_signal_exempt(app: &tauri::AppHandle, sites: &HashSet<String>) {
+    let list: Vec<&String> = sites.iter().collect();
+    if let Ok(json) = serde_json::to_string(&list) {
+        let _ = fs::write(privacy_signal_path(app), json);
+    }
+}
+
+/// Turn DNT / GPC off (or back on) for one site, for sites that break on them.
+/// These are requests, not enforcement — plenty of sites ignore both signals.
+/// Headers change immediately; navigator.* follows on the next tab/reload.
+#[tauri::command]
+async fn set_site_privacy_signals(app: tauri::AppHandle, domain: String, enabled: bool) -> Result<(), String> {
+    let domain = domain.trim().to_lowercase();
+    if domain.is_empty() { return Err("empty domain".into()); }
+    let ps = app.state::<PrivacySignalState>();
+    let snapshot = {
+        let mut exempt = ps.exempt.lock();
+        if enabled { exempt.remove(&domain); } else { exempt.insert(domain); }
+        exempt.clone()
+    };
+    save_privacy_signal_exempt(&app, &snapshot);
+    Ok(())
+}
+
+#[tauri::command]
+async fn get_privacy_signal_exemptions(app: tauri::AppHandle) -> Result<Vec<String>, String> {
+    let ps = app.state::<PrivacySignalState>();
+    let exempt = ps.exempt.lock();
+    Ok(exempt.iter().cloned().collect())
+}
+
+/// Allow or block autoplay for a domain. Sound autoplay follows it right away; the
+/// page script that stops muted media picks it up on the next load.
+#[tauri::command]
+async fn set_autoplay_policy(app: tauri::AppHandle, domain: String, allow: bool) -> Result<(), String> {
+    let domain = domain.trim().to_lowercase();
+    if domain.is_empty() { return Err("empty domain".into()); }
+    let aps = app.state::<AutoplayState>();
+    let snapshot = {
+        let mut sites = aps.sites.lock();
+        sites.insert(domain, allow);
+        sites.clone()
+    };
+    save_autoplay_sites(&app, &snapshot);
+    Ok(())
+}
+
+#[tauri::command]
+async fn get_site_autoplay(app: tauri::AppHandle) -> Result<HashMap<String, bool>, String> {
+    let aps = app.state::<AutoplayState>();
+    let sites = aps.sites.lock();
+    Ok(sites.clone())
+}
+
 #[tauri::command]
 async fn toggle_whitelist(app: tauri::AppHandle, domain: String) -> Result<bool, String> {
     let ws = app.state::<WhitelistState>();
@@ -2211,82 +4037,62 @@ async fn is_whitelisted(app: tauri::AppHandle, domain: String) -> Result<bool, S
     Ok(sites.contains(&domain))
 }
 
+/// Answer a certificate-error prompt. `remember` trusts this exact cert for the host from now on;
+/// without it the allow only lasts until the tab's page has loaded, and that clears the
+/// one-time allows of every tab in the same profile (see clear_profile_cert_allowances).
 #[tauri::command]
-async fn respond_permission(app: tauri::AppHandle, request_id: String, allow: bool, remember: bool) -> Result<(), String> {
+async fn respond_cert_error(app: tauri::AppHandle, request_id: String, proceed: bool, remember: bool) -> Result<(), BushidoError> {
     #[cfg(windows)]
     {
-        use webview2_com::Microsoft::Web::WebView2::Win32::*;
-
-        let perm_state = app.state::<PermissionState>();
-        let pending = perm_state.pending.lock()
-            .remove(&request_id);
-        let pending = pending.ok_or("Permission request not found")?;
-
-        let state_val = COREWEBVIEW2_PERMISSION_STATE(if allow { 1 } else { 2 });
-        unsafe {
-            let _ = pending.args.SetState(state_val);
-            let _ = pending.deferral.Complete();
-        }
-
-        if remember {
-            let mut uri_pw = windows::core::PWSTR::null();
-            unsafe { let _ = pending.args.Uri(&mut uri_pw); }
-            let uri = if !uri_pw.is_null() { unsafe { uri_pw.to_string().unwrap_or_default() } } else { String::new() };
-            let domain = url::Url::parse(&uri)
-                .ok()
-                .and_then(|u| u.host_str().map(|h| h.to_lowercase()))
-                .unwrap_or_default();
-
-            let mut kind_val = COREWEBVIEW2_PERMISSION_KIND(0);
-            unsafe { let _ = pending.args.PermissionKind(&mut kind_val); }
-            let kind_str = match kind_val.0 {
-                1 => "microphone", 2 => "camera", 3 => "geolocation",
-                4 => "notifications", 5 => "othersensors", 6 => "clipboardread",
-                7 => "multipledownloads", 8 => "filereadwrite", 9 => "autoplay",
-                10 => "localfonts", 11 => "midi", 12 => "windowmanagement",
-                _ => "unknown",
-            };
-            let kind_key = format!("{}:{}", domain, kind_str);
-
-            let mut saved = perm_state.saved.lock();
-            saved.insert(kind_key, allow);
-            save_permissions(&app, &saved);
+        let pending = finish_cert_error(&app, &request_id, proceed)
+            .ok_or_else(|| BushidoError::NotFound("cert request not found".into()))?;
+
+        let ces = app.state::<CertErrorState>();
+        if proceed && remember && !pending.thumbprint.is_empty() {
+            let mut saved = ces.saved.lock();
+            saved.insert(format!("{}|{}", pending.host, pending.thumbprint));
+            save_cert_overrides(&app, &saved);
+        } else if proceed {
+            ces.allow_once.lock().insert(pending.tab_id);
         }
     }
-
+    #[cfg(not(windows))]
+    { let _ = (app, request_id, proceed, remember); }
     Ok(())
 }
 
-#[derive(Clone, serde::Serialize)]
-#[serde(rename_all = "camelCase")]
-struct SavedPermissionEntry {
-    domain: String,
-    permission: String,
-    allowed: bool,
-}
-
+/// Answer a basic-auth prompt. `vault_entry_id` fills from the vault so the password never reaches React.
 #[tauri::command]
-async fn get_permissions(app: tauri::AppHandle) -> Result<Vec<SavedPermissionEntry>, String> {
-    let ps = app.state::<PermissionState>();
-    let saved = ps.saved.lock();
-    let result: Vec<SavedPermissionEntry> = saved.iter().map(|(key, &allowed)| {
-        let parts: Vec<&str> = key.splitn(2, ':').collect();
-        SavedPermissionEntry {
-            domain: parts.first().unwrap_or(&"").to_string(),
-            permission: parts.get(1).unwrap_or(&"unknown").to_string(),
-            allowed,
+async fn respond_basic_auth(app: tauri::AppHandle, request_id: String, username: String, password: String, cancel: bool, vault_entry_id: Option<String>) -> Result<(), BushidoError> {
+    #[cfg(windows)]
+    {
+        if cancel {
+            finish_basic_auth(&app, &request_id, None);
+            return Ok(());
         }
-    }).collect();
-    Ok(result)
-}
-
-#[tauri::command]
-async fn revoke_permission(app: tauri::AppHandle, domain: String, permission: String) -> Result<(), String> {
-    let ps = app.state::<PermissionState>();
-    let mut saved = ps.saved.lock();
-    let key = format!("{}:{}", domain, permission);
-    saved.remove(&key);
-    save_permissions(&app, &saved);
+        let host = app.state::<BasicAuthState>().pending.lock()
+            .get(&request_id)
+            .map(|p| p.host.clone())
+            .ok_or_else(|| BushidoError::NotFound("auth request not found".into()))?;
+
+        let (user, pass) = match vault_entry_id {
+            Some(entry_id) => {
+                let vs = app.state::<vault::VaultState>();
+                if vs.derived_key.lock().is_none() { return Err(BushidoError::Locked); }
+                let entry = vault::get_entries_for_domain(&vs, &host)?
+                    .into_iter()
+                    .find(|e| e.id == entry_id)
+                    .ok_or_else(|| BushidoError::NotFound("vault entry not found".into()))?;
+                (entry.username, entry.password)
+            }
+            None => (username, password),
+        };
+        if !finish_basic_auth(&app, &request_id, Some((&user, &pass))) {
+            return Err(BushidoError::NotFound("auth request expired".into()));
+        }
+    }
+    #[cfg(not(windows))]
+    { let _ = (app, request_id, username, password, cancel, vault_entry_id); }
     Ok(())
 }
 
@@ -2333,21 +4139,79 @@ fn rotate_session_backups(app: &tauri::AppHandle) {
     let _ = fs::copy(&session, dir.join("session.backup.1.json"));
 }
 
+fn session_tabs(session: &mut serde_json::Value) -> Option<&mut Vec<serde_json::Value>> {
+    session.get_mut("tabs").and_then(|t| t.as_array_mut())
+}
+
+fn is_pinned_entry(tab: &serde_json::Value) -> bool {
+    tab.get("pinned").and_then(|p| p.as_bool()).unwrap_or(false)
+}
+
 #[tauri::command]
 async fn save_session(app: tauri::AppHandle, tabs: String) -> Result<(), String> {
     rotate_session_backups(&app);
     let path = session_path(&app);
-    fs::write(&path, tabs).map_err(|e| e.to_string())
+    // PinnedState, not the payload, decides what's pinned (the UI pins through set_tab_pinned); pinned tabs go first
+    let data = match serde_json::from_str::<serde_json::Value>(&tabs) {
+        Ok(mut session) => {
+            let mut pinned = app.state::<PinnedState>().tabs.lock();
+            if let Some(list) = session_tabs(&mut session) {
+                let mut ids = HashSet::new();
+                let private = app.state::<WebviewState>().private.lock().clone();
+                list.retain(|t| t.get("id").and_then(|v| v.as_str()).map_or(true, |id| !private.contains(id)));
+                for tab in list.iter_mut() {
+                    let Some(id) = tab.get("id").and_then(|v| v.as_str()).map(String::from) else { continue };
+                    tab["pinned"] = serde_json::json!(pinned.contains(&id));
+                    ids.insert(id);
+                }
+                list.sort_by_key(|t| !is_pinned_entry(t));
+                pinned.retain(|id| ids.contains(id));
+                app.state::<scroll_restore::ScrollState>().stamp_session(list);
+            }
+            let closed: Vec<ClosedTab> = app.state::<RecentlyClosedState>().tabs.lock().iter().cloned().collect();
+            let saved_layout = app.state::<layout::LayoutState>().saved.lock().clone();
+            if let Some(obj) = session.as_object_mut() {
+                obj.insert("recentlyClosed".into(), serde_json::json!(closed));
+                obj.insert("layout".into(), serde_json::json!(saved_layout));
+            }
+            app.state::<tab_groups::TabGroupState>().stamp_session(&mut session);
+            session.to_string()
+        }
+        Err(_) => tabs,
+    };
+    let _guard = SESSION_FILE_LOCK.lock();
+    fs::write(&path, data).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
 async fn load_session(app: tauri::AppHandle) -> Result<String, String> {
     let path = session_path(&app);
-    if path.exists() {
-        fs::read_to_string(&path).map_err(|e| e.to_string())
-    } else {
-        Ok("[]".into())
+    if !path.exists() {
+        return Ok("[]".into());
     }
+    let data = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    let Ok(mut session) = serde_json::from_str::<serde_json::Value>(&data) else { return Ok(data) };
+    if let Some(closed) = session.get("recentlyClosed")
+        .and_then(|v| serde_json::from_value::<VecDeque<ClosedTab>>(v.clone()).ok())
+    {
+        *app.state::<RecentlyClosedState>().tabs.lock() = closed;
+    }
+    if let Some(snapshot) = session.get("layout")
+        .and_then(|v| serde_json::from_value::<layout::LayoutSnapshot>(v.clone()).ok())
+    {
+        *app.state::<layout::LayoutState>().saved.lock() = Some(snapshot);
+    }
+    app.state::<tab_groups::TabGroupState>().restore_from_session(&session);
+    let Some(list) = session_tabs(&mut session) else { return Ok(data) };
+    // pinned tabs are restored first
+    list.sort_by_key(|t| !is_pinned_entry(t));
+    let ids: HashSet<String> = list.iter()
+        .filter(|t| is_pinned_entry(t))
+        .filter_map(|t| t.get("id").and_then(|v| v.as_str()).map(String::from))
+        .collect();
+    *app.state::<PinnedState>().tabs.lock() = ids;
+    app.state::<scroll_restore::ScrollState>().restore_from_session(list);
+    Ok(session.to_string())
 }
 
 #[tauri::command]
@@ -2395,6 +4259,9 @@ async fn save_settings(app: tauri::AppHandle, data: String) -> Result<(), String
 #[tauri::command]
 async fn set_dns_level(app: tauri::AppHandle, level: String) -> Result<(), String> {
     let port = app.state::<DnsPort>().0;
+    if port == 0 && app.state::<DohState>().active.is_some() {
+        return Err("DNS protection is off while a custom DNS-over-HTTPS resolver is in use".into());
+    }
     if port == 0 { return Err("dns resolver not running".into()); }
     let client = reqwest::Client::new();
     client.post(format!("http://127.0.0.1:{}/level", port))
@@ -2405,6 +4272,35 @@ async fn set_dns_level(app: tauri::AppHandle, level: String) -> Result<(), Strin
 
 struct DnsPort(u16);
 
+/// The custom DoH resolver the browser started with (None = the local resolver)
+struct DohState {
+    active: Option<String>,
+}
+
+/// Check a custom DNS-over-HTTPS resolver before the frontend saves it as
+/// `customDoh`/`dohTemplate`. Browser flags are fixed once WebView2 starts, so a change
+/// only applies after a restart; the UI is told with a `restart-required` event.
+/// Returns the normalized template.
+#[tauri::command]
+async fn set_doh_resolver(app: tauri::AppHandle, enabled: bool, template: String) -> Result<String, BushidoError> {
+    let template = if enabled {
+        dns_resolver::validate_template(&template).map_err(BushidoError::Other)?
+    } else {
+        template.trim().to_string()
+    };
+    let wanted = enabled.then(|| template.clone());
+    if wanted != app.state::<DohState>().active {
+        // the local resolver (and with it DNS-level blocking) only runs without a custom one
+        let message = if enabled {
+            "Restart Bushido to apply the new DNS-over-HTTPS resolver. DNS protection's tracker blocking is off while it's in use."
+        } else {
+            "Restart Bushido to go back to DNS protection"
+        };
+        let _ = app.emit_to("main", "restart-required", serde_json::json!({ "message": message }));
+    }
+    Ok(template)
+}
+
 #[tauri::command]
 async fn load_settings(app: tauri::AppHandle) -> Result<String, String> {
     let p = settings_path(&app);
@@ -2413,7 +4309,9 @@ async fn load_settings(app: tauri::AppHandle) -> Result<String, String> {
 
 #[tauri::command]
 async fn save_history(app: tauri::AppHandle, data: String) -> Result<(), String> {
-    fs::write(history_path(&app), data).map_err(|e| e.to_string())
+    fs::write(history_path(&app), data).map_err(|e| e.to_string())?;
+    app.state::<history_search::HistoryIndex>().invalidate();
+    Ok(())
 }
 
 #[tauri::command]
@@ -2424,9 +4322,9 @@ async fn load_history(app: tauri::AppHandle) -> Result<String, String> {
 
 #[tauri::command]
 async fn save_bookmarks(app: tauri::AppHandle, data: String) -> Result<(), String> {
-    // if sync enabled, write through SyncDoc
+    // if bookmark sync is on, write through SyncDoc
     let state = app.try_state::<sync::SyncState>();
-    if let Some(state) = state {
+    if let Some(state) = state.filter(|s| s.syncs(sync::DataType::Bookmarks)) {
         let mut doc_guard = state.sync_doc.lock().await;
         if let Some(ref mut doc) = *doc_guard {
             doc.write_full_from_json(&data)?;
@@ -2442,12 +4340,12 @@ async fn save_bookmarks(app: tauri::AppHandle, data: String) -> Result<(), Strin
 
 #[tauri::command]
 async fn load_bookmarks(app: tauri::AppHandle) -> Result<String, String> {
-    // if sync enabled, read from SyncDoc
+    // if bookmark sync is on, read from SyncDoc
     let state = app.try_state::<sync::SyncState>();
-    if let Some(state) = state {
+    if let Some(state) = state.filter(|s| s.syncs(sync::DataType::Bookmarks)) {
         let doc_guard = state.sync_doc.lock().await;
         if let Some(ref doc) = *doc_guard {
-            return doc.read_bookmarks_as_json();
+            return doc.read_bookmarks_with_token_json();
         }
     }
     // fallback: plain JSON
@@ -2455,33 +4353,119 @@ async fn load_bookmarks(app: tauri::AppHandle) -> Result<String, String> {
     if p.exists() { fs::read_to_string(&p).map_err(|e| e.to_string()) } else { Ok(r#"{"bookmarks":[],"folders":[]}"#.into()) }
 }
 
+const MAX_FOLDER_TABS: usize = 20;
+
+#[derive(Clone, serde::Serialize)]
+struct OpenedTab {
+    id: String,
+    url: String,
+    title: String,
+}
+
+/// Open a bookmark folder's own bookmarks (not its subfolders') as tabs, in stored
+/// order. Opens at most MAX_FOLDER_TABS and never more than the tab limit has room
+/// for; the rest of the tab args are the same as create_tab's.
 #[tauri::command]
-async fn start_download(app: tauri::AppHandle, url: String, filename: String, download_dir: String, cookies: Option<String>, mime_routing: Option<Vec<downloads::MimeRoute>>) -> Result<String, String> {
+async fn open_bookmark_folder(app: tauri::AppHandle, folder_id: String, sidebar_w: f64, top_offset: f64, https_only: bool, ad_blocker: bool, cookie_auto_reject: bool, profile_name: Option<String>, disable_dev_tools: Option<bool>, disable_status_bar: Option<bool>, disable_autofill: Option<bool>, disable_password_save: Option<bool>, block_service_workers: Option<bool>, block_font_enum: Option<bool>, block_popups: Option<bool>, default_zoom: Option<f64>, privacy_signals: Option<bool>) -> Result<Vec<OpenedTab>, BushidoError> {
+    let data: serde_json::Value = serde_json::from_str(&load_bookmarks(app.clone()).await?)
+        .map_err(|e| format!("bookmarks: {}", e))?;
+    let bookmarks: Vec<(String, String)> = data["bookmarks"].as_array()
+        .map(|arr| arr.iter()
+            .filter(|b| b["folderId"].as_str() == Some(folder_id.as_str()))
+            .filter_map(|b| Some((b["url"].as_str()?.to_string(), b["title"].as_str().unwrap_or("").to_string())))
+            .collect())
+        .unwrap_or_default();
+    if bookmarks.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let room = {
+        let max = app.state::<TabLimitState>().max.load(Ordering::Relaxed);
+        max.saturating_sub(app.state::<WebviewState>().tabs.lock().len())
+    };
+    if room == 0 {
+        return Err(BushidoError::Limit("Tab limit reached".into()));
+    }
+
+    // frontend ids are tab-<counter>; the timestamp keeps these from colliding with them
+    let stamp = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_millis();
+    let mut opened = Vec::new();
+    for (i, (url, title)) in bookmarks.into_iter().take(MAX_FOLDER_TABS.min(room)).enumerate() {
+        let id = format!("tab-bm-{}-{}", stamp, i);
+        let created = create_tab(app.clone(), id.clone(), url.clone(), sidebar_w, top_offset, https_only, ad_blocker, cookie_auto_reject, false, profile_name.clone(), None, None, disable_dev_tools, disable_status_bar, disable_autofill, disable_password_save, block_service_workers, block_font_enum, block_popups, default_zoom, privacy_signals, None, None).await;
+        match created {
+            Ok(()) => opened.push(OpenedTab { id, url, title }),
+            // a bad URL shouldn't cost the rest of the folder
+            Err(e) => crash_log::log_warn("open_bookmark_folder", &format!("skipped {}: {}", url, e)),
+        }
+    }
+    Ok(opened)
+}
+
+/// Resolve where a download goes. With `ask`, shows the save dialog (off the WebView2 handler —
+/// this runs inside the async command); a picked path bypasses MIME routing, cancel falls back.
+async fn download_target(app: &tauri::AppHandle, download_dir: String, filename: String, ask: bool, mime_routing: Option<Vec<downloads::MimeRoute>>) -> (String, String, Vec<downloads::MimeRoute>) {
     let dir = if download_dir.is_empty() {
         dirs::download_dir().unwrap_or_else(|| PathBuf::from(".")).to_string_lossy().to_string()
     } else {
         download_dir
     };
+    if ask {
+        if let Some((picked_dir, picked_name)) = downloads::prompt_save_location(app, &dir, &filename).await {
+            return (picked_dir, picked_name, Vec::new());
+        }
+    }
+    (dir, filename, mime_routing.unwrap_or_default())
+}
+
+#[tauri::command]
+async fn start_download(app: tauri::AppHandle, url: String, filename: String, download_dir: String, cookies: Option<String>, mime_routing: Option<Vec<downloads::MimeRoute>>, ask_where_to_save: Option<bool>) -> Result<String, BushidoError> {
+    let (dir, filename, routing) = download_target(&app, download_dir, filename, ask_where_to_save.unwrap_or(false), mime_routing).await;
+    let rl = app.state::<std::sync::Arc<downloads::RateLimiter>>();
+    downloads::start(app.clone(), url, filename, dir, cookies, routing, rl.inner().clone()).await
+}
+
+/// "Save link/image as" from the page context menu — uses the tab's cookies so authenticated resources work
+#[tauri::command]
+async fn download_from_context(app: tauri::AppHandle, tab_id: String, uri: String, suggested_name: Option<String>, download_dir: String, mime_routing: Option<Vec<downloads::MimeRoute>>, ask_where_to_save: Option<bool>) -> Result<String, BushidoError> {
+    if is_blocked_scheme(&uri) {
+        return Err(BushidoError::Permission("Blocked URL scheme".into()));
+    }
+    let parsed = url::Url::parse(&uri)?;
+    if parsed.scheme() != "https" && parsed.scheme() != "http" {
+        return Err(BushidoError::Permission("Only http(s) links can be downloaded".into()));
+    }
+    let filename = suggested_name
+        .filter(|n| !n.trim().is_empty())
+        .unwrap_or_else(|| downloads::parse_filename(&uri, ""));
+    let cookies = tab_cookies(&app, &tab_id, &uri).await;
+    let (dir, filename, routing) = download_target(&app, download_dir, filename, ask_where_to_save.unwrap_or(false), mime_routing).await;
     let rl = app.state::<std::sync::Arc<downloads::RateLimiter>>();
-    downloads::start(app.clone(), url, filename, dir, cookies, mime_routing.unwrap_or_default(), rl.inner().clone()).await
+    downloads::start(app.clone(), uri, filename, dir, cookies, routing, rl.inner().clone()).await
 }
 
 #[tauri::command]
-async fn pause_download(app: tauri::AppHandle, id: String) -> Result<(), String> {
+async fn pause_download(app: tauri::AppHandle, id: String) -> Result<(), BushidoError> {
     downloads::pause(&app, &id)
 }
 
 #[tauri::command]
-async fn resume_download(app: tauri::AppHandle, id: String) -> Result<(), String> {
+async fn resume_download(app: tauri::AppHandle, id: String) -> Result<(), BushidoError> {
     let rl = app.state::<std::sync::Arc<downloads::RateLimiter>>();
     downloads::resume(app.clone(), id, rl.inner().clone()).await
 }
 
 #[tauri::command]
-async fn reorder_download(app: tauri::AppHandle, id: String, priority: u32) -> Result<(), String> {
+async fn reorder_download(app: tauri::AppHandle, id: String, priority: u32) -> Result<(), BushidoError> {
     downloads::set_priority(&app, &id, priority)
 }
 
+/// Set how many downloads run at once; the rest wait queued. Returns the value actually applied.
+#[tauri::command]
+async fn set_max_concurrent_downloads(app: tauri::AppHandle, n: usize) -> Result<usize, String> {
+    Ok(downloads::set_max_concurrent(&app, n))
+}
+
 #[tauri::command]
 async fn set_bandwidth_limit(app: tauri::AppHandle, limit: u64) -> Result<(), String> {
     let rl = app.state::<std::sync::Arc<downloads::RateLimiter>>();
@@ -2489,103 +4473,212 @@ async fn set_bandwidth_limit(app: tauri::AppHandle, limit: u64) -> Result<(), St
     Ok(())
 }
 
+/// Set the open-tab cap, clamped to a sane range. Returns the value actually applied.
+#[tauri::command]
+async fn set_tab_limit(app: tauri::AppHandle, limit: usize) -> Result<usize, String> {
+    let limit = limit.clamp(*MAX_TABS_RANGE.start(), *MAX_TABS_RANGE.end());
+    app.state::<TabLimitState>().max.store(limit, Ordering::Relaxed);
+    Ok(limit)
+}
+
+#[tauri::command]
+async fn get_tab_limit(app: tauri::AppHandle) -> Result<usize, String> {
+    Ok(app.state::<TabLimitState>().max.load(Ordering::Relaxed))
+}
+
+/// Toggle work-offline mode. Going offline pauses active downloads; coming back
+/// resumes them and kicks off a sync.
+#[tauri::command]
+async fn set_offline(app: tauri::AppHandle, offline: bool) -> Result<(), BushidoError> {
+    let os = app.state::<OfflineState>();
+    if os.enabled.swap(offline, Ordering::Relaxed) == offline {
+        return Ok(());
+    }
+
+    if offline {
+        let active: Vec<String> = app.state::<downloads::DownloadManager>().downloads.lock().values()
+            .filter(|d| matches!(d.state, downloads::DlState::Queued | downloads::DlState::Downloading))
+            .map(|d| d.id.clone())
+            .collect();
+        for id in &active {
+            let _ = downloads::pause(&app, id);
+        }
+        *os.paused_downloads.lock() = active;
+    } else {
+        let paused = std::mem::take(&mut *os.paused_downloads.lock());
+        let rl = app.state::<std::sync::Arc<downloads::RateLimiter>>().inner().clone();
+        for id in paused {
+            let still_paused = app.state::<downloads::DownloadManager>().downloads.lock()
+                .get(&id).is_some_and(|d| d.state == downloads::DlState::Paused);
+            if still_paused {
+                if let Err(e) = downloads::resume(app.clone(), id.clone(), rl.clone()).await {
+                    crash_log::log_warn("offline", &format!("resume {} failed: {}", id, e));
+                }
+            }
+        }
+        sync::trigger_sync(app.clone());
+    }
+
+    let _ = app.emit_to("main", "offline-changed", serde_json::json!({ "offline": offline }));
+    Ok(())
+}
+
 #[tauri::command]
-async fn cancel_download(app: tauri::AppHandle, id: String) -> Result<(), String> {
+async fn cancel_download(app: tauri::AppHandle, id: String) -> Result<(), BushidoError> {
     downloads::cancel(&app, &id)
 }
 
 #[tauri::command]
-async fn get_downloads(app: tauri::AppHandle) -> Result<Vec<downloads::DlItem>, String> {
+async fn remove_download(app: tauri::AppHandle, id: String, delete_file: bool) -> Result<(), BushidoError> {
+    downloads::remove(&app, &id, delete_file)
+}
+
+#[tauri::command]
+async fn clear_completed_downloads(app: tauri::AppHandle) -> Result<usize, BushidoError> {
+    Ok(downloads::clear_completed(&app))
+}
+
+/// Export the download list. Without a path the user picks one; returns the written path,
+/// or None if the dialog was cancelled.
+#[tauri::command]
+async fn export_downloads(app: tauri::AppHandle, path: Option<String>) -> Result<Option<String>, BushidoError> {
+    let path = match path {
+        Some(p) => std::path::PathBuf::from(p),
+        None => {
+            use tauri_plugin_dialog::DialogExt;
+            let (tx, rx) = tokio::sync::oneshot::channel();
+            app.dialog()
+                .file()
+                .add_filter("CSV", &["csv"])
+                .add_filter("JSON", &["json"])
+                .set_file_name("downloads.csv")
+                .save_file(move |p| { let _ = tx.send(p); });
+            match rx.await.ok().flatten().and_then(|p| p.into_path().ok()) {
+                Some(p) => p,
+                None => return Ok(None),
+            }
+        }
+    };
+    downloads::export(&app, &path)?;
+    Ok(Some(path.to_string_lossy().to_string()))
+}
+
+#[tauri::command]
+async fn get_downloads(app: tauri::AppHandle) -> Result<Vec<downloads::DlItem>, BushidoError> {
     let dm = app.state::<downloads::DownloadManager>();
     let downloads = dm.downloads.lock();
     Ok(downloads.values().cloned().collect())
 }
 
 #[tauri::command]
-async fn open_download(app: tauri::AppHandle, id: String) -> Result<(), String> {
+async fn open_download(app: tauri::AppHandle, id: String) -> Result<(), BushidoError> {
     let dm = app.state::<downloads::DownloadManager>();
     let path = {
         let downloads = dm.downloads.lock();
-        let item = downloads.get(&id).ok_or("not found")?;
+        let item = downloads.get(&id).ok_or_else(|| BushidoError::NotFound("not found".into()))?;
         item.file_path.clone()
     };
-    tauri_plugin_opener::open_path(&path, None::<&str>).map_err(|e| e.to_string())
+    tauri_plugin_opener::open_path(&path, None::<&str>).map_err(|e| BushidoError::Other(e.to_string()))
 }
 
 #[tauri::command]
-async fn open_download_folder(app: tauri::AppHandle, id: String) -> Result<(), String> {
+async fn open_download_folder(app: tauri::AppHandle, id: String) -> Result<(), BushidoError> {
     let dm = app.state::<downloads::DownloadManager>();
     let path = {
         let downloads = dm.downloads.lock();
-        let item = downloads.get(&id).ok_or("not found")?;
+        let item = downloads.get(&id).ok_or_else(|| BushidoError::NotFound("not found".into()))?;
         item.file_path.clone()
     };
     let parent = std::path::Path::new(&path).parent().map(|p| p.to_string_lossy().to_string()).unwrap_or(path);
-    tauri_plugin_opener::open_path(&parent, None::<&str>).map_err(|e| e.to_string())
+    tauri_plugin_opener::open_path(&parent, None::<&str>).map_err(|e| BushidoError::Other(e.to_string()))
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    // start local DoH resolver before any webview creation
-    let (dns_flags, dns_port) = {
-        let rt = tokio::runtime::Runtime::new().expect("tokio rt for dns");
-        match rt.block_on(dns_resolver::start()) {
-            Ok(port) => {
-                println!("DoH resolver on port {}", port);
-                std::thread::spawn(move || { rt.block_on(std::future::pending::<()>()); });
-                (dns_resolver::flags(port), port)
-            }
-            Err(e) => {
-                eprintln!("dns resolver failed: {}", e);
-                (String::new(), 0)
+    // read saved settings for browser args that need to be set before webview creation
+    let saved_settings: serde_json::Value = {
+        let settings_path = base_data_dir().join("settings.json");
+        std::fs::read_to_string(&settings_path).ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    };
+    let autoplay_policy = saved_settings.get("autoplayPolicy")
+        .and_then(|v| v.as_str().map(String::from))
+        .unwrap_or_else(|| "block-audio".into());
+    // crash_log isn't up yet; a rejected template is logged once it is
+    let (custom_doh, doh_rejected) = if saved_settings.get("customDoh").and_then(|v| v.as_bool()).unwrap_or(false) {
+        let template = saved_settings.get("dohTemplate").and_then(|v| v.as_str()).unwrap_or_default();
+        match dns_resolver::validate_template(template) {
+            Ok(t) => (Some(t), None),
+            Err(e) => (None, Some(e)),
+        }
+    } else {
+        (None, None)
+    };
+    let new_tab_url = saved_settings.get("newTabUrl").and_then(|v| v.as_str()).unwrap_or_default().to_string();
+    let startup_mode = startup::StartupMode::from_setting(saved_settings.get("onStartup"));
+    let startup_homepage = saved_settings.get("customHomepageUrl").and_then(|v| v.as_str()).unwrap_or_default().to_string();
+
+    // start local DoH resolver before any webview creation, unless the user picked their own
+    let (dns_flags, dns_port) = match &custom_doh {
+        Some(template) => (dns_resolver::custom_flags(template), 0),
+        None => {
+            let rt = tokio::runtime::Runtime::new().expect("tokio rt for dns");
+            match rt.block_on(dns_resolver::start()) {
+                Ok(port) => {
+                    println!("DoH resolver on port {}", port);
+                    std::thread::spawn(move || { rt.block_on(std::future::pending::<()>()); });
+                    (dns_resolver::flags(port), port)
+                }
+                Err(e) => {
+                    eprintln!("dns resolver failed: {}", e);
+                    (String::new(), 0)
+                }
             }
         }
     };
 
-    // read saved settings for browser args that need to be set before webview creation
-    let autoplay_arg = {
-        let settings_path = dirs::data_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join("com.bushido.browser")
-            .join("settings.json");
-        let policy = std::fs::read_to_string(&settings_path).ok()
-            .and_then(|s| serde_json::from_str::<serde_json::Value>(&s).ok())
-            .and_then(|v| v.get("autoplayPolicy")?.as_str().map(String::from))
-            .unwrap_or_else(|| "block-audio".into());
-        match policy.as_str() {
-            "block-all" => "--autoplay-policy=no-user-gesture-required",
-            "allow" => "--autoplay-policy=no-user-gesture-required",
-            _ => "--autoplay-policy=document-user-activation-required",
-        }
+    let autoplay_arg = match autoplay_policy.as_str() {
+        "block-all" => "--autoplay-policy=no-user-gesture-required",
+        "allow" => "--autoplay-policy=no-user-gesture-required",
+        _ => "--autoplay-policy=document-user-activation-required",
     };
+    // "block-all" is enforced per-site by media_listener.js (sites can be allowed individually)
+    let autoplay_block_default = autoplay_policy == "block-all";
+    let autoplay_allow_default = autoplay_policy == "allow";
 
+    // handed to every webview through its builder (see proxy::BrowserArgs); an inherited
+    // environment override would win over those and split tabs from the main window
     #[cfg(windows)]
-    {
-        std::env::remove_var("WEBVIEW2_ADDITIONAL_BROWSER_ARGUMENTS");
-        let base_args = format!(
-            "--enable-quic --site-per-process --origin-agent-cluster=true \
-             --disable-dns-prefetch --disable-background-networking \
-             --enable-features=ThirdPartyStoragePartitioning,PartitionedCookies \
-             --disable-features=UserAgentClientHint \
-             --renderer-process-limit=4 \
-             --js-flags=\"--max-old-space-size=512 --max-semi-space-size=2 --optimize-for-size --lite-mode\" \
-             --purge-v8-memory \
-             --disable-low-res-tiling \
-             --gpu-rasterization-msaa-sample-count=0 \
-             --enable-zero-copy \
-             --decoded-image-working-set-budget-mb=128 \
-             {} {}", dns_flags, autoplay_arg
-        );
-        std::env::set_var("WEBVIEW2_ADDITIONAL_BROWSER_ARGUMENTS", &base_args);
-    }
+    std::env::remove_var("WEBVIEW2_ADDITIONAL_BROWSER_ARGUMENTS");
+    let browser_args = proxy::BrowserArgs::new(&format!(
+        "--enable-quic --site-per-process --origin-agent-cluster=true \
+         --disable-dns-prefetch --disable-background-networking \
+         --enable-features=ThirdPartyStoragePartitioning,PartitionedCookies \
+         --disable-features=UserAgentClientHint \
+         --renderer-process-limit=4 \
+         --js-flags=\"--max-old-space-size=512 --max-semi-space-size=2 --optimize-for-size --lite-mode\" \
+         --purge-v8-memory \
+         --disable-low-res-tiling \
+         --gpu-rasterization-msaa-sample-count=0 \
+         --enable-zero-copy \
+         --decoded-image-working-set-budget-mb=128 \
+         {} {}", dns_flags, autoplay_arg
+    ));
 
     // init data dir and crash logging FIRST
-    let data_dir = dirs::data_dir()
-        .unwrap_or_else(|| PathBuf::from("."))
-        .join("com.bushido.browser");
-    let _ = std::fs::create_dir_all(&data_dir);
+    let data_dir = base_data_dir();
     crash_log::init(&data_dir);
     crash_log::log_info("startup", &format!("Bushido Browser v0.9.2 starting, pid={}", std::process::id()));
+    if let Some(e) = doh_rejected {
+        crash_log::log_warn("startup", &format!("custom DoH resolver ignored: {}", e));
+    }
+    if is_portable() {
+        crash_log::log_info("startup", &format!("portable mode, data in {}", data_dir.display()));
+        // keep the WebView2 profile (cookies, cache, site storage) on the portable drive too
+        #[cfg(windows)]
+        std::env::set_var("WEBVIEW2_USER_DATA_FOLDER", data_dir.join("EBWebView"));
+    }
 
     // init adblock-rust engine (cached binary or cold compile)
     let engine = blocker::init_engine(&data_dir);
@@ -2604,6 +4697,8 @@ pub fn run() {
 
     let sync_data_dir = data_dir.clone();
 
+    let search_template = load_search_template(&data_dir.join("settings.json"));
+
     // Load keybindings from settings.json (or use defaults)
     let mut keybinding_map: HashMap<String, String> = HashMap::new();
     let mut shortcut_combos: Vec<String> = Vec::new();
@@ -2643,9 +4738,41 @@ pub fn run() {
 
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
         .manage(WebviewState {
             tabs: Mutex::new(HashMap::new()),
+            injection_bypass: Mutex::new(HashSet::new()),
+            private: Mutex::new(HashSet::new()),
+            last_focus: Mutex::new(HashMap::new()),
+        })
+        .manage(TabLimitState {
+            max: AtomicUsize::new(DEFAULT_MAX_TABS),
+        })
+        .manage(RecentlyClosedState {
+            tabs: Mutex::new(VecDeque::new()),
+        })
+        .manage(PinnedState {
+            tabs: Mutex::new(HashSet::new()),
+        })
+        .manage(MuteState {
+            tabs: Mutex::new(HashSet::new()),
+        })
+        .manage(ImageBlockState {
+            tabs: Mutex::new(HashMap::new()),
+        })
+        .manage(UrlState {
+            tabs: Mutex::new(HashMap::new()),
+        })
+        .manage(SearchEngineState {
+            template: Mutex::new(search_template),
+        })
+        .manage(OfflineState {
+            enabled: AtomicBool::new(false),
+            paused_downloads: Mutex::new(Vec::new()),
+        })
+        .manage(ZoomState {
+            tabs: Mutex::new(HashMap::new()),
         })
         .manage(PanelState {
             ids: Mutex::new(HashSet::new()),
@@ -2655,17 +4782,32 @@ pub fn run() {
             webview_id: Mutex::new(None),
             source_tab: Mutex::new(None),
         })
-        .manage(NetworkLog {
-            entries: Mutex::new(HashMap::new()),
-            enabled: AtomicBool::new(false),
-            pending: Mutex::new(HashMap::new()),
+        .manage(BlockerStatsState {
+            tabs: Mutex::new(HashMap::new()),
         })
         .manage(downloads::DownloadManager::new())
         .manage(std::sync::Arc::new(downloads::RateLimiter::new(0)))
+        .manage(page_monitor::PageMonitorState::new())
+        .manage(console_log::ConsoleLogState::new())
+        .manage(cosmetic_log::CosmeticLogState::new())
+        .manage(history_search::HistoryIndex::new())
+        .manage(history_retention::HistoryRetentionState::new())
+        .manage(security_info::SecurityInfoState::new())
+        .manage(crash_recovery::CrashRecoveryState::new())
+        .manage(throttling::ThrottleState::new())
+        .manage(drag_drop::DropState::new())
+        .manage(tab_window::TabWindowState::new())
+        .manage(net_capture::NetCaptureState::new())
+        .manage(media_session::MediaSessionState::new())
+        .manage(layout::LayoutState::new())
         .manage(keybinding_state)
+        .manage(ScriptResultState {
+            pending: Mutex::new(HashMap::new()),
+        })
         .manage(BlockerState {
             engine: engine.clone(),
             data_dir: data_dir.clone(),
+            enabled: AtomicBool::new(true),
             scriptlet_preamble,
             cosmetic_script,
             cosmetic_observer_script,
@@ -2684,6 +4826,7 @@ pub fn run() {
                 use tauri_plugin_global_shortcut::ShortcutState;
                 if event.state != ShortcutState::Pressed { return; }
                 let normalized = shortcut.to_string().to_lowercase();
+                if media_session::handle_key(app, &normalized) { return; }
                 let kb_state = app.state::<KeybindingState>();
                 let action = {
                     let map = kb_state.map.lock();
@@ -2700,6 +4843,9 @@ pub fn run() {
         )
         .setup(move |app| {
             app.manage(DnsPort(dns_port));
+            app.manage(DohState { active: custom_doh });
+            app.manage(homepage::HomepageState::new(&new_tab_url));
+            app.manage(startup::StartupState::new(startup_mode, &startup_homepage));
 
             #[cfg(windows)]
             {
@@ -2726,26 +4872,60 @@ pub fn run() {
                 sites: Mutex::new(sites),
             });
 
-            let saved_perms = load_permissions(&app.handle());
-            app.manage(PermissionState {
-                saved: Mutex::new(saved_perms),
+            let signal_exempt = load_privacy_signal_exempt(&app.handle());
+            app.manage(PrivacySignalState {
+                exempt: Mutex::new(signal_exempt),
+            });
+
+            app.manage(redirect::RedirectState::load(&app.handle()));
+            app.manage(theme::ThemeState::load(&app.handle()));
+            app.manage(user_agent::UserAgentState::load(&app.handle()));
+            app.manage(site_css::SiteCssState::load(&app.handle()));
+            app.manage(element_picker::CosmeticExceptionState::load(&app.handle()));
+            app.manage(reading_list::ReadingListState::load(&app.handle()));
+            app.manage(fingerprint::FingerprintState::load());
+            app.manage(hibernate::HibernateState::new());
+            hibernate::start(app.handle().clone());
+            page_monitor::clear_leftovers(&app.handle());
+            history_retention::start(app.handle().clone());
+            app.manage(scroll_restore::ScrollState::new());
+            scroll_restore::start(app.handle().clone());
+            app.manage(proxy::ProxyState::new());
+            app.manage(browser_args);
+            app.manage(tab_groups::TabGroupState::new());
+
+            let autoplay_sites = load_autoplay_sites(&app.handle());
+            app.manage(AutoplayState {
+                sites: Mutex::new(autoplay_sites),
+                block_by_default: autoplay_block_default,
+                allow_by_default: autoplay_allow_default,
+            });
+
+            app.manage(permissions::PermissionState::load(&app.handle()));
+            let cert_overrides = load_cert_overrides(&app.handle());
+            app.manage(CertErrorState {
+                saved: Mutex::new(cert_overrides),
+                #[cfg(windows)]
+                pending: Arc::new(Mutex::new(HashMap::new())),
+                #[cfg(windows)]
+                allow_once: Mutex::new(HashSet::new()),
+            });
+            app.manage(BasicAuthState {
                 #[cfg(windows)]
                 pending: Arc::new(Mutex::new(HashMap::new())),
             });
 
             // init vault
-            let vault_path = app.path().app_data_dir().unwrap_or_else(|_| PathBuf::from(".")).join("vault.db");
+            let vault_path = base_data_dir().join("vault.db");
             let vault_state = vault::VaultState::new(vault_path);
             let _ = vault_state.init_db();
             app.manage(vault_state);
+            vault::start_autolock(app.handle().clone());
 
             // Initialize sync state
             let sync_state = match sync::keys::load_identity(&sync_data_dir) {
                 Ok(Some(identity)) => {
-                    // Read syncEnabled from the app settings (Tauri app_data_dir), NOT sync_data_dir
-                    let settings_p = app.path().app_data_dir()
-                        .unwrap_or_else(|_| sync_data_dir.clone())
-                        .join("settings.json");
+                    let settings_p = base_data_dir().join("settings.json");
                     let sync_enabled = if settings_p.exists() {
                         fs::read_to_string(&settings_p).ok()
                             .and_then(|s| serde_json::from_str::<serde_json::Value>(&s).ok())
@@ -2761,6 +4941,12 @@ pub fn run() {
                                 .map(|h| h.to_string_lossy().to_string())
                                 .unwrap_or_else(|_| "My PC".into()));
                         let state = sync::SyncState::from_identity(identity, device_name, sync_data_dir);
+                        // data-type toggles apply from the first sync, before the UI is up
+                        let data_types = fs::read_to_string(&settings_p).ok()
+                            .and_then(|s| serde_json::from_str::<serde_json::Value>(&s).ok())
+                            .and_then(|v| serde_json::from_value::<sync::DataTypes>(v.get("syncDataTypes")?.clone()).ok())
+                            .unwrap_or_default();
+                        state.set_data_types(data_types);
                         // Start discovery automatically
                         if let Ok(mut disc) = sync::discovery::DiscoveryService::new() {
                             let _ = disc.register(&state.device_id, &state.device_name, &state.fingerprint);
@@ -2775,6 +4961,12 @@ pub fn run() {
                 }
                 _ => sync::SyncState::new_disabled(sync_data_dir),
             };
+            // sync bandwidth cap applies from the first sync, before the UI is up
+            let sync_bandwidth = fs::read_to_string(base_data_dir().join("settings.json")).ok()
+                .and_then(|s| serde_json::from_str::<serde_json::Value>(&s).ok())
+                .and_then(|v| v.get("syncBandwidthLimit")?.as_u64())
+                .unwrap_or(0);
+            sync_state.bandwidth.limit_bps.store(sync_bandwidth, Ordering::Relaxed);
             app.manage(sync_state);
 
             // Start TCP listener if sync is enabled
@@ -2791,7 +4983,10 @@ pub fn run() {
                 if ss.enabled {
                     match sync::sync_doc::SyncDoc::init(&ss.app_data_dir, ss.peer_id, &ss.device_id) {
                         Ok(mut doc) => {
-                            let _ = doc.maybe_migrate_json(&ss.app_data_dir);
+                            // with bookmark sync off, bookmarks.json stays the live store
+                            if ss.syncs(sync::DataType::Bookmarks) {
+                                let _ = doc.maybe_migrate_json(&ss.app_data_dir);
+                            }
                             *ss.sync_doc.blocking_lock() = Some(doc);
                             sync::start_sync_debounce(app.handle().clone());
                             sync::start_health_check(app.handle().clone());
@@ -2860,37 +5055,13 @@ pub fn run() {
                 });
             }
 
-            // network log batch emitter — flush pending entries every 500ms
-            {
-                let app_netlog = app.handle().clone();
-                tauri::async_runtime::spawn(async move {
-                    let mut interval = tokio::time::interval(std::time::Duration::from_millis(500));
-                    loop {
-                        interval.tick().await;
-                        let nl = app_netlog.state::<NetworkLog>();
-                        if !nl.enabled.load(Ordering::Relaxed) { continue; }
-                        let batches: HashMap<String, Vec<NetworkLogEntry>> = {
-                            let mut pending = nl.pending.lock();
-                            if pending.is_empty() { continue; }
-                            std::mem::take(&mut *pending)
-                        };
-                        for (tab_id, entries) in &batches {
-                            let mut main = nl.entries.lock();
-                            let deque = main.entry(tab_id.clone()).or_insert_with(VecDeque::new);
-                            for entry in entries {
-                                deque.push_back(entry.clone());
-                                if deque.len() > 500 { deque.pop_front(); }
-                            }
-                        }
-                        for (tab_id, entries) in batches {
-                            let _ = app_netlog.emit_to("main", "network-request", serde_json::json!({
-                                "tabId": tab_id,
-                                "entries": entries
-                            }));
-                        }
-                    }
-                });
-            }
+            net_capture::start(app.handle().clone());
+
+            // created here rather than by tauri.conf.json so it gets the same browser
+            // arguments as the tabs sharing its WebView2 process
+            let mut main_config = app.config().app.windows.first().cloned().ok_or("no main window in tauri.conf.json")?;
+            main_config.additional_browser_args = Some(app.state::<proxy::BrowserArgs>().base().to_string());
+            tauri::WebviewWindowBuilder::from_config(app.handle(), &main_config)?.build()?;
 
             Ok(())
         })
@@ -2899,17 +5070,59 @@ pub fn run() {
             suspend_tab,
             resume_tab,
             close_tab,
+            discard_tab,
+            close_tabs,
             layout_webviews,
+            layout::save_layout,
+            layout::get_saved_layout,
+            layout::restore_layout,
+            theme::set_tab_theme,
+            theme::set_default_theme,
+            theme::set_site_theme,
+            theme::get_theme_config,
+            user_agent::set_site_user_agent,
+            user_agent::get_site_user_agents,
+            user_agent::reload_with_user_agent,
+            site_css::set_site_css,
+            site_css::get_site_css,
+            site_css::clear_site_css,
+            profiles::list_profiles,
+            fingerprint::set_fingerprint_config,
+            hibernate::set_auto_hibernate,
+            crash_recovery::set_crash_recovery,
+            throttling::set_tab_throttling,
+            drag_drop::set_drop_interception,
+            tab_window::move_tab_to_new_window,
             switch_tab,
             navigate_tab,
+            get_selection,
+            search_selection,
             go_back,
             go_forward,
+            get_nav_state,
+            get_tab_url,
             reload_tab,
+            toggle_link_hints,
             detect_video,
             toggle_reader,
+            content::extract_content,
+            content::page_text,
+            content::export_reader_markdown,
+            reader_tts::reader_speak,
+            reader_tts::reader_stop,
+            reader_tts::reader_set_voice,
             toggle_pip,
             media_play_pause,
             media_mute,
+            set_tab_muted,
+            set_tab_images_enabled,
+            tab_groups::list_tab_groups,
+            tab_groups::create_group,
+            tab_groups::add_tab_to_group,
+            tab_groups::remove_tab_from_group,
+            tab_groups::rename_group,
+            tab_groups::set_group_color,
+            media_session::get_media_session,
             resize_webviews,
             find_in_page,
             minimize_window,
@@ -2917,7 +5130,9 @@ pub fn run() {
             close_window,
             toggle_fullscreen,
             zoom_tab,
+            set_zoom_for_all_tabs,
             print_tab,
+            print_pdf::print_to_pdf,
             toggle_devtools,
             copy_text_to_clipboard,
             save_session,
@@ -2926,30 +5141,56 @@ pub fn run() {
             restore_backup,
             save_settings,
             set_dns_level,
+            set_doh_resolver,
+            homepage::set_new_tab_url,
+            startup::get_startup_tabs,
+            homepage::open_home_tab,
             load_settings,
             save_history,
             load_history,
+            history_search::search_history,
+            history_retention::set_history_retention,
+            history_retention::clear_history_range,
             save_bookmarks,
             load_bookmarks,
+            open_bookmark_folder,
             toggle_whitelist,
             get_whitelist,
             is_whitelisted,
-            respond_permission,
-            get_permissions,
-            revoke_permission,
+            set_autoplay_policy,
+            get_site_autoplay,
+            set_site_privacy_signals,
+            get_data_location,
+            get_privacy_signal_exemptions,
+            permissions::respond_permission,
+            respond_basic_auth,
+            respond_cert_error,
+            security_info::get_security_info,
+            permissions::get_permissions,
+            permissions::revoke_permission,
             rebind_shortcut,
             start_download,
+            download_from_context,
             pause_download,
             resume_download,
             cancel_download,
+            remove_download,
+            clear_completed_downloads,
+            export_downloads,
             get_downloads,
             open_download,
             open_download_folder,
             reorder_download,
+            set_max_concurrent_downloads,
             set_bandwidth_limit,
+            set_tab_limit,
+            get_tab_limit,
+            set_offline,
             register_panel,
             unregister_panel,
             position_panel,
+            list_webviews,
+            close_all_panels,
             import::detect_browsers,
             import::import_bookmarks,
             import::import_history,
@@ -2957,12 +5198,46 @@ pub fn run() {
             screenshot::capture_preview_for_select,
             screenshot::capture_area,
             screenshot::capture_fullpage,
+            screenshot::capture_element,
             screenshot::save_screenshot,
+            page_archive::save_page_archive,
             screenshot::copy_image_to_clipboard,
             screenshot::generate_qr_code,
+            console_log::set_console_capture,
+            console_log::get_console_log,
+            console_log::clear_console_log,
+            cosmetic_log::set_cosmetic_logging,
+            cosmetic_log::get_cosmetic_log,
+            cosmetic_log::clear_cosmetic_log,
+            element_picker::start_element_picker,
+            element_picker::add_cosmetic_exception,
+            element_picker::get_cosmetic_exceptions,
+            element_picker::remove_cosmetic_exception,
+            reading_list::reading_list_add,
+            reading_list::reading_list_remove,
+            reading_list::reading_list_mark_read,
+            reading_list::reading_list_get,
+            net_capture::set_network_capture,
+            net_capture::set_network_log_enabled,
+            net_capture::get_tab_network,
+            net_capture::clear_tab_network,
+            net_capture::copy_request_as_curl,
+            net_capture::export_tab_network,
+            reset::reset_defaults,
+            redirect::get_redirect_config,
+            redirect::add_redirect_rule,
+            redirect::remove_redirect_rule,
+            redirect::set_redirect_rule_enabled,
+            redirect::set_strip_tracking_params,
+            page_monitor::start_page_monitor,
+            page_monitor::stop_page_monitor,
+            process_manager::get_renderer_processes,
+            process_manager::kill_renderer,
+            process_manager::get_tab_metrics,
             crash_log::read_crash_log,
             crash_log::clear_crash_log,
             sync::get_sync_status,
+            sync::get_sync_health,
             sync::enable_sync,
             sync::disable_sync,
             sync::get_discovered_peers,
@@ -2979,38 +5254,75 @@ pub fn run() {
             sync::sync_remove_folder,
             sync::sync_rename_folder,
             sync::sync_move_bookmark,
+            sync::sync_add_bookmark_tag,
+            sync::sync_remove_bookmark_tag,
+            sync::get_bookmarks_by_tag,
+            sync::list_bookmark_tags,
             sync::sync_add_history,
             sync::sync_write_setting,
             sync::sync_write_tabs,
             sync::sync_get_all_tabs,
+            sync::sync_write_tab_group,
+            sync::sync_remove_tab_group,
+            sync::sync_get_tab_groups,
+            sync::sync_reading_list_add,
+            sync::sync_reading_list_remove,
+            sync::sync_reading_list_mark_read,
+            sync::sync_get_reading_list,
+            sync::get_bookmark_changes,
             sync::sync_set_data_types,
+            sync::get_sync_conflicts,
+            sync::set_sync_bandwidth_limit,
+            sync::set_sync_compression_threshold,
             sync::send_tab_to_device,
+            sync::send_tabs_to_device,
             sync::reset_sync_data,
             vault::vault_has_master_password,
             vault::vault_setup,
             vault::vault_unlock,
             vault::vault_lock,
+            vault::set_vault_autolock_timeout,
+            vault::vault_note_activity,
             vault::vault_is_unlocked,
             vault::vault_save_entry,
             vault::vault_get_entries,
             vault::vault_delete_entry,
             vault::vault_update_entry,
+            vault::vault_get_totp,
+            vault::vault_export,
+            vault::vault_import,
+            vault::vault_audit,
             vault::vault_generate_password,
             vault_retry_autofill,
-            get_network_log,
-            set_network_log_enabled,
-            clear_network_log,
+            get_blocked_stats,
             open_glance,
             close_glance,
             promote_glance,
             clear_workspace_data,
             set_tab_pinned,
+            get_pinned_tabs,
+            find_tab_by_url,
+            set_tab_injection_bypass,
+            get_recently_closed,
+            reopen_closed_tab,
             set_power_mode,
-            update_filter_lists
+            set_global_blocking,
+            set_search_engine,
+            get_cookies_for_tab,
+            clear_site_data,
+            update_filter_lists,
+            add_custom_filter,
+            remove_custom_filter,
+            list_custom_filters
         ])
-        .run(tauri::generate_context!())
+        .build(tauri::generate_context!())
+        .map(|app| app.run(|handle, event| {
+            if let tauri::RunEvent::Exit = event {
+                sync::store_session_token(handle);
+            }
+        }))
         .unwrap_or_else(|e| {
-            crash_log::log_error("startup", &format!("Tauri run() failed: {}", e));
+            crash_log::log_error("startup", &format!("Tauri build() failed: {}", e));
             eprintln!("FATAL: Bushido failed to start: {}", e);
         });
 }
@@ -0,0 +1,168 @@
+(function() {
+  function post(msg) {
+    if (window.chrome && window.chrome.webview) {
+      msg.__bushido = 'hint';
+      window.chrome.webview.postMessage(JSON.stringify(msg));
+    }
+  }
+
+  // second toggle while active cancels, same as Escape
+  if (window.__bushidoHints) {
+    window.__bushidoHints.cancel();
+    return;
+  }
+
+  var ALPHABET = 'sadfjklewcmpgh';
+  var SELECTOR = 'a[href],button,input:not([type=hidden]),select,textarea,summary,' +
+    '[role=button],[role=link],[role=checkbox],[role=tab],[role=menuitem],[onclick],[tabindex]:not([tabindex="-1"])';
+
+  var vw = window.innerWidth, vh = window.innerHeight;
+
+  // only elements in the viewport whose center isn't covered by something else
+  function visibleTargets() {
+    var out = [];
+    var nodes = document.querySelectorAll(SELECTOR);
+    for (var i = 0; i < nodes.length; i++) {
+      var el = nodes[i];
+      if (el.disabled) continue;
+      var rects = el.getClientRects();
+      for (var j = 0; j < rects.length; j++) {
+        var r = rects[j];
+        if (r.width < 2 || r.height < 2) continue;
+        if (r.bottom <= 0 || r.right <= 0 || r.top >= vh || r.left >= vw) continue;
+        var cx = Math.min(Math.max(r.left + r.width / 2, 0), vw - 1);
+        var cy = Math.min(Math.max(r.top + r.height / 2, 0), vh - 1);
+        var hit = document.elementFromPoint(cx, cy);
+        if (!hit || (hit !== el && !el.contains(hit) && !hit.contains(el))) continue;
+        var style = getComputedStyle(el);
+        if (style.visibility === 'hidden' || style.opacity === '0') continue;
+        out.push({ el: el, x: Math.max(r.left, 0), y: Math.max(r.top, 0) });
+        break;
+      }
+    }
+    return out;
+  }
+
+  // equal-length codes so no label is a prefix of another
+  function labels(n) {
+    var len = 1, cap = ALPHABET.length;
+    while (cap < n) { len++; cap *= ALPHABET.length; }
+    var out = [];
+    for (var i = 0; i < n; i++) {
+      var s = '', v = i;
+      for (var k = 0; k < len; k++) {
+        s = ALPHABET[v % ALPHABET.length] + s;
+        v = Math.floor(v / ALPHABET.length);
+      }
+      out.push(s);
+    }
+    return out;
+  }
+
+  var targets = visibleTargets();
+  if (targets.length === 0) {
+    post({ state: 'empty', count: 0 });
+    return;
+  }
+
+  var host = document.createElement('div');
+  host.style.cssText = 'position:fixed;inset:0;z-index:2147483647;pointer-events:none';
+  var root = host.attachShadow({ mode: 'closed' });
+  var css = document.createElement('style');
+  css.textContent =
+    '.h{position:fixed;padding:1px 4px;border-radius:3px;background:#f5d76e;color:#1a1a1a;' +
+    'font:bold 11px/1.3 ui-monospace,Consolas,monospace;text-transform:uppercase;' +
+    'box-shadow:0 1px 3px rgba(0,0,0,0.4);border:1px solid #c9a834}' +
+    '.h b{color:#b0861a}';
+  root.appendChild(css);
+
+  var codes = labels(targets.length);
+  for (var i = 0; i < targets.length; i++) {
+    var t = targets[i];
+    t.code = codes[i];
+    t.node = document.createElement('div');
+    t.node.className = 'h';
+    t.node.style.left = t.x + 'px';
+    t.node.style.top = t.y + 'px';
+    t.node.textContent = t.code;
+    root.appendChild(t.node);
+  }
+  (document.body || document.documentElement).appendChild(host);
+
+  var typed = '';
+
+  function render() {
+    for (var i = 0; i < targets.length; i++) {
+      var t = targets[i];
+      if (t.code.indexOf(typed) !== 0) {
+        t.node.style.display = 'none';
+        continue;
+      }
+      t.node.style.display = '';
+      t.node.textContent = '';
+      if (typed) {
+        var b = document.createElement('b');
+        b.textContent = typed;
+        t.node.appendChild(b);
+      }
+      t.node.appendChild(document.createTextNode(t.code.slice(typed.length)));
+    }
+  }
+
+  function teardown() {
+    window.removeEventListener('keydown', onKey, true);
+    window.removeEventListener('scroll', cancel, true);
+    window.removeEventListener('resize', cancel, true);
+    host.remove();
+    window.__bushidoHints = null;
+  }
+
+  function cancel() {
+    teardown();
+    post({ state: 'cancelled', count: 0 });
+  }
+
+  function activate(el) {
+    teardown();
+    var tag = el.tagName;
+    var editable = tag === 'TEXTAREA' || tag === 'SELECT' ||
+      (tag === 'INPUT' && !/^(button|submit|reset|checkbox|radio|image|file)$/i.test(el.type));
+    if (editable) {
+      el.focus();
+    } else {
+      el.focus({ preventScroll: true });
+      el.click();
+    }
+    post({ state: 'activated', count: 0 });
+  }
+
+  function onKey(e) {
+    if (e.ctrlKey || e.altKey || e.metaKey) return;
+    e.preventDefault();
+    e.stopImmediatePropagation();
+    if (e.key === 'Escape') { cancel(); return; }
+    if (e.key === 'Backspace') {
+      typed = typed.slice(0, -1);
+      render();
+      return;
+    }
+    var ch = e.key.length === 1 ? e.key.toLowerCase() : '';
+    if (!ch || ALPHABET.indexOf(ch) < 0) return;
+    var next = typed + ch;
+    var matches = targets.filter(function(t) { return t.code.indexOf(next) === 0; });
+    if (matches.length === 0) return; // ignore keys that would leave nothing selectable
+    typed = next;
+    if (matches.length === 1 && matches[0].code === typed) {
+      activate(matches[0].el);
+      return;
+    }
+    render();
+  }
+
+  window.addEventListener('keydown', onKey, true);
+  // positions go stale once the page moves
+  window.addEventListener('scroll', cancel, true);
+  window.addEventListener('resize', cancel, true);
+  window.__bushidoHints = { cancel: cancel };
+  post({ state: 'active', count: targets.length });
+})();
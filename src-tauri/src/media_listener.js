@@ -4,6 +4,46 @@
   var lastState = '';
   var lastTime = 0;
 
+  // Per-site autoplay blocking — rules injected by create_tab as window.__bushidoAutoplay
+  (function() {
+    var ap = window.__bushidoAutoplay;
+    if (!ap) return;
+    var host = location.hostname.toLowerCase();
+    var blocked = !!ap.blockByDefault;
+    var sites = ap.sites || {};
+    // the most specific rule wins, so a sub.example.com rule overrides example.com
+    var best = '';
+    for (var d in sites) {
+      if ((host === d || host.endsWith('.' + d)) && d.length > best.length) best = d;
+    }
+    if (best) blocked = !sites[best];
+    if (!blocked) return;
+
+    var notified = false;
+    function userAllowed() {
+      return window.__bushidoUserPlay || (navigator.userActivation && navigator.userActivation.hasBeenActive);
+    }
+    function notify() {
+      if (notified) return;
+      notified = true;
+      if (window.chrome && window.chrome.webview) {
+        window.chrome.webview.postMessage(JSON.stringify({ __bushido: 'autoplay-blocked', domain: host }));
+      }
+    }
+
+    var origPlay = HTMLMediaElement.prototype.play;
+    HTMLMediaElement.prototype.play = function() {
+      if (userAllowed()) return origPlay.apply(this, arguments);
+      notify();
+      return Promise.reject(new DOMException('Autoplay blocked', 'NotAllowedError'));
+    };
+    // catches the autoplay attribute, which starts playback without calling play()
+    document.addEventListener('play', function(e) {
+      if (userAllowed()) return;
+      if (e.target && e.target.pause) { e.target.pause(); notify(); }
+    }, true);
+  })();
+
   function getMetadata() {
     var meta = {};
     if (navigator.mediaSession && navigator.mediaSession.metadata) {
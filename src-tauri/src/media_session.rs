@@ -0,0 +1,216 @@
+use parking_lot::Mutex;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+// same bridge the mini player uses — __bushidoUserPlay lets it past the autoplay block
+pub const PLAY_PAUSE_JS: &str = r#"(function(){window.__bushidoUserPlay=true;var v=document.querySelector('video,audio');if(v){v.paused?v.play():v.pause()}})()"#;
+pub const MUTE_JS: &str = r#"(function(){var v=document.querySelector('video,audio');if(v){v.muted=!v.muted}})()"#;
+// per-tab mute: applies window.__bushidoMuted to every media element, and keeps doing so
+// for ones added later (YouTube swaps players) or unmuted by the page. Safe to re-run.
+pub const MUTE_ENFORCE_JS: &str = r#"(function(){function sync(m){if(window.__bushidoMuted){if(!m.muted){m.muted=true;m.__bushidoMutedByUs=true}}else if(m.__bushidoMutedByUs){m.muted=false;m.__bushidoMutedByUs=false}}function scan(n){if(n.nodeType!==1)return;if(n.matches('video,audio'))sync(n);n.querySelectorAll('video,audio').forEach(sync)}if(!window.__bushidoMuteObs&&document.documentElement){window.__bushidoMuteObs=new MutationObserver(function(rs){if(!window.__bushidoMuted)return;rs.forEach(function(r){r.addedNodes.forEach(scan)})});window.__bushidoMuteObs.observe(document.documentElement,{childList:true,subtree:true});['volumechange','play'].forEach(function(t){document.addEventListener(t,function(e){var m=e.target;if(window.__bushidoMuted&&m&&m.matches&&m.matches('video,audio')&&!m.muted){m.muted=true;m.__bushidoMutedByUs=true}},true)})}if(document.documentElement)scan(document.documentElement)})()"#;
+const PAUSE_JS: &str = r#"(function(){var v=document.querySelector('video,audio');if(v&&!v.paused){v.pause()}})()"#;
+
+// OS media keys (and the mute chord), as global_hotkey parses them. Only registered while a tab holds the
+// media session, so other players keep their keys the rest of the time.
+const KEY_PLAY_PAUSE: &str = "MediaPlayPause";
+const KEY_STOP: &str = "MediaStop";
+// mutes the playing tab; a chord rather than the hardware mute key, which must keep
+// muting the system. Only registered while something is actually playing.
+const KEY_MUTE: &str = "Ctrl+Alt+M";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MediaSessionInfo {
+    pub id: String,
+    pub state: String,
+    pub title: String,
+}
+
+#[derive(Default)]
+struct Session {
+    // (tab, title) currently playing, most recently started last
+    playing: Vec<(String, String)>,
+    // the tab media keys control; stays on a paused tab so play/pause can resume it
+    current: Option<String>,
+    state: String,
+    title: String,
+    registered: Vec<&'static str>,
+}
+
+impl Session {
+    /// Apply a media state report. True if the session's tab, state or title changed.
+    fn update(&mut self, tab: &str, state: &str, title: &str) -> bool {
+        let before = (self.current.clone(), self.state.clone(), self.title.clone());
+        self.playing.retain(|(t, _)| t != tab);
+        if state == "playing" {
+            self.playing.push((tab.to_string(), title.to_string()));
+            self.current = Some(tab.to_string());
+            self.state = state.to_string();
+            self.title = title.to_string();
+        } else if self.current.as_deref() == Some(tab) {
+            if let Some((next, next_title)) = self.playing.last() {
+                // another tab is still playing — hand the keys to it
+                self.current = Some(next.clone());
+                self.state = "playing".to_string();
+                self.title = next_title.clone();
+            } else if state == "ended" {
+                self.current = None;
+                self.state.clear();
+                self.title.clear();
+            } else {
+                self.state = state.to_string();
+            }
+        }
+        (self.current.clone(), self.state.clone(), self.title.clone()) != before
+    }
+
+    fn remove(&mut self, tab: &str) -> bool {
+        self.update(tab, "ended", "")
+    }
+
+    fn wanted_keys(&self) -> Vec<&'static str> {
+        match self.current {
+            None => vec![],
+            Some(_) if self.state == "playing" => vec![KEY_PLAY_PAUSE, KEY_STOP, KEY_MUTE],
+            Some(_) => vec![KEY_PLAY_PAUSE],
+        }
+    }
+
+    fn info(&self) -> Option<MediaSessionInfo> {
+        self.current.as_ref().map(|id| MediaSessionInfo {
+            id: id.clone(),
+            state: self.state.clone(),
+            title: self.title.clone(),
+        })
+    }
+}
+
+pub struct MediaSessionState {
+    inner: Mutex<Session>,
+}
+
+impl MediaSessionState {
+    pub fn new() -> Self {
+        Self { inner: Mutex::new(Session::default()) }
+    }
+}
+
+/// Register/unregister media keys so they match the session
+fn sync_keys(app: &AppHandle, session: &mut Session) {
+    use tauri_plugin_global_shortcut::GlobalShortcutExt;
+    let gs = app.global_shortcut();
+    let wanted = session.wanted_keys();
+    for key in session.registered.clone() {
+        if !wanted.contains(&key) {
+            let _ = gs.unregister(key);
+        }
+    }
+    for key in &wanted {
+        if !session.registered.contains(key) {
+            if let Err(e) = gs.register(*key) {
+                crate::crash_log::log_warn("media_session", &format!("Failed to register '{}': {}", key, e));
+            }
+        }
+    }
+    session.registered = wanted;
+}
+
+fn emit(app: &AppHandle, session: &Session) {
+    let _ = app.emit_to("main", "media-session-changed", session.info());
+}
+
+/// Called for every `media` IPC message from media_listener.js
+pub fn on_media_state(app: &AppHandle, tab_id: &str, state: &str, title: &str) {
+    let ms = app.state::<MediaSessionState>();
+    let mut session = ms.inner.lock();
+    let changed = session.update(tab_id, state, title);
+    if changed {
+        sync_keys(app, &mut session);
+        emit(app, &session);
+    }
+}
+
+/// Whether the tab has media playing right now
+pub fn is_playing(app: &AppHandle, tab_id: &str) -> bool {
+    app.state::<MediaSessionState>().inner.lock().playing.iter().any(|(t, _)| t == tab_id)
+}
+
+pub fn forget_tab(app: &AppHandle, tab_id: &str) {
+    let ms = app.state::<MediaSessionState>();
+    let mut session = ms.inner.lock();
+    if session.remove(tab_id) {
+        sync_keys(app, &mut session);
+        emit(app, &session);
+    }
+}
+
+/// Re-register after something cleared all global shortcuts (e.g. a keybinding reset)
+pub fn resync_keys(app: &AppHandle) {
+    let ms = app.state::<MediaSessionState>();
+    let mut session = ms.inner.lock();
+    session.registered.clear();
+    sync_keys(app, &mut session);
+}
+
+/// Global shortcut hook. True if `normalized` was a media key and has been handled.
+pub fn handle_key(app: &AppHandle, normalized: &str) -> bool {
+    let js = if normalized == KEY_PLAY_PAUSE.to_lowercase() {
+        PLAY_PAUSE_JS
+    } else if normalized == KEY_STOP.to_lowercase() {
+        PAUSE_JS
+    } else if normalized == crate::normalize_combo(KEY_MUTE) {
+        MUTE_JS
+    } else {
+        return false;
+    };
+    let target = app.state::<MediaSessionState>().inner.lock().current.clone();
+    if let Some(wv) = target.and_then(|id| app.get_webview(&id)) {
+        let _ = wv.eval(js);
+    }
+    true
+}
+
+#[tauri::command]
+pub async fn get_media_session(app: AppHandle) -> Result<Option<MediaSessionInfo>, String> {
+    Ok(app.state::<MediaSessionState>().inner.lock().info())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn most_recent_player_wins() {
+        let mut s = Session::default();
+        assert!(s.update("a", "playing", "A"));
+        assert!(s.update("b", "playing", "B"));
+        assert_eq!(s.current.as_deref(), Some("b"));
+        assert_eq!(s.wanted_keys(), vec![KEY_PLAY_PAUSE, KEY_STOP, KEY_MUTE]);
+
+        // pausing the session tab hands it back to the one still playing
+        assert!(s.update("b", "paused", "B"));
+        assert_eq!(s.current.as_deref(), Some("a"));
+        assert_eq!(s.state, "playing");
+        assert_eq!(s.title, "A");
+
+        // pausing the last player keeps it as the target so play/pause can resume it
+        assert!(s.update("a", "paused", "A"));
+        assert_eq!(s.current.as_deref(), Some("a"));
+        assert_eq!(s.wanted_keys(), vec![KEY_PLAY_PAUSE]);
+        // a paused report from another tab changes nothing
+        assert!(!s.update("b", "paused", "B"));
+    }
+
+    #[test]
+    fn closing_or_ending_releases_keys() {
+        let mut s = Session::default();
+        s.update("a", "playing", "A");
+        assert!(s.remove("a"));
+        assert!(s.current.is_none());
+        assert!(s.wanted_keys().is_empty());
+
+        s.update("a", "playing", "A");
+        assert!(s.update("a", "ended", "A"));
+        assert!(s.info().is_none());
+    }
+}
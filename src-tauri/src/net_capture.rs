@@ -0,0 +1,472 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use parking_lot::Mutex;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::error::BushidoError;
+
+const MAX_ENTRIES: usize = 1000;
+const MAX_URL_LEN: usize = 2048;
+const MAX_METHOD_LEN: usize = 16;
+const MAX_HEADERS: usize = 64;
+const MAX_HEADER_VALUE_LEN: usize = 4096;
+const PANEL_FLUSH_INTERVAL: Duration = Duration::from_millis(500);
+/// left out of "copy as cURL" unless asked for
+const SENSITIVE_HEADERS: &[&str] = &["cookie", "authorization", "proxy-authorization"];
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureEntry {
+    pub method: String,
+    pub url: String,
+    pub resource_type: String,
+    /// None until the response arrives (or forever, for blocked/aborted requests)
+    pub status: Option<u16>,
+    pub started_ms: u64,
+    pub duration_ms: Option<u64>,
+    /// stopped by the ad blocker, work offline or the tab's "no images" mode
+    pub blocked: bool,
+    /// the filter rule that matched, or "offline" / "images off"
+    pub blocked_by: Option<String>,
+    /// request headers as the page sent them, only for copy_request_as_curl; cookies and
+    /// auth included, so never serialized to the UI
+    #[serde(skip)]
+    pub headers: Vec<(String, String)>,
+}
+
+/// Per-tab request log behind both HAR capture (opt-in per tab) and the network panel
+/// (every tab while it's open). Off by default; a tab's buffer is reset on every
+/// top-level navigation.
+pub struct NetCaptureState {
+    enabled: Mutex<HashSet<String>>,
+    /// the network panel is open
+    live: AtomicBool,
+    entries: Mutex<HashMap<String, VecDeque<CaptureEntry>>>,
+    /// per tab, how many of the newest entries the panel hasn't been sent yet
+    unsent: Mutex<HashMap<String, usize>>,
+}
+
+impl NetCaptureState {
+    pub fn new() -> Self {
+        Self {
+            enabled: Mutex::new(HashSet::new()),
+            live: AtomicBool::new(false),
+            entries: Mutex::new(HashMap::new()),
+            unsent: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Whether requests of this tab are being recorded
+    pub fn records(&self, tab_id: &str) -> bool {
+        self.live.load(Ordering::Relaxed) || self.enabled.lock().contains(tab_id)
+    }
+
+    pub fn remove_tab(&self, tab_id: &str) {
+        self.enabled.lock().remove(tab_id);
+        self.entries.lock().remove(tab_id);
+        self.unsent.lock().remove(tab_id);
+    }
+
+    /// Called on top-level navigation so an export only covers the current page
+    pub fn clear_tab(&self, tab_id: &str) {
+        if self.records(tab_id) {
+            self.entries.lock().remove(tab_id);
+        }
+    }
+
+    /// Entries recorded since the last call, per tab
+    fn take_unsent(&self) -> Vec<(String, Vec<CaptureEntry>)> {
+        let unsent = std::mem::take(&mut *self.unsent.lock());
+        let entries = self.entries.lock();
+        unsent.into_iter().filter_map(|(tab_id, n)| {
+            let buf = entries.get(&tab_id)?;
+            let new: Vec<CaptureEntry> = buf.iter().skip(buf.len().saturating_sub(n)).cloned().collect();
+            (!new.is_empty()).then_some((tab_id, new))
+        }).collect()
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64
+}
+
+fn truncate(s: &str, max: usize) -> String {
+    if s.len() <= max { return s.to_string(); }
+    let mut end = max;
+    while !s.is_char_boundary(end) { end -= 1; }
+    s[..end].to_string()
+}
+
+fn push_capped(buf: &mut VecDeque<CaptureEntry>, entry: CaptureEntry) {
+    if buf.len() >= MAX_ENTRIES {
+        buf.pop_front();
+    }
+    buf.push_back(entry);
+}
+
+/// Fill in the oldest still-pending entry for this method + url: responses to the same
+/// request mostly arrive in the order it was sent. False if none matched.
+fn complete(buf: &mut VecDeque<CaptureEntry>, method: &str, url: &str, status: u16, at_ms: u64) -> bool {
+    let url = truncate(url, MAX_URL_LEN);
+    let Some(entry) = buf.iter_mut()
+        .find(|e| e.status.is_none() && e.url == url && e.method.eq_ignore_ascii_case(method))
+    else { return false };
+    entry.status = Some(status);
+    entry.duration_ms = Some(at_ms.saturating_sub(entry.started_ms));
+    true
+}
+
+/// Flag the newest pending entry for this url as blocked. False if none matched.
+fn mark_blocked(buf: &mut VecDeque<CaptureEntry>, url: &str, by: Option<&str>) -> bool {
+    let url = truncate(url, MAX_URL_LEN);
+    let Some(entry) = buf.iter_mut().rev().find(|e| e.status.is_none() && !e.blocked && e.url == url) else { return false };
+    entry.blocked = true;
+    entry.blocked_by = by.map(str::to_string);
+    true
+}
+
+/// Called from the WebResourceRequested handler
+pub fn record_request(app: &AppHandle, tab_id: &str, method: &str, url: &str, resource_type: &str, headers: Vec<(String, String)>) {
+    let ns = app.state::<NetCaptureState>();
+    if !ns.records(tab_id) || url.is_empty() { return; }
+    let entry = CaptureEntry {
+        method: truncate(&method.to_ascii_uppercase(), MAX_METHOD_LEN),
+        url: truncate(url, MAX_URL_LEN),
+        resource_type: resource_type.to_string(),
+        status: None,
+        started_ms: now_ms(),
+        duration_ms: None,
+        blocked: false,
+        blocked_by: None,
+        headers: headers.into_iter()
+            .take(MAX_HEADERS)
+            .map(|(n, v)| (truncate(&n, MAX_HEADER_VALUE_LEN), truncate(&v, MAX_HEADER_VALUE_LEN)))
+            .collect(),
+    };
+    push_capped(ns.entries.lock().entry(tab_id.to_string()).or_default(), entry);
+    if ns.live.load(Ordering::Relaxed) {
+        *ns.unsent.lock().entry(tab_id.to_string()).or_default() += 1;
+    }
+}
+
+/// Called from the WebResourceRequested handler when it stops a request that
+/// record_request already logged; `by` is the filter rule or why it was dropped
+pub fn record_blocked(app: &AppHandle, tab_id: &str, url: &str, by: Option<&str>) {
+    let ns = app.state::<NetCaptureState>();
+    if !ns.records(tab_id) { return; }
+    if let Some(buf) = ns.entries.lock().get_mut(tab_id) {
+        mark_blocked(buf, url, by);
+    }
+}
+
+/// Called from the WebResourceResponseReceived handler
+pub fn record_response(app: &AppHandle, tab_id: &str, method: &str, url: &str, status: u16) {
+    let ns = app.state::<NetCaptureState>();
+    if !ns.records(tab_id) { return; }
+    if let Some(buf) = ns.entries.lock().get_mut(tab_id) {
+        complete(buf, method, url, status, now_ms());
+    }
+}
+
+/// While the network panel is open, send it each tab's new requests in batches
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(PANEL_FLUSH_INTERVAL);
+        loop {
+            interval.tick().await;
+            let ns = app.state::<NetCaptureState>();
+            if !ns.live.load(Ordering::Relaxed) { continue; }
+            for (tab_id, entries) in ns.take_unsent() {
+                let _ = app.emit_to("main", "network-request", serde_json::json!({
+                    "tabId": tab_id,
+                    "entries": entries
+                }));
+            }
+        }
+    });
+}
+
+fn iso_time(ms: u64) -> String {
+    chrono::DateTime::from_timestamp_millis(ms as i64)
+        .map(|d| d.to_rfc3339_opts(chrono::SecondsFormat::Millis, true))
+        .unwrap_or_default()
+}
+
+/// HAR 1.2 with only what the capture knows: no headers, bodies or sizes
+fn to_har(page_url: &str, entries: &[CaptureEntry]) -> serde_json::Value {
+    let started = entries.first().map(|e| e.started_ms).unwrap_or_else(now_ms);
+    let items: Vec<serde_json::Value> = entries.iter().map(|e| {
+        let time = e.duration_ms.map(|d| d as i64).unwrap_or(-1);
+        serde_json::json!({
+            "pageref": "page_1",
+            "startedDateTime": iso_time(e.started_ms),
+            "time": time.max(0),
+            "request": {
+                "method": e.method,
+                "url": e.url,
+                "httpVersion": "",
+                "cookies": [],
+                "headers": [],
+                "queryString": [],
+                "headersSize": -1,
+                "bodySize": -1,
+            },
+            "response": {
+                "status": e.status.unwrap_or(0),
+                "statusText": "",
+                "httpVersion": "",
+                "cookies": [],
+                "headers": [],
+                "content": { "size": -1, "mimeType": "" },
+                "redirectURL": "",
+                "headersSize": -1,
+                "bodySize": -1,
+            },
+            "cache": {},
+            "timings": { "send": 0, "wait": time, "receive": 0 },
+            "_resourceType": e.resource_type,
+            "_blocked": e.blocked,
+        })
+    }).collect();
+
+    serde_json::json!({
+        "log": {
+            "version": "1.2",
+            "creator": { "name": "Bushido", "version": env!("CARGO_PKG_VERSION") },
+            "pages": [{
+                "id": "page_1",
+                "title": page_url,
+                "startedDateTime": iso_time(started),
+                "pageTimings": {},
+            }],
+            "entries": items,
+        }
+    })
+}
+
+/// POSIX shell single quoting
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
+
+/// The request as a curl command line; Cookie and Authorization headers only with `include_sensitive`
+fn to_curl(entry: &CaptureEntry, include_sensitive: bool) -> String {
+    let mut cmd = String::from("curl");
+    if !entry.method.eq_ignore_ascii_case("GET") {
+        cmd.push_str(&format!(" -X {}", shell_quote(&entry.method)));
+    }
+    cmd.push_str(&format!(" {}", shell_quote(&entry.url)));
+    for (name, value) in &entry.headers {
+        if !include_sensitive && SENSITIVE_HEADERS.iter().any(|h| name.eq_ignore_ascii_case(h)) {
+            continue;
+        }
+        cmd.push_str(&format!(" \\\n  -H {}", shell_quote(&format!("{}: {}", name, value))));
+    }
+    cmd
+}
+
+fn write_har(path: &Path, har: &serde_json::Value) -> Result<(), BushidoError> {
+    let json = serde_json::to_string_pretty(har).map_err(|e| BushidoError::Other(e.to_string()))?;
+    std::fs::write(path, json)?;
+    Ok(())
+}
+
+/// Opt a tab in or out of network capture. Turning it off keeps what was already captured.
+#[tauri::command]
+pub async fn set_network_capture(app: AppHandle, id: String, enabled: bool) -> Result<(), BushidoError> {
+    let ns = app.state::<NetCaptureState>();
+    if enabled {
+        ns.enabled.lock().insert(id);
+    } else {
+        ns.enabled.lock().remove(&id);
+    }
+    Ok(())
+}
+
+/// Record every tab while the network panel is open. Closing it keeps what tabs
+/// with capture turned on recorded.
+#[tauri::command]
+pub async fn set_network_log_enabled(app: AppHandle, enabled: bool) -> Result<(), BushidoError> {
+    let ns = app.state::<NetCaptureState>();
+    ns.live.store(enabled, Ordering::Relaxed);
+    if !enabled {
+        ns.unsent.lock().clear();
+        let capturing = ns.enabled.lock().clone();
+        ns.entries.lock().retain(|tab_id, _| capturing.contains(tab_id));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_tab_network(app: AppHandle, id: String) -> Result<Vec<CaptureEntry>, BushidoError> {
+    let ns = app.state::<NetCaptureState>();
+    let entries = ns.entries.lock();
+    Ok(entries.get(&id).map(|d| d.iter().cloned().collect()).unwrap_or_default())
+}
+
+/// Drop what was captured for a tab; capture stays on if it was
+#[tauri::command]
+pub async fn clear_tab_network(app: AppHandle, id: String) -> Result<(), BushidoError> {
+    let ns = app.state::<NetCaptureState>();
+    ns.entries.lock().remove(&id);
+    ns.unsent.lock().remove(&id);
+    Ok(())
+}
+
+/// Copy captured request `request_index` (get_tab_network order) to the clipboard as a
+/// curl command, returning it too. Cookie and Authorization headers are left out
+/// unless `include_sensitive` is set.
+#[tauri::command]
+pub async fn copy_request_as_curl(app: AppHandle, id: String, request_index: usize, include_sensitive: Option<bool>) -> Result<String, BushidoError> {
+    let entry = {
+        let entries = app.state::<NetCaptureState>().entries.lock();
+        let buf = entries.get(&id);
+        let len = buf.map_or(0, |b| b.len());
+        buf.and_then(|b| b.get(request_index)).cloned().ok_or_else(|| {
+            BushidoError::NotFound(format!("request {} is not in the capture ({} captured for this tab)", request_index, len))
+        })?
+    };
+    let cmd = to_curl(&entry, include_sensitive.unwrap_or(false));
+    crate::copy_text_to_clipboard(cmd.clone()).await.map_err(BushidoError::Other)?;
+    Ok(cmd)
+}
+
+/// Export a tab's captured requests as HAR. Without a path the user picks one; returns the
+/// written path, or None if the dialog was cancelled.
+#[tauri::command]
+pub async fn export_tab_network(app: AppHandle, id: String, path: Option<String>) -> Result<Option<String>, BushidoError> {
+    let entries: Vec<CaptureEntry> = app.state::<NetCaptureState>().entries.lock()
+        .get(&id).map(|d| d.iter().cloned().collect()).unwrap_or_default();
+    if entries.is_empty() {
+        return Err(BushidoError::NotFound("no captured requests for this tab".into()));
+    }
+    let page_url = app.get_webview(&id).and_then(|wv| wv.url().ok()).map(|u| u.to_string()).unwrap_or_default();
+
+    let path = match path {
+        Some(p) => std::path::PathBuf::from(p),
+        None => {
+            use tauri_plugin_dialog::DialogExt;
+            let (tx, rx) = tokio::sync::oneshot::channel();
+            app.dialog()
+                .file()
+                .add_filter("HAR", &["har"])
+                .set_file_name("network.har")
+                .save_file(move |p| { let _ = tx.send(p); });
+            match rx.await.ok().flatten().and_then(|p| p.into_path().ok()) {
+                Some(p) => p,
+                None => return Ok(None),
+            }
+        }
+    };
+    write_har(&path, &to_har(&page_url, &entries))?;
+    Ok(Some(path.to_string_lossy().to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pending(method: &str, url: &str, started_ms: u64) -> CaptureEntry {
+        CaptureEntry {
+            method: method.into(),
+            url: url.into(),
+            resource_type: "xmlhttprequest".into(),
+            status: None,
+            started_ms,
+            duration_ms: None,
+            blocked: false,
+            blocked_by: None,
+            headers: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn response_completes_oldest_pending_match() {
+        let mut buf = VecDeque::new();
+        push_capped(&mut buf, pending("GET", "https://a.test/x", 100));
+        push_capped(&mut buf, pending("GET", "https://a.test/x", 150));
+        push_capped(&mut buf, pending("POST", "https://a.test/x", 160));
+
+        assert!(complete(&mut buf, "get", "https://a.test/x", 200, 190));
+        assert_eq!(buf[0].status, Some(200));
+        assert_eq!(buf[0].duration_ms, Some(90));
+        assert_eq!(buf[1].status, None);
+        assert!(complete(&mut buf, "GET", "https://a.test/x", 304, 200));
+        assert_eq!(buf[1].duration_ms, Some(50));
+        assert_eq!(buf[2].status, None);
+        assert!(!complete(&mut buf, "GET", "https://a.test/x", 200, 300));
+        assert!(!complete(&mut buf, "GET", "https://b.test/", 200, 300));
+    }
+
+    #[test]
+    fn blocked_requests_are_flagged_once() {
+        let mut buf = VecDeque::new();
+        push_capped(&mut buf, pending("GET", "https://ads.test/a.js", 100));
+        push_capped(&mut buf, pending("GET", "https://ads.test/a.js", 110));
+        assert!(mark_blocked(&mut buf, "https://ads.test/a.js", Some("||ads.test^")));
+        assert!(buf[1].blocked && !buf[0].blocked);
+        assert_eq!(buf[1].blocked_by.as_deref(), Some("||ads.test^"));
+        assert!(mark_blocked(&mut buf, "https://ads.test/a.js", Some("offline")));
+        assert!(buf[0].blocked);
+        assert!(!mark_blocked(&mut buf, "https://ads.test/a.js", None));
+        assert!(!mark_blocked(&mut buf, "https://cdn.test/b.js", None));
+    }
+
+    #[test]
+    fn curl_quotes_and_hides_credentials() {
+        let mut e = pending("post", "https://a.test/api?q=it's", 0);
+        e.headers = vec![
+            ("Content-Type".into(), "application/json".into()),
+            ("Cookie".into(), "sid=secret".into()),
+            ("authorization".into(), "Bearer t".into()),
+        ];
+        let cmd = to_curl(&e, false);
+        assert_eq!(cmd, "curl -X 'post' 'https://a.test/api?q=it'\\''s' \\\n  -H 'Content-Type: application/json'");
+        let full = to_curl(&e, true);
+        assert!(full.contains("-H 'Cookie: sid=secret'"));
+        assert!(full.contains("-H 'authorization: Bearer t'"));
+        assert_eq!(to_curl(&pending("GET", "https://a.test/", 0), false), "curl 'https://a.test/'");
+    }
+
+    #[test]
+    fn panel_gets_only_new_entries() {
+        let ns = NetCaptureState::new();
+        ns.entries.lock().insert("tab-1".into(), (0..3).map(|i| pending("GET", "https://a.test/", i)).collect());
+        ns.unsent.lock().insert("tab-1".into(), 2);
+        ns.unsent.lock().insert("tab-2".into(), 1);
+        let sent = ns.take_unsent();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].0, "tab-1");
+        assert_eq!(sent[0].1.iter().map(|e| e.started_ms).collect::<Vec<_>>(), [1, 2]);
+        assert!(ns.take_unsent().is_empty());
+    }
+
+    #[test]
+    fn buffer_is_capped() {
+        let mut buf = VecDeque::new();
+        for i in 0..MAX_ENTRIES + 5 {
+            push_capped(&mut buf, pending("GET", "https://a.test/", i as u64));
+        }
+        assert_eq!(buf.len(), MAX_ENTRIES);
+        assert_eq!(buf.front().unwrap().started_ms, 5);
+    }
+
+    #[test]
+    fn har_shape() {
+        let mut done = pending("GET", "https://a.test/app.js", 1_700_000_000_000);
+        done.status = Some(200);
+        done.duration_ms = Some(42);
+        let har = to_har("https://a.test/", &[done, pending("GET", "https://a.test/slow", 1_700_000_000_010)]);
+        let log = &har["log"];
+        assert_eq!(log["version"], "1.2");
+        assert_eq!(log["pages"][0]["startedDateTime"], "2023-11-14T22:13:20.000Z");
+        assert_eq!(log["entries"][0]["response"]["status"], 200);
+        assert_eq!(log["entries"][0]["time"], 42);
+        assert_eq!(log["entries"][1]["response"]["status"], 0);
+        assert_eq!(log["entries"][1]["timings"]["wait"], -1);
+        assert_eq!(log["entries"][1]["_resourceType"], "xmlhttprequest");
+    }
+}
@@ -0,0 +1,140 @@
+use std::path::{Path, PathBuf};
+#[cfg(windows)]
+use std::sync::mpsc;
+#[cfg(windows)]
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::crash_log;
+use crate::error::BushidoError;
+
+// every subresource gets serialized into the MHTML, so heavy pages take a while
+#[cfg(windows)]
+const SNAPSHOT_TIMEOUT: Duration = Duration::from_secs(30);
+#[cfg(windows)]
+const HTML_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// `path` with `ext` added when it has no extension of its own
+fn archive_path(path: &Path, ext: &str) -> PathBuf {
+    if path.extension().is_some() { path.to_path_buf() } else { path.with_extension(ext) }
+}
+
+/// The `data` field of a Page.captureSnapshot response
+fn mhtml_from_response(json: &str) -> Option<String> {
+    let val: serde_json::Value = serde_json::from_str(json).ok()?;
+    val.get("data").and_then(|d| d.as_str()).filter(|d| !d.is_empty()).map(String::from)
+}
+
+/// Raw markup with a comment saying what it is; resources still point at the live site.
+fn fallback_document(html: &str, url: &str) -> String {
+    let when = chrono::Local::now().format("%Y-%m-%d %H:%M:%S");
+    format!(
+        "<!-- Saved by Bushido from {} on {}. A full archive could not be captured: \
+         images, styles and scripts are still loaded from the original site. -->\n{}",
+        url.replace("--", "-%2D"),
+        when,
+        html
+    )
+}
+
+#[cfg(windows)]
+fn capture_mhtml(wv: &tauri::Webview) -> Result<String, String> {
+    let (tx, rx) = mpsc::channel::<Result<String, String>>();
+    wv.with_webview(move |pw| {
+        crate::screenshot::cdp_call(&pw, "Page.captureSnapshot", r#"{"format":"mhtml"}"#, tx);
+    }).map_err(|e| e.to_string())?;
+    let json = rx.recv_timeout(SNAPSHOT_TIMEOUT).map_err(|_| "snapshot timed out".to_string())??;
+    mhtml_from_response(&json).ok_or_else(|| "no data in snapshot response".to_string())
+}
+
+#[cfg(not(windows))]
+fn capture_mhtml(_wv: &tauri::Webview) -> Result<String, String> {
+    Err("MHTML capture needs WebView2".into())
+}
+
+/// The live DOM as markup. ExecuteScript hands back the value directly, so unlike
+/// `eval_with_result` it isn't bounded by the postMessage size limit.
+#[cfg(windows)]
+fn page_html(wv: &tauri::Webview) -> Result<String, String> {
+    let (tx, rx) = mpsc::channel::<Result<String, String>>();
+    wv.with_webview(move |pw| {
+        use webview2_com::Microsoft::Web::WebView2::Win32::*;
+        let tx_err = tx.clone();
+        let core = match unsafe { pw.controller().CoreWebView2() } {
+            Ok(c) => c,
+            Err(e) => { let _ = tx_err.send(Err(e.to_string())); return; }
+        };
+        let script: Vec<u16> = "document.documentElement.outerHTML".encode_utf16().chain(std::iter::once(0)).collect();
+        let handler = webview2_com::ExecuteScriptCompletedHandler::create(Box::new(move |hr, json| {
+            let result = hr.map_err(|e| e.to_string())
+                .and_then(|_| serde_json::from_str::<String>(&json).map_err(|e| e.to_string()));
+            let _ = tx.send(result);
+            Ok(())
+        }));
+        if let Err(e) = unsafe { core.ExecuteScript(windows::core::PCWSTR::from_raw(script.as_ptr()), &handler) } {
+            let _ = tx_err.send(Err(e.to_string()));
+        }
+    }).map_err(|e| e.to_string())?;
+    rx.recv_timeout(HTML_TIMEOUT).map_err(|_| "page HTML timed out".to_string())?
+}
+
+#[cfg(not(windows))]
+fn page_html(_wv: &tauri::Webview) -> Result<String, String> {
+    Err("page HTML needs WebView2".into())
+}
+
+/// Save the tab's page for offline reading as a single MHTML file (DevTools
+/// Page.captureSnapshot). When that isn't available the raw HTML is written
+/// instead, with an `.html` extension and a note at the top. Returns the path written.
+#[tauri::command]
+pub async fn save_page_archive(app: AppHandle, id: String, path: String) -> Result<String, BushidoError> {
+    let wv = app.get_webview(&id).ok_or_else(|| BushidoError::NotFound(format!("tab {}", id)))?;
+    let path = PathBuf::from(path.trim());
+    if path.as_os_str().is_empty() {
+        return Err(BushidoError::Other("no path to save to".into()));
+    }
+
+    let written = match capture_mhtml(&wv) {
+        Ok(mhtml) => {
+            let target = archive_path(&path, "mhtml");
+            std::fs::write(&target, mhtml)?;
+            target
+        }
+        Err(e) => {
+            crash_log::log_warn("page_archive", &format!("MHTML snapshot failed for {}: {}, saving HTML", id, e));
+            let url = wv.url().map(|u| u.to_string()).unwrap_or_default();
+            let html = page_html(&wv).map_err(BushidoError::WebView)?;
+            let target = path.with_extension("html");
+            std::fs::write(&target, fallback_document(&html, &url))?;
+            target
+        }
+    };
+    crash_log::log_info("page_archive", &format!("saved {} to {}", id, written.display()));
+    Ok(written.to_string_lossy().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adds_extension_only_when_missing() {
+        assert_eq!(archive_path(Path::new("out/page"), "mhtml"), PathBuf::from("out/page.mhtml"));
+        assert_eq!(archive_path(Path::new("out/page.mht"), "mhtml"), PathBuf::from("out/page.mht"));
+    }
+
+    #[test]
+    fn reads_snapshot_data() {
+        assert_eq!(mhtml_from_response(r#"{"data":"From: <Saved by Blink>"}"#).as_deref(), Some("From: <Saved by Blink>"));
+        assert_eq!(mhtml_from_response(r#"{"data":""}"#), None);
+        assert_eq!(mhtml_from_response("{}"), None);
+    }
+
+    #[test]
+    fn fallback_note_cannot_close_early() {
+        let doc = fallback_document("<html></html>", "https://example.com/a-->b");
+        let note_end = doc.find("-->").unwrap();
+        assert!(doc[..note_end].contains("a-%2D>b"));
+        assert!(doc.ends_with("-->\n<html></html>"));
+    }
+}
@@ -0,0 +1,254 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use parking_lot::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+use adblock::request::Request;
+
+use crate::crash_log;
+
+const MIN_INTERVAL_SECS: u64 = 60;
+const MAX_MONITORS: usize = 5;
+const LOAD_SETTLE_SECS: u64 = 5;
+const DIFF_WIDTH: u32 = 160;
+const DIFF_HEIGHT: u32 = 120;
+const PIXEL_TOLERANCE: u8 = 24;   // per-pixel luma delta that counts as "changed"
+const CHANGE_THRESHOLD: f64 = 0.02; // fraction of changed pixels that triggers page-changed
+// before/after pairs kept per monitor; older ones are deleted as new changes come in
+const MAX_SAVED_CHANGES: usize = 10;
+
+pub struct PageMonitorState {
+    pub monitors: Mutex<HashMap<String, tokio::sync::watch::Sender<bool>>>,
+}
+
+impl PageMonitorState {
+    pub fn new() -> Self {
+        Self { monitors: Mutex::new(HashMap::new()) }
+    }
+}
+
+fn monitors_root(app: &AppHandle) -> PathBuf {
+    crate::data_dir(app).join("monitors")
+}
+
+fn monitor_dir(app: &AppHandle, id: &str) -> PathBuf {
+    let dir = monitors_root(app).join(id);
+    let _ = std::fs::create_dir_all(&dir);
+    dir
+}
+
+/// Delete all but the newest `keep` before/after pairs in `dir`. File names start
+/// with a timestamp, so name order is age order.
+fn prune_snapshots(dir: &Path, keep: usize) {
+    let Ok(entries) = std::fs::read_dir(dir) else { return };
+    let mut pngs: Vec<PathBuf> = entries.filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|e| e == "png"))
+        .collect();
+    pngs.sort();
+    let excess = pngs.len().saturating_sub(keep * 2);
+    for p in &pngs[..excess] {
+        let _ = std::fs::remove_file(p);
+    }
+}
+
+/// Monitors don't survive a restart, so whatever snapshots they left are orphaned
+pub fn clear_leftovers(app: &AppHandle) {
+    let _ = std::fs::remove_dir_all(monitors_root(app));
+}
+
+// downscale to a small grayscale thumbnail so diffing stays cheap
+fn diff_thumbnail(png: &[u8]) -> Option<image::GrayImage> {
+    let img = image::load_from_memory(png).ok()?;
+    Some(image::imageops::thumbnail(&img.to_luma8(), DIFF_WIDTH, DIFF_HEIGHT))
+}
+
+/// Fraction of pixels (0.0–1.0) whose luma differs by more than PIXEL_TOLERANCE
+fn diff_ratio(a: &image::GrayImage, b: &image::GrayImage) -> f64 {
+    if a.dimensions() != b.dimensions() { return 1.0; }
+    let total = a.as_raw().len();
+    if total == 0 { return 0.0; }
+    let changed = a.as_raw().iter().zip(b.as_raw().iter())
+        .filter(|(x, y)| x.abs_diff(**y) > PIXEL_TOLERANCE)
+        .count();
+    changed as f64 / total as f64
+}
+
+/// Start monitoring a page for visual changes. Returns the monitor id.
+#[tauri::command]
+pub async fn start_page_monitor(app: AppHandle, url: String, interval_secs: u64) -> Result<String, String> {
+    if crate::is_blocked_scheme(&url) {
+        return Err("Blocked URL scheme".into());
+    }
+    let parsed: url::Url = url.parse().map_err(|e: url::ParseError| e.to_string())?;
+    if parsed.scheme() != "https" && parsed.scheme() != "http" {
+        return Err("Only http(s) pages can be monitored".into());
+    }
+    let interval = interval_secs.max(MIN_INTERVAL_SECS);
+
+    let id = format!("monitor-{}", uuid::Uuid::new_v4());
+    let (tx, rx) = tokio::sync::watch::channel(false);
+    {
+        let pm = app.state::<PageMonitorState>();
+        let mut monitors = pm.monitors.lock();
+        if monitors.len() >= MAX_MONITORS {
+            return Err(format!("Max {} page monitors", MAX_MONITORS));
+        }
+        monitors.insert(id.clone(), tx);
+    }
+
+    if let Err(e) = create_monitor_webview(&app, &id, parsed) {
+        app.state::<PageMonitorState>().monitors.lock().remove(&id);
+        return Err(e);
+    }
+
+    crash_log::log_info("page_monitor", &format!("started {} url={} interval={}s", id, url, interval));
+    tauri::async_runtime::spawn(monitor_task(app.clone(), id.clone(), url, interval, rx));
+    Ok(id)
+}
+
+/// Stop a page monitor and destroy its hidden webview
+#[tauri::command]
+pub async fn stop_page_monitor(app: AppHandle, id: String) -> Result<(), String> {
+    let tx = app.state::<PageMonitorState>().monitors.lock().remove(&id);
+    let tx = tx.ok_or("monitor not found")?;
+    let _ = tx.send(true);
+    destroy_monitor_webview(&app, &id);
+    // the snapshots only mean something while the monitor runs
+    let _ = std::fs::remove_dir_all(monitors_root(&app).join(&id));
+    crash_log::log_info("page_monitor", &format!("stopped {}", id));
+    Ok(())
+}
+
+fn create_monitor_webview(app: &AppHandle, id: &str, url: url::Url) -> Result<(), String> {
+    let window = app.get_window("main").ok_or("no main window")?;
+
+    let bs = app.state::<crate::BlockerState>();
+    let engine = bs.engine.clone();
+    let whitelisted = url.host_str()
+        .map(|h| app.state::<crate::WhitelistState>().sites.lock().contains(&h.to_lowercase()))
+        .unwrap_or(false);
+
+    let mut builder = tauri::WebviewBuilder::new(id, tauri::WebviewUrl::External(url))
+        .additional_browser_args(app.state::<crate::proxy::BrowserArgs>().base());
+    builder = builder.initialization_script(&bs.fingerprint_script);
+    if !whitelisted {
+        builder = builder.initialization_script(&bs.cosmetic_script);
+        builder = builder.initialization_script(&bs.cookie_script);
+    }
+    builder = builder.on_navigation(move |nav_url| {
+        let url_str = nav_url.to_string();
+        if crate::is_blocked_scheme(&url_str) { return false; }
+        if !whitelisted {
+            if let Ok(req) = Request::new(&url_str, &url_str, "document") {
+                if engine.read().check_network_request(&req).matched { return false; }
+            }
+        }
+        true
+    });
+
+    window.add_child(
+        builder,
+        tauri::LogicalPosition::new(-9999.0, -9999.0),
+        tauri::LogicalSize::new(1280.0, 800.0),
+    ).map_err(|e| e.to_string())?;
+
+    // register as panel so layout_webviews leaves it off-screen
+    app.state::<crate::PanelState>().ids.lock().insert(id.to_string());
+    Ok(())
+}
+
+fn destroy_monitor_webview(app: &AppHandle, id: &str) {
+    app.state::<crate::PanelState>().ids.lock().remove(id);
+    if let Some(wv) = app.get_webview(id) {
+        let _ = wv.close();
+    }
+}
+
+async fn monitor_task(app: AppHandle, id: String, url: String, interval_secs: u64, mut cancel_rx: tokio::sync::watch::Receiver<bool>) {
+    let mut previous: Option<(Vec<u8>, image::GrayImage)> = None;
+    let mut ticker = tokio::time::interval(std::time::Duration::from_secs(interval_secs));
+
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {}
+            _ = cancel_rx.changed() => return,
+        }
+
+        // reload (first tick uses the initial navigation from create_monitor_webview)
+        if previous.is_some() {
+            match app.get_webview(&id) {
+                Some(wv) => { let _ = wv.eval("window.location.reload()"); }
+                None => return,
+            }
+        }
+        tokio::select! {
+            _ = tokio::time::sleep(std::time::Duration::from_secs(LOAD_SETTLE_SECS)) => {}
+            _ = cancel_rx.changed() => return,
+        }
+
+        let png = match crate::screenshot::capture_visible(app.clone(), id.clone()).await {
+            Ok(b64) => {
+                use base64::Engine as _;
+                match base64::engine::general_purpose::STANDARD.decode(&b64) {
+                    Ok(b) => b,
+                    Err(_) => continue,
+                }
+            }
+            Err(e) => {
+                crash_log::log_warn("page_monitor", &format!("{} capture failed: {}", id, e));
+                continue;
+            }
+        };
+        let thumb = match diff_thumbnail(&png) {
+            Some(t) => t,
+            None => continue,
+        };
+
+        if let Some((ref prev_png, ref prev_thumb)) = previous {
+            let ratio = diff_ratio(prev_thumb, &thumb);
+            if ratio > CHANGE_THRESHOLD {
+                let dir = monitor_dir(&app, &id);
+                let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+                let before = dir.join(format!("{}-before.png", stamp));
+                let after = dir.join(format!("{}-after.png", stamp));
+                let _ = std::fs::write(&before, prev_png);
+                let _ = std::fs::write(&after, &png);
+                prune_snapshots(&dir, MAX_SAVED_CHANGES);
+                let _ = app.emit_to("main", "page-changed", serde_json::json!({
+                    "id": id,
+                    "url": url,
+                    "diffRatio": ratio,
+                    "before": before.to_string_lossy(),
+                    "after": after.to_string_lossy(),
+                }));
+            }
+        }
+        previous = Some((png, thumb));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prune_keeps_the_newest_pairs() {
+        let dir = std::env::temp_dir().join(format!("bushido_monitor_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for stamp in ["20240101-000001", "20240101-000002", "20240101-000003"] {
+            std::fs::write(dir.join(format!("{}-before.png", stamp)), b"x").unwrap();
+            std::fs::write(dir.join(format!("{}-after.png", stamp)), b"x").unwrap();
+        }
+        std::fs::write(dir.join("notes.txt"), b"x").unwrap();
+        prune_snapshots(&dir, 2);
+        let mut left: Vec<String> = std::fs::read_dir(&dir).unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        left.sort();
+        let _ = std::fs::remove_dir_all(&dir);
+        assert_eq!(left, [
+            "20240101-000002-after.png", "20240101-000002-before.png",
+            "20240101-000003-after.png", "20240101-000003-before.png",
+            "notes.txt",
+        ]);
+    }
+}
@@ -0,0 +1,241 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use parking_lot::Mutex;
+use tauri::{AppHandle, Manager};
+
+use crate::error::BushidoError;
+
+/// A page's request held open until respond_permission answers it. Only how the
+/// request is held differs per platform; the saved answers and the
+/// `permission-requested` → respond_permission round trip are shared.
+pub struct PendingPermission {
+    domain: String,
+    permission: &'static str,
+    _tab_id: String,
+    request: PlatformRequest,
+}
+
+#[cfg(windows)]
+pub struct PlatformRequest {
+    pub deferral: webview2_com::Microsoft::Web::WebView2::Win32::ICoreWebView2Deferral,
+    pub args: webview2_com::Microsoft::Web::WebView2::Win32::ICoreWebView2PermissionRequestedEventArgs,
+}
+
+/// GTK objects; only touched again on the main thread (see `complete`)
+#[cfg(target_os = "linux")]
+pub struct PlatformRequest(pub webkit2gtk::PermissionRequest);
+
+/// wry answers WKWebView's media prompt itself and exposes no hook, so nothing is
+/// ever pending on macOS
+#[cfg(not(any(windows, target_os = "linux")))]
+pub enum PlatformRequest {}
+
+#[cfg(any(windows, target_os = "linux"))]
+unsafe impl Send for PlatformRequest {}
+#[cfg(any(windows, target_os = "linux"))]
+unsafe impl Sync for PlatformRequest {}
+
+/// Saved answers ("domain:permission" → allowed, persisted in permissions.json) and
+/// requests waiting on the prompt
+pub struct PermissionState {
+    saved: Mutex<HashMap<String, bool>>,
+    pending: Mutex<HashMap<String, PendingPermission>>,
+}
+
+pub fn permissions_path(app: &AppHandle) -> PathBuf {
+    crate::data_dir(app).join("permissions.json")
+}
+
+fn save_permissions(app: &AppHandle, perms: &HashMap<String, bool>) {
+    if let Ok(json) = serde_json::to_string(perms) {
+        let _ = fs::write(permissions_path(app), json);
+    }
+}
+
+fn permission_key(domain: &str, permission: &str) -> String {
+    format!("{}:{}", domain, permission)
+}
+
+/// Lowercased host of the requesting page, "" when it has none
+pub fn domain_of(uri: &str) -> String {
+    url::Url::parse(uri).ok()
+        .and_then(|u| u.host_str().map(|h| h.to_lowercase()))
+        .unwrap_or_default()
+}
+
+impl PermissionState {
+    pub fn load(app: &AppHandle) -> Self {
+        let saved: HashMap<String, bool> = fs::read_to_string(permissions_path(app)).ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default();
+        Self::with_saved(saved)
+    }
+
+    fn with_saved(saved: HashMap<String, bool>) -> Self {
+        Self { saved: Mutex::new(saved), pending: Mutex::new(HashMap::new()) }
+    }
+
+    /// The saved answer for this site, else the built-in one; None means ask the user
+    pub fn decide(&self, domain: &str, permission: &str) -> Option<bool> {
+        if let Some(&allowed) = self.saved.lock().get(&permission_key(domain, permission)) {
+            return Some(allowed);
+        }
+        match permission {
+            // font enumeration is a fingerprinting vector
+            "localfonts" => Some(false),
+            "multipledownloads" => Some(true),
+            _ => None,
+        }
+    }
+
+    /// Drop every saved answer; returns how many there were
+    pub fn forget_all(&self, app: &AppHandle) -> usize {
+        let mut saved = self.saved.lock();
+        let n = saved.len();
+        saved.clear();
+        save_permissions(app, &saved);
+        n
+    }
+}
+
+/// Hold `request` open and show the prompt; the UI answers with respond_permission
+#[cfg(any(windows, target_os = "linux"))]
+pub fn ask(app: &AppHandle, tab_id: &str, uri: &str, permission: &'static str, user_initiated: bool, request: PlatformRequest) {
+    use tauri::Emitter;
+    let domain = domain_of(uri);
+    let request_id = uuid::Uuid::new_v4().to_string();
+    app.state::<PermissionState>().pending.lock().insert(request_id.clone(), PendingPermission {
+        domain: domain.clone(),
+        permission,
+        _tab_id: tab_id.to_string(),
+        request,
+    });
+    let _ = app.emit_to("main", "permission-requested", serde_json::json!({
+        "requestId": request_id,
+        "tabId": tab_id,
+        "uri": uri,
+        "domain": domain,
+        "permission": permission,
+        "isUserInitiated": user_initiated,
+    }));
+}
+
+#[cfg(windows)]
+fn complete(_app: &AppHandle, request: PlatformRequest, allow: bool) {
+    use webview2_com::Microsoft::Web::WebView2::Win32::*;
+    unsafe {
+        let _ = request.args.SetState(COREWEBVIEW2_PERMISSION_STATE(if allow { 1 } else { 2 }));
+        let _ = request.deferral.Complete();
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn complete(app: &AppHandle, request: PlatformRequest, allow: bool) {
+    use webkit2gtk::PermissionRequestExt;
+    let _ = app.run_on_main_thread(move || {
+        if allow { request.0.allow() } else { request.0.deny() }
+    });
+}
+
+#[cfg(not(any(windows, target_os = "linux")))]
+fn complete(_app: &AppHandle, request: PlatformRequest, _allow: bool) {
+    match request {}
+}
+
+/// Route WebKitGTK's permission-request signal into the shared flow. Returning true
+/// from the handler keeps the request alive until it's answered.
+#[cfg(target_os = "linux")]
+pub fn attach(app: &AppHandle, tab_id: &str, webview: &webkit2gtk::WebView) {
+    use webkit2gtk::glib::object::{Cast, ObjectExt};
+    use webkit2gtk::{GeolocationPermissionRequest, NotificationPermissionRequest, PermissionRequestExt, UserMediaPermissionRequest, WebViewExt};
+
+    let app = app.clone();
+    let tab_id = tab_id.to_string();
+    webview.connect_permission_request(move |wv, request| {
+        let permission: &'static str = if request.is::<GeolocationPermissionRequest>() {
+            "geolocation"
+        } else if request.is::<NotificationPermissionRequest>() {
+            "notifications"
+        } else if let Some(media) = request.downcast_ref::<UserMediaPermissionRequest>() {
+            if media.property::<bool>("is-for-video-device") { "camera" } else { "microphone" }
+        } else {
+            // unknown kinds keep WebKit's default (deny)
+            return false;
+        };
+        let uri = wv.uri().map(|u| u.to_string()).unwrap_or_default();
+        match app.state::<PermissionState>().decide(&domain_of(&uri), permission) {
+            Some(true) => request.allow(),
+            Some(false) => request.deny(),
+            None => ask(&app, &tab_id, &uri, permission, false, PlatformRequest(request.clone())),
+        }
+        true
+    });
+}
+
+#[tauri::command]
+pub async fn respond_permission(app: AppHandle, request_id: String, allow: bool, remember: bool) -> Result<(), BushidoError> {
+    let ps = app.state::<PermissionState>();
+    let pending = ps.pending.lock().remove(&request_id)
+        .ok_or_else(|| BushidoError::NotFound("permission request not found".into()))?;
+    if remember && !pending.domain.is_empty() {
+        let mut saved = ps.saved.lock();
+        saved.insert(permission_key(&pending.domain, pending.permission), allow);
+        save_permissions(&app, &saved);
+    }
+    complete(&app, pending.request, allow);
+    Ok(())
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedPermissionEntry {
+    domain: String,
+    permission: String,
+    allowed: bool,
+}
+
+#[tauri::command]
+pub async fn get_permissions(app: AppHandle) -> Result<Vec<SavedPermissionEntry>, BushidoError> {
+    let saved = app.state::<PermissionState>().saved.lock().clone();
+    Ok(saved.into_iter().map(|(key, allowed)| {
+        let (domain, permission) = key.split_once(':').unwrap_or((key.as_str(), "unknown"));
+        SavedPermissionEntry { domain: domain.to_string(), permission: permission.to_string(), allowed }
+    }).collect())
+}
+
+#[tauri::command]
+pub async fn revoke_permission(app: AppHandle, domain: String, permission: String) -> Result<(), BushidoError> {
+    let ps = app.state::<PermissionState>();
+    let mut saved = ps.saved.lock();
+    saved.remove(&permission_key(&domain, &permission));
+    save_permissions(&app, &saved);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saved_answers_win_over_defaults() {
+        let ps = PermissionState::with_saved(HashMap::from([
+            ("maps.example:geolocation".to_string(), true),
+            ("fonts.example:localfonts".to_string(), true),
+            ("spam.example:multipledownloads".to_string(), false),
+        ]));
+        assert_eq!(ps.decide("maps.example", "geolocation"), Some(true));
+        assert_eq!(ps.decide("other.example", "geolocation"), None);
+        assert_eq!(ps.decide("other.example", "localfonts"), Some(false));
+        assert_eq!(ps.decide("fonts.example", "localfonts"), Some(true));
+        assert_eq!(ps.decide("other.example", "multipledownloads"), Some(true));
+        assert_eq!(ps.decide("spam.example", "multipledownloads"), Some(false));
+    }
+
+    #[test]
+    fn domain_is_the_lowercased_host() {
+        assert_eq!(domain_of("https://Maps.Example:8443/here?x=1"), "maps.example");
+        assert_eq!(domain_of("about:blank"), "");
+        assert_eq!(domain_of("not a url"), "");
+    }
+}
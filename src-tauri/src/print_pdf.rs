@@ -0,0 +1,230 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::Deserialize;
+use tauri::{AppHandle, Manager};
+
+use crate::crash_log;
+use crate::error::BushidoError;
+
+// WebView2's accepted ScaleFactor range
+const MIN_SCALE: f64 = 0.1;
+const MAX_SCALE: f64 = 2.0;
+// anything wider leaves no page on Letter/A4
+const MAX_MARGIN_INCHES: f64 = 4.0;
+const MAX_HEADER_LEN: usize = 512;
+// a hung renderer never calls PrintToPdf's handler back
+#[cfg(windows)]
+const PRINT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Page margins in inches, as WebView2 takes them
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct PdfMargin {
+    pub top: f64,
+    pub right: f64,
+    pub bottom: f64,
+    pub left: f64,
+}
+
+impl Default for PdfMargin {
+    // WebView2's own default
+    fn default() -> Self {
+        Self { top: 0.4, right: 0.4, bottom: 0.4, left: 0.4 }
+    }
+}
+
+/// Subset of ICoreWebView2PrintSettings, mirroring wry's PrintOptions/PrintMargin.
+/// Every field is optional from the frontend.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PdfOptions {
+    pub landscape: bool,
+    pub margins: PdfMargin,
+    pub scale: f64,
+    pub print_background: bool,
+    pub header_footer: bool,
+    /// header text when `header_footer` is on; the page title otherwise
+    pub header_title: Option<String>,
+    /// footer text when `header_footer` is on; the page URL otherwise
+    pub footer_uri: Option<String>,
+}
+
+impl Default for PdfOptions {
+    fn default() -> Self {
+        Self {
+            landscape: false,
+            margins: PdfMargin::default(),
+            scale: 1.0,
+            print_background: false,
+            header_footer: false,
+            header_title: None,
+            footer_uri: None,
+        }
+    }
+}
+
+impl PdfOptions {
+    fn validate(&self) -> Result<(), BushidoError> {
+        if !(MIN_SCALE..=MAX_SCALE).contains(&self.scale) {
+            return Err(BushidoError::Other(format!("scale must be between {} and {}", MIN_SCALE, MAX_SCALE)));
+        }
+        let m = self.margins;
+        if [m.top, m.right, m.bottom, m.left].iter().any(|v| !(0.0..=MAX_MARGIN_INCHES).contains(v)) {
+            return Err(BushidoError::Other(format!("margins must be between 0 and {} inches", MAX_MARGIN_INCHES)));
+        }
+        let too_long = |s: &Option<String>| s.as_ref().is_some_and(|s| s.len() > MAX_HEADER_LEN);
+        if too_long(&self.header_title) || too_long(&self.footer_uri) {
+            return Err(BushidoError::Limit(format!("header and footer are limited to {} characters", MAX_HEADER_LEN)));
+        }
+        Ok(())
+    }
+}
+
+/// Absolute `.pdf` path (PrintToPdf needs one) in an existing directory
+fn pdf_path(path: &str) -> Result<PathBuf, BushidoError> {
+    let path = path.trim();
+    if path.is_empty() {
+        return Err(BushidoError::Other("no path to save to".into()));
+    }
+    let mut path = std::path::absolute(path)?;
+    if path.extension().is_none() {
+        path.set_extension("pdf");
+    }
+    if !path.parent().is_some_and(|p| p.is_dir()) {
+        return Err(BushidoError::NotFound(format!("folder for {}", path.display())));
+    }
+    Ok(path)
+}
+
+/// Fail before printing rather than after: open the target for writing, and remove it
+/// again if it didn't exist so a failed print leaves nothing behind.
+fn check_writable(path: &Path) -> Result<(), BushidoError> {
+    let existed = path.exists();
+    fs::OpenOptions::new().write(true).create(true).truncate(false).open(path)?;
+    if !existed {
+        let _ = fs::remove_file(path);
+    }
+    Ok(())
+}
+
+#[cfg(windows)]
+async fn print(wv: &tauri::Webview, path: &Path, opts: PdfOptions) -> Result<(), BushidoError> {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+    use std::sync::Arc;
+    use parking_lot::Mutex;
+
+    let (tx, rx) = tokio::sync::oneshot::channel::<Result<(), String>>();
+    // the completion handler or the setup below, whichever fails first, answers
+    let tx = Arc::new(Mutex::new(Some(tx)));
+    let finish = |tx: &Arc<Mutex<Option<tokio::sync::oneshot::Sender<Result<(), String>>>>>, r: Result<(), String>| {
+        if let Some(tx) = tx.lock().take() {
+            let _ = tx.send(r);
+        }
+    };
+    let path = path.to_string_lossy().to_string();
+    wv.with_webview(move |pw| {
+        use webview2_com::Microsoft::Web::WebView2::Win32::*;
+        use windows::core::Interface;
+        use windows_core::HSTRING;
+        let tx_done = tx.clone();
+        let started = catch_unwind(AssertUnwindSafe(|| unsafe {
+            let core = pw.controller().CoreWebView2()?;
+            let core7 = core.cast::<ICoreWebView2_7>()?;
+            let env6 = core.cast::<ICoreWebView2_2>()?.Environment()?.cast::<ICoreWebView2Environment6>()?;
+            let settings = env6.CreatePrintSettings()?;
+            settings.SetOrientation(if opts.landscape {
+                COREWEBVIEW2_PRINT_ORIENTATION_LANDSCAPE
+            } else {
+                COREWEBVIEW2_PRINT_ORIENTATION_PORTRAIT
+            })?;
+            settings.SetScaleFactor(opts.scale)?;
+            settings.SetMarginTop(opts.margins.top)?;
+            settings.SetMarginRight(opts.margins.right)?;
+            settings.SetMarginBottom(opts.margins.bottom)?;
+            settings.SetMarginLeft(opts.margins.left)?;
+            settings.SetShouldPrintBackgrounds(opts.print_background)?;
+            settings.SetShouldPrintHeaderAndFooter(opts.header_footer)?;
+            if let Some(title) = &opts.header_title {
+                settings.SetHeaderTitle(&HSTRING::from(title.as_str()))?;
+            }
+            if let Some(footer) = &opts.footer_uri {
+                settings.SetFooterUri(&HSTRING::from(footer.as_str()))?;
+            }
+            let handler = webview2_com::PrintToPdfCompletedHandler::create(Box::new(move |hr, ok| {
+                let result = match hr {
+                    Ok(()) if ok => Ok(()),
+                    Ok(()) => Err("PrintToPdf did not complete".to_string()),
+                    Err(e) => Err(e.to_string()),
+                };
+                finish(&tx_done, result);
+                Ok(())
+            }));
+            core7.PrintToPdf(&HSTRING::from(path.as_str()), &settings, &handler)
+        }));
+        match started {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => finish(&tx, Err(e.to_string())),
+            Err(_) => finish(&tx, Err("PrintToPdf panicked".into())),
+        }
+    })?;
+    match tokio::time::timeout(PRINT_TIMEOUT, rx).await {
+        Ok(Ok(Ok(()))) => Ok(()),
+        Ok(Ok(Err(e))) => Err(BushidoError::WebView(e)),
+        Ok(Err(_)) => Err(BushidoError::WebView("PrintToPdf was dropped".into())),
+        Err(_) => Err(BushidoError::WebView("PDF export timed out".into())),
+    }
+}
+
+#[cfg(not(windows))]
+async fn print(_wv: &tauri::Webview, _path: &Path, _opts: PdfOptions) -> Result<(), BushidoError> {
+    Err(BushidoError::WebView("PDF export needs WebView2".into()))
+}
+
+/// Print the tab to a PDF without the print dialog (`print_tab` is the dialog path).
+/// Returns the written path; `.pdf` is added when `path` has no extension.
+#[tauri::command]
+pub async fn print_to_pdf(app: AppHandle, id: String, path: String, options: Option<PdfOptions>) -> Result<String, BushidoError> {
+    let wv = app.get_webview(&id).ok_or_else(|| BushidoError::NotFound(format!("tab {}", id)))?;
+    let options = options.unwrap_or_default();
+    options.validate()?;
+    let path = pdf_path(&path)?;
+    check_writable(&path)?;
+
+    print(&wv, &path, options).await?;
+    crash_log::log_info("print_pdf", &format!("printed {} to {}", id, path.display()));
+    Ok(path.to_string_lossy().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn options_default_per_field() {
+        let opts: PdfOptions = serde_json::from_str(r#"{"landscape":true,"margins":{"top":1}}"#).unwrap();
+        assert!(opts.landscape);
+        assert_eq!(opts.margins, PdfMargin { top: 1.0, ..PdfMargin::default() });
+        assert_eq!(opts.scale, 1.0);
+        assert!(opts.validate().is_ok());
+    }
+
+    #[test]
+    fn rejects_out_of_range_options() {
+        let scaled = PdfOptions { scale: 2.5, ..PdfOptions::default() };
+        assert!(scaled.validate().is_err());
+        let margins = PdfOptions { margins: PdfMargin { left: -0.1, ..PdfMargin::default() }, ..PdfOptions::default() };
+        assert!(margins.validate().is_err());
+        let nan = PdfOptions { margins: PdfMargin { top: f64::NAN, ..PdfMargin::default() }, ..PdfOptions::default() };
+        assert!(nan.validate().is_err());
+    }
+
+    #[test]
+    fn path_gets_pdf_extension_and_needs_a_folder() {
+        let dir = std::env::temp_dir();
+        let p = pdf_path(&dir.join("bushido-print-test").to_string_lossy()).unwrap();
+        assert_eq!(p.extension().and_then(|e| e.to_str()), Some("pdf"));
+        assert!(p.is_absolute());
+        assert!(pdf_path(&dir.join("no-such-folder-bushido").join("x.pdf").to_string_lossy()).is_err());
+        assert!(pdf_path("  ").is_err());
+    }
+}
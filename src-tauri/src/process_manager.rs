@@ -0,0 +1,309 @@
+use std::collections::{HashMap, HashSet};
+use tauri::{AppHandle, Manager};
+
+use crate::crash_log;
+use crate::error::BushidoError;
+
+const QUERY_TIMEOUT_SECS: u64 = 3;
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RendererProcInfo {
+    pub pid: u32,
+    pub kind: String,
+    pub browser_pid: u32,
+    pub frame_urls: Vec<String>,
+    /// best-effort: tabs whose current origin matches one of the process' frames
+    pub tab_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TabMetrics {
+    /// the one tab this process serves; None for shared and non-renderer processes
+    pub tab_id: Option<String>,
+    pub pid: u32,
+    pub kind: String,
+    /// working set of the whole process
+    pub memory_bytes: u64,
+    /// every tab the process may be serving, when that's more than one
+    pub shared_by: Vec<String>,
+}
+
+// (pid, COREWEBVIEW2_PROCESS_KIND, frame source urls)
+type RawProc = (u32, i32, Vec<String>);
+
+fn kind_name(kind: i32) -> &'static str {
+    match kind {
+        0 => "browser",
+        1 => "renderer",
+        2 => "utility",
+        3 => "sandboxHelper",
+        4 => "gpu",
+        5 => "ppapiPlugin",
+        6 => "ppapiBroker",
+        _ => "unknown",
+    }
+}
+
+fn origin_of(url: &str) -> Option<String> {
+    url::Url::parse(url).ok().map(|u| u.origin().ascii_serialization())
+}
+
+/// List WebView2 child processes (renderers, gpu, utility…) with a best-effort tab mapping
+#[tauri::command]
+pub async fn get_renderer_processes(app: AppHandle) -> Result<Vec<RendererProcInfo>, BushidoError> {
+    collect_processes(&app).await
+}
+
+/// Terminate one WebView2 child process. The affected tab shows the crash UI via ProcessFailed.
+#[tauri::command]
+pub async fn kill_renderer(app: AppHandle, pid: u32) -> Result<(), BushidoError> {
+    if pid == std::process::id() {
+        return Err(BushidoError::Permission("refusing to kill the app process".into()));
+    }
+    // only pids WebView2 itself reports as its children, and never a browser process
+    let procs = collect_processes(&app).await?;
+    let target = procs.iter().find(|p| p.pid == pid)
+        .ok_or_else(|| BushidoError::NotFound("not a WebView2 process".into()))?;
+    if target.kind == "browser" || procs.iter().any(|p| p.browser_pid == pid) {
+        return Err(BushidoError::Permission("refusing to kill the WebView2 browser process".into()));
+    }
+    crash_log::log_warn("process_manager", &format!("killing {} pid={} tabs={:?}", target.kind, pid, target.tab_ids));
+    terminate(pid)
+}
+
+/// Memory per WebView2 process for a task manager: a renderer that maps to exactly one
+/// tab is reported as that tab's, anything else (same-site tabs sharing a renderer, gpu,
+/// utility, browser) as a per-process total. Largest first.
+#[tauri::command]
+pub async fn get_tab_metrics(app: AppHandle) -> Result<Vec<TabMetrics>, BushidoError> {
+    let procs = collect_processes(&app).await?;
+    Ok(tab_metrics(&procs, working_set))
+}
+
+fn tab_metrics(procs: &[RendererProcInfo], memory: impl Fn(u32) -> Option<u64>) -> Vec<TabMetrics> {
+    let mut seen = HashSet::new();
+    let mut out: Vec<TabMetrics> = procs.iter()
+        .filter(|p| seen.insert(p.pid))
+        .map(|p| {
+            let single = p.kind == "renderer" && p.tab_ids.len() == 1;
+            TabMetrics {
+                tab_id: if single { p.tab_ids.first().cloned() } else { None },
+                pid: p.pid,
+                kind: p.kind.clone(),
+                memory_bytes: memory(p.pid).unwrap_or(0),
+                shared_by: if single { Vec::new() } else { p.tab_ids.clone() },
+            }
+        })
+        .collect();
+    out.sort_by(|a, b| b.memory_bytes.cmp(&a.memory_bytes).then(a.pid.cmp(&b.pid)));
+    out
+}
+
+async fn collect_processes(app: &AppHandle) -> Result<Vec<RendererProcInfo>, BushidoError> {
+    let panels = app.state::<crate::PanelState>().ids.lock().clone();
+    let tabs: Vec<(String, tauri::Webview)> = app.webviews().into_iter()
+        .filter(|(label, _)| label != "main" && !panels.contains(label))
+        .collect();
+
+    // tab id by origin, for the best-effort mapping below
+    let mut tabs_by_origin: HashMap<String, Vec<String>> = HashMap::new();
+    for (label, wv) in &tabs {
+        if let Some(origin) = wv.url().ok().and_then(|u| origin_of(u.as_str())) {
+            tabs_by_origin.entry(origin).or_default().push(label.clone());
+        }
+    }
+
+    // profiles get their own browser process — query each environment once
+    let mut seen_browsers: HashSet<u32> = HashSet::new();
+    let mut out = Vec::new();
+    for (_, wv) in app.webviews() {
+        let (browser_pid, procs) = match query_environment(&wv).await {
+            Ok(r) => r,
+            Err(e) => {
+                crash_log::log_warn("process_manager", &format!("query {} failed: {}", wv.label(), e));
+                continue;
+            }
+        };
+        if !seen_browsers.insert(browser_pid) { continue; }
+
+        for (pid, kind, frame_urls) in procs {
+            let mut tab_ids: Vec<String> = frame_urls.iter()
+                .filter_map(|u| origin_of(u))
+                .filter_map(|o| tabs_by_origin.get(&o))
+                .flatten()
+                .cloned()
+                .collect();
+            tab_ids.sort();
+            tab_ids.dedup();
+            out.push(RendererProcInfo {
+                pid,
+                kind: kind_name(kind).to_string(),
+                browser_pid,
+                frame_urls,
+                tab_ids,
+            });
+        }
+    }
+    Ok(out)
+}
+
+/// Working set of Bushido plus every WebView2 process it hosts, in bytes.
+/// None when the environment can't be queried (non-Windows, no webviews yet).
+pub async fn total_memory_bytes(app: &AppHandle) -> Option<u64> {
+    let procs = collect_processes(app).await.ok()?;
+    if procs.is_empty() {
+        return None;
+    }
+    let mut pids: HashSet<u32> = procs.iter().flat_map(|p| [p.pid, p.browser_pid]).collect();
+    pids.insert(std::process::id());
+    Some(pids.into_iter().filter_map(working_set).sum())
+}
+
+#[cfg(windows)]
+fn working_set(pid: u32) -> Option<u64> {
+    use windows::Win32::Foundation::CloseHandle;
+    use windows::Win32::System::ProcessStatus::{K32GetProcessMemoryInfo, PROCESS_MEMORY_COUNTERS};
+    use windows::Win32::System::Threading::{OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION};
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+        let mut counters = PROCESS_MEMORY_COUNTERS::default();
+        let ok = K32GetProcessMemoryInfo(handle, &mut counters, std::mem::size_of::<PROCESS_MEMORY_COUNTERS>() as u32).as_bool();
+        let _ = CloseHandle(handle);
+        ok.then_some(counters.WorkingSetSize as u64)
+    }
+}
+
+#[cfg(not(windows))]
+fn working_set(_pid: u32) -> Option<u64> {
+    None
+}
+
+#[cfg(windows)]
+async fn query_environment(wv: &tauri::Webview) -> Result<(u32, Vec<RawProc>), String> {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    let (tx, rx) = tokio::sync::oneshot::channel::<Result<(u32, Vec<RawProc>), String>>();
+    wv.with_webview(move |pw| {
+        use webview2_com::Microsoft::Web::WebView2::Win32::*;
+        use windows::core::Interface;
+        let _ = catch_unwind(AssertUnwindSafe(move || unsafe {
+            let core = match pw.controller().CoreWebView2() {
+                Ok(c) => c,
+                Err(e) => { let _ = tx.send(Err(e.to_string())); return; }
+            };
+            let mut browser_pid = 0u32;
+            let _ = core.BrowserProcessId(&mut browser_pid);
+            let env = match core.cast::<ICoreWebView2_2>()
+                .and_then(|c| c.Environment())
+                .and_then(|e| e.cast::<ICoreWebView2Environment13>())
+            {
+                Ok(e) => e,
+                Err(e) => { let _ = tx.send(Err(format!("process info unsupported: {}", e))); return; }
+            };
+
+            let handler = webview2_com::GetProcessExtendedInfosCompletedHandler::create(Box::new(
+                move |hr, infos| {
+                    let infos_ref = AssertUnwindSafe(&infos);
+                    let procs = catch_unwind(move || {
+                        let mut procs = Vec::new();
+                        let Some(ref list) = *infos_ref else { return procs };
+                        let mut count = 0u32;
+                        if list.Count(&mut count).is_err() { return procs; }
+                        for i in 0..count {
+                            let Ok(ext) = list.GetValueAtIndex(i) else { continue };
+                            let Ok(info) = ext.ProcessInfo() else { continue };
+                            let mut pid = 0i32;
+                            let mut kind = COREWEBVIEW2_PROCESS_KIND(0);
+                            let _ = info.ProcessId(&mut pid);
+                            let _ = info.Kind(&mut kind);
+
+                            let mut frame_urls = Vec::new();
+                            if let Ok(it) = ext.AssociatedFrameInfos().and_then(|f| f.GetIterator()) {
+                                let mut has = windows_core::BOOL::default();
+                                let _ = it.HasCurrent(&mut has);
+                                while has == true {
+                                    if let Ok(frame) = it.GetCurrent() {
+                                        let mut src = windows::core::PWSTR::null();
+                                        if frame.Source(&mut src).is_ok() && !src.is_null() {
+                                            frame_urls.push(src.to_string().unwrap_or_default());
+                                        }
+                                    }
+                                    if it.MoveNext(&mut has).is_err() { break; }
+                                }
+                            }
+                            procs.push((pid as u32, kind.0, frame_urls));
+                        }
+                        procs
+                    }).unwrap_or_default();
+                    let _ = tx.send(hr.map(|_| (browser_pid, procs)).map_err(|e| e.to_string()));
+                    Ok(())
+                },
+            ));
+            let _ = env.GetProcessExtendedInfos(&handler);
+        }));
+    }).map_err(|e| e.to_string())?;
+
+    match tokio::time::timeout(std::time::Duration::from_secs(QUERY_TIMEOUT_SECS), rx).await {
+        Ok(Ok(res)) => res,
+        _ => Err("process query timed out".into()),
+    }
+}
+
+#[cfg(not(windows))]
+async fn query_environment(_wv: &tauri::Webview) -> Result<(u32, Vec<RawProc>), String> {
+    Err("process info is only available on Windows".into())
+}
+
+#[cfg(windows)]
+fn terminate(pid: u32) -> Result<(), BushidoError> {
+    use windows::Win32::Foundation::CloseHandle;
+    use windows::Win32::System::Threading::{OpenProcess, TerminateProcess, PROCESS_TERMINATE};
+    unsafe {
+        let handle = OpenProcess(PROCESS_TERMINATE, false, pid)
+            .map_err(|e| BushidoError::Permission(format!("open process: {}", e)))?;
+        let res = TerminateProcess(handle, 1);
+        let _ = CloseHandle(handle);
+        res.map_err(|e| BushidoError::Other(format!("terminate: {}", e)))
+    }
+}
+
+#[cfg(not(windows))]
+fn terminate(_pid: u32) -> Result<(), BushidoError> {
+    Err(BushidoError::Other("not supported on this platform".into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn proc(pid: u32, kind: &str, tabs: &[&str]) -> RendererProcInfo {
+        RendererProcInfo {
+            pid,
+            kind: kind.into(),
+            browser_pid: 1,
+            frame_urls: Vec::new(),
+            tab_ids: tabs.iter().map(|t| t.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn attributes_only_unambiguous_renderers() {
+        let procs = vec![
+            proc(10, "renderer", &["tab-1"]),
+            proc(11, "renderer", &["tab-2", "tab-3"]),
+            proc(12, "gpu", &[]),
+            proc(10, "renderer", &["tab-1"]),
+        ];
+        let metrics = tab_metrics(&procs, |pid| Some(pid as u64 * 1000));
+        assert_eq!(metrics.len(), 3);
+        assert_eq!(metrics[0].pid, 12);
+        assert_eq!(metrics[0].tab_id, None);
+        assert_eq!(metrics[1].tab_id, None);
+        assert_eq!(metrics[1].shared_by, vec!["tab-2", "tab-3"]);
+        assert_eq!(metrics[1].memory_bytes, 11_000);
+        assert_eq!(metrics[2].tab_id.as_deref(), Some("tab-1"));
+        assert!(metrics[2].shared_by.is_empty());
+    }
+}
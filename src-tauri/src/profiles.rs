@@ -0,0 +1,76 @@
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+
+use crate::error::BushidoError;
+
+// WebView2's own limit for ProfileName
+const MAX_PROFILE_NAME_LEN: usize = 64;
+const PROFILE_PUNCTUATION: &str = "#@$()+-_~.[]{}";
+
+/// Check a WebView2 profile name: ASCII letters, digits and `#@$()+-_~.[]{}`, at
+/// most 64 characters, not ending in a dot. WebView2 matches names case-insensitively,
+/// so they're lowercased here to make "Work" and "work" the same profile everywhere.
+pub fn validate_profile_name(name: &str) -> Result<String, BushidoError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(BushidoError::Other("empty profile name".into()));
+    }
+    if name.len() > MAX_PROFILE_NAME_LEN {
+        return Err(BushidoError::Limit(format!("profile names are limited to {} characters", MAX_PROFILE_NAME_LEN)));
+    }
+    if name.ends_with('.') || !name.chars().all(|c| c.is_ascii_alphanumeric() || PROFILE_PUNCTUATION.contains(c)) {
+        return Err(BushidoError::Other(format!("invalid profile name: {}", name)));
+    }
+    Ok(name.to_lowercase())
+}
+
+/// WebView2 keeps one folder per profile under `<user data folder>/EBWebView`. The user
+/// data folder is the app's local data dir unless WEBVIEW2_USER_DATA_FOLDER moved it
+/// (portable mode).
+fn profiles_dir(app: &AppHandle) -> Option<PathBuf> {
+    let user_data = match std::env::var_os("WEBVIEW2_USER_DATA_FOLDER") {
+        Some(dir) => PathBuf::from(dir),
+        None => app.path().app_local_data_dir().ok()?,
+    };
+    Some(user_data.join("EBWebView"))
+}
+
+/// Profiles that have storage on disk, "default" being the one tabs without a profile use.
+/// A folder only counts once it has a `Preferences` file, which tells profiles apart
+/// from the runtime's caches living next to them.
+#[tauri::command]
+pub async fn list_profiles(app: AppHandle) -> Result<Vec<String>, BushidoError> {
+    let Some(dir) = profiles_dir(&app) else { return Ok(Vec::new()) };
+    let Ok(entries) = fs::read_dir(&dir) else { return Ok(Vec::new()) };
+    let mut names: Vec<String> = entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().join("Preferences").is_file())
+        .filter_map(|e| validate_profile_name(&e.file_name().to_string_lossy()).ok())
+        .collect();
+    names.sort();
+    names.dedup();
+    Ok(names)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_workspace_ids_and_folds_case() {
+        assert_eq!(validate_profile_name("ws-1").unwrap(), "ws-1");
+        assert_eq!(validate_profile_name(" Work ").unwrap(), "work");
+        assert_eq!(validate_profile_name("a_b~c.d[1]{2}(3)#@$+").unwrap(), "a_b~c.d[1]{2}(3)#@$+");
+    }
+
+    #[test]
+    fn rejects_names_webview2_would_refuse() {
+        assert!(validate_profile_name("").is_err());
+        assert!(validate_profile_name("personal.").is_err());
+        assert!(validate_profile_name("../escape").is_err());
+        assert!(validate_profile_name("two words").is_err());
+        assert!(validate_profile_name("café").is_err());
+        assert!(validate_profile_name(&"a".repeat(MAX_PROFILE_NAME_LEN + 1)).is_err());
+    }
+}
@@ -0,0 +1,281 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use parking_lot::Mutex;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Manager};
+
+use crate::error::BushidoError;
+
+const MAX_BYPASS_RULES: usize = 64;
+const MAX_CREDENTIAL_LEN: usize = 256;
+// wry's own defaults, which additional_browser_args replaces
+const WRY_DEFAULT_ARGS: &str = "--disable-features=msWebOOUI,msPdfOOUI,msSmartScreenProtection";
+
+/// Browser arguments every webview is built with: wry's defaults plus the startup flags
+/// (DNS, autoplay, memory). Webviews sharing a user data folder share one browser
+/// process, which refuses to start a second time with different arguments.
+pub struct BrowserArgs(String);
+
+impl BrowserArgs {
+    pub fn new(startup: &str) -> Self {
+        // Chromium only reads the last --enable-features and --disable-features, so
+        // each list is merged
+        let mut enabled: Vec<&str> = Vec::new();
+        let mut disabled: Vec<&str> = Vec::new();
+        let mut rest: Vec<&str> = Vec::new();
+        for arg in WRY_DEFAULT_ARGS.split_whitespace().chain(startup.split_whitespace()) {
+            if let Some(features) = arg.strip_prefix("--enable-features=") {
+                enabled.extend(features.split(',').filter(|f| !f.is_empty()));
+            } else if let Some(features) = arg.strip_prefix("--disable-features=") {
+                disabled.extend(features.split(',').filter(|f| !f.is_empty()));
+            } else {
+                rest.push(arg);
+            }
+        }
+        let enable = (!enabled.is_empty()).then(|| format!("--enable-features={}", enabled.join(",")));
+        rest.extend(enable.as_deref());
+        rest.push("");
+        Self(format!("{}--disable-features={}", rest.join(" "), disabled.join(",")))
+    }
+
+    pub fn base(&self) -> &str {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProxyScheme {
+    Http,
+    Socks5,
+}
+
+impl ProxyScheme {
+    fn as_str(self) -> &'static str {
+        match self {
+            ProxyScheme::Http => "http",
+            ProxyScheme::Socks5 => "socks5",
+        }
+    }
+}
+
+/// Proxy for one tab, as create_tab receives it
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxySettings {
+    pub scheme: ProxyScheme,
+    pub host: String,
+    pub port: u16,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// hosts that skip the proxy, in Chromium's bypass-list syntax
+    /// ("*.corp.example", "10.0.0.0/8", "<local>")
+    #[serde(default)]
+    pub bypass: Vec<String>,
+}
+
+struct ProxyAuth {
+    host: String,
+    port: u16,
+    username: String,
+    password: String,
+}
+
+/// Credentials of tabs behind an authenticating HTTP proxy, answered from the
+/// BasicAuthenticationRequested handler.
+pub struct ProxyState {
+    auth: Mutex<HashMap<String, ProxyAuth>>,
+}
+
+fn bypass_rule_ok(rule: &str) -> bool {
+    rule.chars().all(|c| c.is_ascii_alphanumeric() || ".*:-_/<>[]".contains(c))
+}
+
+impl ProxySettings {
+    /// Normalized copy, or why the settings can't be used. Everything ends up on the
+    /// browser command line, so hosts and rules are held to a strict character set.
+    fn validate(self) -> Result<Self, BushidoError> {
+        let host = url::Host::parse(&self.host.trim().to_lowercase())
+            .map_err(|e| BushidoError::Other(format!("invalid proxy host: {}", e)))?
+            .to_string();
+        if self.port == 0 {
+            return Err(BushidoError::Other("invalid proxy port".into()));
+        }
+        let username = self.username.filter(|u| !u.is_empty());
+        let password = self.password.filter(|p| !p.is_empty());
+        if (username.is_some() || password.is_some()) && self.scheme == ProxyScheme::Socks5 {
+            return Err(BushidoError::Other("SOCKS5 proxies with a login aren't supported by WebView2".into()));
+        }
+        if password.is_some() && username.is_none() {
+            return Err(BushidoError::Other("proxy password without a username".into()));
+        }
+        let too_long = |s: &Option<String>| s.as_ref().is_some_and(|s| s.len() > MAX_CREDENTIAL_LEN);
+        if too_long(&username) || too_long(&password) {
+            return Err(BushidoError::Limit(format!("proxy credentials are limited to {} characters", MAX_CREDENTIAL_LEN)));
+        }
+        let bypass: Vec<String> = self.bypass.iter()
+            .map(|r| r.trim().to_lowercase())
+            .filter(|r| !r.is_empty())
+            .collect();
+        if bypass.len() > MAX_BYPASS_RULES {
+            return Err(BushidoError::Limit(format!("at most {} proxy bypass rules", MAX_BYPASS_RULES)));
+        }
+        if let Some(bad) = bypass.iter().find(|r| !bypass_rule_ok(r)) {
+            return Err(BushidoError::Other(format!("invalid proxy bypass rule: {}", bad)));
+        }
+        Ok(Self { scheme: self.scheme, host, port: self.port, username, password, bypass })
+    }
+
+    /// `base` (see `BrowserArgs`) plus this route's proxy flags
+    fn browser_args(&self, base: &str) -> String {
+        let mut args = format!("{} --proxy-server={}://{}:{}", base, self.scheme.as_str(), self.host, self.port);
+        if !self.bypass.is_empty() {
+            args.push_str(" --proxy-bypass-list=");
+            args.push_str(&self.bypass.join(";"));
+        }
+        args
+    }
+
+    /// Names the user data folder: the same route shares one, credentials aside
+    fn route_key(&self) -> String {
+        let route = format!("{}://{}:{}|{}", self.scheme.as_str(), self.host, self.port, self.bypass.join(";"));
+        Sha256::digest(route.as_bytes()).iter().take(8).map(|b| format!("{:02x}", b)).collect()
+    }
+}
+
+impl ProxyState {
+    pub fn new() -> Self {
+        Self { auth: Mutex::new(HashMap::new()) }
+    }
+
+    /// Login for a proxy challenge on `tab_id`. WebView2 reports the proxy itself as the
+    /// URI of a proxy challenge, so a site asking for a login never gets these.
+    pub fn credentials_for(&self, tab_id: &str, uri: &str) -> Option<(String, String)> {
+        let u = url::Url::parse(uri).ok()?;
+        let auth = self.auth.lock();
+        let a = auth.get(tab_id)?;
+        let same_proxy = u.host_str().is_some_and(|h| h.eq_ignore_ascii_case(&a.host))
+            && u.port_or_known_default() == Some(a.port);
+        same_proxy.then(|| (a.username.clone(), a.password.clone()))
+    }
+
+    pub fn forget_tab(&self, tab_id: &str) {
+        self.auth.lock().remove(tab_id);
+    }
+}
+
+/// Browser arguments and user data folder for a proxied tab. Proxy flags apply to a whole
+/// WebView2 browser process, and one process serves every tab sharing a user data folder,
+/// so each proxy route gets its own folder (and with it its own cookies and cache). The
+/// proxy is fixed at creation; changing it means recreating the tab.
+pub fn prepare(app: &AppHandle, tab_id: &str, settings: ProxySettings) -> Result<(String, PathBuf), BushidoError> {
+    let settings = settings.validate()?;
+    // portable mode pins every webview to one folder through the environment
+    if std::env::var_os("WEBVIEW2_USER_DATA_FOLDER").is_some() {
+        return Err(BushidoError::Other("per-tab proxies aren't available in portable mode".into()));
+    }
+    let dir = app.path().app_local_data_dir()
+        .map_err(|e| BushidoError::Other(e.to_string()))?
+        .join("proxies")
+        .join(settings.route_key());
+
+    let ps = app.state::<ProxyState>();
+    match (&settings.username, &settings.password) {
+        (Some(username), password) => {
+            ps.auth.lock().insert(tab_id.to_string(), ProxyAuth {
+                host: settings.host.clone(),
+                port: settings.port,
+                username: username.clone(),
+                password: password.clone().unwrap_or_default(),
+            });
+        }
+        _ => ps.forget_tab(tab_id),
+    }
+    Ok((settings.browser_args(app.state::<BrowserArgs>().base()), dir))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(scheme: ProxyScheme, host: &str) -> ProxySettings {
+        ProxySettings { scheme, host: host.into(), port: 9050, username: None, password: None, bypass: Vec::new() }
+    }
+
+    #[test]
+    fn builds_server_and_bypass_args() {
+        let mut s = settings(ProxyScheme::Socks5, " LocalHost ");
+        s.bypass = vec!["*.corp.example".into(), " ".into(), "<local>".into()];
+        let s = s.validate().unwrap();
+        assert_eq!(
+            s.browser_args("--base"),
+            "--base --proxy-server=socks5://localhost:9050 --proxy-bypass-list=*.corp.example;<local>"
+        );
+        let v6 = settings(ProxyScheme::Http, "::1").validate();
+        assert!(v6.is_err());
+        let v6 = settings(ProxyScheme::Http, "[::1]").validate().unwrap();
+        assert!(v6.browser_args("").ends_with("--proxy-server=http://[::1]:9050"));
+    }
+
+    #[test]
+    fn proxied_tabs_keep_the_startup_flags() {
+        let base = BrowserArgs::new("--enable-quic --disable-features=UserAgentClientHint\n             --site-per-process ");
+        assert_eq!(
+            base.base(),
+            "--enable-quic --site-per-process --disable-features=msWebOOUI,msPdfOOUI,msSmartScreenProtection,UserAgentClientHint"
+        );
+        let args = settings(ProxyScheme::Http, "proxy").validate().unwrap().browser_args(base.base());
+        assert!(args.starts_with(base.base()));
+        assert!(args.contains("msSmartScreenProtection"));
+        assert!(args.contains("--site-per-process"));
+        assert!(args.ends_with(" --proxy-server=http://proxy:9050"));
+    }
+
+    #[test]
+    fn enabled_features_are_merged_too() {
+        let base = BrowserArgs::new("--enable-features=A,B --disable-features=C --enable-features=D");
+        assert_eq!(
+            base.base(),
+            "--enable-features=A,B,D --disable-features=msWebOOUI,msPdfOOUI,msSmartScreenProtection,C"
+        );
+        assert!(!BrowserArgs::new("--enable-quic").base().contains("--enable-features"));
+    }
+
+    #[test]
+    fn rejects_unsafe_or_unsupported_settings() {
+        assert!(settings(ProxyScheme::Http, "proxy host").validate().is_err());
+        assert!(settings(ProxyScheme::Http, "a --no-sandbox").validate().is_err());
+        assert!(ProxySettings { port: 0, ..settings(ProxyScheme::Http, "proxy") }.validate().is_err());
+        let mut bad_rule = settings(ProxyScheme::Http, "proxy");
+        bad_rule.bypass = vec!["a --no-sandbox".into()];
+        assert!(bad_rule.validate().is_err());
+        let mut socks_login = settings(ProxyScheme::Socks5, "proxy");
+        socks_login.username = Some("me".into());
+        assert!(socks_login.validate().is_err());
+    }
+
+    #[test]
+    fn route_key_ignores_credentials() {
+        let plain = settings(ProxyScheme::Http, "proxy").validate().unwrap();
+        let mut login = settings(ProxyScheme::Http, "proxy");
+        login.username = Some("me".into());
+        login.password = Some("secret".into());
+        let login = login.validate().unwrap();
+        assert_eq!(plain.route_key(), login.route_key());
+        assert_eq!(plain.route_key().len(), 16);
+        assert_ne!(plain.route_key(), settings(ProxyScheme::Socks5, "proxy").validate().unwrap().route_key());
+    }
+
+    #[test]
+    fn answers_only_the_proxy_challenge() {
+        let state = ProxyState::new();
+        state.auth.lock().insert("tab-1".into(), ProxyAuth {
+            host: "proxy.corp".into(), port: 3128, username: "me".into(), password: "pw".into(),
+        });
+        assert_eq!(state.credentials_for("tab-1", "http://PROXY.corp:3128/"), Some(("me".into(), "pw".into())));
+        assert_eq!(state.credentials_for("tab-1", "https://site.example/login"), None);
+        assert_eq!(state.credentials_for("tab-1", "http://proxy.corp/"), None);
+        assert_eq!(state.credentials_for("tab-2", "http://proxy.corp:3128/"), None);
+    }
+}
@@ -0,0 +1,141 @@
+(function() {
+  if (window.__bushidoTts) return;
+
+  var CURRENT = '__bushido-tts-current';
+  var BLOCKS = 'h1,h2,h3,h4,h5,h6,p,li,blockquote,pre,figcaption,dd,dt';
+  // Chromium cuts long utterances off after ~15s, and cancel() only takes
+  // effect between chunks on some voices; sentences keep both short
+  var MAX_CHUNK = 240;
+
+  var voiceName = null, rate = 1;
+  // chunks: { text, block }; bumped generation makes stale onend callbacks no-ops
+  var chunks = [], blocks = [], pos = 0, gen = 0, currentBlock = -1;
+
+  function post(state, extra) {
+    if (!(window.chrome && window.chrome.webview)) return;
+    var msg = { __bushido: 'tts', state: state };
+    for (var k in extra || {}) msg[k] = extra[k];
+    window.chrome.webview.postMessage(JSON.stringify(msg));
+  }
+
+  function supported() {
+    return 'speechSynthesis' in window && typeof SpeechSynthesisUtterance === 'function';
+  }
+
+  function ensureStyle() {
+    if (document.getElementById(CURRENT + '-style')) return;
+    var s = document.createElement('style');
+    s.id = CURRENT + '-style';
+    s.textContent = '.' + CURRENT + '{background:rgba(255,200,60,.22)!important;border-radius:4px;box-shadow:0 0 0 4px rgba(255,200,60,.22)}';
+    (document.head || document.documentElement).appendChild(s);
+  }
+
+  function splitText(text) {
+    if (text.length <= MAX_CHUNK) return [text];
+    var out = [], cur = '';
+    (text.match(/[^.!?。！？]+[.!?。！？]*\s*/g) || [text]).forEach(function(s) {
+      while (s.length > MAX_CHUNK) {
+        var cut = s.lastIndexOf(' ', MAX_CHUNK);
+        if (cut <= 0) cut = MAX_CHUNK;
+        if (cur) { out.push(cur); cur = ''; }
+        out.push(s.slice(0, cut));
+        s = s.slice(cut);
+      }
+      if (cur.length + s.length > MAX_CHUNK) { out.push(cur); cur = ''; }
+      cur += s;
+    });
+    if (cur.trim()) out.push(cur);
+    return out.map(function(c) { return c.trim(); }).filter(Boolean);
+  }
+
+  // leaf-most text blocks in reading order: a <li> wrapping a <p> is read as the <p>
+  function collect(root) {
+    chunks = []; blocks = [];
+    Array.prototype.forEach.call(root.querySelectorAll(BLOCKS), function(el) {
+      if (el.querySelector(BLOCKS)) return;
+      var text = (el.innerText || el.textContent || '').replace(/\s+/g, ' ').trim();
+      if (!text) return;
+      var b = blocks.push(el) - 1;
+      splitText(text).forEach(function(t) { chunks.push({ text: t, block: b }); });
+    });
+  }
+
+  function pickVoice() {
+    if (!voiceName) return null;
+    var voices = window.speechSynthesis.getVoices();
+    for (var i = 0; i < voices.length; i++) {
+      if (voices[i].name === voiceName || voices[i].voiceURI === voiceName) return voices[i];
+    }
+    return null;
+  }
+
+  function mark(b) {
+    if (b === currentBlock) return;
+    if (blocks[currentBlock]) blocks[currentBlock].classList.remove(CURRENT);
+    currentBlock = b;
+    if (blocks[b]) {
+      blocks[b].classList.add(CURRENT);
+      blocks[b].scrollIntoView({ block: 'center', behavior: 'smooth' });
+    }
+    post('speaking', { index: b, total: blocks.length });
+  }
+
+  function reset() {
+    gen++;
+    if (blocks[currentBlock]) blocks[currentBlock].classList.remove(CURRENT);
+    currentBlock = -1;
+    if (supported()) window.speechSynthesis.cancel();
+  }
+
+  function speakNext(g) {
+    if (g !== gen) return;
+    var c = chunks[pos];
+    // reader closed underneath us
+    if (!c || !blocks[c.block].isConnected) {
+      var done = !c;
+      reset(); chunks = []; blocks = [];
+      post(done ? 'ended' : 'stopped');
+      return;
+    }
+    mark(c.block);
+    var u = new SpeechSynthesisUtterance(c.text);
+    var v = pickVoice();
+    if (v) { u.voice = v; u.lang = v.lang; }
+    u.rate = rate;
+    u.onend = function() { if (g === gen) { pos++; speakNext(g); } };
+    u.onerror = function(e) {
+      if (g !== gen || e.error === 'interrupted' || e.error === 'canceled') return;
+      reset();
+      post('error', { error: String(e.error || '') });
+    };
+    window.speechSynthesis.speak(u);
+  }
+
+  window.__bushidoTts = {
+    speak: function() {
+      if (!supported()) { post('unavailable'); return; }
+      var root = document.querySelector('#__bushido_reader .bushido-reader-content');
+      if (!root) { post('no-reader'); return; }
+      reset();
+      ensureStyle();
+      collect(root);
+      pos = 0;
+      speakNext(gen);
+    },
+    stop: function() {
+      if (!chunks.length) return;
+      reset();
+      chunks = []; blocks = [];
+      post('stopped');
+    },
+    // applies from the current chunk on when already speaking
+    setVoice: function(voice, r) {
+      if (voice !== undefined) voiceName = voice || null;
+      if (typeof r === 'number' && isFinite(r)) rate = r;
+      if (!supported() || !chunks.length) return;
+      gen++;
+      window.speechSynthesis.cancel();
+      speakNext(gen);
+    }
+  };
+})();
@@ -0,0 +1,78 @@
+use tauri::{AppHandle, Manager};
+
+use crate::error::BushidoError;
+
+/// Defines `window.__bushidoTts`; idempotent, so it rides along with every call.
+const TTS_SCRIPT: &str = include_str!("reader_tts.js");
+
+// SpeechSynthesisUtterance.rate's range
+const MIN_RATE: f64 = 0.1;
+const MAX_RATE: f64 = 10.0;
+const MAX_VOICE_LEN: usize = 256;
+
+fn tts_call(call: &str) -> String {
+    format!("{}\nwindow.__bushidoTts.{};", TTS_SCRIPT, call)
+}
+
+fn set_voice_call(voice: Option<&str>, rate: Option<f64>) -> Result<String, BushidoError> {
+    if voice.is_some_and(|v| v.len() > MAX_VOICE_LEN) {
+        return Err(BushidoError::Limit(format!("voice names are limited to {} characters", MAX_VOICE_LEN)));
+    }
+    if let Some(rate) = rate {
+        if !(MIN_RATE..=MAX_RATE).contains(&rate) {
+            return Err(BushidoError::Other(format!("speech rate must be between {} and {}", MIN_RATE, MAX_RATE)));
+        }
+    }
+    let voice = serde_json::to_string(&voice).unwrap_or_else(|_| "null".into());
+    let rate = rate.map(|r| r.to_string()).unwrap_or_else(|| "null".into());
+    Ok(tts_call(&format!("setVoice({},{})", voice, rate)))
+}
+
+fn eval_in_tab(app: &AppHandle, id: &str, js: &str) -> Result<(), BushidoError> {
+    let wv = app.get_webview(id).ok_or_else(|| BushidoError::NotFound(format!("tab {}", id)))?;
+    wv.eval(js)?;
+    Ok(())
+}
+
+/// Read the reader overlay aloud, paragraph by paragraph, highlighting the one being
+/// spoken. Progress arrives as `reader-tts` events; without speech synthesis or an open
+/// reader the page just reports that and nothing happens.
+#[tauri::command]
+pub async fn reader_speak(app: AppHandle, id: String) -> Result<(), BushidoError> {
+    eval_in_tab(&app, &id, &tts_call("speak()"))
+}
+
+#[tauri::command]
+pub async fn reader_stop(app: AppHandle, id: String) -> Result<(), BushidoError> {
+    eval_in_tab(&app, &id, &tts_call("stop()"))
+}
+
+/// Pick the voice (name or voiceURI; None = system default) and rate for this tab.
+/// Takes effect immediately when already speaking. A voice the system doesn't have
+/// falls back to the default.
+#[tauri::command]
+pub async fn reader_set_voice(app: AppHandle, id: String, voice: Option<String>, rate: Option<f64>) -> Result<(), BushidoError> {
+    let js = set_voice_call(voice.as_deref(), rate)?;
+    eval_in_tab(&app, &id, &js)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn voice_name_is_a_string_literal() {
+        let js = set_voice_call(Some("Microsoft Aria'); alert(1); ('"), Some(1.5)).unwrap();
+        assert!(js.ends_with(r#"window.__bushidoTts.setVoice("Microsoft Aria'); alert(1); ('",1.5);"#));
+        let js = set_voice_call(None, None).unwrap();
+        assert!(js.ends_with("window.__bushidoTts.setVoice(null,null);"));
+    }
+
+    #[test]
+    fn rejects_bad_rate_and_long_voice() {
+        assert!(set_voice_call(None, Some(0.0)).is_err());
+        assert!(set_voice_call(None, Some(f64::NAN)).is_err());
+        assert!(set_voice_call(None, Some(11.0)).is_err());
+        assert!(set_voice_call(Some(&"v".repeat(MAX_VOICE_LEN + 1)), None).is_err());
+    }
+}
@@ -0,0 +1,205 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::error::BushidoError;
+
+const MAX_ENTRIES: usize = 1000;
+const MAX_TITLE_LEN: usize = 512;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadingListEntry {
+    pub url: String,
+    pub title: String,
+    pub added_at_ms: u64,
+    pub read: bool,
+    #[serde(default)]
+    pub read_at_ms: Option<u64>,
+}
+
+/// Pages saved to read later, newest first. Kept apart from bookmarks and the sync doc.
+pub struct ReadingListState {
+    /// persisted in reading_list.json
+    entries: Mutex<Vec<ReadingListEntry>>,
+}
+
+fn list_path(app: &AppHandle) -> PathBuf {
+    crate::data_dir(app).join("reading_list.json")
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64
+}
+
+/// The form entries are stored and matched under: http(s) only, fragment dropped so
+/// `#section` links don't add the same article twice. Also the sync doc's item key.
+pub(crate) fn normalize_url(url: &str) -> Result<String, BushidoError> {
+    if crate::is_blocked_scheme(url) {
+        return Err(BushidoError::Other("Blocked URL scheme".into()));
+    }
+    let mut parsed = url::Url::parse(url.trim())?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(BushidoError::Other("only http(s) pages can go on the reading list".into()));
+    }
+    parsed.set_fragment(None);
+    Ok(parsed.to_string())
+}
+
+impl ReadingListState {
+    pub fn load(app: &AppHandle) -> Self {
+        let entries: Vec<ReadingListEntry> = fs::read_to_string(list_path(app)).ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default();
+        Self { entries: Mutex::new(entries) }
+    }
+
+    fn list(&self) -> Vec<ReadingListEntry> {
+        self.entries.lock().clone()
+    }
+
+    /// Adding a URL that's already there keeps its place, takes the new title and
+    /// makes it unread again
+    fn add(&self, url: &str, title: &str, now_ms: u64) -> Result<ReadingListEntry, BushidoError> {
+        let url = normalize_url(url)?;
+        let title: String = title.trim().chars().take(MAX_TITLE_LEN).collect();
+        let mut entries = self.entries.lock();
+        if let Some(e) = entries.iter_mut().find(|e| e.url == url) {
+            if !title.is_empty() {
+                e.title = title;
+            }
+            e.read = false;
+            e.read_at_ms = None;
+            return Ok(e.clone());
+        }
+        if entries.len() >= MAX_ENTRIES {
+            return Err(BushidoError::Limit(format!("at most {} reading list entries", MAX_ENTRIES)));
+        }
+        let entry = ReadingListEntry {
+            title: if title.is_empty() { url.clone() } else { title },
+            url,
+            added_at_ms: now_ms,
+            read: false,
+            read_at_ms: None,
+        };
+        entries.insert(0, entry.clone());
+        Ok(entry)
+    }
+
+    /// Whether the URL was on the list
+    fn remove(&self, url: &str) -> Result<bool, BushidoError> {
+        let url = normalize_url(url)?;
+        let mut entries = self.entries.lock();
+        let before = entries.len();
+        entries.retain(|e| e.url != url);
+        Ok(entries.len() != before)
+    }
+
+    fn mark_read(&self, url: &str, read: bool, now_ms: u64) -> Result<(), BushidoError> {
+        let url = normalize_url(url)?;
+        let mut entries = self.entries.lock();
+        let entry = entries.iter_mut().find(|e| e.url == url)
+            .ok_or_else(|| BushidoError::NotFound(format!("reading list entry {}", url)))?;
+        if entry.read != read {
+            entry.read = read;
+            entry.read_at_ms = read.then_some(now_ms);
+        }
+        Ok(())
+    }
+}
+
+fn persist(app: &AppHandle) {
+    let entries = app.state::<ReadingListState>().list();
+    let _ = app.emit_to("main", "reading-list-changed", &entries);
+    match serde_json::to_string(&entries) {
+        Ok(json) => {
+            if let Err(e) = fs::write(list_path(app), json) {
+                crate::crash_log::log_warn("reading_list", &format!("could not save reading list: {}", e));
+            }
+        }
+        Err(e) => crate::crash_log::log_warn("reading_list", &format!("could not serialize reading list: {}", e)),
+    }
+}
+
+#[tauri::command]
+pub async fn reading_list_add(app: AppHandle, url: String, title: String) -> Result<ReadingListEntry, BushidoError> {
+    let entry = app.state::<ReadingListState>().add(&url, &title, now_ms())?;
+    persist(&app);
+    Ok(entry)
+}
+
+#[tauri::command]
+pub async fn reading_list_remove(app: AppHandle, url: String) -> Result<(), BushidoError> {
+    if app.state::<ReadingListState>().remove(&url)? {
+        persist(&app);
+    }
+    Ok(())
+}
+
+/// Marks the entry read, or unread again with `read: false`
+#[tauri::command]
+pub async fn reading_list_mark_read(app: AppHandle, url: String, read: Option<bool>) -> Result<(), BushidoError> {
+    app.state::<ReadingListState>().mark_read(&url, read.unwrap_or(true), now_ms())?;
+    persist(&app);
+    Ok(())
+}
+
+/// Newest first
+#[tauri::command]
+pub async fn reading_list_get(app: AppHandle) -> Result<Vec<ReadingListEntry>, BushidoError> {
+    Ok(app.state::<ReadingListState>().list())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state() -> ReadingListState {
+        ReadingListState { entries: Mutex::new(Vec::new()) }
+    }
+
+    #[test]
+    fn dedups_by_url_ignoring_fragment() {
+        let list = state();
+        list.add("https://a.example/post", "Post", 1).unwrap();
+        list.add("https://b.example/", "  ", 2).unwrap();
+        list.mark_read("https://a.example/post", true, 3).unwrap();
+        let again = list.add("https://a.example/post#comments", "Post, updated", 4).unwrap();
+        assert_eq!(again.added_at_ms, 1);
+        assert!(!again.read);
+        assert_eq!(again.read_at_ms, None);
+
+        let entries = list.list();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].url, "https://b.example/");
+        assert_eq!(entries[0].title, "https://b.example/");
+        assert_eq!(entries[1].title, "Post, updated");
+    }
+
+    #[test]
+    fn marks_read_and_removes() {
+        let list = state();
+        list.add("https://a.example/", "A", 1).unwrap();
+        list.mark_read("https://a.example/", true, 5).unwrap();
+        list.mark_read("https://a.example/", true, 9).unwrap();
+        assert_eq!(list.list()[0].read_at_ms, Some(5));
+        list.mark_read("https://a.example/", false, 10).unwrap();
+        assert!(!list.list()[0].read);
+        assert!(list.mark_read("https://missing.example/", true, 1).is_err());
+
+        assert!(list.remove("https://a.example/#top").unwrap());
+        assert!(!list.remove("https://a.example/").unwrap());
+    }
+
+    #[test]
+    fn rejects_non_web_urls() {
+        let list = state();
+        assert!(list.add("javascript:alert(1)", "x", 1).is_err());
+        assert!(list.add("ftp://a.example/", "x", 1).is_err());
+        assert!(list.add("not a url", "x", 1).is_err());
+        assert!(list.list().is_empty());
+    }
+}
@@ -0,0 +1,310 @@
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::crash_log;
+use crate::error::BushidoError;
+
+const MAX_RULES: usize = 200;
+// rule chains longer than this are treated as a cycle
+const MAX_CHAIN: usize = 8;
+// a tab rewritten this often within LOOP_WINDOW is bouncing (e.g. the site redirects back)
+const MAX_REWRITES_PER_WINDOW: u32 = 3;
+const LOOP_WINDOW: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum RuleAction {
+    /// swap the host, keeping scheme, path and query
+    ReplaceHost { to: String },
+    ForceHttps,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RedirectRule {
+    pub id: String,
+    /// matched exactly or with a leading "www." — never other subdomains, so
+    /// reddit.com → old.reddit.com doesn't match its own output
+    pub host: String,
+    pub action: RuleAction,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+fn default_true() -> bool { true }
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RedirectConfig {
+    #[serde(default)]
+    pub rules: Vec<RedirectRule>,
+    #[serde(default = "default_true")]
+    pub strip_tracking: bool,
+}
+
+impl Default for RedirectConfig {
+    fn default() -> Self {
+        Self { rules: Vec::new(), strip_tracking: true }
+    }
+}
+
+/// Per-tab rewrite counter, so a site that redirects back can't ping-pong forever
+#[derive(Default)]
+struct LoopGuard {
+    tabs: HashMap<String, (Instant, u32)>,
+}
+
+impl LoopGuard {
+    fn allow(&mut self, tab_id: &str, now: Instant) -> bool {
+        let slot = self.tabs.entry(tab_id.to_string()).or_insert((now, 0));
+        if now.duration_since(slot.0) >= LOOP_WINDOW {
+            *slot = (now, 0);
+        }
+        slot.1 += 1;
+        slot.1 <= MAX_REWRITES_PER_WINDOW
+    }
+}
+
+pub struct RedirectState {
+    config: Mutex<RedirectConfig>,
+    guard: Mutex<LoopGuard>,
+}
+
+impl RedirectState {
+    pub fn load(app: &AppHandle) -> Self {
+        let config = std::fs::read_to_string(config_path(app)).ok()
+            .and_then(|data| serde_json::from_str(&data).ok())
+            .unwrap_or_default();
+        Self { config: Mutex::new(config), guard: Mutex::new(LoopGuard::default()) }
+    }
+
+    pub fn forget_tab(&self, tab_id: &str) {
+        self.guard.lock().tabs.remove(tab_id);
+    }
+
+    /// The user's "strip tracking parameters" setting, which also covers sub-resources
+    pub fn strips_tracking(&self) -> bool {
+        self.config.lock().strip_tracking
+    }
+}
+
+fn config_path(app: &AppHandle) -> PathBuf {
+    crate::data_dir(app).join("redirect_rules.json")
+}
+
+fn save(app: &AppHandle, config: &RedirectConfig) -> Result<(), BushidoError> {
+    let json = serde_json::to_string_pretty(config).map_err(|e| e.to_string())?;
+    std::fs::write(config_path(app), json)?;
+    Ok(())
+}
+
+fn normalize_host(host: &str) -> Option<String> {
+    let host = host.trim().trim_end_matches('.').to_ascii_lowercase();
+    let host = host.strip_prefix("www.").unwrap_or(&host).to_string();
+    match url::Host::parse(&host) {
+        Ok(url::Host::Domain(_)) if host.contains('.') => Some(host),
+        _ => None,
+    }
+}
+
+fn host_matches(host: &str, rule_host: &str) -> bool {
+    host == rule_host || host.strip_prefix("www.") == Some(rule_host)
+}
+
+fn apply_once(config: &RedirectConfig, url: &url::Url) -> Option<url::Url> {
+    let host = url.host_str()?.to_ascii_lowercase();
+    for rule in config.rules.iter().filter(|r| r.enabled && host_matches(&host, &r.host)) {
+        let mut next = url.clone();
+        let changed = match &rule.action {
+            RuleAction::ReplaceHost { to } => to != &host && next.set_host(Some(to)).is_ok(),
+            RuleAction::ForceHttps => url.scheme() == "http" && next.set_scheme("https").is_ok(),
+        };
+        if changed {
+            return Some(next);
+        }
+    }
+    let mut next = url.clone();
+    if config.strip_tracking && crate::blocker::strip_tracking(&mut next) {
+        return Some(next);
+    }
+    None
+}
+
+/// Rewrite a top-level navigation. None if nothing applies or the rules form a cycle.
+pub fn rewrite(config: &RedirectConfig, url: &str) -> Option<String> {
+    let mut current = url::Url::parse(url).ok()?;
+    if !matches!(current.scheme(), "http" | "https") {
+        return None;
+    }
+    let mut seen = HashSet::from([current.to_string()]);
+    let mut changed = false;
+    for _ in 0..MAX_CHAIN {
+        match apply_once(config, &current) {
+            None => return changed.then(|| current.to_string()),
+            Some(next) => {
+                if !seen.insert(next.to_string()) {
+                    return None;
+                }
+                current = next;
+                changed = true;
+            }
+        }
+    }
+    None
+}
+
+/// Called from on_navigation: the rewritten URL if this navigation should be redirected
+pub fn rewrite_for_tab(app: &AppHandle, tab_id: &str, url: &str) -> Option<String> {
+    let rs = app.state::<RedirectState>();
+    let target = rewrite(&rs.config.lock(), url)?;
+    if !rs.guard.lock().allow(tab_id, Instant::now()) {
+        crash_log::log_warn("redirect", &format!("loop on tab {} at {}, letting it through", tab_id, url));
+        return None;
+    }
+    Some(target)
+}
+
+#[tauri::command]
+pub async fn get_redirect_config(app: AppHandle) -> Result<RedirectConfig, BushidoError> {
+    Ok(app.state::<RedirectState>().config.lock().clone())
+}
+
+#[tauri::command]
+pub async fn add_redirect_rule(app: AppHandle, host: String, action: RuleAction) -> Result<RedirectRule, BushidoError> {
+    let host = normalize_host(&host).ok_or_else(|| BushidoError::Other("Invalid host".into()))?;
+    let action = match action {
+        RuleAction::ReplaceHost { to } => {
+            let to = to.trim().trim_end_matches('.').to_ascii_lowercase();
+            match url::Host::parse(&to) {
+                Ok(url::Host::Domain(_)) if to.contains('.') && to != host => RuleAction::ReplaceHost { to },
+                _ => return Err(BushidoError::Other("Invalid target host".into())),
+            }
+        }
+        a => a,
+    };
+    let rule = RedirectRule { id: uuid::Uuid::new_v4().to_string(), host, action, enabled: true };
+
+    let rs = app.state::<RedirectState>();
+    let mut config = rs.config.lock();
+    if config.rules.len() >= MAX_RULES {
+        return Err(BushidoError::Limit(format!("Max {} redirect rules", MAX_RULES)));
+    }
+    // refuse rules that would make an existing rule bounce back (a → b, b → a)
+    let mut candidate = config.clone();
+    candidate.rules.push(rule.clone());
+    let probe = format!("https://{}/", rule.host);
+    if apply_once(&candidate, &url::Url::parse(&probe)?).is_some()
+        && rewrite(&candidate, &probe).is_none()
+    {
+        return Err(BushidoError::Other("Rule would create a redirect loop".into()));
+    }
+    config.rules.push(rule.clone());
+    save(&app, &config)?;
+    Ok(rule)
+}
+
+#[tauri::command]
+pub async fn remove_redirect_rule(app: AppHandle, id: String) -> Result<(), BushidoError> {
+    let rs = app.state::<RedirectState>();
+    let mut config = rs.config.lock();
+    let before = config.rules.len();
+    config.rules.retain(|r| r.id != id);
+    if config.rules.len() == before {
+        return Err(BushidoError::NotFound("No such redirect rule".into()));
+    }
+    save(&app, &config)
+}
+
+#[tauri::command]
+pub async fn set_redirect_rule_enabled(app: AppHandle, id: String, enabled: bool) -> Result<(), BushidoError> {
+    let rs = app.state::<RedirectState>();
+    let mut config = rs.config.lock();
+    let rule = config.rules.iter_mut().find(|r| r.id == id)
+        .ok_or_else(|| BushidoError::NotFound("No such redirect rule".into()))?;
+    rule.enabled = enabled;
+    save(&app, &config)
+}
+
+#[tauri::command]
+pub async fn set_strip_tracking_params(app: AppHandle, enabled: bool) -> Result<(), BushidoError> {
+    let rs = app.state::<RedirectState>();
+    let mut config = rs.config.lock();
+    config.strip_tracking = enabled;
+    save(&app, &config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(host: &str, action: RuleAction) -> RedirectRule {
+        RedirectRule { id: host.into(), host: host.into(), action, enabled: true }
+    }
+
+    fn old_reddit() -> RedirectConfig {
+        RedirectConfig {
+            rules: vec![rule("reddit.com", RuleAction::ReplaceHost { to: "old.reddit.com".into() })],
+            strip_tracking: true,
+        }
+    }
+
+    #[test]
+    fn rewrites_host_and_strips_tracking() {
+        let c = old_reddit();
+        assert_eq!(
+            rewrite(&c, "https://www.reddit.com/r/rust/?utm_source=x&sort=new&fbclid=abc#top").as_deref(),
+            Some("https://old.reddit.com/r/rust/?sort=new#top")
+        );
+        // the rewritten URL is a fixpoint
+        assert_eq!(rewrite(&c, "https://old.reddit.com/r/rust/"), None);
+        // other params keep their original encoding
+        assert_eq!(
+            rewrite(&c, "https://example.com/s?q=a%20b+c&UTM_MEDIUM=mail").as_deref(),
+            Some("https://example.com/s?q=a%20b+c")
+        );
+        assert_eq!(rewrite(&c, "https://example.com/?gclid=1").as_deref(), Some("https://example.com/"));
+        // the blocker's full list applies here too
+        assert_eq!(
+            rewrite(&c, "https://example.com/a?_ga=2.1&id=7&ScCid=x").as_deref(),
+            Some("https://example.com/a?id=7")
+        );
+        assert_eq!(rewrite(&c, "bushido://settings"), None);
+    }
+
+    #[test]
+    fn force_https_only_for_listed_hosts() {
+        let c = RedirectConfig { rules: vec![rule("example.org", RuleAction::ForceHttps)], strip_tracking: false };
+        assert_eq!(rewrite(&c, "http://example.org/a").as_deref(), Some("https://example.org/a"));
+        assert_eq!(rewrite(&c, "https://example.org/a"), None);
+        assert_eq!(rewrite(&c, "http://example.com/?utm_source=x"), None);
+    }
+
+    #[test]
+    fn cyclic_rules_are_ignored() {
+        let c = RedirectConfig {
+            rules: vec![
+                rule("a.com", RuleAction::ReplaceHost { to: "b.com".into() }),
+                rule("b.com", RuleAction::ReplaceHost { to: "a.com".into() }),
+            ],
+            strip_tracking: true,
+        };
+        assert_eq!(rewrite(&c, "https://a.com/"), None);
+        assert_eq!(rewrite(&c, "https://b.com/x"), None);
+    }
+
+    #[test]
+    fn loop_guard_stops_bouncing_tabs() {
+        let mut g = LoopGuard::default();
+        let t0 = Instant::now();
+        for _ in 0..MAX_REWRITES_PER_WINDOW {
+            assert!(g.allow("tab", t0));
+        }
+        assert!(!g.allow("tab", t0));
+        assert!(g.allow("other", t0));
+        assert!(g.allow("tab", t0 + LOOP_WINDOW));
+    }
+}
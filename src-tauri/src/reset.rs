@@ -0,0 +1,130 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::error::BushidoError;
+
+const SUBSYSTEMS: &[&str] = &["keybindings", "settings", "permissions", "whitelist", "blocker-lists"];
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResetSummary {
+    pub subsystem: String,
+    /// `.bak` copies of what was there before, for manual recovery
+    pub backups: Vec<String>,
+    pub detail: String,
+}
+
+/// Copy `path` to `path.bak` (overwriting an older backup). None when there was nothing to back up.
+fn backup(path: &Path) -> Result<Option<PathBuf>, BushidoError> {
+    if !path.exists() { return Ok(None); }
+    let bak = PathBuf::from(format!("{}.bak", path.display()));
+    fs::copy(path, &bak)?;
+    Ok(Some(bak))
+}
+
+fn read_settings(path: &Path) -> serde_json::Map<String, serde_json::Value> {
+    fs::read_to_string(path).ok()
+        .and_then(|s| serde_json::from_str::<serde_json::Value>(&s).ok())
+        .and_then(|v| match v { serde_json::Value::Object(m) => Some(m), _ => None })
+        .unwrap_or_default()
+}
+
+fn write_settings(path: &Path, settings: &serde_json::Map<String, serde_json::Value>) -> Result<(), BushidoError> {
+    let json = serde_json::to_string(settings).map_err(|e| BushidoError::Other(e.to_string()))?;
+    fs::write(path, json)?;
+    Ok(())
+}
+
+/// Unregister every global shortcut and register the shipped defaults
+fn reregister_default_shortcuts(app: &AppHandle) -> usize {
+    use tauri_plugin_global_shortcut::GlobalShortcutExt;
+    let gs = app.global_shortcut();
+    let _ = gs.unregister_all();
+
+    let kb = app.state::<crate::KeybindingState>();
+    let mut map = kb.map.lock();
+    map.clear();
+    let mut failed = 0;
+    for (action, combo) in crate::DEFAULT_KEYBINDINGS {
+        if let Err(e) = gs.register(*combo) {
+            crate::crash_log::log_warn("shortcuts", &format!("Failed to register '{}': {}", combo, e));
+            failed += 1;
+        }
+        map.insert(crate::normalize_combo(combo), action.to_string());
+    }
+    drop(map);
+    // unregister_all also dropped the media keys
+    crate::media_session::resync_keys(app);
+    failed
+}
+
+/// Restore one subsystem to its shipped defaults. The previous state is kept as `.bak`
+/// next to the original file; unrelated data is left alone.
+#[tauri::command]
+pub async fn reset_defaults(app: AppHandle, subsystem: String) -> Result<ResetSummary, BushidoError> {
+    let mut backups = Vec::new();
+    let detail = match subsystem.as_str() {
+        "keybindings" => {
+            let path = crate::settings_path(&app);
+            let mut settings = read_settings(&path);
+            if settings.remove("keybindings").is_some() {
+                backups.extend(backup(&path)?);
+                write_settings(&path, &settings)?;
+            }
+            let failed = reregister_default_shortcuts(&app);
+            if failed > 0 {
+                format!("restored {} default shortcuts ({} could not be registered)", crate::DEFAULT_KEYBINDINGS.len(), failed)
+            } else {
+                format!("restored {} default shortcuts", crate::DEFAULT_KEYBINDINGS.len())
+            }
+        }
+        "settings" => {
+            // keybindings have their own reset
+            let path = crate::settings_path(&app);
+            backups.extend(backup(&path)?);
+            let mut settings = read_settings(&path);
+            let before = settings.len();
+            // everything else falls back to the UI defaults on reload
+            settings.retain(|k, _| k == "keybindings");
+            write_settings(&path, &settings)?;
+            format!("cleared {} saved settings", before - settings.len())
+        }
+        "permissions" => {
+            backups.extend(backup(&crate::permissions::permissions_path(&app))?);
+            let n = app.state::<crate::permissions::PermissionState>().forget_all(&app);
+            format!("forgot {} site permission decisions", n)
+        }
+        "whitelist" => {
+            backups.extend(backup(&crate::whitelist_path(&app))?);
+            let ws = app.state::<crate::WhitelistState>();
+            let mut sites = ws.sites.lock();
+            let n = sites.len();
+            sites.clear();
+            crate::save_whitelist(&app, &sites);
+            format!("removed {} whitelisted sites", n)
+        }
+        "blocker-lists" => {
+            let (data_dir, engine) = {
+                let bs = app.state::<crate::BlockerState>();
+                (bs.data_dir.clone(), bs.engine.clone())
+            };
+            backups.extend(crate::blocker::reset_to_bundled(data_dir, engine).await?);
+            "rebuilt the blocker from the bundled filter lists".to_string()
+        }
+        other => {
+            return Err(BushidoError::NotFound(format!(
+                "unknown subsystem '{}' (expected one of: {})", other, SUBSYSTEMS.join(", ")
+            )));
+        }
+    };
+
+    crate::crash_log::log_info("reset", &format!("{}: {}", subsystem, detail));
+    let _ = app.emit_to("main", "defaults-reset", serde_json::json!({ "subsystem": subsystem }));
+    Ok(ResetSummary {
+        subsystem,
+        backups: backups.iter().map(|p| p.to_string_lossy().to_string()).collect(),
+        detail,
+    })
+}
@@ -144,7 +144,7 @@ pub async fn capture_preview_for_select(app: tauri::AppHandle, id: String) -> Re
 
 /// Helper: call a CDP method and return the JSON response string
 #[cfg(windows)]
-fn cdp_call(
+pub(crate) fn cdp_call(
     wv: &tauri::webview::PlatformWebview,
     method_name: &str,
     params_json: &str,
@@ -292,6 +292,136 @@ pub async fn capture_fullpage(app: tauri::AppHandle, id: String) -> Result<Strin
     extract_cdp_data(&json_str)
 }
 
+// element captures taller than this are taken in tiles and stitched, so the compositor
+// never has to rasterize one huge beyond-viewport surface
+const ELEMENT_TILE_HEIGHT: f64 = 4096.0;
+const ELEMENT_MAX_HEIGHT: f64 = 16384.0;
+
+#[derive(serde::Deserialize)]
+struct ElementRect {
+    x: f64,
+    y: f64,
+    w: f64,
+    h: f64,
+    dpr: f64,
+    // fixed/sticky (itself or an ancestor): only its on-screen position is meaningful
+    pinned: bool,
+}
+
+/// Scroll the first match into view and report its document-relative rect. Pinned elements
+/// are clamped to the viewport, since that's the only place they're painted.
+fn element_rect_script(selector: &str) -> String {
+    let sel = serde_json::to_string(selector).unwrap_or_else(|_| "\"\"".into());
+    format!(
+        r#"(function(){{var el;try{{el=document.querySelector({sel})}}catch(e){{return JSON.stringify({{error:'invalid selector'}})}}
+if(!el)return JSON.stringify({{error:'no match'}});
+var pinned=false;for(var n=el;n&&n.nodeType===1;n=n.parentElement){{var p=getComputedStyle(n).position;if(p==='fixed'||p==='sticky'){{pinned=true;break}}}}
+el.scrollIntoView({{block:'nearest',inline:'nearest'}});
+var r=el.getBoundingClientRect(),x=r.left,y=r.top,w=r.width,h=r.height;
+if(pinned){{x=Math.max(x,0);y=Math.max(y,0);w=Math.min(r.right,innerWidth)-x;h=Math.min(r.bottom,innerHeight)-y}}
+return JSON.stringify({{x:x+scrollX,y:y+scrollY,w:w,h:h,dpr:devicePixelRatio||1,pinned:pinned}})}})()"#
+    )
+}
+
+/// Page.captureScreenshot of one document-relative clip, decoded to PNG bytes.
+/// `beyond` lets the clip leave the viewport, at the cost of a relayout that moves fixed elements.
+#[cfg(windows)]
+fn capture_clip(wv: &tauri::Webview, x: f64, y: f64, w: f64, h: f64, scale: f64, beyond: bool) -> Result<Vec<u8>, String> {
+    use base64::Engine as _;
+    let (tx, rx) = mpsc::channel::<Result<String, String>>();
+    let params = serde_json::json!({
+        "format": "png",
+        "captureBeyondViewport": beyond,
+        "clip": { "x": x, "y": y, "width": w, "height": h, "scale": scale }
+    }).to_string();
+    wv.with_webview(move |wv| {
+        cdp_call(&wv, "Page.captureScreenshot", &params, tx);
+    }).map_err(|e| e.to_string())?;
+    let json_str = rx.recv_timeout(std::time::Duration::from_secs(10))
+        .map_err(|_| "Element capture timed out".to_string())??;
+    base64::engine::general_purpose::STANDARD.decode(extract_cdp_data(&json_str)?)
+        .map_err(|e| format!("Base64 decode: {}", e))
+}
+
+#[cfg(not(windows))]
+fn capture_clip(_wv: &tauri::Webview, _x: f64, _y: f64, _w: f64, _h: f64, _scale: f64, _beyond: bool) -> Result<Vec<u8>, String> {
+    Err("element capture is only available on Windows".into())
+}
+
+/// Stack PNG tiles vertically into one PNG
+fn stitch_vertical(tiles: &[Vec<u8>]) -> Result<Vec<u8>, String> {
+    let images = tiles.iter()
+        .map(|t| image::load_from_memory(t).map(|i| i.to_rgba8()))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Image decode: {}", e))?;
+    let width = images.iter().map(|i| i.width()).max().unwrap_or(0);
+    let height: u32 = images.iter().map(|i| i.height()).sum();
+    let mut out = image::RgbaImage::new(width, height);
+    let mut y = 0i64;
+    for img in &images {
+        image::imageops::replace(&mut out, img, 0, y);
+        y += img.height() as i64;
+    }
+    let mut buf = Vec::new();
+    image::ImageEncoder::write_image(
+        image::codecs::png::PngEncoder::new(&mut buf),
+        out.as_raw(),
+        width,
+        height,
+        image::ExtendedColorType::Rgba8,
+    ).map_err(|e| format!("PNG encode: {}", e))?;
+    Ok(buf)
+}
+
+/// Capture one element by CSS selector, as base64 PNG like the other captures.
+/// Elements taller than a tile are captured in strips and stitched.
+#[tauri::command]
+pub async fn capture_element(app: tauri::AppHandle, id: String, selector: String) -> Result<String, String> {
+    use base64::Engine as _;
+    use tauri::Manager;
+
+    crash_log::log_info("screenshot", &format!("capture_element id={} selector={}", id, selector));
+    if selector.trim().is_empty() {
+        return Err("Selector is empty".into());
+    }
+    let wv = app.get_webview(&id).ok_or_else(|| "webview not found".to_string())?;
+
+    let raw = crate::eval_with_result(&app, &id, &element_rect_script(&selector), std::time::Duration::from_secs(5)).await?;
+    let val: serde_json::Value = serde_json::from_str(&raw).map_err(|e| format!("Parse rect: {}", e))?;
+    if let Some(err) = val.get("error").and_then(|v| v.as_str()) {
+        return Err(match err {
+            "no match" => format!("No element matches '{}'", selector),
+            _ => format!("Invalid selector '{}'", selector),
+        });
+    }
+    let rect: ElementRect = serde_json::from_value(val).map_err(|e| format!("Parse rect: {}", e))?;
+    if rect.w < 1.0 || rect.h < 1.0 {
+        return Err(format!("Element '{}' has no visible size", selector));
+    }
+
+    // device pixels, so the image matches what's on screen; the cap stays in CSS px
+    let scale = rect.dpr.clamp(1.0, 4.0);
+    let height = rect.h.min(ELEMENT_MAX_HEIGHT);
+    let mut tiles = Vec::new();
+    if rect.pinned {
+        // already clamped to the viewport, and a beyond-viewport relayout would move it
+        tiles.push(capture_clip(&wv, rect.x, rect.y, rect.w, height, scale, false)?);
+    } else {
+        let mut offset = 0.0;
+        while offset < height {
+            let tile_h = (height - offset).min(ELEMENT_TILE_HEIGHT);
+            tiles.push(capture_clip(&wv, rect.x, rect.y + offset, rect.w, tile_h, scale, true)?);
+            offset += tile_h;
+        }
+    }
+    let count = tiles.len();
+    let png = if count == 1 { tiles.remove(0) } else { stitch_vertical(&tiles)? };
+
+    crash_log::log_info("screenshot", &format!("capture_element: {}x{} @{}x in {} tile(s) (capped: {}, pinned: {}), {} bytes",
+        rect.w, rect.h, scale, count, rect.h > ELEMENT_MAX_HEIGHT, rect.pinned, png.len()));
+    Ok(base64::engine::general_purpose::STANDARD.encode(&png))
+}
+
 /// Extract base64 "data" field from CDP JSON response
 fn extract_cdp_data(json_str: &str) -> Result<String, String> {
     let val: serde_json::Value = serde_json::from_str(json_str)
@@ -302,30 +432,87 @@ fn extract_cdp_data(json_str: &str) -> Result<String, String> {
         .ok_or_else(|| "No data in CDP response".to_string())
 }
 
-/// Save base64 PNG to Downloads directory
+const DEFAULT_JPEG_QUALITY: u32 = 90;
+
+/// Re-encode a PNG capture as `format`, returning the bytes and file extension.
+/// PNG and WebP (the image crate's WebP encoder is lossless only) reject a `quality`
+/// rather than ignore it; JPEG defaults to DEFAULT_JPEG_QUALITY.
+fn encode_for_save(png: Vec<u8>, format: &str, quality: Option<u32>) -> Result<(Vec<u8>, &'static str), String> {
+    let ext = match format {
+        "png" => "png",
+        "jpeg" => "jpg",
+        "webp" => "webp",
+        other => return Err(format!("Unsupported screenshot format '{}' (expected png, jpeg or webp)", other)),
+    };
+    if ext != "jpg" && quality.is_some() {
+        return Err(format!("{} screenshots are lossless; quality only applies to JPEG", format.to_uppercase()));
+    }
+    let quality = quality.unwrap_or(DEFAULT_JPEG_QUALITY);
+    if !(1..=100).contains(&quality) {
+        return Err(format!("Quality must be between 1 and 100, got {}", quality));
+    }
+    if ext == "png" {
+        return Ok((png, ext));
+    }
+    let img = image::load_from_memory(&png).map_err(|e| format!("Image decode: {}", e))?;
+    let mut buf = Vec::new();
+    if ext == "jpg" {
+        // no alpha channel in JPEG
+        let rgb = img.to_rgb8();
+        image::codecs::jpeg::JpegEncoder::new_with_quality(&mut buf, quality as u8)
+            .encode_image(&rgb)
+            .map_err(|e| format!("JPEG encode: {}", e))?;
+    } else {
+        let rgba = img.to_rgba8();
+        image::ImageEncoder::write_image(
+            image::codecs::webp::WebPEncoder::new_lossless(&mut buf),
+            rgba.as_raw(),
+            rgba.width(),
+            rgba.height(),
+            image::ExtendedColorType::Rgba8,
+        ).map_err(|e| format!("WebP encode: {}", e))?;
+    }
+    Ok((buf, ext))
+}
+
+/// Keep the extension honest about the encoding. Only a trailing image extension is
+/// swapped; anything else (e.g. "example.com") stays part of the name.
+fn save_name(name: &str, ext: &str) -> String {
+    let stem = match name.rsplit_once('.') {
+        Some((stem, old)) if !stem.is_empty()
+            && ["png", "jpg", "jpeg", "webp"].iter().any(|e| old.eq_ignore_ascii_case(e)) => stem,
+        _ => name,
+    };
+    format!("{}.{}", stem, ext)
+}
+
+/// Save a base64 PNG capture to the Downloads directory as PNG (default), JPEG or WebP.
+/// `quality` (1-100) is for JPEG only; PNG and WebP are lossless and reject it.
 #[tauri::command]
-pub async fn save_screenshot(data: String, suggested_name: String) -> Result<String, String> {
+pub async fn save_screenshot(data: String, suggested_name: String, format: Option<String>, quality: Option<u32>) -> Result<String, String> {
     use base64::Engine as _;
 
-    crash_log::log_info("screenshot", &format!("save_screenshot called, data_len={} name={}", data.len(), suggested_name));
+    let format = format.unwrap_or_else(|| "png".into()).to_lowercase();
+    crash_log::log_info("screenshot", &format!("save_screenshot called, data_len={} name={} format={}", data.len(), suggested_name, format));
 
-    let bytes = base64::engine::general_purpose::STANDARD.decode(&data)
+    let png = base64::engine::general_purpose::STANDARD.decode(&data)
         .map_err(|e| {
             crash_log::log_error("screenshot", &format!("save base64 decode failed: {}", e));
             format!("Base64 decode: {}", e)
         })?;
+    let (bytes, ext) = encode_for_save(png, &format, quality)?;
 
     let download_dir = dirs::download_dir()
         .unwrap_or_else(|| std::path::PathBuf::from("."));
 
     let name = if suggested_name.is_empty() {
         let now = chrono::Local::now();
-        format!("Bushido Screenshot {}.png", now.format("%Y-%m-%d %H%M%S"))
+        format!("Bushido Screenshot {}.{}", now.format("%Y-%m-%d %H%M%S"), ext)
     } else {
         suggested_name
     };
 
-    let path = download_dir.join(&name);
+    let path = download_dir.join(save_name(&name, ext));
 
     let final_path = if path.exists() {
         let stem = path.file_stem().unwrap_or_default().to_string_lossy().to_string();
@@ -428,3 +615,74 @@ pub async fn generate_qr_code(url: String) -> Result<String, String> {
 
     Ok(base64::engine::general_purpose::STANDARD.encode(&buf))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A noisy RGBA PNG, so JPEG quality visibly changes the output size
+    fn sample_png() -> Vec<u8> {
+        let img = image::RgbaImage::from_fn(64, 64, |x, y| {
+            let v = (x * 31 + y * 17 + x * y) as u8;
+            image::Rgba([v, v.wrapping_mul(3), v ^ 0x5a, 200])
+        });
+        let mut buf = Vec::new();
+        image::ImageEncoder::write_image(
+            image::codecs::png::PngEncoder::new(&mut buf),
+            img.as_raw(),
+            64,
+            64,
+            image::ExtendedColorType::Rgba8,
+        ).unwrap();
+        buf
+    }
+
+    #[test]
+    fn png_passes_through() {
+        let png = sample_png();
+        let (bytes, ext) = encode_for_save(png.clone(), "png", None).unwrap();
+        assert_eq!(ext, "png");
+        assert_eq!(bytes, png);
+    }
+
+    #[test]
+    fn jpeg_drops_alpha_and_decodes() {
+        let (bytes, ext) = encode_for_save(sample_png(), "jpeg", None).unwrap();
+        assert_eq!(ext, "jpg");
+        assert_eq!(&bytes[..2], &[0xFF, 0xD8]);
+        let img = image::load_from_memory(&bytes).unwrap();
+        assert_eq!((img.width(), img.height()), (64, 64));
+        assert!(!img.color().has_alpha());
+    }
+
+    #[test]
+    fn webp_is_lossless() {
+        let png = sample_png();
+        assert!(encode_for_save(png.clone(), "webp", Some(10)).is_err());
+        let (bytes, ext) = encode_for_save(png.clone(), "webp", None).unwrap();
+        assert_eq!(ext, "webp");
+        assert_eq!((&bytes[..4], &bytes[8..12]), (&b"RIFF"[..], &b"WEBP"[..]));
+        let back = image::load_from_memory(&bytes).unwrap().to_rgba8();
+        assert_eq!(back, image::load_from_memory(&png).unwrap().to_rgba8());
+    }
+
+    #[test]
+    fn jpeg_quality_is_applied_and_checked() {
+        let (low, _) = encode_for_save(sample_png(), "jpeg", Some(10)).unwrap();
+        let (high, _) = encode_for_save(sample_png(), "jpeg", Some(100)).unwrap();
+        assert!(low.len() < high.len());
+        assert!(encode_for_save(sample_png(), "jpeg", Some(0)).is_err());
+        assert!(encode_for_save(sample_png(), "jpeg", Some(101)).is_err());
+        assert!(encode_for_save(sample_png(), "gif", None).is_err());
+        assert!(encode_for_save(sample_png(), "png", Some(90)).is_err());
+    }
+
+    #[test]
+    fn save_name_keeps_dotted_names() {
+        assert_eq!(save_name("www.example.com", "jpg"), "www.example.com.jpg");
+        assert_eq!(save_name("v1.2 notes", "png"), "v1.2 notes.png");
+        assert_eq!(save_name("shot.png", "jpg"), "shot.jpg");
+        assert_eq!(save_name("shot.JPEG", "webp"), "shot.webp");
+        assert_eq!(save_name(".png", "png"), ".png.png");
+    }
+}
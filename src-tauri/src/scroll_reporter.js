@@ -0,0 +1,49 @@
+(function() {
+  if (window.__bushidoScroll || window.top !== window) return;
+  window.__bushidoScroll = true;
+  if (!(window.chrome && window.chrome.webview)) return;
+
+  var timer = null;
+  var lastX = -1, lastY = -1;
+
+  // short selector for the element at the top of the viewport: an id if there is
+  // one nearby, otherwise a nth-of-type path under the closest id'd ancestor
+  function anchorFor(el) {
+    var parts = [];
+    while (el && el.nodeType === 1 && el !== document.body && el !== document.documentElement && parts.length < 8) {
+      if (el.id && /^[A-Za-z][\w-]*$/.test(el.id)) {
+        parts.unshift('#' + el.id);
+        return parts.join('>');
+      }
+      var tag = el.tagName.toLowerCase(), n = 1;
+      for (var s = el.previousElementSibling; s; s = s.previousElementSibling) {
+        if (s.tagName === el.tagName) n++;
+      }
+      parts.unshift(tag + ':nth-of-type(' + n + ')');
+      el = el.parentElement;
+    }
+    return parts.length ? 'body>' + parts.join('>') : '';
+  }
+
+  function report() {
+    timer = null;
+    var x = Math.round(window.scrollX), y = Math.round(window.scrollY);
+    if (x === lastX && y === lastY) return;
+    lastX = x; lastY = y;
+    var msg = { __bushido: 'scroll', x: x, y: y, url: location.href };
+    if (y > 0) {
+      var el = document.elementFromPoint(window.innerWidth / 2, 1);
+      var sel = el ? anchorFor(el) : '';
+      if (sel) {
+        msg.anchor = sel;
+        msg.anchorOffset = Math.round(el.getBoundingClientRect().top);
+      }
+    }
+    try { window.chrome.webview.postMessage(JSON.stringify(msg)); } catch (e) {}
+  }
+
+  window.addEventListener('scroll', function() {
+    if (timer) clearTimeout(timer);
+    timer = setTimeout(report, 800);
+  }, { passive: true });
+})();
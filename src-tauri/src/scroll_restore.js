@@ -0,0 +1,37 @@
+(function() {
+  if (window.__bushidoScrollRestore) return;
+  // lazy pages grow after load; keep trying for about three seconds
+  var RETRIES = 10, DELAY = 300;
+
+  window.__bushidoScrollRestore = function(p) {
+    if (window.top !== window) return;
+    var cancelled = false, tries = 0;
+    // once the user scrolls themselves the saved position no longer matters
+    ['wheel', 'keydown', 'touchstart', 'mousedown'].forEach(function(t) {
+      window.addEventListener(t, function() { cancelled = true; }, { once: true, passive: true, capture: true });
+    });
+
+    function byAnchor() {
+      if (!p.anchor) return false;
+      var el = null;
+      try { el = document.querySelector(p.anchor); } catch (e) {}
+      if (!el) return false;
+      el.scrollIntoView({ block: 'start', inline: 'nearest' });
+      window.scrollBy(0, -(p.anchorOffset || 0));
+      return true;
+    }
+
+    // an id-rooted anchor survives content shifting above it, so it wins; then
+    // the plain offset; a positional anchor is the fallback for pages (feeds,
+    // lazy lists) that never grow tall enough to reach the offset again
+    function attempt() {
+      if (cancelled) return;
+      if (p.anchor && p.anchor.charAt(0) === '#' && byAnchor()) return;
+      window.scrollTo(p.x, p.y);
+      if (Math.abs(window.scrollY - p.y) <= 2) return;
+      if (++tries < RETRIES) { setTimeout(attempt, DELAY); return; }
+      byAnchor();
+    }
+    attempt();
+  };
+})();
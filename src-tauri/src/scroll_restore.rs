@@ -0,0 +1,214 @@
+use std::collections::HashMap;
+use std::fs;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+pub const SCROLL_REPORTER_SCRIPT: &str = include_str!("scroll_reporter.js");
+const RESTORE_SCRIPT: &str = include_str!("scroll_restore.js");
+// scroll alone never makes the UI save the session, so positions are written back on their own
+const FLUSH_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Where a tab was scrolled to. `anchor` is a selector for the element at the top
+/// of the viewport and `anchor_offset` its distance from the top edge.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScrollPos {
+    pub url: String,
+    pub x: f64,
+    pub y: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub anchor: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub anchor_offset: Option<f64>,
+}
+
+/// Scroll positions reported by each tab, and the ones a restored session still
+/// has to put back once the tab's page finishes loading.
+pub struct ScrollState {
+    positions: Mutex<HashMap<String, ScrollPos>>,
+    pending: Mutex<HashMap<String, ScrollPos>>,
+    dirty: AtomicBool,
+}
+
+fn same_page(a: &str, b: &str) -> bool {
+    match (crate::normalize_tab_url(a), crate::normalize_tab_url(b)) {
+        (Some(a), Some(b)) => a == b,
+        _ => false,
+    }
+}
+
+impl ScrollState {
+    pub fn new() -> Self {
+        Self {
+            positions: Mutex::new(HashMap::new()),
+            pending: Mutex::new(HashMap::new()),
+            dirty: AtomicBool::new(false),
+        }
+    }
+
+    pub fn record(&self, tab_id: &str, pos: ScrollPos) {
+        let mut positions = self.positions.lock();
+        if positions.get(tab_id) != Some(&pos) {
+            positions.insert(tab_id.to_string(), pos);
+            self.dirty.store(true, Ordering::Relaxed);
+        }
+    }
+
+    pub fn forget_tab(&self, tab_id: &str) {
+        self.positions.lock().remove(tab_id);
+        self.pending.lock().remove(tab_id);
+    }
+
+    pub fn position(&self, tab_id: &str) -> Option<ScrollPos> {
+        self.positions.lock().get(tab_id).cloned()
+    }
+
+    /// The tab's webview is being recreated under the same id: put its last position
+    /// back once the new one has loaded the page
+    pub fn carry_over(&self, tab_id: &str) {
+        if let Some(pos) = self.position(tab_id) {
+            self.pending.lock().insert(tab_id.to_string(), pos);
+        }
+    }
+
+    /// Put each tab's last position on its session entry, as `scroll`. A position
+    /// for a different page than the entry's URL is left off.
+    pub fn stamp_session(&self, tabs: &mut [serde_json::Value]) {
+        let positions = self.positions.lock();
+        for tab in tabs.iter_mut() {
+            let Some(obj) = tab.as_object_mut() else { continue };
+            let pos = obj.get("id").and_then(|v| v.as_str())
+                .and_then(|id| positions.get(id))
+                .filter(|p| obj.get("url").and_then(|v| v.as_str()).is_some_and(|u| same_page(u, &p.url)));
+            match pos.and_then(|p| serde_json::to_value(p).ok()) {
+                Some(v) => { obj.insert("scroll".into(), v); }
+                None => { obj.remove("scroll"); }
+            }
+        }
+    }
+
+    /// Remember the positions saved with a session; each is applied the first time
+    /// its tab finishes loading. They also count as reported, so tabs that stay
+    /// unloaded keep them across the next save.
+    pub fn restore_from_session(&self, tabs: &[serde_json::Value]) {
+        let restored: HashMap<String, ScrollPos> = tabs.iter()
+            .filter_map(|t| {
+                let id = t.get("id")?.as_str()?;
+                let pos = serde_json::from_value::<ScrollPos>(t.get("scroll")?.clone()).ok()?;
+                Some((id.to_string(), pos))
+            })
+            .collect();
+        *self.positions.lock() = restored.clone();
+        *self.pending.lock() = restored;
+    }
+
+    /// Restore script for a tab that just finished loading `url`. The pending
+    /// position is consumed either way: once the tab has shown another page
+    /// it no longer applies.
+    pub fn take_restore_script(&self, tab_id: &str, url: &str) -> Option<String> {
+        let pos = self.pending.lock().remove(tab_id)?;
+        if !same_page(url, &pos.url) || (pos.y <= 0.0 && pos.x <= 0.0) {
+            return None;
+        }
+        Some(restore_script(&pos))
+    }
+}
+
+fn restore_script(pos: &ScrollPos) -> String {
+    let arg = serde_json::json!({
+        "x": pos.x,
+        "y": pos.y,
+        "anchor": pos.anchor,
+        "anchorOffset": pos.anchor_offset,
+    });
+    format!("{}\nwindow.__bushidoScrollRestore({});", RESTORE_SCRIPT, arg)
+}
+
+/// Rewrite the `scroll` fields in session.json; the rest of the file is left as the UI saved it
+fn flush(app: &AppHandle) {
+    let ss = app.state::<ScrollState>();
+    if !ss.dirty.swap(false, Ordering::Relaxed) {
+        return;
+    }
+    let _guard = crate::SESSION_FILE_LOCK.lock();
+    let path = crate::session_path(app);
+    let Some(mut session) = fs::read_to_string(&path).ok()
+        .and_then(|s| serde_json::from_str::<serde_json::Value>(&s).ok())
+    else { return };
+    let Some(list) = session.get_mut("tabs").and_then(|t| t.as_array_mut()) else { return };
+    ss.stamp_session(list);
+    if let Err(e) = fs::write(&path, session.to_string()) {
+        crate::crash_log::log_warn("scroll_restore", &format!("could not save scroll positions: {}", e));
+    }
+}
+
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(FLUSH_INTERVAL);
+        loop {
+            interval.tick().await;
+            flush(&app);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pos(url: &str, y: f64) -> ScrollPos {
+        ScrollPos { url: url.into(), x: 0.0, y, anchor: Some("#main>p:nth-of-type(3)".into()), anchor_offset: Some(-12.0) }
+    }
+
+    #[test]
+    fn stamps_only_positions_for_the_saved_page() {
+        let state = ScrollState::new();
+        state.record("tab-1", pos("https://example.com/a#section", 800.0));
+        state.record("tab-2", pos("https://example.com/old", 300.0));
+        let mut tabs = vec![
+            serde_json::json!({"id": "tab-1", "url": "https://example.com/a"}),
+            serde_json::json!({"id": "tab-2", "url": "https://example.com/new", "scroll": {"url": "x", "x": 0, "y": 1}}),
+            serde_json::json!({"id": "tab-3", "url": "https://example.com/"}),
+        ];
+        state.stamp_session(&mut tabs);
+        assert_eq!(tabs[0]["scroll"]["y"], 800.0);
+        assert_eq!(tabs[0]["scroll"]["anchorOffset"], -12.0);
+        assert!(tabs[1].get("scroll").is_none());
+        assert!(tabs[2].get("scroll").is_none());
+    }
+
+    #[test]
+    fn restores_once_and_only_on_the_same_page() {
+        let state = ScrollState::new();
+        let tabs = vec![
+            serde_json::json!({"id": "tab-1", "url": "https://example.com/a", "scroll": pos("https://example.com/a", 800.0)}),
+            serde_json::json!({"id": "tab-2", "url": "https://example.com/b", "scroll": pos("https://example.com/b", 50.0)}),
+            serde_json::json!({"id": "tab-3", "url": "https://example.com/c", "scroll": "junk"}),
+        ];
+        state.restore_from_session(&tabs);
+        let script = state.take_restore_script("tab-1", "https://example.com/a/").unwrap();
+        assert!(script.ends_with(r##"window.__bushidoScrollRestore({"anchor":"#main>p:nth-of-type(3)","anchorOffset":-12.0,"x":0.0,"y":800.0});"##));
+        assert_eq!(state.take_restore_script("tab-1", "https://example.com/a"), None);
+        assert_eq!(state.take_restore_script("tab-2", "https://example.com/elsewhere"), None);
+        assert_eq!(state.take_restore_script("tab-2", "https://example.com/b"), None);
+        assert_eq!(state.take_restore_script("tab-3", "https://example.com/c"), None);
+        // restored positions survive a save before the tab is ever loaded
+        let mut saved = vec![serde_json::json!({"id": "tab-2", "url": "https://example.com/b"})];
+        state.stamp_session(&mut saved);
+        assert_eq!(saved[0]["scroll"]["y"], 50.0);
+    }
+
+    #[test]
+    fn recreated_webviews_get_their_position_back() {
+        let state = ScrollState::new();
+        state.carry_over("tab-1");
+        assert_eq!(state.take_restore_script("tab-1", "https://example.com/a"), None);
+        state.record("tab-1", pos("https://example.com/a", 300.0));
+        state.carry_over("tab-1");
+        assert!(state.take_restore_script("tab-1", "https://example.com/a").is_some());
+        assert!(state.position("tab-1").is_some());
+    }
+}
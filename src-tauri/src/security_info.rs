@@ -0,0 +1,257 @@
+use std::collections::HashMap;
+use parking_lot::Mutex;
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::error::BushidoError;
+
+// document responses held per tab until their navigation completes (redirect chains, frames)
+const MAX_PENDING: usize = 16;
+
+/// The server certificate a page was loaded with, from the DevTools `securityDetails`
+#[derive(Debug, Clone, PartialEq)]
+pub struct CertDetails {
+    pub subject: String,
+    pub issuer: String,
+    /// unix ms
+    pub valid_from: u64,
+    pub valid_to: u64,
+}
+
+/// What the padlock panel shows for a tab
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecurityInfo {
+    pub url: String,
+    pub is_secure: bool,
+    pub subject: Option<String>,
+    pub issuer: Option<String>,
+    pub valid_from: Option<u64>,
+    pub valid_to: Option<u64>,
+}
+
+#[derive(Default)]
+struct TabCerts {
+    /// response URL → certificate, oldest first
+    pending: Vec<(String, CertDetails)>,
+    /// the committed page's URL and certificate
+    current: Option<(String, CertDetails)>,
+}
+
+/// Certificates of the pages open in each tab. Filled on Windows from WebView2's
+/// DevTools events; elsewhere only the scheme is known.
+pub struct SecurityInfoState {
+    tabs: Mutex<HashMap<String, TabCerts>>,
+}
+
+/// URL and certificate from a `Network.responseReceived` payload, for top-level
+/// document responses served over TLS only
+pub fn parse_response_received(json: &str) -> Option<(String, CertDetails)> {
+    let v: serde_json::Value = serde_json::from_str(json).ok()?;
+    if v.get("type").and_then(|t| t.as_str()) != Some("Document") {
+        return None;
+    }
+    let response = v.get("response")?;
+    let url = response.get("url")?.as_str()?.to_string();
+    let details = response.get("securityDetails")?;
+    // CDP times are seconds since the epoch
+    let secs = |key: &str| details.get(key).and_then(|t| t.as_f64()).map(|s| (s * 1000.0).max(0.0) as u64);
+    Some((url, CertDetails {
+        subject: details.get("subjectName")?.as_str()?.to_string(),
+        issuer: details.get("issuer")?.as_str()?.to_string(),
+        valid_from: secs("validFrom")?,
+        valid_to: secs("validTo")?,
+    }))
+}
+
+/// The info for `url` given the certificate seen for it, if any. Plain http, and
+/// anything that isn't https, is never secure.
+pub fn info_for(url: &str, cert: Option<&CertDetails>) -> SecurityInfo {
+    let is_secure = url::Url::parse(url).map(|u| u.scheme() == "https").unwrap_or(false);
+    let cert = cert.filter(|_| is_secure);
+    SecurityInfo {
+        url: url.to_string(),
+        is_secure,
+        subject: cert.map(|c| c.subject.clone()),
+        issuer: cert.map(|c| c.issuer.clone()),
+        valid_from: cert.map(|c| c.valid_from),
+        valid_to: cert.map(|c| c.valid_to),
+    }
+}
+
+impl SecurityInfoState {
+    pub fn new() -> Self {
+        Self { tabs: Mutex::new(HashMap::new()) }
+    }
+
+    /// A document response arrived; keep its certificate until the navigation commits
+    pub fn record_response(&self, tab_id: &str, url: String, cert: CertDetails) {
+        let mut tabs = self.tabs.lock();
+        let pending = &mut tabs.entry(tab_id.to_string()).or_default().pending;
+        pending.retain(|(u, _)| *u != url);
+        pending.push((url, cert));
+        if pending.len() > MAX_PENDING {
+            pending.remove(0);
+        }
+    }
+
+    /// The tab finished loading `url`: its certificate, if one was seen, becomes current
+    pub fn commit(&self, tab_id: &str, url: &str) {
+        let mut tabs = self.tabs.lock();
+        let entry = tabs.entry(tab_id.to_string()).or_default();
+        let cert = entry.pending.iter().rev().find(|(u, _)| u == url).map(|(_, c)| c.clone());
+        entry.pending.clear();
+        entry.current = cert.map(|c| (url.to_string(), c));
+    }
+
+    /// Certificate for the tab's page, if it's still the one at `url`
+    fn current(&self, tab_id: &str, url: &str) -> Option<CertDetails> {
+        let tabs = self.tabs.lock();
+        let (u, cert) = tabs.get(tab_id)?.current.as_ref()?;
+        // same-document navigations (fragments, pushState) keep the connection
+        let origin = |s: &str| url::Url::parse(s).ok().map(|u| u.origin());
+        (origin(u).is_some() && origin(u) == origin(url)).then(|| cert.clone())
+    }
+
+    pub fn forget_tab(&self, tab_id: &str) {
+        self.tabs.lock().remove(tab_id);
+    }
+}
+
+/// Watch the tab's document responses and completed navigations for certificates
+#[cfg(windows)]
+pub fn attach(app: &AppHandle, tab_id: &str, core: &webview2_com::Microsoft::Web::WebView2::Win32::ICoreWebView2) {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+    use webview2_com::Microsoft::Web::WebView2::Win32::*;
+
+    let read_pw = |pw: windows::core::PWSTR| -> String {
+        if !pw.is_null() { unsafe { pw.to_string().unwrap_or_default() } } else { String::new() }
+    };
+
+    unsafe {
+        let event: Vec<u16> = "Network.responseReceived".encode_utf16().chain(std::iter::once(0)).collect();
+        if let Ok(receiver) = core.GetDevToolsProtocolEventReceiver(windows::core::PCWSTR::from_raw(event.as_ptr())) {
+            let app_resp = app.clone();
+            let tab_resp = tab_id.to_string();
+            let handler = webview2_com::DevToolsProtocolEventReceivedEventHandler::create(Box::new(
+                move |_sender, args: Option<ICoreWebView2DevToolsProtocolEventReceivedEventArgs>| {
+                    let args_ref = AssertUnwindSafe(&args);
+                    let app_ref = AssertUnwindSafe(&app_resp);
+                    let tab_ref = AssertUnwindSafe(&tab_resp);
+                    let _ = catch_unwind(move || {
+                        let Some(args) = args_ref.as_ref() else { return };
+                        let mut pw = windows::core::PWSTR::null();
+                        let _ = args.ParameterObjectAsJson(&mut pw);
+                        let json = read_pw(pw);
+                        // every subresource comes through here; skip parsing all but documents
+                        if !json.contains("\"Document\"") || !json.contains("securityDetails") {
+                            return;
+                        }
+                        if let Some((url, cert)) = parse_response_received(&json) {
+                            app_ref.state::<SecurityInfoState>().record_response(&tab_ref, url, cert);
+                        }
+                    });
+                    Ok(())
+                },
+            ));
+            let mut token: i64 = 0;
+            let _ = receiver.add_DevToolsProtocolEventReceived(&handler, &mut token);
+
+            // events only flow once the Network domain is on
+            let method: Vec<u16> = "Network.enable".encode_utf16().chain(std::iter::once(0)).collect();
+            let params: Vec<u16> = "{}".encode_utf16().chain(std::iter::once(0)).collect();
+            let done = webview2_com::CallDevToolsProtocolMethodCompletedHandler::create(Box::new(|_, _| Ok(())));
+            let _ = core.CallDevToolsProtocolMethod(
+                windows::core::PCWSTR::from_raw(method.as_ptr()),
+                windows::core::PCWSTR::from_raw(params.as_ptr()),
+                &done,
+            );
+        }
+
+        let app_nav = app.clone();
+        let tab_nav = tab_id.to_string();
+        let nav_handler = webview2_com::NavigationCompletedEventHandler::create(Box::new(
+            move |sender: Option<ICoreWebView2>, _args| {
+                let sender_ref = AssertUnwindSafe(&sender);
+                let app_ref = AssertUnwindSafe(&app_nav);
+                let tab_ref = AssertUnwindSafe(&tab_nav);
+                let _ = catch_unwind(move || {
+                    let Some(wv) = sender_ref.as_ref() else { return };
+                    let mut pw = windows::core::PWSTR::null();
+                    let _ = wv.Source(&mut pw);
+                    app_ref.state::<SecurityInfoState>().commit(&tab_ref, &read_pw(pw));
+                });
+                Ok(())
+            },
+        ));
+        let mut nav_token: i64 = 0;
+        let _ = core.add_NavigationCompleted(&nav_handler, &mut nav_token);
+    }
+}
+
+/// TLS details of the page in tab `id`. Certificate fields are None off Windows,
+/// for http pages, and until the page has finished loading.
+#[tauri::command]
+pub async fn get_security_info(app: AppHandle, id: String) -> Result<SecurityInfo, BushidoError> {
+    let url = app.state::<crate::UrlState>().tabs.lock().get(&id).cloned()
+        .ok_or_else(|| BushidoError::NotFound(format!("tab {}", id)))?;
+    let cert = app.state::<SecurityInfoState>().current(&id, &url);
+    Ok(info_for(&url, cert.as_ref()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cert(subject: &str) -> CertDetails {
+        CertDetails { subject: subject.into(), issuer: "R3".into(), valid_from: 1_000, valid_to: 2_000 }
+    }
+
+    #[test]
+    fn reads_document_responses_only() {
+        let doc = r#"{"requestId":"1","type":"Document","response":{"url":"https://example.com/","securityDetails":{"subjectName":"example.com","issuer":"R3","validFrom":1700000000,"validTo":1707776000.5}}}"#;
+        let (url, c) = parse_response_received(doc).unwrap();
+        assert_eq!(url, "https://example.com/");
+        assert_eq!(c.subject, "example.com");
+        assert_eq!(c.valid_from, 1_700_000_000_000);
+        assert_eq!(c.valid_to, 1_707_776_000_500);
+
+        let script = doc.replace("\"Document\"", "\"Script\"");
+        assert!(parse_response_received(&script).is_none());
+        let plain = r#"{"type":"Document","response":{"url":"http://example.com/"}}"#;
+        assert!(parse_response_received(plain).is_none());
+        assert!(parse_response_received("not json").is_none());
+    }
+
+    #[test]
+    fn commits_the_certificate_for_the_loaded_url() {
+        let s = SecurityInfoState::new();
+        s.record_response("t", "https://a.example/".into(), cert("a.example"));
+        s.record_response("t", "https://b.example/".into(), cert("b.example"));
+        s.commit("t", "https://b.example/");
+        assert_eq!(s.current("t", "https://b.example/#top").unwrap().subject, "b.example");
+        assert_eq!(s.current("t", "https://b.example/other").unwrap().subject, "b.example");
+        assert!(s.current("t", "https://a.example/").is_none());
+
+        // a page that loaded without a certificate clears the old one
+        s.commit("t", "https://c.example/");
+        assert!(s.current("t", "https://b.example/").is_none());
+        s.forget_tab("t");
+        assert!(s.tabs.lock().is_empty());
+    }
+
+    #[test]
+    fn only_https_is_secure() {
+        let c = cert("example.com");
+        let http = info_for("http://example.com/", Some(&c));
+        assert!(!http.is_secure);
+        assert_eq!(http.issuer, None);
+        assert!(!info_for("bushido://newtab", None).is_secure);
+
+        let https = info_for("https://example.com/", Some(&c));
+        assert!(https.is_secure);
+        assert_eq!(https.subject.as_deref(), Some("example.com"));
+        assert_eq!(https.valid_to, Some(2_000));
+        assert!(info_for("https://example.com/", None).subject.is_none());
+    }
+}
@@ -13,6 +13,8 @@
     else if (e.ctrlKey && !e.shiftKey && e.code === 'KeyH') action = 'history';
     else if (e.ctrlKey && !e.shiftKey && e.code === 'KeyK') action = 'command-palette';
     else if (e.ctrlKey && e.shiftKey && e.code === 'KeyR') action = 'reader-mode';
+    // tab cycling lives here, not in global_hotkey — see the shortcut arm in create_tab
+    else if (e.ctrlKey && e.code === 'Tab') action = e.shiftKey ? 'prev-tab' : 'next-tab';
     if (action) {
       e.preventDefault();
       e.stopImmediatePropagation();
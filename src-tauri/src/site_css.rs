@@ -0,0 +1,180 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use parking_lot::Mutex;
+use tauri::{AppHandle, Manager};
+
+use crate::error::BushidoError;
+
+const MAX_SITES: usize = 500;
+const MAX_CSS_LEN: usize = 100 * 1024;
+const STYLE_ID: &str = "bushido-site-css";
+
+/// User stylesheets per domain, injected like the cosmetic filters but never
+/// gated on the blocker.
+pub struct SiteCssState {
+    /// domain → CSS, persisted in site_css.json
+    sites: Mutex<HashMap<String, String>>,
+}
+
+fn sites_path(app: &AppHandle) -> PathBuf {
+    crate::data_dir(app).join("site_css.json")
+}
+
+fn save_sites(app: &AppHandle, sites: &HashMap<String, String>) {
+    if let Ok(json) = serde_json::to_string(sites) {
+        let _ = fs::write(sites_path(app), json);
+    }
+}
+
+fn normalize_domain(domain: &str) -> Result<String, BushidoError> {
+    let domain = domain.trim().trim_start_matches("www.").to_lowercase();
+    if domain.is_empty() { return Err(BushidoError::Other("empty domain".into())); }
+    Ok(domain)
+}
+
+/// The CSS only ever reaches the page as a `<style>` element's text, but a stray
+/// `</style>` would still end the element if the page ever serializes and re-parses
+/// it. `<\/style` is the same thing to the CSS parser.
+fn escape_css(css: &str) -> String {
+    let mut out = String::with_capacity(css.len());
+    let mut rest = css;
+    while let Some(i) = rest.to_ascii_lowercase().find("</style") {
+        out.push_str(&rest[..i]);
+        out.push_str("<\\/");
+        out.push_str(&rest[i + 2..i + 7]);
+        rest = &rest[i + 7..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Replaces this tab's user style; empty CSS just removes it.
+fn style_script(css: &str) -> String {
+    format!(
+        "(function(){{var o=document.getElementById('{id}');if(o)o.remove();var c={css};if(!c)return;var s=document.createElement('style');s.id='{id}';s.textContent=c;(document.head||document.documentElement).appendChild(s)}})();",
+        id = STYLE_ID,
+        css = serde_json::to_string(&escape_css(css)).unwrap_or_else(|_| "''".into()),
+    )
+}
+
+impl SiteCssState {
+    pub fn load(app: &AppHandle) -> Self {
+        let sites: HashMap<String, String> = fs::read_to_string(sites_path(app)).ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default();
+        Self { sites: Mutex::new(sites) }
+    }
+
+    /// Every rule matching the URL's host, broadest domain first so the more
+    /// specific sheet wins the cascade. None when nothing applies.
+    fn css_for_url(&self, url: &str) -> Option<String> {
+        let host = url::Url::parse(url).ok()?.host_str()?.to_lowercase();
+        let sites = self.sites.lock();
+        let mut matching: Vec<(&String, &String)> = sites.iter()
+            .filter(|(d, _)| crate::domain_matches(&host, d))
+            .collect();
+        if matching.is_empty() {
+            return None;
+        }
+        matching.sort_by_key(|(d, _)| d.len());
+        Some(matching.into_iter().map(|(_, css)| css.as_str()).collect::<Vec<_>>().join("\n"))
+    }
+
+    /// Script for on_page_load; None when the site has no user CSS.
+    pub fn script_for_url(&self, url: &str) -> Option<String> {
+        self.css_for_url(url).map(|css| style_script(&css))
+    }
+}
+
+/// Re-style open tabs on `domain` so edits show without a reload
+fn reapply(app: &AppHandle, domain: &str) {
+    let scs = app.state::<SiteCssState>();
+    let tabs: Vec<String> = app.state::<crate::WebviewState>().tabs.lock().keys().cloned().collect();
+    for id in tabs {
+        let Some(wv) = app.get_webview(&id) else { continue };
+        let Ok(url) = wv.url() else { continue };
+        let on_site = url.host_str().is_some_and(|h| crate::domain_matches(&h.to_lowercase(), domain));
+        if on_site {
+            let css = scs.css_for_url(url.as_str()).unwrap_or_default();
+            let _ = wv.eval(&style_script(&css));
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn set_site_css(app: AppHandle, domain: String, css: String) -> Result<(), BushidoError> {
+    let domain = normalize_domain(&domain)?;
+    if css.len() > MAX_CSS_LEN {
+        return Err(BushidoError::Limit(format!("site CSS is limited to {} KB", MAX_CSS_LEN / 1024)));
+    }
+    if css.trim().is_empty() {
+        return clear_site_css(app, domain).await;
+    }
+    let snapshot = {
+        let scs = app.state::<SiteCssState>();
+        let mut sites = scs.sites.lock();
+        if !sites.contains_key(&domain) && sites.len() >= MAX_SITES {
+            return Err(BushidoError::Limit(format!("at most {} sites with custom CSS", MAX_SITES)));
+        }
+        sites.insert(domain.clone(), css);
+        sites.clone()
+    };
+    save_sites(&app, &snapshot);
+    reapply(&app, &domain);
+    Ok(())
+}
+
+/// The CSS stored for exactly this domain (not inherited from a parent domain)
+#[tauri::command]
+pub async fn get_site_css(app: AppHandle, domain: String) -> Result<Option<String>, BushidoError> {
+    let domain = normalize_domain(&domain)?;
+    Ok(app.state::<SiteCssState>().sites.lock().get(&domain).cloned())
+}
+
+#[tauri::command]
+pub async fn clear_site_css(app: AppHandle, domain: String) -> Result<(), BushidoError> {
+    let domain = normalize_domain(&domain)?;
+    let snapshot = {
+        let scs = app.state::<SiteCssState>();
+        let mut sites = scs.sites.lock();
+        if sites.remove(&domain).is_none() {
+            return Ok(());
+        }
+        sites.clone()
+    };
+    save_sites(&app, &snapshot);
+    reapply(&app, &domain);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_closing_style_tag_any_case() {
+        assert_eq!(escape_css("a{}</style><script>x</script>"), "a{}<\\/style><script>x</script>");
+        assert_eq!(escape_css("</STYLE></Style >"), "<\\/STYLE><\\/Style >");
+        assert_eq!(escape_css("body { color: red }"), "body { color: red }");
+    }
+
+    #[test]
+    fn script_embeds_css_as_a_string_literal() {
+        let s = style_script("p::after{content:\"'\\\"\"}");
+        assert!(s.contains("s.textContent=c"));
+        assert!(s.contains(r#"var c="p::after{content:\"'\\\"\"}";"#));
+    }
+
+    #[test]
+    fn broader_domains_come_first() {
+        let state = SiteCssState { sites: Mutex::new(HashMap::from([
+            ("news.example.com".to_string(), "b{}".to_string()),
+            ("example.com".to_string(), "a{}".to_string()),
+            ("other.org".to_string(), "c{}".to_string()),
+        ])) };
+        assert_eq!(state.css_for_url("https://news.example.com/x").as_deref(), Some("a{}\nb{}"));
+        assert_eq!(state.css_for_url("https://example.com/").as_deref(), Some("a{}"));
+        assert_eq!(state.css_for_url("https://example.net/"), None);
+    }
+}
@@ -0,0 +1,128 @@
+use std::fs;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::crash_log;
+use crate::error::BushidoError;
+
+/// What the first window opens, from the `onStartup` setting
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StartupMode {
+    #[default]
+    #[serde(alias = "restore")]
+    RestoreSession,
+    #[serde(alias = "custom")]
+    Homepage,
+    #[serde(alias = "newtab")]
+    Blank,
+}
+
+impl StartupMode {
+    /// The saved setting; missing or unknown values restore the session as before
+    pub fn from_setting(value: Option<&serde_json::Value>) -> Self {
+        value.and_then(|v| serde_json::from_value(v.clone()).ok()).unwrap_or_default()
+    }
+}
+
+/// Startup policy read from settings.json in setup
+pub struct StartupState {
+    mode: StartupMode,
+    /// the `customHomepageUrl` setting, normalized; empty falls back to the new tab URL
+    homepage: String,
+}
+
+impl StartupState {
+    pub fn new(mode: StartupMode, custom_homepage: &str) -> Self {
+        let homepage = if custom_homepage.trim().is_empty() {
+            String::new()
+        } else {
+            crate::homepage::normalize(custom_homepage)
+        };
+        Self { mode, homepage }
+    }
+}
+
+/// What get_startup_tabs tells the UI to open
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartupPlan {
+    pub mode: StartupMode,
+    /// the saved session as load_session returns it, for restore_session only
+    pub session: Option<String>,
+    /// pages for a fresh window: the homepage, or none for a blank new tab page
+    pub urls: Vec<String>,
+}
+
+/// The mode that can actually be honoured: a session file that exists but can't be
+/// parsed is opened as the homepage instead of as an empty window
+fn effective_mode(mode: StartupMode, session_file: Option<&str>) -> StartupMode {
+    match (mode, session_file) {
+        (StartupMode::RestoreSession, Some(data)) if serde_json::from_str::<serde_json::Value>(data).is_err() => {
+            StartupMode::Homepage
+        }
+        _ => mode,
+    }
+}
+
+fn urls_for(mode: StartupMode, homepage: &str) -> Vec<String> {
+    match mode {
+        StartupMode::Homepage => vec![homepage.to_string()],
+        StartupMode::RestoreSession | StartupMode::Blank => Vec::new(),
+    }
+}
+
+/// What to open at launch. Restoring also loads the session's recently closed tabs,
+/// pins and scroll positions, as load_session does.
+#[tauri::command]
+pub async fn get_startup_tabs(app: AppHandle) -> Result<StartupPlan, BushidoError> {
+    let (configured, homepage) = {
+        let ss = app.state::<StartupState>();
+        (ss.mode, ss.homepage.clone())
+    };
+    let homepage = if homepage.is_empty() { app.state::<crate::homepage::HomepageState>().url() } else { homepage };
+
+    let session_file = match configured {
+        StartupMode::RestoreSession => fs::read_to_string(crate::session_path(&app)).ok(),
+        _ => None,
+    };
+    let mode = effective_mode(configured, session_file.as_deref());
+    if mode != configured {
+        crash_log::log_warn("startup", "session.json is corrupt, opening the homepage instead");
+    }
+    let session = match mode {
+        StartupMode::RestoreSession => Some(crate::load_session(app.clone()).await?),
+        _ => None,
+    };
+    Ok(StartupPlan { mode, session, urls: urls_for(mode, &homepage) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_old_and_new_setting_names() {
+        let mode = |v: serde_json::Value| StartupMode::from_setting(Some(&v));
+        assert_eq!(mode(serde_json::json!("restore")), StartupMode::RestoreSession);
+        assert_eq!(mode(serde_json::json!("custom")), StartupMode::Homepage);
+        assert_eq!(mode(serde_json::json!("newtab")), StartupMode::Blank);
+        assert_eq!(mode(serde_json::json!("homepage")), StartupMode::Homepage);
+        assert_eq!(mode(serde_json::json!("blank")), StartupMode::Blank);
+        assert_eq!(mode(serde_json::json!("sideways")), StartupMode::RestoreSession);
+        assert_eq!(StartupMode::from_setting(None), StartupMode::RestoreSession);
+        assert_eq!(serde_json::to_string(&StartupMode::RestoreSession).unwrap(), "\"restore_session\"");
+    }
+
+    #[test]
+    fn corrupt_session_falls_back_to_the_homepage() {
+        let restore = StartupMode::RestoreSession;
+        assert_eq!(effective_mode(restore, Some(r#"{"workspaces":[],"tabs":[]}"#)), restore);
+        assert_eq!(effective_mode(restore, None), restore);
+        assert_eq!(effective_mode(restore, Some("{\"tabs\": [")), StartupMode::Homepage);
+        assert_eq!(effective_mode(StartupMode::Blank, Some("garbage")), StartupMode::Blank);
+
+        assert_eq!(urls_for(StartupMode::Homepage, "https://example.com/"), vec!["https://example.com/".to_string()]);
+        assert!(urls_for(StartupMode::Blank, "https://example.com/").is_empty());
+    }
+}
@@ -1,9 +1,11 @@
 use rand::Rng;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
+use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+// encoded positionally in keys.dat, so fields can't be added without breaking older builds
 #[derive(Clone, Serialize, Deserialize)]
 pub struct PairedDevice {
     pub device_id: String,
@@ -102,12 +104,35 @@ pub fn save_identity(app_data: &Path, identity: &DeviceIdentity) -> Result<(), S
         .map_err(|e| format!("Failed to serialize key bundle: {}", e))?;
 
     let encrypted = dpapi_encrypt(&plaintext)?;
-    fs::write(dir.join("keys.dat"), encrypted)
+    // a crash mid-write must not leave a truncated keys.dat behind
+    let tmp = dir.join("keys.dat.tmp");
+    fs::write(&tmp, encrypted)
         .map_err(|e| format!("Failed to write keys.dat: {}", e))?;
+    fs::rename(&tmp, dir.join("keys.dat"))
+        .map_err(|e| format!("Failed to replace keys.dat: {}", e))?;
 
     Ok(())
 }
 
+/// Unix seconds of the last successful sync per paired device, kept beside keys.dat
+/// rather than in it. Nothing here is secret.
+pub fn load_last_synced(app_data: &Path) -> HashMap<String, i64> {
+    fs::read_to_string(sync_dir(app_data).join("last_synced.json"))
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_last_synced(app_data: &Path, last_synced: &HashMap<String, i64>) -> Result<(), String> {
+    let dir = sync_dir(app_data);
+    let json = serde_json::to_string(last_synced)
+        .map_err(|e| format!("Failed to serialize last_synced: {}", e))?;
+    let tmp = dir.join("last_synced.json.tmp");
+    fs::write(&tmp, json).map_err(|e| format!("Failed to write last_synced.json: {}", e))?;
+    fs::rename(&tmp, dir.join("last_synced.json"))
+        .map_err(|e| format!("Failed to replace last_synced.json: {}", e))
+}
+
 pub fn load_identity(app_data: &Path) -> Result<Option<DeviceIdentity>, String> {
     let dir = sync_dir(app_data);
     let keys_path = dir.join("keys.dat");
@@ -229,3 +254,24 @@ pub(crate) fn dpapi_encrypt(plaintext: &[u8]) -> Result<Vec<u8>, String> {
 pub(crate) fn dpapi_decrypt(encrypted: &[u8]) -> Result<Vec<u8>, String> {
     Ok(encrypted.to_vec())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paired_device_keeps_the_shape_older_builds_read() {
+        let device = PairedDevice {
+            device_id: "abc".into(),
+            name: "PC".into(),
+            noise_public_key: vec![1, 2, 3],
+            fingerprint: "ff".into(),
+            paired_at: 42,
+        };
+        let bytes = rmp_serde::to_vec(&vec![device]).unwrap();
+        // what builds before last-synced tracking decode
+        let old: Vec<(String, String, Vec<u8>, String, i64)> = rmp_serde::from_slice(&bytes).unwrap();
+        assert_eq!(old[0].0, "abc");
+        assert_eq!(old[0].4, 42);
+    }
+}
@@ -9,17 +9,22 @@ pub mod sync_engine;
 
 use discovery::{DiscoveryService, PeerInfo};
 use keys::{DeviceIdentity, PairedDevice};
-use sync_doc::SyncDoc;
+use sync_doc::{AttributedChange, FieldConflict, SyncDoc};
+pub use sync_doc::DataType;
 use protocol::SyncMessage;
-use serde::Serialize;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
 use std::net::SocketAddr;
 use std::path::PathBuf;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
 use parking_lot::Mutex;
 use std::time::{Duration, Instant};
 use tauri::{Emitter, Manager};
 
+use crate::downloads::RateLimiter;
+use crate::error::BushidoError;
+
 #[derive(Clone, Debug, Serialize)]
 pub enum SyncStatus {
     Disabled,
@@ -28,6 +33,68 @@ pub enum SyncStatus {
     Error { message: String },
 }
 
+// pairing rate limit: this many failures per device locks it out for LOCKOUT_DURATION
+const MAX_FAILED_ATTEMPTS: u32 = 3;
+const LOCKOUT_DURATION: Duration = Duration::from_secs(300);
+
+/// Operational counters fed by the listener, sync runs and the health check —
+/// read by `get_sync_health`.
+#[derive(Clone, Default)]
+pub struct HealthRecord {
+    pub listener_port: Option<u16>,
+    pub last_sync_at: Option<i64>,
+    pub last_error: Option<String>,
+    pub last_check_at: Option<i64>,
+    pub discovery_restarts: u32,
+    pub listener_restarts: u32,
+}
+
+impl DataType {
+    // frontend event fired when remote changes for this type arrive
+    fn changed_event(self) -> &'static str {
+        match self {
+            DataType::Bookmarks => "sync-bookmarks-changed",
+            DataType::History => "sync-history-changed",
+            DataType::Settings => "sync-settings-changed",
+            DataType::Tabs => "sync-tabs-changed",
+            DataType::ReadingList => "sync-reading-list-changed",
+        }
+    }
+}
+
+/// The `syncDataTypes` setting — which slices this device sends and applies.
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DataTypes {
+    pub bookmarks: bool,
+    pub history: bool,
+    pub settings: bool,
+    pub tabs: bool,
+    pub reading_list: bool,
+}
+
+impl Default for DataTypes {
+    fn default() -> Self {
+        DataTypes { bookmarks: true, history: true, settings: true, tabs: true, reading_list: true }
+    }
+}
+
+// conflict log size; older entries drop off the front
+const MAX_CONFLICTS: usize = 100;
+
+/// A bookmark field both this device and a peer edited between syncs. Loro has already
+/// merged it; the log only makes that visible.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncConflict {
+    pub device_id: String,
+    pub bookmark_id: String,
+    pub field: &'static str,
+    pub local_value: String,
+    pub remote_value: String,
+    pub timestamp: i64,
+}
+
 pub struct SyncState {
     pub enabled: bool,
     pub device_id: String,
@@ -48,11 +115,24 @@ pub struct SyncState {
     // Phase C+D: CRDT sync doc (bookmarks, history, settings, tabs)
     pub sync_doc: tokio::sync::Mutex<Option<SyncDoc>>,
     pub sync_debounce: Mutex<Option<tokio::sync::mpsc::Sender<()>>>,
+    pub debounce_pending: AtomicBool,
+    pub health: Mutex<HealthRecord>,
     // selective sync flags
     pub sync_bookmarks: AtomicBool,
     pub sync_history: AtomicBool,
     pub sync_settings: AtomicBool,
     pub sync_tabs: AtomicBool,
+    pub sync_reading_list: AtomicBool,
+    // cleared once the "since last session" summary has run for this launch
+    pub session_summary_pending: AtomicBool,
+    // most recent concurrent bookmark edits, oldest first, capped at MAX_CONFLICTS
+    pub conflicts: Mutex<VecDeque<SyncConflict>>,
+    // device_id → unix seconds of the last successful sync with that device
+    pub last_synced: Mutex<HashMap<String, i64>>,
+    // outgoing sync traffic cap, separate from the download limiter
+    pub bandwidth: Arc<RateLimiter>,
+    // smallest encrypted frame worth zstd-compressing, when the peer supports it
+    pub compress_threshold: AtomicUsize,
 }
 
 impl SyncState {
@@ -75,10 +155,18 @@ impl SyncState {
             failed_attempts: Mutex::new(HashMap::new()),
             sync_doc: tokio::sync::Mutex::new(None),
             sync_debounce: Mutex::new(None),
+            debounce_pending: AtomicBool::new(false),
+            health: Mutex::new(HealthRecord::default()),
             sync_bookmarks: AtomicBool::new(true),
             sync_history: AtomicBool::new(true),
             sync_settings: AtomicBool::new(true),
             sync_tabs: AtomicBool::new(true),
+            sync_reading_list: AtomicBool::new(true),
+            session_summary_pending: AtomicBool::new(true),
+            conflicts: Mutex::new(VecDeque::new()),
+            last_synced: Mutex::new(HashMap::new()),
+            bandwidth: Arc::new(RateLimiter::new(0)),
+            compress_threshold: AtomicUsize::new(protocol::DEFAULT_COMPRESS_THRESHOLD),
         }
     }
 
@@ -87,6 +175,7 @@ impl SyncState {
         device_name: String,
         app_data_dir: PathBuf,
     ) -> Self {
+        let last_synced = keys::load_last_synced(&app_data_dir);
         SyncState {
             enabled: true,
             device_id: identity.device_id,
@@ -105,10 +194,60 @@ impl SyncState {
             failed_attempts: Mutex::new(HashMap::new()),
             sync_doc: tokio::sync::Mutex::new(None),
             sync_debounce: Mutex::new(None),
+            debounce_pending: AtomicBool::new(false),
+            health: Mutex::new(HealthRecord::default()),
             sync_bookmarks: AtomicBool::new(true),
             sync_history: AtomicBool::new(true),
             sync_settings: AtomicBool::new(true),
             sync_tabs: AtomicBool::new(true),
+            sync_reading_list: AtomicBool::new(true),
+            session_summary_pending: AtomicBool::new(true),
+            conflicts: Mutex::new(VecDeque::new()),
+            last_synced: Mutex::new(last_synced),
+            bandwidth: Arc::new(RateLimiter::new(0)),
+            compress_threshold: AtomicUsize::new(protocol::DEFAULT_COMPRESS_THRESHOLD),
+        }
+    }
+
+    pub fn compress_threshold(&self) -> usize {
+        self.compress_threshold.load(Ordering::Relaxed)
+    }
+
+    fn flag(&self, t: DataType) -> &AtomicBool {
+        match t {
+            DataType::Bookmarks => &self.sync_bookmarks,
+            DataType::History => &self.sync_history,
+            DataType::Settings => &self.sync_settings,
+            DataType::Tabs => &self.sync_tabs,
+            DataType::ReadingList => &self.sync_reading_list,
+        }
+    }
+
+    /// Whether sync is on and `t` is one of the types this device exchanges. A disabled
+    /// type is never written locally, and its doc is left out of a sync in both directions.
+    pub fn syncs(&self, t: DataType) -> bool {
+        self.enabled && self.flag(t).load(Ordering::Relaxed)
+    }
+
+    /// The types this device exchanges right now
+    pub fn synced_types(&self) -> Vec<DataType> {
+        DataType::ALL.into_iter().filter(|t| self.syncs(*t)).collect()
+    }
+
+    pub fn set_data_types(&self, types: DataTypes) {
+        self.sync_bookmarks.store(types.bookmarks, Ordering::Relaxed);
+        self.sync_history.store(types.history, Ordering::Relaxed);
+        self.sync_settings.store(types.settings, Ordering::Relaxed);
+        self.sync_tabs.store(types.tabs, Ordering::Relaxed);
+        self.sync_reading_list.store(types.reading_list, Ordering::Relaxed);
+    }
+}
+
+/// Tell the frontend which enabled types may have changed after a merge.
+fn emit_remote_changes(app: &tauri::AppHandle, state: &SyncState) {
+    for t in DataType::ALL {
+        if state.syncs(t) {
+            let _ = app.emit_to("main", t.changed_event(), ());
         }
     }
 }
@@ -141,9 +280,14 @@ pub fn start_tcp_listener(app: tauri::AppHandle) {
             Ok(l) => l,
             Err(e) => {
                 eprintln!("[sync] TCP listener bind failed: {}", e);
+                let state = app.state::<SyncState>();
+                let mut health = state.health.lock();
+                health.listener_port = None;
+                health.last_error = Some(format!("listener bind failed: {}", e));
                 return;
             }
         };
+        app.state::<SyncState>().health.lock().listener_port = listener.local_addr().ok().map(|a| a.port());
 
         loop {
             let (mut stream, _addr) = match listener.accept().await {
@@ -239,10 +383,10 @@ async fn handle_incoming_pair(
             .failed_attempts
             .lock();
         if let Some((count, since)) = attempts.get(peer_device_id) {
-            if *count >= 3 && since.elapsed() < std::time::Duration::from_secs(300) {
+            if *count >= MAX_FAILED_ATTEMPTS && since.elapsed() < LOCKOUT_DURATION {
                 true
             } else {
-                if since.elapsed() >= std::time::Duration::from_secs(300) {
+                if since.elapsed() >= LOCKOUT_DURATION {
                     attempts.remove(peer_device_id);
                 }
                 false
@@ -407,6 +551,8 @@ fn store_paired_device(
         fingerprint: result.fingerprint.clone(),
         paired_at: chrono::Utc::now().timestamp(),
     };
+    // a re-pair starts over
+    forget_peer_synced(state, &result.device_id);
 
     let devices = {
         let mut devices = state
@@ -486,8 +632,8 @@ async fn handle_incoming_sync(
     }
 
     // receive first encrypted message — could be Hello (sync) or SendTab (tab push)
-    let first_msg = match protocol::recv_encrypted(&mut ns).await {
-        Ok(msg) => msg,
+    let (first_msg, hello_ext) = match protocol::recv_with_ext(&mut ns).await {
+        Ok(received) => received,
         Err(_) => return,
     };
 
@@ -495,12 +641,11 @@ async fn handle_incoming_sync(
     if let SyncMessage::SendTab { sender_device_id, sender_device_name, url, title } = first_msg {
         // validate url
         if sync_doc::is_safe_url_pub(&url) {
-            let safe_title = title.replace('<', "&lt;").replace('>', "&gt;");
             let _ = app.emit_to("main", "tab-received", serde_json::json!({
                 "from_device": sender_device_name,
                 "from_device_id": sender_device_id,
                 "url": url,
-                "title": safe_title,
+                "title": escape_title(&title),
             }));
             emit_log(&app, "receive", &format!("received tab: {}", &url), Some(&sender_device_id));
         }
@@ -508,6 +653,28 @@ async fn handle_incoming_sync(
         return;
     }
 
+    // same for a tab group — one event for the whole list
+    if let SyncMessage::SendTabs { sender_device_id, sender_device_name, tabs } = first_msg {
+        if tabs.len() > MAX_SENT_TABS {
+            emit_log(&app, "error", &format!("refused {} tabs (max {})", tabs.len(), MAX_SENT_TABS), Some(&sender_device_id));
+        } else {
+            let tabs: Vec<serde_json::Value> = tabs.into_iter()
+                .filter(|t| sync_doc::is_safe_url_pub(&t.url))
+                .map(|t| serde_json::json!({ "url": t.url, "title": escape_title(&t.title) }))
+                .collect();
+            if !tabs.is_empty() {
+                emit_log(&app, "receive", &format!("received {} tabs", tabs.len()), Some(&sender_device_id));
+                let _ = app.emit_to("main", "tabs-received", serde_json::json!({
+                    "from_device": sender_device_name,
+                    "from_device_id": sender_device_id,
+                    "tabs": tabs,
+                }));
+            }
+        }
+        let _ = protocol::send_encrypted(&mut ns, &SyncMessage::SendTabAck).await;
+        return;
+    }
+
     let remote_vv = match first_msg {
         SyncMessage::Hello { vv, .. } => vv,
         _ => return,
@@ -517,38 +684,35 @@ async fn handle_incoming_sync(
     eprintln!("[sync] handle_incoming_sync: starting responder for peer {}", peer_device_id);
     let _ = app.emit_to("main", "sync-activity", "syncing");
     emit_log(&app, "sync", "syncing with peer", Some(peer_device_id));
-    let changes = {
-        let mut doc_guard = state.sync_doc.lock().await;
-        let doc = match doc_guard.as_mut() {
-            Some(d) => {
-                eprintln!("[sync] sync_doc is Some, proceeding");
-                d
-            }
-            None => {
-                eprintln!("[sync] sync_doc is None! Cannot sync.");
-                return;
-            }
-        };
-        sync_engine::handle_sync_responder(&mut ns, remote_vv, doc).await
-    };
+    if state.sync_doc.lock().await.is_none() {
+        eprintln!("[sync] sync_doc is None! Cannot sync.");
+        return;
+    }
+    // the engine locks the doc itself, only around export/import
+    let changes = sync_engine::handle_sync_responder(&mut ns, peer_device_id, remote_vv, &hello_ext, &state.synced_types(), &state.sync_doc, state.bandwidth.clone(), state.compress_threshold()).await;
 
-    eprintln!("[sync] handle_incoming_sync: result = {:?}", changes.as_ref().map(|r| format!("{:?}", r)).unwrap_or_else(|e| format!("Err({})", e)));
+    eprintln!("[sync] handle_incoming_sync: result = {:?}", changes.as_ref().map(|(r, _)| format!("{:?}", r)).unwrap_or_else(|e| format!("Err({})", e)));
     match changes {
-        Ok(sync_engine::SyncResult::ChangesReceived | sync_engine::SyncResult::BothSynced) => {
+        Ok((sync_engine::SyncResult::ChangesReceived | sync_engine::SyncResult::BothSynced, conflicts)) => {
+            record_conflicts(&app, &state, peer_device_id, conflicts);
             eprintln!("[sync] emitting sync-*-changed to frontend");
-            let _ = app.emit_to("main", "sync-bookmarks-changed", ());
-            let _ = app.emit_to("main", "sync-history-changed", ());
-            let _ = app.emit_to("main", "sync-settings-changed", ());
-            let _ = app.emit_to("main", "sync-tabs-changed", ());
+            emit_remote_changes(&app, &state);
             let _ = app.emit_to("main", "sync-activity", "success");
             emit_log(&app, "receive", "received changes from peer", Some(peer_device_id));
+            record_sync_ok(&state);
+            record_peer_synced(&state, peer_device_id);
+            emit_session_summary(&app).await;
         }
-        Ok(ref r) => {
+        Ok((ref r, _)) => {
             eprintln!("[sync] no incoming changes, result: {:?}", r);
             let _ = app.emit_to("main", "sync-activity", "success");
+            record_sync_ok(&state);
+            record_peer_synced(&state, peer_device_id);
+            emit_session_summary(&app).await;
         }
         Err(e) => {
             eprintln!("[sync] responder error: {}", e);
+            record_sync_error(&state, &e);
             let _ = app.emit_to("main", "sync-activity", "error");
             emit_log(&app, "error", &format!("sync error: {}", e), Some(peer_device_id));
         }
@@ -558,7 +722,8 @@ async fn handle_incoming_sync(
 /// Sync with all paired+discovered peers.
 pub fn trigger_sync(app: tauri::AppHandle) {
     let state = app.state::<SyncState>();
-    if !state.enabled {
+    // work-offline mode holds sync; set_offline(false) triggers a catch-up run
+    if !state.enabled || crate::is_offline(&app) {
         return;
     }
 
@@ -599,31 +764,33 @@ pub fn trigger_sync(app: tauri::AppHandle) {
 
         tauri::async_runtime::spawn(async move {
             let state = app2.state::<SyncState>();
-            // grab doc, sync, release
-            let result = {
-                let mut doc_guard = state.sync_doc.lock().await;
-                let doc = match doc_guard.as_mut() {
-                    Some(d) => d,
-                    None => return,
-                };
-                sync_engine::sync_with_peer(&peer, addr, &pk, &did, doc).await
-            };
+            if state.sync_doc.lock().await.is_none() {
+                return;
+            }
+            // the engine locks the doc itself, only around export/import
+            let result = sync_engine::sync_with_peer(&peer, addr, &pk, &did, &state.synced_types(), &state.sync_doc, state.bandwidth.clone(), state.compress_threshold()).await;
 
             match result {
-                Ok(
+                Ok((
                     sync_engine::SyncResult::ChangesReceived
                     | sync_engine::SyncResult::BothSynced,
-                ) => {
-                    let _ = app2.emit_to("main", "sync-bookmarks-changed", ());
-                    let _ = app2.emit_to("main", "sync-history-changed", ());
-                    let _ = app2.emit_to("main", "sync-settings-changed", ());
-                    let _ = app2.emit_to("main", "sync-tabs-changed", ());
+                    conflicts,
+                )) => {
+                    record_conflicts(&app2, &state, &peer.device_id, conflicts);
+                    emit_remote_changes(&app2, &state);
                     let _ = app2.emit_to("main", "sync-activity", "success");
+                    record_sync_ok(&state);
+                    record_peer_synced(&state, &peer.device_id);
+                    emit_session_summary(&app2).await;
                 }
                 Ok(_) => {
                     let _ = app2.emit_to("main", "sync-activity", "success");
+                    record_sync_ok(&state);
+                    record_peer_synced(&state, &peer.device_id);
+                    emit_session_summary(&app2).await;
                 }
-                Err(_) => {} // peer offline or busy — silent, retry next trigger
+                // peer offline or busy — silent, retry next trigger
+                Err(e) => record_sync_error(&state, &e),
             }
         });
     }
@@ -651,6 +818,7 @@ pub fn start_sync_debounce(app: tauri::AppHandle) {
             }
             tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
             while rx.try_recv().is_ok() {}
+            app_debounce.state::<SyncState>().debounce_pending.store(false, Ordering::Relaxed);
             trigger_sync(app_debounce.clone());
         }
     });
@@ -672,7 +840,9 @@ pub fn notify_sync_change(state: &SyncState) {
         .sync_debounce
         .lock();
     if let Some(ref tx) = *sender {
-        let _ = tx.try_send(());
+        if tx.try_send(()).is_ok() {
+            state.debounce_pending.store(true, Ordering::Relaxed);
+        }
     }
 }
 
@@ -695,6 +865,8 @@ pub struct PairedDeviceInfo {
     pub name: String,
     pub fingerprint: String,
     pub paired_at: i64,
+    /// None until the first successful sync since pairing
+    pub last_synced_at: Option<i64>,
 }
 
 impl From<&PairedDevice> for PairedDeviceInfo {
@@ -704,12 +876,13 @@ impl From<&PairedDevice> for PairedDeviceInfo {
             name: pd.name.clone(),
             fingerprint: pd.fingerprint.clone(),
             paired_at: pd.paired_at,
+            last_synced_at: None,
         }
     }
 }
 
 #[tauri::command]
-pub async fn get_sync_status(state: tauri::State<'_, SyncState>) -> Result<SyncInfo, String> {
+pub async fn get_sync_status(state: tauri::State<'_, SyncState>) -> Result<SyncInfo, BushidoError> {
     let status = state
         .status
         .lock()
@@ -723,12 +896,17 @@ pub async fn get_sync_status(state: tauri::State<'_, SyncState>) -> Result<SyncI
             None => Vec::new(),
         }
     };
+    let last_synced = state.last_synced.lock();
     let paired = state
         .paired_devices
         .lock()
         .iter()
-        .map(PairedDeviceInfo::from)
+        .map(|d| PairedDeviceInfo {
+            last_synced_at: last_synced.get(&d.device_id).copied(),
+            ..PairedDeviceInfo::from(d)
+        })
         .collect();
+    drop(last_synced);
 
     Ok(SyncInfo {
         enabled: state.enabled,
@@ -741,12 +919,71 @@ pub async fn get_sync_status(state: tauri::State<'_, SyncState>) -> Result<SyncI
     })
 }
 
+#[derive(Serialize)]
+pub struct SyncHealth {
+    pub enabled: bool,
+    pub listener_running: bool,
+    pub listener_bound: bool,
+    pub listener_port: Option<u16>,
+    pub discovery_active: bool,
+    pub peer_count: usize,
+    pub paired_online: usize,
+    pub doc_loaded: bool,
+    pub last_sync_at: Option<i64>,
+    pub last_error: Option<String>,
+    pub debounce_pending: bool,
+    pub failed_attempts: u32,
+    pub locked_out_peers: usize,
+    pub last_health_check_at: Option<i64>,
+    pub discovery_restarts: u32,
+    pub listener_restarts: u32,
+}
+
+/// Operational snapshot for diagnosing sync — read-only, takes no async locks.
+#[tauri::command]
+pub async fn get_sync_health(state: tauri::State<'_, SyncState>) -> Result<SyncHealth, BushidoError> {
+    let peers = state.discovery.lock().as_ref().map(|d| d.get_peers());
+    let paired_online = {
+        let devices = state.paired_devices.lock();
+        let peers = peers.as_deref().unwrap_or_default();
+        devices.iter().filter(|d| peers.iter().any(|p| p.device_id == d.device_id)).count()
+    };
+    let (failed_attempts, locked_out_peers) = {
+        let attempts = state.failed_attempts.lock();
+        let total = attempts.values().map(|(n, _)| *n).sum();
+        let locked = attempts.values().filter(|(n, t)| *n >= MAX_FAILED_ATTEMPTS && t.elapsed() < LOCKOUT_DURATION).count();
+        (total, locked)
+    };
+    // the doc is only locked while a sync or a mutation is running, and both need it loaded
+    let doc_loaded = state.sync_doc.try_lock().map(|d| d.is_some()).unwrap_or(true);
+    let health = state.health.lock().clone();
+
+    Ok(SyncHealth {
+        enabled: state.enabled,
+        listener_running: state.listener_handle.lock().is_some(),
+        listener_bound: health.listener_port.is_some(),
+        listener_port: health.listener_port,
+        discovery_active: peers.is_some(),
+        peer_count: peers.map(|p| p.len()).unwrap_or(0),
+        paired_online,
+        doc_loaded,
+        last_sync_at: health.last_sync_at,
+        last_error: health.last_error,
+        debounce_pending: state.debounce_pending.load(Ordering::Relaxed),
+        failed_attempts,
+        locked_out_peers,
+        last_health_check_at: health.last_check_at,
+        discovery_restarts: health.discovery_restarts,
+        listener_restarts: health.listener_restarts,
+    })
+}
+
 #[tauri::command]
 pub async fn enable_sync(
     app: tauri::AppHandle,
     state: tauri::State<'_, SyncState>,
     device_name: String,
-) -> Result<SyncInfo, String> {
+) -> Result<SyncInfo, BushidoError> {
     if state.enabled {
         return get_sync_status(state).await;
     }
@@ -794,7 +1031,7 @@ pub async fn enable_sync(
 }
 
 #[tauri::command]
-pub async fn disable_sync(state: tauri::State<'_, SyncState>) -> Result<(), String> {
+pub async fn disable_sync(state: tauri::State<'_, SyncState>) -> Result<(), BushidoError> {
     // Stop discovery
     let disc = {
         let mut discovery = state
@@ -815,6 +1052,7 @@ pub async fn disable_sync(state: tauri::State<'_, SyncState>) -> Result<(), Stri
             handle.abort();
         }
     }
+    state.health.lock().listener_port = None;
 
     {
         let mut status = state.status.lock();
@@ -827,7 +1065,7 @@ pub async fn disable_sync(state: tauri::State<'_, SyncState>) -> Result<(), Stri
 #[tauri::command]
 pub async fn get_discovered_peers(
     state: tauri::State<'_, SyncState>,
-) -> Result<Vec<PeerInfo>, String> {
+) -> Result<Vec<PeerInfo>, BushidoError> {
     let discovery = state
         .discovery
         .lock();
@@ -841,7 +1079,7 @@ pub async fn get_discovered_peers(
 pub async fn set_device_name(
     state: tauri::State<'_, SyncState>,
     name: String,
-) -> Result<(), String> {
+) -> Result<(), BushidoError> {
     if let Some(identity) = keys::load_identity(&state.app_data_dir)? {
         let mut discovery = state
             .discovery
@@ -861,9 +1099,9 @@ pub async fn start_pairing(
     app: tauri::AppHandle,
     state: tauri::State<'_, SyncState>,
     peer_id: String,
-) -> Result<String, String> {
+) -> Result<String, BushidoError> {
     if !state.enabled {
-        return Err("Sync is not enabled".into());
+        return Err(BushidoError::Sync("Sync is not enabled".into()));
     }
 
     // Check not already pairing
@@ -981,7 +1219,7 @@ pub async fn enter_pairing_code(
     state: tauri::State<'_, SyncState>,
     _peer_id: String,
     code: String,
-) -> Result<(), String> {
+) -> Result<(), BushidoError> {
     let sender = {
         let mut s = state
             .pairing_code_sender
@@ -991,8 +1229,8 @@ pub async fn enter_pairing_code(
     match sender {
         Some(tx) => tx
             .send(code)
-            .map_err(|_| "Pairing session expired".to_string()),
-        None => Err("No active pairing session".into()),
+            .map_err(|_| BushidoError::Sync("Pairing session expired".into())),
+        None => Err(BushidoError::Sync("No active pairing session".into())),
     }
 }
 
@@ -1000,7 +1238,7 @@ pub async fn enter_pairing_code(
 pub async fn remove_device(
     state: tauri::State<'_, SyncState>,
     device_id: String,
-) -> Result<(), String> {
+) -> Result<(), BushidoError> {
     let devices = {
         let mut devices = state
             .paired_devices
@@ -1008,6 +1246,7 @@ pub async fn remove_device(
         devices.retain(|d| d.device_id != device_id);
         devices.clone()
     };
+    forget_peer_synced(&state, &device_id);
 
     // Save to disk
     if let Ok(Some(mut identity)) = keys::load_identity(&state.app_data_dir) {
@@ -1029,9 +1268,9 @@ pub async fn remove_device(
 pub async fn simulate_pairing(
     app: tauri::AppHandle,
     state: tauri::State<'_, SyncState>,
-) -> Result<serde_json::Value, String> {
+) -> Result<serde_json::Value, BushidoError> {
     if !state.enabled {
-        return Err("Sync is not enabled".into());
+        return Err(BushidoError::Sync("Sync is not enabled".into()));
     }
 
     // Generate a fake device identity
@@ -1129,9 +1368,9 @@ pub async fn simulate_pairing(
 // ── Phase C: Sync Commands ─────────────────────────────────────────────────
 
 #[tauri::command]
-pub async fn force_sync(app: tauri::AppHandle, state: tauri::State<'_, SyncState>) -> Result<(), String> {
+pub async fn force_sync(app: tauri::AppHandle, state: tauri::State<'_, SyncState>) -> Result<(), BushidoError> {
     if !state.enabled {
-        return Err("Sync is not enabled".into());
+        return Err(BushidoError::Sync("Sync is not enabled".into()));
     }
     trigger_sync(app);
     Ok(())
@@ -1143,12 +1382,12 @@ pub async fn force_sync(app: tauri::AppHandle, state: tauri::State<'_, SyncState
 pub async fn sync_add_bookmark(
     app: tauri::AppHandle, id: String, url: String, title: String,
     favicon: Option<String>, folder_id: String, created_at: f64,
-) -> Result<(), String> {
+) -> Result<(), BushidoError> {
     let state = app.state::<SyncState>();
-    if !state.enabled { return Ok(()); }
+    if !state.syncs(DataType::Bookmarks) { return Ok(()); }
     {
         let mut g = state.sync_doc.lock().await;
-        let doc = g.as_mut().ok_or("no doc")?;
+        let doc = g.as_mut().ok_or_else(|| BushidoError::Sync("no doc".into()))?;
         doc.add_bookmark(&id, &url, &title, favicon.as_deref(), &folder_id, created_at)?;
         doc.save()?;
     }
@@ -1159,12 +1398,12 @@ pub async fn sync_add_bookmark(
 #[tauri::command]
 pub async fn sync_remove_bookmark(
     app: tauri::AppHandle, id: String,
-) -> Result<(), String> {
+) -> Result<(), BushidoError> {
     let state = app.state::<SyncState>();
-    if !state.enabled { return Ok(()); }
+    if !state.syncs(DataType::Bookmarks) { return Ok(()); }
     {
         let mut g = state.sync_doc.lock().await;
-        let doc = g.as_mut().ok_or("no doc")?;
+        let doc = g.as_mut().ok_or_else(|| BushidoError::Sync("no doc".into()))?;
         doc.remove_bookmark(&id)?;
         doc.save()?;
     }
@@ -1175,12 +1414,12 @@ pub async fn sync_remove_bookmark(
 #[tauri::command]
 pub async fn sync_add_folder(
     app: tauri::AppHandle, id: String, name: String, parent_id: String, order: f64,
-) -> Result<(), String> {
+) -> Result<(), BushidoError> {
     let state = app.state::<SyncState>();
-    if !state.enabled { return Ok(()); }
+    if !state.syncs(DataType::Bookmarks) { return Ok(()); }
     {
         let mut g = state.sync_doc.lock().await;
-        let doc = g.as_mut().ok_or("no doc")?;
+        let doc = g.as_mut().ok_or_else(|| BushidoError::Sync("no doc".into()))?;
         doc.add_folder(&id, &name, &parent_id, order)?;
         doc.save()?;
     }
@@ -1191,12 +1430,12 @@ pub async fn sync_add_folder(
 #[tauri::command]
 pub async fn sync_remove_folder(
     app: tauri::AppHandle, id: String,
-) -> Result<(), String> {
+) -> Result<(), BushidoError> {
     let state = app.state::<SyncState>();
-    if !state.enabled { return Ok(()); }
+    if !state.syncs(DataType::Bookmarks) { return Ok(()); }
     {
         let mut g = state.sync_doc.lock().await;
-        let doc = g.as_mut().ok_or("no doc")?;
+        let doc = g.as_mut().ok_or_else(|| BushidoError::Sync("no doc".into()))?;
         doc.delete_folder_cascade(&id)?;
         doc.save()?;
     }
@@ -1207,12 +1446,12 @@ pub async fn sync_remove_folder(
 #[tauri::command]
 pub async fn sync_rename_folder(
     app: tauri::AppHandle, id: String, name: String,
-) -> Result<(), String> {
+) -> Result<(), BushidoError> {
     let state = app.state::<SyncState>();
-    if !state.enabled { return Ok(()); }
+    if !state.syncs(DataType::Bookmarks) { return Ok(()); }
     {
         let mut g = state.sync_doc.lock().await;
-        let doc = g.as_mut().ok_or("no doc")?;
+        let doc = g.as_mut().ok_or_else(|| BushidoError::Sync("no doc".into()))?;
         doc.rename_folder(&id, &name)?;
         doc.save()?;
     }
@@ -1223,12 +1462,12 @@ pub async fn sync_rename_folder(
 #[tauri::command]
 pub async fn sync_move_bookmark(
     app: tauri::AppHandle, id: String, folder_id: String,
-) -> Result<(), String> {
+) -> Result<(), BushidoError> {
     let state = app.state::<SyncState>();
-    if !state.enabled { return Ok(()); }
+    if !state.syncs(DataType::Bookmarks) { return Ok(()); }
     {
         let mut g = state.sync_doc.lock().await;
-        let doc = g.as_mut().ok_or("no doc")?;
+        let doc = g.as_mut().ok_or_else(|| BushidoError::Sync("no doc".into()))?;
         doc.move_bookmark(&id, &folder_id)?;
         doc.save()?;
     }
@@ -1236,6 +1475,62 @@ pub async fn sync_move_bookmark(
     Ok(())
 }
 
+// tags live only in the sync doc, so unlike the surgical ops above these fail when bookmark sync is off
+
+#[tauri::command]
+pub async fn sync_add_bookmark_tag(
+    app: tauri::AppHandle, id: String, tag: String,
+) -> Result<(), BushidoError> {
+    let state = app.state::<SyncState>();
+    if !state.syncs(DataType::Bookmarks) { return Err(BushidoError::Sync("Bookmark sync not enabled".into())); }
+    {
+        let mut g = state.sync_doc.lock().await;
+        let doc = g.as_mut().ok_or_else(|| BushidoError::Sync("no doc".into()))?;
+        doc.add_bookmark_tag(&id, &tag)?;
+        doc.save()?;
+    }
+    notify_sync_change(&state);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn sync_remove_bookmark_tag(
+    app: tauri::AppHandle, id: String, tag: String,
+) -> Result<(), BushidoError> {
+    let state = app.state::<SyncState>();
+    if !state.syncs(DataType::Bookmarks) { return Err(BushidoError::Sync("Bookmark sync not enabled".into())); }
+    {
+        let mut g = state.sync_doc.lock().await;
+        let doc = g.as_mut().ok_or_else(|| BushidoError::Sync("no doc".into()))?;
+        doc.remove_bookmark_tag(&id, &tag)?;
+        doc.save()?;
+    }
+    notify_sync_change(&state);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_bookmarks_by_tag(
+    app: tauri::AppHandle, tag: String,
+) -> Result<String, BushidoError> {
+    let state = app.state::<SyncState>();
+    if !state.syncs(DataType::Bookmarks) { return Err(BushidoError::Sync("Bookmark sync not enabled".into())); }
+    let g = state.sync_doc.lock().await;
+    let doc = g.as_ref().ok_or_else(|| BushidoError::Sync("no doc".into()))?;
+    doc.get_bookmarks_by_tag_json(&tag).map_err(BushidoError::Sync)
+}
+
+#[tauri::command]
+pub async fn list_bookmark_tags(
+    app: tauri::AppHandle,
+) -> Result<String, BushidoError> {
+    let state = app.state::<SyncState>();
+    if !state.syncs(DataType::Bookmarks) { return Err(BushidoError::Sync("Bookmark sync not enabled".into())); }
+    let g = state.sync_doc.lock().await;
+    let doc = g.as_ref().ok_or_else(|| BushidoError::Sync("no doc".into()))?;
+    doc.list_all_tags_json().map_err(BushidoError::Sync)
+}
+
 /// Full loopback test: pair a ghost device, then sync sample bookmarks into our LoroDoc.
 /// Returns the pairing code for the user to enter in the UI. After the code is entered,
 /// the ghost pairs, then immediately syncs 3 sample bookmarks into the real device.
@@ -1243,9 +1538,9 @@ pub async fn sync_move_bookmark(
 pub async fn simulate_sync(
     app: tauri::AppHandle,
     state: tauri::State<'_, SyncState>,
-) -> Result<serde_json::Value, String> {
+) -> Result<serde_json::Value, BushidoError> {
     if !state.enabled {
-        return Err("Sync is not enabled".into());
+        return Err(BushidoError::Sync("Sync is not enabled".into()));
     }
 
     // check we have a LoroDoc
@@ -1453,7 +1748,7 @@ pub async fn simulate_sync(
             &SyncMessage::Hello {
                 device_id: ghost_did.clone(),
                 vv: ghost_vv,
-            },
+            }, // no HelloExt: the ghost acts like a pre-compression peer
         ).await {
             eprintln!("[simulate_sync] send Hello failed: {}", e);
             return;
@@ -1461,7 +1756,7 @@ pub async fn simulate_sync(
 
         // receive HelloAck
         let _peer_vv = match protocol::recv_encrypted(&mut ns).await {
-            Ok(SyncMessage::HelloAck { vv }) => vv,
+            Ok(SyncMessage::HelloAck { vv, .. }) => vv,
             Ok(other) => {
                 eprintln!("[simulate_sync] expected HelloAck, got {:?}", other);
                 return;
@@ -1528,13 +1823,14 @@ pub async fn simulate_sync(
 #[tauri::command]
 pub async fn sync_add_history(
     app: tauri::AppHandle, url: String, title: String,
-    favicon: Option<String>, timestamp: i64,
-) -> Result<(), String> {
+    favicon: Option<String>, timestamp: i64, tab_id: Option<String>,
+) -> Result<(), BushidoError> {
+    if tab_id.is_some_and(|id| crate::is_private_tab(&app, &id)) { return Ok(()); }
     let state = app.state::<SyncState>();
-    if !state.enabled || !state.sync_history.load(Ordering::Relaxed) { return Ok(()); }
+    if !state.syncs(DataType::History) { return Ok(()); }
     {
         let mut g = state.sync_doc.lock().await;
-        let doc = g.as_mut().ok_or("no doc")?;
+        let doc = g.as_mut().ok_or_else(|| BushidoError::Sync("no doc".into()))?;
         doc.add_history(&url, &title, favicon.as_deref(), timestamp)?;
         doc.save()?;
     }
@@ -1545,12 +1841,12 @@ pub async fn sync_add_history(
 #[tauri::command]
 pub async fn sync_write_setting(
     app: tauri::AppHandle, key: String, value: String,
-) -> Result<(), String> {
+) -> Result<(), BushidoError> {
     let state = app.state::<SyncState>();
-    if !state.enabled || !state.sync_settings.load(Ordering::Relaxed) { return Ok(()); }
+    if !state.syncs(DataType::Settings) { return Ok(()); }
     {
         let mut g = state.sync_doc.lock().await;
-        let doc = g.as_mut().ok_or("no doc")?;
+        let doc = g.as_mut().ok_or_else(|| BushidoError::Sync("no doc".into()))?;
         doc.write_setting(&key, &value)?;
         doc.save()?;
     }
@@ -1561,12 +1857,12 @@ pub async fn sync_write_setting(
 #[tauri::command]
 pub async fn sync_write_tabs(
     app: tauri::AppHandle, tabs: String,
-) -> Result<(), String> {
+) -> Result<(), BushidoError> {
     let state = app.state::<SyncState>();
-    if !state.enabled || !state.sync_tabs.load(Ordering::Relaxed) { return Ok(()); }
+    if !state.syncs(DataType::Tabs) { return Ok(()); }
     {
         let mut g = state.sync_doc.lock().await;
-        let doc = g.as_mut().ok_or("no doc")?;
+        let doc = g.as_mut().ok_or_else(|| BushidoError::Sync("no doc".into()))?;
         doc.write_tabs(&tabs)?;
         doc.save()?;
     }
@@ -1577,38 +1873,272 @@ pub async fn sync_write_tabs(
 #[tauri::command]
 pub async fn sync_get_all_tabs(
     app: tauri::AppHandle,
-) -> Result<String, String> {
+) -> Result<String, BushidoError> {
+    let state = app.state::<SyncState>();
+    if !state.syncs(DataType::Tabs) { return Ok("[]".into()); }
+    let g = state.sync_doc.lock().await;
+    let doc = g.as_ref().ok_or_else(|| BushidoError::Sync("no doc".into()))?;
+    Ok(doc.read_all_tabs()?)
+}
+
+/// Publish a tab group (see tab_groups.rs) to paired devices. Rides on the tabs
+/// data type: with tab sync off nothing is written.
+#[tauri::command]
+pub async fn sync_write_tab_group(
+    app: tauri::AppHandle, id: String, name: String, color: String, tab_ids: Vec<String>,
+) -> Result<(), BushidoError> {
+    let state = app.state::<SyncState>();
+    if !state.syncs(DataType::Tabs) { return Ok(()); }
+    {
+        let mut g = state.sync_doc.lock().await;
+        let doc = g.as_mut().ok_or_else(|| BushidoError::Sync("no doc".into()))?;
+        doc.write_tab_group(&id, &name, &color, &tab_ids)?;
+        doc.save()?;
+    }
+    notify_sync_change(&state);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn sync_remove_tab_group(
+    app: tauri::AppHandle, id: String,
+) -> Result<(), BushidoError> {
+    let state = app.state::<SyncState>();
+    if !state.syncs(DataType::Tabs) { return Ok(()); }
+    {
+        let mut g = state.sync_doc.lock().await;
+        let doc = g.as_mut().ok_or_else(|| BushidoError::Sync("no doc".into()))?;
+        doc.remove_tab_group(&id)?;
+        doc.save()?;
+    }
+    notify_sync_change(&state);
+    Ok(())
+}
+
+/// Synced tab groups from every device, `[{id, deviceId, name, color, tabIds}]`; re-read on
+/// `sync-tabs-changed`
+#[tauri::command]
+pub async fn sync_get_tab_groups(
+    app: tauri::AppHandle,
+) -> Result<String, BushidoError> {
+    let state = app.state::<SyncState>();
+    if !state.syncs(DataType::Tabs) { return Ok("[]".into()); }
+    let g = state.sync_doc.lock().await;
+    let doc = g.as_ref().ok_or_else(|| BushidoError::Sync("no doc".into()))?;
+    Ok(doc.read_tab_groups_json()?)
+}
+
+/// Put a reading list item (see reading_list.rs) on paired devices, or re-title it.
+/// Re-adding a read item makes it unread everywhere. Items are keyed by the same
+/// normalized URL as the local list, so `#section` variants are one item.
+#[tauri::command]
+pub async fn sync_reading_list_add(
+    app: tauri::AppHandle, url: String, title: String,
+) -> Result<(), BushidoError> {
+    let state = app.state::<SyncState>();
+    if !state.syncs(DataType::ReadingList) { return Ok(()); }
+    let url = crate::reading_list::normalize_url(&url)?;
+    {
+        let mut g = state.sync_doc.lock().await;
+        let doc = g.as_mut().ok_or_else(|| BushidoError::Sync("no doc".into()))?;
+        doc.write_reading_list_item(&url, &title, now_ms() as u64)?;
+        doc.save()?;
+    }
+    notify_sync_change(&state);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn sync_reading_list_remove(
+    app: tauri::AppHandle, url: String,
+) -> Result<(), BushidoError> {
+    let state = app.state::<SyncState>();
+    if !state.syncs(DataType::ReadingList) { return Ok(()); }
+    let url = crate::reading_list::normalize_url(&url)?;
+    {
+        let mut g = state.sync_doc.lock().await;
+        let doc = g.as_mut().ok_or_else(|| BushidoError::Sync("no doc".into()))?;
+        doc.remove_reading_list_item(&url)?;
+        doc.save()?;
+    }
+    notify_sync_change(&state);
+    Ok(())
+}
+
+/// Read state is last-writer-wins per item across devices
+#[tauri::command]
+pub async fn sync_reading_list_mark_read(
+    app: tauri::AppHandle, url: String, read: Option<bool>,
+) -> Result<(), BushidoError> {
+    let state = app.state::<SyncState>();
+    if !state.syncs(DataType::ReadingList) { return Ok(()); }
+    let url = crate::reading_list::normalize_url(&url)?;
+    {
+        let mut g = state.sync_doc.lock().await;
+        let doc = g.as_mut().ok_or_else(|| BushidoError::Sync("no doc".into()))?;
+        doc.set_reading_list_read(&url, read.unwrap_or(true), now_ms() as u64)?;
+        doc.save()?;
+    }
+    notify_sync_change(&state);
+    Ok(())
+}
+
+/// The synced reading list, newest first, in reading_list_get's shape; re-read on
+/// `sync-reading-list-changed`
+#[tauri::command]
+pub async fn sync_get_reading_list(
+    app: tauri::AppHandle,
+) -> Result<String, BushidoError> {
     let state = app.state::<SyncState>();
-    if !state.enabled { return Ok("[]".into()); }
+    if !state.syncs(DataType::ReadingList) { return Ok("[]".into()); }
     let g = state.sync_doc.lock().await;
-    let doc = g.as_ref().ok_or("no doc")?;
-    doc.read_all_tabs()
+    let doc = g.as_ref().ok_or_else(|| BushidoError::Sync("no doc".into()))?;
+    Ok(doc.read_reading_list_json()?)
+}
+
+/// Incremental bookmark delta since a token from load_bookmarks / a previous call.
+/// Returns `{bookmarks, folders, removed, token}`; on error the UI should do a full reload.
+#[tauri::command]
+pub async fn get_bookmark_changes(
+    app: tauri::AppHandle, since_token: String,
+) -> Result<String, BushidoError> {
+    let state = app.state::<SyncState>();
+    if !state.syncs(DataType::Bookmarks) { return Err(BushidoError::Sync("Bookmark sync not enabled".into())); }
+    let g = state.sync_doc.lock().await;
+    let doc = g.as_ref().ok_or_else(|| BushidoError::Sync("no doc".into()))?;
+    doc.read_bookmark_changes_json(&since_token).map_err(BushidoError::Sync)
+}
+
+/// Cap outgoing sync traffic in bytes per second; 0 lifts the cap.
+#[tauri::command]
+pub async fn set_sync_bandwidth_limit(
+    app: tauri::AppHandle, bytes_per_sec: u64,
+) -> Result<(), BushidoError> {
+    let state = app.state::<SyncState>();
+    state.bandwidth.limit_bps.store(bytes_per_sec, Ordering::Relaxed);
+    Ok(())
+}
+
+/// Only zstd-compress sync frames of at least `bytes` (default 512). Takes effect on
+/// the next sync.
+#[tauri::command]
+pub async fn set_sync_compression_threshold(
+    app: tauri::AppHandle, bytes: usize,
+) -> Result<(), BushidoError> {
+    let state = app.state::<SyncState>();
+    state.compress_threshold.store(bytes, Ordering::Relaxed);
+    Ok(())
+}
+
+/// Concurrent bookmark edits seen during recent syncs, oldest first.
+#[tauri::command]
+pub async fn get_sync_conflicts(
+    app: tauri::AppHandle,
+) -> Result<Vec<SyncConflict>, BushidoError> {
+    let state = app.state::<SyncState>();
+    let log = state.conflicts.lock();
+    Ok(log.iter().cloned().collect())
 }
 
 #[tauri::command]
 pub async fn sync_set_data_types(
-    app: tauri::AppHandle, bookmarks: bool, history: bool, settings: bool, tabs: bool,
-) -> Result<(), String> {
+    app: tauri::AppHandle, bookmarks: bool, history: bool, settings: bool, tabs: bool, reading_list: Option<bool>,
+) -> Result<(), BushidoError> {
     let state = app.state::<SyncState>();
-    state.sync_bookmarks.store(bookmarks, Ordering::Relaxed);
-    state.sync_history.store(history, Ordering::Relaxed);
-    state.sync_settings.store(settings, Ordering::Relaxed);
-    state.sync_tabs.store(tabs, Ordering::Relaxed);
+    let had_bookmarks = state.syncs(DataType::Bookmarks);
+    let had_tabs = state.syncs(DataType::Tabs);
+    // settings saved before the reading list existed don't send it
+    let reading_list = reading_list.unwrap_or(true);
+    state.set_data_types(DataTypes { bookmarks, history, settings, tabs, reading_list });
+    if !state.enabled { return Ok(()); }
+
+    let mut changed = false;
+    {
+        let mut g = state.sync_doc.lock().await;
+        let Some(doc) = g.as_mut() else { return Ok(()) };
+        if had_bookmarks && !bookmarks {
+            // bookmarks carry on locally from where the doc left off
+            std::fs::write(crate::bookmarks_path(&app), doc.read_bookmarks_as_json()?)?;
+        } else if !had_bookmarks && bookmarks {
+            // keep what was added here while off; peers' additions stay too
+            if let Ok(local) = std::fs::read_to_string(crate::bookmarks_path(&app)) {
+                changed |= doc.merge_missing_bookmarks_from_json(&local)? > 0;
+            }
+        }
+        if had_tabs && !tabs {
+            // peers drop our entry themselves once our Hello stops listing tabs
+            changed |= doc.remove_own_tabs()?;
+        }
+        if changed {
+            doc.save()?;
+        }
+    }
+    if changed {
+        notify_sync_change(&state);
+    }
+    if had_bookmarks != bookmarks {
+        // the bookmark source just switched between the doc and the local file
+        let _ = app.emit_to("main", DataType::Bookmarks.changed_event(), ());
+    }
     Ok(())
 }
 
+// cap per SendTabs message, checked on both ends
+const MAX_SENT_TABS: usize = 100;
+
 #[tauri::command]
 pub async fn send_tab_to_device(
     app: tauri::AppHandle, device_id: String, url: String, title: String,
-) -> Result<(), String> {
+) -> Result<(), BushidoError> {
     let state = app.state::<SyncState>();
-    if !state.enabled { return Err("Sync not enabled".into()); }
+    if !state.enabled { return Err(BushidoError::Sync("Sync not enabled".into())); }
 
     // validate url scheme
     if !sync_doc::is_safe_url_pub(&url) {
         return Err("blocked url scheme".into());
     }
 
+    let msg = SyncMessage::SendTab {
+        sender_device_id: state.device_id.clone(),
+        sender_device_name: state.device_name.clone(),
+        url: url.clone(),
+        title,
+    };
+    push_to_device(app.clone(), &device_id, msg, format!("sent tab: {}", &url))
+}
+
+/// Send a whole tab group in one message. Tabs with blocked url schemes are left out.
+#[tauri::command]
+pub async fn send_tabs_to_device(
+    app: tauri::AppHandle, device_id: String, tabs: Vec<protocol::SentTab>,
+) -> Result<(), BushidoError> {
+    let state = app.state::<SyncState>();
+    if !state.enabled { return Err(BushidoError::Sync("Sync not enabled".into())); }
+
+    let tabs: Vec<protocol::SentTab> = tabs.into_iter()
+        .filter(|t| sync_doc::is_safe_url_pub(&t.url))
+        .collect();
+    if tabs.is_empty() {
+        return Err("no tabs to send".into());
+    }
+    if tabs.len() > MAX_SENT_TABS {
+        return Err(BushidoError::Limit(format!("at most {} tabs can be sent at once", MAX_SENT_TABS)));
+    }
+
+    let count = tabs.len();
+    let msg = SyncMessage::SendTabs {
+        sender_device_id: state.device_id.clone(),
+        sender_device_name: state.device_name.clone(),
+        tabs,
+    };
+    push_to_device(app.clone(), &device_id, msg, format!("sent {} tabs", count))
+}
+
+/// Resolve a paired, discovered device and deliver `msg` to it in the background:
+/// connect, noise handshake, one message, wait for the ack. Logs `sent` on success.
+fn push_to_device(app: tauri::AppHandle, device_id: &str, msg: SyncMessage, sent: String) -> Result<(), BushidoError> {
+    let state = app.state::<SyncState>();
+
     // find peer address
     let (peer, addr) = {
         let devices = state.paired_devices.lock();
@@ -1626,9 +2156,9 @@ pub async fn send_tab_to_device(
 
     let private_key = state.noise_private_key.clone();
     let own_did = state.device_id.clone();
-    let own_name = state.device_name.clone();
+    let device_id = device_id.to_string();
 
-    // async: connect, noise handshake, send tab
+    // async: connect, noise handshake, send
     tauri::async_runtime::spawn(async move {
         let result = async {
             let timeout = tokio::time::Duration::from_secs(5);
@@ -1636,7 +2166,7 @@ pub async fn send_tab_to_device(
                 .await.map_err(|_| "connect timeout".to_string())?
                 .map_err(|e| format!("connect: {}", e))?;
 
-            protocol::send_message(&mut stream, &SyncMessage::SyncRequest { device_id: own_did.clone() }).await?;
+            protocol::send_message(&mut stream, &SyncMessage::SyncRequest { device_id: own_did }).await?;
             let resp = protocol::recv_message(&mut stream).await?;
             match resp {
                 SyncMessage::SyncAccept => {}
@@ -1652,13 +2182,8 @@ pub async fn send_tab_to_device(
                 return Err("key mismatch".into());
             }
 
-            // send SendTab instead of Hello
-            protocol::send_encrypted(&mut ns, &SyncMessage::SendTab {
-                sender_device_id: own_did,
-                sender_device_name: own_name,
-                url: url.clone(),
-                title: title.clone(),
-            }).await?;
+            // send the tab message instead of Hello
+            protocol::send_encrypted(&mut ns, &msg).await?;
 
             // wait for ack
             let _ = protocol::recv_encrypted(&mut ns).await;
@@ -1669,7 +2194,7 @@ pub async fn send_tab_to_device(
             eprintln!("[sync] send_tab failed: {}", e);
             emit_log(&app, "error", &format!("send tab failed: {}", e), Some(&device_id));
         } else {
-            emit_log(&app, "send", &format!("sent tab: {}", &url), Some(&device_id));
+            emit_log(&app, "send", &sent, Some(&device_id));
         }
     });
 
@@ -1679,9 +2204,9 @@ pub async fn send_tab_to_device(
 #[tauri::command]
 pub async fn reset_sync_data(
     app: tauri::AppHandle,
-) -> Result<(), String> {
+) -> Result<(), BushidoError> {
     let state = app.state::<SyncState>();
-    if !state.enabled { return Err("Sync not enabled".into()); }
+    if !state.enabled { return Err(BushidoError::Sync("Sync not enabled".into())); }
     {
         let mut g = state.sync_doc.lock().await;
         if let Some(ref doc) = *g {
@@ -1690,7 +2215,7 @@ pub async fn reset_sync_data(
         // reinit with fresh doc
         match SyncDoc::init(&state.app_data_dir, state.peer_id, &state.device_id) {
             Ok(doc) => *g = Some(doc),
-            Err(e) => return Err(format!("reset failed: {}", e)),
+            Err(e) => return Err(BushidoError::Sync(format!("reset failed: {}", e))),
         }
     }
     emit_log(&app, "sync", "sync data reset", None);
@@ -1699,6 +2224,74 @@ pub async fn reset_sync_data(
 
 // ── Activity log helper ──
 
+fn now_ms() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as i64
+}
+
+fn record_conflicts(app: &tauri::AppHandle, state: &SyncState, peer_device_id: &str, found: Vec<FieldConflict>) {
+    if found.is_empty() { return; }
+    let timestamp = now_ms();
+    let mut log = state.conflicts.lock();
+    for c in found {
+        let entry = SyncConflict {
+            device_id: peer_device_id.to_string(),
+            bookmark_id: c.bookmark_id,
+            field: c.field,
+            local_value: c.local,
+            remote_value: c.remote,
+            timestamp,
+        };
+        let _ = app.emit_to("main", "sync-conflict", &entry);
+        log.push_back(entry);
+        if log.len() > MAX_CONFLICTS {
+            log.pop_front();
+        }
+    }
+}
+
+// received titles end up in UI text
+fn escape_title(title: &str) -> String {
+    title.replace('<', "&lt;").replace('>', "&gt;")
+}
+
+// a sync that went through means whatever failed before has recovered
+fn record_sync_ok(state: &SyncState) {
+    let mut health = state.health.lock();
+    health.last_sync_at = Some(now_ms());
+    health.last_error = None;
+}
+
+/// Note a successful sync with `device_id`, in memory and in last_synced.json.
+fn record_peer_synced(state: &SyncState, device_id: &str) {
+    if !state.paired_devices.lock().iter().any(|d| d.device_id == device_id) {
+        return;
+    }
+    let snapshot = {
+        let mut last_synced = state.last_synced.lock();
+        last_synced.insert(device_id.to_string(), chrono::Utc::now().timestamp());
+        last_synced.clone()
+    };
+    let _ = keys::save_last_synced(&state.app_data_dir, &snapshot);
+}
+
+fn forget_peer_synced(state: &SyncState, device_id: &str) {
+    let snapshot = {
+        let mut last_synced = state.last_synced.lock();
+        if last_synced.remove(device_id).is_none() {
+            return;
+        }
+        last_synced.clone()
+    };
+    let _ = keys::save_last_synced(&state.app_data_dir, &snapshot);
+}
+
+fn record_sync_error(state: &SyncState, err: &str) {
+    state.health.lock().last_error = Some(format!("{} (at {})", err, now_ms()));
+}
+
 fn emit_log(app: &tauri::AppHandle, log_type: &str, message: &str, device: Option<&str>) {
     let _ = app.emit_to("main", "sync-log", serde_json::json!({
         "type": log_type,
@@ -1711,6 +2304,96 @@ fn emit_log(app: &tauri::AppHandle, log_type: &str, message: &str, device: Optio
     }));
 }
 
+// ── "While you were away" summary ──
+
+// version token of the doc at last shutdown
+const SESSION_TOKEN_FILE: &str = "last_session.token";
+// per list; totals are still exact
+const SESSION_SUMMARY_LIMIT: usize = 20;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SessionChange {
+    #[serde(flatten)]
+    change: AttributedChange,
+    device_name: Option<String>,
+}
+
+#[derive(Serialize)]
+struct SessionChangeList {
+    total: usize,
+    items: Vec<SessionChange>,
+}
+
+fn session_token_path(state: &SyncState) -> PathBuf {
+    state.app_data_dir.join("sync").join(SESSION_TOKEN_FILE)
+}
+
+/// Called on exit so the next launch can summarize what arrived since.
+pub fn store_session_token(app: &tauri::AppHandle) {
+    let state = app.state::<SyncState>();
+    if !state.enabled { return; }
+    // don't hold up shutdown behind a running sync — keeping the older token only
+    // makes the next summary cover a little more
+    let Ok(g) = state.sync_doc.try_lock() else { return };
+    if let Some(ref doc) = *g {
+        let _ = std::fs::write(session_token_path(&state), doc.version_token());
+    }
+}
+
+/// After the first successful sync of this launch, emit `sync-since-last-session` with
+/// the bookmarks other devices added, removed or changed since last shutdown.
+/// Runs once per launch; nothing is emitted when nothing changed.
+async fn emit_session_summary(app: &tauri::AppHandle) {
+    let state = app.state::<SyncState>();
+    if !state.session_summary_pending.swap(false, Ordering::Relaxed) { return; }
+    // the summary only covers bookmarks
+    if !state.syncs(DataType::Bookmarks) { return; }
+    // first launch with sync — nothing to compare against yet
+    let Ok(token) = std::fs::read_to_string(session_token_path(&state)) else { return };
+    let diff = {
+        let g = state.sync_doc.lock().await;
+        match g.as_ref().map(|doc| doc.bookmark_diff_since(token.trim())) {
+            Some(Ok(diff)) => diff,
+            // stale token after a reset — the doc it points into is gone
+            Some(Err(e)) => {
+                eprintln!("[sync] session summary skipped: {}", e);
+                return;
+            }
+            None => return,
+        }
+    };
+
+    let names: HashMap<String, String> = state.paired_devices.lock().iter()
+        .map(|d| (d.device_id.clone(), d.name.clone()))
+        .collect();
+    let list = |changes: Vec<AttributedChange>| {
+        // our own edits from before the first sync aren't news
+        let theirs: Vec<AttributedChange> = changes.into_iter()
+            .filter(|c| c.device_id.as_deref() != Some(state.device_id.as_str()))
+            .collect();
+        SessionChangeList {
+            total: theirs.len(),
+            items: theirs.into_iter().take(SESSION_SUMMARY_LIMIT).map(|change| SessionChange {
+                device_name: change.device_id.as_ref().and_then(|d| names.get(d)).cloned(),
+                change,
+            }).collect(),
+        }
+    };
+    let (added, removed, changed) = (list(diff.added), list(diff.removed), list(diff.changed));
+    if added.total + removed.total + changed.total == 0 { return; }
+
+    emit_log(app, "receive", &format!(
+        "since last session: {} added, {} removed, {} changed",
+        added.total, removed.total, changed.total
+    ), None);
+    let _ = app.emit_to("main", "sync-since-last-session", serde_json::json!({
+        "added": added,
+        "removed": removed,
+        "changed": changed,
+    }));
+}
+
 // ── Health check + compaction (Phase E) ──
 
 /// 60s health check: restart dead discovery/listener
@@ -1722,6 +2405,7 @@ pub fn start_health_check(app: tauri::AppHandle) {
             interval.tick().await;
             let state = app.state::<SyncState>();
             if !state.enabled { continue; }
+            state.health.lock().last_check_at = Some(now_ms());
 
             // check discovery alive
             let disc_alive = {
@@ -1730,6 +2414,7 @@ pub fn start_health_check(app: tauri::AppHandle) {
             };
             if !disc_alive {
                 eprintln!("[sync] health: discovery dead, restarting");
+                state.health.lock().discovery_restarts += 1;
                 if let Ok(mut disc) = DiscoveryService::new() {
                     let _ = disc.register(&state.device_id, &state.device_name, &state.fingerprint);
                     let _ = disc.start_browsing(app.clone(), state.device_id.clone());
@@ -1744,6 +2429,7 @@ pub fn start_health_check(app: tauri::AppHandle) {
             };
             if !has_handle {
                 eprintln!("[sync] health: listener handle missing, restarting");
+                state.health.lock().listener_restarts += 1;
                 start_tcp_listener(app.clone());
             }
         }
@@ -1,4 +1,6 @@
+use crate::downloads::RateLimiter;
 use snow::TransportState;
+use std::sync::Arc;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::TcpStream;
 
@@ -12,6 +14,13 @@ pub struct NoiseStream {
     transport: TransportState,
     send_buf: Vec<u8>,
     recv_buf: Vec<u8>,
+    /// set once both sides advertised CAP_ZSTD with their Hello/HelloAck
+    pub compress: bool,
+    /// smallest frame worth compressing once `compress` is on
+    pub compress_threshold: usize,
+    pub stats: super::protocol::FrameStats,
+    /// paces outgoing frames when a sync bandwidth cap is set
+    pub throttle: Option<Arc<RateLimiter>>,
 }
 
 impl NoiseStream {
@@ -55,6 +64,10 @@ impl NoiseStream {
             transport,
             send_buf: vec![0u8; MAX_MSG_LEN],
             recv_buf: vec![0u8; MAX_MSG_LEN],
+            compress: false,
+            compress_threshold: super::protocol::DEFAULT_COMPRESS_THRESHOLD,
+            stats: Default::default(),
+            throttle: None,
         })
     }
 
@@ -98,6 +111,10 @@ impl NoiseStream {
             transport,
             send_buf: vec![0u8; MAX_MSG_LEN],
             recv_buf: vec![0u8; MAX_MSG_LEN],
+            compress: false,
+            compress_threshold: super::protocol::DEFAULT_COMPRESS_THRESHOLD,
+            stats: Default::default(),
+            throttle: None,
         })
     }
 
@@ -107,6 +124,9 @@ impl NoiseStream {
             .transport
             .write_message(plaintext, &mut self.send_buf)
             .map_err(|e| format!("noise encrypt: {}", e))?;
+        if let Some(ref throttle) = self.throttle {
+            throttle.acquire_large(len as u64).await;
+        }
         send_frame(&mut self.stream, &self.send_buf[..len]).await
     }
 
@@ -4,6 +4,40 @@ use tokio::net::TcpStream;
 
 const MAX_MSG_LEN: usize = 65535;
 
+/// Capability bits advertised in the HelloExt trailing a Hello/HelloAck
+pub const CAP_ZSTD: u32 = 1;
+/// Understands SyncSlices; HelloExt::slices lists the types this side exchanges
+pub const CAP_SLICES: u32 = 1 << 1;
+/// Frames smaller than this go out uncompressed: below it the zstd frame header
+/// eats most of the gain. Adjustable with set_sync_compression_threshold.
+pub const DEFAULT_COMPRESS_THRESHOLD: usize = 512;
+const ZSTD_LEVEL: i32 = 3;
+// 0xc1 is never used by MessagePack, so a plain frame can't start with it
+const ZSTD_MARKER: u8 = 0xc1;
+// recv_large already caps the wire size; this caps what a small frame may expand to
+const MAX_DECOMPRESSED_LEN: usize = 64 * 1024 * 1024;
+
+/// One tab in a SendTabs group
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SentTab {
+    pub url: String,
+    pub title: String,
+}
+
+/// The version a side has of one data type's doc
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SliceVv {
+    pub kind: String,
+    pub vv: Vec<u8>,
+}
+
+/// One data type's changes in a SyncSlices message
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SliceDelta {
+    pub kind: String,
+    pub data: Vec<u8>, // lz4-compressed loro delta
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum SyncMessage {
     // pairing (phase B)
@@ -20,6 +54,7 @@ pub enum SyncMessage {
     SyncAccept,
 
     // sync protocol (encrypted, over NoiseStream)
+    // fields are positional on the wire, so these can't grow; see HelloExt
     Hello { device_id: String, vv: Vec<u8> },
     HelloAck { vv: Vec<u8> },
     SyncDelta { data: Vec<u8> },    // lz4-compressed loro delta
@@ -34,12 +69,33 @@ pub enum SyncMessage {
         url: String,
         title: String,
     },
+    // a whole tab group in one message, acked with SendTabAck
+    SendTabs {
+        sender_device_id: String,
+        sender_device_name: String,
+        tabs: Vec<SentTab>,
+    },
     SendTabAck,
 
     // general
     Close { reason: String },
     Ping,
     Pong,
+
+    // sent after SyncDelta/SyncUpToDate when both sides have CAP_SLICES
+    SyncSlices { slices: Vec<SliceDelta> },
+}
+
+/// Capabilities sent after a Hello/HelloAck in the same frame. Older builds decode the
+/// message and never look at the trailing bytes, so they simply don't advertise any.
+/// Encoded as a map so later fields can be added without breaking this build.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HelloExt {
+    #[serde(default)]
+    pub caps: u32,
+    /// with CAP_SLICES: the non-bookmark types this side exchanges, and its version of each
+    #[serde(default)]
+    pub slices: Vec<SliceVv>,
 }
 
 /// Encode a SyncMessage to MessagePack bytes.
@@ -52,6 +108,22 @@ pub fn decode(bytes: &[u8]) -> Result<SyncMessage, String> {
     rmp_serde::from_slice(bytes).map_err(|e| format!("decode: {}", e))
 }
 
+/// Encode a message followed by its HelloExt.
+pub fn encode_with_ext(msg: &SyncMessage, ext: &HelloExt) -> Result<Vec<u8>, String> {
+    let mut data = encode(msg)?;
+    data.extend(rmp_serde::to_vec_named(ext).map_err(|e| format!("encode ext: {}", e))?);
+    Ok(data)
+}
+
+/// Decode a message and the HelloExt after it, if any. A missing or unreadable
+/// extension (an older peer) reads as no capabilities.
+pub fn decode_with_ext(bytes: &[u8]) -> Result<(SyncMessage, HelloExt), String> {
+    let mut rest = bytes;
+    let msg = rmp_serde::from_read(&mut rest).map_err(|e| format!("decode: {}", e))?;
+    let ext = if rest.is_empty() { HelloExt::default() } else { rmp_serde::from_slice(rest).unwrap_or_default() };
+    Ok((msg, ext))
+}
+
 /// Send a length-prefixed MessagePack message over TCP (unencrypted — for pairing).
 pub async fn send_message(stream: &mut TcpStream, msg: &SyncMessage) -> Result<(), String> {
     let data = encode(msg)?;
@@ -84,14 +156,75 @@ pub async fn recv_message(stream: &mut TcpStream) -> Result<SyncMessage, String>
     decode(&buf)
 }
 
+// ── Payload compression ─────────────────────────────────────────────────
+
+/// Bytes before/after compression for the frames sent on one stream
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FrameStats {
+    pub raw_bytes: usize,
+    pub wire_bytes: usize,
+}
+
+impl FrameStats {
+    /// wire/raw, e.g. 0.4 means 60% saved. None if nothing was sent.
+    pub fn ratio(&self) -> Option<f64> {
+        if self.raw_bytes == 0 { return None; }
+        Some(self.wire_bytes as f64 / self.raw_bytes as f64)
+    }
+}
+
+/// zstd-compress an encoded frame if it's at least `threshold` bytes and actually
+/// shrinks. CRDT deltas are already lz4'd, so this mostly pays off on large snapshots.
+pub fn compress_frame(data: Vec<u8>, threshold: usize) -> Vec<u8> {
+    if data.len() < threshold {
+        return data;
+    }
+    match zstd::bulk::compress(&data, ZSTD_LEVEL) {
+        Ok(c) if c.len() + 1 < data.len() => {
+            let mut out = Vec::with_capacity(c.len() + 1);
+            out.push(ZSTD_MARKER);
+            out.extend_from_slice(&c);
+            out
+        }
+        _ => data,
+    }
+}
+
+/// Inverse of compress_frame; plain frames pass through unchanged.
+pub fn decompress_frame(data: Vec<u8>) -> Result<Vec<u8>, String> {
+    if data.first() != Some(&ZSTD_MARKER) {
+        return Ok(data);
+    }
+    zstd::bulk::decompress(&data[1..], MAX_DECOMPRESSED_LEN).map_err(|e| format!("zstd decompress: {}", e))
+}
+
 // ── Encrypted transport (over NoiseStream) ──────────────────────────────
 
-/// Send a SyncMessage over an encrypted NoiseStream.
+/// Send a SyncMessage over an encrypted NoiseStream, compressed if negotiated.
 pub async fn send_encrypted(
     ns: &mut super::noise::NoiseStream,
     msg: &SyncMessage,
 ) -> Result<(), String> {
     let data = encode(msg)?;
+    send_frame(ns, data).await
+}
+
+/// Send a Hello/HelloAck with our capabilities. Always uncompressed: compression
+/// isn't agreed on until both sides have seen each other's.
+pub async fn send_hello(
+    ns: &mut super::noise::NoiseStream,
+    msg: &SyncMessage,
+    ext: &HelloExt,
+) -> Result<(), String> {
+    let data = encode_with_ext(msg, ext)?;
+    send_frame(ns, data).await
+}
+
+async fn send_frame(ns: &mut super::noise::NoiseStream, data: Vec<u8>) -> Result<(), String> {
+    let raw_len = data.len();
+    let data = if ns.compress { compress_frame(data, ns.compress_threshold) } else { data };
+    ns.stats.raw_bytes += raw_len;
+    ns.stats.wire_bytes += data.len();
     ns.send_large(&data).await
 }
 
@@ -99,8 +232,15 @@ pub async fn send_encrypted(
 pub async fn recv_encrypted(
     ns: &mut super::noise::NoiseStream,
 ) -> Result<SyncMessage, String> {
+    Ok(recv_with_ext(ns).await?.0)
+}
+
+/// Receive a SyncMessage and the HelloExt it carries — the first message of a sync.
+pub async fn recv_with_ext(
+    ns: &mut super::noise::NoiseStream,
+) -> Result<(SyncMessage, HelloExt), String> {
     let data = ns.recv_large().await?;
-    decode(&data)
+    decode_with_ext(&decompress_frame(data)?)
 }
 
 #[cfg(test)]
@@ -170,6 +310,18 @@ mod tests {
         });
     }
 
+    #[test]
+    fn roundtrip_send_tabs() {
+        roundtrip(&SyncMessage::SendTabs {
+            sender_device_id: "device-xyz".into(),
+            sender_device_name: "Laptop".into(),
+            tabs: vec![
+                SentTab { url: "https://a.com".into(), title: "A".into() },
+                SentTab { url: "https://b.com/<x>".into(), title: "B & co".into() },
+            ],
+        });
+    }
+
     #[test]
     fn roundtrip_sync_accept() {
         roundtrip(&SyncMessage::SyncAccept);
@@ -190,6 +342,103 @@ mod tests {
         });
     }
 
+    // what a build from before capability negotiation decodes into
+    // (variants up to HelloAck, in the same order, so the encoding matches)
+    #[derive(Debug, Serialize, Deserialize)]
+    #[allow(dead_code)]
+    enum OldMessage {
+        PairRequest { device_id: String, device_name: String },
+        PairAccept { device_id: String, device_name: String },
+        PairReject { reason: String },
+        SpakeMsg { msg: Vec<u8> },
+        PairConfirm { hmac: Vec<u8> },
+        PairKeyExchange { encrypted_public_key: Vec<u8> },
+        PairComplete,
+        SyncRequest { device_id: String },
+        SyncAccept,
+        Hello { device_id: String, vv: Vec<u8> },
+        HelloAck { vv: Vec<u8> },
+    }
+
+    #[test]
+    fn old_build_decodes_hello_with_ext() {
+        let ext = HelloExt { caps: CAP_ZSTD, ..HelloExt::default() };
+        let hello = encode_with_ext(&SyncMessage::Hello { device_id: "new".into(), vv: vec![1, 2] }, &ext).unwrap();
+        match rmp_serde::from_slice::<OldMessage>(&hello).unwrap() {
+            OldMessage::Hello { device_id, vv } => {
+                assert_eq!(device_id, "new");
+                assert_eq!(vv, vec![1, 2]);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        let ack = encode_with_ext(&SyncMessage::HelloAck { vv: vec![3] }, &ext).unwrap();
+        assert!(matches!(rmp_serde::from_slice::<OldMessage>(&ack).unwrap(), OldMessage::HelloAck { .. }));
+    }
+
+    #[test]
+    fn hello_from_old_build_has_no_caps() {
+        let old = rmp_serde::to_vec(&OldMessage::Hello { device_id: "old".into(), vv: vec![1] }).unwrap();
+        let (msg, ext) = decode_with_ext(&old).unwrap();
+        assert!(matches!(msg, SyncMessage::Hello { ref device_id, .. } if device_id == "old"));
+        assert_eq!(ext.caps, 0);
+    }
+
+    #[test]
+    fn slices_in_ext_are_optional_both_ways() {
+        #[derive(Debug, Serialize, Deserialize)]
+        struct OldExt {
+            caps: u32,
+        }
+        let ext = HelloExt {
+            caps: CAP_ZSTD | CAP_SLICES,
+            slices: vec![SliceVv { kind: "history".into(), vv: vec![1, 2] }],
+        };
+        let old: OldExt = rmp_serde::from_slice(&rmp_serde::to_vec_named(&ext).unwrap()).unwrap();
+        assert_eq!(old.caps, CAP_ZSTD | CAP_SLICES);
+        let from_old: HelloExt = rmp_serde::from_slice(&rmp_serde::to_vec_named(&OldExt { caps: CAP_ZSTD }).unwrap()).unwrap();
+        assert_eq!(from_old, HelloExt { caps: CAP_ZSTD, slices: Vec::new() });
+    }
+
+    #[test]
+    fn hello_ext_roundtrip() {
+        let ext = HelloExt { caps: CAP_ZSTD, slices: vec![SliceVv { kind: "tabs".into(), vv: vec![7] }] };
+        let data = encode_with_ext(&SyncMessage::HelloAck { vv: vec![9; 40] }, &ext).unwrap();
+        let (msg, got) = decode_with_ext(&data).unwrap();
+        assert!(matches!(msg, SyncMessage::HelloAck { ref vv } if vv.len() == 40));
+        assert_eq!(got, ext);
+        // garbage after the message is ignored rather than failing the sync
+        let mut junk = encode(&SyncMessage::Ping).unwrap();
+        junk.extend_from_slice(&[0xc1, 0xff]);
+        assert_eq!(decode_with_ext(&junk).unwrap().1, HelloExt::default());
+    }
+
+    #[test]
+    fn compressed_frame_roundtrip() {
+        let msg = SyncMessage::SyncSnapshot { data: b"bookmark ".repeat(2000) };
+        let raw = encode(&msg).unwrap();
+        let frame = compress_frame(raw.clone(), DEFAULT_COMPRESS_THRESHOLD);
+        assert_eq!(frame[0], ZSTD_MARKER);
+        assert!(frame.len() < raw.len());
+        let decoded = decode(&decompress_frame(frame).unwrap()).unwrap();
+        assert_eq!(format!("{:?}", msg), format!("{:?}", decoded));
+    }
+
+    #[test]
+    fn small_frame_stays_uncompressed() {
+        let raw = encode(&SyncMessage::SyncAck).unwrap();
+        let frame = compress_frame(raw.clone(), DEFAULT_COMPRESS_THRESHOLD);
+        assert_eq!(frame, raw);
+        assert!(matches!(decode(&decompress_frame(frame).unwrap()).unwrap(), SyncMessage::SyncAck));
+    }
+
+    #[test]
+    fn threshold_is_respected() {
+        let raw = encode(&SyncMessage::SyncDelta { data: vec![7; 300] }).unwrap();
+        assert_eq!(compress_frame(raw.clone(), DEFAULT_COMPRESS_THRESHOLD), raw);
+        assert_eq!(compress_frame(raw.clone(), 64)[0], ZSTD_MARKER);
+        assert_eq!(compress_frame(raw.clone(), usize::MAX), raw);
+    }
+
     #[test]
     fn roundtrip_sync_delta() {
         roundtrip(&SyncMessage::SyncDelta {
@@ -1,6 +1,7 @@
-use loro::{ExportMode, LoroDoc, LoroMap, TreeID};
+use loro::{ExportMode, LoroDoc, LoroMap, LoroTree, TreeID};
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 
 const COMPACT_THRESHOLD: u32 = 500;
@@ -8,12 +9,28 @@ const TREE_BOOKMARKS: &str = "bookmarks";
 const MAP_HISTORY: &str = "history";
 const MAP_SETTINGS: &str = "settings";
 const MAP_TABS: &str = "open_tabs";
+// group id → JSON {name, color}, and tab id → group id: each tab's membership is its
+// own key, so it resolves last-writer-wins per tab
+const MAP_TAB_GROUPS: &str = "tab_groups";
+const MAP_TAB_GROUP_MEMBERS: &str = "tab_group_members";
+const MAX_GROUP_NAME_LEN: usize = 64;
+// url → JSON {title, addedAtMs}, and url → JSON {read, atMs}: read state is its own
+// key so it never clobbers a re-title, and concurrent marks resolve last-writer-wins
+const MAP_READING_LIST: &str = "reading_list";
+const MAP_READING_LIST_READ: &str = "reading_list_read";
+const MAX_READING_TITLE_LEN: usize = 512;
+// loro peer id → device_id, so changes can be attributed to the device that made them
+const MAP_PEERS: &str = "peers";
+// each tag is its own "tag:<name>" key on the bookmark's meta map, so concurrent
+// add/remove of different tags never conflict and the same tag resolves last-writer-wins
+const TAG_PREFIX: &str = "tag:";
+const MAX_TAG_LEN: usize = 64;
 
 // device-local settings (never synced)
 const DEVICE_LOCAL: &[&str] = &[
     "compactMode", "suspendTimeout", "downloadLocation",
     "askDownloadLocation", "onStartup", "syncDeviceName",
-    "syncEnabled", "onboardingComplete",
+    "syncEnabled", "onboardingComplete", "syncBandwidthLimit",
 ];
 
 // dangerous uri schemes (blocked on receive)
@@ -31,7 +48,7 @@ struct BookmarkData {
     folders: Vec<BookmarkFolder>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 struct Bookmark {
     id: String,
     url: String,
@@ -42,9 +59,11 @@ struct Bookmark {
     folder_id: String,
     #[serde(rename = "createdAt")]
     created_at: f64,
+    #[serde(default)]
+    tags: Vec<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 struct BookmarkFolder {
     id: String,
     name: String,
@@ -53,10 +72,116 @@ struct BookmarkFolder {
     order: f64,
 }
 
+// delta returned by read_bookmark_changes_json — upserts carry the full record
+#[derive(Debug, Serialize)]
+struct BookmarkChanges {
+    bookmarks: Vec<Bookmark>,
+    folders: Vec<BookmarkFolder>,
+    removed: Vec<String>,
+    token: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SyncedTabGroup {
+    id: String,
+    device_id: String,
+    name: String,
+    color: String,
+    tab_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SyncedReadingItem {
+    url: String,
+    title: String,
+    added_at_ms: u64,
+    read: bool,
+    read_at_ms: Option<u64>,
+}
+
+/// A bookmark or folder in a session diff, with the device that made the change
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttributedChange {
+    pub id: String,
+    pub kind: &'static str,
+    /// bookmark title or folder name
+    pub title: String,
+    pub url: Option<String>,
+    /// None when the editing peer never registered a device (older builds)
+    pub device_id: Option<String>,
+}
+
+/// A bookmark field this device and a peer both changed since they last synced.
+/// The CRDT has already settled on one value; this only records that it happened.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldConflict {
+    pub bookmark_id: String,
+    pub field: &'static str,
+    pub local: String,
+    pub remote: String,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct SessionDiff {
+    pub added: Vec<AttributedChange>,
+    pub removed: Vec<AttributedChange>,
+    pub changed: Vec<AttributedChange>,
+}
+
+/// A data type that can be left out of a sync on its own. Bookmarks (and the peers map
+/// used to attribute them) live in the main doc; every other type has a doc of its own,
+/// since one causal doc can't be exported or imported per container.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DataType {
+    Bookmarks,
+    History,
+    Settings,
+    Tabs,
+    ReadingList,
+}
+
+impl DataType {
+    pub const ALL: [DataType; 5] = [DataType::Bookmarks, DataType::History, DataType::Settings, DataType::Tabs, DataType::ReadingList];
+    /// The types kept outside the main doc
+    pub const SLICES: [DataType; 4] = [DataType::History, DataType::Settings, DataType::Tabs, DataType::ReadingList];
+
+    /// Stable name, used on the wire and in the slice's file name
+    pub fn key(self) -> &'static str {
+        match self {
+            DataType::Bookmarks => "bookmarks",
+            DataType::History => "history",
+            DataType::Settings => "settings",
+            DataType::Tabs => "tabs",
+            DataType::ReadingList => "reading_list",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|t| t.key() == key)
+    }
+
+    // the maps this type owned in the main doc before it moved to its own
+    fn containers(self) -> &'static [&'static str] {
+        match self {
+            DataType::Bookmarks => &[],
+            DataType::History => &[MAP_HISTORY],
+            DataType::Settings => &[MAP_SETTINGS],
+            DataType::Tabs => &[MAP_TABS, MAP_TAB_GROUPS, MAP_TAB_GROUP_MEMBERS],
+            DataType::ReadingList => &[MAP_READING_LIST, MAP_READING_LIST_READ],
+        }
+    }
+}
+
 // ── main struct ──
 
 pub struct SyncDoc {
+    /// bookmarks and peers; what Hello's version vector describes
     pub(crate) doc: LoroDoc,
+    /// one doc per entry of DataType::SLICES
+    slices: Vec<(DataType, LoroDoc)>,
     save_path: PathBuf,
     op_count: u32,
     device_id: String,
@@ -93,8 +218,35 @@ impl SyncDoc {
         // enable fractional index for ordered tree children
         doc.get_tree(TREE_BOOKMARKS).enable_fractional_index(0);
 
+        let mut slices = Vec::new();
+        for t in DataType::SLICES {
+            let path = slice_path(&save_path, t);
+            let slice = LoroDoc::new();
+            slice.set_peer_id(peer_id).map_err(|e| format!("set peer: {}", e))?;
+            if path.exists() {
+                let bytes = std::fs::read(&path).map_err(|e| format!("read {}: {}", path.display(), e))?;
+                slice.import(&bytes).map_err(|e| format!("import {}: {}", path.display(), e))?;
+            } else {
+                // first run with split docs: carry over what older builds kept in the main doc
+                for name in t.containers() {
+                    copy_map(&doc.get_map(*name), &slice.get_map(*name));
+                }
+            }
+            slices.push((t, slice));
+        }
+
+        // announce which device this peer is, for attribution on other devices
+        if !device_id.is_empty() {
+            let peers = doc.get_map(MAP_PEERS);
+            let key = peer_id.to_string();
+            if get_map_str(&peers, &key).as_deref() != Some(device_id) {
+                let _ = peers.insert(&key, device_id);
+            }
+        }
+
         Ok(SyncDoc {
             doc,
+            slices,
             save_path,
             op_count: 0,
             device_id: device_id.to_string(),
@@ -115,6 +267,12 @@ impl SyncDoc {
             .map_err(|e| format!("export delta: {}", e))
     }
 
+    /// Opaque cursor for get_bookmark_changes (base64 of the encoded version vector)
+    pub fn version_token(&self) -> String {
+        use base64::Engine as _;
+        base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(self.version_vector())
+    }
+
     pub fn export_snapshot(&self) -> Result<Vec<u8>, String> {
         self.doc
             .export(ExportMode::Snapshot)
@@ -134,18 +292,49 @@ impl SyncDoc {
         Ok(())
     }
 
+    /// The doc holding `t`; bookmarks are in the main doc
+    fn slice(&self, t: DataType) -> &LoroDoc {
+        self.slices.iter()
+            .find(|(kind, _)| *kind == t)
+            .map_or(&self.doc, |(_, doc)| doc)
+    }
+
+    pub fn slice_version_vector(&self, t: DataType) -> Vec<u8> {
+        self.slice(t).oplog_vv().encode()
+    }
+
+    /// `t`'s changes since `from_vv`, or nothing when the peer already has them all
+    pub fn export_slice(&self, t: DataType, from_vv: &[u8]) -> Result<Vec<u8>, String> {
+        let vv = loro::VersionVector::decode(from_vv)
+            .map_err(|e| format!("decode vv: {}", e))?;
+        let doc = self.slice(t);
+        if matches!(doc.oplog_vv().partial_cmp(&vv), Some(std::cmp::Ordering::Less | std::cmp::Ordering::Equal)) {
+            return Ok(Vec::new());
+        }
+        doc.export(ExportMode::Updates { from: Cow::Owned(vv) })
+            .map_err(|e| format!("export {}: {}", t.key(), e))
+    }
+
+    pub fn import_slice(&mut self, t: DataType, bytes: &[u8]) -> Result<(), String> {
+        self.slice(t).import(bytes).map_err(|e| format!("import {}: {}", t.key(), e))?;
+        Ok(())
+    }
+
     pub fn save(&self) -> Result<(), String> {
-        let bytes = self.export_snapshot()?;
-        let tmp = self.save_path.with_extension("loro.tmp");
-        std::fs::write(&tmp, &bytes).map_err(|e| format!("write tmp: {}", e))?;
-        std::fs::rename(&tmp, &self.save_path).map_err(|e| format!("rename: {}", e))?;
+        write_atomic(&self.save_path, &self.export_snapshot()?)?;
+        for (t, slice) in &self.slices {
+            let bytes = slice.export(ExportMode::Snapshot)
+                .map_err(|e| format!("export {}: {}", t.key(), e))?;
+            write_atomic(&slice_path(&self.save_path, *t), &bytes)?;
+        }
         Ok(())
     }
 
     pub fn backup(&self) {
-        if self.save_path.exists() {
-            let bak = self.save_path.with_extension("loro.bak");
-            let _ = std::fs::copy(&self.save_path, &bak);
+        let paths = std::iter::once(self.save_path.clone())
+            .chain(self.slices.iter().map(|(t, _)| slice_path(&self.save_path, *t)));
+        for path in paths.filter(|p| p.exists()) {
+            let _ = std::fs::copy(&path, path.with_extension("loro.bak"));
         }
     }
 
@@ -168,13 +357,12 @@ impl SyncDoc {
         if self.op_count < COMPACT_THRESHOLD {
             return Ok(());
         }
-        let snapshot = self.export_snapshot()?;
-        let peer = self.doc.peer_id();
-        let new_doc = LoroDoc::new();
-        new_doc.set_peer_id(peer).map_err(|e| format!("set peer: {}", e))?;
-        new_doc.import(&snapshot).map_err(|e| format!("compact: {}", e))?;
+        let new_doc = reloaded(&self.doc)?;
         new_doc.get_tree(TREE_BOOKMARKS).enable_fractional_index(0);
         self.doc = new_doc;
+        for (_, slice) in self.slices.iter_mut() {
+            *slice = reloaded(slice)?;
+        }
         self.op_count = 0;
         Ok(())
     }
@@ -195,67 +383,249 @@ impl SyncDoc {
             let _ = tree.delete(node_id);
         }
 
-        // write folders
         for folder in &data.folders {
-            let node = tree.create(None).map_err(|e| format!("create folder: {}", e))?;
-            let meta = tree.get_meta(node).map_err(|e| format!("get meta: {}", e))?;
-            set_map_str(&meta, "type", "folder");
-            set_map_str(&meta, "id", &folder.id);
-            set_map_str(&meta, "name", &folder.name);
-            set_map_str(&meta, "parentId", &folder.parent_id);
-            set_map_f64(&meta, "order", folder.order);
+            write_folder_node(&tree, folder)?;
         }
-
-        // write bookmarks
         for bm in &data.bookmarks {
-            let node = tree.create(None).map_err(|e| format!("create bookmark: {}", e))?;
-            let meta = tree.get_meta(node).map_err(|e| format!("get meta: {}", e))?;
-            set_map_str(&meta, "type", "bookmark");
-            set_map_str(&meta, "id", &bm.id);
-            set_map_str(&meta, "url", &bm.url);
-            set_map_str(&meta, "title", &bm.title);
-            if let Some(ref fav) = bm.favicon {
-                set_map_str(&meta, "favicon", fav);
-            }
-            set_map_str(&meta, "folderId", &bm.folder_id);
-            set_map_f64(&meta, "createdAt", bm.created_at);
+            write_bookmark_node(&tree, bm)?;
         }
 
         Ok(())
     }
 
-    pub fn read_bookmarks_as_json(&self) -> Result<String, String> {
-        let tree = self.doc.get_tree(TREE_BOOKMARKS);
-        let mut bookmarks = Vec::new();
-        let mut folders = Vec::new();
+    /// Add bookmarks and folders from `json` whose ids the doc doesn't have yet. Used when
+    /// bookmark sync is turned back on: edits made locally while it was off are folded in
+    /// without clobbering what peers added in the meantime. Returns how many were added.
+    pub fn merge_missing_bookmarks_from_json(&mut self, json: &str) -> Result<usize, String> {
+        let data: BookmarkData =
+            serde_json::from_str(json).map_err(|e| format!("parse: {}", e))?;
+        let (bookmarks, folders) = read_bookmark_nodes(&self.doc)?;
+        let known: HashSet<String> = bookmarks.into_iter().map(|b| b.id)
+            .chain(folders.into_iter().map(|f| f.id))
+            .collect();
 
-        for node_id in tree.children(None).unwrap_or_default() {
-            let meta = tree.get_meta(node_id).map_err(|e| format!("read meta: {}", e))?;
-            let node_type = get_map_str(&meta, "type").unwrap_or_default();
-
-            if node_type == "folder" {
-                folders.push(BookmarkFolder {
-                    id: get_map_str(&meta, "id").unwrap_or_default(),
-                    name: get_map_str(&meta, "name").unwrap_or_default(),
-                    parent_id: get_map_str(&meta, "parentId").unwrap_or_default(),
-                    order: get_map_f64(&meta, "order").unwrap_or(0.0),
-                });
-            } else if node_type == "bookmark" {
-                bookmarks.push(Bookmark {
-                    id: get_map_str(&meta, "id").unwrap_or_default(),
-                    url: get_map_str(&meta, "url").unwrap_or_default(),
-                    title: get_map_str(&meta, "title").unwrap_or_default(),
-                    favicon: get_map_str(&meta, "favicon"),
-                    folder_id: get_map_str(&meta, "folderId").unwrap_or_default(),
-                    created_at: get_map_f64(&meta, "createdAt").unwrap_or(0.0),
-                });
-            }
+        let tree = self.doc.get_tree(TREE_BOOKMARKS);
+        let mut added = 0;
+        for folder in data.folders.iter().filter(|f| !known.contains(&f.id)) {
+            write_folder_node(&tree, folder)?;
+            added += 1;
+        }
+        for bm in data.bookmarks.iter().filter(|b| !known.contains(&b.id)) {
+            write_bookmark_node(&tree, bm)?;
+            added += 1;
+        }
+        if added > 0 {
+            self.maybe_compact()?;
         }
+        Ok(added)
+    }
 
+    pub fn read_bookmarks_as_json(&self) -> Result<String, String> {
+        let (bookmarks, folders) = read_bookmark_nodes(&self.doc)?;
         let data = BookmarkData { bookmarks, folders };
         serde_json::to_string(&data).map_err(|e| format!("serialize: {}", e))
     }
 
+    /// Same as read_bookmarks_as_json plus the current version token
+    pub fn read_bookmarks_with_token_json(&self) -> Result<String, String> {
+        let (bookmarks, folders) = read_bookmark_nodes(&self.doc)?;
+        serde_json::to_string(&serde_json::json!({
+            "bookmarks": bookmarks,
+            "folders": folders,
+            "token": self.version_token(),
+        })).map_err(|e| format!("serialize: {}", e))
+    }
+
+    /// Bookmarks/folders added, updated or removed since `token`, plus a fresh token.
+    /// Errors on a token this doc has never seen (e.g. after reset) — caller should full-reload.
+    pub fn read_bookmark_changes_json(&self, token: &str) -> Result<String, String> {
+        let since = self.decode_token(token)?;
+        let current = self.doc.oplog_vv();
+
+        let mut changes = BookmarkChanges {
+            bookmarks: Vec::new(),
+            folders: Vec::new(),
+            removed: Vec::new(),
+            token: self.version_token(),
+        };
+        if since == current {
+            return serde_json::to_string(&changes).map_err(|e| format!("serialize: {}", e));
+        }
+
+        let old_doc = self.fork_at(&since)?;
+        let (old_bms, old_folders) = read_bookmark_nodes(&old_doc)?;
+        let (new_bms, new_folders) = read_bookmark_nodes(&self.doc)?;
+
+        let old_bms: HashMap<String, Bookmark> = old_bms.into_iter().map(|b| (b.id.clone(), b)).collect();
+        let old_folders: HashMap<String, BookmarkFolder> = old_folders.into_iter().map(|f| (f.id.clone(), f)).collect();
+        let mut seen: HashSet<String> = HashSet::new();
+
+        for bm in new_bms {
+            seen.insert(bm.id.clone());
+            if old_bms.get(&bm.id) != Some(&bm) {
+                changes.bookmarks.push(bm);
+            }
+        }
+        for folder in new_folders {
+            seen.insert(folder.id.clone());
+            if old_folders.get(&folder.id) != Some(&folder) {
+                changes.folders.push(folder);
+            }
+        }
+        for id in old_bms.keys().chain(old_folders.keys()) {
+            if !seen.contains(id) {
+                changes.removed.push(id.clone());
+            }
+        }
+
+        serde_json::to_string(&changes).map_err(|e| format!("serialize: {}", e))
+    }
+
+    /// Decode a version token, rejecting ones this doc has never seen (e.g. after reset)
+    fn decode_token(&self, token: &str) -> Result<loro::VersionVector, String> {
+        use base64::Engine as _;
+        let bytes = base64::engine::general_purpose::URL_SAFE_NO_PAD.decode(token)
+            .map_err(|_| "invalid token".to_string())?;
+        let since = loro::VersionVector::decode(&bytes)
+            .map_err(|e| format!("decode vv: {}", e))?;
+        if !matches!(since.partial_cmp(&self.doc.oplog_vv()), Some(std::cmp::Ordering::Less | std::cmp::Ordering::Equal)) {
+            return Err("stale token".into());
+        }
+        Ok(since)
+    }
+
+    /// Materialize the state at `vv` on a fork so the live doc is never checked out
+    fn fork_at(&self, vv: &loro::VersionVector) -> Result<LoroDoc, String> {
+        let old_doc = self.doc.fork();
+        old_doc.checkout(&self.doc.vv_to_frontiers(vv))
+            .map_err(|e| format!("checkout: {}", e))?;
+        Ok(old_doc)
+    }
+
+    /// Bookmarks/folders added, removed or changed since `token`, each attributed to the
+    /// device that made it. Removals carry no per-node editor, so they're only attributed
+    /// when a single peer made changes since the token.
+    pub fn bookmark_diff_since(&self, token: &str) -> Result<SessionDiff, String> {
+        let since = self.decode_token(token)?;
+        let current = self.doc.oplog_vv();
+        let mut diff = SessionDiff::default();
+        if since == current {
+            return Ok(diff);
+        }
+
+        // peers with ops after the token — an editor outside this set predates it
+        let advanced: HashSet<u64> = current.iter()
+            .filter(|(peer, counter)| since.get(*peer).map_or(true, |c| c < *counter))
+            .map(|(peer, _)| *peer)
+            .collect();
+        let own = self.doc.peer_id();
+        let peers = self.doc.get_map(MAP_PEERS);
+        let device_of = |peer: u64| get_map_str(&peers, &peer.to_string());
+
+        let old_doc = self.fork_at(&since)?;
+        let (old_bms, old_folders) = read_bookmark_nodes(&old_doc)?;
+        let (new_bms, new_folders) = read_bookmark_nodes(&self.doc)?;
+        let mut old_bms: HashMap<String, Bookmark> = old_bms.into_iter().map(|b| (b.id.clone(), b)).collect();
+        let mut old_folders: HashMap<String, BookmarkFolder> = old_folders.into_iter().map(|f| (f.id.clone(), f)).collect();
+
+        let tree = self.doc.get_tree(TREE_BOOKMARKS);
+        let metas: HashMap<String, LoroMap> = tree.children(None).unwrap_or_default().into_iter()
+            .filter_map(|node| tree.get_meta(node).ok())
+            .filter_map(|meta| get_map_str(&meta, "id").map(|id| (id, meta)))
+            .collect();
+        let editor = |id: &str| metas.get(id).and_then(|m| last_editor_since(m, &advanced, own)).and_then(device_of);
+
+        for bm in new_bms {
+            let change = AttributedChange {
+                id: bm.id.clone(), kind: "bookmark", title: bm.title.clone(),
+                url: Some(bm.url.clone()), device_id: editor(&bm.id),
+            };
+            match old_bms.remove(&bm.id) {
+                None => diff.added.push(change),
+                Some(old) if old != bm => diff.changed.push(change),
+                Some(_) => {}
+            }
+        }
+        for folder in new_folders {
+            let change = AttributedChange {
+                id: folder.id.clone(), kind: "folder", title: folder.name.clone(),
+                url: None, device_id: editor(&folder.id),
+            };
+            match old_folders.remove(&folder.id) {
+                None => diff.added.push(change),
+                Some(old) if old != folder => diff.changed.push(change),
+                Some(_) => {}
+            }
+        }
+
+        // whatever is left in the old maps is gone now
+        let remover = if advanced.len() == 1 { advanced.iter().next().copied().and_then(device_of) } else { None };
+        for bm in old_bms.into_values() {
+            diff.removed.push(AttributedChange {
+                id: bm.id, kind: "bookmark", title: bm.title, url: Some(bm.url), device_id: remover.clone(),
+            });
+        }
+        for folder in old_folders.into_values() {
+            diff.removed.push(AttributedChange {
+                id: folder.id, kind: "folder", title: folder.name, url: None, device_id: remover.clone(),
+            });
+        }
+        Ok(diff)
+    }
+
+    /// Import a peer's changes and report bookmark fields both sides edited concurrently.
+    /// `remote_vv` is the version the peer announced in its Hello/HelloAck.
+    pub fn import_remote_with_conflicts(&mut self, bytes: &[u8], remote_vv: &[u8]) -> Result<Vec<FieldConflict>, String> {
+        // pending local edits count as ours, so land them before taking the version
+        self.doc.commit();
+        let before = self.doc.oplog_vv();
+        self.import_remote(bytes)?;
+        // an undecodable vv only costs us the report, not the merge
+        let Ok(remote) = loro::VersionVector::decode(remote_vv) else { return Ok(Vec::new()) };
+        self.concurrent_bookmark_edits(&before, &remote)
+    }
+
+    /// Fields changed differently on `local` and `remote` relative to their common ancestor
+    fn concurrent_bookmark_edits(&self, local: &loro::VersionVector, remote: &loro::VersionVector) -> Result<Vec<FieldConflict>, String> {
+        // one side already had everything the other did — nothing was concurrent
+        if local.partial_cmp(remote).is_some() {
+            return Ok(Vec::new());
+        }
+        // the peer's version has to be fully present to be checked out
+        if !matches!(remote.partial_cmp(&self.doc.oplog_vv()), Some(std::cmp::Ordering::Less | std::cmp::Ordering::Equal)) {
+            return Ok(Vec::new());
+        }
+
+        let mut base = loro::VersionVector::default();
+        for (peer, counter) in local.iter() {
+            if let Some(theirs) = remote.get(peer) {
+                base.insert(*peer, (*counter).min(*theirs));
+            }
+        }
+        let read = |vv: &loro::VersionVector| -> Result<HashMap<String, Bookmark>, String> {
+            let (bms, _) = read_bookmark_nodes(&self.fork_at(vv)?)?;
+            Ok(bms.into_iter().map(|b| (b.id.clone(), b)).collect())
+        };
+        let (base, ours, theirs) = (read(&base)?, read(local)?, read(remote)?);
+
+        let mut conflicts = Vec::new();
+        for (id, old) in &base {
+            let (Some(ours), Some(theirs)) = (ours.get(id), theirs.get(id)) else { continue };
+            let fields = bookmark_fields(old).into_iter()
+                .zip(bookmark_fields(ours))
+                .zip(bookmark_fields(theirs));
+            for (((field, was), (_, mine)), (_, other)) in fields {
+                if mine != was && other != was && mine != other {
+                    conflicts.push(FieldConflict {
+                        bookmark_id: id.clone(), field, local: mine, remote: other,
+                    });
+                }
+            }
+        }
+        conflicts.sort_by(|a, b| (&a.bookmark_id, a.field).cmp(&(&b.bookmark_id, b.field)));
+        Ok(conflicts)
+    }
+
     // surgical bookmark ops
 
     pub fn add_bookmark(
@@ -314,6 +684,51 @@ impl SyncDoc {
         self.maybe_compact()
     }
 
+    pub fn add_bookmark_tag(&mut self, id: &str, tag: &str) -> Result<(), String> {
+        let tag = normalize_tag(tag).ok_or("invalid tag")?;
+        let meta = self.bookmark_meta(id)?;
+        meta.insert(&format!("{}{}", TAG_PREFIX, tag), true).map_err(|e| format!("tag: {}", e))?;
+        self.maybe_compact()
+    }
+
+    pub fn remove_bookmark_tag(&mut self, id: &str, tag: &str) -> Result<(), String> {
+        let tag = normalize_tag(tag).ok_or("invalid tag")?;
+        let meta = self.bookmark_meta(id)?;
+        // a delete is itself an op, so it wins over an older concurrent add
+        meta.delete(&format!("{}{}", TAG_PREFIX, tag)).map_err(|e| format!("untag: {}", e))?;
+        self.maybe_compact()
+    }
+
+    pub fn get_bookmarks_by_tag_json(&self, tag: &str) -> Result<String, String> {
+        let tag = normalize_tag(tag).ok_or("invalid tag")?;
+        let (bookmarks, _) = read_bookmark_nodes(&self.doc)?;
+        let tagged: Vec<Bookmark> = bookmarks.into_iter().filter(|b| b.tags.contains(&tag)).collect();
+        serde_json::to_string(&tagged).map_err(|e| format!("serialize: {}", e))
+    }
+
+    /// `[{tag, count}]`, sorted by tag
+    pub fn list_all_tags_json(&self) -> Result<String, String> {
+        let (bookmarks, _) = read_bookmark_nodes(&self.doc)?;
+        let mut counts: std::collections::BTreeMap<String, usize> = std::collections::BTreeMap::new();
+        for tag in bookmarks.into_iter().flat_map(|b| b.tags) {
+            *counts.entry(tag).or_default() += 1;
+        }
+        let list: Vec<serde_json::Value> = counts.into_iter()
+            .map(|(tag, count)| serde_json::json!({ "tag": tag, "count": count }))
+            .collect();
+        serde_json::to_string(&list).map_err(|e| format!("serialize: {}", e))
+    }
+
+    fn bookmark_meta(&self, id: &str) -> Result<LoroMap, String> {
+        let node = self.find_node_by_id(id).ok_or("bookmark not found")?;
+        let tree = self.doc.get_tree(TREE_BOOKMARKS);
+        let meta = tree.get_meta(node).map_err(|e| format!("meta: {}", e))?;
+        if get_map_str(&meta, "type").as_deref() != Some("bookmark") {
+            return Err("not a bookmark".into());
+        }
+        Ok(meta)
+    }
+
     pub fn delete_folder_cascade(&mut self, folder_id: &str) -> Result<(), String> {
         let tree = self.doc.get_tree(TREE_BOOKMARKS);
         // move bookmarks out of folder
@@ -338,7 +753,7 @@ impl SyncDoc {
     pub fn add_history(&mut self, url: &str, title: &str, favicon: Option<&str>, timestamp: i64) -> Result<(), String> {
         if !is_safe_url(url) { return Ok(()); } // skip dangerous urls
         let safe_title = sanitize_title(title);
-        let map = self.doc.get_map(MAP_HISTORY);
+        let map = self.slice(DataType::History).get_map(MAP_HISTORY);
         let key = format!("{}|{}", url, timestamp);
         let val = serde_json::json!({
             "url": url,
@@ -351,7 +766,7 @@ impl SyncDoc {
     }
 
     pub fn read_history_json(&self) -> Result<String, String> {
-        let map = self.doc.get_map(MAP_HISTORY);
+        let map = self.slice(DataType::History).get_map(MAP_HISTORY);
         let mut entries = Vec::new();
 
         // iterate all keys in the map
@@ -377,7 +792,7 @@ impl SyncDoc {
             .as_millis() as i64;
         let cutoff_ms = now_ms - (cutoff_days as i64 * 86_400_000);
 
-        let map = self.doc.get_map(MAP_HISTORY);
+        let map = self.slice(DataType::History).get_map(MAP_HISTORY);
         let mut to_delete = Vec::new();
         let mut all_keys: Vec<(String, i64)> = Vec::new();
 
@@ -415,6 +830,27 @@ impl SyncDoc {
         Ok(count)
     }
 
+    /// Delete visits timestamped within `from_ms..=to_ms`. Unlike compaction this is the
+    /// user clearing history, so the deletions are meant to reach peers.
+    pub fn remove_history_range(&mut self, from_ms: i64, to_ms: i64) -> Result<usize, String> {
+        let map = self.slice(DataType::History).get_map(MAP_HISTORY);
+        let mut to_delete = Vec::new();
+        map.for_each(|key, _value| {
+            // the URL may contain '|' itself; the timestamp never does
+            let ts = key.rsplit_once('|').and_then(|(_, ts)| ts.parse::<i64>().ok());
+            if ts.is_some_and(|ts| (from_ms..=to_ms).contains(&ts)) {
+                to_delete.push(key.to_string());
+            }
+        });
+        for key in &to_delete {
+            let _ = map.delete(key);
+        }
+        if !to_delete.is_empty() {
+            self.maybe_compact()?;
+        }
+        Ok(to_delete.len())
+    }
+
     // ── settings ──
 
     pub fn write_setting(&mut self, key: &str, value: &str) -> Result<(), String> {
@@ -422,13 +858,13 @@ impl SyncDoc {
         if DEVICE_LOCAL.contains(&key) { return Ok(()); }
         // validate json
         if !validate_setting(value) { return Err("invalid setting value".into()); }
-        let map = self.doc.get_map(MAP_SETTINGS);
+        let map = self.slice(DataType::Settings).get_map(MAP_SETTINGS);
         let _ = map.insert(key, value);
         self.maybe_compact()
     }
 
     pub fn read_settings_json(&self) -> Result<String, String> {
-        let map = self.doc.get_map(MAP_SETTINGS);
+        let map = self.slice(DataType::Settings).get_map(MAP_SETTINGS);
         let mut obj = serde_json::Map::new();
 
         map.for_each(|key, value| {
@@ -447,7 +883,7 @@ impl SyncDoc {
     // ── open tabs ──
 
     pub fn write_tabs(&mut self, tabs_json: &str) -> Result<(), String> {
-        let map = self.doc.get_map(MAP_TABS);
+        let map = self.slice(DataType::Tabs).get_map(MAP_TABS);
         let now = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap_or_default()
@@ -457,8 +893,27 @@ impl SyncDoc {
         Ok(()) // no compact — tabs overwrite, don't accumulate ops
     }
 
+    /// Drop this device's entry from the shared tab list, e.g. when tab sync is turned off
+    pub fn remove_own_tabs(&mut self) -> Result<bool, String> {
+        let own = self.device_id.clone();
+        self.remove_device_tabs(&own)
+    }
+
+    /// Drop a device's entry from the shared tab list, along with its tab groups. Used for
+    /// a peer whose Hello no longer lists tabs, since its own delete can't reach us once
+    /// it stops syncing them.
+    pub fn remove_device_tabs(&mut self, device_id: &str) -> Result<bool, String> {
+        let map = self.slice(DataType::Tabs).get_map(MAP_TABS);
+        let had_tabs = map.get(device_id).is_some();
+        if had_tabs {
+            map.delete(device_id).map_err(|e| format!("delete tabs: {}", e))?;
+        }
+        let had_groups = self.remove_device_groups(device_id);
+        Ok(had_tabs || had_groups)
+    }
+
     pub fn read_all_tabs(&self) -> Result<String, String> {
-        let map = self.doc.get_map(MAP_TABS);
+        let map = self.slice(DataType::Tabs).get_map(MAP_TABS);
         let mut devices = Vec::new();
 
         map.for_each(|key, value| {
@@ -487,7 +942,7 @@ impl SyncDoc {
             .as_millis() as i64;
         let cutoff = now_ms - (stale_days as i64 * 86_400_000);
 
-        let map = self.doc.get_map(MAP_TABS);
+        let map = self.slice(DataType::Tabs).get_map(MAP_TABS);
         let mut to_delete = Vec::new();
 
         map.for_each(|key, value| {
@@ -505,10 +960,191 @@ impl SyncDoc {
         let count = to_delete.len();
         for key in &to_delete {
             let _ = map.delete(key);
+            self.remove_device_groups(key);
         }
         Ok(count)
     }
 
+    // ── tab groups ──
+
+    /// Create or update one of this device's groups and make `tab_ids` its members.
+    /// Groups and members are keyed `device/id` like the tab list is keyed by device,
+    /// since local tab and group ids repeat across devices.
+    pub fn write_tab_group(&mut self, id: &str, name: &str, color: &str, tab_ids: &[String]) -> Result<(), String> {
+        if id.is_empty() { return Err("empty group id".into()); }
+        let color = normalize_group_color(color).ok_or("invalid group color")?;
+        let name: String = sanitize_title(name.trim()).chars().take(MAX_GROUP_NAME_LEN).collect();
+        let group_key = device_key(&self.device_id, id);
+        let groups = self.slice(DataType::Tabs).get_map(MAP_TAB_GROUPS);
+        let record = serde_json::json!({ "name": name, "color": color }).to_string();
+        if get_map_str(&groups, &group_key).as_deref() != Some(record.as_str()) {
+            let _ = groups.insert(&group_key, record);
+        }
+
+        let members = self.slice(DataType::Tabs).get_map(MAP_TAB_GROUP_MEMBERS);
+        let tab_keys: Vec<String> = tab_ids.iter().map(|t| device_key(&self.device_id, t)).collect();
+        for tab in group_members(&members, &group_key) {
+            if !tab_keys.contains(&tab) {
+                let _ = members.delete(&tab);
+            }
+        }
+        for tab in &tab_keys {
+            if get_map_str(&members, tab).as_deref() != Some(group_key.as_str()) {
+                let _ = members.insert(tab, group_key.as_str());
+            }
+        }
+        self.maybe_compact()
+    }
+
+    pub fn remove_tab_group(&mut self, id: &str) -> Result<(), String> {
+        let group_key = device_key(&self.device_id, id);
+        let groups = self.slice(DataType::Tabs).get_map(MAP_TAB_GROUPS);
+        if groups.get(&group_key).is_some() {
+            groups.delete(&group_key).map_err(|e| format!("delete group: {}", e))?;
+        }
+        let members = self.slice(DataType::Tabs).get_map(MAP_TAB_GROUP_MEMBERS);
+        for tab in group_members(&members, &group_key) {
+            let _ = members.delete(&tab);
+        }
+        self.maybe_compact()
+    }
+
+    /// Drop every group and member a device published. Returns whether there were any.
+    fn remove_device_groups(&mut self, device_id: &str) -> bool {
+        let prefix = device_key(device_id, "");
+        let mut removed = false;
+        for name in [MAP_TAB_GROUPS, MAP_TAB_GROUP_MEMBERS] {
+            let map = self.slice(DataType::Tabs).get_map(name);
+            let mut keys = Vec::new();
+            map.for_each(|key, _| {
+                if key.starts_with(&prefix) {
+                    keys.push(key.to_string());
+                }
+            });
+            for key in &keys {
+                let _ = map.delete(key);
+            }
+            removed |= !keys.is_empty();
+        }
+        removed
+    }
+
+    /// Groups sorted by device then id, as `[{id, deviceId, name, color, tabIds}]`.
+    /// Members of a deleted group are dropped, and so are groups left without members.
+    pub fn read_tab_groups_json(&self) -> Result<String, String> {
+        let members = self.slice(DataType::Tabs).get_map(MAP_TAB_GROUP_MEMBERS);
+        let mut by_group: HashMap<String, Vec<String>> = HashMap::new();
+        members.for_each(|tab, value| {
+            if let loro::ValueOrContainer::Value(loro::LoroValue::String(g)) = value {
+                let Some((_, tab)) = tab.split_once('/') else { return };
+                by_group.entry(g.to_string()).or_default().push(tab.to_string());
+            }
+        });
+
+        let groups = self.slice(DataType::Tabs).get_map(MAP_TAB_GROUPS);
+        let mut out = Vec::new();
+        groups.for_each(|key, value| {
+            let loro::ValueOrContainer::Value(loro::LoroValue::String(s)) = value else { return };
+            let Some((device_id, id)) = key.split_once('/') else { return };
+            let Ok(v) = serde_json::from_str::<serde_json::Value>(&s) else { return };
+            let Some(mut tab_ids) = by_group.remove(key) else { return };
+            tab_ids.sort();
+            let name = v.get("name").and_then(|n| n.as_str()).unwrap_or_default();
+            out.push(SyncedTabGroup {
+                id: id.to_string(),
+                device_id: device_id.to_string(),
+                name: sanitize_title(name).chars().take(MAX_GROUP_NAME_LEN).collect(),
+                color: v.get("color").and_then(|c| c.as_str())
+                    .and_then(normalize_group_color)
+                    .unwrap_or_else(|| "#6366f1".into()),
+                tab_ids,
+            });
+        });
+        out.sort_by(|a, b| (&a.device_id, &a.id).cmp(&(&b.device_id, &b.id)));
+        serde_json::to_string(&out).map_err(|e| format!("serialize: {}", e))
+    }
+
+    // ── reading list ──
+
+    /// Whether the item is marked read, and since when
+    fn reading_item_read(&self, url: &str) -> (bool, Option<u64>) {
+        let read = self.slice(DataType::ReadingList).get_map(MAP_READING_LIST_READ);
+        let Some(v) = get_map_str(&read, url).and_then(|s| serde_json::from_str::<serde_json::Value>(&s).ok()) else {
+            return (false, None);
+        };
+        let is_read = v.get("read").and_then(|r| r.as_bool()).unwrap_or(false);
+        (is_read, v.get("atMs").and_then(|t| t.as_u64()).filter(|_| is_read))
+    }
+
+    /// Add or re-title an item. Adding one that's already there keeps its added time
+    /// and makes it unread again, like the local list.
+    pub fn write_reading_list_item(&mut self, url: &str, title: &str, now_ms: u64) -> Result<(), String> {
+        if url.is_empty() || !is_safe_url(url) { return Err("invalid reading list url".into()); }
+        let items = self.slice(DataType::ReadingList).get_map(MAP_READING_LIST);
+        let existing = get_map_str(&items, url);
+        let added_at_ms = existing.as_deref()
+            .and_then(|s| serde_json::from_str::<serde_json::Value>(s).ok())
+            .and_then(|v| v.get("addedAtMs")?.as_u64())
+            .unwrap_or(now_ms);
+        let title: String = sanitize_title(title.trim()).chars().take(MAX_READING_TITLE_LEN).collect();
+        let record = serde_json::json!({ "title": title, "addedAtMs": added_at_ms }).to_string();
+        if existing.as_deref() != Some(record.as_str()) {
+            let _ = items.insert(url, record);
+        }
+        if self.reading_item_read(url).0 {
+            self.set_reading_list_read(url, false, now_ms)?;
+        }
+        self.maybe_compact()
+    }
+
+    pub fn set_reading_list_read(&mut self, url: &str, read: bool, now_ms: u64) -> Result<(), String> {
+        if self.slice(DataType::ReadingList).get_map(MAP_READING_LIST).get(url).is_none() {
+            return Err("not on the reading list".into());
+        }
+        if self.reading_item_read(url).0 == read {
+            return Ok(());
+        }
+        let record = serde_json::json!({ "read": read, "atMs": now_ms }).to_string();
+        let _ = self.slice(DataType::ReadingList).get_map(MAP_READING_LIST_READ).insert(url, record);
+        self.maybe_compact()
+    }
+
+    pub fn remove_reading_list_item(&mut self, url: &str) -> Result<(), String> {
+        let items = self.slice(DataType::ReadingList).get_map(MAP_READING_LIST);
+        if items.get(url).is_some() {
+            items.delete(url).map_err(|e| format!("delete reading list item: {}", e))?;
+        }
+        let read = self.slice(DataType::ReadingList).get_map(MAP_READING_LIST_READ);
+        if read.get(url).is_some() {
+            let _ = read.delete(url);
+        }
+        self.maybe_compact()
+    }
+
+    /// Items newest first, as `[{url, title, addedAtMs, read, readAtMs}]`
+    pub fn read_reading_list_json(&self) -> Result<String, String> {
+        let items = self.slice(DataType::ReadingList).get_map(MAP_READING_LIST);
+        let mut out = Vec::new();
+        items.for_each(|url, value| {
+            let loro::ValueOrContainer::Value(loro::LoroValue::String(s)) = value else { return };
+            let Ok(v) = serde_json::from_str::<serde_json::Value>(&s) else { return };
+            if !is_safe_url(url) { return; }
+            let title = v.get("title").and_then(|t| t.as_str()).unwrap_or_default();
+            out.push(SyncedReadingItem {
+                url: url.to_string(),
+                title: sanitize_title(title).chars().take(MAX_READING_TITLE_LEN).collect(),
+                added_at_ms: v.get("addedAtMs").and_then(|t| t.as_u64()).unwrap_or(0),
+                read: false,
+                read_at_ms: None,
+            });
+        });
+        for item in out.iter_mut() {
+            (item.read, item.read_at_ms) = self.reading_item_read(&item.url);
+        }
+        out.sort_by(|a, b| b.added_at_ms.cmp(&a.added_at_ms).then_with(|| a.url.cmp(&b.url)));
+        serde_json::to_string(&out).map_err(|e| format!("serialize: {}", e))
+    }
+
     // ── migration helper ──
 
     pub fn maybe_migrate_json(
@@ -542,13 +1178,165 @@ impl SyncDoc {
     }
 }
 
+// where a slice doc is stored, next to sync.loro
+fn slice_path(save_path: &std::path::Path, t: DataType) -> PathBuf {
+    save_path.with_file_name(format!("slice-{}.loro", t.key()))
+}
+
+fn write_atomic(path: &std::path::Path, bytes: &[u8]) -> Result<(), String> {
+    let tmp = path.with_extension("loro.tmp");
+    std::fs::write(&tmp, bytes).map_err(|e| format!("write tmp: {}", e))?;
+    std::fs::rename(&tmp, path).map_err(|e| format!("rename: {}", e))
+}
+
+// same doc, history and peer, rebuilt from a snapshot
+fn reloaded(doc: &LoroDoc) -> Result<LoroDoc, String> {
+    let snapshot = doc.export(ExportMode::Snapshot).map_err(|e| format!("export snapshot: {}", e))?;
+    let new_doc = LoroDoc::new();
+    new_doc.set_peer_id(doc.peer_id()).map_err(|e| format!("set peer: {}", e))?;
+    new_doc.import(&snapshot).map_err(|e| format!("compact: {}", e))?;
+    Ok(new_doc)
+}
+
+fn copy_map(from: &LoroMap, to: &LoroMap) {
+    from.for_each(|key, value| {
+        if let loro::ValueOrContainer::Value(v) = value {
+            let _ = to.insert(key, v);
+        }
+    });
+}
+
 // ── sanitization helpers (security) ──
 
+// bookmark tree → (bookmarks, folders), in tree order
+fn write_folder_node(tree: &LoroTree, folder: &BookmarkFolder) -> Result<(), String> {
+    let node = tree.create(None).map_err(|e| format!("create folder: {}", e))?;
+    let meta = tree.get_meta(node).map_err(|e| format!("get meta: {}", e))?;
+    set_map_str(&meta, "type", "folder");
+    set_map_str(&meta, "id", &folder.id);
+    set_map_str(&meta, "name", &folder.name);
+    set_map_str(&meta, "parentId", &folder.parent_id);
+    set_map_f64(&meta, "order", folder.order);
+    Ok(())
+}
+
+fn write_bookmark_node(tree: &LoroTree, bm: &Bookmark) -> Result<(), String> {
+    let node = tree.create(None).map_err(|e| format!("create bookmark: {}", e))?;
+    let meta = tree.get_meta(node).map_err(|e| format!("get meta: {}", e))?;
+    set_map_str(&meta, "type", "bookmark");
+    set_map_str(&meta, "id", &bm.id);
+    set_map_str(&meta, "url", &bm.url);
+    set_map_str(&meta, "title", &bm.title);
+    if let Some(ref fav) = bm.favicon {
+        set_map_str(&meta, "favicon", fav);
+    }
+    set_map_str(&meta, "folderId", &bm.folder_id);
+    set_map_f64(&meta, "createdAt", bm.created_at);
+    for tag in bm.tags.iter().filter_map(|t| normalize_tag(t)) {
+        let _ = meta.insert(&format!("{}{}", TAG_PREFIX, tag), true);
+    }
+    Ok(())
+}
+
+fn read_bookmark_nodes(doc: &LoroDoc) -> Result<(Vec<Bookmark>, Vec<BookmarkFolder>), String> {
+    let tree = doc.get_tree(TREE_BOOKMARKS);
+    let mut bookmarks = Vec::new();
+    let mut folders = Vec::new();
+
+    for node_id in tree.children(None).unwrap_or_default() {
+        let meta = tree.get_meta(node_id).map_err(|e| format!("read meta: {}", e))?;
+        let node_type = get_map_str(&meta, "type").unwrap_or_default();
+
+        if node_type == "folder" {
+            folders.push(BookmarkFolder {
+                id: get_map_str(&meta, "id").unwrap_or_default(),
+                name: get_map_str(&meta, "name").unwrap_or_default(),
+                parent_id: get_map_str(&meta, "parentId").unwrap_or_default(),
+                order: get_map_f64(&meta, "order").unwrap_or(0.0),
+            });
+        } else if node_type == "bookmark" {
+            bookmarks.push(Bookmark {
+                id: get_map_str(&meta, "id").unwrap_or_default(),
+                url: get_map_str(&meta, "url").unwrap_or_default(),
+                title: get_map_str(&meta, "title").unwrap_or_default(),
+                favicon: get_map_str(&meta, "favicon"),
+                folder_id: get_map_str(&meta, "folderId").unwrap_or_default(),
+                created_at: get_map_f64(&meta, "createdAt").unwrap_or(0.0),
+                tags: read_tags(&meta),
+            });
+        }
+    }
+    Ok((bookmarks, folders))
+}
+
+// fields a conflict can be reported on — tags are per-key and merge without conflict
+fn bookmark_fields(bm: &Bookmark) -> [(&'static str, String); 4] {
+    [
+        ("title", bm.title.clone()),
+        ("url", bm.url.clone()),
+        ("folderId", bm.folder_id.clone()),
+        ("favicon", bm.favicon.clone().unwrap_or_default()),
+    ]
+}
+
+fn read_tags(meta: &LoroMap) -> Vec<String> {
+    let mut tags = Vec::new();
+    meta.for_each(|key, v| {
+        if let (Some(tag), loro::ValueOrContainer::Value(loro::LoroValue::Bool(true))) = (key.strip_prefix(TAG_PREFIX), v) {
+            tags.push(tag.to_string());
+        }
+    });
+    tags.sort();
+    tags
+}
+
+/// The peer that last edited any key of `meta` after the token, preferring another
+/// device over this one when both touched it
+fn last_editor_since(meta: &LoroMap, advanced: &HashSet<u64>, own: u64) -> Option<u64> {
+    let mut keys = Vec::new();
+    meta.for_each(|key, _| keys.push(key.to_string()));
+    let editors: Vec<u64> = keys.iter()
+        .filter_map(|k| meta.get_last_editor(k))
+        .filter(|p| advanced.contains(p))
+        .collect();
+    editors.iter().find(|p| **p != own).or(editors.first()).copied()
+}
+
+// tags are matched case-insensitively; None for empty/oversized/control-char tags
+fn normalize_tag(raw: &str) -> Option<String> {
+    let tag = raw.trim().to_lowercase();
+    if tag.is_empty() || tag.chars().count() > MAX_TAG_LEN || tag.chars().any(|c| c.is_control()) {
+        return None;
+    }
+    Some(sanitize_title(&tag))
+}
+
 fn sanitize_title(raw: &str) -> String {
     // strip html tags — match existing pattern from lib.rs title sanitization
     raw.replace('<', "&lt;").replace('>', "&gt;")
 }
 
+// `#rrggbb`, lowercased
+fn normalize_group_color(raw: &str) -> Option<String> {
+    let hex = raw.trim().strip_prefix('#')?;
+    (hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit())).then(|| format!("#{}", hex.to_lowercase()))
+}
+
+// tab group and member keys: `device/id`
+fn device_key(device_id: &str, id: &str) -> String {
+    format!("{}/{}", device_id, id)
+}
+
+fn group_members(members: &LoroMap, group_id: &str) -> Vec<String> {
+    let mut tabs = Vec::new();
+    members.for_each(|tab, value| {
+        if matches!(value, loro::ValueOrContainer::Value(loro::LoroValue::String(ref g)) if g.to_string() == group_id) {
+            tabs.push(tab.to_string());
+        }
+    });
+    tabs
+}
+
 fn is_safe_url(url: &str) -> bool {
     let lower = url.to_lowercase();
     !BLOCKED_SCHEMES.iter().any(|s| lower.starts_with(s))
@@ -613,6 +1401,17 @@ mod tests {
         let _ = std::fs::remove_dir_all(dir);
     }
 
+    // one direction of a sync with every data type on
+    fn ship(from: &SyncDoc, to: &mut SyncDoc) {
+        to.import_remote(&from.export_all_updates().unwrap()).unwrap();
+        for t in DataType::SLICES {
+            let delta = from.export_slice(t, &to.slice_version_vector(t)).unwrap();
+            if !delta.is_empty() {
+                to.import_slice(t, &delta).unwrap();
+            }
+        }
+    }
+
     fn sample_json() -> &'static str {
         r#"{"bookmarks":[
             {"id":"b1","url":"https://rust-lang.org","title":"Rust","favicon":"https://rust-lang.org/fav.ico","folderId":"f1","createdAt":1700000000},
@@ -665,6 +1464,37 @@ mod tests {
         cleanup(&dir);
     }
 
+    #[test]
+    fn bookmark_changes_since_token() {
+        let dir = temp_dir();
+        let mut doc = SyncDoc::init(&dir, 1, "dev1").unwrap();
+        doc.add_bookmark("b1", "https://a.com", "A", None, "", 1.0).unwrap();
+        doc.add_bookmark("b2", "https://b.com", "B", None, "", 2.0).unwrap();
+        doc.add_folder("f1", "Work", "", 0.0).unwrap();
+        let token = doc.version_token();
+
+        // nothing changed yet
+        let v: serde_json::Value = serde_json::from_str(&doc.read_bookmark_changes_json(&token).unwrap()).unwrap();
+        assert!(v["bookmarks"].as_array().unwrap().is_empty());
+        assert!(v["removed"].as_array().unwrap().is_empty());
+
+        doc.add_bookmark("b3", "https://c.com", "C", None, "", 3.0).unwrap();
+        doc.move_bookmark("b1", "f1").unwrap();
+        doc.remove_bookmark("b2").unwrap();
+        doc.rename_folder("f1", "Job").unwrap();
+
+        let v: serde_json::Value = serde_json::from_str(&doc.read_bookmark_changes_json(&token).unwrap()).unwrap();
+        let mut ids: Vec<&str> = v["bookmarks"].as_array().unwrap().iter().map(|b| b["id"].as_str().unwrap()).collect();
+        ids.sort();
+        assert_eq!(ids, vec!["b1", "b3"]);
+        assert_eq!(v["folders"][0]["name"], "Job");
+        assert_eq!(v["removed"], serde_json::json!(["b2"]));
+        assert_eq!(v["token"], doc.version_token());
+
+        assert!(doc.read_bookmark_changes_json("not a token!").is_err());
+        cleanup(&dir);
+    }
+
     #[test]
     fn history_add_and_read() {
         let dir = temp_dir();
@@ -730,6 +1560,25 @@ mod tests {
         cleanup(&dir);
     }
 
+    #[test]
+    fn history_range_removal_includes_both_ends() {
+        let dir = temp_dir();
+        let mut doc = SyncDoc::init(&dir, 1, "dev1").unwrap();
+        doc.add_history("https://before.com", "", None, 99).unwrap();
+        doc.add_history("https://from.com", "", None, 100).unwrap();
+        doc.add_history("https://a.com/?q=x|y", "", None, 150).unwrap();
+        doc.add_history("https://to.com", "", None, 200).unwrap();
+        doc.add_history("https://after.com", "", None, 201).unwrap();
+
+        assert_eq!(doc.remove_history_range(100, 200).unwrap(), 3);
+        let entries: Vec<serde_json::Value> = serde_json::from_str(&doc.read_history_json().unwrap()).unwrap();
+        let mut urls: Vec<&str> = entries.iter().map(|e| e["url"].as_str().unwrap()).collect();
+        urls.sort();
+        assert_eq!(urls, vec!["https://after.com", "https://before.com"]);
+        assert_eq!(doc.remove_history_range(100, 200).unwrap(), 0);
+        cleanup(&dir);
+    }
+
     #[test]
     fn settings_write_and_read() {
         let dir = temp_dir();
@@ -815,10 +1664,8 @@ mod tests {
         doc_b.add_history("https://b.com", "B", None, 1700000001000).unwrap();
 
         // exchange updates
-        let updates_a = doc_a.export_all_updates().unwrap();
-        let updates_b = doc_b.export_all_updates().unwrap();
-        doc_a.import_remote(&updates_b).unwrap();
-        doc_b.import_remote(&updates_a).unwrap();
+        ship(&doc_a, &mut doc_b);
+        ship(&doc_b, &mut doc_a);
 
         // both should have merged data
         let bm_a = doc_a.read_bookmarks_as_json().unwrap();
@@ -838,6 +1685,262 @@ mod tests {
         cleanup(&dir2);
     }
 
+    #[test]
+    fn own_tabs_removal_reaches_peer() {
+        let dir1 = temp_dir();
+        let dir2 = temp_dir();
+        let mut doc_a = SyncDoc::init(&dir1, 1, "devA").unwrap();
+        let mut doc_b = SyncDoc::init(&dir2, 2, "devB").unwrap();
+
+        doc_a.write_tabs(r#"[{"id":"t1","url":"https://a.com","title":"A"}]"#).unwrap();
+        ship(&doc_a, &mut doc_b);
+        let devices: Vec<serde_json::Value> = serde_json::from_str(&doc_b.read_all_tabs().unwrap()).unwrap();
+        assert_eq!(devices.len(), 1);
+
+        // tab sync turned off on A
+        assert!(doc_a.remove_own_tabs().unwrap());
+        assert!(!doc_a.remove_own_tabs().unwrap());
+        ship(&doc_a, &mut doc_b);
+        let devices: Vec<serde_json::Value> = serde_json::from_str(&doc_b.read_all_tabs().unwrap()).unwrap();
+        assert!(devices.is_empty());
+
+        cleanup(&dir1);
+        cleanup(&dir2);
+    }
+
+    #[test]
+    fn tab_groups_reach_peer() {
+        let dir1 = temp_dir();
+        let dir2 = temp_dir();
+        let mut doc_a = SyncDoc::init(&dir1, 1, "devA").unwrap();
+        let mut doc_b = SyncDoc::init(&dir2, 2, "devB").unwrap();
+
+        let tabs = |v: &[&str]| v.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        doc_a.write_tab_group("g1", "Work <b>", "#22C55E", &tabs(&["t1", "t2"])).unwrap();
+        assert!(doc_a.write_tab_group("g2", "Bad", "green", &tabs(&["t3"])).is_err());
+        ship(&doc_a, &mut doc_b);
+        let groups: Vec<serde_json::Value> = serde_json::from_str(&doc_b.read_tab_groups_json().unwrap()).unwrap();
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0]["name"], "Work &lt;b&gt;");
+        assert_eq!(groups[0]["color"], "#22c55e");
+        assert_eq!(groups[0]["tabIds"], serde_json::json!(["t1", "t2"]));
+
+        assert_eq!(groups[0]["deviceId"], "devA");
+
+        // the same local ids on B are B's own tabs and group, not A's
+        doc_b.write_tab_group("g1", "News", "#f43f5e", &tabs(&["t2"])).unwrap();
+        doc_a.write_tab_group("g1", "Work", "#22c55e", &tabs(&["t2"])).unwrap();
+        ship(&doc_a, &mut doc_b);
+        ship(&doc_b, &mut doc_a);
+        let read_a = doc_a.read_tab_groups_json().unwrap();
+        assert_eq!(read_a, doc_b.read_tab_groups_json().unwrap());
+        let groups: Vec<serde_json::Value> = serde_json::from_str(&read_a).unwrap();
+        assert_eq!(groups.len(), 2);
+        assert_eq!((&groups[0]["deviceId"], &groups[0]["name"]), (&serde_json::json!("devA"), &serde_json::json!("Work")));
+        assert_eq!((&groups[1]["deviceId"], &groups[1]["name"]), (&serde_json::json!("devB"), &serde_json::json!("News")));
+        assert!(groups.iter().all(|g| g["tabIds"] == serde_json::json!(["t2"])));
+
+        // a removal syncs, members and all, and only touches the remover's group
+        doc_a.remove_tab_group("g1").unwrap();
+        ship(&doc_a, &mut doc_b);
+        let groups: Vec<serde_json::Value> = serde_json::from_str(&doc_b.read_tab_groups_json().unwrap()).unwrap();
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0]["deviceId"], "devB");
+
+        // dropping a device's tabs drops its groups too
+        assert!(doc_a.remove_device_tabs("devB").unwrap());
+        assert_eq!(doc_a.read_tab_groups_json().unwrap(), "[]");
+
+        cleanup(&dir1);
+        cleanup(&dir2);
+    }
+
+    #[test]
+    fn reading_list_read_state_reaches_peer() {
+        let dir1 = temp_dir();
+        let dir2 = temp_dir();
+        let mut doc_a = SyncDoc::init(&dir1, 1, "devA").unwrap();
+        let mut doc_b = SyncDoc::init(&dir2, 2, "devB").unwrap();
+        let unread = |doc: &SyncDoc| -> Vec<String> {
+            let items: Vec<serde_json::Value> = serde_json::from_str(&doc.read_reading_list_json().unwrap()).unwrap();
+            items.iter().filter(|i| i["read"] == false).map(|i| i["url"].as_str().unwrap().to_string()).collect()
+        };
+
+        doc_a.write_reading_list_item("https://a.com/post", "Post", 1).unwrap();
+        doc_a.write_reading_list_item("https://b.com/", "B", 2).unwrap();
+        assert!(doc_a.write_reading_list_item("javascript:alert(1)", "x", 3).is_err());
+        ship(&doc_a, &mut doc_b);
+        assert_eq!(unread(&doc_b), vec!["https://b.com/", "https://a.com/post"]);
+
+        // marked read on A: gone from B's unread items
+        doc_a.set_reading_list_read("https://a.com/post", true, 10).unwrap();
+        ship(&doc_a, &mut doc_b);
+        assert_eq!(unread(&doc_b), vec!["https://b.com/"]);
+        let items: Vec<serde_json::Value> = serde_json::from_str(&doc_b.read_reading_list_json().unwrap()).unwrap();
+        assert_eq!(items[1]["readAtMs"], 10);
+        assert!(doc_b.set_reading_list_read("https://missing.com/", true, 11).is_err());
+
+        // concurrent marks settle on one value everywhere
+        doc_a.set_reading_list_read("https://b.com/", true, 20).unwrap();
+        doc_b.set_reading_list_read("https://a.com/post", false, 21).unwrap();
+        doc_b.set_reading_list_read("https://b.com/", true, 22).unwrap();
+        ship(&doc_a, &mut doc_b);
+        ship(&doc_b, &mut doc_a);
+        assert_eq!(doc_a.read_reading_list_json().unwrap(), doc_b.read_reading_list_json().unwrap());
+        assert_eq!(unread(&doc_a), vec!["https://a.com/post"]);
+
+        doc_b.remove_reading_list_item("https://a.com/post").unwrap();
+        ship(&doc_b, &mut doc_a);
+        assert!(unread(&doc_a).is_empty());
+
+        cleanup(&dir1);
+        cleanup(&dir2);
+    }
+
+    #[test]
+    fn merge_missing_bookmarks_keeps_remote_additions() {
+        let dir = temp_dir();
+        let mut doc = SyncDoc::init(&dir, 1, "dev1").unwrap();
+        doc.write_full_from_json(sample_json()).unwrap();
+        // arrived from a peer while bookmark sync was off here
+        doc.add_bookmark("b9", "https://peer.dev", "Peer", None, "root", 3.0).unwrap();
+
+        // local list kept b1/b2/f1/f2 and gained b3 while off
+        let local = r#"{"bookmarks":[
+            {"id":"b1","url":"https://rust-lang.org","title":"Rust","folderId":"f1","createdAt":1700000000},
+            {"id":"b2","url":"https://tauri.app","title":"Tauri","folderId":"root","createdAt":1700000001},
+            {"id":"b3","url":"https://local.dev","title":"Local","folderId":"f2","createdAt":1700000002,"tags":["work"]}
+        ],"folders":[
+            {"id":"f1","name":"Dev","parentId":"root","order":0},
+            {"id":"f2","name":"News","parentId":"root","order":1}
+        ]}"#;
+        assert_eq!(doc.merge_missing_bookmarks_from_json(local).unwrap(), 1);
+        assert_eq!(doc.merge_missing_bookmarks_from_json(local).unwrap(), 0);
+
+        let v: serde_json::Value = serde_json::from_str(&doc.read_bookmarks_as_json().unwrap()).unwrap();
+        let ids: HashSet<&str> = v["bookmarks"].as_array().unwrap().iter()
+            .map(|b| b["id"].as_str().unwrap())
+            .collect();
+        assert_eq!(ids, HashSet::from(["b1", "b2", "b3", "b9"]));
+        assert_eq!(v["folders"].as_array().unwrap().len(), 2);
+        cleanup(&dir);
+    }
+
+    #[test]
+    fn diff_since_token_attributes_devices() {
+        let dir1 = temp_dir();
+        let dir2 = temp_dir();
+        let mut doc_a = SyncDoc::init(&dir1, 1, "devA").unwrap();
+        let mut doc_b = SyncDoc::init(&dir2, 2, "devB").unwrap();
+        doc_a.add_bookmark("b1", "https://a.com", "A", None, "", 1.0).unwrap();
+        doc_b.import_remote(&doc_a.export_all_updates().unwrap()).unwrap();
+        let token = doc_a.version_token();
+
+        // "while A was away", B adds one and deletes another
+        doc_b.add_bookmark("b2", "https://b.com", "B", None, "", 2.0).unwrap();
+        doc_b.remove_bookmark("b1").unwrap();
+        doc_a.import_remote(&doc_b.export_all_updates().unwrap()).unwrap();
+
+        let diff = doc_a.bookmark_diff_since(&token).unwrap();
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.added[0].id, "b2");
+        assert_eq!(diff.added[0].device_id.as_deref(), Some("devB"));
+        assert_eq!(diff.removed.len(), 1);
+        assert_eq!(diff.removed[0].id, "b1");
+        assert_eq!(diff.removed[0].device_id.as_deref(), Some("devB"));
+        assert!(diff.changed.is_empty());
+
+        let none = doc_a.bookmark_diff_since(&doc_a.version_token()).unwrap();
+        assert!(none.added.is_empty() && none.removed.is_empty());
+
+        cleanup(&dir1);
+        cleanup(&dir2);
+    }
+
+    #[test]
+    fn concurrent_field_edits_are_reported() {
+        let dir1 = temp_dir();
+        let dir2 = temp_dir();
+        let mut doc_a = SyncDoc::init(&dir1, 1, "devA").unwrap();
+        let mut doc_b = SyncDoc::init(&dir2, 2, "devB").unwrap();
+        doc_a.add_bookmark("b1", "https://a.com", "A", None, "root", 1.0).unwrap();
+        doc_a.add_bookmark("b2", "https://b.com", "B", None, "root", 2.0).unwrap();
+        doc_b.import_remote(&doc_a.export_all_updates().unwrap()).unwrap();
+
+        // both retitle b1; on b2 A only moves it and B only retitles it
+        set_map_str(&doc_a.bookmark_meta("b1").unwrap(), "title", "From A");
+        doc_a.move_bookmark("b2", "f1").unwrap();
+        set_map_str(&doc_b.bookmark_meta("b1").unwrap(), "title", "From B");
+        set_map_str(&doc_b.bookmark_meta("b2").unwrap(), "title", "B renamed");
+
+        // each side announces its version before taking the other's changes, as in a sync
+        let (updates_a, vv_a) = (doc_a.export_all_updates().unwrap(), doc_a.version_vector());
+        let (updates_b, vv_b) = (doc_b.export_all_updates().unwrap(), doc_b.version_vector());
+        let conflicts = doc_a.import_remote_with_conflicts(&updates_b, &vv_b).unwrap();
+        assert_eq!(conflicts, vec![FieldConflict {
+            bookmark_id: "b1".into(), field: "title", local: "From A".into(), remote: "From B".into(),
+        }]);
+
+        // B sees the same pair, mirrored
+        let conflicts = doc_b.import_remote_with_conflicts(&updates_a, &vv_a).unwrap();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!((conflicts[0].local.as_str(), conflicts[0].remote.as_str()), ("From B", "From A"));
+
+        // once in sync, further edits are sequential, not conflicts
+        set_map_str(&doc_b.bookmark_meta("b1").unwrap(), "title", "Settled");
+        let updates_b = doc_b.export_all_updates().unwrap();
+        assert!(doc_a.import_remote_with_conflicts(&updates_b, &doc_b.version_vector()).unwrap().is_empty());
+
+        cleanup(&dir1);
+        cleanup(&dir2);
+    }
+
+    #[test]
+    fn concurrent_tag_edits_converge() {
+        let dir1 = temp_dir();
+        let dir2 = temp_dir();
+        let mut doc_a = SyncDoc::init(&dir1, 1, "devA").unwrap();
+        let mut doc_b = SyncDoc::init(&dir2, 2, "devB").unwrap();
+
+        doc_a.add_bookmark("b1", "https://a.com", "A", None, "", 1.0).unwrap();
+        doc_a.add_bookmark_tag("b1", "Rust").unwrap();
+        doc_a.add_bookmark_tag("b1", "old").unwrap();
+        doc_b.import_remote(&doc_a.export_all_updates().unwrap()).unwrap();
+
+        // concurrently: A drops "old" and adds "work", B re-adds "rust" and adds "later"
+        doc_a.remove_bookmark_tag("b1", "old").unwrap();
+        doc_a.add_bookmark_tag("b1", "work").unwrap();
+        doc_b.add_bookmark_tag("b1", "rust").unwrap();
+        doc_b.add_bookmark_tag("b1", "later").unwrap();
+
+        let updates_a = doc_a.export_all_updates().unwrap();
+        let updates_b = doc_b.export_all_updates().unwrap();
+        doc_a.import_remote(&updates_b).unwrap();
+        doc_b.import_remote(&updates_a).unwrap();
+
+        let json_a = doc_a.read_bookmarks_as_json().unwrap();
+        assert_eq!(json_a, doc_b.read_bookmarks_as_json().unwrap());
+        let v: serde_json::Value = serde_json::from_str(&json_a).unwrap();
+        assert_eq!(v["bookmarks"][0]["tags"], serde_json::json!(["later", "rust", "work"]));
+
+        // both sides remove the same tag concurrently — still gone after merge
+        doc_a.remove_bookmark_tag("b1", "later").unwrap();
+        doc_b.remove_bookmark_tag("b1", "later").unwrap();
+        doc_a.import_remote(&doc_b.export_all_updates().unwrap()).unwrap();
+        let by_tag: Vec<serde_json::Value> = serde_json::from_str(&doc_a.get_bookmarks_by_tag_json("later").unwrap()).unwrap();
+        assert!(by_tag.is_empty());
+
+        let tags: Vec<serde_json::Value> = serde_json::from_str(&doc_a.list_all_tags_json().unwrap()).unwrap();
+        assert_eq!(tags, vec![
+            serde_json::json!({"tag": "rust", "count": 1}),
+            serde_json::json!({"tag": "work", "count": 1}),
+        ]);
+        assert!(doc_a.add_bookmark_tag("b1", "   ").is_err());
+
+        cleanup(&dir1);
+        cleanup(&dir2);
+    }
+
     #[test]
     fn save_and_reload() {
         let dir = temp_dir();
@@ -867,4 +1970,32 @@ mod tests {
 
         cleanup(&dir);
     }
+
+    #[test]
+    fn older_single_doc_moves_into_slices() {
+        let dir = temp_dir();
+        // what an older build saved: every type in sync.loro
+        let old = LoroDoc::new();
+        old.set_peer_id(1).unwrap();
+        old.get_map(MAP_HISTORY).insert("https://a.com|1", r#"{"url":"https://a.com","title":"A","ts":1}"#).unwrap();
+        old.get_map(MAP_SETTINGS).insert("themeMode", "\"dark\"").unwrap();
+        std::fs::create_dir_all(dir.join("sync")).unwrap();
+        std::fs::write(dir.join("sync").join("sync.loro"), old.export(ExportMode::Snapshot).unwrap()).unwrap();
+
+        let mut doc = SyncDoc::init(&dir, 1, "dev1").unwrap();
+        let h: Vec<serde_json::Value> = serde_json::from_str(&doc.read_history_json().unwrap()).unwrap();
+        assert_eq!(h.len(), 1);
+        let s: serde_json::Value = serde_json::from_str(&doc.read_settings_json().unwrap()).unwrap();
+        assert_eq!(s["themeMode"], "dark");
+
+        // once the slice exists it's the only source — the copy isn't made again
+        doc.write_setting("themeMode", "\"light\"").unwrap();
+        doc.save().unwrap();
+        let doc = SyncDoc::init(&dir, 1, "dev1").unwrap();
+        let s: serde_json::Value = serde_json::from_str(&doc.read_settings_json().unwrap()).unwrap();
+        assert_eq!(s["themeMode"], "light");
+        assert!(dir.join("sync").join("slice-settings.loro").exists());
+
+        cleanup(&dir);
+    }
 }
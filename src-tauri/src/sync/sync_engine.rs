@@ -1,10 +1,16 @@
 use super::keys::PairedDevice;
-use super::sync_doc::SyncDoc;
+use super::sync_doc::{DataType, FieldConflict, SyncDoc};
 use super::noise::NoiseStream;
-use super::protocol::{self, SyncMessage};
+use super::protocol::{self, HelloExt, SliceDelta, SliceVv, SyncMessage};
+use crate::downloads::RateLimiter;
 use std::net::SocketAddr;
+use std::sync::Arc;
 use tokio::net::TcpStream;
 
+/// The shared doc slot; the engine locks it only to export or import, never while
+/// waiting on the network, so a throttled send doesn't stall local edits.
+pub type DocSlot = tokio::sync::Mutex<Option<SyncDoc>>;
+
 #[derive(Debug)]
 pub enum SyncResult {
     ChangesReceived,
@@ -13,14 +19,19 @@ pub enum SyncResult {
     AlreadyUpToDate,
 }
 
-/// Initiator side: connect to peer, handshake, exchange deltas.
+/// Initiator side: connect to peer, handshake, exchange deltas for the data `types`
+/// this device syncs. Also returns bookmark fields both sides had edited since they last synced.
+#[allow(clippy::too_many_arguments)]
 pub async fn sync_with_peer(
     peer: &PairedDevice,
     addr: SocketAddr,
     private_key: &[u8],
     device_id: &str,
-    sync_doc: &mut SyncDoc,
-) -> Result<SyncResult, String> {
+    types: &[DataType],
+    sync_doc: &DocSlot,
+    throttle: Arc<RateLimiter>,
+    compress_threshold: usize,
+) -> Result<(SyncResult, Vec<FieldConflict>), String> {
     // tcp connect
     let timeout = tokio::time::Duration::from_secs(5);
     let stream = tokio::time::timeout(timeout, TcpStream::connect(addr))
@@ -48,6 +59,8 @@ pub async fn sync_with_peer(
 
     // noise handshake (initiator)
     let mut ns = NoiseStream::handshake_initiator(stream, private_key).await?;
+    ns.throttle = Some(throttle);
+    ns.compress_threshold = compress_threshold;
 
     // verify remote key matches paired device
     let remote_key = ns.remote_static_key().ok_or("no remote static key")?;
@@ -56,65 +69,92 @@ pub async fn sync_with_peer(
     }
 
     // send Hello with our version vector
-    let local_vv = sync_doc.version_vector();
-    protocol::send_encrypted(
+    let (local_vv, local_ext) = with_doc(sync_doc, |d| Ok((d.version_vector(), hello_ext(d, types)))).await?;
+    protocol::send_hello(
         &mut ns,
         &SyncMessage::Hello {
             device_id: device_id.to_string(),
             vv: local_vv,
         },
+        &local_ext,
     )
     .await?;
 
     // receive HelloAck with peer's version vector
-    let ack = protocol::recv_encrypted(&mut ns).await?;
+    let (ack, ext) = protocol::recv_with_ext(&mut ns).await?;
     let peer_vv = match ack {
-        SyncMessage::HelloAck { vv } => vv,
+        SyncMessage::HelloAck { vv } => {
+            ns.compress = ext.caps & protocol::CAP_ZSTD != 0;
+            vv
+        }
         _ => return Err("expected HelloAck".into()),
     };
+    let slices = ext.caps & protocol::CAP_SLICES != 0;
 
     // send our changes to peer
-    let sent = send_delta_or_snapshot(sync_doc, &peer_vv, &mut ns).await?;
+    let outgoing = with_doc(sync_doc, |d| Ok(outgoing(d, types, &peer_vv, &ext))).await?;
+    let sent = send_all(&mut ns, outgoing).await?;
 
     // receive peer's changes
-    let received = recv_and_import(sync_doc, &mut ns).await?;
+    let incoming = recv_incoming(&mut ns, slices).await?;
+    let (received, conflicts) = with_doc(sync_doc, |d| {
+        import_incoming(d, types, incoming, &peer_vv, &ext, &peer.device_id)
+    }).await?;
 
     // exchange acks
     protocol::send_encrypted(&mut ns, &SyncMessage::SyncAck).await?;
     let _ = protocol::recv_encrypted(&mut ns).await?; // peer's ack
+    log_compression(&ns);
 
-    if received && sent {
-        Ok(SyncResult::BothSynced)
+    let result = if received && sent {
+        SyncResult::BothSynced
     } else if received {
-        Ok(SyncResult::ChangesReceived)
+        SyncResult::ChangesReceived
     } else if sent {
-        Ok(SyncResult::ChangesSent)
+        SyncResult::ChangesSent
     } else {
-        Ok(SyncResult::AlreadyUpToDate)
-    }
+        SyncResult::AlreadyUpToDate
+    };
+    Ok((result, conflicts))
 }
 
-/// Responder side: called after receiving Hello on an already-established NoiseStream.
+/// Responder side: called after receiving Hello (and its `remote_ext`) from `peer_device_id`
+/// on an already-established NoiseStream. Conflicts are reported the same way as `sync_with_peer`.
+#[allow(clippy::too_many_arguments)]
 pub async fn handle_sync_responder(
     ns: &mut NoiseStream,
+    peer_device_id: &str,
     remote_vv: Vec<u8>,
-    sync_doc: &mut SyncDoc,
-) -> Result<SyncResult, String> {
+    remote_ext: &HelloExt,
+    types: &[DataType],
+    sync_doc: &DocSlot,
+    throttle: Arc<RateLimiter>,
+    compress_threshold: usize,
+) -> Result<(SyncResult, Vec<FieldConflict>), String> {
+    ns.throttle = Some(throttle);
+    ns.compress_threshold = compress_threshold;
     // send HelloAck with our version vector
-    let local_vv = sync_doc.version_vector();
-    protocol::send_encrypted(ns, &SyncMessage::HelloAck { vv: local_vv }).await?;
+    let (local_vv, local_ext) = with_doc(sync_doc, |d| Ok((d.version_vector(), hello_ext(d, types)))).await?;
+    protocol::send_hello(ns, &SyncMessage::HelloAck { vv: local_vv }, &local_ext).await?;
+    ns.compress = remote_ext.caps & protocol::CAP_ZSTD != 0;
+    let slices = remote_ext.caps & protocol::CAP_SLICES != 0;
 
     // receive peer's changes
-    let received = recv_and_import(sync_doc, ns).await?;
-    eprintln!("[sync-engine] responder: received={}", received);
+    let incoming = recv_incoming(ns, slices).await?;
+    let (received, conflicts) = with_doc(sync_doc, |d| {
+        import_incoming(d, types, incoming, &remote_vv, remote_ext, peer_device_id)
+    }).await?;
+    eprintln!("[sync-engine] responder: received={} conflicts={}", received, conflicts.len());
 
     // send our changes to peer
-    let sent = send_delta_or_snapshot(sync_doc, &remote_vv, ns).await?;
+    let outgoing = with_doc(sync_doc, |d| Ok(outgoing(d, types, &remote_vv, remote_ext))).await?;
+    let sent = send_all(ns, outgoing).await?;
     eprintln!("[sync-engine] responder: sent={}", sent);
 
     // exchange acks
     let _ = protocol::recv_encrypted(ns).await?; // peer's ack
     protocol::send_encrypted(ns, &SyncMessage::SyncAck).await?;
+    log_compression(ns);
 
     let result = if received && sent {
         SyncResult::BothSynced
@@ -131,52 +171,111 @@ pub async fn handle_sync_responder(
         SyncResult::ChangesSent => "ChangesSent",
         SyncResult::AlreadyUpToDate => "AlreadyUpToDate",
     });
-    Ok(result)
+    Ok((result, conflicts))
 }
 
 // ── internal helpers ──────────────────────────────────────────────────
 
-/// Try delta export, fall back to snapshot if delta is empty or fails.
-/// Returns true if something was sent.
-async fn send_delta_or_snapshot(
-    doc: &SyncDoc,
-    remote_vv: &[u8],
-    ns: &mut NoiseStream,
-) -> Result<bool, String> {
-    // try delta first
-    match doc.export_delta(remote_vv) {
-        Ok(delta) if !delta.is_empty() => {
-            let compressed = lz4_flex::compress_prepend_size(&delta);
-            protocol::send_encrypted(ns, &SyncMessage::SyncDelta { data: compressed }).await?;
-            Ok(true)
-        }
-        _ => {
+fn log_compression(ns: &NoiseStream) {
+    if let Some(ratio) = ns.stats.ratio() {
+        eprintln!(
+            "[sync-engine] sent {} bytes as {} on the wire (ratio {:.2}, zstd {})",
+            ns.stats.raw_bytes,
+            ns.stats.wire_bytes,
+            ratio,
+            if ns.compress { "on" } else { "off" },
+        );
+    }
+}
+
+/// Run `f` on the doc under its lock, released again before the caller awaits anything else
+async fn with_doc<R>(slot: &DocSlot, f: impl FnOnce(&mut SyncDoc) -> Result<R, String>) -> Result<R, String> {
+    let mut guard = slot.lock().await;
+    let doc = guard.as_mut().ok_or("sync doc not loaded")?;
+    f(doc)
+}
+
+/// Our messages for a peer at `remote_vv`: the main doc as a delta (falling back to all
+/// updates if the delta is empty or fails), then SyncSlices if the peer understands it.
+/// With bookmark sync off the main doc stays here and the peer is told we're up to date.
+fn outgoing(doc: &SyncDoc, types: &[DataType], remote_vv: &[u8], remote: &HelloExt) -> Vec<SyncMessage> {
+    let main = if !types.contains(&DataType::Bookmarks) {
+        None
+    } else {
+        match doc.export_delta(remote_vv) {
+            Ok(delta) if !delta.is_empty() => Some(delta),
             // empty delta or failed — send all updates (not snapshot, which discards foreign nodes)
-            match doc.export_all_updates() {
-                Ok(updates) if !updates.is_empty() => {
-                    let compressed = lz4_flex::compress_prepend_size(&updates);
-                    protocol::send_encrypted(
-                        ns,
-                        &SyncMessage::SyncDelta { data: compressed },
-                    )
-                    .await?;
-                    Ok(true)
-                }
-                _ => {
-                    protocol::send_encrypted(ns, &SyncMessage::SyncUpToDate).await?;
-                    Ok(false)
-                }
+            _ => doc.export_all_updates().ok().filter(|u| !u.is_empty()),
+        }
+    };
+    let mut msgs = vec![match main {
+        Some(updates) => SyncMessage::SyncDelta { data: lz4_flex::compress_prepend_size(&updates) },
+        None => SyncMessage::SyncUpToDate,
+    }];
+    if remote.caps & protocol::CAP_SLICES != 0 {
+        msgs.push(SyncMessage::SyncSlices { slices: outgoing_slices(doc, types, &remote.slices) });
+    }
+    msgs
+}
+
+/// Send what `outgoing` built. Returns true if any of it carried changes.
+async fn send_all(ns: &mut NoiseStream, msgs: Vec<SyncMessage>) -> Result<bool, String> {
+    let mut sent = false;
+    for msg in &msgs {
+        sent |= match msg {
+            SyncMessage::SyncDelta { .. } => true,
+            SyncMessage::SyncSlices { slices } => !slices.is_empty(),
+            _ => false,
+        };
+        protocol::send_encrypted(ns, msg).await?;
+    }
+    Ok(sent)
+}
+
+/// The peer's main-doc message, and its SyncSlices when both sides send one
+async fn recv_incoming(ns: &mut NoiseStream, slices: bool) -> Result<(SyncMessage, Option<SyncMessage>), String> {
+    let main = protocol::recv_encrypted(ns).await?;
+    let slices = if slices { Some(protocol::recv_encrypted(ns).await?) } else { None };
+    Ok((main, slices))
+}
+
+/// Import what `recv_incoming` got. Returns true if anything changed here, plus any
+/// concurrent bookmark edits against `remote_vv`.
+fn import_incoming(
+    doc: &mut SyncDoc,
+    types: &[DataType],
+    (main, slices): (SyncMessage, Option<SyncMessage>),
+    remote_vv: &[u8],
+    remote: &HelloExt,
+    peer_device_id: &str,
+) -> Result<(bool, Vec<FieldConflict>), String> {
+    let (mut received, conflicts) = import_main(doc, main, remote_vv, types.contains(&DataType::Bookmarks))?;
+    if let Some(msg) = slices {
+        let SyncMessage::SyncSlices { slices } = msg else {
+            return Err("expected SyncSlices".into());
+        };
+        if !slices.is_empty() {
+            doc.backup();
+            if import_slices(doc, types, slices)? {
+                doc.save()?;
+                received = true;
             }
         }
+        received |= forget_unshared_tabs(doc, types, remote, peer_device_id);
     }
+    Ok((received, conflicts))
 }
 
-/// Receive delta/snapshot from peer and import into doc.
-/// Returns true if changes were imported.
+/// Import the peer's delta/snapshot into the main doc; with bookmark sync off it's
+/// dropped. Returns true if changes were imported, plus any concurrent bookmark
+/// edits against `remote_vv`.
 /// Backs up .loro → .loro.bak before importing for crash recovery.
-async fn recv_and_import(doc: &mut SyncDoc, ns: &mut NoiseStream) -> Result<bool, String> {
-    let msg = protocol::recv_encrypted(ns).await?;
+fn import_main(doc: &mut SyncDoc, msg: SyncMessage, remote_vv: &[u8], bookmarks: bool) -> Result<(bool, Vec<FieldConflict>), String> {
     match msg {
+        SyncMessage::SyncDelta { .. } | SyncMessage::SyncSnapshot { .. } if !bookmarks => {
+            eprintln!("[sync-engine] bookmark sync off, dropping peer's main doc changes");
+            Ok((false, Vec::new()))
+        }
         SyncMessage::SyncDelta { data } => {
             eprintln!("[sync-engine] recv delta: {} bytes compressed", data.len());
             let decompressed = lz4_flex::decompress_size_prepended(&data)
@@ -188,7 +287,7 @@ async fn recv_and_import(doc: &mut SyncDoc, ns: &mut NoiseStream) -> Result<bool
             eprintln!("[sync-engine] tree nodes before: {}", tree_before.children(None).unwrap_or_default().len());
             doc.backup(); // .loro → .loro.bak
             eprintln!("[sync-engine] doc vv before import: {:?}", &doc.version_vector()[..doc.version_vector().len().min(20)]);
-            doc.import_remote(&decompressed)?;
+            let conflicts = doc.import_remote_with_conflicts(&decompressed, remote_vv)?;
             eprintln!("[sync-engine] doc vv after import: {:?}", &doc.version_vector()[..doc.version_vector().len().min(20)]);
             let tree_after = doc.doc.get_tree("bookmarks");
             let children = tree_after.children(None).unwrap_or_default();
@@ -206,7 +305,7 @@ async fn recv_and_import(doc: &mut SyncDoc, ns: &mut NoiseStream) -> Result<bool
             doc.save()?;
             let json = doc.read_bookmarks_as_json().unwrap_or_default();
             eprintln!("[sync-engine] after save, read_as_json: {}", &json[..json.len().min(300)]);
-            Ok(true)
+            Ok((true, conflicts))
         }
         SyncMessage::SyncSnapshot { data } => {
             eprintln!("[sync-engine] recv snapshot: {} bytes compressed", data.len());
@@ -214,16 +313,169 @@ async fn recv_and_import(doc: &mut SyncDoc, ns: &mut NoiseStream) -> Result<bool
                 .map_err(|e| format!("lz4 decompress: {}", e))?;
             eprintln!("[sync-engine] decompressed to {} bytes", decompressed.len());
             doc.backup();
-            doc.import_remote(&decompressed)?;
+            let conflicts = doc.import_remote_with_conflicts(&decompressed, remote_vv)?;
             doc.save()?;
             let json = doc.read_bookmarks_as_json().unwrap_or_default();
             eprintln!("[sync-engine] after import, doc has: {}", &json[..json.len().min(300)]);
-            Ok(true)
+            Ok((true, conflicts))
         }
         SyncMessage::SyncUpToDate => {
             eprintln!("[sync-engine] recv SyncUpToDate (no changes from peer)");
-            Ok(false)
+            Ok((false, Vec::new()))
         }
         _ => Err("expected SyncDelta/SyncSnapshot/SyncUpToDate".into()),
     }
 }
+
+/// Our side of the HelloExt: the non-bookmark types we exchange and our version of each
+fn hello_ext(doc: &SyncDoc, types: &[DataType]) -> HelloExt {
+    HelloExt {
+        caps: protocol::CAP_ZSTD | protocol::CAP_SLICES,
+        slices: types.iter()
+            .filter(|t| **t != DataType::Bookmarks)
+            .map(|t| SliceVv { kind: t.key().into(), vv: doc.slice_version_vector(*t) })
+            .collect(),
+    }
+}
+
+// a slice kind both sides exchange: the peer listed it and it's on here
+fn shared_slice(kind: &str, types: &[DataType]) -> Option<DataType> {
+    DataType::from_key(kind).filter(|t| *t != DataType::Bookmarks && types.contains(t))
+}
+
+/// Deltas for the types the peer asked for that are also on here; a type that either
+/// side has off is never sent.
+fn outgoing_slices(doc: &SyncDoc, types: &[DataType], theirs: &[SliceVv]) -> Vec<SliceDelta> {
+    theirs.iter()
+        .filter_map(|remote| {
+            let t = shared_slice(&remote.kind, types)?;
+            let delta = doc.export_slice(t, &remote.vv).ok().filter(|d| !d.is_empty())?;
+            Some(SliceDelta { kind: remote.kind.clone(), data: lz4_flex::compress_prepend_size(&delta) })
+        })
+        .collect()
+}
+
+/// Import the slices of types that are on here and drop the rest unread.
+/// Returns true if anything was imported.
+fn import_slices(doc: &mut SyncDoc, types: &[DataType], slices: Vec<SliceDelta>) -> Result<bool, String> {
+    let mut imported = false;
+    for slice in slices {
+        let Some(t) = shared_slice(&slice.kind, types) else {
+            eprintln!("[sync-engine] dropping {} changes, not synced here", slice.kind);
+            continue;
+        };
+        let data = lz4_flex::decompress_size_prepended(&slice.data)
+            .map_err(|e| format!("lz4 decompress: {}", e))?;
+        doc.import_slice(t, &data)?;
+        imported = true;
+    }
+    Ok(imported)
+}
+
+/// A peer that stopped syncing tabs can't send the delete for its tab list, so drop it
+/// here. Returns true if there was one.
+fn forget_unshared_tabs(doc: &mut SyncDoc, types: &[DataType], remote: &HelloExt, peer_device_id: &str) -> bool {
+    let shares_tabs = remote.slices.iter().any(|s| s.kind == DataType::Tabs.key());
+    if shares_tabs || !types.contains(&DataType::Tabs) {
+        return false;
+    }
+    let removed = doc.remove_device_tabs(peer_device_id).unwrap_or(false);
+    if removed {
+        let _ = doc.save();
+    }
+    removed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    static TEST_COUNTER: AtomicU32 = AtomicU32::new(0);
+
+    fn temp_dir() -> PathBuf {
+        let n = TEST_COUNTER.fetch_add(1, Ordering::Relaxed);
+        let p = std::env::temp_dir().join(format!("bushido_engine_{}_{}", std::process::id(), n));
+        let _ = std::fs::remove_dir_all(&p);
+        let _ = std::fs::create_dir_all(&p);
+        p
+    }
+
+    // the slice half of one sync between two devices
+    fn exchange(a: &mut SyncDoc, a_types: &[DataType], b: &mut SyncDoc, b_types: &[DataType]) {
+        let (ext_a, ext_b) = (hello_ext(a, a_types), hello_ext(b, b_types));
+        let to_b = outgoing_slices(a, a_types, &ext_b.slices);
+        let to_a = outgoing_slices(b, b_types, &ext_a.slices);
+        import_slices(b, b_types, to_b).unwrap();
+        import_slices(a, a_types, to_a).unwrap();
+    }
+
+    fn history_urls(doc: &SyncDoc) -> Vec<String> {
+        let entries: Vec<serde_json::Value> = serde_json::from_str(&doc.read_history_json().unwrap()).unwrap();
+        let mut urls: Vec<String> = entries.iter().map(|e| e["url"].as_str().unwrap().to_string()).collect();
+        urls.sort();
+        urls
+    }
+
+    #[test]
+    fn toggling_a_type_mid_session_stops_it_both_ways() {
+        let (dir_a, dir_b) = (temp_dir(), temp_dir());
+        let mut a = SyncDoc::init(&dir_a, 1, "devA").unwrap();
+        let mut b = SyncDoc::init(&dir_b, 2, "devB").unwrap();
+        let all = DataType::ALL.to_vec();
+        let no_history: Vec<DataType> = all.iter().copied().filter(|t| *t != DataType::History).collect();
+
+        a.add_history("https://x.com", "X", None, 1).unwrap();
+        exchange(&mut a, &all, &mut b, &all);
+        assert_eq!(history_urls(&b), ["https://x.com"]);
+
+        // A turns history off; y was recorded just before and must not leave
+        a.add_history("https://y.com", "Y", None, 2).unwrap();
+        b.add_history("https://z.com", "Z", None, 3).unwrap();
+        a.write_setting("themeMode", "\"dark\"").unwrap();
+        exchange(&mut a, &no_history, &mut b, &all);
+        assert_eq!(history_urls(&a), ["https://x.com", "https://y.com"]);
+        assert_eq!(history_urls(&b), ["https://x.com", "https://z.com"]);
+        // other types keep flowing
+        let settings: serde_json::Value = serde_json::from_str(&b.read_settings_json().unwrap()).unwrap();
+        assert_eq!(settings["themeMode"], "dark");
+
+        // A never asked for history, and a peer that sends it anyway is ignored
+        let ext_a = hello_ext(&a, &no_history);
+        assert!(outgoing_slices(&b, &all, &ext_a.slices).is_empty());
+        let ext_stale = hello_ext(&a, &all);
+        let pushed = outgoing_slices(&b, &all, &ext_stale.slices);
+        assert!(!pushed.is_empty());
+        assert!(!import_slices(&mut a, &no_history, pushed).unwrap());
+        assert_eq!(history_urls(&a), ["https://x.com", "https://y.com"]);
+
+        // back on: both sides catch up
+        exchange(&mut a, &all, &mut b, &all);
+        assert_eq!(history_urls(&a), ["https://x.com", "https://y.com", "https://z.com"]);
+        assert_eq!(history_urls(&b), history_urls(&a));
+
+        let _ = std::fs::remove_dir_all(&dir_a);
+        let _ = std::fs::remove_dir_all(&dir_b);
+    }
+
+    #[test]
+    fn peer_that_stops_sharing_tabs_is_dropped() {
+        let (dir_a, dir_b) = (temp_dir(), temp_dir());
+        let mut a = SyncDoc::init(&dir_a, 1, "devA").unwrap();
+        let mut b = SyncDoc::init(&dir_b, 2, "devB").unwrap();
+        let all = DataType::ALL.to_vec();
+        let no_tabs: Vec<DataType> = all.iter().copied().filter(|t| *t != DataType::Tabs).collect();
+
+        a.write_tabs(r#"[{"id":"t1","url":"https://a.com","title":"A"}]"#).unwrap();
+        exchange(&mut a, &all, &mut b, &all);
+        assert!(!forget_unshared_tabs(&mut b, &all, &hello_ext(&a, &all), "devA"));
+        assert!(b.read_all_tabs().unwrap().contains("devA"));
+
+        assert!(forget_unshared_tabs(&mut b, &all, &hello_ext(&a, &no_tabs), "devA"));
+        assert!(!b.read_all_tabs().unwrap().contains("devA"));
+
+        let _ = std::fs::remove_dir_all(&dir_a);
+        let _ = std::fs::remove_dir_all(&dir_b);
+    }
+}
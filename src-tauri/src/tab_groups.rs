@@ -0,0 +1,272 @@
+use std::collections::HashSet;
+use std::fs;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::error::BushidoError;
+
+const MAX_GROUPS: usize = 100;
+const MAX_NAME_LEN: usize = 64;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TabGroup {
+    pub id: String,
+    pub name: String,
+    /// `#rrggbb`, like workspace colors
+    pub color: String,
+    /// members in the order they were added
+    pub tab_ids: Vec<String>,
+}
+
+/// Tab groups in creation order. A tab is in at most one group, and a group that
+/// loses its last tab is deleted. Saved with the session as `tabGroups`.
+pub struct TabGroupState {
+    groups: Mutex<Vec<TabGroup>>,
+}
+
+fn valid_name(name: &str) -> Result<String, BushidoError> {
+    let name = name.trim();
+    if name.chars().count() > MAX_NAME_LEN {
+        return Err(BushidoError::Limit(format!("group names are limited to {} characters", MAX_NAME_LEN)));
+    }
+    Ok(name.to_string())
+}
+
+fn valid_color(color: &str) -> Result<String, BushidoError> {
+    let color = color.trim();
+    let hex = color.strip_prefix('#').unwrap_or_default();
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(BushidoError::Other(format!("invalid group color: {}", color)));
+    }
+    Ok(color.to_lowercase())
+}
+
+impl TabGroupState {
+    pub fn new() -> Self {
+        Self { groups: Mutex::new(Vec::new()) }
+    }
+
+    pub fn list(&self) -> Vec<TabGroup> {
+        self.groups.lock().clone()
+    }
+
+    fn remove_from_all(groups: &mut Vec<TabGroup>, tab_id: &str) {
+        for g in groups.iter_mut() {
+            g.tab_ids.retain(|t| t != tab_id);
+        }
+        groups.retain(|g| !g.tab_ids.is_empty());
+    }
+
+    fn create(&self, name: &str, color: &str, tab_ids: Vec<String>) -> Result<TabGroup, BushidoError> {
+        let name = valid_name(name)?;
+        let color = valid_color(color)?;
+        let mut seen = HashSet::new();
+        let tab_ids: Vec<String> = tab_ids.into_iter().filter(|t| seen.insert(t.clone())).collect();
+        if tab_ids.is_empty() {
+            return Err(BushidoError::Other("a group needs at least one tab".into()));
+        }
+        let mut groups = self.groups.lock();
+        if groups.len() >= MAX_GROUPS {
+            return Err(BushidoError::Limit(format!("at most {} tab groups", MAX_GROUPS)));
+        }
+        for t in &tab_ids {
+            Self::remove_from_all(&mut groups, t);
+        }
+        let group = TabGroup { id: format!("group-{}", uuid::Uuid::new_v4()), name, color, tab_ids };
+        groups.push(group.clone());
+        Ok(group)
+    }
+
+    fn with_group(&self, group_id: &str, f: impl FnOnce(&mut TabGroup)) -> Result<(), BushidoError> {
+        let mut groups = self.groups.lock();
+        let group = groups.iter_mut().find(|g| g.id == group_id)
+            .ok_or_else(|| BushidoError::NotFound(format!("tab group {}", group_id)))?;
+        f(group);
+        Ok(())
+    }
+
+    /// Moves the tab out of any other group first
+    fn add_tab(&self, group_id: &str, tab_id: &str) -> Result<(), BushidoError> {
+        let mut groups = self.groups.lock();
+        let Some(pos) = groups.iter().position(|g| g.id == group_id) else {
+            return Err(BushidoError::NotFound(format!("tab group {}", group_id)));
+        };
+        if groups[pos].tab_ids.iter().any(|t| t == tab_id) {
+            return Ok(());
+        }
+        for g in groups.iter_mut() {
+            g.tab_ids.retain(|t| t != tab_id);
+        }
+        groups[pos].tab_ids.push(tab_id.to_string());
+        groups.retain(|g| !g.tab_ids.is_empty());
+        Ok(())
+    }
+
+    /// Whether the tab was in a group
+    pub fn remove_tab(&self, tab_id: &str) -> bool {
+        let mut groups = self.groups.lock();
+        let was_grouped = groups.iter().any(|g| g.tab_ids.iter().any(|t| t == tab_id));
+        Self::remove_from_all(&mut groups, tab_id);
+        was_grouped
+    }
+
+    /// Drop tabs the session no longer has (closed without going through close_tab's
+    /// cleanup, or lost on restore)
+    fn retain_tabs(&self, ids: &HashSet<String>) {
+        let mut groups = self.groups.lock();
+        for g in groups.iter_mut() {
+            g.tab_ids.retain(|t| ids.contains(t));
+        }
+        groups.retain(|g| !g.tab_ids.is_empty());
+    }
+
+    /// Put the groups on a session about to be saved, keeping only tabs it has
+    pub fn stamp_session(&self, session: &mut serde_json::Value) {
+        let ids: HashSet<String> = session.get("tabs").and_then(|t| t.as_array())
+            .map(|list| list.iter().filter_map(|t| t.get("id")?.as_str().map(String::from)).collect())
+            .unwrap_or_default();
+        self.retain_tabs(&ids);
+        if let Some(obj) = session.as_object_mut() {
+            obj.insert("tabGroups".into(), serde_json::json!(self.list()));
+        }
+    }
+
+    pub fn restore_from_session(&self, session: &serde_json::Value) {
+        let groups = session.get("tabGroups")
+            .and_then(|v| serde_json::from_value::<Vec<TabGroup>>(v.clone()).ok())
+            .unwrap_or_default();
+        *self.groups.lock() = groups;
+    }
+}
+
+/// Write the groups into session.json right away: group edits alone don't make the
+/// UI save the session
+fn persist(app: &AppHandle) {
+    let groups = app.state::<TabGroupState>().list();
+    let _ = app.emit_to("main", "tab-groups-changed", &groups);
+    let _guard = crate::SESSION_FILE_LOCK.lock();
+    let path = crate::session_path(app);
+    let Some(mut session) = fs::read_to_string(&path).ok()
+        .and_then(|s| serde_json::from_str::<serde_json::Value>(&s).ok())
+    else { return };
+    let Some(obj) = session.as_object_mut() else { return };
+    obj.insert("tabGroups".into(), serde_json::json!(groups));
+    if let Err(e) = fs::write(&path, session.to_string()) {
+        crate::crash_log::log_warn("tab_groups", &format!("could not save tab groups: {}", e));
+    }
+}
+
+/// A tab closed for good leaves its group
+pub fn forget_tab(app: &AppHandle, tab_id: &str) {
+    if app.state::<TabGroupState>().remove_tab(tab_id) {
+        persist(app);
+    }
+}
+
+#[tauri::command]
+pub async fn list_tab_groups(app: AppHandle) -> Result<Vec<TabGroup>, BushidoError> {
+    Ok(app.state::<TabGroupState>().list())
+}
+
+/// New group holding `tab_ids`, which leave whatever group they were in
+#[tauri::command]
+pub async fn create_group(app: AppHandle, name: String, color: String, tab_ids: Vec<String>) -> Result<TabGroup, BushidoError> {
+    let group = app.state::<TabGroupState>().create(&name, &color, tab_ids)?;
+    persist(&app);
+    Ok(group)
+}
+
+#[tauri::command]
+pub async fn add_tab_to_group(app: AppHandle, group_id: String, tab_id: String) -> Result<(), BushidoError> {
+    app.state::<TabGroupState>().add_tab(&group_id, &tab_id)?;
+    persist(&app);
+    Ok(())
+}
+
+/// Ungroups the tab; its group goes away when it was the last one in it
+#[tauri::command]
+pub async fn remove_tab_from_group(app: AppHandle, tab_id: String) -> Result<(), BushidoError> {
+    if app.state::<TabGroupState>().remove_tab(&tab_id) {
+        persist(&app);
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn rename_group(app: AppHandle, group_id: String, name: String) -> Result<(), BushidoError> {
+    let name = valid_name(&name)?;
+    app.state::<TabGroupState>().with_group(&group_id, |g| g.name = name)?;
+    persist(&app);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_group_color(app: AppHandle, group_id: String, color: String) -> Result<(), BushidoError> {
+    let color = valid_color(&color)?;
+    app.state::<TabGroupState>().with_group(&group_id, |g| g.color = color)?;
+    persist(&app);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(v: &[&str]) -> Vec<String> {
+        v.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn a_tab_belongs_to_one_group_and_empty_groups_go() {
+        let state = TabGroupState::new();
+        let work = state.create(" Work ", "#6366F1", ids(&["tab-1", "tab-2", "tab-1"])).unwrap();
+        assert_eq!(work.name, "Work");
+        assert_eq!(work.color, "#6366f1");
+        assert_eq!(work.tab_ids, ids(&["tab-1", "tab-2"]));
+        let news = state.create("News", "#f43f5e", ids(&["tab-3"])).unwrap();
+
+        state.add_tab(&news.id, "tab-1").unwrap();
+        let groups = state.list();
+        assert_eq!(groups[0].tab_ids, ids(&["tab-2"]));
+        assert_eq!(groups[1].tab_ids, ids(&["tab-3", "tab-1"]));
+
+        // moving the last tab out deletes its group
+        state.add_tab(&news.id, "tab-2").unwrap();
+        assert_eq!(state.list().len(), 1);
+        assert!(state.add_tab(&work.id, "tab-4").is_err());
+
+        assert!(state.remove_tab("tab-3"));
+        assert!(!state.remove_tab("tab-3"));
+        state.remove_tab("tab-1");
+        state.remove_tab("tab-2");
+        assert!(state.list().is_empty());
+    }
+
+    #[test]
+    fn rejects_bad_color_name_and_empty_groups() {
+        let state = TabGroupState::new();
+        assert!(state.create("x", "red", ids(&["tab-1"])).is_err());
+        assert!(state.create("x", "#12345g", ids(&["tab-1"])).is_err());
+        assert!(state.create(&"n".repeat(MAX_NAME_LEN + 1), "#123456", ids(&["tab-1"])).is_err());
+        assert!(state.create("x", "#123456", Vec::new()).is_err());
+    }
+
+    #[test]
+    fn round_trips_through_the_session() {
+        let state = TabGroupState::new();
+        state.create("Work", "#22c55e", ids(&["tab-1", "tab-gone"])).unwrap();
+        state.create("Gone", "#22c55e", ids(&["tab-gone-too"])).unwrap();
+        let mut session = serde_json::json!({"tabs": [{"id": "tab-1"}, {"id": "tab-2"}]});
+        state.stamp_session(&mut session);
+        assert_eq!(session["tabGroups"].as_array().unwrap().len(), 1);
+        assert_eq!(session["tabGroups"][0]["tabIds"], serde_json::json!(["tab-1"]));
+
+        let restored = TabGroupState::new();
+        restored.restore_from_session(&session);
+        assert_eq!(restored.list(), state.list());
+        restored.restore_from_session(&serde_json::json!({"tabGroups": "junk"}));
+        assert!(restored.list().is_empty());
+    }
+}
@@ -0,0 +1,87 @@
+use std::collections::HashMap;
+use parking_lot::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::error::BushidoError;
+
+const WINDOW_W: f64 = 1100.0;
+const WINDOW_H: f64 = 750.0;
+
+/// Tabs torn out into a window of their own: tab id → window label. create_tab puts
+/// these tabs' webviews in that window, and layout/suspend/hibernate leave them alone.
+/// Like the private mark it survives a discard/reload so the tab comes back there.
+pub struct TabWindowState {
+    windows: Mutex<HashMap<String, String>>,
+}
+
+impl TabWindowState {
+    pub fn new() -> Self {
+        Self { windows: Mutex::new(HashMap::new()) }
+    }
+
+    pub fn window_for(&self, tab_id: &str) -> Option<String> {
+        self.windows.lock().get(tab_id).cloned()
+    }
+
+    pub fn is_detached(&self, tab_id: &str) -> bool {
+        self.windows.lock().contains_key(tab_id)
+    }
+}
+
+/// The tab is closed for good: drop the mark and its window, which only ever holds it
+pub fn forget_tab(app: &AppHandle, tab_id: &str) {
+    let label = app.state::<TabWindowState>().windows.lock().remove(tab_id);
+    if let Some(window) = label.and_then(|l| app.get_window(&l)) {
+        let _ = window.close();
+    }
+}
+
+/// Move tab `id` into a new window and return the window's label. A webview can't
+/// be reparented between WebView2 windows, so the tab's webview is closed here and
+/// the UI recreates it with create_tab, as after a discard; the scroll position is
+/// carried over. The main window is never closed, even if this was its last tab.
+#[tauri::command]
+pub async fn move_tab_to_new_window(app: AppHandle, id: String) -> Result<String, BushidoError> {
+    if app.state::<crate::PanelState>().ids.lock().contains(&id) {
+        return Err(BushidoError::Other("panels can't be moved to a window".into()));
+    }
+    if !app.state::<crate::WebviewState>().tabs.lock().contains_key(&id) {
+        return Err(BushidoError::NotFound(format!("tab {}", id)));
+    }
+    if let Some(label) = app.state::<TabWindowState>().window_for(&id) {
+        return Err(BushidoError::Other(format!("tab is already in window {}", label)));
+    }
+    let url = app.state::<crate::UrlState>().tabs.lock().get(&id).cloned().unwrap_or_default();
+
+    let label = format!("tab-window-{}", uuid::Uuid::new_v4().simple());
+    let title = url::Url::parse(&url).ok()
+        .and_then(|u| u.host_str().map(|h| format!("{} - Bushido", h)))
+        .unwrap_or_else(|| "Bushido".to_string());
+    let window = tauri::window::WindowBuilder::new(&app, &label)
+        .title(title)
+        .inner_size(WINDOW_W, WINDOW_H)
+        .min_inner_size(400.0, 300.0)
+        .build()?;
+
+    let app_events = app.clone();
+    let tab_events = id.clone();
+    window.on_window_event(move |event| {
+        if !matches!(event, tauri::WindowEvent::Destroyed) {
+            return;
+        }
+        // closing the window closes the tab — unless closing the tab got here first
+        if app_events.state::<TabWindowState>().windows.lock().remove(&tab_events).is_none() {
+            return;
+        }
+        let app = app_events.clone();
+        let id = tab_events.clone();
+        tauri::async_runtime::spawn(async move {
+            let _ = crate::close_tab(app.clone(), id.clone(), Some(true), None, None, None, None).await;
+            let _ = app.emit_to("main", "tab-window-closed", serde_json::json!({ "id": id }));
+        });
+    });
+
+    crate::close_tab(app.clone(), id.clone(), None, None, None, None, Some(true)).await?;
+    app.state::<TabWindowState>().windows.lock().insert(id, label.clone());
+    Ok(label)
+}
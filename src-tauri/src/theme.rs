@@ -0,0 +1,229 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use parking_lot::Mutex;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::error::BushidoError;
+
+const THEMES: &[&str] = &["auto", "light", "dark"];
+const MAX_SITE_THEMES: usize = 500;
+
+/// Forced `prefers-color-scheme`, emulated per tab over CDP so a pick on one tab leaves
+/// the other tabs of its workspace alone.
+pub struct ThemeState {
+    default: Mutex<String>,
+    /// domain → theme, persisted in theme_sites.json
+    sites: Mutex<HashMap<String, String>>,
+    /// explicit set_tab_theme picks; win over site rules until the tab closes
+    tabs: Mutex<HashMap<String, String>>,
+    /// what was last applied per tab, so navigation only re-applies on change
+    applied: Mutex<HashMap<String, String>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThemeConfig {
+    pub default: String,
+    pub sites: HashMap<String, String>,
+}
+
+fn sites_path(app: &AppHandle) -> PathBuf {
+    crate::data_dir(app).join("theme_sites.json")
+}
+
+fn save_sites(app: &AppHandle, sites: &HashMap<String, String>) {
+    if let Ok(json) = serde_json::to_string(sites) {
+        let _ = fs::write(sites_path(app), json);
+    }
+}
+
+fn check_theme(theme: &str) -> Result<String, BushidoError> {
+    let t = theme.trim().to_lowercase();
+    if THEMES.contains(&t.as_str()) {
+        Ok(t)
+    } else {
+        Err(BushidoError::Other(format!("unknown theme '{}' (expected auto, light or dark)", theme)))
+    }
+}
+
+impl ThemeState {
+    pub fn load(app: &AppHandle) -> Self {
+        let sites: HashMap<String, String> = fs::read_to_string(sites_path(app)).ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default();
+        Self {
+            default: Mutex::new("auto".into()),
+            sites: Mutex::new(sites.into_iter().filter(|(_, t)| THEMES.contains(&t.as_str())).collect()),
+            tabs: Mutex::new(HashMap::new()),
+            applied: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// tab pick > site rule > global default
+    fn resolve(&self, tab_id: &str, url: &str) -> String {
+        if let Some(t) = self.tabs.lock().get(tab_id) {
+            return t.clone();
+        }
+        let host = url::Url::parse(url).ok()
+            .and_then(|u| u.host_str().map(|h| h.to_lowercase()))
+            .unwrap_or_default();
+        if !host.is_empty() {
+            let sites = self.sites.lock();
+            // most specific rule wins
+            if let Some((_, t)) = sites.iter()
+                .filter(|(d, _)| crate::domain_matches(&host, d))
+                .max_by_key(|(d, _)| d.len())
+            {
+                return t.clone();
+            }
+        }
+        self.default.lock().clone()
+    }
+
+    pub fn forget_tab(&self, tab_id: &str) {
+        self.tabs.lock().remove(tab_id);
+        self.applied.lock().remove(tab_id);
+    }
+}
+
+/// Emulate the color scheme on the tab's own webview and confirm with `tab-theme-changed`.
+/// Nothing is recorded while the webview doesn't exist yet (the first on_navigation runs
+/// before add_child returns), so create_tab's call afterwards still applies it.
+fn apply(app: &AppHandle, tab_id: &str, theme: &str) {
+    let Some(wv) = app.get_webview(tab_id) else { return };
+    app.state::<ThemeState>().applied.lock().insert(tab_id.to_string(), theme.to_string());
+
+    #[cfg(windows)]
+    {
+        let app_t = app.clone();
+        let id = tab_id.to_string();
+        let theme = theme.to_string();
+        let _ = wv.with_webview(move |pw| {
+            // an empty value drops the override, which is what "auto" means
+            let value = match theme.as_str() {
+                "light" | "dark" => theme.as_str(),
+                _ => "",
+            };
+            let params = serde_json::json!({
+                "features": [{ "name": "prefers-color-scheme", "value": value }]
+            }).to_string();
+            let method: Vec<u16> = "Emulation.setEmulatedMedia".encode_utf16().chain(std::iter::once(0)).collect();
+            let params: Vec<u16> = params.encode_utf16().chain(std::iter::once(0)).collect();
+            let app_done = app_t.clone();
+            let id_done = id.clone();
+            let theme_done = theme.clone();
+            let done = webview2_com::CallDevToolsProtocolMethodCompletedHandler::create(Box::new(move |hr, _| {
+                let applied = hr.is_ok();
+                if !applied {
+                    crate::crash_log::log_warn("theme", &format!("setEmulatedMedia failed: {:?}", hr));
+                }
+                let _ = app_done.emit_to("main", "tab-theme-changed", serde_json::json!({
+                    "id": id_done, "theme": theme_done, "applied": applied
+                }));
+                Ok(())
+            }));
+            let sent = unsafe {
+                pw.controller().CoreWebView2().and_then(|core| core.CallDevToolsProtocolMethod(
+                    windows::core::PCWSTR::from_raw(method.as_ptr()),
+                    windows::core::PCWSTR::from_raw(params.as_ptr()),
+                    &done,
+                ))
+            };
+            if let Err(e) = sent {
+                crate::crash_log::log_warn("theme", &format!("setEmulatedMedia not sent: {}", e));
+                let _ = app_t.emit_to("main", "tab-theme-changed", serde_json::json!({
+                    "id": id, "theme": theme, "applied": false
+                }));
+            }
+        });
+    }
+    #[cfg(not(windows))]
+    {
+        let _ = wv;
+        let _ = app.emit_to("main", "tab-theme-changed", serde_json::json!({
+            "id": tab_id, "theme": theme, "applied": false
+        }));
+    }
+}
+
+/// Called from create_tab and top-level navigation; only re-emulates when the
+/// resolved theme differs from what this tab last had.
+pub fn apply_for_url(app: &AppHandle, tab_id: &str, url: &str) {
+    let ts = app.state::<ThemeState>();
+    let theme = ts.resolve(tab_id, url);
+    let unchanged = ts.applied.lock().get(tab_id) == Some(&theme);
+    // a fresh tab at "auto" needs nothing — that's the runtime default
+    if unchanged || (theme == "auto" && !ts.applied.lock().contains_key(tab_id)) {
+        return;
+    }
+    apply(app, tab_id, &theme);
+}
+
+/// Force a tab's color scheme. "auto" drops the pick and falls back to site rules/default.
+#[tauri::command]
+pub async fn set_tab_theme(app: AppHandle, id: String, theme: String) -> Result<(), BushidoError> {
+    let theme = check_theme(&theme)?;
+    let ts = app.state::<ThemeState>();
+    if theme == "auto" {
+        ts.tabs.lock().remove(&id);
+    } else {
+        ts.tabs.lock().insert(id.clone(), theme.clone());
+    }
+    let url = app.get_webview(&id).and_then(|wv| wv.url().ok()).map(|u| u.to_string()).unwrap_or_default();
+    let resolved = ts.resolve(&id, &url);
+    apply(&app, &id, &resolved);
+    Ok(())
+}
+
+fn reapply_open_tabs(app: &AppHandle) {
+    let tabs: Vec<String> = app.state::<crate::WebviewState>().tabs.lock().keys().cloned().collect();
+    for id in tabs {
+        if let Some(url) = app.get_webview(&id).and_then(|wv| wv.url().ok()) {
+            apply_for_url(app, &id, url.as_str());
+        }
+    }
+}
+
+/// Global default applied to new tabs (and open ones without a pick or site rule)
+#[tauri::command]
+pub async fn set_default_theme(app: AppHandle, theme: String) -> Result<(), BushidoError> {
+    let theme = check_theme(&theme)?;
+    *app.state::<ThemeState>().default.lock() = theme;
+    reapply_open_tabs(&app);
+    Ok(())
+}
+
+/// Persist a per-site theme; None (or "auto") removes the rule
+#[tauri::command]
+pub async fn set_site_theme(app: AppHandle, domain: String, theme: Option<String>) -> Result<(), BushidoError> {
+    let domain = domain.trim().trim_start_matches("www.").to_lowercase();
+    if domain.is_empty() { return Err(BushidoError::Other("empty domain".into())); }
+    let theme = theme.map(|t| check_theme(&t)).transpose()?.filter(|t| t != "auto");
+    let snapshot = {
+        let ts = app.state::<ThemeState>();
+        let mut sites = ts.sites.lock();
+        match theme {
+            Some(t) => {
+                if !sites.contains_key(&domain) && sites.len() >= MAX_SITE_THEMES {
+                    return Err(BushidoError::Limit(format!("at most {} site themes", MAX_SITE_THEMES)));
+                }
+                sites.insert(domain, t);
+            }
+            None => { sites.remove(&domain); }
+        }
+        sites.clone()
+    };
+    save_sites(&app, &snapshot);
+    reapply_open_tabs(&app);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_theme_config(app: AppHandle) -> Result<ThemeConfig, BushidoError> {
+    let ts = app.state::<ThemeState>();
+    let default = ts.default.lock().clone();
+    let sites = ts.sites.lock().clone();
+    Ok(ThemeConfig { default, sites })
+}
@@ -0,0 +1,106 @@
+use std::collections::HashMap;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::error::BushidoError;
+
+/// What a tab may do while it's in the background
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThrottlePolicy {
+    /// keeps running at full speed: never suspended or hibernated (music, timers)
+    Disabled,
+    /// suspended as soon as it's hidden
+    Suspend,
+    /// the engine's default timer throttling, plus the usual idle suspend
+    #[default]
+    Throttle,
+}
+
+impl ThrottlePolicy {
+    /// The same policy for wry, which only honours it on macOS 14+. WebView2 and
+    /// WebKitGTK ignore it, so there suspend_tab and hibernation do the work instead.
+    pub fn to_config(self) -> tauri::utils::config::BackgroundThrottlingPolicy {
+        use tauri::utils::config::BackgroundThrottlingPolicy as P;
+        match self {
+            ThrottlePolicy::Disabled => P::Disabled,
+            ThrottlePolicy::Suspend => P::Suspend,
+            ThrottlePolicy::Throttle => P::Throttle,
+        }
+    }
+}
+
+/// Tab id → non-default policy. Like MuteState it survives discard/reload.
+pub struct ThrottleState {
+    tabs: Mutex<HashMap<String, ThrottlePolicy>>,
+}
+
+impl ThrottleState {
+    pub fn new() -> Self {
+        Self { tabs: Mutex::new(HashMap::new()) }
+    }
+
+    pub fn policy(&self, id: &str) -> ThrottlePolicy {
+        self.tabs.lock().get(id).copied().unwrap_or_default()
+    }
+
+    pub fn set(&self, id: &str, policy: ThrottlePolicy) {
+        let mut tabs = self.tabs.lock();
+        if policy == ThrottlePolicy::default() {
+            tabs.remove(id);
+        } else {
+            tabs.insert(id.to_string(), policy);
+        }
+    }
+
+    /// Tabs the user asked to keep running — suspend and hibernation leave them alone
+    pub fn keeps_running(&self, id: &str) -> bool {
+        self.policy(id) == ThrottlePolicy::Disabled
+    }
+
+    pub fn forget_tab(&self, id: &str) {
+        self.tabs.lock().remove(id);
+    }
+}
+
+/// Set how tab `id` behaves in the background. Only macOS applies it inside the
+/// engine, and only when the webview is next created; elsewhere "disabled" exempts
+/// the tab from suspending (resuming it if it's suspended now) and "suspend" has the
+/// UI suspend it as soon as it's hidden.
+#[tauri::command]
+pub async fn set_tab_throttling(app: AppHandle, id: String, policy: ThrottlePolicy) -> Result<(), BushidoError> {
+    app.state::<ThrottleState>().set(&id, policy);
+    if policy == ThrottlePolicy::Disabled {
+        crate::resume_tab(app.clone(), id.clone()).await?;
+    }
+    let _ = app.emit_to("main", "tab-throttling-changed", serde_json::json!({ "id": id, "policy": policy }));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_non_default_policies_are_kept() {
+        let s = ThrottleState::new();
+        assert_eq!(s.policy("t"), ThrottlePolicy::Throttle);
+        s.set("t", ThrottlePolicy::Disabled);
+        assert!(s.keeps_running("t"));
+        s.set("t", ThrottlePolicy::Suspend);
+        assert!(!s.keeps_running("t"));
+        s.set("t", ThrottlePolicy::Throttle);
+        assert!(s.tabs.lock().is_empty());
+    }
+
+    #[test]
+    fn policy_names_round_trip() {
+        for (p, name) in [(ThrottlePolicy::Disabled, "disabled"), (ThrottlePolicy::Suspend, "suspend"), (ThrottlePolicy::Throttle, "throttle")] {
+            let json = serde_json::to_string(&p).unwrap();
+            assert_eq!(json, format!("\"{}\"", name));
+            assert_eq!(serde_json::from_str::<ThrottlePolicy>(&json).unwrap(), p);
+        }
+        assert!(serde_json::from_str::<ThrottlePolicy>("\"off\"").is_err());
+    }
+}
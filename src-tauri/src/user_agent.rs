@@ -0,0 +1,246 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use parking_lot::Mutex;
+use tauri::{AppHandle, Manager};
+
+use crate::error::BushidoError;
+
+pub const DESKTOP_UA: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/131.0.0.0 Safari/537.36";
+pub const MOBILE_UA: &str = "Mozilla/5.0 (Linux; Android 10; K) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/131.0.0.0 Mobile Safari/537.36";
+const MAX_SITE_USER_AGENTS: usize = 500;
+const MAX_UA_LEN: usize = 512;
+
+/// Per-site user-agent overrides. Tabs without a matching rule keep the desktop
+/// UA (mobile for panels) they were built with.
+pub struct UserAgentState {
+    /// domain → UA, persisted in user_agent_sites.json
+    sites: Mutex<HashMap<String, String>>,
+    /// override currently set on each tab, so navigation only touches tabs that change
+    applied: Mutex<HashMap<String, String>>,
+}
+
+fn sites_path(app: &AppHandle) -> PathBuf {
+    crate::data_dir(app).join("user_agent_sites.json")
+}
+
+fn save_sites(app: &AppHandle, sites: &HashMap<String, String>) {
+    if let Ok(json) = serde_json::to_string(sites) {
+        let _ = fs::write(sites_path(app), json);
+    }
+}
+
+/// The UA goes out as a request header: no line breaks or other control characters.
+fn clean_user_agent(ua: &str) -> Result<String, BushidoError> {
+    let ua: String = ua.chars().map(|c| if c.is_control() { ' ' } else { c }).collect();
+    let ua = ua.split_whitespace().collect::<Vec<_>>().join(" ");
+    if ua.is_empty() {
+        return Err(BushidoError::Other("user agent is empty".into()));
+    }
+    if ua.len() > MAX_UA_LEN {
+        return Err(BushidoError::Limit(format!("user agent is longer than {} characters", MAX_UA_LEN)));
+    }
+    Ok(ua)
+}
+
+fn host_of(url: &str) -> String {
+    url::Url::parse(url).ok()
+        .and_then(|u| u.host_str().map(|h| h.to_lowercase()))
+        .unwrap_or_default()
+}
+
+impl UserAgentState {
+    pub fn load(app: &AppHandle) -> Self {
+        let sites: HashMap<String, String> = fs::read_to_string(sites_path(app)).ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default();
+        Self {
+            sites: Mutex::new(sites.into_iter().filter_map(|(d, ua)| Some((d, clean_user_agent(&ua).ok()?))).collect()),
+            applied: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Most specific site rule for the URL's host, if any
+    fn site_override(&self, url: &str) -> Option<String> {
+        let host = host_of(url);
+        if host.is_empty() {
+            return None;
+        }
+        self.sites.lock().iter()
+            .filter(|(d, _)| crate::domain_matches(&host, d))
+            .max_by_key(|(d, _)| d.len())
+            .map(|(_, ua)| ua.clone())
+    }
+
+    /// UA to build a new webview with; records the override so navigation can undo it
+    pub fn for_new_tab(&self, tab_id: &str, url: &str, is_panel: bool) -> String {
+        match self.site_override(url) {
+            Some(ua) => {
+                self.applied.lock().insert(tab_id.to_string(), ua.clone());
+                ua
+            }
+            None => (if is_panel { MOBILE_UA } else { DESKTOP_UA }).to_string(),
+        }
+    }
+
+    pub fn forget_tab(&self, tab_id: &str) {
+        self.applied.lock().remove(tab_id);
+    }
+
+    /// The override `url` wants and the UA that means for the tab, or None when the
+    /// tab already has it
+    fn change_for(&self, app: &AppHandle, tab_id: &str, url: &str) -> Option<(Option<String>, String)> {
+        let want = self.site_override(url);
+        if want == self.applied.lock().get(tab_id).cloned() {
+            return None;
+        }
+        let ua = want.clone().unwrap_or_else(|| default_for(app, tab_id).to_string());
+        Some((want, ua))
+    }
+
+    fn record(&self, tab_id: &str, want: Option<String>) {
+        match want {
+            Some(ua) => { self.applied.lock().insert(tab_id.to_string(), ua); }
+            None => { self.applied.lock().remove(tab_id); }
+        }
+    }
+}
+
+fn default_for(app: &AppHandle, tab_id: &str) -> &'static str {
+    if app.state::<crate::PanelState>().ids.lock().contains(tab_id) { MOBILE_UA } else { DESKTOP_UA }
+}
+
+/// `Settings.UserAgent` on the tab's core; requests made from then on carry it.
+#[cfg(windows)]
+fn set_on_core(core: &webview2_com::Microsoft::Web::WebView2::Win32::ICoreWebView2, ua: &str) -> bool {
+    use webview2_com::Microsoft::Web::WebView2::Win32::*;
+    use windows::core::Interface;
+    unsafe {
+        core.Settings()
+            .and_then(|settings| settings.cast::<ICoreWebView2Settings2>())
+            .and_then(|s2| s2.SetUserAgent(&windows_core::HSTRING::from(ua)))
+            .is_ok()
+    }
+}
+
+/// Swap the UA on a live webview; the current document keeps what it loaded with.
+/// Only WebView2 can change it after creation.
+fn apply(app: &AppHandle, tab_id: &str, ua: &str) -> Result<(), BushidoError> {
+    let wv = app.get_webview(tab_id).ok_or_else(|| BushidoError::NotFound(format!("tab {}", tab_id)))?;
+
+    #[cfg(windows)]
+    {
+        let ua = ua.to_string();
+        let id = tab_id.to_string();
+        wv.with_webview(move |pw| {
+            let applied = unsafe { pw.controller().CoreWebView2() }.is_ok_and(|core| set_on_core(&core, &ua));
+            if !applied {
+                crate::crash_log::log_warn("user_agent", &format!("could not set user agent for {}", id));
+            }
+        })?;
+        Ok(())
+    }
+    #[cfg(not(windows))]
+    {
+        let _ = (wv, ua);
+        Err(BushidoError::WebView("the user agent of an open tab can't be changed on this platform".into()))
+    }
+}
+
+/// Put on the site's UA for `url`, or take it off again when leaving an overridden
+/// site. Goes through the event loop, so it's for explicit reloads; navigations use
+/// `apply_on_navigation`.
+pub fn apply_for_url(app: &AppHandle, tab_id: &str, url: &str) {
+    let uas = app.state::<UserAgentState>();
+    let Some((want, ua)) = uas.change_for(app, tab_id, url) else { return };
+    if apply(app, tab_id, &ua).is_ok() {
+        uas.record(tab_id, want);
+    }
+}
+
+/// From the tab's WebView2 NavigationStarting handler: set synchronously, while the
+/// navigation is still pending, so its own request already carries the site's UA.
+#[cfg(windows)]
+pub fn apply_on_navigation(app: &AppHandle, tab_id: &str, url: &str, core: &webview2_com::Microsoft::Web::WebView2::Win32::ICoreWebView2) {
+    let uas = app.state::<UserAgentState>();
+    let Some((want, ua)) = uas.change_for(app, tab_id, url) else { return };
+    if set_on_core(core, &ua) {
+        uas.record(tab_id, want);
+    } else {
+        crate::crash_log::log_warn("user_agent", &format!("could not set user agent for {}", tab_id));
+    }
+}
+
+/// Re-resolve the tab's UA and reload so the page itself sees the change,
+/// without recreating the webview.
+#[tauri::command]
+pub async fn reload_with_user_agent(app: AppHandle, id: String) -> Result<(), BushidoError> {
+    let wv = app.get_webview(&id).ok_or_else(|| BushidoError::NotFound(format!("tab {}", id)))?;
+    let url = wv.url().map(|u| u.to_string()).unwrap_or_default();
+    apply_for_url(&app, &id, &url);
+    wv.eval("window.location.reload()")?;
+    Ok(())
+}
+
+/// Persist a per-site UA; None removes the rule. "desktop" and "mobile" stand for the
+/// built-in UAs. Open tabs on that site reload with it.
+#[tauri::command]
+pub async fn set_site_user_agent(app: AppHandle, domain: String, ua: Option<String>) -> Result<(), BushidoError> {
+    let domain = domain.trim().trim_start_matches("www.").to_lowercase();
+    if domain.is_empty() { return Err(BushidoError::Other("empty domain".into())); }
+    let ua = ua.map(|u| match u.as_str() {
+        "desktop" => Ok(DESKTOP_UA.to_string()),
+        "mobile" => Ok(MOBILE_UA.to_string()),
+        _ => clean_user_agent(&u),
+    }).transpose()?;
+    let snapshot = {
+        let uas = app.state::<UserAgentState>();
+        let mut sites = uas.sites.lock();
+        match ua {
+            Some(ua) => {
+                if !sites.contains_key(&domain) && sites.len() >= MAX_SITE_USER_AGENTS {
+                    return Err(BushidoError::Limit(format!("at most {} site user agents", MAX_SITE_USER_AGENTS)));
+                }
+                sites.insert(domain.clone(), ua);
+            }
+            None => { sites.remove(&domain); }
+        }
+        sites.clone()
+    };
+    save_sites(&app, &snapshot);
+
+    let tabs: Vec<String> = app.state::<crate::WebviewState>().tabs.lock().keys().cloned().collect();
+    for id in tabs {
+        let on_site = app.get_webview(&id)
+            .and_then(|wv| wv.url().ok())
+            .is_some_and(|u| crate::domain_matches(&host_of(u.as_str()), &domain));
+        if on_site {
+            let _ = reload_with_user_agent(app.clone(), id).await;
+        }
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_site_user_agents(app: AppHandle) -> Result<HashMap<String, String>, BushidoError> {
+    Ok(app.state::<UserAgentState>().sites.lock().clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_line_breaks_and_controls() {
+        assert_eq!(clean_user_agent("Foo/1.0\r\nX-Evil: 1").unwrap(), "Foo/1.0 X-Evil: 1");
+        assert_eq!(clean_user_agent("  Foo/1.0\t(bar)  ").unwrap(), "Foo/1.0 (bar)");
+    }
+
+    #[test]
+    fn rejects_empty_and_oversized() {
+        assert!(clean_user_agent("").is_err());
+        assert!(clean_user_agent(" \r\n ").is_err());
+        assert!(clean_user_agent(&"a".repeat(MAX_UA_LEN + 1)).is_err());
+        assert!(clean_user_agent(DESKTOP_UA).is_ok());
+    }
+}
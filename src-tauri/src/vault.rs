@@ -1,7 +1,9 @@
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
 use parking_lot::Mutex;
 use rusqlite::Connection;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use rand::Rng;
 use argon2::{Argon2, PasswordHasher, PasswordVerifier};
@@ -9,12 +11,28 @@ use argon2::password_hash::{SaltString, PasswordHash};
 use chacha20poly1305::{ChaCha20Poly1305, KeyInit, aead::Aead};
 use chacha20poly1305::aead::generic_array::GenericArray;
 
+use tauri::{Emitter, Manager};
+
+use crate::error::BushidoError;
+use crate::totp::{self, TotpCode};
 use crate::sync::keys::{dpapi_encrypt, dpapi_decrypt};
 
+// until the frontend passes the user's setting; matches its default (0 disables)
+pub const DEFAULT_AUTOLOCK_MINUTES: u64 = 15;
+// a day; anything longer might as well be "never" (0)
+const MAX_AUTOLOCK_MINUTES: u64 = 24 * 60;
+const AUTOLOCK_CHECK_INTERVAL: Duration = Duration::from_secs(15);
+const BACKUP_FORMAT: &str = "bushido-vault";
+const BACKUP_VERSION: u32 = 1;
+
 pub struct VaultState {
     pub db_path: PathBuf,
     // derived key from master password, None = locked
     pub derived_key: Mutex<Option<[u8; 32]>>,
+    // idle minutes before the key is dropped, 0 = never
+    pub autolock_minutes: AtomicU64,
+    // last vault access or user interaction
+    last_activity: Mutex<Instant>,
 }
 
 #[derive(Serialize, Clone)]
@@ -24,13 +42,83 @@ pub struct VaultEntry {
     pub username: String,
     pub password: String,
     pub notes: String,
+    // the secret itself stays in the db; codes come from vault_get_totp
+    pub has_totp: bool,
     pub created_at: i64,
     pub updated_at: i64,
 }
 
+/// One entry as it travels inside an encrypted backup.
+#[derive(Serialize, Deserialize)]
+struct BackupEntry {
+    domain: String,
+    username: String,
+    password: String,
+    #[serde(default)]
+    notes: String,
+    #[serde(default)]
+    totp_secret: Option<String>,
+    created_at: i64,
+    updated_at: i64,
+}
+
+/// On-disk backup file. Everything but the header is ChaCha20-Poly1305 under an
+/// Argon2id key from the export password, so the file is portable between machines
+/// (unlike the DPAPI-wrapped db).
+#[derive(Serialize, Deserialize)]
+struct BackupFile {
+    format: String,
+    version: u32,
+    kdf: String,
+    salt: String,
+    nonce: String,
+    data: String,
+}
+
+/// Security-checkup row for one entry. Never carries the password itself.
+#[derive(Serialize)]
+pub struct VaultAuditEntry {
+    pub id: String,
+    pub domain: String,
+    pub username: String,
+    /// 0 (trivial) to 4 (strong)
+    pub strength: u8,
+    pub entropy_bits: u32,
+    /// same password saved under another entry
+    pub reused: bool,
+    /// days since the password itself last changed; other edits don't reset it
+    pub age_days: i64,
+}
+
+#[derive(Serialize)]
+pub struct VaultImportResult {
+    pub imported: usize,
+    pub skipped: usize,
+}
+
 impl VaultState {
     pub fn new(db_path: PathBuf) -> Self {
-        Self { db_path, derived_key: Mutex::new(None) }
+        Self {
+            db_path,
+            derived_key: Mutex::new(None),
+            autolock_minutes: AtomicU64::new(DEFAULT_AUTOLOCK_MINUTES),
+            last_activity: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Restart the idle clock.
+    pub fn touch(&self) {
+        *self.last_activity.lock() = Instant::now();
+    }
+
+    /// Zero and drop the derived key. Returns whether the vault was unlocked.
+    fn lock_key(&self) -> bool {
+        let mut guard = self.derived_key.lock();
+        let Some(ref mut key) = *guard else { return false };
+        // zero out before dropping
+        key.iter_mut().for_each(|b| *b = 0);
+        *guard = None;
+        true
     }
 
     pub fn init_db(&self) -> Result<(), String> {
@@ -51,7 +139,15 @@ impl VaultState {
                 key TEXT PRIMARY KEY,
                 value BLOB NOT NULL
             );"
-        ).map_err(|e| format!("db init: {}", e))
+        ).map_err(|e| format!("db init: {}", e))?;
+        // added after the first release; fails harmlessly once the column exists
+        let _ = conn.execute_batch(
+            "ALTER TABLE vault_entries ADD COLUMN totp_enc BLOB;
+            ALTER TABLE vault_entries ADD COLUMN totp_nonce BLOB;"
+        );
+        // updated_at also moves on username/notes/2FA edits, so password age gets its own
+        let _ = conn.execute_batch("ALTER TABLE vault_entries ADD COLUMN password_changed_at INTEGER;");
+        Ok(())
     }
 
     fn open_db(&self) -> Result<Connection, String> {
@@ -59,7 +155,7 @@ impl VaultState {
     }
 
     // encrypt password: chacha20 then dpapi wrap
-    fn encrypt_password(&self, plaintext: &str) -> Result<(Vec<u8>, Vec<u8>), String> {
+    fn encrypt_password(&self, plaintext: &str) -> Result<(Vec<u8>, Vec<u8>), BushidoError> {
         let key = self.get_key()?;
         let mut nonce_bytes = [0u8; 12];
         rand::thread_rng().fill(&mut nonce_bytes);
@@ -71,22 +167,81 @@ impl VaultState {
         Ok((wrapped, nonce_bytes.to_vec()))
     }
 
-    fn decrypt_password(&self, enc: &[u8], nonce: &[u8]) -> Result<String, String> {
+    fn decrypt_password(&self, enc: &[u8], nonce: &[u8]) -> Result<String, BushidoError> {
         let key = self.get_key()?;
         let unwrapped = dpapi_decrypt(enc)?;
         let nonce_arr = GenericArray::from_slice(nonce);
         let cipher = ChaCha20Poly1305::new(GenericArray::from_slice(&key));
         let decrypted = cipher.decrypt(nonce_arr, unwrapped.as_ref())
             .map_err(|_| "decrypt failed".to_string())?;
-        String::from_utf8(decrypted).map_err(|_| "invalid utf8".to_string())
+        String::from_utf8(decrypted).map_err(|_| "invalid utf8".into())
+    }
+
+    /// Every entry with its secrets decrypted, for backups. Needs the vault unlocked.
+    fn backup_entries(&self) -> Result<Vec<BackupEntry>, BushidoError> {
+        self.get_key()?;
+        let conn = self.open_db()?;
+        let mut stmt = conn.prepare(
+            "SELECT domain, username, password_enc, nonce, notes, totp_enc, totp_nonce, created_at, updated_at FROM vault_entries ORDER BY domain, updated_at DESC"
+        ).map_err(|e| format!("query: {}", e))?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?, row.get::<_, String>(1)?,
+                row.get::<_, Vec<u8>>(2)?, row.get::<_, Vec<u8>>(3)?, row.get::<_, String>(4)?,
+                row.get::<_, Option<Vec<u8>>>(5)?, row.get::<_, Option<Vec<u8>>>(6)?,
+                row.get::<_, i64>(7)?, row.get::<_, i64>(8)?,
+            ))
+        }).map_err(|e| format!("query: {}", e))?;
+        let mut out = Vec::new();
+        for row in rows {
+            let (domain, username, enc, nonce, notes, totp_enc, totp_nonce, created_at, updated_at) = row.map_err(|e| format!("row: {}", e))?;
+            // a backup that silently drops a password is worse than no backup
+            let password = self.decrypt_password(&enc, &nonce)?;
+            let totp_secret = match (totp_enc, totp_nonce) {
+                (Some(e), Some(n)) => Some(self.decrypt_password(&e, &n)?),
+                _ => None,
+            };
+            out.push(BackupEntry { domain, username, password, notes, totp_secret, created_at, updated_at });
+        }
+        Ok(out)
     }
 
-    fn get_key(&self) -> Result<[u8; 32], String> {
+    fn get_key(&self) -> Result<[u8; 32], BushidoError> {
         let guard = self.derived_key.lock();
-        guard.ok_or_else(|| "vault locked".to_string())
+        let key = guard.ok_or(BushidoError::Locked)?;
+        self.touch();
+        Ok(key)
     }
 }
 
+/// Note user activity so auto-lock waits. Only callers that can't be driven by page
+/// script: our own commands, main-window input, and WebView2's focus and user-initiated
+/// navigation events.
+pub fn note_activity(app: &tauri::AppHandle) {
+    if let Some(vs) = app.try_state::<VaultState>() {
+        vs.touch();
+    }
+}
+
+/// Background check that locks the vault once it has been idle for `autolock_minutes`,
+/// then emits `vault-locked`.
+pub fn start_autolock(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(AUTOLOCK_CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            let vs = app.state::<VaultState>();
+            let minutes = vs.autolock_minutes.load(Ordering::Relaxed);
+            if minutes == 0 || vs.last_activity.lock().elapsed() < Duration::from_secs(minutes * 60) {
+                continue;
+            }
+            if vs.lock_key() {
+                let _ = app.emit_to("main", "vault-locked", ());
+            }
+        }
+    });
+}
+
 // argon2 derive key from master password + salt
 fn derive_key(password: &str, salt: &[u8]) -> Result<[u8; 32], String> {
     use argon2::Argon2;
@@ -97,6 +252,123 @@ fn derive_key(password: &str, salt: &[u8]) -> Result<[u8; 32], String> {
     Ok(output)
 }
 
+fn seal_backup(entries: &[BackupEntry], export_password: &str) -> Result<Vec<u8>, BushidoError> {
+    use base64::Engine as _;
+    use zeroize::Zeroize;
+    let b64 = base64::engine::general_purpose::STANDARD;
+
+    let mut salt = [0u8; 16];
+    let mut nonce = [0u8; 12];
+    rand::thread_rng().fill(&mut salt);
+    rand::thread_rng().fill(&mut nonce);
+    let mut key = derive_key(export_password, &salt)?;
+    let mut plaintext = serde_json::to_vec(entries).map_err(|e| BushidoError::Other(e.to_string()))?;
+    let sealed = ChaCha20Poly1305::new(GenericArray::from_slice(&key))
+        .encrypt(GenericArray::from_slice(&nonce), plaintext.as_ref())
+        .map_err(|_| "encrypt failed".to_string());
+    plaintext.zeroize();
+    key.zeroize();
+
+    let file = BackupFile {
+        format: BACKUP_FORMAT.into(),
+        version: BACKUP_VERSION,
+        kdf: "argon2id".into(),
+        salt: b64.encode(salt),
+        nonce: b64.encode(nonce),
+        data: b64.encode(sealed?),
+    };
+    serde_json::to_vec_pretty(&file).map_err(|e| BushidoError::Other(e.to_string()))
+}
+
+fn open_backup(bytes: &[u8], export_password: &str) -> Result<Vec<BackupEntry>, BushidoError> {
+    use base64::Engine as _;
+    use zeroize::Zeroize;
+    let b64 = base64::engine::general_purpose::STANDARD;
+
+    let file: BackupFile = serde_json::from_slice(bytes)
+        .map_err(|_| BushidoError::Other("not a Bushido vault backup".into()))?;
+    if file.format != BACKUP_FORMAT || file.kdf != "argon2id" {
+        return Err(BushidoError::Other("not a Bushido vault backup".into()));
+    }
+    if file.version > BACKUP_VERSION {
+        return Err(BushidoError::Other(format!("backup version {} is newer than this browser supports", file.version)));
+    }
+    let corrupt = |_| BushidoError::Other("backup file is corrupt".into());
+    let salt = b64.decode(&file.salt).map_err(corrupt)?;
+    let nonce = b64.decode(&file.nonce).map_err(corrupt)?;
+    let data = b64.decode(&file.data).map_err(corrupt)?;
+    if nonce.len() != 12 {
+        return Err(BushidoError::Other("backup file is corrupt".into()));
+    }
+
+    let mut key = derive_key(export_password, &salt)?;
+    let opened = ChaCha20Poly1305::new(GenericArray::from_slice(&key))
+        .decrypt(GenericArray::from_slice(&nonce), data.as_ref());
+    key.zeroize();
+    // the AEAD tag can't tell a wrong password from tampering; the former is far likelier
+    let mut plaintext = opened.map_err(|_| BushidoError::Permission("wrong export password".into()))?;
+    let entries = serde_json::from_slice(&plaintext).map_err(|_| BushidoError::Other("backup file is corrupt".into()));
+    plaintext.zeroize();
+    entries
+}
+
+/// Ask for a backup path. `save` picks the save vs open dialog; None = cancelled.
+async fn pick_backup_path(app: &tauri::AppHandle, save: bool) -> Option<PathBuf> {
+    use tauri_plugin_dialog::DialogExt;
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    let dialog = app.dialog().file().add_filter("Bushido vault backup", &["bvault"]);
+    if save {
+        dialog.set_file_name("bushido-vault.bvault").save_file(move |p| { let _ = tx.send(p); });
+    } else {
+        dialog.pick_file(move |p| { let _ = tx.send(p); });
+    }
+    rx.await.ok().flatten().and_then(|p| p.into_path().ok())
+}
+
+/// Rough entropy of a password: character-pool bits per char, minus what repeats and
+/// keyboard/alphabet runs give away. Deliberately pessimistic — it only has to rank entries.
+fn estimate_entropy(password: &str) -> f64 {
+    let chars: Vec<char> = password.chars().collect();
+    if chars.is_empty() {
+        return 0.0;
+    }
+    let mut pool = 0u32;
+    if chars.iter().any(|c| c.is_ascii_lowercase()) { pool += 26; }
+    if chars.iter().any(|c| c.is_ascii_uppercase()) { pool += 26; }
+    if chars.iter().any(|c| c.is_ascii_digit()) { pool += 10; }
+    if chars.iter().any(|c| c.is_ascii_punctuation() || *c == ' ') { pool += 33; }
+    if chars.iter().any(|c| !c.is_ascii()) { pool += 100; }
+    let per_char = (pool.max(1) as f64).log2();
+
+    // a char that repeats or continues a run (aaa, abc, 321) adds little
+    let mut effective = 1.0;
+    for w in chars.windows(2) {
+        let step = w[1] as i64 - w[0] as i64;
+        effective += if step.abs() <= 1 { 0.25 } else { 1.0 };
+    }
+    let lower = password.to_lowercase();
+    if COMMON_PASSWORDS.iter().any(|p| lower.contains(p)) {
+        effective /= 2.0;
+    }
+    effective * per_char
+}
+
+// substrings that make a password guessable no matter what surrounds them
+const COMMON_PASSWORDS: &[&str] = &[
+    "password", "passw0rd", "123456", "qwerty", "letmein", "welcome", "admin",
+    "iloveyou", "monkey", "dragon", "abc123", "111111", "login", "master",
+];
+
+fn strength_score(bits: f64) -> u8 {
+    match bits {
+        b if b < 28.0 => 0,
+        b if b < 36.0 => 1,
+        b if b < 60.0 => 2,
+        b if b < 80.0 => 3,
+        _ => 4,
+    }
+}
+
 // sync check if master password is set (no async, no key needed)
 pub fn has_master_password_sync(state: &VaultState) -> bool {
     let conn = match state.open_db() {
@@ -117,26 +389,26 @@ pub fn get_entries_for_domain(state: &VaultState, domain: &str) -> Result<Vec<Va
     }
     let conn = state.open_db()?;
     let mut stmt = conn.prepare(
-        "SELECT id, domain, username, password_enc, nonce, notes, created_at, updated_at FROM vault_entries WHERE domain = ?1 ORDER BY updated_at DESC"
+        "SELECT id, domain, username, password_enc, nonce, notes, created_at, updated_at, totp_enc IS NOT NULL FROM vault_entries WHERE domain = ?1 ORDER BY updated_at DESC"
     ).map_err(|e| format!("query: {}", e))?;
     let rows = stmt.query_map([domain], |row| {
         Ok((
             row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?,
             row.get::<_, Vec<u8>>(3)?, row.get::<_, Vec<u8>>(4)?, row.get::<_, String>(5)?,
-            row.get::<_, i64>(6)?, row.get::<_, i64>(7)?,
+            row.get::<_, i64>(6)?, row.get::<_, i64>(7)?, row.get::<_, bool>(8)?,
         ))
     }).map_err(|e| format!("query: {}", e))?;
     let mut entries = Vec::new();
     for row in rows {
-        let (id, domain, username, enc, nonce, notes, created_at, updated_at) = row.map_err(|e| format!("row: {}", e))?;
+        let (id, domain, username, enc, nonce, notes, created_at, updated_at, has_totp) = row.map_err(|e| format!("row: {}", e))?;
         let password = state.decrypt_password(&enc, &nonce).unwrap_or_default();
-        entries.push(VaultEntry { id, domain, username, password, notes, created_at, updated_at });
+        entries.push(VaultEntry { id, domain, username, password, notes, has_totp, created_at, updated_at });
     }
     Ok(entries)
 }
 
 #[tauri::command]
-pub async fn vault_has_master_password(state: tauri::State<'_, VaultState>) -> Result<bool, String> {
+pub async fn vault_has_master_password(state: tauri::State<'_, VaultState>) -> Result<bool, BushidoError> {
     let conn = state.open_db()?;
     let exists: bool = conn.query_row(
         "SELECT COUNT(*) > 0 FROM vault_meta WHERE key = 'master_hash'",
@@ -147,7 +419,7 @@ pub async fn vault_has_master_password(state: tauri::State<'_, VaultState>) -> R
 }
 
 #[tauri::command]
-pub async fn vault_setup(state: tauri::State<'_, VaultState>, master_password: String) -> Result<(), String> {
+pub async fn vault_setup(state: tauri::State<'_, VaultState>, master_password: String) -> Result<(), BushidoError> {
     if master_password.len() < 8 {
         return Err("password must be at least 8 characters".into());
     }
@@ -173,12 +445,13 @@ pub async fn vault_setup(state: tauri::State<'_, VaultState>, master_password: S
     let key = derive_key(&master_password, salt.as_str().as_bytes())?;
     let mut guard = state.derived_key.lock();
     *guard = Some(key);
+    state.touch();
 
     Ok(())
 }
 
 #[tauri::command]
-pub async fn vault_unlock(state: tauri::State<'_, VaultState>, master_password: String) -> Result<(), String> {
+pub async fn vault_unlock(state: tauri::State<'_, VaultState>, master_password: String) -> Result<(), BushidoError> {
     let conn = state.open_db()?;
 
     let hash_str: String = conn.query_row(
@@ -204,28 +477,42 @@ pub async fn vault_unlock(state: tauri::State<'_, VaultState>, master_password:
         .map_err(|e| format!("parse hash: {}", e))?;
     Argon2::default()
         .verify_password(master_password.as_bytes(), &parsed_hash)
-        .map_err(|_| "wrong password".to_string())?;
+        .map_err(|_| BushidoError::Permission("wrong password".into()))?;
 
     let key = derive_key(&master_password, salt_str.as_bytes())?;
     let mut guard = state.derived_key.lock();
     *guard = Some(key);
+    state.touch();
 
     Ok(())
 }
 
 #[tauri::command]
-pub async fn vault_lock(state: tauri::State<'_, VaultState>) -> Result<(), String> {
-    let mut guard = state.derived_key.lock();
-    if let Some(ref mut key) = *guard {
-        // zero out before dropping
-        key.iter_mut().for_each(|b| *b = 0);
-    }
-    *guard = None;
+pub async fn vault_lock(state: tauri::State<'_, VaultState>) -> Result<(), BushidoError> {
+    state.lock_key();
     Ok(())
 }
 
+/// Idle minutes before the vault relocks itself, clamped to a day; 0 disables.
+/// Returns the value actually applied.
 #[tauri::command]
-pub async fn vault_is_unlocked(state: tauri::State<'_, VaultState>) -> Result<bool, String> {
+pub async fn set_vault_autolock_timeout(state: tauri::State<'_, VaultState>, minutes: u64) -> Result<u64, BushidoError> {
+    let minutes = minutes.min(MAX_AUTOLOCK_MINUTES);
+    state.autolock_minutes.store(minutes, Ordering::Relaxed);
+    // a shorter timeout shouldn't fire on idle time from before it was set
+    state.touch();
+    Ok(minutes)
+}
+
+/// Main-window input (keys, clicks) — web content activity is noted on the Rust side.
+#[tauri::command]
+pub async fn vault_note_activity(state: tauri::State<'_, VaultState>) -> Result<(), BushidoError> {
+    state.touch();
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn vault_is_unlocked(state: tauri::State<'_, VaultState>) -> Result<bool, BushidoError> {
     let guard = state.derived_key.lock();
     Ok(guard.is_some())
 }
@@ -237,16 +524,24 @@ pub async fn vault_save_entry(
     username: String,
     password: String,
     notes: Option<String>,
-) -> Result<String, String> {
+    totp_secret: Option<String>,
+) -> Result<String, BushidoError> {
     let (enc, nonce) = state.encrypt_password(&password)?;
+    let (totp_enc, totp_nonce) = match totp_secret.as_deref().filter(|s| !s.trim().is_empty()) {
+        Some(secret) => {
+            let (e, n) = state.encrypt_password(&totp::normalize_secret(secret)?)?;
+            (Some(e), Some(n))
+        }
+        None => (None, None),
+    };
     let id = Uuid::new_v4().to_string();
     let now = chrono::Utc::now().timestamp();
     let notes = notes.unwrap_or_default();
 
     let conn = state.open_db()?;
     conn.execute(
-        "INSERT INTO vault_entries (id, domain, username, password_enc, nonce, notes, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
-        rusqlite::params![id, domain, username, enc, nonce, notes, now, now],
+        "INSERT INTO vault_entries (id, domain, username, password_enc, nonce, notes, created_at, updated_at, totp_enc, totp_nonce, password_changed_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?8)",
+        rusqlite::params![id, domain, username, enc, nonce, notes, now, now, totp_enc, totp_nonce],
     ).map_err(|e| format!("save: {}", e))?;
 
     Ok(id)
@@ -256,13 +551,13 @@ pub async fn vault_save_entry(
 pub async fn vault_get_entries(
     state: tauri::State<'_, VaultState>,
     domain: Option<String>,
-) -> Result<Vec<VaultEntry>, String> {
+) -> Result<Vec<VaultEntry>, BushidoError> {
     let conn = state.open_db()?;
 
     let mut entries = Vec::new();
     if let Some(ref d) = domain {
         let mut stmt = conn.prepare(
-            "SELECT id, domain, username, password_enc, nonce, notes, created_at, updated_at FROM vault_entries WHERE domain = ?1 ORDER BY updated_at DESC"
+            "SELECT id, domain, username, password_enc, nonce, notes, created_at, updated_at, totp_enc IS NOT NULL FROM vault_entries WHERE domain = ?1 ORDER BY updated_at DESC"
         ).map_err(|e| format!("query: {}", e))?;
 
         let rows = stmt.query_map([d], |row| {
@@ -275,18 +570,19 @@ pub async fn vault_get_entries(
                 row.get::<_, String>(5)?,
                 row.get::<_, i64>(6)?,
                 row.get::<_, i64>(7)?,
+                row.get::<_, bool>(8)?,
             ))
         }).map_err(|e| format!("query: {}", e))?;
 
         for row in rows {
-            let (id, domain, username, enc, nonce, notes, created_at, updated_at) = row.map_err(|e| format!("row: {}", e))?;
+            let (id, domain, username, enc, nonce, notes, created_at, updated_at, has_totp) = row.map_err(|e| format!("row: {}", e))?;
             // only decrypt if vault is unlocked
             let password = state.decrypt_password(&enc, &nonce).unwrap_or_default();
-            entries.push(VaultEntry { id, domain, username, password, notes, created_at, updated_at });
+            entries.push(VaultEntry { id, domain, username, password, notes, has_totp, created_at, updated_at });
         }
     } else {
         let mut stmt = conn.prepare(
-            "SELECT id, domain, username, password_enc, nonce, notes, created_at, updated_at FROM vault_entries ORDER BY domain, updated_at DESC"
+            "SELECT id, domain, username, password_enc, nonce, notes, created_at, updated_at, totp_enc IS NOT NULL FROM vault_entries ORDER BY domain, updated_at DESC"
         ).map_err(|e| format!("query: {}", e))?;
 
         let rows = stmt.query_map([], |row| {
@@ -299,13 +595,14 @@ pub async fn vault_get_entries(
                 row.get::<_, String>(5)?,
                 row.get::<_, i64>(6)?,
                 row.get::<_, i64>(7)?,
+                row.get::<_, bool>(8)?,
             ))
         }).map_err(|e| format!("query: {}", e))?;
 
         for row in rows {
-            let (id, domain, username, enc, nonce, notes, created_at, updated_at) = row.map_err(|e| format!("row: {}", e))?;
+            let (id, domain, username, enc, nonce, notes, created_at, updated_at, has_totp) = row.map_err(|e| format!("row: {}", e))?;
             let password = state.decrypt_password(&enc, &nonce).unwrap_or_default();
-            entries.push(VaultEntry { id, domain, username, password, notes, created_at, updated_at });
+            entries.push(VaultEntry { id, domain, username, password, notes, has_totp, created_at, updated_at });
         }
     }
 
@@ -313,7 +610,7 @@ pub async fn vault_get_entries(
 }
 
 #[tauri::command]
-pub async fn vault_delete_entry(state: tauri::State<'_, VaultState>, id: String) -> Result<(), String> {
+pub async fn vault_delete_entry(state: tauri::State<'_, VaultState>, id: String) -> Result<(), BushidoError> {
     let conn = state.open_db()?;
     conn.execute("DELETE FROM vault_entries WHERE id = ?1", [&id])
         .map_err(|e| format!("delete: {}", e))?;
@@ -327,14 +624,15 @@ pub async fn vault_update_entry(
     username: Option<String>,
     password: Option<String>,
     notes: Option<String>,
-) -> Result<(), String> {
+    totp_secret: Option<String>,
+) -> Result<(), BushidoError> {
     let conn = state.open_db()?;
     let now = chrono::Utc::now().timestamp();
 
     if let Some(ref pw) = password {
         let (enc, nonce) = state.encrypt_password(pw)?;
         conn.execute(
-            "UPDATE vault_entries SET password_enc = ?1, nonce = ?2, updated_at = ?3 WHERE id = ?4",
+            "UPDATE vault_entries SET password_enc = ?1, nonce = ?2, updated_at = ?3, password_changed_at = ?3 WHERE id = ?4",
             rusqlite::params![enc, nonce, now, id],
         ).map_err(|e| format!("update pw: {}", e))?;
     }
@@ -350,10 +648,170 @@ pub async fn vault_update_entry(
             rusqlite::params![n, now, id],
         ).map_err(|e| format!("update notes: {}", e))?;
     }
+    // an empty secret removes 2FA from the entry
+    if let Some(ref t) = totp_secret {
+        let (enc, nonce) = if t.trim().is_empty() {
+            (None, None)
+        } else {
+            let (e, n) = state.encrypt_password(&totp::normalize_secret(t)?)?;
+            (Some(e), Some(n))
+        };
+        conn.execute(
+            "UPDATE vault_entries SET totp_enc = ?1, totp_nonce = ?2, updated_at = ?3 WHERE id = ?4",
+            rusqlite::params![enc, nonce, now, id],
+        ).map_err(|e| format!("update totp: {}", e))?;
+    }
 
     Ok(())
 }
 
+/// Current 2FA code for an entry and how long until it rotates.
+#[tauri::command]
+pub async fn vault_get_totp(state: tauri::State<'_, VaultState>, entry_id: String) -> Result<TotpCode, BushidoError> {
+    let conn = state.open_db()?;
+    let row = conn.query_row(
+        "SELECT totp_enc, totp_nonce FROM vault_entries WHERE id = ?1",
+        [&entry_id],
+        |row| Ok((row.get::<_, Option<Vec<u8>>>(0)?, row.get::<_, Option<Vec<u8>>>(1)?)),
+    ).map_err(|e| match e {
+        rusqlite::Error::QueryReturnedNoRows => BushidoError::NotFound(format!("vault entry {}", entry_id)),
+        e => BushidoError::Other(format!("totp: {}", e)),
+    })?;
+    let (Some(enc), Some(nonce)) = row else {
+        return Err(BushidoError::NotFound("entry has no TOTP secret".into()));
+    };
+    let secret = state.decrypt_password(&enc, &nonce)?;
+    totp::generate_now(&secret)
+}
+
+/// Write an encrypted backup of every entry. Without a path the user picks one; returns
+/// the written path, or None if the dialog was cancelled. Plaintext only ever exists in memory.
+#[tauri::command]
+pub async fn vault_export(app: tauri::AppHandle, export_password: String, path: Option<String>) -> Result<Option<String>, BushidoError> {
+    if export_password.len() < 8 {
+        return Err("export password must be at least 8 characters".into());
+    }
+    let state = app.state::<VaultState>();
+    let entries = state.backup_entries()?;
+    let bytes = seal_backup(&entries, &export_password)?;
+    drop(entries);
+
+    let path = match path {
+        Some(p) => PathBuf::from(p),
+        None => match pick_backup_path(&app, true).await {
+            Some(p) => p,
+            None => return Ok(None),
+        },
+    };
+    std::fs::write(&path, bytes)?;
+    Ok(Some(path.to_string_lossy().to_string()))
+}
+
+/// Merge entries from an encrypted backup, skipping any domain+username already in the vault.
+/// Without a path the user picks one; None if the dialog was cancelled.
+#[tauri::command]
+pub async fn vault_import(app: tauri::AppHandle, path: Option<String>, export_password: String) -> Result<Option<VaultImportResult>, BushidoError> {
+    let state = app.state::<VaultState>();
+    // fail before the dialog rather than after the user found the file
+    state.get_key()?;
+    let path = match path {
+        Some(p) => PathBuf::from(p),
+        None => match pick_backup_path(&app, false).await {
+            Some(p) => p,
+            None => return Ok(None),
+        },
+    };
+    let entries = open_backup(&std::fs::read(&path)?, &export_password)?;
+
+    let mut conn = state.open_db()?;
+    let tx = conn.transaction().map_err(|e| format!("import: {}", e))?;
+    let mut result = VaultImportResult { imported: 0, skipped: 0 };
+    for e in entries {
+        let exists: bool = tx.query_row(
+            "SELECT COUNT(*) > 0 FROM vault_entries WHERE domain = ?1 AND username = ?2",
+            [&e.domain, &e.username],
+            |row| row.get(0),
+        ).map_err(|e| format!("import: {}", e))?;
+        if exists {
+            result.skipped += 1;
+            continue;
+        }
+        let (enc, nonce) = state.encrypt_password(&e.password)?;
+        let (totp_enc, totp_nonce) = match e.totp_secret.as_deref() {
+            Some(secret) => {
+                let (te, tn) = state.encrypt_password(&totp::normalize_secret(secret)?)?;
+                (Some(te), Some(tn))
+            }
+            None => (None, None),
+        };
+        tx.execute(
+            "INSERT INTO vault_entries (id, domain, username, password_enc, nonce, notes, created_at, updated_at, totp_enc, totp_nonce) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            rusqlite::params![Uuid::new_v4().to_string(), e.domain, e.username, enc, nonce, e.notes, e.created_at, e.updated_at, totp_enc, totp_nonce],
+        ).map_err(|e| format!("import: {}", e))?;
+        result.imported += 1;
+    }
+    tx.commit().map_err(|e| format!("import: {}", e))?;
+    Ok(Some(result))
+}
+
+// rows from before the password_changed_at column only have updated_at to go on
+const PASSWORD_CHANGED_AT: &str = "COALESCE(password_changed_at, updated_at)";
+
+// whole days from `since` to `now` (unix seconds); a clock that went backwards reads as 0
+fn age_days(now: i64, since: i64) -> i64 {
+    (now - since).max(0) / 86_400
+}
+
+/// Local-only security checkup: strength, reuse and age of every entry.
+#[tauri::command]
+pub async fn vault_audit(state: tauri::State<'_, VaultState>) -> Result<Vec<VaultAuditEntry>, BushidoError> {
+    use sha2::{Digest, Sha256};
+    use zeroize::Zeroize;
+
+    state.get_key()?;
+    let conn = state.open_db()?;
+    let mut stmt = conn.prepare(
+        &format!("SELECT id, domain, username, password_enc, nonce, {} FROM vault_entries ORDER BY domain, updated_at DESC", PASSWORD_CHANGED_AT)
+    ).map_err(|e| format!("query: {}", e))?;
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?,
+            row.get::<_, Vec<u8>>(3)?, row.get::<_, Vec<u8>>(4)?, row.get::<_, i64>(5)?,
+        ))
+    }).map_err(|e| format!("query: {}", e))?;
+
+    let now = chrono::Utc::now().timestamp();
+    // only digests are kept across entries; each plaintext is wiped as soon as it's scored
+    let mut audited = Vec::new();
+    let mut digests: Vec<[u8; 32]> = Vec::new();
+    for row in rows {
+        let (id, domain, username, enc, nonce, password_changed_at) = row.map_err(|e| format!("row: {}", e))?;
+        let mut password = state.decrypt_password(&enc, &nonce)?;
+        let bits = estimate_entropy(&password);
+        digests.push(Sha256::digest(password.as_bytes()).into());
+        password.zeroize();
+        audited.push(VaultAuditEntry {
+            id,
+            domain,
+            username,
+            strength: strength_score(bits),
+            entropy_bits: bits.round() as u32,
+            reused: false,
+            age_days: age_days(now, password_changed_at),
+        });
+    }
+
+    let mut counts: std::collections::HashMap<[u8; 32], usize> = std::collections::HashMap::new();
+    for d in &digests {
+        *counts.entry(*d).or_default() += 1;
+    }
+    for (entry, d) in audited.iter_mut().zip(&digests) {
+        entry.reused = counts[d] > 1;
+    }
+    digests.iter_mut().for_each(|d| d.zeroize());
+    Ok(audited)
+}
+
 #[tauri::command]
 pub async fn vault_generate_password(
     length: Option<u32>,
@@ -361,7 +819,7 @@ pub async fn vault_generate_password(
     lowercase: Option<bool>,
     digits: Option<bool>,
     symbols: Option<bool>,
-) -> Result<String, String> {
+) -> Result<String, BushidoError> {
     let len = length.unwrap_or(20).max(4).min(128) as usize;
     let uc = uppercase.unwrap_or(true);
     let lc = lowercase.unwrap_or(true);
@@ -381,3 +839,115 @@ pub async fn vault_generate_password(
 
     Ok(password)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::Engine as _;
+
+    fn entry(domain: &str) -> BackupEntry {
+        BackupEntry {
+            domain: domain.into(),
+            username: "alice".into(),
+            password: "hunter2".into(),
+            notes: "work".into(),
+            totp_secret: Some("JBSWY3DPEHPK3PXP".into()),
+            created_at: 1,
+            updated_at: 2,
+        }
+    }
+
+    // decode the sealed file, let `f` edit it, encode it again
+    fn edit(sealed: &[u8], f: impl FnOnce(&mut BackupFile)) -> Vec<u8> {
+        let mut file: BackupFile = serde_json::from_slice(sealed).unwrap();
+        f(&mut file);
+        serde_json::to_vec(&file).unwrap()
+    }
+
+    #[test]
+    fn age_counts_whole_days_and_never_goes_negative() {
+        let day = 86_400;
+        assert_eq!(age_days(10 * day, 10 * day), 0);
+        assert_eq!(age_days(10 * day, 9 * day + 1), 0);
+        assert_eq!(age_days(10 * day, 9 * day), 1);
+        assert_eq!(age_days(400 * day + 5, 5), 400);
+        assert_eq!(age_days(0, day), 0);
+    }
+
+    #[test]
+    fn password_age_ignores_other_edits() {
+        let dir = std::env::temp_dir().join(format!("bushido_vault_age_{}", std::process::id()));
+        let _ = std::fs::create_dir_all(&dir);
+        let vs = VaultState::new(dir.join("vault.db"));
+        vs.init_db().unwrap();
+        // running the migration twice is harmless
+        vs.init_db().unwrap();
+
+        let conn = vs.open_db().unwrap();
+        let insert = |id: &str, updated_at: i64, changed_at: Option<i64>| {
+            conn.execute(
+                "INSERT INTO vault_entries (id, domain, username, password_enc, nonce, created_at, updated_at, password_changed_at) VALUES (?1, 'a.example', 'u', x'00', x'00', 0, ?2, ?3)",
+                rusqlite::params![id, updated_at, changed_at],
+            ).unwrap();
+        };
+        // a notes edit yesterday on a password set 100 days ago, and a row from before the column
+        insert("edited", 99 * 86_400, Some(0));
+        insert("legacy", 50 * 86_400, None);
+        let age = |id: &str| -> i64 {
+            let since: i64 = conn.query_row(
+                &format!("SELECT {} FROM vault_entries WHERE id = ?1", PASSWORD_CHANGED_AT),
+                [id],
+                |r| r.get(0),
+            ).unwrap();
+            age_days(100 * 86_400, since)
+        };
+        assert_eq!(age("edited"), 100);
+        assert_eq!(age("legacy"), 50);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn backup_round_trips() {
+        let sealed = seal_backup(&[entry("a.example"), entry("b.example")], "export pw").unwrap();
+        let opened = open_backup(&sealed, "export pw").unwrap();
+        assert_eq!(opened.len(), 2);
+        assert_eq!(opened[1].domain, "b.example");
+        assert_eq!(opened[0].password, "hunter2");
+        assert_eq!(opened[0].totp_secret.as_deref(), Some("JBSWY3DPEHPK3PXP"));
+        assert_eq!((opened[0].created_at, opened[0].updated_at), (1, 2));
+        // nothing readable leaks into the file
+        assert!(!String::from_utf8_lossy(&sealed).contains("hunter2"));
+    }
+
+    #[test]
+    fn wrong_password_is_rejected() {
+        let sealed = seal_backup(&[entry("a.example")], "export pw").unwrap();
+        assert!(matches!(open_backup(&sealed, "export PW"), Err(BushidoError::Permission(_))));
+    }
+
+    #[test]
+    fn tampered_or_truncated_backups_are_rejected() {
+        let b64 = base64::engine::general_purpose::STANDARD;
+        let sealed = seal_backup(&[entry("a.example")], "export pw").unwrap();
+
+        let flipped = edit(&sealed, |f| {
+            let mut data = b64.decode(&f.data).unwrap();
+            data[0] ^= 1;
+            f.data = b64.encode(data);
+        });
+        assert!(open_backup(&flipped, "export pw").is_err());
+
+        let short = edit(&sealed, |f| {
+            let data = b64.decode(&f.data).unwrap();
+            f.data = b64.encode(&data[..data.len() - 4]);
+        });
+        assert!(open_backup(&short, "export pw").is_err());
+
+        let bad_nonce = edit(&sealed, |f| f.nonce = b64.encode([0u8; 8]));
+        assert!(matches!(open_backup(&bad_nonce, "export pw"), Err(BushidoError::Other(_))));
+
+        assert!(open_backup(&sealed[..sealed.len() / 2], "export pw").is_err());
+        let newer = edit(&sealed, |f| f.version = BACKUP_VERSION + 1);
+        assert!(open_backup(&newer, "export pw").is_err());
+    }
+}
@@ -18,7 +18,7 @@ import AnnotationEditor from "./components/AnnotationEditor";
 import ShareMenu from "./components/ShareMenu";
 import Onboarding from "./components/Onboarding";
 import GlanceOverlay from "./components/GlanceOverlay";
-import { Tab, Workspace, SessionData, HistoryEntry, BookmarkData, FrecencyResult, BushidoSettings, DEFAULT_SETTINGS, DownloadItem, PaneRect, DividerInfo, WebPanel, DropZone, PermissionRequest } from "./types";
+import { Tab, Workspace, SessionData, HistoryEntry, BookmarkData, FrecencyResult, BushidoSettings, DEFAULT_SETTINGS, DownloadItem, PaneRect, LayoutSnapshot, SessionSummary, DividerInfo, WebPanel, DropZone, PermissionRequest, BasicAuthRequest, CertErrorRequest, StartupPlan } from "./types";
 import { allLeafIds, insertPane, removePane, computeRects, computeDividers, updateRatio, hasLeaf, detectDropZone } from "./splitLayout";
 import { useTabStore } from "./store/tabStore";
 import { useUiStore } from "./store/uiStore";
@@ -150,6 +150,8 @@ export default function App() {
   const setReaderTabs = useFeatureStore(s => s.setReaderTabs);
   const readerSettings = useFeatureStore(s => s.readerSettings);
   const setReaderSettings = useFeatureStore(s => s.setReaderSettings);
+  const readerProgress = useFeatureStore(s => s.readerProgress);
+  const setReaderProgress = useFeatureStore(s => s.setReaderProgress);
   const hasVideo = useFeatureStore(s => s.hasVideo);
   const setHasVideo = useFeatureStore(s => s.setHasVideo);
   const pipActive = useFeatureStore(s => s.pipActive);
@@ -170,18 +172,30 @@ export default function App() {
   const permReq = useVaultStore(s => s.permReq);
   const setPermReq = useVaultStore(s => s.setPermReq);
   const permRemember = useVaultStore(s => s.permRemember);
+  const authReq = useVaultStore(s => s.authReq);
+  const setAuthReq = useVaultStore(s => s.setAuthReq);
+  const certReq = useVaultStore(s => s.certReq);
+  const setCertReq = useVaultStore(s => s.setCertReq);
+  const [certRemember, setCertRemember] = useState(false);
   const setPermRemember = useVaultStore(s => s.setPermRemember);
 
   const syncToast = useSyncStore(s => s.syncToast);
   const setSyncToast = useSyncStore(s => s.setSyncToast);
   const syncTabReceived = useSyncStore(s => s.syncTabReceived);
   const setSyncTabReceived = useSyncStore(s => s.setSyncTabReceived);
+  const syncTabsReceived = useSyncStore(s => s.syncTabsReceived);
+  const setSyncTabsReceived = useSyncStore(s => s.setSyncTabsReceived);
+  const syncSinceLastSession = useSyncStore(s => s.syncSinceLastSession);
+  const setSyncSinceLastSession = useSyncStore(s => s.setSyncSinceLastSession);
   const syncPairedDevices = useSyncStore(s => s.syncPairedDevices);
   const setSyncPairedDevices = useSyncStore(s => s.setSyncPairedDevices);
 
   const [networkEntries, setNetworkEntries] = useState<NetworkEntry[]>([]);
   const networkEntriesRef = useRef<NetworkEntry[]>([]);
   const [cookieToast, setCookieToast] = useState(false);
+  const [dropHint, setDropHint] = useState(false);
+  const [offline, setOffline] = useState(false);
+  const [blockingPaused, setBlockingPaused] = useState(false);
   const [updateToast, setUpdateToast] = useState<string | null>(null);
   const [pendingUpdate, setPendingUpdate] = useState<{ version: string; downloading: boolean } | null>(null);
 
@@ -195,9 +209,9 @@ export default function App() {
   const settingsRef = useRef(settings);
   const historyLoaded = useRef(false);
   const bookmarksLoaded = useRef(false);
+  const bookmarkToken = useRef<string | null>(null); // cursor for get_bookmark_changes
   const bookmarkBulkRef = useRef(false);
   const prevSettingsRef = useRef<BushidoSettings | null>(null);
-  const closedTabsRef = useRef<{url: string; title: string; workspaceId: string}[]>([]);
   const zoomRef = useRef<Record<string, number>>({});
   const [zoomDisplay, setZoomDisplay] = useState<Record<string, number>>({});
   const pageCtxRef = useRef<HTMLDivElement>(null);
@@ -216,8 +230,8 @@ export default function App() {
     disableDevTools: sr.disableDevTools, disableStatusBar: sr.disableStatusBar,
     disableAutofill: sr.disableAutofill, disablePasswordSave: sr.disablePasswordSave,
     blockServiceWorkers: sr.blockServiceWorkers, blockFontEnum: sr.blockFontEnumeration,
-    spoofHwConcurrency: sr.spoofHardwareConcurrency,
     blockPopups: sr.blockPopups, defaultZoom: sr.defaultZoom,
+    privacySignals: sr.privacySignals,
   }), []);
 
   const applyTheme = useCallback((accent: string, mode: "dark" | "light") => {
@@ -242,10 +256,13 @@ export default function App() {
   const pinnedTabs = useMemo(() => currentWsTabs.filter(t => t.pinned), [currentWsTabs]);
   const regularTabs = useMemo(() => currentWsTabs.filter(t => !t.pinned), [currentWsTabs]);
 
-  // media mini player — find any tab with media state (prefer playing over paused)
+  // media mini player — the backend's media session tab (the one OS media keys control),
+  // else any tab with media state (prefer playing over paused)
+  const [mediaSessionTab, setMediaSessionTab] = useState<string | null>(null);
   const playingTab = useMemo(() =>
-    tabs.find(t => t.mediaState === "playing") || tabs.find(t => t.mediaState === "paused"),
-  [tabs]);
+    (mediaSessionTab ? tabs.find(t => t.id === mediaSessionTab && t.mediaState) : undefined)
+      || tabs.find(t => t.mediaState === "playing") || tabs.find(t => t.mediaState === "paused"),
+  [tabs, mediaSessionTab]);
 
   // clear loading after a delay since webview2 child events are unreliable
   const clearLoading = useCallback((tabId: string, ms = 2000) => {
@@ -271,7 +288,7 @@ export default function App() {
       else panes = [{ tabId: w.activeTabId, ...rect }];
     }
 
-    invoke("layout_webviews", { panes, focusedTabId: w.activeTabId, sidebarW: layoutOffset, topOffset });
+    invoke("layout_webviews", { panes, focusedTabId: w.activeTabId, sidebarW: layoutOffset, topOffset, paneLayout: w.paneLayout ?? null });
   }, [activeWs, tabs, layoutOffset, topOffset]);
 
   // restore session or create first tab
@@ -282,8 +299,8 @@ export default function App() {
     // load settings first so create_tab can use them, then load session
     Promise.all([
       invoke<string>("load_settings"),
-      invoke<string>("load_session"),
-    ]).then(([settingsJson, json]) => {
+      invoke<StartupPlan>("get_startup_tabs"),
+    ]).then(([settingsJson, plan]) => {
       let s = { ...DEFAULT_SETTINGS };
       try {
         const p = JSON.parse(settingsJson);
@@ -299,6 +316,34 @@ export default function App() {
       if (s.bandwidthLimit) {
         invoke("set_bandwidth_limit", { limit: s.bandwidthLimit });
       }
+      if (s.maxConcurrentDownloads !== DEFAULT_SETTINGS.maxConcurrentDownloads) {
+        invoke("set_max_concurrent_downloads", { n: s.maxConcurrentDownloads });
+      }
+      if (s.maxTabs !== DEFAULT_SETTINGS.maxTabs) {
+        invoke("set_tab_limit", { limit: s.maxTabs });
+      }
+      if (s.pageColorScheme !== DEFAULT_SETTINGS.pageColorScheme) {
+        invoke("set_default_theme", { theme: s.pageColorScheme });
+      }
+      if (s.historyRetentionDays) {
+        invoke("set_history_retention", { days: s.historyRetentionDays });
+      }
+      if (s.autoHibernate) {
+        invoke("set_auto_hibernate", { enabled: true, thresholdMb: s.autoHibernateThresholdMb });
+      }
+      if (s.dropFilesToPage) {
+        invoke("set_drop_interception", { block: false });
+      }
+      if (s.crashRecoveryRetries) {
+        invoke("set_crash_recovery", { enabled: true, maxRetries: s.crashRecoveryRetries });
+      }
+      if (s.syncCompressThreshold !== DEFAULT_SETTINGS.syncCompressThreshold) {
+        invoke("set_sync_compression_threshold", { bytes: s.syncCompressThreshold });
+      }
+      const autolock = s.vaultAutoLock ? s.vaultLockTimeout : 0;
+      if (autolock !== DEFAULT_SETTINGS.vaultLockTimeout) {
+        invoke("set_vault_autolock_timeout", { minutes: autolock });
+      }
 
       // if onboarding hasn't been completed, show it and skip session restore
       if (!s.onboardingComplete) {
@@ -314,7 +359,7 @@ export default function App() {
         return;
       }
 
-      const sa = { disableDevTools: s.disableDevTools, disableStatusBar: s.disableStatusBar, disableAutofill: s.disableAutofill, disablePasswordSave: s.disablePasswordSave, blockServiceWorkers: s.blockServiceWorkers, blockFontEnum: s.blockFontEnumeration, spoofHwConcurrency: s.spoofHardwareConcurrency };
+      const sa = { disableDevTools: s.disableDevTools, disableStatusBar: s.disableStatusBar, disableAutofill: s.disableAutofill, disablePasswordSave: s.disablePasswordSave, blockServiceWorkers: s.blockServiceWorkers, blockFontEnum: s.blockFontEnumeration };
       const tabArgs = { httpsOnly: s.httpsOnly, adBlocker: s.adBlocker, cookieAutoReject: s.cookieAutoReject, isPanel: false, ...sa };
 
       // helper: open a fresh NTP (no session restore)
@@ -329,11 +374,22 @@ export default function App() {
         setCompactMode(s.compactMode);
       };
 
-      // if onStartup is "newtab", skip session restore
-      if (s.onStartup === "newtab") {
-        openFreshNtp();
+      // the backend decides from onStartup, and opens the homepage when the session is corrupt
+      if (plan.mode !== "restore_session") {
+        if (!plan.urls.length) { openFreshNtp(); return; }
+        const wsId = genWsId();
+        const opened: Tab[] = plan.urls.map(url => ({ id: genId(), url, title: "New Tab", loading: !url.startsWith("bushido://"), workspaceId: wsId, lastActiveAt: Date.now() }));
+        setWorkspaces([{ id: wsId, name: "Home", color: DEFAULT_WS_COLOR, activeTabId: opened[0].id }]);
+        setTabs(opened);
+        setActiveWorkspaceId(wsId);
+        setCompactMode(s.compactMode);
+        opened.forEach(t => {
+          invoke("create_tab", { id: t.id, url: t.url, sidebarW: s.compactMode ? 3 : 300, topOffset: 40, profileName: t.workspaceId, ...tabArgs });
+          clearLoading(t.id);
+        });
         return;
       }
+      const json = plan.session ?? "[]";
 
       let parsed: any = null;
       try { parsed = JSON.parse(json); } catch {}
@@ -390,8 +446,10 @@ export default function App() {
         const restoredTopOffset = 40;
         const firstActiveWs = restoredWs.find(w => w.id === session.activeWorkspaceId);
         restoredTabs.forEach(t => {
+          // tabs restored under a new id aren't in the backend's pinned set yet
+          if (t.pinned) invoke("set_tab_pinned", { id: t.id, pinned: true });
           if (!t.url.startsWith("bushido://") && !t.suspended) {
-            invoke("create_tab", { id: t.id, url: t.url, sidebarW: restoredSidebarW, topOffset: restoredTopOffset, profileName: t.workspaceId, ...tabArgs });
+            invoke("create_tab", { id: t.id, url: t.url, sidebarW: restoredSidebarW, topOffset: restoredTopOffset, profileName: t.workspaceId, throttling: t.throttling ?? null, ...tabArgs });
             clearLoading(t.id);
           }
         });
@@ -407,7 +465,7 @@ export default function App() {
             const panes = firstActiveWs.paneLayout
               ? computeRects(firstActiveWs.paneLayout, rect)
               : [{ tabId: firstActiveWs.activeTabId, x: 0, y: 0, w: cw, h: ch }];
-            invoke("layout_webviews", { panes, focusedTabId: firstActiveWs.activeTabId, sidebarW: restoredSidebarW, topOffset: restoredTopOffset });
+            invoke("layout_webviews", { panes, focusedTabId: firstActiveWs.activeTabId, sidebarW: restoredSidebarW, topOffset: restoredTopOffset, paneLayout: firstActiveWs.paneLayout ?? null });
           }
         }
 
@@ -442,6 +500,7 @@ export default function App() {
           setActiveWorkspaceId(wsId);
 
           restored.forEach(t => {
+            if (t.pinned) invoke("set_tab_pinned", { id: t.id, pinned: true });
             invoke("create_tab", { id: t.id, url: t.url, sidebarW: 300, topOffset: 40, profileName: t.workspaceId, ...tabArgs });
             clearLoading(t.id);
           });
@@ -459,7 +518,7 @@ export default function App() {
     const t = setTimeout(() => {
       const session: SessionData = {
         workspaces: workspaces.map(w => ({ id: w.id, name: w.name, color: w.color, icon: w.icon, activeTabId: w.activeTabId, paneLayout: w.paneLayout })),
-        tabs: tabs.map(tab => ({ id: tab.id, url: tab.url, title: tab.title, pinned: tab.pinned, workspaceId: tab.workspaceId, parentId: tab.parentId, suspended: tab.suspended })),
+        tabs: tabs.filter(tab => !tab.private).map(tab => ({ id: tab.id, url: tab.url, title: tab.title, pinned: tab.pinned, workspaceId: tab.workspaceId, parentId: tab.parentId, suspended: tab.suspended, throttling: tab.throttling })),
         activeWorkspaceId,
         compactMode,
         panels: panels.map(p => ({ id: p.id, url: p.url, title: p.title, favicon: p.favicon })),
@@ -507,7 +566,8 @@ export default function App() {
   useEffect(() => {
     invoke<string>("load_bookmarks").then(json => {
       try {
-        const p = JSON.parse(json);
+        const { token, ...p } = JSON.parse(json);
+        bookmarkToken.current = token ?? null;
         if (p?.bookmarks) {
           // backfill order field for existing bookmarks that don't have it
           p.bookmarks = p.bookmarks.map((b: any, i: number) => ({ ...b, order: b.order ?? i }));
@@ -550,6 +610,20 @@ export default function App() {
     return () => clearTimeout(t);
   }, [settings]);
 
+  // default zoom changed in settings → apply to every open tab (new tabs get it via create_tab)
+  const appliedZoomRef = useRef<number | null>(null);
+  useEffect(() => {
+    if (!settingsLoaded.current) return;
+    if (appliedZoomRef.current === null || appliedZoomRef.current === settings.defaultZoom) {
+      appliedZoomRef.current = settings.defaultZoom;
+      return;
+    }
+    appliedZoomRef.current = settings.defaultZoom;
+    invoke("set_zoom_for_all_tabs", { factor: settings.defaultZoom / 100 }).catch(e => console.warn("[bushido]", e));
+    zoomRef.current = {};
+    setZoomDisplay({});
+  }, [settings.defaultZoom]);
+
   // sync settings to CRDT — diff only changed keys (React optimization)
   useEffect(() => {
     if (!settingsLoaded.current || !settings.syncEnabled) return;
@@ -582,7 +656,7 @@ export default function App() {
   useEffect(() => {
     if (!settings.syncEnabled) return;
     const sync = () => {
-      const tabsForSync = tabs.filter(t => t.memoryState !== "destroyed")
+      const tabsForSync = tabs.filter(t => t.memoryState !== "destroyed" && !t.private)
         .map(t => ({ id: t.id, url: t.url, title: t.title, favicon: t.favicon }));
       invoke("sync_write_tabs", { tabs: JSON.stringify(tabsForSync) }).catch(e => console.warn("[bushido]", e));
     };
@@ -592,7 +666,7 @@ export default function App() {
   }, [settings.syncEnabled, tabs]);
 
   // record history from navigation events
-  const recordHistory = useCallback((url: string, title: string, favicon?: string) => {
+  const recordHistory = useCallback((url: string, title: string, favicon?: string, tabId?: string) => {
     if (!historyLoaded.current || url.startsWith("bushido://") || !url.startsWith("http")) return;
     const now = Date.now();
     setHistoryEntries(prev => {
@@ -608,14 +682,14 @@ export default function App() {
     });
     // sync history to CRDT (fire-and-forget)
     if (settingsRef.current.syncEnabled) {
-      invoke("sync_add_history", { url, title: title || "", favicon: favicon || null, timestamp: now }).catch(e => console.warn("[bushido]", e));
+      invoke("sync_add_history", { url, title: title || "", favicon: favicon || null, timestamp: now, tabId: tabId || null }).catch(e => console.warn("[bushido]", e));
     }
   }, []);
 
   // listen for webview events from rust
   useEffect(() => {
     const promises = [
-      listen<{ id: string; url: string }>("tab-url-changed", (e) => {
+      listen<{ id: string; url: string; private?: boolean }>("tab-url-changed", (e) => {
         let favicon: string | undefined;
         let domain = "";
         try {
@@ -630,7 +704,7 @@ export default function App() {
           glanceRef.current = { ...glanceRef.current, url: e.payload.url };
           setGlance(prev => prev && prev.id === e.payload.id ? { ...prev, url: e.payload.url } : prev);
         }
-        recordHistory(e.payload.url, "", favicon);
+        if (!e.payload.private) recordHistory(e.payload.url, "", favicon, e.payload.id);
         if (domain) {
           invoke<boolean>("is_whitelisted", { domain }).then(wl => {
             setTabs(prev => prev.map(t => t.id === e.payload.id ? { ...t, whitelisted: wl } : t));
@@ -641,7 +715,7 @@ export default function App() {
         const clean = e.payload.title.replace(/<[^>]*>/g, "");
         setTabs(prev => {
           const tab = prev.find(t => t.id === e.payload.id);
-          if (tab) {
+          if (tab && !tab.private) {
             setHistoryEntries(hp => hp.map(h => h.url === tab.url ? { ...h, title: clean } : h));
           }
           return prev.map(t => t.id === e.payload.id ? { ...t, title: clean, loading: false } : t);
@@ -679,16 +753,31 @@ export default function App() {
             : t
         ));
       }),
+      listen<{ id: string; state: string; title: string } | null>("media-session-changed", (e) => {
+        setMediaSessionTab(e.payload?.id ?? null);
+      }),
+      listen<{ id: string; theme: string; applied: boolean }>("tab-theme-changed", (e) => {
+        if (!e.payload.applied && e.payload.theme !== "auto") showError("Could not force a color scheme on this tab");
+      }),
       listen<{ id: string }>("tab-crashed", (e) => {
         setTabs(prev => prev.map(t =>
           t.id === e.payload.id ? { ...t, crashed: true, loading: false } : t
         ));
       }),
+      // the user closed a tab's own window; the backend already closed the tab
+      listen<{ id: string }>("tab-window-closed", (e) => {
+        setTabs(prev => prev.filter(t => t.id !== e.payload.id));
+      }),
+      listen<{ id: string; url: string }>("tab-recovered", (e) => {
+        setTabs(prev => prev.map(t =>
+          t.id === e.payload.id ? { ...t, crashed: false, loading: true } : t
+        ));
+      }),
       // download events
       listen<{ url: string; suggestedFilename: string; cookies?: string }>("download-intercepted", (e) => {
         const dir = settingsRef.current.downloadLocation || "";
         const routing = settingsRef.current.mimeRouting || [];
-        invoke("start_download", { url: e.payload.url, filename: e.payload.suggestedFilename, downloadDir: dir, cookies: e.payload.cookies || null, mimeRouting: routing });
+        invoke("start_download", { url: e.payload.url, filename: e.payload.suggestedFilename, downloadDir: dir, cookies: e.payload.cookies || null, mimeRouting: routing, askWhereToSave: !!settingsRef.current.askDownloadLocation });
         setDownloadsOpen(true);
       }),
       listen<DownloadItem>("download-started", (e) => {
@@ -720,6 +809,65 @@ export default function App() {
       listen<{ id: string }>("download-cancelled", (e) => {
         setDownloads(prev => prev.filter(d => d.id !== e.payload.id));
       }),
+      listen<DownloadItem[]>("downloads-updated", (e) => {
+        setDownloads(e.payload);
+      }),
+      listen<{ id: string; pinned: boolean }>("tab-pinned-changed", (e) => {
+        setTabs(prev => prev.map(t => t.id === e.payload.id && t.pinned !== e.payload.pinned ? { ...t, pinned: e.payload.pinned } : t));
+      }),
+      listen<{ id: string; can_go_back: boolean; can_go_forward: boolean }>("nav-state-changed", (e) => {
+        const { id, can_go_back, can_go_forward } = e.payload;
+        setTabs(prev => prev.map(t => t.id === id && (t.canGoBack !== can_go_back || t.canGoForward !== can_go_forward)
+          ? { ...t, canGoBack: can_go_back, canGoForward: can_go_forward } : t));
+      }),
+      // memory-pressure hibernation: shown like a timed suspend, resumed on activation
+      listen<{ id: string }>("tab-hibernated", (e) => {
+        setTabs(prev => prev.map(t => t.id === e.payload.id && (t.memoryState ?? "active") === "active" ? { ...t, memoryState: "suspended" as const } : t));
+      }),
+      listen<{ id: string; policy: "disabled" | "suspend" | "throttle" }>("tab-throttling-changed", (e) => {
+        const throttling = e.payload.policy === "throttle" ? undefined : e.payload.policy;
+        setTabs(prev => prev.map(t => t.id === e.payload.id
+          ? { ...t, throttling, memoryState: throttling === "disabled" ? "active" as const : t.memoryState }
+          : t));
+      }),
+      listen<{ id: string; muted: boolean }>("tab-muted-changed", (e) => {
+        setTabs(prev => prev.map(t => t.id === e.payload.id ? { ...t, muted: e.payload.muted || undefined } : t));
+      }),
+      listen<{ id: string; enabled: boolean }>("tab-images-changed", (e) => {
+        setTabs(prev => prev.map(t => t.id === e.payload.id ? { ...t, imagesOff: !e.payload.enabled || undefined } : t));
+      }),
+      listen<{ id: string; bypass: boolean }>("tab-injection-bypass-changed", (e) => {
+        setTabs(prev => prev.map(t => t.id === e.payload.id ? { ...t, injectionBypass: e.payload.bypass || undefined } : t));
+      }),
+      listen<{ offline: boolean }>("offline-changed", (e) => {
+        setOffline(e.payload.offline);
+      }),
+      listen<{ enabled: boolean }>("blocking-toggled", (e) => {
+        setBlockingPaused(!e.payload.enabled);
+      }),
+      listen<{ id: string; percent: number; minutes?: number }>("reader-progress", (e) => {
+        const { id, percent, minutes } = e.payload;
+        setReaderProgress(prev => ({ ...prev, [id]: { percent, minutes: minutes ?? prev[id]?.minutes } }));
+      }),
+      listen<{ id: string; state: string; error?: string | null }>("reader-tts", (e) => {
+        const { state, error } = e.payload;
+        if (state === "unavailable") showError("Speech synthesis isn't available");
+        else if (state === "no-reader") showError("Open reader view first");
+        else if (state === "error") showError(`Reading aloud failed${error ? ` (${error})` : ""}`);
+      }),
+      listen<{ message: string }>("restart-required", (e) => showError(e.payload.message)),
+      listen<{ id: string }>("private-tab-unsupported", () => {
+        showError("This WebView2 runtime can't keep private tabs out of storage. Update it to stop sites saving data.");
+      }),
+      listen<{ id: string; state: string; count: number }>("link-hints", (e) => {
+        if (e.payload.state === "empty") showError("No clickable elements in view");
+      }),
+      // picked with the element picker: keep it visible on this site from now on
+      listen<{ id: string; selector: string | null; domain: string | null }>("element-picked", (e) => {
+        const { selector, domain } = e.payload;
+        if (!selector || !domain) return;
+        invoke("add_cosmetic_exception", { domain, selector }).catch(err => showError(err?.message || String(err)));
+      }),
       // speculative preload promotion
       listen<{ preloadId: string; url: string; sourceTabId: string }>("preload-promoted", (e) => {
         const { preloadId, url, sourceTabId } = e.payload;
@@ -748,11 +896,33 @@ export default function App() {
         setNetworkEntries(networkEntriesRef.current);
       }),
       // sync: reload bookmarks when remote changes arrive
-      listen("sync-bookmarks-changed", () => {
-        console.log("[sync] sync-bookmarks-changed event received, reloading...");
+      listen("sync-bookmarks-changed", async () => {
+        // incremental delta when we have a cursor, full reload otherwise
+        if (bookmarkToken.current) {
+          try {
+            const d = JSON.parse(await invoke<string>("get_bookmark_changes", { sinceToken: bookmarkToken.current }));
+            bookmarkToken.current = d.token;
+            const removed = new Set<string>(d.removed);
+            const merge = <T extends { id: string }>(list: T[], changed: T[]) => {
+              const byId = new Map(changed.map(c => [c.id, c]));
+              const kept = list.filter(x => !removed.has(x.id)).map(x => byId.has(x.id) ? { ...x, ...byId.get(x.id)! } : x);
+              const known = new Set(list.map(x => x.id));
+              return [...kept, ...changed.filter(c => !known.has(c.id))];
+            };
+            setBookmarkData(prev => ({
+              ...prev,
+              bookmarks: merge(prev.bookmarks, d.bookmarks.map((b: any) => ({ ...b, order: b.order ?? prev.bookmarks.length }))),
+              folders: merge(prev.folders, d.folders),
+            }));
+            return;
+          } catch (e) { console.warn("[sync] bookmark delta failed, reloading:", e); }
+        }
         invoke<string>("load_bookmarks").then(json => {
-          console.log("[sync] load_bookmarks returned:", json?.substring(0, 300));
-          try { const p = JSON.parse(json); if (p?.bookmarks) { console.log("[sync] setting bookmarkData:", p.bookmarks.length, "bookmarks,", p.folders.length, "folders"); setBookmarkData(p); } } catch (e) { console.error("[sync] parse error:", e); }
+          try {
+            const { token, ...p } = JSON.parse(json);
+            bookmarkToken.current = token ?? null;
+            if (p?.bookmarks) setBookmarkData(p);
+          } catch (e) { console.error("[sync] parse error:", e); }
         }).catch(e => console.error("[sync] load_bookmarks failed:", e));
       }),
       // sync: activity indicator
@@ -764,6 +934,12 @@ export default function App() {
           syncToastTimer.current = setTimeout(() => setSyncToast(null), 2500);
         }
       }),
+      // backend pruned history.json (retention, range clears); take its copy
+      listen("history-changed", () => {
+        invoke<string>("load_history").then(json => {
+          try { const p = JSON.parse(json); if (Array.isArray(p)) setHistoryEntries(p); } catch {}
+        });
+      }),
       // sync: merge remote history
       listen("sync-history-changed", () => {
         // history is additive from CRDT — no merge needed, local state is source of truth
@@ -775,10 +951,13 @@ export default function App() {
         invoke<string>("load_settings").then(json => {
           try {
             const remote = JSON.parse(json);
+            if (remote.fingerprint) {
+              invoke("set_fingerprint_config", { config: { ...DEFAULT_SETTINGS.fingerprint, ...remote.fingerprint } }).catch(e => console.warn("[bushido]", e));
+            }
             setSettings(prev => {
               const merged = { ...prev };
               // only apply universal (non-device-local) keys
-              const deviceLocal = new Set(["compactMode", "suspendTimeout", "downloadLocation", "askDownloadLocation", "onStartup", "syncDeviceName", "syncEnabled", "onboardingComplete"]);
+              const deviceLocal = new Set(["compactMode", "suspendTimeout", "downloadLocation", "askDownloadLocation", "onStartup", "syncDeviceName", "syncEnabled", "onboardingComplete", "syncBandwidthLimit", "syncCompressThreshold"]);
               for (const key of Object.keys(remote)) {
                 if (!deviceLocal.has(key) && remote[key] !== undefined) {
                   (merged as any)[key] = remote[key];
@@ -789,11 +968,47 @@ export default function App() {
           } catch {}
         }).catch(e => console.warn("[bushido]", e));
       }),
+      // reset_defaults: pick up the rewritten settings file / cleared whitelist
+      listen<{ subsystem: string }>("defaults-reset", (e) => {
+        const sub = e.payload.subsystem;
+        if (sub === "settings" || sub === "keybindings") {
+          invoke<string>("load_settings").then(json => {
+            let s = { ...DEFAULT_SETTINGS };
+            try {
+              const p = JSON.parse(json);
+              if (p && typeof p === "object") s = { ...s, ...p };
+            } catch {}
+            setSettings(s);
+            invoke("set_fingerprint_config", { config: s.fingerprint }).catch(e => console.warn("[bushido]", e));
+          }).catch(e => console.warn("[bushido]", e));
+        } else if (sub === "whitelist") {
+          setTabs(prev => prev.map(t => t.whitelisted ? { ...t, whitelisted: false } : t));
+        }
+      }),
       // sync: tab received from another device
       listen<{ from_device: string; url: string; title: string }>("tab-received", (e) => {
         setSyncTabReceived(e.payload);
         setTimeout(() => setSyncTabReceived(null), 8000);
       }),
+      listen<{ from_device: string; tabs: { url: string; title: string }[] }>("tabs-received", (e) => {
+        setSyncTabsReceived(e.payload);
+        setTimeout(() => setSyncTabsReceived(null), 8000);
+      }),
+      // sync: what other devices changed since last shutdown (once per launch)
+      listen<SessionSummary>("sync-since-last-session", (e) => {
+        const { added, removed, changed } = e.payload;
+        const parts = [
+          added.total && `${added.total} bookmark${added.total === 1 ? "" : "s"} added`,
+          removed.total && `${removed.total} removed`,
+          changed.total && `${changed.total} changed`,
+        ].filter(Boolean);
+        const devices = new Set(
+          [...added.items, ...removed.items, ...changed.items].map(c => c.deviceName).filter(Boolean)
+        );
+        const from = devices.size === 1 ? ` from ${[...devices][0]}` : devices.size > 1 ? ` from ${devices.size} devices` : "";
+        setSyncSinceLastSession(`While you were away: ${parts.join(", ")}${from}`);
+        setTimeout(() => setSyncSinceLastSession(null), 12000);
+      }),
       // page context menu (right-click on web content)
       listen<any>("webview-context-menu", (e) => {
         const p = e.payload;
@@ -810,6 +1025,21 @@ export default function App() {
         });
       }),
       // permission prompt from webview
+      listen<CertErrorRequest>("cert-error", (e) => {
+        setCertRemember(false);
+        setCertReq(e.payload);
+      }),
+      listen<{ requestId: string }>("cert-error-expired", (e) => {
+        const cur = useVaultStore.getState().certReq;
+        if (cur?.requestId === e.payload.requestId) setCertReq(null);
+      }),
+      listen<BasicAuthRequest>("basic-auth-requested", (e) => {
+        setAuthReq(e.payload);
+      }),
+      listen<{ requestId: string }>("basic-auth-expired", (e) => {
+        const cur = useVaultStore.getState().authReq;
+        if (cur?.requestId === e.payload.requestId) setAuthReq(null);
+      }),
       listen<PermissionRequest>("permission-requested", (e) => {
         setPermReq(e.payload);
         setPermRemember(true);
@@ -830,6 +1060,7 @@ export default function App() {
         // hide tab webviews so overlay is visible
         invoke("layout_webviews", { panes: [], focusedTabId: "__none__", sidebarW: layoutOffsetRef.current, topOffset });
       }),
+      listen("vault-locked", () => setVaultUnlocked(false)),
       listen<{ domain: string; username: string; password: string }>("vault-save-prompt", (e) => {
         setVaultSavePrompt(e.payload);
         if (vaultSaveTimer.current) clearTimeout(vaultSaveTimer.current);
@@ -929,28 +1160,24 @@ export default function App() {
     }
   }, [vaultMasterModal, vaultSavePrompt]);
 
-  // vault auto-lock after inactivity
+  // vault auto-lock runs in the backend; feed it main-window activity (tab
+  // webviews report their own through the shortcut bridge)
   useEffect(() => {
-    if (!settings.vaultAutoLock || !settings.vaultLockTimeout || !vaultUnlocked) return;
-    const timeoutMs = settings.vaultLockTimeout * 60_000;
-    let timer = setTimeout(() => {
-      invoke("vault_lock").then(() => setVaultUnlocked(false)).catch(e => console.warn("[bushido]", e));
-    }, timeoutMs);
-    const reset = () => {
-      clearTimeout(timer);
-      timer = setTimeout(() => {
-        invoke("vault_lock").then(() => setVaultUnlocked(false)).catch(e => console.warn("[bushido]", e));
-      }, timeoutMs);
+    if (!vaultUnlocked) return;
+    let last = 0;
+    const note = () => {
+      const now = Date.now();
+      if (now - last < 30_000) return;
+      last = now;
+      invoke("vault_note_activity").catch(e => console.warn("[bushido]", e));
     };
-    // reset timer on user activity signals
-    window.addEventListener("mousedown", reset);
-    window.addEventListener("keydown", reset);
+    window.addEventListener("mousedown", note);
+    window.addEventListener("keydown", note);
     return () => {
-      clearTimeout(timer);
-      window.removeEventListener("mousedown", reset);
-      window.removeEventListener("keydown", reset);
+      window.removeEventListener("mousedown", note);
+      window.removeEventListener("keydown", note);
     };
-  }, [settings.vaultAutoLock, settings.vaultLockTimeout, vaultUnlocked]);
+  }, [vaultUnlocked]);
 
   // hide panel webview when entering compact mode
   useEffect(() => {
@@ -982,13 +1209,14 @@ export default function App() {
           if (t.pinned || t.url.startsWith("bushido://")) return t;
           if (t.memoryState === "destroyed") return t;
           if (t.mediaState === "playing") return t;
+          if (t.throttling === "disabled" || t.windowLabel) return t;
 
           const idle = now - (t.lastActiveAt || 0);
           const state = t.memoryState || "active";
 
           // tier 2: destroy webview (full page reload on restore)
           if (idle > destroyMs) {
-            invoke("close_tab", { id: t.id });
+            invoke("discard_tab", { id: t.id });
             changed = true;
             return { ...t, memoryState: "destroyed" as const, suspended: true, loading: false };
           }
@@ -1008,6 +1236,21 @@ export default function App() {
     return () => clearInterval(interval);
   }, [activeTab, paneTabIds, settings.suspendTimeout]);
 
+  // "suspend" throttling policy: suspended as soon as it's hidden, whatever suspendTimeout says
+  useEffect(() => {
+    const suspendNow = (t: Tab) => t.throttling === "suspend"
+      && t.id !== activeTab && !paneTabIds.includes(t.id)
+      && (t.memoryState || "active") === "active"
+      && !t.pinned && !t.url.startsWith("bushido://")
+      && t.mediaState !== "playing" && !t.windowLabel;
+    if (!tabs.some(suspendNow)) return;
+    setTabs(prev => prev.map(t => {
+      if (!suspendNow(t)) return t;
+      invoke("suspend_tab", { id: t.id }).catch(() => {});
+      return { ...t, memoryState: "suspended" as const };
+    }));
+  }, [tabs, activeTab, paneTabIds]);
+
   // --- workspace operations ---
 
   const switchWorkspace = useCallback((wsId: string) => {
@@ -1149,8 +1392,9 @@ export default function App() {
   }, []);
 
   const muteTab = useCallback((tabId: string) => {
-    invoke("media_mute", { id: tabId });
-  }, []);
+    const tab = tabs.find(t => t.id === tabId);
+    invoke("set_tab_muted", { id: tabId, muted: !tab?.muted }).catch(e => console.warn("[bushido]", e));
+  }, [tabs]);
 
   const renameTab = useCallback((tabId: string, customTitle: string) => {
     setTabs(prev => prev.map(t => t.id === tabId ? { ...t, customTitle: customTitle || undefined } : t));
@@ -1158,21 +1402,23 @@ export default function App() {
 
   // --- tab operations (workspace-aware) ---
 
-  const addTab = useCallback((url = NEW_TAB_URL, parentId?: string) => {
-    const id = genId();
+  const addTab = useCallback((url = settingsRef.current.newTabUrl || NEW_TAB_URL, parentId?: string, restore?: { id: string; title: string; pinned: boolean }, isPrivate = false) => {
+    const id = restore?.id ?? genId();
     const isInternal = url.startsWith("bushido://");
-    const title = url === SETTINGS_URL ? "Settings" : "New Tab";
-    const tab: Tab = { id, url, title, loading: !isInternal, workspaceId: activeWorkspaceId, parentId, lastActiveAt: Date.now() };
+    const title = restore?.title || (url === SETTINGS_URL ? "Settings" : "New Tab");
+    const tab: Tab = { id, url, title, loading: !isInternal, pinned: restore?.pinned || undefined, workspaceId: activeWorkspaceId, parentId, lastActiveAt: Date.now(), private: isPrivate || undefined };
     setTabs(prev => [...prev, tab]);
     setWorkspaces(prev => prev.map(w => w.id === activeWorkspaceId ? { ...w, activeTabId: id, paneLayout: undefined } : w));
     if (!isInternal) {
       const sr = settingsRef.current;
       const cw = window.innerWidth - layoutOffset;
       const ch = window.innerHeight - topOffset;
-      invoke("create_tab", { id, url, sidebarW: layoutOffset, topOffset, httpsOnly: sr.httpsOnly, adBlocker: sr.adBlocker, cookieAutoReject: sr.cookieAutoReject, isPanel: false, profileName: activeWorkspaceId, ...secArgs(sr) }).then(() => {
+      invoke("create_tab", { id, url, sidebarW: layoutOffset, topOffset, httpsOnly: sr.httpsOnly, adBlocker: sr.adBlocker, cookieAutoReject: sr.cookieAutoReject, isPanel: false, profileName: activeWorkspaceId, private: isPrivate, ...secArgs(sr) }).then(() => {
         invoke("layout_webviews", { panes: [{ tabId: id, x: 0, y: 0, w: cw, h: ch }], focusedTabId: id, sidebarW: layoutOffset, topOffset });
-      }).catch(() => {
-        useUiStore.getState().showError("Failed to create tab");
+      }).catch(e => {
+        useUiStore.getState().showError(e?.kind === "Limit"
+          ? `${e.message}. Close some tabs${sr.suspendTimeout ? "" : " or turn on tab suspending"}, or raise the limit in Settings.`
+          : "Failed to create tab");
         setTabs(prev => prev.filter(t => t.id !== id));
       });
       clearLoading(id);
@@ -1182,21 +1428,24 @@ export default function App() {
     }
   }, [activeWorkspaceId, clearLoading, layoutOffset, topOffset]);
 
-  const closeTab = useCallback((id: string) => {
+  const reopenClosedTab = useCallback(() => {
+    const id = genId();
+    invoke<{ url: string; title: string; pinned: boolean } | null>("reopen_closed_tab", { id }).then(c => {
+      if (c) addTab(c.url, undefined, { id, title: c.title, pinned: c.pinned });
+    }).catch(e => console.warn("[bushido]", e));
+  }, [addTab]);
+
+  // UI side of closing a tab whose webview is already gone
+  const dropTab = useCallback((id: string) => {
     // if source tab of glance is being closed, close glance too
     if (glanceRef.current?.sourceTabId === id) {
       invoke("close_glance", { glanceId: glanceRef.current.id }).catch(e => console.warn("[bushido]", e));
       glanceRef.current = null;
       setGlance(null);
     }
-    invoke("close_tab", { id });
     setTabs(prev => {
       const tab = prev.find(t => t.id === id);
       if (!tab) return prev;
-      if (!tab.url.startsWith("bushido://")) {
-        closedTabsRef.current.push({ url: tab.url, title: tab.title, workspaceId: tab.workspaceId });
-        if (closedTabsRef.current.length > 20) closedTabsRef.current.shift();
-      }
       const wsId = tab.workspaceId;
       const wsTabs = prev.filter(t => t.workspaceId === wsId);
       const next = prev.filter(t => t.id !== id).map(t =>
@@ -1250,6 +1499,26 @@ export default function App() {
     });
   }, [activeWorkspaceId, layoutOffset, topOffset, syncLayout]);
 
+  const closeTab = useCallback((id: string) => {
+    // outside the updater: StrictMode runs updaters twice, which would remember the tab twice
+    const closing = useTabStore.getState().tabs.find(t => t.id === id);
+    if (closing) {
+      invoke("close_tab", { id, remember: true, url: closing.url, title: closing.title, workspaceId: closing.workspaceId });
+    } else {
+      invoke("close_tab", { id });
+    }
+    dropTab(id);
+  }, [dropTab]);
+
+  // bulk closes skip pinned tabs; the backend says which ones it actually closed
+  const closeTabs = useCallback((ids: string[]) => {
+    const closing = useTabStore.getState().tabs.filter(t => ids.includes(t.id))
+      .map(t => ({ id: t.id, url: t.url, title: t.title, workspaceId: t.workspaceId }));
+    invoke<string[]>("close_tabs", { tabs: closing })
+      .then(closed => closed.forEach(dropTab))
+      .catch(e => console.warn("[bushido]", e));
+  }, [dropTab]);
+
   const selectTab = useCallback((id: string) => {
     const targetTab = tabs.find(t => t.id === id);
     const ws = workspaces.find(w => w.id === activeWorkspaceId);
@@ -1273,7 +1542,7 @@ export default function App() {
     if (targetTab?.crashed) {
       // recreate crashed webview
       const sr = settingsRef.current;
-      invoke("close_tab", { id }).then(() =>
+      invoke("close_tab", { id, recreate: true }).then(() =>
         invoke("create_tab", { id, url: targetTab.url, sidebarW: layoutOffset, topOffset, httpsOnly: sr.httpsOnly, adBlocker: sr.adBlocker, cookieAutoReject: sr.cookieAutoReject, isPanel: false, profileName: targetTab.workspaceId, ...secArgs(sr) })
       ).then(() => syncLayout(updated));
       clearLoading(id);
@@ -1298,6 +1567,37 @@ export default function App() {
     }
   }, [activeWorkspaceId, tabs, workspaces, layoutOffset, topOffset, clearLoading, syncLayout]);
 
+  // "open in new tab", but jump to an existing tab with the same URL when the setting is on
+  const openOrSwitchTab = useCallback((url: string) => {
+    if (!settingsRef.current.switchToExistingTab || url.startsWith("bushido://")) { addTab(url); return; }
+    invoke<string | null>("find_tab_by_url", { url }).then(id => {
+      const tab = id ? tabs.find(t => t.id === id) : undefined;
+      if (!tab) { addTab(url); return; }
+      if (tab.workspaceId === activeWorkspaceId) { selectTab(tab.id); return; }
+      setWorkspaces(prev => prev.map(w => w.id === tab.workspaceId ? { ...w, activeTabId: tab.id, paneLayout: undefined } : w));
+      switchWorkspace(tab.workspaceId);
+    }).catch(() => addTab(url));
+  }, [addTab, tabs, activeWorkspaceId, selectTab, switchWorkspace]);
+
+  // bring back the arrangement stored by save_layout, recreating panes whose tabs were closed
+  const restoreSavedLayout = useCallback(async () => {
+    const snap = await invoke<LayoutSnapshot | null>("get_saved_layout").catch(() => null);
+    if (!snap) { showError("No saved layout"); return; }
+    const have = new Set(tabs.map(t => t.id));
+    const missing = snap.panes.filter(p => !have.has(p.tabId));
+    const sr = settingsRef.current;
+    setTabs(prev => [...prev, ...missing.map(p => ({
+      id: p.tabId, url: p.url || NEW_TAB_URL, title: "New Tab", loading: true, workspaceId: activeWorkspaceId, lastActiveAt: Date.now(),
+    } as Tab))]);
+    await Promise.all(missing.map(p =>
+      invoke("create_tab", { id: p.tabId, url: p.url || NEW_TAB_URL, sidebarW: layoutOffset, topOffset, httpsOnly: sr.httpsOnly, adBlocker: sr.adBlocker, cookieAutoReject: sr.cookieAutoReject, isPanel: false, profileName: activeWorkspaceId, ...secArgs(sr) })
+        .then(() => clearLoading(p.tabId))
+        .catch(() => setTabs(prev => prev.filter(t => t.id !== p.tabId)))
+    ));
+    setWorkspaces(prev => prev.map(w => w.id === activeWorkspaceId ? { ...w, activeTabId: snap.focusedTabId, paneLayout: snap.tree ?? undefined } : w));
+    invoke("restore_layout", { snapshot: { ...snap, sidebarW: layoutOffset, topOffset } }).catch(e => console.warn("[bushido]", e));
+  }, [tabs, activeWorkspaceId, layoutOffset, topOffset, clearLoading, showError]);
+
   const pinTab = useCallback((id: string) => {
     setTabs(prev => {
       const tab = prev.find(t => t.id === id);
@@ -1372,6 +1672,12 @@ export default function App() {
     }
   }, [settings.searchEngine, settings.customSearchUrl]);
 
+  // address-bar searches in navigate_tab use the backend's copy of the template
+  useEffect(() => {
+    const template = getSearchUrl("%s").replace("%25s", "%s");
+    invoke("set_search_engine", { template }).catch(e => console.warn("[bushido] search engine:", e));
+  }, [getSearchUrl]);
+
   const navigate = useCallback((url: string) => {
     if (!activeTab) return;
     let finalUrl = url;
@@ -1386,7 +1692,7 @@ export default function App() {
     setTabs(prev => prev.map(t => t.id === activeTab ? { ...t, url: finalUrl, loading: true, blockedCount: 0 } : t));
     if (currentTab?.url?.startsWith("bushido://") || currentTab?.suspended || currentTab?.memoryState === "destroyed" || currentTab?.memoryState === "suspended") {
       const sr = settingsRef.current;
-      invoke("create_tab", { id: activeTab, url: finalUrl, sidebarW: layoutOffset, topOffset, httpsOnly: sr.httpsOnly, adBlocker: sr.adBlocker, cookieAutoReject: sr.cookieAutoReject, isPanel: false, profileName: currentTab?.workspaceId, ...secArgs(sr) }).then(() => {
+      invoke("create_tab", { id: activeTab, url: finalUrl, sidebarW: layoutOffset, topOffset, httpsOnly: sr.httpsOnly, adBlocker: sr.adBlocker, cookieAutoReject: sr.cookieAutoReject, isPanel: false, profileName: currentTab?.workspaceId, private: !!currentTab?.private, ...secArgs(sr) }).then(() => {
         // directly position — syncLayout would read stale tab URL from state
         const cw = window.innerWidth - layoutOffset;
         const ch = window.innerHeight - topOffset;
@@ -1577,7 +1883,8 @@ export default function App() {
   const showInternalPage = showNtp || showSettings;
 
   const toggleWhitelist = useCallback(() => {
-    if (!activeTab) return;
+    // while blocking is paused everywhere the shield shows that, not the site's whitelist
+    if (!activeTab || blockingPaused) return;
     const tab = tabs.find(t => t.id === activeTab);
     if (!tab) return;
     let domain = "";
@@ -1587,8 +1894,35 @@ export default function App() {
       setTabs(prev => prev.map(t => t.id === activeTab ? { ...t, whitelisted } : t));
       invoke("reload_tab", { id: activeTab });
     });
+  }, [activeTab, tabs, blockingPaused]);
+
+  // DNT/GPC off for sites that break on them (headers: now, navigator.*: after the reload)
+  const togglePrivacySignalsForSite = useCallback(() => {
+    const tab = tabs.find(t => t.id === activeTab);
+    let domain = "";
+    try { domain = tab ? new URL(tab.url).hostname : ""; } catch {}
+    if (!domain) return;
+    invoke<string[]>("get_privacy_signal_exemptions").then(exempt => {
+      const enabled = exempt.includes(domain.toLowerCase());
+      return invoke("set_site_privacy_signals", { domain, enabled });
+    }).then(() => invoke("reload_tab", { id: activeTab })).catch(e => console.warn("[bushido]", e));
   }, [activeTab, tabs]);
 
+  // injected scripts are fixed at webview creation, so toggling recreates the tab
+  const toggleInjectionBypass = useCallback((tabId: string) => {
+    const tab = tabs.find(t => t.id === tabId);
+    if (!tab || tab.url.startsWith("bushido://")) return;
+    const sr = settingsRef.current;
+    invoke("set_tab_injection_bypass", { id: tabId, bypass: !tab.injectionBypass })
+      .then(() => invoke("close_tab", { id: tabId, recreate: true }))
+      .then(() => invoke("create_tab", { id: tabId, url: tab.url, sidebarW: layoutOffset, topOffset, httpsOnly: sr.httpsOnly, adBlocker: sr.adBlocker, cookieAutoReject: sr.cookieAutoReject, isPanel: false, profileName: tab.workspaceId, ...secArgs(sr) }))
+      .then(() => {
+        const ws = workspaces.find(w => w.id === activeWorkspaceId);
+        if (ws) syncLayout(ws);
+      })
+      .catch(e => console.warn("[bushido]", e));
+  }, [tabs, layoutOffset, topOffset, secArgs, workspaces, activeWorkspaceId, syncLayout]);
+
   // --- bookmark operations ---
 
   const addBookmark = useCallback((url: string, title: string, favicon?: string, folderId = "") => {
@@ -1696,6 +2030,24 @@ export default function App() {
     }));
   }, []);
 
+  const openBookmarkFolder = useCallback((folderId: string) => {
+    const sr = settingsRef.current;
+    const wsId = activeWorkspaceId;
+    invoke<{ id: string; url: string; title: string }[]>("open_bookmark_folder", {
+      folderId, sidebarW: layoutOffset, topOffset, httpsOnly: sr.httpsOnly, adBlocker: sr.adBlocker,
+      cookieAutoReject: sr.cookieAutoReject, profileName: wsId, ...secArgs(sr),
+    }).then(opened => {
+      if (!opened.length) return;
+      const now = Date.now();
+      setTabs(prev => [...prev, ...opened.map(o => ({ id: o.id, url: o.url, title: o.title || o.url, loading: true, workspaceId: wsId, lastActiveAt: now }))]);
+      setWorkspaces(prev => prev.map(w => w.id === wsId ? { ...w, activeTabId: opened[0].id, paneLayout: undefined } : w));
+      opened.forEach(o => clearLoading(o.id));
+      const cw = window.innerWidth - layoutOffset;
+      const ch = window.innerHeight - topOffset;
+      invoke("layout_webviews", { panes: [{ tabId: opened[0].id, x: 0, y: 0, w: cw, h: ch }], focusedTabId: opened[0].id, sidebarW: layoutOffset, topOffset });
+    }).catch(e => showError(e?.kind === "Limit" ? `${e.message}. Close some tabs first.` : "Couldn't open the folder"));
+  }, [activeWorkspaceId, layoutOffset, topOffset, secArgs, clearLoading, showError]);
+
   // RSS fetch effect — on startup + every 30 min
   useEffect(() => {
     const fetchRssForFolder = async (folder: { id: string; rssUrl?: string; lastFetched?: number }) => {
@@ -1746,6 +2098,13 @@ export default function App() {
   // eslint-disable-next-line react-hooks/exhaustive-deps
   }, [bookmarkData.folders.filter(f => f.rssUrl).map(f => f.id + f.rssUrl).join(",")]);
 
+  const saveFromContext = useCallback((tabId: string, uri: string, suggestedName?: string) => {
+    const sr = settingsRef.current;
+    invoke("download_from_context", { tabId, uri, suggestedName: suggestedName || null, downloadDir: sr.downloadLocation || "", mimeRouting: sr.mimeRouting || null, askWhereToSave: !!sr.askDownloadLocation })
+      .then(() => setDownloadsOpen(true))
+      .catch((err: any) => showError(err?.message || String(err)));
+  }, [showError]);
+
   const bookmarkedUrls = useMemo(() => new Set(bookmarkData.bookmarks.map(b => b.url)), [bookmarkData.bookmarks]);
 
   const toggleBookmark = useCallback(() => {
@@ -1763,6 +2122,7 @@ export default function App() {
     if (range === 'all') { setHistoryEntries([]); return; }
     const cutoff = range === 'hour' ? Date.now() - 3600_000 : new Date().setHours(0, 0, 0, 0);
     setHistoryEntries(prev => prev.filter(h => h.lastVisitAt < cutoff));
+    invoke("clear_history_range", { fromTs: cutoff, toTs: Date.now() }).catch(() => {});
   }, []);
 
   const selectBookmark = useCallback((url: string) => {
@@ -1803,16 +2163,26 @@ export default function App() {
     return sites;
   }, [historyEntries]);
 
+  // history is matched and ranked by the backend, off this thread; bookmarks are few
+  // enough to filter here
+  const [historyMatches, setHistoryMatches] = useState<HistoryEntry[]>([]);
+  useEffect(() => {
+    if (!urlQuery || urlQuery.length < 2) { setHistoryMatches([]); return; }
+    let stale = false;
+    invoke<HistoryEntry[]>("search_history", { query: urlQuery, limit: 8 })
+      .then(r => { if (!stale) setHistoryMatches(r); })
+      .catch(e => console.warn("[bushido]", e));
+    return () => { stale = true; };
+  }, [urlQuery]);
+
   // frecency suggestions for URL bar
   const suggestions = useMemo((): FrecencyResult[] => {
     if (!urlQuery || urlQuery.length < 2) return [];
     const q = urlQuery.toLowerCase();
     const map = new Map<string, FrecencyResult>();
 
-    for (const h of historyEntries) {
-      if (h.url.toLowerCase().includes(q) || h.title.toLowerCase().includes(q)) {
-        map.set(h.url, { url: h.url, title: h.title, favicon: h.favicon, score: frecencyScore(h.visitCount, h.lastVisitAt), type: 'history' });
-      }
+    for (const h of historyMatches) {
+      map.set(h.url, { url: h.url, title: h.title, favicon: h.favicon, score: frecencyScore(h.visitCount, h.lastVisitAt), type: 'history' });
     }
     for (const b of bookmarkData.bookmarks) {
       if (b.url.toLowerCase().includes(q) || b.title.toLowerCase().includes(q)) {
@@ -1823,7 +2193,7 @@ export default function App() {
     }
 
     return Array.from(map.values()).sort((a, b) => b.score - a.score).slice(0, 8);
-  }, [urlQuery, historyEntries, bookmarkData.bookmarks]);
+  }, [urlQuery, historyMatches, bookmarkData.bookmarks]);
 
   const onSuggestionSelect = useCallback((url: string) => {
     navigate(url);
@@ -1848,6 +2218,8 @@ export default function App() {
       font: readerSettings.font,
       theme: readerSettings.theme,
       lineWidth: readerSettings.lineWidth,
+      letterSpacing: readerSettings.letterSpacing,
+      paragraphSpacing: readerSettings.paragraphSpacing,
     });
 
     setReaderTabs(prev => {
@@ -1856,6 +2228,12 @@ export default function App() {
       else next.add(activeTab);
       return next;
     });
+    setReaderProgress(prev => {
+      if (!(activeTab in prev)) return prev;
+      const next = { ...prev };
+      delete next[activeTab];
+      return next;
+    });
   }, [activeTab, tabs, readerSettings]);
 
   const updateReaderSettings = useCallback((update: Partial<typeof readerSettings>) => {
@@ -1913,6 +2291,8 @@ export default function App() {
   const executeAction = useCallback((action: string) => {
     switch (action) {
       case "action-new-tab": addTab(); break;
+      case "action-new-private-tab": addTab(undefined, undefined, undefined, true); break;
+      case "action-home-tab": invoke("open_home_tab").catch(e => showError(e?.message || String(e))); break;
       case "action-close-tab": closeTab(activeTab); break;
       case "action-toggle-compact": setCompactMode(p => { const next = !p; setSettings(s => ({ ...s, compactMode: next })); return next; }); break;
       case "action-toggle-sidebar": setSidebarOpen(p => !p); break;
@@ -1922,8 +2302,85 @@ export default function App() {
       case "action-history": setHistoryOpen(true); break;
       case "action-bookmark": toggleBookmark(); break;
       case "action-screenshot": openScreenshot(); break;
+      case "action-clear-site-data": {
+        const tab = tabs.find(t => t.id === activeTab);
+        let domain = "";
+        try { domain = tab ? new URL(tab.url).hostname : ""; } catch {}
+        if (!domain) break;
+        invoke<number>("clear_site_data", { id: activeTab, domain })
+          .then(() => invoke("reload_tab", { id: activeTab }))
+          .catch(e => showError(e?.message || String(e)));
+        break;
+      }
+      case "action-site-privacy-signals": togglePrivacySignalsForSite(); break;
+      case "action-toggle-offline": invoke("set_offline", { offline: !offline }).catch(() => {}); break;
+      case "action-link-hints": if (activeTab) invoke("toggle_link_hints", { id: activeTab }).catch(e => console.warn("[bushido]", e)); break;
+      case "action-move-to-window": {
+        const t = tabs.find(x => x.id === activeTab);
+        if (!t || t.windowLabel || t.url.startsWith("bushido://")) break;
+        const sr = settingsRef.current;
+        invoke<string>("move_tab_to_new_window", { id: t.id }).then(label => {
+          setTabs(prev => prev.map(x => x.id === t.id ? { ...x, windowLabel: label, loading: true } : x));
+          // sidebarW/topOffset are ignored for a tab in its own window
+          invoke("create_tab", { id: t.id, url: t.url, sidebarW: 0, topOffset: 0, httpsOnly: sr.httpsOnly, adBlocker: sr.adBlocker, cookieAutoReject: sr.cookieAutoReject, isPanel: false, profileName: t.workspaceId, ...secArgs(sr) });
+          clearLoading(t.id);
+          // the main window keeps a tab of its own, even if this was its last
+          const other = tabs.find(x => x.workspaceId === t.workspaceId && x.id !== t.id && !x.windowLabel);
+          if (other) selectTab(other.id); else addTab();
+        }).catch(e => showError(e?.message || String(e)));
+        break;
+      }
+      case "action-keep-running": {
+        const t = tabs.find(x => x.id === activeTab);
+        if (t) invoke("set_tab_throttling", { id: t.id, policy: t.throttling === "disabled" ? "throttle" : "disabled" }).catch(e => showError(e?.message || String(e)));
+        break;
+      }
+      case "action-allow-autoplay": {
+        const url = useTabStore.getState().tabs.find(t => t.id === activeTab)?.url;
+        let domain = "";
+        try { domain = url ? new URL(url).hostname : ""; } catch {}
+        if (domain) invoke("set_autoplay_policy", { domain, allow: true }).catch(e => showError(e?.message || String(e)));
+        break;
+      }
+      case "action-unhide-element": if (activeTab) invoke("start_element_picker", { id: activeTab }).catch(e => showError(e?.message || String(e))); break;
+      case "action-toggle-blocking": invoke("set_global_blocking", { enabled: blockingPaused }).catch(() => {}); break;
+      case "action-toggle-injection-bypass": if (activeTab) toggleInjectionBypass(activeTab); break;
+      case "action-toggle-network-capture": {
+        const tab = tabs.find(t => t.id === activeTab);
+        if (!tab) break;
+        const enabled = !tab.networkCapture;
+        invoke("set_network_capture", { id: tab.id, enabled })
+          .then(() => setTabs(prev => prev.map(t => t.id === tab.id ? { ...t, networkCapture: enabled || undefined } : t)))
+          .catch(e => console.warn("[bushido]", e));
+        break;
+      }
+      case "action-tab-theme-dark": if (activeTab) invoke("set_tab_theme", { id: activeTab, theme: "dark" }).catch(e => console.warn("[bushido]", e)); break;
+      case "action-tab-theme-light": if (activeTab) invoke("set_tab_theme", { id: activeTab, theme: "light" }).catch(e => console.warn("[bushido]", e)); break;
+      case "action-tab-theme-auto": if (activeTab) invoke("set_tab_theme", { id: activeTab, theme: "auto" }).catch(e => console.warn("[bushido]", e)); break;
+      case "action-site-ua-desktop":
+      case "action-site-ua-mobile":
+      case "action-site-ua-reset": {
+        const tab = tabs.find(t => t.id === activeTab);
+        let domain = "";
+        try { domain = tab ? new URL(tab.url).hostname : ""; } catch {}
+        if (!domain) break;
+        const ua = action === "action-site-ua-reset" ? null : action === "action-site-ua-mobile" ? "mobile" : "desktop";
+        invoke("set_site_user_agent", { domain, ua }).catch(e => showError(e?.message || String(e)));
+        break;
+      }
+      case "action-save-layout": invoke("save_layout").catch(e => showError(e?.message || "Nothing to save")); break;
+      case "action-restore-layout": restoreSavedLayout(); break;
+      case "action-export-network": if (activeTab) invoke("export_tab_network", { id: activeTab, path: null }).catch(e => console.warn("[bushido]", e)); break;
+      case "action-export-reader-markdown": if (activeTab) invoke("export_reader_markdown", { id: activeTab }).catch(e => showError(e?.message || String(e))); break;
+      case "action-reader-speak": if (activeTab) invoke("reader_speak", { id: activeTab }).catch(e => showError(e?.message || String(e))); break;
+      case "action-toggle-images": {
+        const tab = tabs.find(t => t.id === activeTab);
+        if (tab) invoke("set_tab_images_enabled", { id: tab.id, enabled: !!tab.imagesOff, reload: true }).catch(e => showError(e?.message || String(e)));
+        break;
+      }
+      case "action-reader-stop": if (activeTab) invoke("reader_stop", { id: activeTab }).catch(e => console.warn("[bushido]", e)); break;
     }
-  }, [addTab, closeTab, activeTab, clearHistory, toggleBookmark, onOpenSettings, toggleReader, openScreenshot]);
+  }, [addTab, closeTab, activeTab, clearHistory, toggleBookmark, onOpenSettings, toggleReader, openScreenshot, offline, blockingPaused, togglePrivacySignalsForSite, toggleInjectionBypass, tabs, restoreSavedLayout, showError, selectTab, secArgs, clearLoading]);
 
   const handleQuickAction = useCallback((action: string) => {
     switch (action) {
@@ -1950,6 +2407,13 @@ export default function App() {
     }
   }, [activeTab, tabs, toggleSplit, onOpenSettings, pinTab, muteTab, toggleBookmark, openScreenshot, toggleReader, togglePip, setFindOpen, clearHistory, addWorkspace, closeTab]);
 
+  const cycleTab = useCallback((dir: 1 | -1) => {
+    const wsTabs = currentWsTabs;
+    if (wsTabs.length === 0) return;
+    const idx = wsTabs.findIndex(t => t.id === activeTab);
+    selectTab(wsTabs[(idx + dir + wsTabs.length) % wsTabs.length].id);
+  }, [currentWsTabs, activeTab, selectTab]);
+
   // keyboard shortcuts (works when React UI has focus)
   useEffect(() => {
     const handler = (e: KeyboardEvent) => {
@@ -1962,7 +2426,7 @@ export default function App() {
       if (ctrl && e.key === "f") { e.preventDefault(); setFindOpen(true); }
       if (ctrl && e.key === "k") { e.preventDefault(); setCmdOpen(p => !p); }
       if (ctrl && e.shiftKey && e.key === "R") { e.preventDefault(); toggleReader(); }
-      if (ctrl && e.shiftKey && e.key === "T") { e.preventDefault(); const c = closedTabsRef.current.pop(); if (c) addTab(c.url); }
+      if (ctrl && e.shiftKey && e.key === "T") { e.preventDefault(); reopenClosedTab(); }
       if (ctrl && e.shiftKey && e.key === "I") { e.preventDefault(); invoke("toggle_devtools", { id: activeTab }); }
       if (ctrl && e.key === "d" && !e.shiftKey) { e.preventDefault(); toggleBookmark(); }
       if (ctrl && e.key === "h" && !e.shiftKey) { e.preventDefault(); setHistoryOpen(p => !p); }
@@ -1975,16 +2439,7 @@ export default function App() {
       if (e.key === "F11") { e.preventDefault(); invoke("toggle_fullscreen"); }
       if (e.key === "F5") { e.preventDefault(); invoke("reload_tab", { id: activeTab }); }
       // Ctrl+Tab cycles tabs within current workspace
-      if (ctrl && e.key === "Tab") {
-        e.preventDefault();
-        const wsTabs = currentWsTabs;
-        if (wsTabs.length === 0) return;
-        const idx = wsTabs.findIndex(t => t.id === activeTab);
-        const next = e.shiftKey
-          ? (idx - 1 + wsTabs.length) % wsTabs.length
-          : (idx + 1) % wsTabs.length;
-        selectTab(wsTabs[next].id);
-      }
+      if (ctrl && e.key === "Tab") { e.preventDefault(); cycleTab(e.shiftKey ? -1 : 1); }
       // Ctrl+1-9 switches workspaces
       if (ctrl && e.key >= "1" && e.key <= "9") {
         e.preventDefault();
@@ -1994,7 +2449,7 @@ export default function App() {
     };
     window.addEventListener("keydown", handler);
     return () => window.removeEventListener("keydown", handler);
-  }, [addTab, closeTab, activeTab, currentWsTabs, workspaces, selectTab, switchWorkspace, toggleBookmark, toggleReader]);
+  }, [addTab, closeTab, reopenClosedTab, activeTab, cycleTab, workspaces, switchWorkspace, toggleBookmark, toggleReader]);
 
   // global shortcut bridge: Rust eval() calls this directly on the main webview
   // also handles child webview shortcuts forwarded via title encoding → global-shortcut event
@@ -2012,20 +2467,22 @@ export default function App() {
         case "command-palette": setCmdOpen(p => !p); break;
         case "screenshot": openScreenshot(); break;
         case "reader-mode": toggleReader(); break;
+        case "next-tab": cycleTab(1); break;
+        case "prev-tab": cycleTab(-1); break;
         case "split-view": toggleSplit(); break;
         case "print": invoke("print_tab", { id: activeTab }); break;
         case "reload": invoke("reload_tab", { id: activeTab }); break;
         case "fullscreen": invoke("toggle_fullscreen"); break;
         case "downloads": setDownloadsOpen(p => !p); break;
         case "devtools": invoke("toggle_devtools", { id: activeTab }); break;
-        case "reopen-tab": { const c = closedTabsRef.current.pop(); if (c) addTab(c.url); break; }
+        case "reopen-tab": reopenClosedTab(); break;
         case "zoom-in": { const z = Math.min((zoomRef.current[activeTab] || 1) + 0.1, 3); zoomRef.current[activeTab] = z; setZoomDisplay(p => ({ ...p, [activeTab]: z })); invoke("zoom_tab", { id: activeTab, factor: z }); break; }
         case "zoom-out": { const z = Math.max((zoomRef.current[activeTab] || 1) - 0.1, 0.3); zoomRef.current[activeTab] = z; setZoomDisplay(p => ({ ...p, [activeTab]: z })); invoke("zoom_tab", { id: activeTab, factor: z }); break; }
         case "zoom-reset": { zoomRef.current[activeTab] = 1; setZoomDisplay(p => ({ ...p, [activeTab]: 1 })); invoke("zoom_tab", { id: activeTab, factor: 1 }); break; }
       }
     };
     return () => { delete (window as any).__bushidoGlobalShortcut; };
-  }, [toggleBookmark, addTab, closeTab, activeTab, toggleSplit, openScreenshot]);
+  }, [toggleBookmark, addTab, closeTab, reopenClosedTab, activeTab, toggleSplit, openScreenshot, cycleTab]);
 
   // listen for child webview shortcut bridge events
   useEffect(() => {
@@ -2036,12 +2493,44 @@ export default function App() {
     return () => { p.then(u => u()); };
   }, []);
 
+  // open_home_tab leaves creating the tab to us
+  useEffect(() => {
+    const p = listen<{ url: string }>("open-home-tab", (e) => addTab(e.payload.url));
+    return () => { p.then(u => u()); };
+  }, [addTab]);
+
+  // files dragged over a tab: pages don't get them (unless the setting allows it),
+  // so show a hint and open each dropped file in its own tab
+  useEffect(() => {
+    const p = listen<{ tabId: string; type: "enter" | "over" | "drop" | "leave"; paths: string[] | null; blocked: boolean }>("drag-drop", (e) => {
+      const { type, paths, blocked } = e.payload;
+      if (type === "enter") setDropHint(blocked);
+      else if (type === "leave" || type === "drop") setDropHint(false);
+      if (type === "drop" && blocked && paths) {
+        for (const path of paths) {
+          const normalized = path.replace(/\\/g, "/");
+          addTab(encodeURI("file://" + (normalized.startsWith("/") ? "" : "/") + normalized));
+        }
+      }
+    });
+    return () => { p.then(u => u()); };
+  }, [addTab]);
+
   // stable callbacks for child components (prevents re-renders from new arrow refs)
   const toggleSidebar = useCallback(() => setSidebarOpen(p => !p), []);
   const addChildTab = useCallback((parentId: string) => addTab(NEW_TAB_URL, parentId), [addTab]);
   const goBack = useCallback(() => invoke("go_back", { id: activeTab }), [activeTab]);
   const goForward = useCallback(() => invoke("go_forward", { id: activeTab }), [activeTab]);
   const goReload = useCallback(() => invoke("reload_tab", { id: activeTab }), [activeTab]);
+
+  // tabs that haven't reported history yet (restored, never navigated) ask once on activation
+  const currentNavKnown = current?.canGoBack !== undefined;
+  useEffect(() => {
+    if (!activeTab || currentNavKnown) return;
+    invoke<{ can_go_back: boolean; can_go_forward: boolean }>("get_nav_state", { id: activeTab })
+      .then(s => setTabs(prev => prev.map(t => t.id === activeTab ? { ...t, canGoBack: s.can_go_back, canGoForward: s.can_go_forward } : t)))
+      .catch(() => {});
+  }, [activeTab, currentNavKnown]);
   const closeFindBar = useCallback(() => setFindOpen(false), []);
 
   // download callbacks
@@ -2058,7 +2547,10 @@ export default function App() {
     invoke("start_download", { url: dl.url, filename: dl.fileName, downloadDir: dir, cookies: null });
   }, [downloads, cancelDownload]);
   const clearCompletedDownloads = useCallback(() => {
-    setDownloads(prev => prev.filter(d => d.state !== "completed"));
+    invoke("clear_completed_downloads").catch(() => {});
+  }, []);
+  const exportDownloads = useCallback(() => {
+    invoke("export_downloads", { path: null }).catch(() => {});
   }, []);
   const toggleDownloads = useCallback(() => setDownloadsOpen(p => !p), []);
   const activeDownloadCount = useMemo(() => downloads.filter(d => d.state === "downloading").length, [downloads]);
@@ -2068,13 +2560,13 @@ export default function App() {
     if (!activeTab) return;
     networkEntriesRef.current = [];
     setNetworkEntries([]);
-    invoke("clear_network_log", { tabId: activeTab });
+    invoke("clear_tab_network", { id: activeTab });
   }, [activeTab]);
 
   useEffect(() => {
     invoke("set_network_log_enabled", { enabled: networkOpen });
     if (networkOpen && activeTab) {
-      invoke<NetworkEntry[]>("get_network_log", { tabId: activeTab }).then(entries => {
+      invoke<NetworkEntry[]>("get_tab_network", { id: activeTab }).then(entries => {
         networkEntriesRef.current = entries;
         setNetworkEntries(entries);
       });
@@ -2180,12 +2672,12 @@ export default function App() {
     const base = { httpsOnly: sr.httpsOnly, adBlocker: sr.adBlocker, cookieAutoReject: sr.cookieAutoReject, ...secArgs(sr) };
     tabs.forEach(t => {
       if (t.url.startsWith("bushido://") || t.suspended || t.memoryState === "destroyed") return;
-      invoke("close_tab", { id: t.id }).then(() => {
+      invoke("close_tab", { id: t.id, recreate: true }).then(() => {
         invoke("create_tab", { id: t.id, url: t.url, sidebarW: layoutOffset, topOffset, isPanel: false, profileName: t.workspaceId, ...base });
       });
     });
     panels.forEach(p => {
-      invoke("close_tab", { id: p.id }).then(() => {
+      invoke("close_tab", { id: p.id, recreate: true }).then(() => {
         invoke("create_tab", { id: p.id, url: p.url, sidebarW: layoutOffset, topOffset, isPanel: true, ...base })
           .then(() => invoke("register_panel", { id: p.id }));
       });
@@ -2239,6 +2731,8 @@ export default function App() {
           onClose={() => { setScreenshotPreview(null); syncLayout(); }}
           onAnnotate={(data) => { setScreenshotPreview(null); setAnnotationData(data); }}
           onRestoreWebview={() => syncLayout()}
+          saveFormat={settings.screenshotFormat}
+          saveQuality={settings.screenshotQuality}
         />
       )}
       {glance && (
@@ -2257,6 +2751,8 @@ export default function App() {
         <AnnotationEditor
           imageData={annotationData}
           onClose={() => { setAnnotationData(null); syncLayout(); }}
+          saveFormat={settings.screenshotFormat}
+          saveQuality={settings.screenshotQuality}
         />
       )}
       {shareOpen && current?.url && (
@@ -2301,6 +2797,7 @@ export default function App() {
           compact={compactMode}
           onSelect={selectTab}
           onClose={closeTab}
+          onCloseTabs={closeTabs}
           onPin={pinTab}
           onNew={addTab}
           onReorder={reorderTabs}
@@ -2333,9 +2830,12 @@ export default function App() {
           onReorderFolders={reorderFolders}
           onSetFolderRss={setFolderRss}
           onRemoveFolderRss={removeFolderRss}
+          onOpenBookmarkFolder={openBookmarkFolder}
           onToggleHistory={toggleHistory}
           onBack={goBack}
           onForward={goForward}
+          canGoBack={current?.canGoBack ?? true}
+          canGoForward={current?.canGoForward ?? true}
           onReload={goReload}
           url={current?.url || ""}
           onNavigate={navigate}
@@ -2343,6 +2843,7 @@ export default function App() {
           inputRef={urlBarRef}
           blockedCount={current?.blockedCount || 0}
           whitelisted={current?.whitelisted || false}
+          blockingPaused={blockingPaused}
           onToggleWhitelist={toggleWhitelist}
           suggestions={suggestions}
           topSites={topSites}
@@ -2352,6 +2853,7 @@ export default function App() {
           onToggleBookmark={toggleBookmark}
           onToggleReader={toggleReader}
           isReaderActive={readerTabs.has(activeTab)}
+          readerProgress={readerTabs.has(activeTab) ? readerProgress[activeTab] : undefined}
           readerSettings={readerSettings}
           onUpdateReaderSettings={updateReaderSettings}
           hasVideo={hasVideo}
@@ -2402,6 +2904,7 @@ export default function App() {
             onOpen={openDownload}
             onOpenFolder={openDownloadFolder}
             onClearCompleted={clearCompletedDownloads}
+            onExport={exportDownloads}
             onClose={toggleDownloads}
             onRetry={retryDownload}
           />
@@ -2434,7 +2937,7 @@ export default function App() {
               onUpdate={updateSettings}
               onReloadAllTabs={reloadAllTabs}
               onThemeChange={handleThemeChange}
-              onOpenUrl={addTab}
+              onOpenUrl={openOrSwitchTab}
               onImportBookmarks={handleImportBookmarks}
               onImportHistory={handleImportHistory}
             />
@@ -2467,6 +2970,27 @@ export default function App() {
         </div>
       </div>
     </div>
+    {tabs.some(t => t.id === activeTab && t.injectionBypass) && (
+      <button className="offline-indicator bypass-indicator" onClick={() => toggleInjectionBypass(activeTab)} title="reload with Bushido's page scripts">
+        <svg width="12" height="12" viewBox="0 0 12 12" fill="none">
+          <path d="M6 1L10.5 2.8V5.8C10.5 8.3 8.6 10.3 6 11C3.4 10.3 1.5 8.3 1.5 5.8V2.8L6 1ZM1.5 1.5L10.5 10.5" stroke="currentColor" strokeWidth="1.2" strokeLinecap="round" strokeLinejoin="round"/>
+        </svg>
+        <span>scripts bypassed</span>
+      </button>
+    )}
+    {offline && (
+      <button className="offline-indicator" onClick={() => invoke("set_offline", { offline: false }).catch(() => {})} title="go back online">
+        <svg width="12" height="12" viewBox="0 0 12 12" fill="none">
+          <path d="M1 4.5C4 1.8 8 1.8 11 4.5M3 6.7C4.8 5.2 7.2 5.2 9 6.7M1.5 1.5L10.5 10.5" stroke="currentColor" strokeWidth="1.2" strokeLinecap="round"/>
+        </svg>
+        <span>working offline</span>
+      </button>
+    )}
+    {dropHint && (
+      <div className="sync-toast" style={{ pointerEvents: 'none' }}>
+        <span>Drop to open in a new tab</span>
+      </div>
+    )}
     {cookieToast && (
       <div className="sync-toast sync-toast--success" style={{ pointerEvents: 'none' }}>
         <svg className="sync-toast-icon" width="16" height="16" viewBox="0 0 16 16" fill="none">
@@ -2555,6 +3079,28 @@ export default function App() {
         <span style={{ opacity: 0.6, fontSize: 11 }}>click to open</span>
       </div>
     )}
+    {syncTabsReceived && (
+      <div className="sync-toast sync-toast--tab-received" onClick={() => {
+        for (const t of syncTabsReceived.tabs) addTab(t.url);
+        setSyncTabsReceived(null);
+      }}>
+        <svg className="sync-toast-icon" width="16" height="16" viewBox="0 0 16 16" fill="none">
+          <path d="M2 3h12v10H2z" stroke="var(--accent)" strokeWidth="1.3"/>
+          <path d="M2 5.5h12" stroke="var(--accent)" strokeWidth="1"/>
+        </svg>
+        <span>{syncTabsReceived.tabs.length} tab{syncTabsReceived.tabs.length === 1 ? "" : "s"} from {syncTabsReceived.from_device}</span>
+        <span style={{ opacity: 0.6, fontSize: 11 }}>click to open all</span>
+      </div>
+    )}
+    {syncSinceLastSession && (
+      <div className="sync-toast sync-toast--tab-received" onClick={() => setSyncSinceLastSession(null)}>
+        <svg className="sync-toast-icon" width="16" height="16" viewBox="0 0 16 16" fill="none">
+          <path d="M4 2h8v12l-4-3-4 3z" stroke="var(--accent)" strokeWidth="1.3" strokeLinejoin="round"/>
+        </svg>
+        <span>{syncSinceLastSession}</span>
+        <span style={{ opacity: 0.6, fontSize: 11 }}>click to dismiss</span>
+      </div>
+    )}
     {vaultSavePrompt && (
       <div className="vault-save-banner" style={{ left: layoutOffset, right: 0 }}>
         <svg width="14" height="14" viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="1.5" style={{ flexShrink: 0 }}><rect x="3" y="11" width="18" height="11" rx="2"/><path d="M7 11V7a5 5 0 0110 0v4"/><circle cx="12" cy="16" r="1"/></svg>
@@ -2593,9 +3139,9 @@ export default function App() {
             if (vaultMasterModal === "setup") {
               const confirm = (form.elements.namedItem("vaultpw2") as HTMLInputElement).value;
               if (pw !== confirm) { showError("Passwords don't match"); return; }
-              await invoke("vault_setup", { masterPassword: pw }).catch((err: any) => { showError(String(err)); return; });
+              await invoke("vault_setup", { masterPassword: pw }).catch((err: any) => { showError(err?.message || String(err)); return; });
             } else {
-              await invoke("vault_unlock", { masterPassword: pw }).catch((err: any) => { showError(String(err)); return; });
+              await invoke("vault_unlock", { masterPassword: pw }).catch((err: any) => { showError(err?.message || String(err)); return; });
             }
             setVaultUnlocked(true);
             setVaultMasterModal(null);
@@ -2649,6 +3195,63 @@ export default function App() {
         }}>Allow</button>
       </div>
     )}
+    {certReq && (
+      <div className="permission-prompt cert-error-prompt">
+        <div className="permission-prompt-icon cert-error-icon">
+          <svg width="18" height="18" viewBox="0 0 24 24" fill="none"><path d="M12 2l8 4v6c0 5-3.5 9-8 10-4.5-1-8-5-8-10V6l8-4z" stroke="currentColor" strokeWidth="1.5"/><path d="M12 8v5M12 16v.5" stroke="currentColor" strokeWidth="1.5" strokeLinecap="round"/></svg>
+        </div>
+        <div className="permission-prompt-body">
+          <span className="permission-prompt-domain">{certReq.host}</span>
+          <span className="permission-prompt-text" title={`${certReq.subject}\nIssuer: ${certReq.issuer}\nSHA-256: ${certReq.thumbprint}`}>{certReq.reason}</span>
+        </div>
+        <label className="permission-prompt-remember">
+          <input type="checkbox" checked={certRemember} onChange={e => setCertRemember(e.target.checked)} />
+          Trust this certificate
+        </label>
+        <button className="permission-btn allow" onClick={() => {
+          invoke("respond_cert_error", { requestId: certReq.requestId, proceed: false, remember: false });
+          setCertReq(null);
+        }}>Go back</button>
+        <button className="permission-btn deny" onClick={() => {
+          invoke("respond_cert_error", { requestId: certReq.requestId, proceed: true, remember: certRemember });
+          setCertReq(null);
+        }}>Proceed anyway</button>
+      </div>
+    )}
+    {authReq && (
+      <form className="permission-prompt basic-auth-prompt" onSubmit={(e) => {
+        e.preventDefault();
+        const form = e.target as HTMLFormElement;
+        const username = (form.elements.namedItem("authuser") as HTMLInputElement).value;
+        const password = (form.elements.namedItem("authpass") as HTMLInputElement).value;
+        invoke("respond_basic_auth", { requestId: authReq.requestId, username, password, cancel: false })
+          .catch((err: any) => showError(err?.message || String(err)));
+        setAuthReq(null);
+      }}>
+        <div className="permission-prompt-body">
+          <span className="permission-prompt-domain">{authReq.host}</span>
+          <span className="permission-prompt-text">{authReq.realm ? `Sign in to "${authReq.realm}"` : "Sign in required"}</span>
+        </div>
+        {authReq.vaultEntries.length > 0 && (
+          <select className="basic-auth-vault" defaultValue="" onChange={e => {
+            if (!e.target.value) return;
+            invoke("respond_basic_auth", { requestId: authReq.requestId, username: "", password: "", cancel: false, vaultEntryId: e.target.value })
+              .catch((err: any) => showError(err?.message || String(err)));
+            setAuthReq(null);
+          }}>
+            <option value="">From vault…</option>
+            {authReq.vaultEntries.map(v => <option key={v.id} value={v.id}>{v.username}</option>)}
+          </select>
+        )}
+        <input name="authuser" className="basic-auth-input" placeholder="Username" autoFocus autoComplete="off" />
+        <input name="authpass" className="basic-auth-input" type="password" placeholder="Password" autoComplete="off" />
+        <button type="button" className="permission-btn deny" onClick={() => {
+          invoke("respond_basic_auth", { requestId: authReq.requestId, username: "", password: "", cancel: true });
+          setAuthReq(null);
+        }}>Cancel</button>
+        <button type="submit" className="permission-btn allow">Sign in</button>
+      </form>
+    )}
     {pageCtx && (
       <div className="ctx-overlay" onClick={() => setPageCtx(null)} onKeyDown={(e) => {
         if (e.key === "Escape") { setPageCtx(null); return; }
@@ -2664,9 +3267,12 @@ export default function App() {
         <div ref={pageCtxRef} className="ctx-menu page-ctx" style={{ top: pageCtxPos.top, left: pageCtxPos.left }}>
           {pageCtx.linkUri && (
             <>
-              <div className="ctx-item" tabIndex={0} onClick={() => { if (isSafeUrl(pageCtx.linkUri)) addTab(pageCtx.linkUri); setPageCtx(null); }}>
+              <div className="ctx-item" tabIndex={0} onClick={() => { if (isSafeUrl(pageCtx.linkUri)) openOrSwitchTab(pageCtx.linkUri); setPageCtx(null); }}>
                 Open link in new tab
               </div>
+              <div className="ctx-item" tabIndex={0} onClick={() => { saveFromContext(pageCtx.tabId, pageCtx.linkUri); setPageCtx(null); }}>
+                Save link as...
+              </div>
               <div className="ctx-item" tabIndex={0} onClick={() => { invoke("copy_text_to_clipboard", { text: pageCtx.linkUri }); setPageCtx(null); }}>
                 Copy link address
               </div>
@@ -2680,9 +3286,7 @@ export default function App() {
               </div>
               <div className="ctx-item" tabIndex={0} onClick={() => {
                 const imgUrl = pageCtx.sourceUri;
-                const filename = imgUrl.split("/").pop()?.split("?")[0] || "image.png";
-                const sr = settingsRef.current;
-                invoke("start_download", { url: imgUrl, filename, downloadDir: sr.downloadLocation || "", cookies: null, mimeRouting: sr.mimeRouting || null });
+                saveFromContext(pageCtx.tabId, imgUrl, imgUrl.split("/").pop()?.split("?")[0] || "image.png");
                 setPageCtx(null);
               }}>
                 Save image as...
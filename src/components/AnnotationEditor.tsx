@@ -1,14 +1,17 @@
 import { useState, useRef, useCallback, useEffect } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { BushidoSettings } from "../types";
 
 type Tool = "pen" | "arrow" | "rect" | "highlight" | "blur" | "text" | "eraser";
 
 interface Props {
   imageData: string; // base64 PNG
   onClose: () => void;
+  saveFormat: BushidoSettings["screenshotFormat"];
+  saveQuality: number;
 }
 
-export default function AnnotationEditor({ imageData, onClose }: Props) {
+export default function AnnotationEditor({ imageData, onClose, saveFormat, saveQuality }: Props) {
   const [tool, setTool] = useState<Tool>("pen");
   const [color, setColor] = useState("#ef4444");
   const [thickness, setThickness] = useState(3);
@@ -332,9 +335,9 @@ export default function AnnotationEditor({ imageData, onClose }: Props) {
     const b64 = exportImage();
     if (!b64) return;
     setSaving(true);
-    try { await invoke("save_screenshot", { data: b64, suggestedName: "" }); } catch { /* ignore */ }
+    try { await invoke("save_screenshot", { data: b64, suggestedName: "", format: saveFormat, quality: saveFormat === "jpeg" ? saveQuality : null }); } catch { /* ignore */ }
     setSaving(false);
-  }, [exportImage]);
+  }, [exportImage, saveFormat, saveQuality]);
 
   // Shape preview SVG overlay
   const renderPreview = () => {
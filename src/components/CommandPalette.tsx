@@ -22,6 +22,7 @@ interface Props {
 
 const ACTIONS: CmdResult[] = [
   { id: "action-new-tab", type: "action", title: "New Tab", subtitle: "Ctrl+T" },
+  { id: "action-new-private-tab", type: "action", title: "New Private Tab", subtitle: "Nothing is saved once it's closed" },
   { id: "action-close-tab", type: "action", title: "Close Tab", subtitle: "Ctrl+W" },
   { id: "action-settings", type: "action", title: "Settings", subtitle: "" },
   { id: "action-toggle-compact", type: "action", title: "Toggle Compact Mode", subtitle: "Ctrl+Shift+B" },
@@ -31,6 +32,31 @@ const ACTIONS: CmdResult[] = [
   { id: "action-bookmark", type: "action", title: "Bookmark Page", subtitle: "Ctrl+D" },
   { id: "action-clear-history", type: "action", title: "Clear All History", subtitle: "" },
   { id: "action-screenshot", type: "action", title: "Screenshot", subtitle: "Ctrl+Shift+S" },
+  { id: "action-site-privacy-signals", type: "action", title: "Toggle Do Not Track / GPC For This Site", subtitle: "" },
+  { id: "action-clear-site-data", type: "action", title: "Clear Cookies And Site Data For This Site", subtitle: "" },
+  { id: "action-toggle-offline", type: "action", title: "Toggle Work Offline", subtitle: "" },
+  { id: "action-link-hints", type: "action", title: "Click Links With The Keyboard", subtitle: "hints" },
+  { id: "action-toggle-blocking", type: "action", title: "Pause/Resume Ad Blocking Everywhere", subtitle: "toggle" },
+  { id: "action-home-tab", type: "action", title: "Open Home Page In New Tab", subtitle: "" },
+  { id: "action-move-to-window", type: "action", title: "Move Tab To New Window", subtitle: "" },
+  { id: "action-keep-running", type: "action", title: "Keep This Tab Running In The Background", subtitle: "toggle" },
+  { id: "action-allow-autoplay", type: "action", title: "Allow Autoplay On This Site", subtitle: "media" },
+  { id: "action-unhide-element", type: "action", title: "Unhide An Element On This Page", subtitle: "pick" },
+  { id: "action-toggle-images", type: "action", title: "Block/Allow Images In This Tab", subtitle: "low bandwidth" },
+  { id: "action-toggle-injection-bypass", type: "action", title: "Reload Tab Without Bushido Scripts", subtitle: "toggle" },
+  { id: "action-toggle-network-capture", type: "action", title: "Record Network Requests For This Tab", subtitle: "toggle" },
+  { id: "action-export-network", type: "action", title: "Export Recorded Network Requests (HAR)", subtitle: "" },
+  { id: "action-export-reader-markdown", type: "action", title: "Save Reader View As Markdown", subtitle: "downloads" },
+  { id: "action-reader-speak", type: "action", title: "Read Reader View Aloud", subtitle: "text to speech" },
+  { id: "action-reader-stop", type: "action", title: "Stop Reading Aloud", subtitle: "text to speech" },
+  { id: "action-tab-theme-dark", type: "action", title: "Force Dark Color Scheme For This Tab", subtitle: "" },
+  { id: "action-tab-theme-light", type: "action", title: "Force Light Color Scheme For This Tab", subtitle: "" },
+  { id: "action-tab-theme-auto", type: "action", title: "Reset Color Scheme For This Tab", subtitle: "" },
+  { id: "action-site-ua-desktop", type: "action", title: "Request Desktop Site For This Domain", subtitle: "user agent" },
+  { id: "action-site-ua-mobile", type: "action", title: "Request Mobile Site For This Domain", subtitle: "user agent" },
+  { id: "action-site-ua-reset", type: "action", title: "Reset User Agent For This Domain", subtitle: "user agent" },
+  { id: "action-save-layout", type: "action", title: "Save Window Layout", subtitle: "" },
+  { id: "action-restore-layout", type: "action", title: "Restore Saved Window Layout", subtitle: "" },
 ];
 
 function fuzzyScore(query: string, text: string): number {
@@ -10,6 +10,7 @@ interface Props {
   onOpen: (id: string) => void;
   onOpenFolder: (id: string) => void;
   onClearCompleted: () => void;
+  onExport: () => void;
   onClose: () => void;
   onRetry: (id: string) => void;
 }
@@ -26,19 +27,17 @@ function formatSpeed(bps: number): string {
   return `${formatBytes(bps)}/s`;
 }
 
-function formatEta(total: number | null, received: number, speed: number): string {
-  if (!total || speed === 0) return "";
-  const remaining = total - received;
-  const secs = Math.ceil(remaining / speed);
-  if (secs < 60) return `${secs}s`;
-  if (secs < 3600) return `${Math.floor(secs / 60)}m ${secs % 60}s`;
-  return `${Math.floor(secs / 3600)}h ${Math.floor((secs % 3600) / 60)}m`;
+function formatEta(secs: number | null): string {
+  if (secs === null) return "";
+  if (secs < 60) return `${secs} s left`;
+  if (secs < 3600) return `${Math.round(secs / 60)} min left`;
+  return `${Math.floor(secs / 3600)} h ${Math.floor((secs % 3600) / 60)} min left`;
 }
 
 export default memo(function DownloadPanel({
-  downloads, onPause, onResume, onCancel, onOpen, onOpenFolder, onClearCompleted, onClose, onRetry,
+  downloads, onPause, onResume, onCancel, onOpen, onOpenFolder, onClearCompleted, onExport, onClose, onRetry,
 }: Props) {
-  const hasCompleted = downloads.some(d => d.state === "completed");
+  const hasCompleted = downloads.some(d => d.state === "completed" || d.state === "failed");
   const [dragIdx, setDragIdx] = useState<number | null>(null);
   const [dropIdx, setDropIdx] = useState<number | null>(null);
   const dragStartY = useRef(0);
@@ -102,6 +101,9 @@ export default memo(function DownloadPanel({
           </svg>
         </button>
         <span className="download-title">downloads</span>
+        {sorted.length > 0 && (
+          <button className="download-clear-btn" onClick={onExport}>export</button>
+        )}
         {hasCompleted && (
           <button className="download-clear-btn" onClick={onClearCompleted}>clear</button>
         )}
@@ -123,11 +125,14 @@ export default memo(function DownloadPanel({
                 {d.state === "downloading" && (
                   <>
                     <span>{formatBytes(d.receivedBytes)}{d.totalBytes ? ` / ${formatBytes(d.totalBytes)}` : ""}</span>
-                    {d.speed > 0 && <span className="download-speed">{formatSpeed(d.speed)}</span>}
+                    {d.speedBps > 0 && <span className="download-speed">{formatSpeed(d.speedBps)}</span>}
                     {d.segments > 1 && <span className="download-segments">{d.segments}x</span>}
-                    {d.speed > 0 && <span className="download-eta">{formatEta(d.totalBytes, d.receivedBytes, d.speed)}</span>}
+                    {d.etaSecs !== null && <span className="download-eta">· {formatEta(d.etaSecs)}</span>}
                   </>
                 )}
+                {d.state === "queued" && (
+                  <span className="download-paused-label">queued{d.receivedBytes > 0 ? ` — ${formatBytes(d.receivedBytes)}` : ""}</span>
+                )}
                 {d.state === "paused" && (
                   <span className="download-paused-label">paused — {formatBytes(d.receivedBytes)}{d.totalBytes ? ` / ${formatBytes(d.totalBytes)}` : ""}</span>
                 )}
@@ -150,7 +155,7 @@ export default memo(function DownloadPanel({
             )}
 
             <div className="download-actions">
-              {d.state === "downloading" && (
+              {(d.state === "downloading" || d.state === "queued") && (
                 <>
                   <button className="download-action-btn" onClick={() => onPause(d.id)} title="pause">
                     <svg width="12" height="12" viewBox="0 0 12 12" fill="none">
@@ -18,21 +18,18 @@ export default memo(function FindBar({ tabId, onClose }: Props) {
   }, []);
 
   useEffect(() => {
-    const p = listen<{ id: string; count: number }>("match-count", (e) => {
-      if (e.payload.id === tabId) setMatchCount(e.payload.count);
+    const p = listen<{ id: string; count: number; current: number }>("match-count", (e) => {
+      if (e.payload.id !== tabId) return;
+      setMatchCount(e.payload.count);
+      setActiveIdx(e.payload.current);
     });
     return () => { p.then(u => u()); };
   }, [tabId]);
 
+  // the page owns the current match; the index comes back with match-count
   const find = useCallback((forward = true) => {
     invoke("find_in_page", { id: tabId, query, forward });
-    if (matchCount && matchCount > 0) {
-      setActiveIdx(prev => {
-        if (forward) return prev >= matchCount ? 1 : prev + 1;
-        return prev <= 1 ? matchCount : prev - 1;
-      });
-    }
-  }, [tabId, query, matchCount]);
+  }, [tabId, query]);
 
   const close = useCallback(() => {
     invoke("find_in_page", { id: tabId, query: "", forward: true });
@@ -41,14 +38,16 @@ export default memo(function FindBar({ tabId, onClose }: Props) {
     onClose();
   }, [tabId, onClose]);
 
-  // reset index when query changes
+  // emptying the box drops the highlights too
   useEffect(() => {
-    if (!query) { setMatchCount(null); setActiveIdx(0); }
-    else setActiveIdx(1);
-  }, [query]);
+    if (query) return;
+    setMatchCount(null);
+    setActiveIdx(0);
+    invoke("find_in_page", { id: tabId, query: "", forward: true });
+  }, [query, tabId]);
 
   const countLabel = matchCount !== null
-    ? (matchCount > 0 ? `${Math.min(activeIdx, matchCount)} of ${matchCount}` : "no matches")
+    ? (matchCount > 0 ? `${activeIdx} of ${matchCount}` : "no matches")
     : null;
 
   return (
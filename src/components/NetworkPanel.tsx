@@ -1,11 +1,14 @@
 import { memo, useState, useMemo, useCallback } from "react";
 
 export interface NetworkEntry {
+  method: string;
   url: string;
-  resource_type: string;
+  resourceType: string;
+  status: number | null;
+  startedMs: number;
+  durationMs: number | null;
   blocked: boolean;
-  filter_rule: string | null;
-  timestamp_ms: number;
+  blockedBy: string | null;
 }
 
 interface Props {
@@ -52,7 +55,7 @@ export default memo(function NetworkPanel({ entries, onClose, onClear }: Props)
     let result = entries;
     if (filter !== "all") {
       const types = FILTER_TYPES[filter];
-      result = result.filter(e => types.includes(e.resource_type));
+      result = result.filter(e => types.includes(e.resourceType));
     }
     if (search) {
       const q = search.toLowerCase();
@@ -121,10 +124,10 @@ export default memo(function NetworkPanel({ entries, onClose, onClear }: Props)
             key={i}
             className={`history-entry${e.blocked ? " network-blocked" : ""}`}
             onClick={() => copyUrl(e.url, i)}
-            title={e.blocked && e.filter_rule ? `blocked: ${e.filter_rule}` : e.url}
+            title={e.blocked && e.blockedBy ? `blocked: ${e.blockedBy}` : e.url}
           >
-            <span className={`network-tag ${e.blocked ? "blocked" : e.resource_type}`}>
-              {e.blocked ? "x" : (TYPE_LABELS[e.resource_type] || e.resource_type)}
+            <span className={`network-tag ${e.blocked ? "blocked" : e.resourceType}`}>
+              {e.blocked ? "x" : (TYPE_LABELS[e.resourceType] || e.resourceType)}
             </span>
             <div className="history-entry-text">
               <span className="history-entry-title">
@@ -51,7 +51,7 @@ export default memo(function PairingWizard({ mode, peerDeviceId, peerDeviceName,
     try {
       await invoke("enter_pairing_code", { peerId: peerDeviceId, code: inputCode });
     } catch (e: any) {
-      setErrorMsg(String(e));
+      setErrorMsg(e?.message || String(e));
       setStep("error");
     }
   }, [inputCode, peerDeviceId]);
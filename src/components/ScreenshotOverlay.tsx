@@ -1,5 +1,6 @@
 import { useState, useRef, useCallback, useEffect } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { BushidoSettings } from "../types";
 
 type Phase = "pick" | "selecting" | "capturing" | "result";
 
@@ -10,15 +11,19 @@ interface Props {
   onClose: () => void;
   onAnnotate: (data: string) => void;
   onRestoreWebview: () => void;
+  saveFormat: BushidoSettings["screenshotFormat"];
+  saveQuality: number;
 }
 
-export default function ScreenshotOverlay({ tabId, tabUrl, preview, onClose, onAnnotate, onRestoreWebview }: Props) {
+export default function ScreenshotOverlay({ tabId, tabUrl, preview, onClose, onAnnotate, onRestoreWebview, saveFormat, saveQuality }: Props) {
   const [phase, setPhase] = useState<Phase>("pick");
   const [resultData, setResultData] = useState("");
   const [error, setError] = useState("");
   const [saving, setSaving] = useState(false);
   const [copied, setCopied] = useState(false);
   const [saved, setSaved] = useState("");
+  // element mode: null until picked, then the CSS selector being typed
+  const [selector, setSelector] = useState<string | null>(null);
 
   // Area select — everything via canvas, no <img> coordinate issues
   const canvasRef = useRef<HTMLCanvasElement>(null);
@@ -190,6 +195,25 @@ export default function ScreenshotOverlay({ tabId, tabUrl, preview, onClose, onA
     }
   }, [tabId, onRestoreWebview]);
 
+  const captureElement = useCallback(async () => {
+    const sel = selector?.trim();
+    if (!sel) return;
+    setPhase("capturing");
+    setError("");
+    try {
+      onRestoreWebview();
+      await new Promise(r => setTimeout(r, 200));
+      const b64: string = await invoke("capture_element", { id: tabId, selector: sel });
+      invoke("layout_webviews", { panes: [], focusedTabId: tabId, sidebarW: 0, topOffset: 0 });
+      setResultData(b64);
+      setPhase("result");
+    } catch (e: any) {
+      invoke("layout_webviews", { panes: [], focusedTabId: tabId, sidebarW: 0, topOffset: 0 });
+      setError(e?.toString() || "Element capture failed");
+      setPhase("pick");
+    }
+  }, [tabId, selector, onRestoreWebview]);
+
   const copyToClipboard = useCallback(async () => {
     if (!resultData) return;
     setCopied(false);
@@ -204,11 +228,11 @@ export default function ScreenshotOverlay({ tabId, tabUrl, preview, onClose, onA
     if (!resultData) return;
     setSaving(true); setSaved("");
     try {
-      const path: string = await invoke("save_screenshot", { data: resultData, suggestedName: "" });
+      const path: string = await invoke("save_screenshot", { data: resultData, suggestedName: "", format: saveFormat, quality: saveFormat === "jpeg" ? saveQuality : null });
       setSaved(path);
     } catch (e: any) { setError(e?.toString() || "Save failed"); }
     setSaving(false);
-  }, [resultData]);
+  }, [resultData, saveFormat, saveQuality]);
 
   return (
     <div className="screenshot-overlay">
@@ -244,7 +268,28 @@ export default function ScreenshotOverlay({ tabId, tabUrl, preview, onClose, onA
                 </svg>
                 <span>Full page</span>
               </button>
+              <button className="ss-mode-btn" onClick={() => setSelector(selector ?? "")}>
+                <svg width="24" height="24" viewBox="0 0 24 24" fill="none">
+                  <path d="M8 6L3 12L8 18" stroke="currentColor" strokeWidth="2" strokeLinecap="round" strokeLinejoin="round"/>
+                  <path d="M16 6L21 12L16 18" stroke="currentColor" strokeWidth="2" strokeLinecap="round" strokeLinejoin="round"/>
+                </svg>
+                <span>Element</span>
+              </button>
             </div>
+            {selector !== null && (
+              <div className="ss-selector-row">
+                <input
+                  className="ss-selector-input"
+                  autoFocus
+                  spellCheck={false}
+                  placeholder="CSS selector, e.g. #chart or table.results"
+                  value={selector}
+                  onChange={e => setSelector(e.target.value)}
+                  onKeyDown={e => { if (e.key === "Enter") captureElement(); }}
+                />
+                <button className="ss-btn ss-btn-primary" onClick={captureElement} disabled={!selector.trim()}>Capture</button>
+              </div>
+            )}
             <div className="ss-mode-hint">Ctrl+Shift+S</div>
           </div>
         </div>
@@ -2,13 +2,15 @@ import { memo, useCallback, useState, useEffect } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
 import { getVersion } from "@tauri-apps/api/app";
-import { BushidoSettings, DEFAULT_SETTINGS, VaultEntry } from "../types";
+import { BushidoSettings, DEFAULT_SETTINGS, FingerprintConfig, VaultEntry } from "../types";
 import { useUiStore } from "../store/uiStore";
 import PairingWizard from "./PairingWizard";
 
 interface ImportedBookmark { title: string; url: string; folder: string; }
 interface ImportedHistory { title: string; url: string; visit_count: number; last_visit: number; }
 interface BrowserInfo { name: string; has_bookmarks: boolean; has_history: boolean; }
+interface RedirectRule { id: string; host: string; action: { type: "replaceHost"; to: string } | { type: "forceHttps" }; enabled: boolean; }
+interface RedirectConfig { rules: RedirectRule[]; stripTracking: boolean; }
 
 interface Props {
   settings: BushidoSettings;
@@ -37,6 +39,57 @@ const BANDWIDTH_OPTIONS: { value: number; label: string }[] = [
   { value: 10 * 1024 * 1024, label: "10 MB/s" },
 ];
 
+const COMPRESS_THRESHOLD_OPTIONS: { value: number; label: string }[] = [
+  { value: 128, label: "128 bytes" },
+  { value: 512, label: "512 bytes" },
+  { value: 4096, label: "4 KB" },
+  { value: 65536, label: "64 KB" },
+];
+
+const HIBERNATE_OPTIONS: { value: number; label: string }[] = [
+  { value: 0, label: "Off" },
+  { value: 2048, label: "Above 2 GB" },
+  { value: 4096, label: "Above 4 GB" },
+  { value: 6144, label: "Above 6 GB" },
+  { value: 8192, label: "Above 8 GB" },
+];
+
+const CRASH_RECOVERY_OPTIONS: { value: number; label: string }[] = [
+  { value: 0, label: "Off" },
+  { value: 1, label: "Once a minute" },
+  { value: 3, label: "Up to 3 times a minute" },
+  { value: 5, label: "Up to 5 times a minute" },
+];
+
+const VAULT_AUTOLOCK_OPTIONS: { value: number; label: string }[] = [
+  { value: 0, label: "Never" },
+  { value: 1, label: "1 minute" },
+  { value: 5, label: "5 minutes" },
+  { value: 15, label: "15 minutes" },
+  { value: 30, label: "30 minutes" },
+  { value: 60, label: "1 hour" },
+];
+
+const SCREENSHOT_FORMAT_OPTIONS: { value: string; label: string }[] = [
+  { value: "png", label: "PNG (lossless)" },
+  { value: "webp", label: "WebP (lossless)" },
+  { value: "jpeg", label: "JPEG" },
+];
+
+const JPEG_QUALITY_OPTIONS: { value: number; label: string }[] =
+  [60, 75, 90, 100].map(q => ({ value: q, label: `${q}%` }));
+
+const CONCURRENT_DOWNLOAD_OPTIONS: { value: number; label: string }[] =
+  [1, 2, 3, 4, 6, 10].map(n => ({ value: n, label: String(n) }));
+
+const HISTORY_RETENTION_OPTIONS: { value: number; label: string }[] = [
+  { value: 0, label: "Forever" },
+  { value: 7, label: "1 week" },
+  { value: 30, label: "30 days" },
+  { value: 90, label: "90 days" },
+  { value: 365, label: "1 year" },
+];
+
 const SUSPEND_OPTIONS: { value: number; label: string }[] = [
   { value: 5, label: "5 minutes" },
   { value: 10, label: "10 minutes" },
@@ -45,6 +98,14 @@ const SUSPEND_OPTIONS: { value: number; label: string }[] = [
   { value: 0, label: "Never" },
 ];
 
+const MAX_TABS_OPTIONS: { value: number; label: string }[] = [
+  { value: 25, label: "25 tabs" },
+  { value: 50, label: "50 tabs" },
+  { value: 100, label: "100 tabs" },
+  { value: 200, label: "200 tabs" },
+  { value: 500, label: "500 tabs" },
+];
+
 const ZOOM_OPTIONS: { value: number; label: string }[] = [
   { value: 80, label: "80%" },
   { value: 90, label: "90%" },
@@ -97,6 +158,15 @@ const SHORTCUT_GROUPS: { group: string; items: { action: string; desc: string }[
   ]},
 ];
 
+// unix seconds → "just now" / "5 min ago" / "3 h ago" / "2 d ago"
+function formatSyncedAgo(secs: number): string {
+  const diff = Math.max(0, Math.floor(Date.now() / 1000 - secs));
+  if (diff < 60) return "just now";
+  if (diff < 3600) return `${Math.floor(diff / 60)} min ago`;
+  if (diff < 86400) return `${Math.floor(diff / 3600)} h ago`;
+  return `${Math.floor(diff / 86400)} d ago`;
+}
+
 /** Build a combo string from a KeyboardEvent, e.g. "Ctrl+Shift+T" */
 function comboFromEvent(e: KeyboardEvent): string | null {
   // Ignore lone modifier keys
@@ -172,7 +242,34 @@ const ACCENT_COLORS = [
 
 const SECURITY_KEYS: (keyof BushidoSettings)[] = [
   "disableDevTools", "disableStatusBar", "disableAutofill", "disablePasswordSave",
-  "blockServiceWorkers", "blockFontEnumeration", "spoofHardwareConcurrency",
+  "blockServiceWorkers", "blockFontEnumeration", "fingerprint",
+];
+
+const FINGERPRINT_TOGGLES: { key: Exclude<keyof FingerprintConfig, "language">; label: string; hint: string }[] = [
+  { key: "canvasNoise", label: "Canvas noise", hint: "Adds invisible noise to canvas reads" },
+  { key: "webglVendor", label: "Mask graphics card", hint: "Reports a common GPU instead of yours" },
+  { key: "audioNoise", label: "Audio noise", hint: "Perturbs audio processing output" },
+  { key: "timezone", label: "Spoof timezone", hint: "Reports America/New_York (breaks local times on some sites)" },
+  { key: "screen", label: "Normalize screen size", hint: "Reports a 1920×1080 display" },
+  { key: "hardwareConcurrency", label: "Spoof CPU core count", hint: "Reports 8 cores instead of real count" },
+];
+
+const FINGERPRINT_LANGUAGES: { value: string; label: string }[] = [
+  { value: "en-US", label: "English (US)" },
+  { value: "en-GB", label: "English (UK)" },
+  { value: "de-DE", label: "German" },
+  { value: "fr-FR", label: "French" },
+  { value: "es-ES", label: "Spanish" },
+  { value: "pt-BR", label: "Portuguese (Brazil)" },
+  { value: "ja-JP", label: "Japanese" },
+];
+
+const RESET_SUBSYSTEMS = [
+  { id: "settings", label: "Settings", hint: "All preferences except keyboard shortcuts" },
+  { id: "keybindings", label: "Keyboard shortcuts", hint: "Re-registers the default global shortcuts" },
+  { id: "permissions", label: "Site permissions", hint: "Sites will ask again for camera, location, etc." },
+  { id: "whitelist", label: "Ad blocker whitelist", hint: "Blocking is re-enabled on every site" },
+  { id: "blocker-lists", label: "Filter lists", hint: "Discards downloaded lists and rebuilds from the bundled ones" },
 ];
 
 interface SyncPeer {
@@ -190,7 +287,7 @@ interface SyncInfo {
   fingerprint: string;
   status: string | { Error: { message: string } };
   peers: SyncPeer[];
-  paired_devices: { device_id: string; name: string; fingerprint: string; paired_at: number }[];
+  paired_devices: { device_id: string; name: string; fingerprint: string; paired_at: number; last_synced_at: number | null }[];
 }
 
 export default memo(function SettingsPage({ settings, onUpdate, onReloadAllTabs, onThemeChange, onOpenUrl, onImportBookmarks, onImportHistory }: Props) {
@@ -205,7 +302,7 @@ export default memo(function SettingsPage({ settings, onUpdate, onReloadAllTabs,
     code?: string;
   } | null>(null);
   const [simulateCode, setSimulateCode] = useState<string | null>(null);
-  const syncTypes = settings.syncDataTypes || { bookmarks: true, history: true, settings: true, tabs: true };
+  const syncTypes = settings.syncDataTypes || { bookmarks: true, history: true, settings: true, tabs: true, readingList: true };
   const setSyncTypes = (next: typeof syncTypes) => set("syncDataTypes", next);
   const [recordingAction, setRecordingAction] = useState<string | null>(null);
   const [vaultEntries, setVaultEntries] = useState<VaultEntry[]>([]);
@@ -215,6 +312,8 @@ export default memo(function SettingsPage({ settings, onUpdate, onReloadAllTabs,
   const [vaultGenLen, setVaultGenLen] = useState(20);
   const [vaultGenResult, setVaultGenResult] = useState("");
   const [vaultHasMaster, setVaultHasMaster] = useState(false);
+  const [vaultBackupPw, setVaultBackupPw] = useState("");
+  const [vaultBackupStatus, setVaultBackupStatus] = useState<string | null>(null);
   const [importBrowsers, setImportBrowsers] = useState<BrowserInfo[]>([]);
   const [importSelected, setImportSelected] = useState<string | null>(null);
   const [importStatus, setImportStatus] = useState<string | null>(null);
@@ -306,6 +405,27 @@ export default memo(function SettingsPage({ settings, onUpdate, onReloadAllTabs,
     if (SECURITY_KEYS.includes(key)) setSecurityDirty(true);
   }, [onUpdate]);
 
+  // the resolver is checked by the backend before it's saved
+  const [dohDraft, setDohDraft] = useState<string | null>(null);
+  const applyDoh = useCallback((enabled: boolean, template: string) => {
+    invoke<string>("set_doh_resolver", { enabled, template })
+      .then(t => { onUpdate({ customDoh: enabled, dohTemplate: t || template }); setDohDraft(null); })
+      .catch(e => useUiStore.getState().showError(e?.message || String(e)));
+  }, [onUpdate]);
+
+  // the backend falls back to the built-in page for anything it can't open
+  const [newTabDraft, setNewTabDraft] = useState<string | null>(null);
+  const applyNewTabUrl = useCallback((url: string) => {
+    invoke<string>("set_new_tab_url", { url })
+      .then(u => { onUpdate({ newTabUrl: u === "bushido://newtab" ? "" : u }); setNewTabDraft(null); })
+      .catch(e => useUiStore.getState().showError(e?.message || String(e)));
+  }, [onUpdate]);
+
+  const setFingerprint = useCallback((next: FingerprintConfig) => {
+    set("fingerprint", next);
+    invoke("set_fingerprint_config", { config: next }).catch(e => console.warn("[bushido]", e));
+  }, [set]);
+
   const renderGeneral = () => (
     <section className="settings-section">
       <h2 className="settings-section-title">General</h2>
@@ -471,6 +591,20 @@ export default memo(function SettingsPage({ settings, onUpdate, onReloadAllTabs,
   const renderNewTab = () => (
     <section className="settings-section">
       <h2 className="settings-section-title">New Tab</h2>
+      <div className="settings-row">
+        <div className="settings-label">
+          <span>New tabs open</span>
+          <span className="settings-hint">A web address, or leave empty for this page</span>
+        </div>
+        <input
+          className="settings-input"
+          value={newTabDraft ?? settings.newTabUrl}
+          onChange={e => setNewTabDraft(e.target.value)}
+          onBlur={() => { if (newTabDraft !== null) applyNewTabUrl(newTabDraft); }}
+          placeholder="bushido://newtab"
+          spellCheck={false}
+        />
+      </div>
       <div className="settings-row">
         <div className="settings-label"><span>Show top sites</span></div>
         <Toggle checked={settings.showTopSites} onChange={v => set("showTopSites", v)} />
@@ -533,6 +667,41 @@ export default memo(function SettingsPage({ settings, onUpdate, onReloadAllTabs,
           }}
         />
       </div>
+      <div className="settings-row">
+        <div className="settings-label">
+          <span>Simultaneous downloads</span>
+          <span className="settings-hint">Extra downloads wait in a queue, in list order</span>
+        </div>
+        <Select
+          value={settings.maxConcurrentDownloads}
+          options={CONCURRENT_DOWNLOAD_OPTIONS}
+          onChange={(v: number) => {
+            set("maxConcurrentDownloads", v);
+            invoke("set_max_concurrent_downloads", { n: v });
+          }}
+        />
+      </div>
+      <div className="settings-row">
+        <div className="settings-label">
+          <span>Screenshot format</span>
+          <span className="settings-hint">WebP is lossless; JPEG is smallest but drops transparency</span>
+        </div>
+        <Select
+          value={settings.screenshotFormat}
+          options={SCREENSHOT_FORMAT_OPTIONS}
+          onChange={(v: BushidoSettings["screenshotFormat"]) => set("screenshotFormat", v)}
+        />
+      </div>
+      {settings.screenshotFormat === "jpeg" && (
+        <div className="settings-row">
+          <div className="settings-label"><span>JPEG quality</span></div>
+          <Select
+            value={settings.screenshotQuality}
+            options={JPEG_QUALITY_OPTIONS}
+            onChange={(v: number) => set("screenshotQuality", v)}
+          />
+        </div>
+      )}
       <h3 className="settings-subsection-title" style={{ marginTop: 16 }}>MIME auto-sort</h3>
       <p className="settings-info-text" style={{ marginBottom: 8 }}>
         Route downloads to different folders based on file type. Leave folder empty to use the default location.
@@ -570,6 +739,56 @@ export default memo(function SettingsPage({ settings, onUpdate, onReloadAllTabs,
           onChange={(v: number) => set("suspendTimeout", v)}
         />
       </div>
+      <div className="settings-row">
+        <div className="settings-label">
+          <span>Hibernate tabs under memory pressure</span>
+          <span className="settings-hint">Suspends the longest-unused tabs when Bushido uses more than this much memory. Pinned and playing tabs are kept</span>
+        </div>
+        <Select
+          value={settings.autoHibernate ? settings.autoHibernateThresholdMb : 0}
+          options={HIBERNATE_OPTIONS}
+          onChange={(v: number) => {
+            set("autoHibernate", v > 0);
+            if (v > 0) set("autoHibernateThresholdMb", v);
+            invoke("set_auto_hibernate", { enabled: v > 0, thresholdMb: v || settings.autoHibernateThresholdMb }).catch(e => console.warn("[bushido]", e));
+          }}
+        />
+      </div>
+      <div className="settings-row">
+        <div className="settings-label">
+          <span>Reload crashed tabs</span>
+          <span className="settings-hint">Reloads a tab whose page crashed, waiting longer after each try. A tab that keeps crashing is left for you to reload</span>
+        </div>
+        <Select
+          value={settings.crashRecoveryRetries}
+          options={CRASH_RECOVERY_OPTIONS}
+          onChange={(v: number) => {
+            set("crashRecoveryRetries", v);
+            invoke("set_crash_recovery", { enabled: v > 0, maxRetries: v || null }).catch(e => console.warn("[bushido]", e));
+          }}
+        />
+      </div>
+      <div className="settings-row">
+        <div className="settings-label">
+          <span>Maximum open tabs</span>
+          <span className="settings-hint">Each tab is a separate page process — raise this only on machines with plenty of memory</span>
+        </div>
+        <Select
+          value={settings.maxTabs}
+          options={MAX_TABS_OPTIONS}
+          onChange={(v: number) => {
+            set("maxTabs", v);
+            invoke("set_tab_limit", { limit: v });
+          }}
+        />
+      </div>
+      <div className="settings-row">
+        <div className="settings-label">
+          <span>Switch to already open tabs</span>
+          <span className="settings-hint">Opening a link that's already open in another tab jumps to that tab instead</span>
+        </div>
+        <Toggle checked={settings.switchToExistingTab} onChange={v => set("switchToExistingTab", v)} />
+      </div>
       <div className="settings-row">
         <div className="settings-label">
           <span>Suspend excluded URLs</span>
@@ -601,6 +820,56 @@ export default memo(function SettingsPage({ settings, onUpdate, onReloadAllTabs,
     </section>
   );
 
+  const [redirects, setRedirects] = useState<RedirectConfig | null>(null);
+  const [newRedirectHost, setNewRedirectHost] = useState("");
+  const [newRedirectTo, setNewRedirectTo] = useState("");
+
+  useEffect(() => {
+    if (activeTab === "privacy" && !redirects) {
+      invoke<RedirectConfig>("get_redirect_config").then(setRedirects).catch(e => console.warn("[bushido]", e));
+    }
+  }, [activeTab, redirects]);
+
+  const addRedirect = useCallback(() => {
+    const host = newRedirectHost.trim();
+    const to = newRedirectTo.trim();
+    if (!host) return;
+    const action = to ? { type: "replaceHost", to } : { type: "forceHttps" };
+    invoke<RedirectRule>("add_redirect_rule", { host, action }).then(rule => {
+      setRedirects(prev => prev ? { ...prev, rules: [...prev.rules, rule] } : prev);
+      setNewRedirectHost("");
+      setNewRedirectTo("");
+    }).catch(e => useUiStore.getState().showError(e?.message || String(e)));
+  }, [newRedirectHost, newRedirectTo]);
+
+  const removeRedirect = useCallback((id: string) => {
+    invoke("remove_redirect_rule", { id }).then(() => {
+      setRedirects(prev => prev ? { ...prev, rules: prev.rules.filter(r => r.id !== id) } : prev);
+    }).catch(e => console.warn("[bushido]", e));
+  }, []);
+
+  const [customFilters, setCustomFilters] = useState<string[] | null>(null);
+  const [newCustomFilter, setNewCustomFilter] = useState("");
+
+  useEffect(() => {
+    if (activeTab === "privacy" && !customFilters) {
+      invoke<string[]>("list_custom_filters").then(setCustomFilters).catch(e => console.warn("[bushido]", e));
+    }
+  }, [activeTab, customFilters]);
+
+  const addCustomFilter = useCallback(() => {
+    const rule = newCustomFilter.trim();
+    if (!rule) return;
+    invoke<string[]>("add_custom_filter", { rule }).then(rules => {
+      setCustomFilters(rules);
+      setNewCustomFilter("");
+    }).catch(e => useUiStore.getState().showError(e?.message || String(e)));
+  }, [newCustomFilter]);
+
+  const removeCustomFilter = useCallback((rule: string) => {
+    invoke<string[]>("remove_custom_filter", { rule }).then(setCustomFilters).catch(e => console.warn("[bushido]", e));
+  }, []);
+
   const renderPrivacy = () => (
     <section className="settings-section">
       <h2 className="settings-section-title">Privacy</h2>
@@ -625,6 +894,16 @@ export default memo(function SettingsPage({ settings, onUpdate, onReloadAllTabs,
         </div>
         <Toggle checked={settings.cookieAutoReject} onChange={v => set("cookieAutoReject", v)} />
       </div>
+      <div className="settings-row">
+        <div className="settings-label">
+          <span>Let pages receive dropped files</span>
+          <span className="settings-hint">Off: files dropped on a page open in a new tab. On: the page gets them, e.g. for upload areas</span>
+        </div>
+        <Toggle checked={settings.dropFilesToPage} onChange={v => {
+          set("dropFilesToPage", v);
+          invoke("set_drop_interception", { block: !v }).catch(e => useUiStore.getState().showError(e?.message || String(e)));
+        }} />
+      </div>
       <div className="settings-row">
         <div className="settings-label">
           <span>Clear data on exit</span>
@@ -632,6 +911,20 @@ export default memo(function SettingsPage({ settings, onUpdate, onReloadAllTabs,
         </div>
         <Toggle checked={settings.clearDataOnExit} onChange={v => set("clearDataOnExit", v)} />
       </div>
+      <div className="settings-row">
+        <div className="settings-label">
+          <span>Keep history</span>
+          <span className="settings-hint">Older history is removed automatically</span>
+        </div>
+        <Select
+          value={settings.historyRetentionDays}
+          options={HISTORY_RETENTION_OPTIONS}
+          onChange={(v: number) => {
+            set("historyRetentionDays", v);
+            invoke("set_history_retention", { days: v }).catch(e => useUiStore.getState().showError(e?.message || String(e)));
+          }}
+        />
+      </div>
       <div className="settings-row">
         <div className="settings-label">
           <span>Block pop-ups</span>
@@ -639,6 +932,13 @@ export default memo(function SettingsPage({ settings, onUpdate, onReloadAllTabs,
         </div>
         <Toggle checked={settings.blockPopups} onChange={v => set("blockPopups", v)} />
       </div>
+      <div className="settings-row">
+        <div className="settings-label">
+          <span>Send Do Not Track and Global Privacy Control</span>
+          <span className="settings-hint">Asks sites not to sell or share your data. Many sites ignore it (applies to new tabs)</span>
+        </div>
+        <Toggle checked={settings.privacySignals} onChange={v => set("privacySignals", v)} />
+      </div>
       <div className="settings-row">
         <div className="settings-label">
           <span>Block autoplay</span>
@@ -665,6 +965,100 @@ export default memo(function SettingsPage({ settings, onUpdate, onReloadAllTabs,
           onChange={(v: BushidoSettings["dnsLevel"]) => set("dnsLevel", v)}
         />
       </div>
+      <div className="settings-row">
+        <div className="settings-label">
+          <span>Custom DNS-over-HTTPS resolver</span>
+          <span className="settings-hint">Resolve through your own DoH server instead of DNS protection, which turns off DNS-level tracker blocking (takes effect on restart)</span>
+        </div>
+        <Toggle checked={settings.customDoh} onChange={v => applyDoh(v, settings.dohTemplate)} />
+      </div>
+      {settings.customDoh && (
+        <div className="settings-row">
+          <div className="settings-label">
+            <span>Resolver URL</span>
+            <span className="settings-hint">https:// template, optionally ending in {"{?dns}"}</span>
+          </div>
+          <input
+            className="settings-input"
+            value={dohDraft ?? settings.dohTemplate}
+            onChange={e => setDohDraft(e.target.value)}
+            onBlur={() => { if (dohDraft !== null) applyDoh(true, dohDraft); }}
+            placeholder="https://dns.quad9.net/dns-query"
+            spellCheck={false}
+          />
+        </div>
+      )}
+      <div className="settings-row">
+        <div className="settings-label">
+          <span>Strip tracking parameters</span>
+          <span className="settings-hint">Remove utm_*, fbclid, gclid and similar from links before loading them</span>
+        </div>
+        <Toggle
+          checked={redirects?.stripTracking ?? true}
+          onChange={v => invoke("set_strip_tracking_params", { enabled: v }).then(() => setRedirects(prev => prev ? { ...prev, stripTracking: v } : prev))}
+        />
+      </div>
+      <h3 className="settings-subsection-title" style={{ marginTop: 16 }}>Redirect rules</h3>
+      <p className="settings-info-text" style={{ marginBottom: 8 }}>
+        Rewrite a site before it loads, e.g. reddit.com → old.reddit.com. Leave the target empty to force HTTPS for that site.
+      </p>
+      {redirects && redirects.rules.length > 0 && (
+        <div className="settings-perm-table">
+          {redirects.rules.map(r => (
+            <div key={r.id} className="settings-perm-row">
+              <span className="settings-perm-domain">{r.host}</span>
+              <span className="settings-perm-kind">{r.action.type === "replaceHost" ? `→ ${r.action.to}` : "force HTTPS"}</span>
+              <span />
+              <button className="settings-perm-revoke" onClick={() => removeRedirect(r.id)}>Remove</button>
+            </div>
+          ))}
+        </div>
+      )}
+      <div className="settings-row" style={{ gap: 8 }}>
+        <input
+          className="settings-input"
+          value={newRedirectHost}
+          onChange={e => setNewRedirectHost(e.target.value)}
+          placeholder="reddit.com"
+          spellCheck={false}
+        />
+        <input
+          className="settings-input"
+          value={newRedirectTo}
+          onChange={e => setNewRedirectTo(e.target.value)}
+          onKeyDown={e => { if (e.key === "Enter") addRedirect(); }}
+          placeholder="old.reddit.com"
+          spellCheck={false}
+        />
+        <button className="settings-perm-revoke" onClick={addRedirect}>Add</button>
+      </div>
+      <h3 className="settings-subsection-title" style={{ marginTop: 16 }}>Custom filters</h3>
+      <p className="settings-info-text" style={{ marginBottom: 8 }}>
+        Your own Adblock Plus-style rules, e.g. ||ads.example.com^ or example.com##.banner. They apply to open tabs right away.
+      </p>
+      {customFilters && customFilters.length > 0 && (
+        <div className="settings-perm-table">
+          {customFilters.map(rule => (
+            <div key={rule} className="settings-perm-row">
+              <span className="settings-perm-domain">{rule}</span>
+              <span />
+              <span />
+              <button className="settings-perm-revoke" onClick={() => removeCustomFilter(rule)}>Remove</button>
+            </div>
+          ))}
+        </div>
+      )}
+      <div className="settings-row" style={{ gap: 8 }}>
+        <input
+          className="settings-input"
+          value={newCustomFilter}
+          onChange={e => setNewCustomFilter(e.target.value)}
+          onKeyDown={e => { if (e.key === "Enter") addCustomFilter(); }}
+          placeholder="||tracker.example.com^"
+          spellCheck={false}
+        />
+        <button className="settings-perm-revoke" onClick={addCustomFilter}>Add</button>
+      </div>
     </section>
   );
 
@@ -719,12 +1113,25 @@ export default memo(function SettingsPage({ settings, onUpdate, onReloadAllTabs,
         </div>
         <Toggle checked={settings.blockFontEnumeration} onChange={v => set("blockFontEnumeration", v)} />
       </div>
+      {FINGERPRINT_TOGGLES.map(t => (
+        <div className="settings-row" key={t.key}>
+          <div className="settings-label">
+            <span>{t.label}</span>
+            <span className="settings-hint">{t.hint}</span>
+          </div>
+          <Toggle checked={settings.fingerprint[t.key]} onChange={v => setFingerprint({ ...settings.fingerprint, [t.key]: v })} />
+        </div>
+      ))}
       <div className="settings-row">
         <div className="settings-label">
-          <span>Spoof CPU core count</span>
-          <span className="settings-hint">Reports 4 cores instead of real count</span>
+          <span>Reported language</span>
+          <span className="settings-hint">Sent to sites as both navigator.language and Accept-Language</span>
         </div>
-        <Toggle checked={settings.spoofHardwareConcurrency} onChange={v => set("spoofHardwareConcurrency", v)} />
+        <Select
+          value={settings.fingerprint.language}
+          options={FINGERPRINT_LANGUAGES}
+          onChange={(v: string) => setFingerprint({ ...settings.fingerprint, language: v })}
+        />
       </div>
     </section>
   );
@@ -753,6 +1160,15 @@ export default memo(function SettingsPage({ settings, onUpdate, onReloadAllTabs,
     }
   }, [activeTab]);
 
+  // the vault can relock itself on idle while this page is open
+  useEffect(() => {
+    const un = listen("vault-locked", () => {
+      setVaultUnlocked(false);
+      setVaultEntries([]);
+    });
+    return () => { un.then(u => u()); };
+  }, []);
+
   const revokePermission = useCallback((domain: string, permission: string) => {
     invoke("revoke_permission", { domain, permission }).then(() => {
       setSavedPerms(prev => prev.filter(p => !(p.domain === domain && p.permission === permission)));
@@ -837,6 +1253,24 @@ export default memo(function SettingsPage({ settings, onUpdate, onReloadAllTabs,
           onChange={(v: "dark" | "light") => onThemeChange(settings.accentColor, v)}
         />
       </div>
+      <div className="settings-row">
+        <div className="settings-label">
+          <span>Page color scheme</span>
+          <span className="settings-hint">What sites see as your preferred color scheme. Per-site and per-tab picks override it.</span>
+        </div>
+        <Select
+          value={settings.pageColorScheme}
+          options={[
+            { value: "auto", label: "Follow system" },
+            { value: "dark", label: "Dark" },
+            { value: "light", label: "Light" },
+          ]}
+          onChange={(v: "auto" | "dark" | "light") => {
+            set("pageColorScheme", v);
+            invoke("set_default_theme", { theme: v });
+          }}
+        />
+      </div>
       <div className="settings-row">
         <div className="settings-label">
           <span>Compact mode</span>
@@ -975,6 +1409,8 @@ export default memo(function SettingsPage({ settings, onUpdate, onReloadAllTabs,
                     <span className="settings-peer-fp">{d.fingerprint}</span>
                     <span className="settings-peer-addr">
                       Paired {new Date(d.paired_at * 1000).toLocaleDateString()}
+                      {" · "}
+                      {d.last_synced_at == null ? "Never synced" : `Last synced ${formatSyncedAgo(d.last_synced_at)}`}
                     </span>
                   </div>
                   <button className="settings-remove-btn" onClick={async () => {
@@ -1025,6 +1461,42 @@ export default memo(function SettingsPage({ settings, onUpdate, onReloadAllTabs,
                 invoke("sync_set_data_types", next).catch(e => console.warn("[bushido]", e));
               }} />
             </div>
+            <div className="settings-row">
+              <div className="settings-label"><span>Sync reading list</span></div>
+              <Toggle checked={syncTypes.readingList ?? true} onChange={v => {
+                const next = { ...syncTypes, readingList: v };
+                setSyncTypes(next);
+                invoke("sync_set_data_types", next).catch(e => console.warn("[bushido]", e));
+              }} />
+            </div>
+            <div className="settings-row">
+              <div className="settings-label">
+                <span>Sync bandwidth limit</span>
+                <span className="settings-hint">Paces uploads to other devices, e.g. on a metered connection</span>
+              </div>
+              <Select
+                value={settings.syncBandwidthLimit}
+                options={BANDWIDTH_OPTIONS}
+                onChange={(v: number) => {
+                  set("syncBandwidthLimit", v);
+                  invoke("set_sync_bandwidth_limit", { bytesPerSec: v }).catch(e => console.warn("[bushido]", e));
+                }}
+              />
+            </div>
+            <div className="settings-row">
+              <div className="settings-label">
+                <span>Compress sync frames from</span>
+                <span className="settings-hint">Smaller messages are sent as-is; only used with devices that support compression</span>
+              </div>
+              <Select
+                value={settings.syncCompressThreshold}
+                options={COMPRESS_THRESHOLD_OPTIONS}
+                onChange={(v: number) => {
+                  set("syncCompressThreshold", v);
+                  invoke("set_sync_compression_threshold", { bytes: v }).catch(e => console.warn("[bushido]", e));
+                }}
+              />
+            </div>
           </div>
           <div className="settings-subsection">
             <h3 className="settings-subsection-title">Danger Zone</h3>
@@ -1163,7 +1635,7 @@ export default memo(function SettingsPage({ settings, onUpdate, onReloadAllTabs,
       await invoke("vault_unlock", { masterPassword: pw });
       setVaultUnlocked(true);
       invoke<VaultEntry[]>("vault_get_entries", {}).then(setVaultEntries).catch(e => console.warn("[bushido]", e));
-    } catch (e) { useUiStore.getState().showError(String(e)); }
+    } catch (e: any) { useUiStore.getState().showError(e?.message || String(e)); }
   };
 
   const vaultSetup = async (pw: string) => {
@@ -1171,7 +1643,7 @@ export default memo(function SettingsPage({ settings, onUpdate, onReloadAllTabs,
       await invoke("vault_setup", { masterPassword: pw });
       setVaultUnlocked(true);
       setVaultHasMaster(true);
-    } catch (e) { useUiStore.getState().showError(String(e)); }
+    } catch (e: any) { useUiStore.getState().showError(e?.message || String(e)); }
   };
 
   const renderPasswords = () => {
@@ -1192,7 +1664,25 @@ export default memo(function SettingsPage({ settings, onUpdate, onReloadAllTabs,
               <button type="submit" className="settings-about-btn">Set Master Password</button>
             </form>
           </div>
-        ) : !vaultUnlocked ? (
+        ) : null}
+        {vaultHasMaster && (
+          <div className="settings-row">
+            <div className="settings-label">
+              <span>Lock after inactivity</span>
+              <span className="settings-hint">Forgets the master key when you haven't used the browser for this long</span>
+            </div>
+            <Select
+              value={settings.vaultAutoLock ? settings.vaultLockTimeout : 0}
+              options={VAULT_AUTOLOCK_OPTIONS}
+              onChange={(v: number) => {
+                set("vaultAutoLock", v > 0);
+                if (v > 0) set("vaultLockTimeout", v);
+                invoke("set_vault_autolock_timeout", { minutes: v }).catch(e => console.warn("[bushido]", e));
+              }}
+            />
+          </div>
+        )}
+        {!vaultHasMaster ? null : !vaultUnlocked ? (
           <div className="settings-row" style={{ flexDirection: "column", gap: 8, alignItems: "flex-start" }}>
             <p style={{ opacity: 0.6, fontSize: 13 }}>Vault is locked. Enter your master password.</p>
             <form onSubmit={e => { e.preventDefault(); const pw = ((e.target as HTMLFormElement).elements.namedItem("pw") as HTMLInputElement).value; vaultUnlock(pw); }} style={{ display: "flex", gap: 8, alignItems: "center" }}>
@@ -1216,6 +1706,33 @@ export default memo(function SettingsPage({ settings, onUpdate, onReloadAllTabs,
               </button>
             </div>
 
+            <div className="settings-row" style={{ gap: 8 }}>
+              <input
+                type="password"
+                placeholder="Backup password"
+                value={vaultBackupPw}
+                onChange={e => setVaultBackupPw(e.target.value)}
+                className="settings-input"
+                style={{ maxWidth: 200 }}
+              />
+              <button className="settings-about-btn" disabled={vaultBackupPw.length < 8} onClick={() => {
+                invoke<string | null>("vault_export", { exportPassword: vaultBackupPw, path: null })
+                  .then(p => { if (p) { setVaultBackupStatus(`Backup saved to ${p}`); setVaultBackupPw(""); } })
+                  .catch(e => setVaultBackupStatus(`Export failed: ${e?.message || e}`));
+              }}>Export</button>
+              <button className="settings-about-btn" disabled={!vaultBackupPw} onClick={() => {
+                invoke<{ imported: number; skipped: number } | null>("vault_import", { path: null, exportPassword: vaultBackupPw })
+                  .then(r => {
+                    if (!r) return;
+                    setVaultBackupStatus(`Imported ${r.imported}, skipped ${r.skipped} already saved`);
+                    setVaultBackupPw("");
+                    invoke<VaultEntry[]>("vault_get_entries", {}).then(setVaultEntries).catch(e => console.warn("[bushido]", e));
+                  })
+                  .catch(e => setVaultBackupStatus(`Import failed: ${e?.message || e}`));
+              }}>Import</button>
+            </div>
+            {vaultBackupStatus && <p style={{ opacity: 0.6, fontSize: 12 }}>{vaultBackupStatus}</p>}
+
             <div className="vault-entries">
               {filtered.length === 0 && <p style={{ opacity: 0.5, fontSize: 13, padding: "12px 0" }}>No saved passwords{vaultSearch ? " matching search" : ""}.</p>}
               {filtered.map(entry => (
@@ -1265,6 +1782,17 @@ export default memo(function SettingsPage({ settings, onUpdate, onReloadAllTabs,
   const [appVersion, setAppVersion] = useState("...");
   useEffect(() => { getVersion().then(v => setAppVersion(v)).catch(() => setAppVersion("unknown")); }, []);
 
+  const resetSubsystem = async (subsystem: string, label: string) => {
+    if (!confirm(`Reset ${label} to defaults? A .bak copy of the current state is kept.`)) return;
+    try {
+      const summary = await invoke<{ subsystem: string; backups: string[]; detail: string }>("reset_defaults", { subsystem });
+      if (subsystem === "permissions") setSavedPerms([]);
+      alert(`Reset ${label}: ${summary.detail}.` + (summary.backups.length ? `\n\nBackup: ${summary.backups.join(", ")}` : ""));
+    } catch (e: any) {
+      useUiStore.getState().showError(`Reset failed: ${e?.message || e}`);
+    }
+  };
+
   const renderAbout = () => (
     <section className="settings-section">
       <h2 className="settings-section-title">About</h2>
@@ -1278,6 +1806,18 @@ export default memo(function SettingsPage({ settings, onUpdate, onReloadAllTabs,
             setTimeout(() => window.location.reload(), 600);
           }}>Replay Onboarding</button>
         </div>
+        <div className="settings-subsection" style={{ marginTop: 20 }}>
+          <h3 className="settings-subsection-title">Reset to Defaults</h3>
+          {RESET_SUBSYSTEMS.map(({ id, label, hint }) => (
+            <div key={id} className="settings-row">
+              <div className="settings-label">
+                <span>{label}</span>
+                <span className="settings-hint">{hint}</span>
+              </div>
+              <button className="settings-remove-btn" onClick={() => resetSubsystem(id, label.toLowerCase())}>Reset</button>
+            </div>
+          ))}
+        </div>
         <div style={{ marginTop: 20, fontSize: 12, opacity: 0.5, lineHeight: 1.8 }}>
           <div>Built with Tauri, React, and WebView2</div>
           <div>Licensed under GPL-3.0</div>
@@ -2,6 +2,7 @@ import { useState, useCallback, useRef, useEffect, useMemo, memo, RefObject } fr
 import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
 import { Tab, Workspace, Bookmark, BookmarkFolder, FrecencyResult, WebPanel, SyncTab } from "../types";
+import { ReaderFont, ReaderProgress, ReaderSettings } from "../store/featureStore";
 import logoSrc from "../assets/logo.png";
 
 const GearIcon = () => (
@@ -23,6 +24,17 @@ const EMOJI_CATEGORIES = [
   { label: "Fun", emojis: "\uD83D\uDE80 \uD83D\uDC7E \uD83E\uDD16 \uD83C\uDFAA \uD83C\uDFAD \uD83E\uDD8A \uD83D\uDC31 \uD83E\uDD84 \uD83D\uDC19 \uD83D\uDC7B".split(" ") },
 ];
 
+const READER_FONT_OPTIONS: { value: ReaderFont; label: string }[] = [
+  { value: "serif", label: "Serif" },
+  { value: "sans", label: "Sans" },
+  { value: "mono", label: "Mono" },
+  { value: "verdana", label: "Verdana" },
+  { value: "atkinson", label: "Atkinson Hyperlegible" },
+  { value: "lexend", label: "Lexend" },
+  { value: "opendyslexic", label: "OpenDyslexic" },
+  { value: "comic", label: "Comic Sans" },
+];
+
 const PANEL_PRESETS = [
   { name: "ChatGPT", url: "https://chatgpt.com", favicon: "https://www.google.com/s2/favicons?domain=chatgpt.com&sz=32" },
   { name: "Spotify", url: "https://open.spotify.com", favicon: "https://www.google.com/s2/favicons?domain=spotify.com&sz=32" },
@@ -132,6 +144,7 @@ interface Props {
   compact: boolean;
   onSelect: (id: string) => void;
   onClose: (id: string) => void;
+  onCloseTabs: (ids: string[]) => void;
   onPin: (id: string) => void;
   onNew: (url?: string) => void;
   onToggle: () => void;
@@ -164,9 +177,12 @@ interface Props {
   onReorderFolders: (folderId: string, targetFolderId: string, position: "before" | "after") => void;
   onSetFolderRss?: (folderId: string, rssUrl: string) => void;
   onRemoveFolderRss?: (folderId: string) => void;
+  onOpenBookmarkFolder?: (folderId: string) => void;
   onToggleHistory: () => void;
   onBack: () => void;
   onForward: () => void;
+  canGoBack: boolean;
+  canGoForward: boolean;
   onReload: () => void;
   url: string;
   onNavigate: (url: string) => void;
@@ -174,6 +190,8 @@ interface Props {
   inputRef: RefObject<HTMLInputElement>;
   blockedCount: number;
   whitelisted: boolean;
+  // global pause from set_global_blocking — shows the shield as down on every tab
+  blockingPaused: boolean;
   onToggleWhitelist: () => void;
   suggestions: FrecencyResult[];
   topSites: FrecencyResult[];
@@ -183,8 +201,9 @@ interface Props {
   onToggleBookmark: () => void;
   onToggleReader: () => void;
   isReaderActive: boolean;
-  readerSettings: { fontSize: number; font: "serif" | "sans"; theme: "dark" | "light" | "sepia"; lineWidth: number };
-  onUpdateReaderSettings: (update: Partial<{ fontSize: number; font: "serif" | "sans"; theme: "dark" | "light" | "sepia"; lineWidth: number }>) => void;
+  readerProgress?: ReaderProgress;
+  readerSettings: ReaderSettings;
+  onUpdateReaderSettings: (update: Partial<ReaderSettings>) => void;
   hasVideo: boolean;
   pipActive: boolean;
   onTogglePip: () => void;
@@ -289,21 +308,21 @@ function flattenTree(nodes: TabNode[]): TabNode[] {
 
 export default memo(function Sidebar({
   tabs, pinnedTabs, activeTab, open, compact,
-  onSelect, onClose, onPin, onNew, onToggle, onReorder,
+  onSelect, onClose, onCloseTabs, onPin, onNew, onToggle, onReorder,
   workspaces, activeWorkspaceId,
   onSwitchWorkspace, onAddWorkspace, onDeleteWorkspace, onClearWorkspaceData, onRenameWorkspace, onRecolorWorkspace, onSetWorkspaceIcon,
   onToggleCollapse, onAddChildTab, onMoveTabToWorkspace, onDuplicateWorkspace, onReorderWorkspaces, onMuteTab, onRenameTab,
   bookmarks, bookmarkFolders, onSelectBookmark, onRemoveBookmark,
   onAddBookmarkFolder, onRenameBookmarkFolder, onDeleteBookmarkFolder, onMoveBookmarkToFolder,
   onReorderBookmarks, onReorderFolders,
-  onSetFolderRss, onRemoveFolderRss,
+  onSetFolderRss, onRemoveFolderRss, onOpenBookmarkFolder,
   onToggleHistory,
-  onBack, onForward, onReload,
+  onBack, onForward, canGoBack, canGoForward, onReload,
   url, onNavigate, loading, inputRef,
-  blockedCount, whitelisted, onToggleWhitelist,
+  blockedCount, whitelisted: siteWhitelisted, blockingPaused, onToggleWhitelist,
   suggestions, topSites, onSuggestionSelect, onInputChange,
   isBookmarked, onToggleBookmark,
-  onToggleReader, isReaderActive, readerSettings, onUpdateReaderSettings,
+  onToggleReader, isReaderActive, readerProgress, readerSettings, onUpdateReaderSettings,
   hasVideo, pipActive, onTogglePip,
   onOpenSettings,
   activeDownloadCount, onToggleDownloads, onToggleNetwork,
@@ -316,7 +335,17 @@ export default memo(function Sidebar({
   zoomLevel, onZoomReset, onEditBookmark, onQuickAction,
   showDomainOnly, showMediaControls,
 }: Props) {
+  const whitelisted = siteWhitelisted || blockingPaused;
   const [ctx, setCtx] = useState<CtxMenu | null>(null);
+  // exact per-type breakdown for the shield tooltip, fetched on hover
+  const [shieldBreakdown, setShieldBreakdown] = useState("");
+  const loadShieldBreakdown = useCallback(() => {
+    invoke<{ total: number; by_type: Record<string, number> }>("get_blocked_stats", { id: activeTab }).then(stats => {
+      const parts = Object.entries(stats.by_type).sort((a, b) => b[1] - a[1]).map(([type, n]) => `${type} ${n}`);
+      setShieldBreakdown(stats.total > 0 ? `${stats.total} blocked: ${parts.join(", ")}` : "");
+    }).catch(() => setShieldBreakdown(""));
+  }, [activeTab]);
+  useEffect(() => setShieldBreakdown(""), [activeTab]);
   const [wsCtx, setWsCtx] = useState<WsCtxMenu | null>(null);
   const [emojiPicker, setEmojiPicker] = useState<{ wsId: string; x: number; y: number } | null>(null);
   const [renaming, setRenaming] = useState<string | null>(null);
@@ -776,9 +805,11 @@ export default memo(function Sidebar({
                   </svg>
                 </div>
                 <div
-                  className={`shield-badge ${whitelisted ? "shield-off" : ""}`}
-                  title={whitelisted ? "shields down (click to enable)" : `${blockedCount} tracker${blockedCount !== 1 ? 's' : ''} blocked (click to disable for this site)`}
-                  onClick={onToggleWhitelist}
+                  className={`shield-badge ${whitelisted ? "shield-off" : ""} ${blockingPaused ? "shield-paused" : ""}`}
+                  aria-disabled={blockingPaused}
+                  title={blockingPaused ? "ad blocking is paused everywhere — resume it to change this site" : whitelisted ? "shields down (click to enable)" : `${shieldBreakdown || `${blockedCount} tracker${blockedCount !== 1 ? 's' : ''} blocked`} (click to disable for this site)`}
+                  onMouseEnter={loadShieldBreakdown}
+                  onClick={blockingPaused ? undefined : onToggleWhitelist}
                 >
                   <svg width="16" height="16" viewBox="0 0 16 16" fill="none">
                     <path d="M8 1L2 4V7.5C2 11.1 4.5 14.4 8 15.2C11.5 14.4 14 11.1 14 7.5V4L8 1Z"
@@ -799,12 +830,12 @@ export default memo(function Sidebar({
 
             {/* nav buttons — ghost style, reload pushed right */}
             <div className="sidebar-nav-row">
-              <button className="nav-btn" onClick={onBack} title="Back (Alt+←)">
+              <button className="nav-btn" onClick={onBack} disabled={!canGoBack} title="Back (Alt+←)">
                 <svg width="16" height="16" viewBox="0 0 16 16" fill="none">
                   <path d="M10 3L5 8L10 13" stroke="currentColor" strokeWidth="2" strokeLinecap="round" strokeLinejoin="round"/>
                 </svg>
               </button>
-              <button className="nav-btn" onClick={onForward} title="Forward (Alt+→)">
+              <button className="nav-btn" onClick={onForward} disabled={!canGoForward} title="Forward (Alt+→)">
                 <svg width="16" height="16" viewBox="0 0 16 16" fill="none">
                   <path d="M6 3L11 8L6 13" stroke="currentColor" strokeWidth="2" strokeLinecap="round" strokeLinejoin="round"/>
                 </svg>
@@ -939,6 +970,23 @@ export default memo(function Sidebar({
                         <button className={`reader-theme-dot ${readerSettings.theme === "light" ? "active" : ""}`} style={{ background: "#fafafa" }} onClick={() => onUpdateReaderSettings({ theme: "light" })} />
                         <button className={`reader-theme-dot ${readerSettings.theme === "sepia" ? "active" : ""}`} style={{ background: "#f4ecd8" }} onClick={() => onUpdateReaderSettings({ theme: "sepia" })} />
                       </div>
+                      <div className="reader-settings-row">
+                        <select
+                          className="reader-font-btn"
+                          value={readerSettings.font}
+                          onChange={e => onUpdateReaderSettings({ font: e.target.value as ReaderFont })}
+                          title="Reader font"
+                        >
+                          {READER_FONT_OPTIONS.map(f => <option key={f.value} value={f.value}>{f.label}</option>)}
+                        </select>
+                        <button className="reader-size-btn" title="Tighter letters" onClick={() => onUpdateReaderSettings({ letterSpacing: Math.max(0, +(readerSettings.letterSpacing - 0.05).toFixed(2)) })}>a-</button>
+                        <button className="reader-size-btn" title="Wider letters" onClick={() => onUpdateReaderSettings({ letterSpacing: Math.min(0.3, +(readerSettings.letterSpacing + 0.05).toFixed(2)) })}>a+</button>
+                        <button className="reader-size-btn" title="Less paragraph space" onClick={() => onUpdateReaderSettings({ paragraphSpacing: Math.max(0.5, +(readerSettings.paragraphSpacing - 0.25).toFixed(2)) })}>¶-</button>
+                        <button className="reader-size-btn" title="More paragraph space" onClick={() => onUpdateReaderSettings({ paragraphSpacing: Math.min(3, +(readerSettings.paragraphSpacing + 0.25).toFixed(2)) })}>¶+</button>
+                        {readerProgress?.minutes !== undefined && (
+                          <span className="reader-size-label">{readerProgress.minutes} min read · {readerProgress.percent}%</span>
+                        )}
+                      </div>
                     </div>
                   )}
 
@@ -955,6 +1003,9 @@ export default memo(function Sidebar({
                 </div>
               )}
               {loading && <div className="url-progress" />}
+              {!loading && readerProgress && (
+                <div className="reader-progress" style={{ width: `${readerProgress.percent}%` }} />
+              )}
               {/* Top sites grid — shows on focus with no typed query */}
               {urlFocused && suggestions.length === 0 && quickActions.length === 0 && topSites.length > 0 && (
                 <div className="url-topsites">
@@ -1641,6 +1692,18 @@ export default memo(function Sidebar({
                       send to {d.name}
                     </button>
                   ))}
+                  {pairedDevices.map(d => (
+                    <button key={`${d.device_id}-all`} className="ctx-item" onClick={() => {
+                      const all = [...pinnedTabs, ...tabs]
+                        .filter(t => t.url.startsWith("http") && !t.private)
+                        .map(t => ({ url: t.url, title: t.title }));
+                      invoke("send_tabs_to_device", { deviceId: d.device_id, tabs: all })
+                        .catch(e => console.warn("[bushido]", e));
+                      closeCtx();
+                    }}>
+                      send all tabs to {d.name}
+                    </button>
+                  ))}
                 </>
               ) : null;
             })()}
@@ -1679,14 +1742,14 @@ export default memo(function Sidebar({
             )}
             <div className="ctx-divider" />
             <button className="ctx-item" onClick={() => {
-              tabs.filter(t => t.id !== ctx.tabId).forEach(t => onClose(t.id));
+              onCloseTabs(tabs.filter(t => t.id !== ctx.tabId).map(t => t.id));
               closeCtx();
             }}>
               close other tabs
             </button>
             <button className="ctx-item" onClick={() => {
               const idx = tabs.findIndex(t => t.id === ctx.tabId);
-              if (idx >= 0) tabs.slice(idx + 1).forEach(t => onClose(t.id));
+              if (idx >= 0) onCloseTabs(tabs.slice(idx + 1).map(t => t.id));
               closeCtx();
             }}>
               close tabs below
@@ -1771,6 +1834,11 @@ export default memo(function Sidebar({
       {folderCtx && (
         <div className="ctx-overlay" onClick={() => setFolderCtx(null)}>
           <div ref={folderCtxMenuRef} className="ctx-menu" style={{ top: folderCtxPos.top, left: folderCtxPos.left }}>
+            {onOpenBookmarkFolder && (
+              <button className="ctx-item" onClick={() => { onOpenBookmarkFolder(folderCtx.folderId); setFolderCtx(null); }}>
+                open all in tabs
+              </button>
+            )}
             <button className="ctx-item" onClick={() => {
               const folder = bookmarkFolders.find(f => f.id === folderCtx.folderId);
               setRenameFolderValue(folder?.name || "");
@@ -8,11 +8,21 @@ interface GlanceState {
   sourceTabId: string;
 }
 
-interface ReaderSettings {
+// keys of READER_FONTS in lib.rs
+export type ReaderFont = "serif" | "sans" | "mono" | "verdana" | "atkinson" | "lexend" | "opendyslexic" | "comic";
+
+export interface ReaderSettings {
   fontSize: number;
-  font: "serif" | "sans";
+  font: ReaderFont;
   theme: "dark" | "light" | "sepia";
   lineWidth: number;
+  letterSpacing: number; // em
+  paragraphSpacing: number; // em
+}
+
+export interface ReaderProgress {
+  percent: number;
+  minutes?: number; // estimated reading time, sent once when the overlay opens
 }
 
 interface FeatureState {
@@ -21,6 +31,7 @@ interface FeatureState {
   shareOpen: boolean;
   readerTabs: Set<string>;
   readerSettings: ReaderSettings;
+  readerProgress: Record<string, ReaderProgress>;
   hasVideo: boolean;
   pipActive: boolean;
   panels: WebPanel[];
@@ -32,6 +43,7 @@ interface FeatureState {
   setShareOpen: (v: boolean) => void;
   setReaderTabs: (updater: Set<string> | ((prev: Set<string>) => Set<string>)) => void;
   setReaderSettings: (updater: ReaderSettings | ((prev: ReaderSettings) => ReaderSettings)) => void;
+  setReaderProgress: (updater: Record<string, ReaderProgress> | ((prev: Record<string, ReaderProgress>) => Record<string, ReaderProgress>)) => void;
   setHasVideo: (v: boolean) => void;
   setPipActive: (v: boolean) => void;
   setPanels: (updater: WebPanel[] | ((prev: WebPanel[]) => WebPanel[])) => void;
@@ -44,7 +56,8 @@ export const useFeatureStore = create<FeatureState>((set) => ({
   annotationData: null,
   shareOpen: false,
   readerTabs: new Set(),
-  readerSettings: { fontSize: 18, font: "serif", theme: "dark", lineWidth: 680 },
+  readerSettings: { fontSize: 18, font: "serif", theme: "dark", lineWidth: 680, letterSpacing: 0, paragraphSpacing: 1 },
+  readerProgress: {},
   hasVideo: false,
   pipActive: false,
   panels: [],
@@ -58,6 +71,8 @@ export const useFeatureStore = create<FeatureState>((set) => ({
     set((s) => ({ readerTabs: typeof updater === "function" ? updater(s.readerTabs) : updater })),
   setReaderSettings: (updater) =>
     set((s) => ({ readerSettings: typeof updater === "function" ? updater(s.readerSettings) : updater })),
+  setReaderProgress: (updater) =>
+    set((s) => ({ readerProgress: typeof updater === "function" ? updater(s.readerProgress) : updater })),
   setHasVideo: (v) => set({ hasVideo: v }),
   setPipActive: (v) => set({ pipActive: v }),
   setPanels: (updater) =>
@@ -3,19 +3,27 @@ import { create } from "zustand";
 interface SyncState {
   syncToast: "syncing" | "success" | "error" | null;
   syncTabReceived: { from_device: string; url: string; title: string } | null;
+  syncTabsReceived: { from_device: string; tabs: { url: string; title: string }[] } | null;
   syncPairedDevices: { device_id: string; name: string }[];
+  syncSinceLastSession: string | null;
 
   setSyncToast: (v: "syncing" | "success" | "error" | null) => void;
   setSyncTabReceived: (v: { from_device: string; url: string; title: string } | null) => void;
+  setSyncTabsReceived: (v: { from_device: string; tabs: { url: string; title: string }[] } | null) => void;
   setSyncPairedDevices: (v: { device_id: string; name: string }[]) => void;
+  setSyncSinceLastSession: (v: string | null) => void;
 }
 
 export const useSyncStore = create<SyncState>((set) => ({
   syncToast: null,
   syncTabReceived: null,
+  syncTabsReceived: null,
   syncPairedDevices: [],
+  syncSinceLastSession: null,
 
   setSyncToast: (v) => set({ syncToast: v }),
   setSyncTabReceived: (v) => set({ syncTabReceived: v }),
+  setSyncTabsReceived: (v) => set({ syncTabsReceived: v }),
   setSyncPairedDevices: (v) => set({ syncPairedDevices: v }),
+  setSyncSinceLastSession: (v) => set({ syncSinceLastSession: v }),
 }));
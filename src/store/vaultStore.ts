@@ -1,5 +1,5 @@
 import { create } from "zustand";
-import { PermissionRequest } from "../types";
+import { BasicAuthRequest, CertErrorRequest, PermissionRequest } from "../types";
 
 interface VaultState {
   vaultSavePrompt: { domain: string; username: string; password: string } | null;
@@ -7,12 +7,16 @@ interface VaultState {
   vaultUnlocked: boolean;
   permReq: PermissionRequest | null;
   permRemember: boolean;
+  authReq: BasicAuthRequest | null;
+  certReq: CertErrorRequest | null;
 
   setVaultSavePrompt: (v: { domain: string; username: string; password: string } | null) => void;
   setVaultMasterModal: (v: "setup" | "unlock" | null) => void;
   setVaultUnlocked: (v: boolean) => void;
   setPermReq: (v: PermissionRequest | null) => void;
   setPermRemember: (v: boolean) => void;
+  setAuthReq: (v: BasicAuthRequest | null) => void;
+  setCertReq: (v: CertErrorRequest | null) => void;
 }
 
 export const useVaultStore = create<VaultState>((set) => ({
@@ -21,10 +25,14 @@ export const useVaultStore = create<VaultState>((set) => ({
   vaultUnlocked: false,
   permReq: null,
   permRemember: true,
+  authReq: null,
+  certReq: null,
 
   setVaultSavePrompt: (v) => set({ vaultSavePrompt: v }),
   setVaultMasterModal: (v) => set({ vaultMasterModal: v }),
   setVaultUnlocked: (v) => set({ vaultUnlocked: v }),
   setPermReq: (v) => set({ permReq: v }),
   setPermRemember: (v) => set({ permRemember: v }),
+  setAuthReq: (v) => set({ authReq: v }),
+  setCertReq: (v) => set({ certReq: v }),
 }));
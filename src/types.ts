@@ -12,6 +12,8 @@ export interface Tab {
   loading: boolean;
   favicon?: string;
   pinned?: boolean;
+  muted?: boolean;
+  imagesOff?: boolean; // "block all images" mode
   blockedCount?: number;
   whitelisted?: boolean;
   workspaceId: string;
@@ -27,7 +29,14 @@ export interface Tab {
   mediaDuration?: number;
   mediaPlaybackRate?: number;
   crashed?: boolean;
+  canGoBack?: boolean;
+  canGoForward?: boolean;
+  injectionBypass?: boolean;
+  networkCapture?: boolean;
   customTitle?: string;
+  private?: boolean; // non-persistent storage, kept out of the session and history
+  throttling?: "disabled" | "suspend"; // background policy, unset = default throttling
+  windowLabel?: string; // moved to a window of its own
 }
 
 // split view layout tree
@@ -39,6 +48,15 @@ export interface PaneSplit { type: "split"; dir: SplitDir; children: PaneChild[]
 
 export interface PaneRect { tabId: string; x: number; y: number; w: number; h: number }
 
+// save_layout / restore_layout — rects as last laid out, with each pane's url
+export interface LayoutSnapshot {
+  tree: PaneSplit | null;
+  panes: (PaneRect & { url: string })[];
+  focusedTabId: string;
+  sidebarW: number;
+  topOffset: number;
+}
+
 export interface DividerInfo {
   dir: SplitDir;
   x: number; y: number; w: number; h: number;
@@ -61,14 +79,29 @@ export interface Workspace {
   paneLayout?: PaneSplit;
 }
 
+// backend-owned (tab_groups.rs): list_tab_groups, "tab-groups-changed"
+export interface TabGroup {
+  id: string;
+  name: string;
+  color: string;
+  tabIds: string[];
+}
+
 export interface SessionData {
   workspaces: { id: string; name: string; color: string; icon?: string; activeTabId: string; paneLayout?: PaneSplit }[];
-  tabs: { id: string; url: string; title: string; pinned?: boolean; workspaceId: string; parentId?: string; suspended?: boolean }[];
+  tabs: { id: string; url: string; title: string; pinned?: boolean; workspaceId: string; parentId?: string; suspended?: boolean; throttling?: "disabled" | "suspend" }[];
   activeWorkspaceId: string;
   compactMode?: boolean;
   panels?: { id: string; url: string; title: string; favicon?: string }[];
 }
 
+// get_startup_tabs: what the first window opens
+export interface StartupPlan {
+  mode: "restore_session" | "homepage" | "blank";
+  session: string | null; // load_session's result, restore_session only
+  urls: string[];
+}
+
 export interface HistoryEntry {
   url: string;
   title: string;
@@ -85,6 +118,7 @@ export interface Bookmark {
   folderId: string;
   createdAt: number;
   order: number;
+  tags?: string[];
 }
 
 export interface BookmarkFolder {
@@ -123,18 +157,25 @@ export interface BushidoSettings {
   adBlocker: boolean;
   cookieAutoReject: boolean;
   clearDataOnExit: boolean;
+  historyRetentionDays: number; // 0 = forever
   compactMode: boolean;
   suspendTimeout: number; // minutes, 0 = never
+  autoHibernate: boolean;
+  autoHibernateThresholdMb: number;
+  crashRecoveryRetries: number; // reloads per minute after a crash, 0 = off
+  dropFilesToPage: boolean; // pages receive dropped files instead of them opening in new tabs
+  maxTabs: number;
+  switchToExistingTab: boolean;
   disableDevTools: boolean;
   disableStatusBar: boolean;
   disableAutofill: boolean;
   disablePasswordSave: boolean;
   blockServiceWorkers: boolean;
   blockFontEnumeration: boolean;
-  spoofHardwareConcurrency: boolean;
   onboardingComplete: boolean;
   accentColor: string;
   themeMode: "dark" | "light";
+  pageColorScheme: "auto" | "dark" | "light"; // prefers-color-scheme seen by web pages
   syncEnabled: boolean;
   syncDeviceName: string;
   topSiteRows: number;
@@ -146,16 +187,37 @@ export interface BushidoSettings {
   confirmBeforeQuit: boolean;
   searchSuggestions: boolean;
   blockPopups: boolean;
+  privacySignals: boolean;
   autoplayPolicy: "block-all" | "block-audio" | "allow";
   dnsLevel: "standard" | "strict" | "maximum";
+  customDoh: boolean; // own DNS-over-HTTPS resolver instead of the local one (applies on restart)
+  dohTemplate: string;
+  newTabUrl: string; // "" = the built-in new tab page
   showMediaControls: boolean;
   showDomainOnly: boolean;
   keybindings: Record<string, string>;
   bandwidthLimit: number;
+  maxConcurrentDownloads: number;
+  screenshotFormat: "png" | "jpeg" | "webp";
+  screenshotQuality: number; // 1-100, JPEG only
   mimeRouting: MimeRoute[];
   vaultAutoLock: boolean;
   vaultLockTimeout: number;
-  syncDataTypes: { bookmarks: boolean; history: boolean; settings: boolean; tabs: boolean };
+  syncDataTypes: { bookmarks: boolean; history: boolean; settings: boolean; tabs: boolean; readingList?: boolean };
+  syncBandwidthLimit: number;
+  syncCompressThreshold: number; // bytes; smaller sync frames aren't compressed
+  fingerprint: FingerprintConfig;
+}
+
+// per-category fingerprint protections; `language` also sets Accept-Language
+export interface FingerprintConfig {
+  canvasNoise: boolean;
+  webglVendor: boolean;
+  audioNoise: boolean;
+  timezone: boolean;
+  screen: boolean;
+  hardwareConcurrency: boolean;
+  language: string;
 }
 
 export type PermissionKindType = "microphone" | "camera" | "geolocation" | "notifications" | "othersensors" | "clipboardread" | "filereadwrite" | "autoplay" | "localfonts" | "midi" | "windowmanagement" | "unknown";
@@ -169,13 +231,33 @@ export interface PermissionRequest {
   isUserInitiated: boolean;
 }
 
+export interface BasicAuthRequest {
+  requestId: string;
+  tabId: string;
+  uri: string;
+  host: string;
+  realm: string;
+  vaultEntries: { id: string; username: string }[];
+}
+
+export interface CertErrorRequest {
+  requestId: string;
+  tabId: string;
+  uri: string;
+  host: string;
+  reason: string;
+  subject: string;
+  issuer: string;
+  thumbprint: string;
+}
+
 export interface SavedPermission {
   domain: string;
   permission: string;
   allowed: boolean;
 }
 
-export type DownloadState = 'downloading' | 'paused' | 'completed' | 'failed';
+export type DownloadState = 'queued' | 'downloading' | 'paused' | 'completed' | 'failed';
 export interface DownloadItem {
   id: string;
   url: string;
@@ -185,7 +267,8 @@ export interface DownloadItem {
   totalBytes: number | null;
   receivedBytes: number;
   state: DownloadState;
-  speed: number;
+  speedBps: number;
+  etaSecs: number | null; // null when size or speed is unknown
   error: string | null;
   createdAt: number;
   supportsRange: boolean;
@@ -204,10 +287,21 @@ export interface VaultEntry {
   username: string;
   password: string;
   notes: string;
+  has_totp: boolean;
   created_at: number;
   updated_at: number;
 }
 
+export interface VaultAuditEntry {
+  id: string;
+  domain: string;
+  username: string;
+  strength: number; // 0-4
+  entropy_bits: number;
+  reused: boolean;
+  age_days: number;
+}
+
 // sync types (Phase D)
 export interface SyncTab {
   id: string;
@@ -230,6 +324,22 @@ export interface SyncLogEntry {
   device?: string;
 }
 
+// sync-since-last-session — items are capped, totals aren't
+export interface SessionChange {
+  id: string;
+  kind: "bookmark" | "folder";
+  title: string;
+  url: string | null;
+  deviceId: string | null;
+  deviceName: string | null;
+}
+
+export interface SessionSummary {
+  added: { total: number; items: SessionChange[] };
+  removed: { total: number; items: SessionChange[] };
+  changed: { total: number; items: SessionChange[] };
+}
+
 export const DEFAULT_SETTINGS: BushidoSettings = {
   searchEngine: "google",
   customSearchUrl: "",
@@ -243,18 +353,25 @@ export const DEFAULT_SETTINGS: BushidoSettings = {
   adBlocker: true,
   cookieAutoReject: true,
   clearDataOnExit: false,
+  historyRetentionDays: 0,
   compactMode: false,
   suspendTimeout: 5,
+  autoHibernate: false,
+  autoHibernateThresholdMb: 4096,
+  crashRecoveryRetries: 0,
+  dropFilesToPage: false,
+  maxTabs: 50,
+  switchToExistingTab: false,
   disableDevTools: false,
   disableStatusBar: false,
   disableAutofill: true,
   disablePasswordSave: true,
   blockServiceWorkers: false,
   blockFontEnumeration: false,
-  spoofHardwareConcurrency: false,
   onboardingComplete: false,
   accentColor: "#6366f1",
   themeMode: "dark" as const,
+  pageColorScheme: "auto" as const,
   syncEnabled: false,
   syncDeviceName: "",
   topSiteRows: 2,
@@ -266,11 +383,18 @@ export const DEFAULT_SETTINGS: BushidoSettings = {
   confirmBeforeQuit: false,
   searchSuggestions: true,
   blockPopups: true,
+  privacySignals: true,
   autoplayPolicy: "block-audio",
   dnsLevel: "strict",
+  customDoh: false,
+  dohTemplate: "https://dns.quad9.net/dns-query",
+  newTabUrl: "",
   showMediaControls: true,
   showDomainOnly: false,
   bandwidthLimit: 0,
+  maxConcurrentDownloads: 3,
+  screenshotFormat: "png",
+  screenshotQuality: 90,
   mimeRouting: [
     { mimePrefix: "image/", folder: "" },
     { mimePrefix: "video/", folder: "" },
@@ -278,8 +402,14 @@ export const DEFAULT_SETTINGS: BushidoSettings = {
     { mimePrefix: "application/pdf", folder: "" },
   ],
   vaultAutoLock: true,
-  vaultLockTimeout: 0,
-  syncDataTypes: { bookmarks: true, history: true, settings: true, tabs: true },
+  vaultLockTimeout: 15,
+  syncDataTypes: { bookmarks: true, history: true, settings: true, tabs: true, readingList: true },
+  syncBandwidthLimit: 0,
+  syncCompressThreshold: 512,
+  fingerprint: {
+    canvasNoise: true, webglVendor: true, audioNoise: true, timezone: true,
+    screen: true, hardwareConcurrency: true, language: "en-US",
+  },
   keybindings: {
     "new-tab": "Ctrl+T",
     "close-tab": "Ctrl+W",